@@ -0,0 +1,34 @@
+use sea_orm_migration::prelude::*;
+
+/// Creates the table, or adds the columns it lacks when a build from before versioned
+/// migrations already created it. Columns such builds added later carry a default so
+/// existing rows can take them.
+pub async fn create_or_complete(
+    manager: &SchemaManager<'_>,
+    table: TableCreateStatement,
+) -> Result<(), DbErr> {
+    let Some(TableRef::Table(name)) = table.get_table_name().cloned() else {
+        return Err(DbErr::Custom("migration table has no plain name".into()));
+    };
+    if !manager.has_table(name.to_string()).await? {
+        return manager.create_table(table).await;
+    }
+
+    for column in table.get_columns() {
+        if manager
+            .has_column(name.to_string(), column.get_column_name())
+            .await?
+        {
+            continue;
+        }
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(name.clone())
+                    .add_column(&mut column.clone())
+                    .to_owned(),
+            )
+            .await?;
+    }
+    Ok(())
+}
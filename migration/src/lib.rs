@@ -0,0 +1,23 @@
+//! Versioned schema migrations for ASM Lab Server, applied in order and recorded in
+//! `seaql_migrations`. Add a migration as a new `mYYYYMMDD_NNNNNN_<name>` module at the
+//! end of [`Migrator::migrations`]; applied migrations are never edited.
+
+pub use sea_orm_migration::{MigrationStatus, prelude::*, seaql_migrations};
+
+mod baseline;
+mod m20261018_000001_create_classrooms;
+mod m20261018_000002_create_submissions;
+mod m20261018_000003_create_operations;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m20261018_000001_create_classrooms::Migration),
+            Box::new(m20261018_000002_create_submissions::Migration),
+            Box::new(m20261018_000003_create_operations::Migration),
+        ]
+    }
+}
@@ -0,0 +1,608 @@
+//! Accounts, classrooms with their roster and tasks, and the data migration ledger.
+
+use sea_orm_migration::prelude::*;
+
+use crate::baseline;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(Accounts::Table)
+                .col(
+                    ColumnDef::new(Accounts::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(Accounts::Npm)
+                        .string()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(ColumnDef::new(Accounts::Role).string().not_null())
+                .col(ColumnDef::new(Accounts::Email).string().null())
+                .col(
+                    ColumnDef::new(Accounts::EmailVerifiedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Accounts::Status)
+                        .string()
+                        .not_null()
+                        .default("active"),
+                )
+                .col(
+                    ColumnDef::new(Accounts::ApprovedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Accounts::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(Accounts::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(Classrooms::Table)
+                .col(
+                    ColumnDef::new(Classrooms::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Classrooms::Name).string().not_null())
+                .col(
+                    ColumnDef::new(Classrooms::ProgrammingLanguage)
+                        .string()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::LanguageLocked)
+                        .boolean()
+                        .not_null()
+                        .default(false),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::Tasks)
+                        .string()
+                        .not_null()
+                        .default("[]"),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::IsExam)
+                        .boolean()
+                        .not_null()
+                        .default(false),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::TestCode)
+                        .string()
+                        .not_null()
+                        .default(""),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::ExamStart)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::ExamEnd)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::PresetupCode)
+                        .string()
+                        .not_null()
+                        .default(""),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::TestMode)
+                        .string()
+                        .not_null()
+                        .default("full"),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::TestSampleSize)
+                        .integer()
+                        .not_null()
+                        .default(3),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::PracticeMode)
+                        .boolean()
+                        .not_null()
+                        .default(true),
+                )
+                .col(ColumnDef::new(Classrooms::GradingScript).text().null())
+                .col(
+                    ColumnDef::new(Classrooms::GradingScriptLanguageId)
+                        .integer()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::Status)
+                        .string()
+                        .not_null()
+                        .default("published"),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::PublishedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::DevicePolicy)
+                        .string()
+                        .not_null()
+                        .default("off"),
+                )
+                .col(ColumnDef::new(Classrooms::Term).string().null())
+                .col(ColumnDef::new(Classrooms::OutputLimitKb).integer().null())
+                .col(ColumnDef::new(Classrooms::MaxCodeChars).integer().null())
+                .col(
+                    ColumnDef::new(Classrooms::MaxDescriptionChars)
+                        .integer()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::SubmissionCooldownSecs)
+                        .integer()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::PostFinishPolicy)
+                        .string()
+                        .not_null()
+                        .default("deactivate"),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::FingerprintSharing)
+                        .boolean()
+                        .not_null()
+                        .default(true),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::SandboxEnableNetwork)
+                        .boolean()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::SandboxMaxProcesses)
+                        .integer()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::SandboxRedirectStderr)
+                        .boolean()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::ExamGatedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(ColumnDef::new(Classrooms::ExamGateReason).string().null())
+                .col(
+                    ColumnDef::new(Classrooms::ExecutorCheckedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::ResolvedLanguageId)
+                        .integer()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::LanguageMissingSince)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(Classrooms::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(Users::Table)
+                .col(
+                    ColumnDef::new(Users::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Users::ClassroomId).integer().not_null())
+                .col(ColumnDef::new(Users::Name).string().not_null())
+                .col(ColumnDef::new(Users::Npm).string().not_null())
+                .col(ColumnDef::new(Users::Code).text().not_null())
+                .col(
+                    ColumnDef::new(Users::Active)
+                        .boolean()
+                        .not_null()
+                        .default(true),
+                )
+                .col(
+                    ColumnDef::new(Users::ExamStartedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(ColumnDef::new(Users::PresetupOverride).string().null())
+                .col(ColumnDef::new(Users::LanguageId).integer().null())
+                .col(
+                    ColumnDef::new(Users::CodeUpdatedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Users::CodeSeq)
+                        .big_integer()
+                        .not_null()
+                        .default(0),
+                )
+                .col(
+                    ColumnDef::new(Users::LastGradedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Users::SubmissionsLockedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(ColumnDef::new(Users::DeviceFingerprint).string().null())
+                .col(
+                    ColumnDef::new(Users::DeviceBoundAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(ColumnDef::new(Users::SeatRow).integer().null())
+                .col(ColumnDef::new(Users::SeatColumn).integer().null())
+                .col(ColumnDef::new(Users::TicketNonce).string().null())
+                .col(ColumnDef::new(Users::TicketCodeHash).string().null())
+                .col(
+                    ColumnDef::new(Users::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(Users::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-users-classroom_id")
+                        .from(Users::Table, Users::ClassroomId)
+                        .to(Classrooms::Table, Classrooms::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(ExamPauses::Table)
+                .col(
+                    ColumnDef::new(ExamPauses::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(ExamPauses::ClassroomId).integer().not_null())
+                .col(
+                    ColumnDef::new(ExamPauses::PausedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ExamPauses::ResumedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-exam_pauses-classroom_id")
+                        .from(ExamPauses::Table, ExamPauses::ClassroomId)
+                        .to(Classrooms::Table, Classrooms::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(Tasks::Table)
+                .col(
+                    ColumnDef::new(Tasks::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Tasks::ClassroomId).integer().not_null())
+                .col(ColumnDef::new(Tasks::Position).integer().not_null())
+                .col(ColumnDef::new(Tasks::Title).string().not_null())
+                .col(ColumnDef::new(Tasks::Description).string().not_null())
+                .col(ColumnDef::new(Tasks::LanguageId).integer().null())
+                .col(
+                    ColumnDef::new(Tasks::DueAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(ColumnDef::new(Tasks::StarterCode).text().null())
+                .col(ColumnDef::new(Tasks::ExpectedOutput).text().null())
+                .col(
+                    ColumnDef::new(Tasks::Weight)
+                        .integer()
+                        .not_null()
+                        .default(1),
+                )
+                .col(
+                    ColumnDef::new(Tasks::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(Tasks::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-tasks-classroom_id")
+                        .from(Tasks::Table, Tasks::ClassroomId)
+                        .to(Classrooms::Table, Classrooms::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(TestCases::Table)
+                .col(
+                    ColumnDef::new(TestCases::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(TestCases::TaskId).integer().not_null())
+                .col(ColumnDef::new(TestCases::Position).integer().not_null())
+                .col(ColumnDef::new(TestCases::Stdin).string().not_null())
+                .col(
+                    ColumnDef::new(TestCases::ExpectedOutput)
+                        .string()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(TestCases::Hidden).boolean().not_null())
+                .col(ColumnDef::new(TestCases::Points).integer().not_null())
+                .col(
+                    ColumnDef::new(TestCases::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(TestCases::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-test_cases-task_id")
+                        .from(TestCases::Table, TestCases::TaskId)
+                        .to(Tasks::Table, Tasks::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(MigrationsMeta::Table)
+                .col(
+                    ColumnDef::new(MigrationsMeta::Version)
+                        .integer()
+                        .not_null()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(MigrationsMeta::Name).string().not_null())
+                .col(
+                    ColumnDef::new(MigrationsMeta::AppliedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MigrationsMeta::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(TestCases::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Tasks::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ExamPauses::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Users::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Classrooms::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Accounts::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Accounts {
+    Table,
+    Id,
+    Npm,
+    Role,
+    Email,
+    EmailVerifiedAt,
+    Status,
+    ApprovedAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum Classrooms {
+    Table,
+    Id,
+    Name,
+    ProgrammingLanguage,
+    LanguageLocked,
+    Tasks,
+    IsExam,
+    TestCode,
+    ExamStart,
+    ExamEnd,
+    PresetupCode,
+    TestMode,
+    TestSampleSize,
+    PracticeMode,
+    GradingScript,
+    GradingScriptLanguageId,
+    Status,
+    PublishedAt,
+    DevicePolicy,
+    Term,
+    OutputLimitKb,
+    MaxCodeChars,
+    MaxDescriptionChars,
+    SubmissionCooldownSecs,
+    PostFinishPolicy,
+    FingerprintSharing,
+    SandboxEnableNetwork,
+    SandboxMaxProcesses,
+    SandboxRedirectStderr,
+    ExamGatedAt,
+    ExamGateReason,
+    ExecutorCheckedAt,
+    ResolvedLanguageId,
+    LanguageMissingSince,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum Users {
+    Table,
+    Id,
+    ClassroomId,
+    Name,
+    Npm,
+    Code,
+    Active,
+    ExamStartedAt,
+    PresetupOverride,
+    LanguageId,
+    CodeUpdatedAt,
+    CodeSeq,
+    LastGradedAt,
+    SubmissionsLockedAt,
+    DeviceFingerprint,
+    DeviceBoundAt,
+    SeatRow,
+    SeatColumn,
+    TicketNonce,
+    TicketCodeHash,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum ExamPauses {
+    Table,
+    Id,
+    ClassroomId,
+    PausedAt,
+    ResumedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum Tasks {
+    Table,
+    Id,
+    ClassroomId,
+    Position,
+    Title,
+    Description,
+    LanguageId,
+    DueAt,
+    StarterCode,
+    ExpectedOutput,
+    Weight,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum TestCases {
+    Table,
+    Id,
+    TaskId,
+    Position,
+    Stdin,
+    ExpectedOutput,
+    Hidden,
+    Points,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum MigrationsMeta {
+    Table,
+    Version,
+    Name,
+    AppliedAt,
+}
@@ -0,0 +1,782 @@
+//! Submissions and what grading, archiving and integrity checks keep about them.
+
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    baseline,
+    m20261018_000001_create_classrooms::{Classrooms, Tasks, TestCases, Users},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(Submissions::Table)
+                .col(
+                    ColumnDef::new(Submissions::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(Submissions::ClassroomId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(Submissions::UserId).integer().not_null())
+                .col(ColumnDef::new(Submissions::SourceCode).text().not_null())
+                .col(ColumnDef::new(Submissions::LanguageId).integer().not_null())
+                .col(ColumnDef::new(Submissions::Stdout).text().null())
+                .col(ColumnDef::new(Submissions::Stderr).text().null())
+                .col(ColumnDef::new(Submissions::CompileOutput).text().null())
+                .col(ColumnDef::new(Submissions::Status).string().null())
+                .col(ColumnDef::new(Submissions::Time).string().null())
+                .col(ColumnDef::new(Submissions::Memory).big_integer().null())
+                .col(ColumnDef::new(Submissions::ExitCode).integer().null())
+                .col(ColumnDef::new(Submissions::ExitSignal).integer().null())
+                .col(ColumnDef::new(Submissions::IsFinal).boolean().not_null())
+                .col(
+                    ColumnDef::new(Submissions::AutoSubmitted)
+                        .boolean()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(Submissions::TaskId).integer().null())
+                .col(
+                    ColumnDef::new(Submissions::Late)
+                        .boolean()
+                        .not_null()
+                        .default(false),
+                )
+                .col(ColumnDef::new(Submissions::PassedCases).integer().null())
+                .col(ColumnDef::new(Submissions::TotalCases).integer().null())
+                .col(ColumnDef::new(Submissions::Score).integer().null())
+                .col(ColumnDef::new(Submissions::MaxScore).integer().null())
+                .col(ColumnDef::new(Submissions::GradingError).string().null())
+                .col(
+                    ColumnDef::new(Submissions::GradingAttempts)
+                        .integer()
+                        .not_null()
+                        .default(0),
+                )
+                .col(
+                    ColumnDef::new(Submissions::LastAttemptAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(ColumnDef::new(Submissions::GradingStatus).string().null())
+                .col(ColumnDef::new(Submissions::GradingCase).integer().null())
+                .col(
+                    ColumnDef::new(Submissions::GradingCaseCount)
+                        .integer()
+                        .null(),
+                )
+                .col(ColumnDef::new(Submissions::ScriptVerdict).text().null())
+                .col(ColumnDef::new(Submissions::FinishFeedback).text().null())
+                .col(ColumnDef::new(Submissions::Judge0Token).string().null())
+                .col(ColumnDef::new(Submissions::Judge0Instance).string().null())
+                .col(ColumnDef::new(Submissions::Judge0StatusId).integer().null())
+                .col(
+                    ColumnDef::new(Submissions::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(Submissions::GradedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-submissions-classroom_id")
+                        .from(Submissions::Table, Submissions::ClassroomId)
+                        .to(Classrooms::Table, Classrooms::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-submissions-user_id")
+                        .from(Submissions::Table, Submissions::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(SubmissionCaseResults::Table)
+                .col(
+                    ColumnDef::new(SubmissionCaseResults::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(SubmissionCaseResults::SubmissionId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(SubmissionCaseResults::TestCaseId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(SubmissionCaseResults::Passed)
+                        .boolean()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(SubmissionCaseResults::Status)
+                        .string()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(SubmissionCaseResults::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-submission_case_results-submission_id")
+                        .from(
+                            SubmissionCaseResults::Table,
+                            SubmissionCaseResults::SubmissionId,
+                        )
+                        .to(Submissions::Table, Submissions::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-submission_case_results-test_case_id")
+                        .from(
+                            SubmissionCaseResults::Table,
+                            SubmissionCaseResults::TestCaseId,
+                        )
+                        .to(TestCases::Table, TestCases::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(GradingDeadLetters::Table)
+                .col(
+                    ColumnDef::new(GradingDeadLetters::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(GradingDeadLetters::SubmissionId)
+                        .integer()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(
+                    ColumnDef::new(GradingDeadLetters::ClassroomId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(GradingDeadLetters::Attempts)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(GradingDeadLetters::LastError)
+                        .text()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(GradingDeadLetters::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-grading_dead_letters-submission_id")
+                        .from(GradingDeadLetters::Table, GradingDeadLetters::SubmissionId)
+                        .to(Submissions::Table, Submissions::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-grading_dead_letters-classroom_id")
+                        .from(GradingDeadLetters::Table, GradingDeadLetters::ClassroomId)
+                        .to(Classrooms::Table, Classrooms::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(ArchiveOutbox::Table)
+                .col(
+                    ColumnDef::new(ArchiveOutbox::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(ArchiveOutbox::SubmissionId)
+                        .integer()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(
+                    ColumnDef::new(ArchiveOutbox::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ArchiveOutbox::ArchivedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(ColumnDef::new(ArchiveOutbox::Attempts).integer().not_null())
+                .col(ColumnDef::new(ArchiveOutbox::LastError).text().null())
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-archive_outbox-submission_id")
+                        .from(ArchiveOutbox::Table, ArchiveOutbox::SubmissionId)
+                        .to(Submissions::Table, Submissions::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(Disputes::Table)
+                .col(
+                    ColumnDef::new(Disputes::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Disputes::SubmissionId).integer().not_null())
+                .col(ColumnDef::new(Disputes::UserId).integer().not_null())
+                .col(ColumnDef::new(Disputes::Message).text().not_null())
+                .col(ColumnDef::new(Disputes::Status).string().not_null())
+                .col(ColumnDef::new(Disputes::Response).text().null())
+                .col(ColumnDef::new(Disputes::RespondedBy).integer().null())
+                .col(ColumnDef::new(Disputes::Regraded).boolean().not_null())
+                .col(ColumnDef::new(Disputes::PreviousScore).integer().null())
+                .col(ColumnDef::new(Disputes::RegradedScore).integer().null())
+                .col(
+                    ColumnDef::new(Disputes::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(Disputes::RespondedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-disputes-submission_id")
+                        .from(Disputes::Table, Disputes::SubmissionId)
+                        .to(Submissions::Table, Submissions::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-disputes-user_id")
+                        .from(Disputes::Table, Disputes::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(VerdictMessages::Table)
+                .col(
+                    ColumnDef::new(VerdictMessages::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(VerdictMessages::ClassroomId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(VerdictMessages::StatusId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(VerdictMessages::Message).text().not_null())
+                .col(
+                    ColumnDef::new(VerdictMessages::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(VerdictMessages::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-verdict_messages-classroom_id")
+                        .from(VerdictMessages::Table, VerdictMessages::ClassroomId)
+                        .to(Classrooms::Table, Classrooms::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(CodeFingerprints::Table)
+                .col(
+                    ColumnDef::new(CodeFingerprints::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(CodeFingerprints::Hash)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(CodeFingerprints::SubmissionId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(CodeFingerprints::ClassroomId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(CodeFingerprints::Owner)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(CodeFingerprints::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(FingerprintSettings::Table)
+                .col(
+                    ColumnDef::new(FingerprintSettings::Id)
+                        .integer()
+                        .not_null()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(FingerprintSettings::RetentionDays)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(FingerprintSettings::UpdatedBy)
+                        .integer()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(FingerprintSettings::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(RunConfigs::Table)
+                .col(
+                    ColumnDef::new(RunConfigs::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(RunConfigs::UserId).integer().not_null())
+                .col(ColumnDef::new(RunConfigs::TaskId).integer().null())
+                .col(ColumnDef::new(RunConfigs::Name).string().not_null())
+                .col(ColumnDef::new(RunConfigs::Stdin).text().not_null())
+                .col(
+                    ColumnDef::new(RunConfigs::CommandLineArguments)
+                        .string()
+                        .null(),
+                )
+                .col(ColumnDef::new(RunConfigs::Options).text().not_null())
+                .col(
+                    ColumnDef::new(RunConfigs::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(RunConfigs::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-run_configs-user_id")
+                        .from(RunConfigs::Table, RunConfigs::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-run_configs-task_id")
+                        .from(RunConfigs::Table, RunConfigs::TaskId)
+                        .to(Tasks::Table, Tasks::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(TaskProgress::Table)
+                .col(
+                    ColumnDef::new(TaskProgress::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(TaskProgress::TaskId).integer().not_null())
+                .col(ColumnDef::new(TaskProgress::UserId).integer().not_null())
+                .col(
+                    ColumnDef::new(TaskProgress::FirstOpenedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(TaskProgress::LastEditedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(ColumnDef::new(TaskProgress::EditCount).integer().not_null())
+                .col(
+                    ColumnDef::new(TaskProgress::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-task_progress-task_id")
+                        .from(TaskProgress::Table, TaskProgress::TaskId)
+                        .to(Tasks::Table, Tasks::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-task_progress-user_id")
+                        .from(TaskProgress::Table, TaskProgress::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_submissions_judge0_token")
+                    .table(Submissions::Table)
+                    .col(Submissions::Judge0Token)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_submission_case_results_submission_case")
+                    .table(SubmissionCaseResults::Table)
+                    .col(SubmissionCaseResults::SubmissionId)
+                    .col(SubmissionCaseResults::TestCaseId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_submission_case_results_test_case")
+                    .table(SubmissionCaseResults::Table)
+                    .col(SubmissionCaseResults::TestCaseId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_verdict_messages_classroom_status")
+                    .table(VerdictMessages::Table)
+                    .col(VerdictMessages::ClassroomId)
+                    .col(VerdictMessages::StatusId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_code_fingerprints_hash")
+                    .table(CodeFingerprints::Table)
+                    .col(CodeFingerprints::Hash)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_code_fingerprints_created_at")
+                    .table(CodeFingerprints::Table)
+                    .col(CodeFingerprints::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_run_configs_user_task")
+                    .table(RunConfigs::Table)
+                    .col(RunConfigs::UserId)
+                    .col(RunConfigs::TaskId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_task_progress_task_user")
+                    .table(TaskProgress::Table)
+                    .col(TaskProgress::TaskId)
+                    .col(TaskProgress::UserId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TaskProgress::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(RunConfigs::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(FingerprintSettings::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(CodeFingerprints::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(VerdictMessages::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Disputes::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ArchiveOutbox::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(GradingDeadLetters::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(SubmissionCaseResults::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Submissions::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Submissions {
+    Table,
+    Id,
+    ClassroomId,
+    UserId,
+    SourceCode,
+    LanguageId,
+    Stdout,
+    Stderr,
+    CompileOutput,
+    Status,
+    Time,
+    Memory,
+    ExitCode,
+    ExitSignal,
+    IsFinal,
+    AutoSubmitted,
+    TaskId,
+    Late,
+    PassedCases,
+    TotalCases,
+    Score,
+    MaxScore,
+    GradingError,
+    GradingAttempts,
+    LastAttemptAt,
+    GradingStatus,
+    GradingCase,
+    GradingCaseCount,
+    ScriptVerdict,
+    FinishFeedback,
+    Judge0Token,
+    Judge0Instance,
+    Judge0StatusId,
+    CreatedAt,
+    GradedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum SubmissionCaseResults {
+    Table,
+    Id,
+    SubmissionId,
+    TestCaseId,
+    Passed,
+    Status,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum GradingDeadLetters {
+    Table,
+    Id,
+    SubmissionId,
+    ClassroomId,
+    Attempts,
+    LastError,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum ArchiveOutbox {
+    Table,
+    Id,
+    SubmissionId,
+    CreatedAt,
+    ArchivedAt,
+    Attempts,
+    LastError,
+}
+
+#[derive(DeriveIden)]
+pub enum Disputes {
+    Table,
+    Id,
+    SubmissionId,
+    UserId,
+    Message,
+    Status,
+    Response,
+    RespondedBy,
+    Regraded,
+    PreviousScore,
+    RegradedScore,
+    CreatedAt,
+    RespondedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum VerdictMessages {
+    Table,
+    Id,
+    ClassroomId,
+    StatusId,
+    Message,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum CodeFingerprints {
+    Table,
+    Id,
+    Hash,
+    SubmissionId,
+    ClassroomId,
+    Owner,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum FingerprintSettings {
+    Table,
+    Id,
+    RetentionDays,
+    UpdatedBy,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum RunConfigs {
+    Table,
+    Id,
+    UserId,
+    TaskId,
+    Name,
+    Stdin,
+    CommandLineArguments,
+    Options,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum TaskProgress {
+    Table,
+    Id,
+    TaskId,
+    UserId,
+    FirstOpenedAt,
+    LastEditedAt,
+    EditCount,
+    UpdatedAt,
+}
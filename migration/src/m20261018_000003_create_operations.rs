@@ -0,0 +1,969 @@
+//! Messaging, events, audit and login history, statistics, exports and server-wide settings.
+
+use sea_orm_migration::prelude::*;
+
+use crate::{
+    baseline,
+    m20261018_000001_create_classrooms::{Accounts, Classrooms, Users},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(Messages::Table)
+                .col(
+                    ColumnDef::new(Messages::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Messages::ClassroomId).integer().not_null())
+                .col(ColumnDef::new(Messages::Body).text().not_null())
+                .col(
+                    ColumnDef::new(Messages::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-messages-classroom_id")
+                        .from(Messages::Table, Messages::ClassroomId)
+                        .to(Classrooms::Table, Classrooms::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(MessageRecipients::Table)
+                .col(
+                    ColumnDef::new(MessageRecipients::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(MessageRecipients::MessageId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(MessageRecipients::UserId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(MessageRecipients::ReadAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-message_recipients-message_id")
+                        .from(MessageRecipients::Table, MessageRecipients::MessageId)
+                        .to(Messages::Table, Messages::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-message_recipients-user_id")
+                        .from(MessageRecipients::Table, MessageRecipients::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(IntegrityEvents::Table)
+                .col(
+                    ColumnDef::new(IntegrityEvents::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(IntegrityEvents::ClassroomId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(IntegrityEvents::UserId).integer().not_null())
+                .col(ColumnDef::new(IntegrityEvents::Kind).string().not_null())
+                .col(ColumnDef::new(IntegrityEvents::Detail).text().not_null())
+                .col(
+                    ColumnDef::new(IntegrityEvents::Blocked)
+                        .boolean()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(IntegrityEvents::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-integrity_events-classroom_id")
+                        .from(IntegrityEvents::Table, IntegrityEvents::ClassroomId)
+                        .to(Classrooms::Table, Classrooms::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-integrity_events-user_id")
+                        .from(IntegrityEvents::Table, IntegrityEvents::UserId)
+                        .to(Users::Table, Users::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(ClassroomEvents::Table)
+                .col(
+                    ColumnDef::new(ClassroomEvents::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(ClassroomEvents::ClassroomId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(ClassroomEvents::Kind).string().not_null())
+                .col(ColumnDef::new(ClassroomEvents::Audience).text().null())
+                .col(ColumnDef::new(ClassroomEvents::Payload).text().not_null())
+                .col(
+                    ColumnDef::new(ClassroomEvents::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-classroom_events-classroom_id")
+                        .from(ClassroomEvents::Table, ClassroomEvents::ClassroomId)
+                        .to(Classrooms::Table, Classrooms::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(ClassroomSettingsVersions::Table)
+                .col(
+                    ColumnDef::new(ClassroomSettingsVersions::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(ClassroomSettingsVersions::ClassroomId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ClassroomSettingsVersions::Version)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ClassroomSettingsVersions::Change)
+                        .string()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ClassroomSettingsVersions::Before)
+                        .text()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ClassroomSettingsVersions::After)
+                        .text()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ClassroomSettingsVersions::ChangedBy)
+                        .integer()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(ClassroomSettingsVersions::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-classroom_settings_versions-classroom_id")
+                        .from(
+                            ClassroomSettingsVersions::Table,
+                            ClassroomSettingsVersions::ClassroomId,
+                        )
+                        .to(Classrooms::Table, Classrooms::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(ClassroomAssistants::Table)
+                .col(
+                    ColumnDef::new(ClassroomAssistants::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(ClassroomAssistants::ClassroomId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ClassroomAssistants::AccountId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ClassroomAssistants::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-classroom_assistants-classroom_id")
+                        .from(ClassroomAssistants::Table, ClassroomAssistants::ClassroomId)
+                        .to(Classrooms::Table, Classrooms::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-classroom_assistants-account_id")
+                        .from(ClassroomAssistants::Table, ClassroomAssistants::AccountId)
+                        .to(Accounts::Table, Accounts::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(WebauthnCredentials::Table)
+                .col(
+                    ColumnDef::new(WebauthnCredentials::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(WebauthnCredentials::AccountId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(WebauthnCredentials::CredentialId)
+                        .string()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(
+                    ColumnDef::new(WebauthnCredentials::PublicKey)
+                        .text()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(WebauthnCredentials::SignCount)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(WebauthnCredentials::Name)
+                        .string()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(WebauthnCredentials::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(WebauthnCredentials::LastUsedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-webauthn_credentials-account_id")
+                        .from(WebauthnCredentials::Table, WebauthnCredentials::AccountId)
+                        .to(Accounts::Table, Accounts::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(WebhookEndpoints::Table)
+                .col(
+                    ColumnDef::new(WebhookEndpoints::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(WebhookEndpoints::Name).string().not_null())
+                .col(ColumnDef::new(WebhookEndpoints::Url).string().not_null())
+                .col(
+                    ColumnDef::new(WebhookEndpoints::SecretEncrypted)
+                        .text()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(WebhookEndpoints::Active)
+                        .boolean()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(WebhookEndpoints::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(WebhookEndpoints::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(AuditLog::Table)
+                .col(
+                    ColumnDef::new(AuditLog::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(AuditLog::AccountId).integer().not_null())
+                .col(ColumnDef::new(AuditLog::Kind).string().not_null())
+                .col(ColumnDef::new(AuditLog::Detail).string().not_null())
+                .col(ColumnDef::new(AuditLog::ClassroomId).integer().null())
+                .col(ColumnDef::new(AuditLog::Status).integer().null())
+                .col(ColumnDef::new(AuditLog::RequestId).string().null())
+                .col(
+                    ColumnDef::new(AuditLog::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(LoginEvents::Table)
+                .col(
+                    ColumnDef::new(LoginEvents::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(LoginEvents::Npm).string().not_null())
+                .col(ColumnDef::new(LoginEvents::AccountId).integer().null())
+                .col(ColumnDef::new(LoginEvents::Ip).string().not_null())
+                .col(ColumnDef::new(LoginEvents::Success).boolean().not_null())
+                .col(ColumnDef::new(LoginEvents::Failure).string().null())
+                .col(ColumnDef::new(LoginEvents::ClassroomId).integer().null())
+                .col(ColumnDef::new(LoginEvents::Flags).string().not_null())
+                .col(
+                    ColumnDef::new(LoginEvents::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(ClientErrors::Table)
+                .col(
+                    ColumnDef::new(ClientErrors::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(ClientErrors::Source).string().not_null())
+                .col(ColumnDef::new(ClientErrors::Kind).string().not_null())
+                .col(ColumnDef::new(ClientErrors::Message).text().not_null())
+                .col(ColumnDef::new(ClientErrors::Stack).text().null())
+                .col(ColumnDef::new(ClientErrors::Url).string().null())
+                .col(ColumnDef::new(ClientErrors::Method).string().null())
+                .col(ColumnDef::new(ClientErrors::Status).integer().null())
+                .col(ColumnDef::new(ClientErrors::RequestId).string().null())
+                .col(ColumnDef::new(ClientErrors::ClassroomId).integer().null())
+                .col(ColumnDef::new(ClientErrors::Npm).string().null())
+                .col(ColumnDef::new(ClientErrors::UserAgent).string().null())
+                .col(ColumnDef::new(ClientErrors::Context).text().null())
+                .col(
+                    ColumnDef::new(ClientErrors::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(DailyStats::Table)
+                .col(
+                    ColumnDef::new(DailyStats::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(DailyStats::Day).date().not_null())
+                .col(ColumnDef::new(DailyStats::ClassroomId).integer().not_null())
+                .col(ColumnDef::new(DailyStats::Logins).big_integer().not_null())
+                .col(
+                    ColumnDef::new(DailyStats::Submissions)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(DailyStats::FinalSubmissions)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(DailyStats::ActiveUsers)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(DailyStats::Verdicts).text().not_null())
+                .col(
+                    ColumnDef::new(DailyStats::RolledUpAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(LanguageUsage::Table)
+                .col(
+                    ColumnDef::new(LanguageUsage::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(LanguageUsage::Day).date().not_null())
+                .col(
+                    ColumnDef::new(LanguageUsage::ClassroomId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(LanguageUsage::LanguageId)
+                        .integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(LanguageUsage::Runs).big_integer().not_null())
+                .col(
+                    ColumnDef::new(LanguageUsage::Submissions)
+                        .big_integer()
+                        .not_null(),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(ExportJobs::Table)
+                .col(
+                    ColumnDef::new(ExportJobs::Id)
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(ExportJobs::ClassroomId).integer().not_null())
+                .col(ColumnDef::new(ExportJobs::Kind).string().not_null())
+                .col(ColumnDef::new(ExportJobs::Format).string().not_null())
+                .col(ColumnDef::new(ExportJobs::TimeDisplay).string().not_null())
+                .col(ColumnDef::new(ExportJobs::Status).string().not_null())
+                .col(ColumnDef::new(ExportJobs::Progress).integer().not_null())
+                .col(ColumnDef::new(ExportJobs::Error).text().null())
+                .col(ColumnDef::new(ExportJobs::FileName).string().null())
+                .col(ColumnDef::new(ExportJobs::ContentType).string().null())
+                .col(ColumnDef::new(ExportJobs::SizeBytes).big_integer().null())
+                .col(ColumnDef::new(ExportJobs::RequestedBy).integer().not_null())
+                .col(
+                    ColumnDef::new(ExportJobs::CreatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ExportJobs::StartedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(ExportJobs::FinishedAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(ExportJobs::ExpiresAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk-export_jobs-classroom_id")
+                        .from(ExportJobs::Table, ExportJobs::ClassroomId)
+                        .to(Classrooms::Table, Classrooms::Id)
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(Banner::Table)
+                .col(
+                    ColumnDef::new(Banner::Id)
+                        .integer()
+                        .not_null()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Banner::Message).text().not_null())
+                .col(ColumnDef::new(Banner::Level).string().not_null())
+                .col(
+                    ColumnDef::new(Banner::StartsAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(
+                    ColumnDef::new(Banner::EndsAt)
+                        .timestamp_with_time_zone()
+                        .null(),
+                )
+                .col(ColumnDef::new(Banner::UpdatedBy).integer().null())
+                .col(
+                    ColumnDef::new(Banner::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .to_owned(),
+        )
+        .await?;
+        baseline::create_or_complete(
+            manager,
+            Table::create()
+                .table(ExecutorConfig::Table)
+                .col(
+                    ColumnDef::new(ExecutorConfig::Id)
+                        .integer()
+                        .not_null()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(ExecutorConfig::BaseUrls).text().not_null())
+                .col(ColumnDef::new(ExecutorConfig::Strategy).string().not_null())
+                .col(
+                    ColumnDef::new(ExecutorConfig::EjectAfterFailures)
+                        .integer()
+                        .not_null(),
+                )
+                .col(
+                    ColumnDef::new(ExecutorConfig::HealthCheckIntervalSecs)
+                        .big_integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(ExecutorConfig::UpdatedBy).integer().null())
+                .col(
+                    ColumnDef::new(ExecutorConfig::UpdatedAt)
+                        .timestamp_with_time_zone()
+                        .not_null(),
+                )
+                .to_owned(),
+        )
+        .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_classroom_settings_versions_classroom_version")
+                    .table(ClassroomSettingsVersions::Table)
+                    .col(ClassroomSettingsVersions::ClassroomId)
+                    .col(ClassroomSettingsVersions::Version)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_classroom_assistants_classroom_account")
+                    .table(ClassroomAssistants::Table)
+                    .col(ClassroomAssistants::ClassroomId)
+                    .col(ClassroomAssistants::AccountId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_audit_log_account")
+                    .table(AuditLog::Table)
+                    .col(AuditLog::AccountId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_login_events_npm_created_at")
+                    .table(LoginEvents::Table)
+                    .col(LoginEvents::Npm)
+                    .col(LoginEvents::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_daily_stats_day_classroom")
+                    .table(DailyStats::Table)
+                    .col(DailyStats::Day)
+                    .col(DailyStats::ClassroomId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_language_usage_day_classroom_language")
+                    .table(LanguageUsage::Table)
+                    .col(LanguageUsage::Day)
+                    .col(LanguageUsage::ClassroomId)
+                    .col(LanguageUsage::LanguageId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_export_jobs_classroom")
+                    .table(ExportJobs::Table)
+                    .col(ExportJobs::ClassroomId)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ExecutorConfig::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Banner::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ExportJobs::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(LanguageUsage::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(DailyStats::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ClientErrors::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(LoginEvents::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(AuditLog::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(WebhookEndpoints::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(WebauthnCredentials::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ClassroomAssistants::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ClassroomSettingsVersions::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ClassroomEvents::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(IntegrityEvents::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(MessageRecipients::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Messages::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Messages {
+    Table,
+    Id,
+    ClassroomId,
+    Body,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum MessageRecipients {
+    Table,
+    Id,
+    MessageId,
+    UserId,
+    ReadAt,
+}
+
+#[derive(DeriveIden)]
+pub enum IntegrityEvents {
+    Table,
+    Id,
+    ClassroomId,
+    UserId,
+    Kind,
+    Detail,
+    Blocked,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum ClassroomEvents {
+    Table,
+    Id,
+    ClassroomId,
+    Kind,
+    Audience,
+    Payload,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum ClassroomSettingsVersions {
+    Table,
+    Id,
+    ClassroomId,
+    Version,
+    Change,
+    Before,
+    After,
+    ChangedBy,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum ClassroomAssistants {
+    Table,
+    Id,
+    ClassroomId,
+    AccountId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum WebauthnCredentials {
+    Table,
+    Id,
+    AccountId,
+    CredentialId,
+    PublicKey,
+    SignCount,
+    Name,
+    CreatedAt,
+    LastUsedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum WebhookEndpoints {
+    Table,
+    Id,
+    Name,
+    Url,
+    SecretEncrypted,
+    Active,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum AuditLog {
+    Table,
+    Id,
+    AccountId,
+    Kind,
+    Detail,
+    ClassroomId,
+    Status,
+    RequestId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum LoginEvents {
+    Table,
+    Id,
+    Npm,
+    AccountId,
+    Ip,
+    Success,
+    Failure,
+    ClassroomId,
+    Flags,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum ClientErrors {
+    Table,
+    Id,
+    Source,
+    Kind,
+    Message,
+    Stack,
+    Url,
+    Method,
+    Status,
+    RequestId,
+    ClassroomId,
+    Npm,
+    UserAgent,
+    Context,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum DailyStats {
+    Table,
+    Id,
+    Day,
+    ClassroomId,
+    Logins,
+    Submissions,
+    FinalSubmissions,
+    ActiveUsers,
+    Verdicts,
+    RolledUpAt,
+}
+
+#[derive(DeriveIden)]
+pub enum LanguageUsage {
+    Table,
+    Id,
+    Day,
+    ClassroomId,
+    LanguageId,
+    Runs,
+    Submissions,
+}
+
+#[derive(DeriveIden)]
+pub enum ExportJobs {
+    Table,
+    Id,
+    ClassroomId,
+    Kind,
+    Format,
+    TimeDisplay,
+    Status,
+    Progress,
+    Error,
+    FileName,
+    ContentType,
+    SizeBytes,
+    RequestedBy,
+    CreatedAt,
+    StartedAt,
+    FinishedAt,
+    ExpiresAt,
+}
+
+#[derive(DeriveIden)]
+pub enum Banner {
+    Table,
+    Id,
+    Message,
+    Level,
+    StartsAt,
+    EndsAt,
+    UpdatedBy,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+pub enum ExecutorConfig {
+    Table,
+    Id,
+    BaseUrls,
+    Strategy,
+    EjectAfterFailures,
+    HealthCheckIntervalSecs,
+    UpdatedBy,
+    UpdatedAt,
+}
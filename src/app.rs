@@ -0,0 +1,263 @@
+//! Assembles the server from its settings. Shared by the server binary and the
+//! embedded test server so both run the same state, background jobs and router.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{
+    Router,
+    http::{
+        HeaderName, HeaderValue, Method,
+        header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    },
+    middleware,
+};
+use reqwest::Client;
+use sea_orm::DatabaseConnection;
+use tower_http::{
+    compression::CompressionLayer,
+    cors::{AllowOrigin, CorsLayer},
+};
+
+use crate::{auth, config, docs, dto, routes, services, setup, state::AppState};
+
+pub fn build_state(settings: &config::Settings, mut db: DatabaseConnection) -> Result<AppState> {
+    let http_client = Client::builder().build()?;
+    let metrics: Arc<services::metrics::Metrics> = Default::default();
+    services::sql_trace::install(
+        &mut db,
+        Arc::clone(&metrics),
+        (settings.sql_slow_query_ms > 0)
+            .then(|| std::time::Duration::from_millis(settings.sql_slow_query_ms)),
+    );
+
+    let jwt_secret = settings.jwt_secret.clone().unwrap_or_else(|| {
+        tracing::warn!(
+            "JWT_SECRET tidak diatur; memakai secret acak, token tidak berlaku lagi setelah restart"
+        );
+        let bytes: [u8; 32] = rand::random();
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    });
+    let secret_key = settings.secret_encryption_key.clone().unwrap_or_else(|| {
+        tracing::warn!("SECRET_ENCRYPTION_KEY tidak diatur; secret terenkripsi memakai kunci turunan JWT_SECRET");
+        format!("asm-lab-secret-box:{jwt_secret}")
+    });
+
+    let exam_warning_thresholds =
+        services::exam_clock::parse_warning_thresholds(&settings.exam_warning_thresholds)
+            .expect("EXAM_WARNING_THRESHOLDS harus berupa daftar durasi, mis. 15m,5m,1m");
+
+    Ok(AppState {
+        db,
+        http_client,
+        executors: Arc::new(services::executor_pool::ExecutorPool::new(
+            services::executor_pool::PoolSettings {
+                base_urls: settings.judge0_base_urls.clone(),
+                strategy: settings.judge0_balance,
+                eject_after_failures: settings.judge0_eject_after_failures.max(1),
+                health_check_interval: std::time::Duration::from_secs(
+                    settings.judge0_health_interval_secs.max(1),
+                ),
+            },
+        )),
+        judge0_timeout: std::time::Duration::from_secs(settings.judge0_timeout_secs),
+        executor_queue: Arc::new(services::admission::ExecutorQueue::new(
+            settings.judge0_queue_size,
+        )),
+        exam_student_capacity: (settings.exam_student_capacity > 0)
+            .then_some(settings.exam_student_capacity),
+        grading_max_attempts: settings.grading_max_attempts.max(1),
+        output_limit_kb: settings
+            .output_limit_kb
+            .clamp(1, services::output::MAX_LIMIT_KB as u32),
+        field_limits: services::field_limits::FieldLimits::new(
+            settings.max_code_chars,
+            settings.max_description_chars,
+        ),
+        exam_warning_thresholds: exam_warning_thresholds.into(),
+        time_policy: services::time_policy::TimePolicy::new(
+            settings.exam_clock_tolerance_secs,
+            settings.exam_submit_grace_secs,
+        ),
+        events: services::event_bus::EventBus::new(),
+        connections: Arc::new(services::connections::ConnectionTracker::new(
+            settings.sse_max_connections,
+        )),
+        started_at: chrono::Utc::now(),
+        metrics,
+        error_budget: Arc::new(services::alerting::ErrorBudget::new(
+            services::alerting::AlertRules {
+                window: std::time::Duration::from_secs(settings.alert_window_secs.max(1)),
+                cooldown: std::time::Duration::from_secs(settings.alert_cooldown_secs),
+                route_server_errors: settings.alert_route_5xx_threshold,
+                judge0_failures: settings.alert_judge0_failure_threshold,
+                database_errors: settings.alert_db_error_threshold,
+            },
+        )),
+        jwt: Arc::new(auth::jwt::JwtKeys::new(
+            jwt_secret.as_bytes(),
+            chrono::Duration::hours(settings.jwt_ttl_hours),
+        )),
+        secrets: Arc::new(services::secret_box::SecretBox::new(&secret_key)),
+        rate_limit: Arc::new(services::rate_limit::SubmissionLimiter::new(
+            settings.submission_rate_limit_min_per_minute,
+            settings.submission_rate_limit_per_minute,
+            settings.judge0_latency_target_ms,
+        )),
+        account_email_enabled: settings.account_email_enabled,
+        account_approval_required: settings.account_approval_required,
+        mail_relay_url: settings.mail_relay_url.clone(),
+        // Not driven by the latency controller, so the rate stays fixed.
+        email_rate_limit: Arc::new(services::rate_limit::SubmissionLimiter::new(
+            services::mailer::REQUESTS_PER_MINUTE,
+            services::mailer::REQUESTS_PER_MINUTE,
+            settings.judge0_latency_target_ms,
+        )),
+        openapi_baseline: settings.openapi_baseline.clone(),
+        client_error_sample_rate: settings.client_error_sample_rate.clamp(0.0, 1.0),
+        display_timezone: settings.display_timezone,
+        fingerprint_retention_days: settings.fingerprint_retention_days,
+        branding: Arc::new(dto::ClientBranding {
+            name: settings.client_brand_name.clone(),
+            logo_url: settings.client_brand_logo_url.clone(),
+            support_contact: settings.client_support_contact.clone(),
+        }),
+        export_storage: Arc::new(services::export_storage::ExportStorage::new(
+            settings.export_storage_dir.clone(),
+        )),
+        export_url_ttl_secs: settings.export_url_ttl_secs.max(1),
+        finish_nonces: Arc::new(services::replay_guard::NonceCache::new()),
+        replication: Arc::new(
+            services::replication::Replication::new(services::replication::ReplicationSettings {
+                role: settings.replication_role,
+                standby_url: settings.replication_standby_url.clone(),
+                primary_url: settings.replication_primary_url.clone(),
+                secret: settings.replication_secret.clone(),
+                interval_secs: settings.replication_interval_secs,
+                snapshot_path: settings.replication_snapshot_path.clone(),
+            })
+            .map_err(anyhow::Error::msg)?,
+        ),
+        archive: settings
+            .archive_sink
+            .as_deref()
+            .map(services::archive::ArchiveTarget::parse)
+            .transpose()
+            .map_err(anyhow::Error::msg)?
+            .map(|target| Arc::new(services::archive::Archive::new(target))),
+        judge0_polls: Arc::new(tokio::sync::Notify::new()),
+        #[cfg(feature = "chaos")]
+        chaos: Arc::default(),
+        webauthn: settings.webauthn_rp_id.clone().map(|rp_id| {
+            Arc::new(auth::webauthn::WebAuthn::new(
+                rp_id,
+                settings.webauthn_rp_name.clone(),
+                settings.webauthn_origins.clone(),
+                settings.webauthn_required_roles.clone(),
+            ))
+        }),
+    })
+}
+
+/// Loads the stored executor configuration and starts the background jobs.
+pub async fn start_background(state: &AppState, settings: &config::Settings) -> Result<()> {
+    let task_due_reminders =
+        services::exam_clock::parse_warning_thresholds(&settings.task_due_reminders)
+            .expect("TASK_DUE_REMINDERS harus berupa daftar durasi, mis. 24h,1h");
+
+    services::executor_pool::load_stored(&state.db, &state.executors).await?;
+
+    #[cfg(feature = "chaos")]
+    tracing::warn!(
+        "dibangun dengan fitur chaos: admin dapat menyuntikkan gangguan lewat /api/admin/chaos; jangan dipakai saat ujian"
+    );
+
+    services::finalizer::spawn(state.clone());
+    services::exam_gate::spawn(state.clone());
+    services::grading_retry::spawn(state.clone());
+    services::language_map::spawn(
+        state.clone(),
+        std::time::Duration::from_secs(settings.language_refresh_secs.max(60)),
+    );
+    services::executor_pool::spawn_health_checks(state.clone());
+    services::task_deadline::spawn(state.clone(), task_due_reminders.into());
+    services::daily_stats::spawn(state.clone());
+    services::rate_limit::spawn_controller(state.clone());
+    services::retention::spawn(state.clone(), settings.event_retention_days);
+    services::fingerprint::spawn_pruning(state.clone());
+    services::export_jobs::spawn(state.clone());
+    services::time_policy::spawn_drift_check(state.clone(), settings.clock_reference_url.clone());
+    services::replication::spawn(state.clone());
+    services::archive::spawn(state.clone());
+    services::judge0_poll::spawn(state.clone());
+    Ok(())
+}
+
+/// The API, docs and setup page with every middleware applied. Serve it with
+/// `into_make_service_with_connect_info::<SocketAddr>()`; some handlers read the
+/// client address.
+pub fn router(state: AppState) -> Router {
+    let api_router = routes::api_router(&state);
+
+    let allowed_origins = AllowOrigin::list(
+        config::ALLOWED_ORIGINS
+            .iter()
+            .map(|origin| HeaderValue::from_static(origin)),
+    );
+
+    let cors = CorsLayer::new()
+        .allow_origin(allowed_origins)
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers([
+            ACCEPT,
+            AUTHORIZATION,
+            CONTENT_TYPE,
+            IF_NONE_MATCH,
+            dto::version::ACCEPT_VERSION,
+            auth::device::DEVICE_FINGERPRINT_HEADER,
+            routes::request_id::REQUEST_ID,
+            services::replay_guard::REQUEST_NONCE,
+            services::replay_guard::REQUEST_TIMESTAMP,
+        ])
+        .expose_headers([
+            HeaderName::from_static("x-total-count"),
+            ETAG,
+            dto::version::API_VERSION,
+            routes::request_id::REQUEST_ID,
+        ]);
+
+    let router = Router::new()
+        .nest("/api", api_router)
+        .merge(docs::router(state.clone()))
+        .merge(setup::router());
+    #[cfg(feature = "chaos")]
+    let router = router.layer(middleware::from_fn_with_state(
+        state.clone(),
+        routes::chaos::inject,
+    ));
+
+    router
+        // Event streams are left uncompressed by the default predicate.
+        .layer(CompressionLayer::new())
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            routes::audit::record_staff_actions,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            routes::replication::refuse_writes_on_standby,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            routes::request_id::assign,
+        ))
+        .layer(cors)
+        .with_state(state)
+}
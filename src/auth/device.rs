@@ -0,0 +1,122 @@
+use std::convert::Infallible;
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{HeaderName, request::Parts},
+};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, IntoActiveModel};
+
+use crate::{
+    dto::{DevicePolicy, IntegrityAlertEvent},
+    entities::{classroom, integrity_event, user},
+    error::AppError,
+    services::event_bus::{Audience, EventKind},
+    state::AppState,
+};
+
+pub const DEVICE_FINGERPRINT_HEADER: HeaderName = HeaderName::from_static("x-device-fingerprint");
+
+const MAX_FINGERPRINT_LEN: usize = 256;
+
+/// Client-provided device fingerprint from the `X-Device-Fingerprint` header.
+pub struct DeviceFingerprint(pub Option<String>);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for DeviceFingerprint {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let fingerprint = parts
+            .headers
+            .get(DEVICE_FINGERPRINT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.chars().take(MAX_FINGERPRINT_LEN).collect());
+
+        Ok(Self(fingerprint))
+    }
+}
+
+/// Checks an exam request against the device the attempt is bound to.
+///
+/// With `bind_if_unbound` (the exam login) an unbound attempt is bound to the given
+/// fingerprint. A mismatch is recorded as an integrity event and, under the `block`
+/// policy, rejected. Returns the user with the binding applied.
+pub async fn enforce(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+    user_model: user::Model,
+    fingerprint: Option<&str>,
+    action: &str,
+    bind_if_unbound: bool,
+) -> Result<user::Model, AppError> {
+    let policy =
+        DevicePolicy::from_str(&classroom_model.device_policy).unwrap_or(DevicePolicy::Off);
+    if !classroom_model.is_exam || policy == DevicePolicy::Off {
+        return Ok(user_model);
+    }
+
+    let Some(bound) = user_model.device_fingerprint.clone() else {
+        return match fingerprint {
+            Some(fingerprint) if bind_if_unbound => {
+                let mut user_am = user_model.into_active_model();
+                user_am.device_fingerprint = Set(Some(fingerprint.to_owned()));
+                user_am.device_bound_at = Set(Some(Utc::now()));
+                user_am.updated_at = Set(Utc::now());
+                Ok(user_am.update(&state.db).await?)
+            }
+            _ => Ok(user_model),
+        };
+    };
+
+    if fingerprint == Some(bound.as_str()) {
+        return Ok(user_model);
+    }
+
+    let blocked = policy == DevicePolicy::Block;
+    let detail = match fingerprint {
+        Some(fingerprint) => format!(
+            "{action} from device {} while the attempt is bound to {}",
+            short(fingerprint),
+            short(&bound)
+        ),
+        None => format!("{action} without a device fingerprint while the attempt is bound"),
+    };
+    tracing::warn!("integrity event for {}: {detail}", user_model.npm);
+
+    let recorded = integrity_event::ActiveModel {
+        classroom_id: Set(classroom_model.id),
+        user_id: Set(user_model.id),
+        kind: Set("device_mismatch".to_owned()),
+        detail: Set(detail),
+        blocked: Set(blocked),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await?;
+    state
+        .events
+        .publish(
+            &state.db,
+            classroom_model.id,
+            Audience::Staff,
+            EventKind::Integrity(IntegrityAlertEvent::from_models(&recorded, &user_model)),
+        )
+        .await;
+
+    if blocked {
+        return Err(AppError::DeviceMismatch(
+            "this exam attempt is bound to another device".into(),
+        ));
+    }
+    Ok(user_model)
+}
+
+/// Prefix of a fingerprint, enough for proctors to tell devices apart.
+fn short(fingerprint: &str) -> String {
+    fingerprint.chars().take(12).collect()
+}
@@ -0,0 +1,100 @@
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::{Deserialize, Serialize};
+
+use crate::{entities::account, error::AppError};
+
+/// Claims carried by access tokens. The role is informational; handlers re-read the
+/// account so role changes and deletions take effect immediately.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Claims {
+    /// Account id.
+    pub sub: i32,
+    pub npm: String,
+    pub role: String,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+/// Claims of the emailed verification link. Lacking `npm` and `role`, it cannot be
+/// used as an access token, and access tokens lack `purpose`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmailClaims {
+    /// Account id.
+    pub sub: i32,
+    pub email: String,
+    pub purpose: String,
+    pub exp: i64,
+}
+
+const VERIFY_EMAIL_PURPOSE: &str = "verify-email";
+
+/// How long an emailed verification link stays valid.
+const VERIFY_EMAIL_TTL_HOURS: i64 = 24;
+
+/// HS256 signing keys and token lifetime.
+pub struct JwtKeys {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    ttl: Duration,
+}
+
+impl JwtKeys {
+    pub fn new(secret: &[u8], ttl: Duration) -> Self {
+        Self {
+            encoding: EncodingKey::from_secret(secret),
+            decoding: DecodingKey::from_secret(secret),
+            ttl,
+        }
+    }
+
+    /// Signs a token for the account, returning it with its expiry.
+    pub fn issue(&self, account: &account::Model) -> Result<(String, DateTime<Utc>), AppError> {
+        let now = Utc::now();
+        let expires_at = now + self.ttl;
+        let claims = Claims {
+            sub: account.id,
+            npm: account.npm.clone(),
+            role: account.role.clone(),
+            iat: now.timestamp(),
+            exp: expires_at.timestamp(),
+        };
+
+        let token = encode(&Header::default(), &claims, &self.encoding)
+            .map_err(|err| AppError::Unauthorized(format!("Gagal membuat token: {err}")))?;
+        Ok((token, expires_at))
+    }
+
+    pub fn verify(&self, token: &str) -> Result<Claims, AppError> {
+        decode::<Claims>(token, &self.decoding, &Validation::default())
+            .map(|data| data.claims)
+            .map_err(|_| AppError::Unauthorized("Token tidak valid atau kedaluwarsa.".into()))
+    }
+
+    /// Signs the link token confirming that the account owns `email`.
+    pub fn issue_email_verification(
+        &self,
+        account: &account::Model,
+        email: &str,
+    ) -> Result<String, AppError> {
+        let claims = EmailClaims {
+            sub: account.id,
+            email: email.to_owned(),
+            purpose: VERIFY_EMAIL_PURPOSE.to_owned(),
+            exp: (Utc::now() + Duration::hours(VERIFY_EMAIL_TTL_HOURS)).timestamp(),
+        };
+
+        encode(&Header::default(), &claims, &self.encoding)
+            .map_err(|err| AppError::Internal(format!("Gagal membuat token verifikasi: {err}")))
+    }
+
+    pub fn verify_email_verification(&self, token: &str) -> Result<EmailClaims, AppError> {
+        decode::<EmailClaims>(token, &self.decoding, &Validation::default())
+            .map(|data| data.claims)
+            .ok()
+            .filter(|claims| claims.purpose == VERIFY_EMAIL_PURPOSE)
+            .ok_or_else(|| {
+                AppError::BadRequest("Tautan verifikasi tidak valid atau kedaluwarsa.".into())
+            })
+    }
+}
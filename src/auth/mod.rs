@@ -0,0 +1,211 @@
+pub mod device;
+pub mod jwt;
+pub mod permissions;
+pub mod webauthn;
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, MatchedPath, RawPathParams, Request, State},
+    http::{HeaderMap, Method, Uri, header::AUTHORIZATION, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
+
+use crate::{
+    auth::permissions::{Permission, RouteAccess},
+    dto::{AccountRole, AccountStatus, Permissions},
+    entities::{account, user},
+    error::AppError,
+    state::AppState,
+};
+
+/// Account behind the request's `Authorization: Bearer` token.
+#[derive(Clone)]
+pub struct AuthAccount {
+    pub account: account::Model,
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for AuthAccount {
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        // Already verified by `require_token` on routes behind it.
+        if let Some(auth) = parts.extensions.get::<AuthAccount>() {
+            return Ok(auth.clone());
+        }
+        let token = bearer_token(&parts.headers)
+            .ok_or_else(|| AppError::Unauthorized("Token wajib disertakan.".into()))?;
+
+        Self::from_token(state, token).await
+    }
+}
+
+impl AuthAccount {
+    /// Verifies the token and reloads the account it was issued to.
+    pub async fn from_token(state: &AppState, token: &str) -> Result<Self, AppError> {
+        let claims = state.jwt.verify(token)?;
+        let account = account::Entity::find_by_id(claims.sub)
+            .one(&state.db)
+            .await?
+            .ok_or_else(|| AppError::Unauthorized("Akun tidak ditemukan.".into()))?;
+        ensure_approved(&account)?;
+
+        Ok(Self { account })
+    }
+
+    pub fn is_admin(&self) -> bool {
+        AccountRole::from_str(&self.account.role) == Some(AccountRole::Admin)
+    }
+
+    pub fn require_admin(&self) -> Result<(), AppError> {
+        if !self.is_admin() {
+            return Err(AppError::Forbidden("admin access required".into()));
+        }
+        Ok(())
+    }
+
+    /// Admins, assistants and observers, as opposed to students.
+    pub fn is_staff(&self) -> bool {
+        matches!(
+            AccountRole::from_str(&self.account.role),
+            Some(AccountRole::Admin | AccountRole::Assistant | AccountRole::Observer)
+        )
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        permissions::granted(AccountRole::from_str(&self.account.role), permission)
+    }
+
+    pub fn require(&self, permission: Permission) -> Result<(), AppError> {
+        if !self.can(permission) {
+            return Err(AppError::Forbidden(format!(
+                "{} permission required",
+                permission.as_str()
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn permissions(&self) -> Permissions {
+        permissions::for_role(AccountRole::from_str(&self.account.role))
+    }
+
+    /// Students may only act as the roster entries with their own NPM; staff who
+    /// manage classrooms may act for any student.
+    pub fn require_acting_as(&self, npm: &str) -> Result<(), AppError> {
+        if self.can(Permission::ManageClassrooms) || self.account.npm == npm.trim() {
+            return Ok(());
+        }
+        Err(AppError::Forbidden(
+            "students can only act as themselves".into(),
+        ))
+    }
+
+    /// Staff may open any classroom; students need [`Permission::Submit`] and a roster
+    /// entry in `classroom_id` when the route names one.
+    pub async fn require_member(
+        &self,
+        db: &DatabaseConnection,
+        classroom_id: Option<i32>,
+    ) -> Result<(), AppError> {
+        if self.is_staff() {
+            return Ok(());
+        }
+        self.require(Permission::Submit)?;
+        let Some(classroom_id) = classroom_id else {
+            return Ok(());
+        };
+        let enrolled = user::Entity::find()
+            .filter(user::Column::ClassroomId.eq(classroom_id))
+            .filter(user::Column::Npm.eq(&self.account.npm))
+            .count(db)
+            .await?
+            > 0;
+        if !enrolled {
+            return Err(AppError::Forbidden(
+                "you are not on this classroom's roster".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Refuses requests without a valid token on the routes it is layered on, and hands
+/// the verified account on to their [`AuthAccount`] extractors. Event streams may pass
+/// the token as `?token=` because `EventSource` cannot send headers.
+pub async fn require_token(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let token = bearer_token(request.headers())
+        .map(str::to_owned)
+        .or_else(|| event_stream_token(request.method(), request.uri()))
+        .ok_or_else(|| AppError::Unauthorized("Token wajib disertakan.".into()))?;
+    let auth = AuthAccount::from_token(&state, &token).await?;
+    request.extensions_mut().insert(auth);
+    Ok(next.run(request).await)
+}
+
+/// Applies [`permissions::route_access`] to the matched route. Layered inside
+/// [`require_token`], which has already put the caller's account in the extensions.
+pub async fn authorize(
+    State(state): State<AppState>,
+    route: MatchedPath,
+    params: RawPathParams,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let auth = request
+        .extensions()
+        .get::<AuthAccount>()
+        .cloned()
+        .ok_or_else(|| AppError::Unauthorized("Token wajib disertakan.".into()))?;
+    match permissions::route_access(request.method(), route.as_str()) {
+        RouteAccess::Requires(permission) => auth.require(permission)?,
+        RouteAccess::ClassroomMembers => {
+            let classroom_id = params
+                .iter()
+                .find(|(name, _)| matches!(*name, "id" | "classroom_id"))
+                .and_then(|(_, value)| value.parse().ok());
+            auth.require_member(&state.db, classroom_id).await?;
+        }
+    }
+    Ok(next.run(request).await)
+}
+
+fn event_stream_token(method: &Method, uri: &Uri) -> Option<String> {
+    if method != Method::GET || !uri.path().ends_with("/events") {
+        return None;
+    }
+    uri.query()?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+        .filter(|token| !token.is_empty())
+        .map(str::to_owned)
+}
+
+/// Refuses accounts still waiting for admin approval.
+pub fn ensure_approved(account: &account::Model) -> Result<(), AppError> {
+    if AccountStatus::from_str(&account.status) == Some(AccountStatus::Pending) {
+        return Err(AppError::AccountPending(
+            "Akun menunggu persetujuan admin.".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Token from an `Authorization: Bearer` header, if present and non-empty.
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
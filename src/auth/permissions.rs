@@ -0,0 +1,101 @@
+use axum::http::Method;
+
+use crate::dto::{AccountRole, Permissions};
+
+/// Actions the API authorizes per role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Create, edit, publish and proctor classrooms.
+    ManageClassrooms,
+    /// Create, approve, import and export accounts and change their roles.
+    ManageAccounts,
+    /// Answer disputes and regrade submissions.
+    Grade,
+    /// Read other students' submitted code; assistants only in classrooms assigned to
+    /// them (see [`crate::services::code_visibility`]).
+    ViewCode,
+    /// Submit code as a student of a classroom.
+    Submit,
+}
+
+impl Permission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::ManageClassrooms => "manage_classrooms",
+            Permission::ManageAccounts => "manage_accounts",
+            Permission::Grade => "grade",
+            Permission::ViewCode => "view_code",
+            Permission::Submit => "submit",
+        }
+    }
+}
+
+/// The permission matrix: whether `role` may perform `permission`.
+pub fn granted(role: Option<AccountRole>, permission: Permission) -> bool {
+    match role {
+        Some(AccountRole::Admin) => matches!(
+            permission,
+            Permission::ManageClassrooms
+                | Permission::ManageAccounts
+                | Permission::Grade
+                | Permission::ViewCode
+        ),
+        Some(AccountRole::Assistant) => permission == Permission::ViewCode,
+        Some(AccountRole::User) => permission == Permission::Submit,
+        Some(AccountRole::Observer) => false,
+        None => false,
+    }
+}
+
+/// Every permission of the role, as reported to clients.
+pub fn for_role(role: Option<AccountRole>) -> Permissions {
+    Permissions {
+        manage_classrooms: granted(role.clone(), Permission::ManageClassrooms),
+        manage_accounts: granted(role.clone(), Permission::ManageAccounts),
+        grade: granted(role.clone(), Permission::Grade),
+        view_code: granted(role.clone(), Permission::ViewCode),
+        submit: granted(role, Permission::Submit),
+    }
+}
+
+/// Who may call a route behind [`crate::auth::require_token`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteAccess {
+    /// Accounts whose role has the permission.
+    Requires(Permission),
+    /// Staff of any role, and students on the roster of the classroom in the path.
+    /// Handlers still check that students only act as themselves.
+    ClassroomMembers,
+}
+
+/// The routes students use while taking a class; every other route behind the token
+/// is for staff.
+const MEMBER_ROUTES: &[(Method, &str)] = &[
+    (Method::GET, "/classrooms"),
+    (Method::GET, "/classrooms/:id"),
+    (Method::GET, "/classrooms/:id/events"),
+    (Method::POST, "/classrooms/:id/finish"),
+    (Method::GET, "/classrooms/:id/grading-status"),
+    (Method::GET, "/classrooms/:id/users"),
+    (Method::PUT, "/classrooms/:classroom_id/users/:user_id"),
+    (Method::GET, "/classrooms/:id/tasks/:task_id/rendered"),
+    (Method::POST, "/classrooms/:id/tasks/:task_id/run"),
+    (Method::POST, "/classrooms/:id/tasks/:task_id/progress"),
+    (Method::POST, "/judge0/submissions"),
+    (Method::GET, "/judge0/submissions/:token"),
+];
+
+/// Access rule for `route`, the matched path pattern such as `/api/classrooms/:id`.
+pub fn route_access(method: &Method, route: &str) -> RouteAccess {
+    let route = route.strip_prefix("/api").unwrap_or(route);
+    if MEMBER_ROUTES
+        .iter()
+        .any(|(member_method, member_route)| member_method == method && *member_route == route)
+    {
+        RouteAccess::ClassroomMembers
+    } else if route.starts_with("/accounts") || route.starts_with("/admin/accounts") {
+        RouteAccess::Requires(Permission::ManageAccounts)
+    } else {
+        RouteAccess::Requires(Permission::ManageClassrooms)
+    }
+}
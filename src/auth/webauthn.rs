@@ -0,0 +1,590 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use ring::{digest, signature};
+use serde::Deserialize;
+use serde_cbor::Value;
+
+use crate::{dto::AccountRole, error::AppError};
+
+/// How long a started registration or login may take to finish.
+pub const CEREMONY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Ceremonies of one kind an account may have open at once; starting another drops
+/// the oldest. Login can be started without a token, so this keeps the challenge map
+/// bounded by the number of accounts with a passkey.
+const MAX_PENDING_PER_ACCOUNT: usize = 5;
+
+/// COSE algorithm ids offered at registration: ES256 and RS256.
+pub const ES256: i64 = -7;
+pub const RS256: i64 = -257;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ceremony {
+    Register,
+    Login,
+}
+
+impl Ceremony {
+    /// `type` the browser puts in the client data.
+    fn client_data_type(&self) -> &'static str {
+        match self {
+            Ceremony::Register => "webauthn.create",
+            Ceremony::Login => "webauthn.get",
+        }
+    }
+}
+
+struct PendingChallenge {
+    ceremony: Ceremony,
+    account_id: i32,
+    expires_at: Instant,
+}
+
+/// Credential accepted by [`WebAuthn::verify_registration`], encoded for storage.
+pub struct NewCredential {
+    pub credential_id: String,
+    pub public_key: String,
+    pub sign_count: u32,
+}
+
+/// Relying party settings and the challenges of ceremonies in progress. Only `none`
+/// attestation is requested, so registration trusts the authenticator's key without
+/// checking who made the authenticator.
+pub struct WebAuthn {
+    pub rp_id: String,
+    pub rp_name: String,
+    origins: Vec<String>,
+    required_roles: Vec<AccountRole>,
+    challenges: Mutex<HashMap<String, PendingChallenge>>,
+}
+
+impl WebAuthn {
+    pub fn new(
+        rp_id: String,
+        rp_name: String,
+        origins: Vec<String>,
+        required_roles: Vec<AccountRole>,
+    ) -> Self {
+        Self {
+            rp_id,
+            rp_name,
+            origins,
+            required_roles,
+            challenges: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Accounts of the role must log in with a passkey once they have one.
+    pub fn required_for(&self, role: Option<AccountRole>) -> bool {
+        role.is_some_and(|role| self.required_roles.contains(&role))
+    }
+
+    /// Starts a ceremony for the account, returning its single-use challenge.
+    pub fn challenge(&self, ceremony: Ceremony, account_id: i32) -> String {
+        let now = Instant::now();
+        let challenge = URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>());
+        let mut challenges = self.challenges.lock().expect("challenge map poisoned");
+        challenges.retain(|_, pending| pending.expires_at > now);
+        let open: Vec<(&String, Instant)> = challenges
+            .iter()
+            .filter(|(_, pending)| pending.ceremony == ceremony && pending.account_id == account_id)
+            .map(|(challenge, pending)| (challenge, pending.expires_at))
+            .collect();
+        if open.len() >= MAX_PENDING_PER_ACCOUNT
+            && let Some(oldest) = open
+                .into_iter()
+                .min_by_key(|&(_, expires_at)| expires_at)
+                .map(|(challenge, _)| challenge.clone())
+        {
+            challenges.remove(&oldest);
+        }
+        challenges.insert(
+            challenge.clone(),
+            PendingChallenge {
+                ceremony,
+                account_id,
+                expires_at: now + CEREMONY_TIMEOUT,
+            },
+        );
+        challenge
+    }
+
+    /// Checks a `navigator.credentials.create()` result against the account's
+    /// registration challenge.
+    pub fn verify_registration(
+        &self,
+        account_id: i32,
+        client_data_json: &str,
+        attestation_object: &str,
+    ) -> Result<NewCredential, AppError> {
+        self.check_client_data(client_data_json, Ceremony::Register, account_id)?;
+
+        let attestation: Value = serde_cbor::from_slice(&decode(attestation_object)?)
+            .map_err(|_| invalid("attestationObject bukan CBOR"))?;
+        let Value::Map(attestation) = attestation else {
+            return Err(invalid("attestationObject bukan map"));
+        };
+        let Some(Value::Bytes(auth_data)) = attestation.get(&Value::Text("authData".into())) else {
+            return Err(invalid("authData tidak ada"));
+        };
+
+        let auth_data = self.check_authenticator_data(auth_data)?;
+        if auth_data.flags & FLAG_ATTESTED_CREDENTIAL == 0 {
+            return Err(invalid("data kredensial tidak ada"));
+        }
+        let attested = auth_data.rest;
+        if attested.len() < 18 {
+            return Err(invalid("data kredensial terpotong"));
+        }
+        // AAGUID (16 bytes), credential id length, credential id, COSE key.
+        let id_len = u16::from_be_bytes([attested[16], attested[17]]) as usize;
+        let credential_id = attested
+            .get(18..18 + id_len)
+            .ok_or_else(|| invalid("id kredensial terpotong"))?;
+        let key_and_extensions = &attested[18 + id_len..];
+
+        let mut deserializer = serde_cbor::Deserializer::from_slice(key_and_extensions);
+        Value::deserialize(&mut deserializer).map_err(|_| invalid("kunci publik bukan CBOR"))?;
+        let public_key = &key_and_extensions[..deserializer.byte_offset()];
+        PublicKey::from_cose(public_key)?;
+
+        Ok(NewCredential {
+            credential_id: URL_SAFE_NO_PAD.encode(credential_id),
+            public_key: URL_SAFE_NO_PAD.encode(public_key),
+            sign_count: auth_data.sign_count,
+        })
+    }
+
+    /// Checks a `navigator.credentials.get()` result made with the stored key against
+    /// the account's login challenge. Returns the authenticator's new signature counter.
+    pub fn verify_assertion(
+        &self,
+        account_id: i32,
+        public_key: &str,
+        stored_sign_count: i64,
+        client_data_json: &str,
+        authenticator_data: &str,
+        signature: &str,
+    ) -> Result<u32, AppError> {
+        self.check_client_data(client_data_json, Ceremony::Login, account_id)?;
+
+        let raw_auth_data = decode(authenticator_data)?;
+        let auth_data = self.check_authenticator_data(&raw_auth_data)?;
+
+        let client_data_hash = digest::digest(&digest::SHA256, &decode(client_data_json)?);
+        let mut signed = raw_auth_data.clone();
+        signed.extend_from_slice(client_data_hash.as_ref());
+        let public_key = PublicKey::from_cose(&decode(public_key)?)?;
+        if !public_key.verify(&signed, &decode(signature)?) {
+            return Err(AppError::Unauthorized("Verifikasi passkey gagal.".into()));
+        }
+
+        // Authenticators without a counter always report 0; otherwise it must grow.
+        let sign_count = auth_data.sign_count;
+        if (sign_count != 0 || stored_sign_count != 0) && i64::from(sign_count) <= stored_sign_count
+        {
+            return Err(AppError::Unauthorized(
+                "Penghitung passkey tidak bertambah; authenticator mungkin telah disalin.".into(),
+            ));
+        }
+        Ok(sign_count)
+    }
+
+    fn check_client_data(
+        &self,
+        client_data_json: &str,
+        ceremony: Ceremony,
+        account_id: i32,
+    ) -> Result<(), AppError> {
+        #[derive(Deserialize)]
+        struct ClientData {
+            #[serde(rename = "type")]
+            kind: String,
+            challenge: String,
+            origin: String,
+        }
+
+        let client_data: ClientData = serde_json::from_slice(&decode(client_data_json)?)
+            .map_err(|_| invalid("clientDataJSON tidak valid"))?;
+        if client_data.kind != ceremony.client_data_type() {
+            return Err(invalid("jenis clientDataJSON tidak sesuai"));
+        }
+        if !self.origins.contains(&client_data.origin) {
+            return Err(invalid("origin tidak diizinkan"));
+        }
+
+        let pending = self
+            .challenges
+            .lock()
+            .expect("challenge map poisoned")
+            .remove(&client_data.challenge);
+        let valid = pending.is_some_and(|pending| {
+            pending.ceremony == ceremony
+                && pending.account_id == account_id
+                && pending.expires_at > Instant::now()
+        });
+        if !valid {
+            return Err(AppError::BadRequest(
+                "Challenge passkey tidak dikenal atau kedaluwarsa; mulai ulang prosesnya.".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_authenticator_data<'a>(
+        &self,
+        data: &'a [u8],
+    ) -> Result<AuthenticatorData<'a>, AppError> {
+        if data.len() < 37 {
+            return Err(invalid("authenticatorData terpotong"));
+        }
+        let rp_id_hash = digest::digest(&digest::SHA256, self.rp_id.as_bytes());
+        if &data[..32] != rp_id_hash.as_ref() {
+            return Err(invalid("RP ID tidak sesuai"));
+        }
+        let flags = data[32];
+        if flags & FLAG_USER_PRESENT == 0 {
+            return Err(invalid("pengguna tidak hadir"));
+        }
+
+        Ok(AuthenticatorData {
+            flags,
+            sign_count: u32::from_be_bytes([data[33], data[34], data[35], data[36]]),
+            rest: &data[37..],
+        })
+    }
+}
+
+struct AuthenticatorData<'a> {
+    flags: u8,
+    sign_count: u32,
+    rest: &'a [u8],
+}
+
+enum PublicKey {
+    /// Uncompressed P-256 point.
+    Es256(Vec<u8>),
+    Rs256 {
+        n: Vec<u8>,
+        e: Vec<u8>,
+    },
+}
+
+impl PublicKey {
+    fn from_cose(bytes: &[u8]) -> Result<Self, AppError> {
+        let Ok(Value::Map(key)) = serde_cbor::from_slice::<Value>(bytes) else {
+            return Err(invalid("kunci publik bukan map COSE"));
+        };
+
+        match (cose_int(&key, 1), cose_int(&key, 3)) {
+            // kty EC2, crv P-256.
+            (Some(2), Some(ES256)) if cose_int(&key, -1) == Some(1) => {
+                let (Some(x), Some(y)) = (cose_bytes(&key, -2), cose_bytes(&key, -3)) else {
+                    return Err(invalid("koordinat kunci EC tidak ada"));
+                };
+                if x.len() != 32 || y.len() != 32 {
+                    return Err(invalid("koordinat kunci EC tidak valid"));
+                }
+                let mut point = Vec::with_capacity(65);
+                point.push(0x04);
+                point.extend_from_slice(x);
+                point.extend_from_slice(y);
+                Ok(PublicKey::Es256(point))
+            }
+            // kty RSA.
+            (Some(3), Some(RS256)) => {
+                let (Some(n), Some(e)) = (cose_bytes(&key, -1), cose_bytes(&key, -2)) else {
+                    return Err(invalid("modulus atau eksponen RSA tidak ada"));
+                };
+                Ok(PublicKey::Rs256 {
+                    n: n.to_vec(),
+                    e: e.to_vec(),
+                })
+            }
+            _ => Err(invalid(
+                "algoritma kunci tidak didukung; gunakan ES256 atau RS256",
+            )),
+        }
+    }
+
+    fn verify(&self, message: &[u8], sig: &[u8]) -> bool {
+        match self {
+            PublicKey::Es256(point) => {
+                signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, point)
+                    .verify(message, sig)
+                    .is_ok()
+            }
+            PublicKey::Rs256 { n, e } => signature::RsaPublicKeyComponents { n, e }
+                .verify(&signature::RSA_PKCS1_2048_8192_SHA256, message, sig)
+                .is_ok(),
+        }
+    }
+}
+
+fn cose_int(key: &BTreeMap<Value, Value>, label: i128) -> Option<i64> {
+    match key.get(&Value::Integer(label)) {
+        Some(Value::Integer(value)) => i64::try_from(*value).ok(),
+        _ => None,
+    }
+}
+
+fn cose_bytes(key: &BTreeMap<Value, Value>, label: i128) -> Option<&[u8]> {
+    match key.get(&Value::Integer(label)) {
+        Some(Value::Bytes(value)) => Some(value),
+        _ => None,
+    }
+}
+
+/// Base64url as sent by browsers; padding is tolerated.
+fn decode(value: &str) -> Result<Vec<u8>, AppError> {
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| invalid("data bukan base64url"))
+}
+
+fn invalid(reason: &str) -> AppError {
+    AppError::BadRequest(format!("Respons passkey tidak valid: {reason}."))
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::{
+        rand::SystemRandom,
+        signature::{ECDSA_P256_SHA256_ASN1_SIGNING, EcdsaKeyPair, KeyPair},
+    };
+
+    use super::*;
+
+    const RP_ID: &str = "lab.example";
+    const ORIGIN: &str = "https://lab.example";
+    const ACCOUNT: i32 = 7;
+
+    fn webauthn() -> WebAuthn {
+        WebAuthn::new(
+            RP_ID.into(),
+            "ASM Lab".into(),
+            vec![ORIGIN.into()],
+            vec![AccountRole::Admin],
+        )
+    }
+
+    fn key_pair() -> EcdsaKeyPair {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+        EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng).unwrap()
+    }
+
+    fn client_data(kind: &str, challenge: &str, origin: &str) -> String {
+        let json = serde_json::json!({ "type": kind, "challenge": challenge, "origin": origin });
+        URL_SAFE_NO_PAD.encode(json.to_string())
+    }
+
+    fn authenticator_data(flags: u8, sign_count: u32, attested: &[u8]) -> Vec<u8> {
+        let mut data = digest::digest(&digest::SHA256, RP_ID.as_bytes())
+            .as_ref()
+            .to_vec();
+        data.push(flags);
+        data.extend_from_slice(&sign_count.to_be_bytes());
+        data.extend_from_slice(attested);
+        data
+    }
+
+    /// A `none` attestation of the key pair's public key, as a browser sends it.
+    fn attestation_object(key_pair: &EcdsaKeyPair, credential_id: &[u8]) -> String {
+        let point = key_pair.public_key().as_ref();
+        let cose_key = Value::Map(BTreeMap::from([
+            (Value::Integer(1), Value::Integer(2)),
+            (Value::Integer(3), Value::Integer(ES256.into())),
+            (Value::Integer(-1), Value::Integer(1)),
+            (Value::Integer(-2), Value::Bytes(point[1..33].to_vec())),
+            (Value::Integer(-3), Value::Bytes(point[33..].to_vec())),
+        ]));
+
+        let mut attested = vec![0; 16];
+        attested.extend_from_slice(&(credential_id.len() as u16).to_be_bytes());
+        attested.extend_from_slice(credential_id);
+        attested.extend(serde_cbor::to_vec(&cose_key).unwrap());
+        let auth_data =
+            authenticator_data(FLAG_USER_PRESENT | FLAG_ATTESTED_CREDENTIAL, 0, &attested);
+
+        let object = Value::Map(BTreeMap::from([
+            (Value::Text("fmt".into()), Value::Text("none".into())),
+            (Value::Text("attStmt".into()), Value::Map(BTreeMap::new())),
+            (Value::Text("authData".into()), Value::Bytes(auth_data)),
+        ]));
+        URL_SAFE_NO_PAD.encode(serde_cbor::to_vec(&object).unwrap())
+    }
+
+    fn register(webauthn: &WebAuthn, key_pair: &EcdsaKeyPair) -> NewCredential {
+        let challenge = webauthn.challenge(Ceremony::Register, ACCOUNT);
+        webauthn
+            .verify_registration(
+                ACCOUNT,
+                &client_data("webauthn.create", &challenge, ORIGIN),
+                &attestation_object(key_pair, b"credential-1"),
+            )
+            .unwrap()
+    }
+
+    /// Client data, authenticator data and signature of a login, signed by `key_pair`.
+    fn assertion(key_pair: &EcdsaKeyPair, challenge: &str, sign_count: u32) -> [String; 3] {
+        let client_data = client_data("webauthn.get", challenge, ORIGIN);
+        let auth_data = authenticator_data(FLAG_USER_PRESENT, sign_count, &[]);
+        let mut signed = auth_data.clone();
+        signed.extend_from_slice(
+            digest::digest(&digest::SHA256, &decode(&client_data).unwrap()).as_ref(),
+        );
+        let signature = key_pair.sign(&SystemRandom::new(), &signed).unwrap();
+        [
+            client_data,
+            URL_SAFE_NO_PAD.encode(auth_data),
+            URL_SAFE_NO_PAD.encode(signature.as_ref()),
+        ]
+    }
+
+    fn login(
+        webauthn: &WebAuthn,
+        credential: &NewCredential,
+        stored_sign_count: i64,
+        [client_data, auth_data, signature]: &[String; 3],
+    ) -> Result<u32, AppError> {
+        webauthn.verify_assertion(
+            ACCOUNT,
+            &credential.public_key,
+            stored_sign_count,
+            client_data,
+            auth_data,
+            signature,
+        )
+    }
+
+    #[test]
+    fn registers_and_logs_in_with_an_es256_key() {
+        let webauthn = webauthn();
+        let key_pair = key_pair();
+        let credential = register(&webauthn, &key_pair);
+        assert_eq!(
+            credential.credential_id,
+            URL_SAFE_NO_PAD.encode(b"credential-1")
+        );
+        assert_eq!(credential.sign_count, 0);
+
+        let challenge = webauthn.challenge(Ceremony::Login, ACCOUNT);
+        let sign_count = login(
+            &webauthn,
+            &credential,
+            0,
+            &assertion(&key_pair, &challenge, 1),
+        );
+        assert_eq!(sign_count.unwrap(), 1);
+    }
+
+    #[test]
+    fn rejects_a_signature_by_another_key() {
+        let webauthn = webauthn();
+        let credential = register(&webauthn, &key_pair());
+
+        let challenge = webauthn.challenge(Ceremony::Login, ACCOUNT);
+        let result = login(
+            &webauthn,
+            &credential,
+            0,
+            &assertion(&key_pair(), &challenge, 1),
+        );
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn rejects_a_counter_that_did_not_grow() {
+        let webauthn = webauthn();
+        let key_pair = key_pair();
+        let credential = register(&webauthn, &key_pair);
+
+        let challenge = webauthn.challenge(Ceremony::Login, ACCOUNT);
+        let result = login(
+            &webauthn,
+            &credential,
+            4,
+            &assertion(&key_pair, &challenge, 4),
+        );
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+    }
+
+    #[test]
+    fn challenges_are_single_use_and_bound_to_ceremony_and_account() {
+        let webauthn = webauthn();
+        let key_pair = key_pair();
+        let credential = register(&webauthn, &key_pair);
+
+        let challenge = webauthn.challenge(Ceremony::Login, ACCOUNT);
+        let signed = assertion(&key_pair, &challenge, 1);
+        assert!(login(&webauthn, &credential, 0, &signed).is_ok());
+        assert!(matches!(
+            login(&webauthn, &credential, 0, &signed),
+            Err(AppError::BadRequest(_))
+        ));
+
+        let other_account = webauthn.challenge(Ceremony::Login, ACCOUNT + 1);
+        let result = login(
+            &webauthn,
+            &credential,
+            1,
+            &assertion(&key_pair, &other_account, 2),
+        );
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        let registration = webauthn.challenge(Ceremony::Register, ACCOUNT);
+        let result = login(
+            &webauthn,
+            &credential,
+            1,
+            &assertion(&key_pair, &registration, 2),
+        );
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn rejects_an_unknown_origin() {
+        let webauthn = webauthn();
+        let challenge = webauthn.challenge(Ceremony::Register, ACCOUNT);
+        let result = webauthn.verify_registration(
+            ACCOUNT,
+            &client_data("webauthn.create", &challenge, "https://evil.example"),
+            &attestation_object(&key_pair(), b"credential-1"),
+        );
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn keeps_only_the_newest_login_challenges_of_an_account() {
+        let webauthn = webauthn();
+        let key_pair = key_pair();
+        let credential = register(&webauthn, &key_pair);
+
+        let challenges: Vec<String> = (0..MAX_PENDING_PER_ACCOUNT + 3)
+            .map(|_| webauthn.challenge(Ceremony::Login, ACCOUNT))
+            .collect();
+        assert_eq!(
+            webauthn.challenges.lock().unwrap().len(),
+            MAX_PENDING_PER_ACCOUNT
+        );
+
+        let result = login(
+            &webauthn,
+            &credential,
+            0,
+            &assertion(&key_pair, &challenges[0], 1),
+        );
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        let newest = challenges.last().unwrap();
+        assert!(login(&webauthn, &credential, 0, &assertion(&key_pair, newest, 1)).is_ok());
+    }
+}
@@ -0,0 +1,362 @@
+//! Offline administration for ASM Lab Server. Works directly on the configured
+//! database, so it can be used before the server is first started or while it is down.
+
+use anyhow::{Context, Result, bail};
+use asm_lab_server::{
+    config, db,
+    dto::{AccountRole, AccountStatus, GradeFormat, TimeDisplay},
+    entities::account,
+    services::{
+        accounts::{self, AdminGuard},
+        archive::{self, ArchiveTarget},
+        code_encryption::{self, CodeKeyRing},
+        display_time::TimeFormatter,
+        grades, replication, roster,
+    },
+};
+use chrono::Utc;
+use migration::{MigrationStatus, Migrator, MigratorTrait};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter,
+};
+
+const USAGE: &str = "\
+Penggunaan: asmctl <perintah> [argumen]
+
+Perintah:
+  migrate                                   Menjalankan migrasi database
+  migrate status                            Menampilkan migrasi skema yang sudah dan belum dijalankan
+  migrate down [jumlah]                     Membatalkan migrasi skema terakhir (default 1); tabel
+                                            yang dibuat migrasi tersebut ikut dihapus beserta isinya
+  create-admin <npm>                        Membuat akun admin (atau menjadikan akun yang ada admin)
+  set-role <npm> <role> [--force]           Mengganti peran akun (user, admin, assistant, observer);
+                                            --force mengizinkan menurunkan admin aktif terakhir
+  delete-account <npm> [--force]            Menghapus akun; --force mengizinkan menghapus admin
+                                            aktif terakhir
+  import-roster <id-kelas> <file.csv>       Menambahkan mahasiswa dari CSV npm,name,code
+  export-grades <id-kelas> [file.csv] [--format=csv|moodle|canvas] [--time=utc|local]
+                                            Mengekspor nilai akhir (default ke stdout); --time=local
+                                            menambah kolom waktu di DISPLAY_TIMEZONE
+  backup <file.db>                          Menyalin database SQLite ke file baru
+  promote [--force]                         Menjadikan snapshot standby database utama; ditolak
+                                            selama primary masih sehat kecuali --force
+  reencrypt-code                            Mengenkripsi ulang kode mahasiswa dengan CODE_ENCRYPTION_KEY
+                                            (baris plaintext ikut dienkripsi; tanpa kunci, kode
+                                            didekripsi kembali ke plaintext)
+  verify-archive                            Membandingkan jumlah submission final yang tercatat
+                                            terarsip dengan isi ARCHIVE_SINK; gagal jika ada
+                                            yang hilang dari arsip
+
+Konfigurasi dibaca dari environment, `.env`, dan file konfigurasi yang sama dengan server.";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(command) = args.first() else {
+        println!("{USAGE}");
+        return Ok(());
+    };
+    let rest = &args[1..];
+
+    if matches!(command.as_str(), "-h" | "--help" | "help") {
+        println!("{USAGE}");
+        return Ok(());
+    }
+
+    config::load(false)?;
+    let settings = config::Settings::from_env();
+    code_encryption::install(CodeKeyRing::new(
+        settings.code_encryption_key.as_deref(),
+        &settings.code_encryption_previous_keys,
+    ));
+    // Promotion replaces the database file, so it must not be opened first.
+    if command == "promote" {
+        return promote(&settings, rest).await;
+    }
+    let db = db::connect(&settings.database_url)
+        .await
+        .with_context(|| format!("gagal membuka database {}", settings.database_url))?;
+
+    match (command.as_str(), rest) {
+        ("migrate", []) => {
+            db::init(&db).await?;
+            println!("Migrasi selesai.");
+        }
+        ("migrate", [status]) if status == "status" => {
+            for migration in Migrator::get_migration_with_status(&db).await? {
+                let applied = migration.status() == MigrationStatus::Applied;
+                println!(
+                    "{} {}",
+                    if applied { "[x]" } else { "[ ]" },
+                    migration.name()
+                );
+            }
+        }
+        ("migrate", [down, steps @ ..]) if down == "down" && steps.len() <= 1 => {
+            let steps = match steps.first() {
+                Some(raw) => raw
+                    .parse::<u32>()
+                    .with_context(|| format!("jumlah migrasi tidak valid: {raw}"))?,
+                None => 1,
+            };
+            Migrator::down(&db, Some(steps)).await?;
+            println!("{steps} migrasi skema dibatalkan.");
+        }
+        ("create-admin", [npm]) => {
+            db::init(&db).await?;
+            create_admin(&db, npm.trim()).await?;
+        }
+        ("set-role", [npm, role, flags @ ..]) if flags.len() <= 1 => {
+            let role = AccountRole::from_str(role.trim())
+                .with_context(|| format!("peran tidak dikenal: {role}"))?;
+            let guard = parse_guard(flags)?;
+            db::init(&db).await?;
+            let id = find_account_id(&db, npm.trim()).await?;
+            let updated = accounts::set_role(&db, id, role, None, guard).await?;
+            println!("Peran akun {} menjadi {}.", updated.npm, updated.role);
+        }
+        ("delete-account", [npm, flags @ ..]) if flags.len() <= 1 => {
+            let guard = parse_guard(flags)?;
+            db::init(&db).await?;
+            let id = find_account_id(&db, npm.trim()).await?;
+            accounts::delete(&db, id, None, guard).await?;
+            println!("Akun {} dihapus.", npm.trim());
+        }
+        ("import-roster", [classroom_id, path]) => {
+            db::init(&db).await?;
+            let contents =
+                std::fs::read_to_string(path).with_context(|| format!("gagal membaca {path}"))?;
+            let entries = roster::parse(&contents)?;
+            let result = roster::import(&db, parse_id(classroom_id)?, entries).await?;
+            println!("{} mahasiswa ditambahkan.", result.added);
+            if !result.skipped.is_empty() {
+                println!(
+                    "Dilewati karena sudah terdaftar: {}",
+                    result.skipped.join(", ")
+                );
+            }
+        }
+        ("export-grades", [classroom_id, options @ ..]) if options.len() <= 3 => {
+            let mut format = GradeFormat::Csv;
+            let mut output = Vec::new();
+            let mut time = TimeDisplay::Utc;
+            for option in options {
+                if let Some(raw) = option.strip_prefix("--format=") {
+                    format = GradeFormat::from_str(raw)
+                        .with_context(|| format!("format tidak dikenal: {raw}"))?;
+                } else if let Some(raw) = option.strip_prefix("--time=") {
+                    time = TimeDisplay::from_str(raw)
+                        .with_context(|| format!("mode waktu tidak dikenal: {raw}"))?;
+                } else {
+                    output.push(option);
+                }
+            }
+            if output.len() > 1 {
+                eprintln!("{USAGE}");
+                std::process::exit(2);
+            }
+            db::init(&db).await?;
+            let csv = grades::export(
+                &db,
+                parse_id(classroom_id)?,
+                format,
+                TimeFormatter::new(time, settings.display_timezone),
+            )
+            .await?;
+            match output.first() {
+                Some(path) => {
+                    std::fs::write(path, csv).with_context(|| format!("gagal menulis {path}"))?;
+                    println!("Nilai diekspor ke {path}.");
+                }
+                None => print!("{csv}"),
+            }
+        }
+        ("reencrypt-code", []) => {
+            db::init(&db).await?;
+            let report = code_encryption::reencrypt_all(&db).await?;
+            match code_encryption::key_ring().current_key_id() {
+                Some(key_id) => println!(
+                    "{} kode roster dan {} submission dienkripsi dengan kunci {key_id}.",
+                    report.users, report.submissions
+                ),
+                None => println!(
+                    "CODE_ENCRYPTION_KEY tidak diatur; {} kode roster dan {} submission didekripsi ke plaintext.",
+                    report.users, report.submissions
+                ),
+            }
+        }
+        ("verify-archive", []) => {
+            let Some(sink) = settings.archive_sink.as_deref() else {
+                bail!("ARCHIVE_SINK tidak diatur");
+            };
+            let target = ArchiveTarget::parse(sink).map_err(anyhow::Error::msg)?;
+            db::init(&db).await?;
+            let report = archive::verify(&db, &target).await?;
+            println!("Submission final          : {}", report.final_submissions);
+            println!("  sebelum arsip aktif     : {}", report.untracked);
+            println!(
+                "  menunggu diarsipkan     : {} ({} gagal terakhir kali)",
+                report.pending, report.failing
+            );
+            println!("  tercatat terarsip       : {}", report.archived);
+            println!(
+                "Submission di arsip      : {} ({} catatan ganda)",
+                report.in_sink, report.duplicates
+            );
+            if !report.consistent() {
+                bail!(
+                    "{} submission tercatat terarsip tetapi tidak ada di arsip, mis. id {:?}",
+                    report.missing_count,
+                    report.missing
+                );
+            }
+            println!("Arsip cocok.");
+        }
+        ("backup", [path]) => {
+            if std::path::Path::new(path).exists() {
+                bail!("{path} sudah ada; pilih file tujuan baru");
+            }
+            db::backup(&db, path).await?;
+            println!("Backup ditulis ke {path}.");
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    }
+
+    Ok(())
+}
+
+async fn create_admin(db: &DatabaseConnection, npm: &str) -> Result<()> {
+    if npm.is_empty() {
+        bail!("NPM wajib diisi");
+    }
+
+    let existing = account::Entity::find()
+        .filter(account::Column::Npm.eq(npm))
+        .one(db)
+        .await?;
+
+    match existing {
+        Some(model) if AccountRole::from_str(&model.role) == Some(AccountRole::Admin) => {
+            println!("Akun {npm} sudah admin.");
+        }
+        Some(model) => {
+            let now = Utc::now();
+            let pending = AccountStatus::from_str(&model.status) == Some(AccountStatus::Pending);
+            let mut active = model.into_active_model();
+            active.role = Set(AccountRole::Admin.as_str().to_owned());
+            if pending {
+                active.status = Set(AccountStatus::Active.as_str().to_owned());
+                active.approved_at = Set(Some(now));
+            }
+            active.updated_at = Set(now);
+            active.update(db).await?;
+            println!("Akun {npm} dijadikan admin.");
+        }
+        None => {
+            let now = Utc::now();
+            account::ActiveModel {
+                npm: Set(npm.to_owned()),
+                role: Set(AccountRole::Admin.as_str().to_owned()),
+                status: Set(AccountStatus::Active.as_str().to_owned()),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+            println!("Akun admin {npm} dibuat.");
+        }
+    }
+
+    Ok(())
+}
+
+async fn find_account_id(db: &DatabaseConnection, npm: &str) -> Result<i32> {
+    account::Entity::find()
+        .filter(account::Column::Npm.eq(npm))
+        .one(db)
+        .await?
+        .map(|model| model.id)
+        .with_context(|| format!("akun {npm} tidak ditemukan"))
+}
+
+fn parse_guard(flags: &[String]) -> Result<AdminGuard> {
+    match flags {
+        [] => Ok(AdminGuard::Enforce),
+        [flag] if flag == "--force" => Ok(AdminGuard::Override),
+        [flag] => bail!("opsi tidak dikenal: {flag}"),
+        _ => bail!("terlalu banyak opsi"),
+    }
+}
+
+async fn promote(settings: &config::Settings, flags: &[String]) -> Result<()> {
+    let force = match flags {
+        [] => false,
+        [flag] if flag == "--force" => true,
+        [flag] => bail!("opsi tidak dikenal: {flag}"),
+        _ => bail!("terlalu banyak opsi"),
+    };
+    let snapshot = &settings.replication_snapshot_path;
+    if !snapshot.exists() {
+        bail!("snapshot {} tidak ditemukan", snapshot.display());
+    }
+    let target = db::sqlite_path(&settings.database_url)
+        .with_context(|| format!("DATABASE_URL bukan file SQLite: {}", settings.database_url))?;
+
+    match settings.replication_primary_url.as_deref() {
+        Some(primary_url) => {
+            println!("Memeriksa primary {primary_url}...");
+            if replication::primary_reachable(&reqwest::Client::new(), primary_url).await {
+                if !force {
+                    bail!(
+                        "primary {primary_url} masih sehat; hentikan primary dulu atau pakai --force"
+                    );
+                }
+                println!("Primary masih sehat, tetap dipromosikan karena --force.");
+            }
+        }
+        None if !force => {
+            bail!(
+                "REPLICATION_PRIMARY_URL tidak diatur; pakai --force untuk promosi tanpa pemeriksaan"
+            )
+        }
+        None => {}
+    }
+
+    replication::verify_snapshot(snapshot).await?;
+
+    if target.exists() {
+        let aside = format!(
+            "{}.before-promote-{}",
+            target.display(),
+            Utc::now().format("%Y%m%d%H%M%S")
+        );
+        std::fs::rename(&target, &aside)
+            .with_context(|| format!("gagal memindahkan {}", target.display()))?;
+        println!("Database lama dipindahkan ke {aside}.");
+    }
+    std::fs::copy(snapshot, &target)
+        .with_context(|| format!("gagal menyalin snapshot ke {}", target.display()))?;
+
+    match replication::read_manifest(snapshot) {
+        Some(manifest) => println!(
+            "Snapshot {} (diambil {}) menjadi database utama.",
+            snapshot.display(),
+            manifest.taken_at
+        ),
+        None => println!("Snapshot {} menjadi database utama.", snapshot.display()),
+    }
+    println!(
+        "Jalankan ulang server dengan REPLICATION_ROLE=off, atau primary dengan standby baru."
+    );
+    Ok(())
+}
+
+fn parse_id(raw: &str) -> Result<i32> {
+    raw.parse()
+        .with_context(|| format!("id kelas tidak valid: {raw}"))
+}
@@ -0,0 +1,534 @@
+//! Load generator for ASM Lab Server. Simulates a class of students against a running
+//! server (login, event stream, periodic autosave, final submission) and reports
+//! latency percentiles per operation, so a lab machine can be sized before exam day.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, bail};
+use asm_lab_server::{
+    dto::{
+        CreateUserRequest, FinishExamRequest, LoginRequest, LoginResponse, UpdateUserRequest,
+        UserResponse,
+    },
+    services::replay_guard,
+};
+use rand::Rng;
+use reqwest::{Client, RequestBuilder};
+use tokio::sync::Mutex;
+
+const USAGE: &str = "\
+Penggunaan: loadgen <url-server> <id-kelas> --admin-npm=NPM [opsi]
+
+Mensimulasikan mahasiswa yang login, membuka stream event kelas, menyimpan kode
+berkala, lalu mengumpulkan jawaban. Mahasiswa <prefix>001..<prefix>NNN ditambahkan ke
+roster kelas bila belum ada. Kelas harus sudah dipublikasikan.
+
+Opsi:
+  --admin-npm=NPM       NPM akun admin untuk membaca dan mengisi roster (wajib)
+  --students=N          Jumlah mahasiswa simulasi (default 150)
+  --duration=DETIK      Lama sesi tiap mahasiswa (default 60)
+  --autosave=DETIK      Jeda rata-rata antar-autosave (default 10)
+  --ramp-up=DETIK       Rentang waktu mulainya mahasiswa (default 10)
+  --prefix=TEKS         Awalan NPM mahasiswa simulasi (default LOAD)
+  --language-id=ID      Bahasa Judge0 saat mengumpulkan (default bahasa kelas)
+  --no-submit           Tidak mengumpulkan jawaban di akhir sesi
+  --p95-budget-ms=MS    Batas p95 tiap operasi agar dinyatakan lulus (default 1000)
+
+Keluar dengan kode 1 bila ada permintaan gagal atau p95 melewati batas.";
+
+/// Per-request timeout for everything except the long-lived event stream.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+const SAMPLE_CODE: &str = "section .text\nglobal _start\n_start:\n    mov eax, 1\n";
+
+struct Options {
+    base_url: String,
+    classroom_id: i32,
+    admin_npm: String,
+    students: usize,
+    duration: Duration,
+    autosave_every: Duration,
+    ramp_up: Duration,
+    prefix: String,
+    language_id: Option<i32>,
+    submit: bool,
+    p95_budget: Duration,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Option<Self>> {
+        let [base_url, classroom_id, flags @ ..] = args else {
+            return Ok(None);
+        };
+        let mut options = Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            classroom_id: classroom_id
+                .parse()
+                .with_context(|| format!("id kelas tidak valid: {classroom_id}"))?,
+            admin_npm: String::new(),
+            students: 150,
+            duration: Duration::from_secs(60),
+            autosave_every: Duration::from_secs(10),
+            ramp_up: Duration::from_secs(10),
+            prefix: "LOAD".to_owned(),
+            language_id: None,
+            submit: true,
+            p95_budget: Duration::from_millis(1000),
+        };
+
+        for flag in flags {
+            let (key, value) = flag.split_once('=').unwrap_or((flag, ""));
+            match key {
+                "--admin-npm" if !value.is_empty() => options.admin_npm = value.to_owned(),
+                "--students" => options.students = parse_number(key, value)?,
+                "--duration" => options.duration = Duration::from_secs(parse_number(key, value)?),
+                "--autosave" => {
+                    options.autosave_every = Duration::from_secs(parse_number(key, value)?)
+                }
+                "--ramp-up" => options.ramp_up = Duration::from_secs(parse_number(key, value)?),
+                "--prefix" if !value.is_empty() => options.prefix = value.to_owned(),
+                "--language-id" => options.language_id = Some(parse_number(key, value)?),
+                "--no-submit" => options.submit = false,
+                "--p95-budget-ms" => {
+                    options.p95_budget = Duration::from_millis(parse_number(key, value)?)
+                }
+                _ => bail!("opsi tidak dikenal: {flag}"),
+            }
+        }
+        if options.admin_npm.is_empty() {
+            bail!("--admin-npm wajib diisi");
+        }
+        if options.students == 0 {
+            bail!("--students minimal 1");
+        }
+        if options.autosave_every.is_zero() {
+            bail!("--autosave minimal 1 detik");
+        }
+        Ok(Some(options))
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/api{path}", self.base_url)
+    }
+
+    fn npm(&self, index: usize) -> String {
+        format!("{}{:03}", self.prefix, index + 1)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T> {
+    value
+        .parse()
+        .ok()
+        .with_context(|| format!("nilai {key} tidak valid: {value:?}"))
+}
+
+/// Latencies and failures per operation.
+#[derive(Default)]
+struct Recorder {
+    samples: Mutex<BTreeMap<&'static str, Vec<Duration>>>,
+    failures: Mutex<BTreeMap<&'static str, (u64, String)>>,
+    events: AtomicU64,
+}
+
+impl Recorder {
+    async fn success(&self, operation: &'static str, elapsed: Duration) {
+        self.samples
+            .lock()
+            .await
+            .entry(operation)
+            .or_default()
+            .push(elapsed);
+    }
+
+    async fn failure(&self, operation: &'static str, err: &anyhow::Error) {
+        let mut failures = self.failures.lock().await;
+        let entry = failures
+            .entry(operation)
+            .or_insert_with(|| (0, format!("{err:#}")));
+        entry.0 += 1;
+    }
+
+    /// Sends the request and records how long the server took to answer with a
+    /// success status.
+    async fn timed(
+        &self,
+        operation: &'static str,
+        request: RequestBuilder,
+    ) -> Option<reqwest::Response> {
+        let started = Instant::now();
+        let result = async {
+            let response = request.send().await?;
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                bail!("HTTP {status}: {}", body.trim());
+            }
+            Ok(response)
+        }
+        .await;
+        match result {
+            Ok(response) => {
+                self.success(operation, started.elapsed()).await;
+                Some(response)
+            }
+            Err(err) => {
+                self.failure(operation, &err).await;
+                None
+            }
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args
+        .first()
+        .is_some_and(|arg| matches!(arg.as_str(), "-h" | "--help" | "help"))
+    {
+        println!("{USAGE}");
+        return Ok(());
+    }
+    let Some(options) = Options::parse(&args)? else {
+        eprintln!("{USAGE}");
+        std::process::exit(2);
+    };
+    let options = Arc::new(options);
+    let client = Client::builder()
+        .pool_max_idle_per_host(options.students)
+        .build()?;
+
+    let roster = seed_roster(&client, &options).await?;
+    println!(
+        "Menjalankan {} mahasiswa selama {} detik terhadap {} (kelas {}).",
+        options.students,
+        options.duration.as_secs(),
+        options.base_url,
+        options.classroom_id
+    );
+
+    let recorder = Arc::new(Recorder::default());
+    let started = Instant::now();
+    let mut handles = Vec::with_capacity(options.students);
+    for (index, (npm, user_id)) in roster.into_iter().enumerate() {
+        let delay = options
+            .ramp_up
+            .mul_f64(index as f64 / options.students as f64);
+        let client = client.clone();
+        let options = options.clone();
+        let recorder = recorder.clone();
+        handles.push(tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            simulate_student(&client, &options, &recorder, npm, user_id).await;
+        }));
+    }
+    for handle in handles {
+        handle.await?;
+    }
+
+    let passed = report(&options, &recorder, started.elapsed()).await;
+    if !passed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Makes sure every simulated student is on the classroom roster and returns their
+/// NPMs with roster ids.
+async fn seed_roster(client: &Client, options: &Options) -> Result<Vec<(String, i32)>> {
+    let admin: LoginResponse = client
+        .post(options.url("/auth/login"))
+        .json(&LoginRequest {
+            npm: options.admin_npm.clone(),
+            as_admin: true,
+            join_code: None,
+        })
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .with_context(|| format!("server {} tidak dapat dihubungi", options.base_url))?
+        .error_for_status()
+        .context("gagal login sebagai admin")?
+        .json()
+        .await?;
+
+    let users_url = options.url(&format!("/classrooms/{}/users", options.classroom_id));
+    let existing: Vec<UserResponse> = client
+        .get(&users_url)
+        .bearer_auth(&admin.token)
+        .query(&[("search", options.prefix.as_str())])
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()
+        .context("gagal membaca roster kelas")?
+        .json()
+        .await?;
+    let existing: HashMap<String, i32> = existing
+        .into_iter()
+        .map(|user| (user.npm, user.id))
+        .collect();
+
+    let mut roster = Vec::with_capacity(options.students);
+    let mut added = 0;
+    for index in 0..options.students {
+        let npm = options.npm(index);
+        let user_id = match existing.get(&npm) {
+            Some(&user_id) => user_id,
+            None => {
+                let created: UserResponse = client
+                    .post(&users_url)
+                    .bearer_auth(&admin.token)
+                    .json(&CreateUserRequest {
+                        name: format!("Load Test {}", index + 1),
+                        npm: npm.clone(),
+                        code: String::new(),
+                    })
+                    .timeout(REQUEST_TIMEOUT)
+                    .send()
+                    .await?
+                    .error_for_status()
+                    .with_context(|| format!("gagal menambahkan {npm} ke roster"))?
+                    .json()
+                    .await?;
+                added += 1;
+                created.id
+            }
+        };
+        roster.push((npm, user_id));
+    }
+    if added > 0 {
+        println!("{added} mahasiswa simulasi ditambahkan ke roster.");
+    }
+    Ok(roster)
+}
+
+async fn simulate_student(
+    client: &Client,
+    options: &Options,
+    recorder: &Arc<Recorder>,
+    npm: String,
+    user_id: i32,
+) {
+    let deadline = Instant::now() + options.duration;
+
+    let Some(response) = recorder
+        .timed(
+            "login",
+            client
+                .post(options.url("/auth/login"))
+                .json(&LoginRequest {
+                    npm: npm.clone(),
+                    as_admin: false,
+                    join_code: None,
+                })
+                .timeout(REQUEST_TIMEOUT),
+        )
+        .await
+    else {
+        return;
+    };
+    let token = match response.json::<LoginResponse>().await {
+        Ok(login) => login.token,
+        Err(err) => {
+            recorder.failure("login", &err.into()).await;
+            return;
+        }
+    };
+
+    let stream = tokio::spawn(follow_events(
+        client.clone(),
+        options.url(&format!("/classrooms/{}/events", options.classroom_id)),
+        npm.clone(),
+        token.clone(),
+        recorder.clone(),
+        deadline,
+    ));
+
+    let mut code = SAMPLE_CODE.to_owned();
+    let mut keystrokes = 0;
+    loop {
+        let jitter = rand::thread_rng().gen_range(0.5..1.5);
+        let wait = options.autosave_every.mul_f64(jitter);
+        if Instant::now() + wait >= deadline {
+            break;
+        }
+        tokio::time::sleep(wait).await;
+        keystrokes += 1;
+        code.push_str(&format!("    add eax, {keystrokes}\n"));
+        recorder
+            .timed(
+                "autosave",
+                client
+                    .put(options.url(&format!(
+                        "/classrooms/{}/users/{user_id}",
+                        options.classroom_id
+                    )))
+                    .bearer_auth(&token)
+                    .json(&UpdateUserRequest {
+                        name: None,
+                        npm: None,
+                        code: Some(code.clone()),
+                        active: None,
+                    })
+                    .timeout(REQUEST_TIMEOUT),
+            )
+            .await;
+    }
+    tokio::time::sleep(deadline.saturating_duration_since(Instant::now())).await;
+
+    if options.submit {
+        recorder
+            .timed(
+                "submit",
+                client
+                    .post(options.url(&format!("/classrooms/{}/finish", options.classroom_id)))
+                    .bearer_auth(&token)
+                    .header(
+                        replay_guard::REQUEST_NONCE.as_str(),
+                        format!("{:016x}", rand::random::<u64>()),
+                    )
+                    .header(
+                        replay_guard::REQUEST_TIMESTAMP.as_str(),
+                        chrono::Utc::now().timestamp().to_string(),
+                    )
+                    .json(&FinishExamRequest {
+                        npm,
+                        code,
+                        language_id: options.language_id,
+                        task_id: None,
+                    })
+                    .timeout(REQUEST_TIMEOUT),
+            )
+            .await;
+    }
+    stream.abort();
+}
+
+/// Holds the classroom event stream open until `deadline`, counting received events.
+/// The recorded latency is the time until the stream was accepted.
+async fn follow_events(
+    client: Client,
+    url: String,
+    npm: String,
+    token: String,
+    recorder: Arc<Recorder>,
+    deadline: Instant,
+) {
+    let Some(mut response) = recorder
+        .timed(
+            "events-connect",
+            client
+                .get(url)
+                .query(&[("npm", npm.as_str())])
+                .bearer_auth(&token)
+                .header(reqwest::header::ACCEPT, "text/event-stream"),
+        )
+        .await
+    else {
+        return;
+    };
+
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    let _ = tokio::time::timeout(remaining, async {
+        loop {
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    let events = chunk
+                        .split(|&byte| byte == b'\n')
+                        .filter(|line| line.starts_with(b"data:"))
+                        .count();
+                    recorder.events.fetch_add(events as u64, Ordering::Relaxed);
+                }
+                Ok(None) => {
+                    let closed = anyhow::anyhow!("stream closed by the server");
+                    recorder.failure("events-stream", &closed).await;
+                    break;
+                }
+                Err(err) => {
+                    recorder.failure("events-stream", &err.into()).await;
+                    break;
+                }
+            }
+        }
+    })
+    .await;
+}
+
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.0}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Prints the latency table and verdict; true when the run passed.
+async fn report(options: &Options, recorder: &Recorder, elapsed: Duration) -> bool {
+    let mut samples = recorder.samples.lock().await;
+    let failures = recorder.failures.lock().await;
+    let mut operations: Vec<&'static str> =
+        samples.keys().chain(failures.keys()).copied().collect();
+    operations.sort_unstable();
+    operations.dedup();
+
+    println!();
+    println!(
+        "{:<16}{:>8}{:>8}{:>9}{:>9}{:>9}{:>9}{:>9}",
+        "Operasi", "n", "gagal", "p50", "p90", "p95", "p99", "maks"
+    );
+    let mut passed = true;
+    for operation in &operations {
+        let sorted = samples.entry(operation).or_default();
+        sorted.sort_unstable();
+        let failed = failures.get(operation).map_or(0, |(count, _)| *count);
+        let p95 = percentile(sorted, 95);
+        passed &= failed == 0 && p95 <= options.p95_budget;
+        println!(
+            "{:<16}{:>8}{:>8}{:>9}{:>9}{:>9}{:>9}{:>9}",
+            operation,
+            sorted.len(),
+            failed,
+            millis(percentile(sorted, 50)),
+            millis(percentile(sorted, 90)),
+            millis(p95),
+            millis(percentile(sorted, 99)),
+            millis(sorted.last().copied().unwrap_or_default()),
+        );
+    }
+
+    println!();
+    println!(
+        "Durasi {:.1} detik, {} event diterima.",
+        elapsed.as_secs_f64(),
+        recorder.events.load(Ordering::Relaxed)
+    );
+    for (operation, (count, first)) in failures.iter() {
+        println!("Kegagalan {operation} ({count}x), contoh: {first}");
+    }
+    if passed {
+        println!(
+            "LULUS: {} mahasiswa tanpa kegagalan, p95 setiap operasi <= {}.",
+            options.students,
+            millis(options.p95_budget)
+        );
+    } else {
+        println!(
+            "GAGAL: ada permintaan gagal atau p95 melewati {}.",
+            millis(options.p95_budget)
+        );
+    }
+    passed
+}
@@ -0,0 +1,704 @@
+use std::{
+    fmt::Write as _,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+
+use crate::{
+    dto::{AccountRole, BalanceStrategy, ReplicationRole},
+    services::executor_pool,
+};
+
+/// Overrides the location of the generated config file.
+pub const CONFIG_PATH_ENV: &str = "ASM_LAB_CONFIG";
+/// Set to any value to keep the first run from opening the setup page in a browser.
+pub const NO_BROWSER_ENV: &str = "ASM_LAB_NO_BROWSER";
+
+/// Browser origins allowed by CORS: the frontend students load during an exam.
+pub const ALLOWED_ORIGINS: &[&str] = &["http://localhost:5173", "https://tsfarizi.github.io"];
+
+const CONFIG_FILE_NAME: &str = "config.env";
+const DATABASE_FILE_NAME: &str = "asm_lab.db";
+/// Database file used by earlier releases, relative to the working directory.
+const LEGACY_DATABASE_FILE: &str = "asm_lab.db";
+
+/// Where the configuration came from, reported at startup and by `--print-config`.
+pub struct ConfigSources {
+    pub dotenv_file: Option<PathBuf>,
+    pub config_file: PathBuf,
+    pub config_file_loaded: bool,
+    /// True when the config file was generated on this run.
+    pub first_run: bool,
+}
+
+/// Loads `.env` and the per-user config file into the environment. Values already
+/// in the environment win over `.env`, which wins over the config file.
+///
+/// When nothing is configured at all and `generate` is set, a config file with a
+/// database in the platform data directory and random secrets is written first.
+pub fn load(generate: bool) -> Result<ConfigSources> {
+    let dotenv_file = dotenvy::dotenv().ok();
+    let dirs = ProjectDirs::from("id", "tsfarizi", "ASM Lab Server");
+    let config_file = std::env::var_os(CONFIG_PATH_ENV)
+        .map(PathBuf::from)
+        .or_else(|| {
+            dirs.as_ref()
+                .map(|dirs| dirs.config_dir().join(CONFIG_FILE_NAME))
+        })
+        .unwrap_or_else(|| PathBuf::from(CONFIG_FILE_NAME));
+
+    let unconfigured = dotenv_file.is_none() && std::env::var_os("DATABASE_URL").is_none();
+    let first_run = generate && unconfigured && !config_file.exists();
+    if first_run {
+        let data_dir = dirs
+            .as_ref()
+            .map(|dirs| dirs.data_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+        write_default_config(&config_file, &data_dir)?;
+    }
+
+    let config_file_loaded = config_file.exists();
+    if config_file_loaded {
+        dotenvy::from_path(&config_file)
+            .with_context(|| format!("gagal membaca {}", config_file.display()))?;
+    }
+
+    Ok(ConfigSources {
+        dotenv_file,
+        config_file,
+        config_file_loaded,
+        first_run,
+    })
+}
+
+fn write_default_config(config_file: &Path, data_dir: &Path) -> Result<()> {
+    // Keep using a database left in the working directory by an earlier release.
+    let database_path = match std::fs::canonicalize(LEGACY_DATABASE_FILE) {
+        Ok(legacy) => legacy,
+        Err(_) => {
+            std::fs::create_dir_all(data_dir)
+                .with_context(|| format!("gagal membuat {}", data_dir.display()))?;
+            data_dir.join(DATABASE_FILE_NAME)
+        }
+    };
+    if let Some(parent) = config_file
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("gagal membuat {}", parent.display()))?;
+    }
+
+    let jwt_secret: [u8; 32] = rand::random();
+    let encryption_key: [u8; 32] = rand::random();
+    let contents = format!(
+        "# Konfigurasi ASM Lab Server, dibuat otomatis saat pertama kali dijalankan.\n\
+         # Variabel environment dan file `.env` tetap didahulukan dari file ini.\n\
+         \n\
+         DATABASE_URL=sqlite://{}?mode=rwc\n\
+         JUDGE0_BASE_URL=http://127.0.0.1:2358\n\
+         JWT_SECRET={}\n\
+         SECRET_ENCRYPTION_KEY={}\n\
+         SERVER_ADDR=0.0.0.0:3000\n",
+        database_path.display(),
+        hex::encode(jwt_secret),
+        hex::encode(encryption_key),
+    );
+    std::fs::write(config_file, contents)
+        .with_context(|| format!("gagal menulis {}", config_file.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(config_file, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    Ok(())
+}
+
+/// Effective settings read from the environment after [`load`].
+pub struct Settings {
+    pub database_url: String,
+    pub judge0_base_urls: Vec<String>,
+    pub judge0_balance: BalanceStrategy,
+    pub judge0_eject_after_failures: u32,
+    pub judge0_health_interval_secs: u64,
+    pub judge0_timeout_secs: u64,
+    pub language_refresh_secs: u64,
+    pub judge0_queue_size: usize,
+    pub exam_student_capacity: usize,
+    pub sql_log: bool,
+    pub sql_slow_query_ms: u64,
+    pub grading_max_attempts: u32,
+    pub jwt_secret: Option<String>,
+    pub jwt_ttl_hours: i64,
+    pub secret_encryption_key: Option<String>,
+    pub code_encryption_key: Option<String>,
+    pub code_encryption_previous_keys: Vec<String>,
+    pub sse_max_connections: usize,
+    pub submission_rate_limit_per_minute: u32,
+    pub submission_rate_limit_min_per_minute: u32,
+    pub judge0_latency_target_ms: u64,
+    pub exam_warning_thresholds: String,
+    pub task_due_reminders: String,
+    pub exam_clock_tolerance_secs: u64,
+    pub exam_submit_grace_secs: u64,
+    pub clock_reference_url: Option<String>,
+    pub event_retention_days: u32,
+    pub fingerprint_retention_days: u32,
+    pub export_storage_dir: PathBuf,
+    pub export_url_ttl_secs: u64,
+    pub output_limit_kb: u32,
+    pub max_code_chars: usize,
+    pub max_description_chars: usize,
+    pub account_email_enabled: bool,
+    pub account_approval_required: bool,
+    pub mail_relay_url: Option<String>,
+    pub webauthn_rp_id: Option<String>,
+    pub webauthn_rp_name: String,
+    pub webauthn_origins: Vec<String>,
+    pub webauthn_required_roles: Vec<AccountRole>,
+    pub openapi_baseline: Option<PathBuf>,
+    pub client_error_sample_rate: f64,
+    pub display_timezone: chrono_tz::Tz,
+    pub client_brand_name: String,
+    pub client_brand_logo_url: Option<String>,
+    pub client_support_contact: Option<String>,
+    pub alert_window_secs: u64,
+    pub alert_cooldown_secs: u64,
+    pub alert_route_5xx_threshold: u32,
+    pub alert_judge0_failure_threshold: u32,
+    pub alert_db_error_threshold: u32,
+    pub replication_role: ReplicationRole,
+    pub replication_standby_url: Option<String>,
+    pub replication_primary_url: Option<String>,
+    pub replication_secret: Option<String>,
+    pub replication_interval_secs: u64,
+    pub replication_snapshot_path: PathBuf,
+    pub archive_sink: Option<String>,
+    pub server_addr: SocketAddr,
+}
+
+impl Settings {
+    pub fn from_env() -> Self {
+        Self {
+            database_url: std::env::var("DATABASE_URL")
+                .unwrap_or_else(|_| "sqlite://asm_lab.db?mode=rwc".into()),
+            judge0_base_urls: executor_pool::normalize_base_urls(split_list(
+                &std::env::var("JUDGE0_BASE_URL").unwrap_or_else(|_| "http://127.0.0.1:2358".into()),
+            ))
+            .unwrap_or_else(|err| panic!("JUDGE0_BASE_URL tidak valid: {err}")),
+            judge0_balance: std::env::var("JUDGE0_BALANCE")
+                .map(|value| {
+                    BalanceStrategy::from_str(value.trim())
+                        .expect("JUDGE0_BALANCE harus round-robin atau least-pending")
+                })
+                .unwrap_or(BalanceStrategy::LeastPending),
+            judge0_eject_after_failures: parse_env(
+                "JUDGE0_EJECT_AFTER_FAILURES",
+                3,
+                "JUDGE0_EJECT_AFTER_FAILURES harus berupa bilangan bulat",
+            ),
+            judge0_health_interval_secs: parse_env(
+                "JUDGE0_HEALTH_INTERVAL_SECS",
+                15,
+                "JUDGE0_HEALTH_INTERVAL_SECS harus berupa bilangan bulat detik",
+            ),
+            judge0_timeout_secs: parse_env(
+                "JUDGE0_TIMEOUT_SECS",
+                30,
+                "JUDGE0_TIMEOUT_SECS harus berupa bilangan bulat detik",
+            ),
+            language_refresh_secs: parse_env(
+                "LANGUAGE_REFRESH_SECS",
+                3600,
+                "LANGUAGE_REFRESH_SECS harus berupa bilangan bulat detik",
+            ),
+            judge0_queue_size: parse_env(
+                "JUDGE0_QUEUE_SIZE",
+                32,
+                "JUDGE0_QUEUE_SIZE harus berupa bilangan bulat",
+            ),
+            exam_student_capacity: parse_env(
+                "EXAM_STUDENT_CAPACITY",
+                0,
+                "EXAM_STUDENT_CAPACITY harus berupa bilangan bulat",
+            ),
+            sql_log: parse_env("SQL_LOG", false, "SQL_LOG harus berupa true atau false"),
+            sql_slow_query_ms: parse_env(
+                "SQL_SLOW_QUERY_MS",
+                500,
+                "SQL_SLOW_QUERY_MS harus berupa bilangan bulat milidetik",
+            ),
+            grading_max_attempts: parse_env(
+                "GRADING_MAX_ATTEMPTS",
+                5,
+                "GRADING_MAX_ATTEMPTS harus berupa bilangan bulat",
+            ),
+            jwt_secret: std::env::var("JWT_SECRET").ok(),
+            jwt_ttl_hours: parse_env(
+                "JWT_TTL_HOURS",
+                12,
+                "JWT_TTL_HOURS harus berupa bilangan bulat jam",
+            ),
+            secret_encryption_key: std::env::var("SECRET_ENCRYPTION_KEY").ok(),
+            code_encryption_key: std::env::var("CODE_ENCRYPTION_KEY_FILE")
+                .map(|path| {
+                    std::fs::read_to_string(&path)
+                        .map(|key| key.trim().to_owned())
+                        .unwrap_or_else(|err| {
+                            panic!("CODE_ENCRYPTION_KEY_FILE {path} tidak dapat dibaca: {err}")
+                        })
+                })
+                .or_else(|_| std::env::var("CODE_ENCRYPTION_KEY"))
+                .ok()
+                .filter(|key| !key.is_empty()),
+            code_encryption_previous_keys: std::env::var("CODE_ENCRYPTION_PREVIOUS_KEYS")
+                .map(|keys| split_list(&keys).map(str::to_owned).collect())
+                .unwrap_or_default(),
+            sse_max_connections: parse_env(
+                "SSE_MAX_CONNECTIONS_PER_USER",
+                3,
+                "SSE_MAX_CONNECTIONS_PER_USER harus berupa bilangan bulat",
+            ),
+            submission_rate_limit_per_minute: parse_env(
+                "SUBMISSION_RATE_LIMIT_PER_MINUTE",
+                30,
+                "SUBMISSION_RATE_LIMIT_PER_MINUTE harus berupa bilangan bulat",
+            ),
+            submission_rate_limit_min_per_minute: parse_env(
+                "SUBMISSION_RATE_LIMIT_MIN_PER_MINUTE",
+                5,
+                "SUBMISSION_RATE_LIMIT_MIN_PER_MINUTE harus berupa bilangan bulat",
+            ),
+            judge0_latency_target_ms: parse_env(
+                "JUDGE0_LATENCY_TARGET_MS",
+                5000,
+                "JUDGE0_LATENCY_TARGET_MS harus berupa bilangan bulat milidetik",
+            ),
+            exam_warning_thresholds: std::env::var("EXAM_WARNING_THRESHOLDS")
+                .unwrap_or_else(|_| crate::services::exam_clock::DEFAULT_WARNING_THRESHOLDS.into()),
+            task_due_reminders: std::env::var("TASK_DUE_REMINDERS").unwrap_or_else(|_| {
+                crate::services::task_deadline::DEFAULT_REMINDER_THRESHOLDS.into()
+            }),
+            exam_clock_tolerance_secs: parse_env(
+                "EXAM_CLOCK_TOLERANCE_SECS",
+                30,
+                "EXAM_CLOCK_TOLERANCE_SECS harus berupa bilangan bulat detik",
+            ),
+            exam_submit_grace_secs: parse_env(
+                "EXAM_SUBMIT_GRACE_SECS",
+                60,
+                "EXAM_SUBMIT_GRACE_SECS harus berupa bilangan bulat detik",
+            ),
+            clock_reference_url: std::env::var("CLOCK_REFERENCE_URL")
+                .ok()
+                .filter(|url| !url.trim().is_empty()),
+            event_retention_days: parse_env(
+                "EVENT_RETENTION_DAYS",
+                7,
+                "EVENT_RETENTION_DAYS harus berupa bilangan bulat hari",
+            ),
+            fingerprint_retention_days: parse_env(
+                "FINGERPRINT_RETENTION_DAYS",
+                crate::services::fingerprint::DEFAULT_RETENTION_DAYS,
+                "FINGERPRINT_RETENTION_DAYS harus berupa bilangan bulat hari",
+            ),
+            export_storage_dir: std::env::var("EXPORT_STORAGE_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from("exports")),
+            export_url_ttl_secs: parse_env(
+                "EXPORT_URL_TTL_SECS",
+                900,
+                "EXPORT_URL_TTL_SECS harus berupa bilangan bulat detik",
+            ),
+            output_limit_kb: parse_env(
+                "OUTPUT_LIMIT_KB",
+                64,
+                "OUTPUT_LIMIT_KB harus berupa bilangan bulat KB",
+            ),
+            max_code_chars: parse_env(
+                "MAX_CODE_CHARS",
+                100_000,
+                "MAX_CODE_CHARS harus berupa bilangan bulat karakter",
+            ),
+            max_description_chars: parse_env(
+                "MAX_DESCRIPTION_CHARS",
+                20_000,
+                "MAX_DESCRIPTION_CHARS harus berupa bilangan bulat karakter",
+            ),
+            account_email_enabled: parse_env(
+                "ACCOUNT_EMAIL_ENABLED",
+                false,
+                "ACCOUNT_EMAIL_ENABLED harus berupa true atau false",
+            ),
+            account_approval_required: parse_env(
+                "ACCOUNT_APPROVAL_REQUIRED",
+                false,
+                "ACCOUNT_APPROVAL_REQUIRED harus berupa true atau false",
+            ),
+            mail_relay_url: std::env::var("MAIL_RELAY_URL")
+                .ok()
+                .filter(|url| !url.trim().is_empty()),
+            webauthn_rp_id: std::env::var("WEBAUTHN_RP_ID")
+                .ok()
+                .map(|rp_id| rp_id.trim().to_owned())
+                .filter(|rp_id| !rp_id.is_empty()),
+            webauthn_rp_name: std::env::var("WEBAUTHN_RP_NAME")
+                .unwrap_or_else(|_| "ASM Lab".into()),
+            webauthn_origins: std::env::var("WEBAUTHN_ORIGINS")
+                .map(|origins| split_list(&origins).map(str::to_owned).collect())
+                .unwrap_or_else(|_| {
+                    ALLOWED_ORIGINS
+                        .iter()
+                        .map(|origin| origin.to_string())
+                        .collect()
+                }),
+            webauthn_required_roles: std::env::var("WEBAUTHN_REQUIRED_ROLES")
+                .map(|roles| {
+                    split_list(&roles)
+                        .map(|role| {
+                            AccountRole::from_str(role).expect(
+                                "WEBAUTHN_REQUIRED_ROLES harus berisi peran admin, assistant, observer atau user",
+                            )
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            openapi_baseline: std::env::var_os("OPENAPI_BASELINE")
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            client_error_sample_rate: parse_env(
+                "CLIENT_ERROR_SAMPLE_RATE",
+                1.0,
+                "CLIENT_ERROR_SAMPLE_RATE harus berupa angka antara 0 dan 1",
+            ),
+            display_timezone: parse_env(
+                "DISPLAY_TIMEZONE",
+                crate::services::display_time::DEFAULT_TIMEZONE
+                    .parse()
+                    .expect("zona waktu default valid"),
+                "DISPLAY_TIMEZONE harus berupa nama zona waktu IANA, mis. Asia/Jakarta",
+            ),
+            client_brand_name: std::env::var("CLIENT_BRAND_NAME")
+                .ok()
+                .map(|name| name.trim().to_owned())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| "ASM Lab".into()),
+            client_brand_logo_url: std::env::var("CLIENT_BRAND_LOGO_URL")
+                .ok()
+                .filter(|url| !url.trim().is_empty()),
+            client_support_contact: std::env::var("CLIENT_SUPPORT_CONTACT")
+                .ok()
+                .filter(|contact| !contact.trim().is_empty()),
+            alert_window_secs: parse_env(
+                "ALERT_WINDOW_SECS",
+                300,
+                "ALERT_WINDOW_SECS harus berupa bilangan bulat detik",
+            ),
+            alert_cooldown_secs: parse_env(
+                "ALERT_COOLDOWN_SECS",
+                900,
+                "ALERT_COOLDOWN_SECS harus berupa bilangan bulat detik",
+            ),
+            alert_route_5xx_threshold: parse_env(
+                "ALERT_ROUTE_5XX_THRESHOLD",
+                10,
+                "ALERT_ROUTE_5XX_THRESHOLD harus berupa bilangan bulat",
+            ),
+            alert_judge0_failure_threshold: parse_env(
+                "ALERT_JUDGE0_FAILURE_THRESHOLD",
+                5,
+                "ALERT_JUDGE0_FAILURE_THRESHOLD harus berupa bilangan bulat",
+            ),
+            alert_db_error_threshold: parse_env(
+                "ALERT_DB_ERROR_THRESHOLD",
+                3,
+                "ALERT_DB_ERROR_THRESHOLD harus berupa bilangan bulat",
+            ),
+            replication_role: std::env::var("REPLICATION_ROLE")
+                .map(|value| {
+                    ReplicationRole::from_str(value.trim())
+                        .expect("REPLICATION_ROLE harus off, primary, atau standby")
+                })
+                .unwrap_or(ReplicationRole::Off),
+            replication_standby_url: std::env::var("REPLICATION_STANDBY_URL")
+                .ok()
+                .map(|url| url.trim().trim_end_matches('/').to_owned())
+                .filter(|url| !url.is_empty()),
+            replication_primary_url: std::env::var("REPLICATION_PRIMARY_URL")
+                .ok()
+                .map(|url| url.trim().trim_end_matches('/').to_owned())
+                .filter(|url| !url.is_empty()),
+            replication_secret: std::env::var("REPLICATION_SECRET")
+                .ok()
+                .filter(|secret| !secret.is_empty()),
+            replication_interval_secs: parse_env(
+                "REPLICATION_INTERVAL_SECS",
+                30,
+                "REPLICATION_INTERVAL_SECS harus berupa bilangan bulat detik",
+            ),
+            replication_snapshot_path: std::env::var("REPLICATION_SNAPSHOT_PATH")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from("asm_lab.standby.db")),
+            archive_sink: std::env::var("ARCHIVE_SINK")
+                .ok()
+                .map(|sink| sink.trim().to_owned())
+                .filter(|sink| !sink.is_empty()),
+            server_addr: std::env::var("SERVER_ADDR")
+                .unwrap_or_else(|_| "0.0.0.0:3000".to_string())
+                .parse()
+                .expect("SERVER_ADDR harus dalam format host:port"),
+        }
+    }
+
+    /// `KEY=value` lines for `--print-config`, with secrets masked.
+    pub fn describe(&self, sources: &ConfigSources) -> String {
+        let mut out = String::new();
+        let dotenv = sources
+            .dotenv_file
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "(tidak ada)".into());
+        let config_state = if sources.config_file_loaded {
+            ""
+        } else {
+            " (belum ada)"
+        };
+        let _ = writeln!(out, "# .env: {dotenv}");
+        let _ = writeln!(
+            out,
+            "# file konfigurasi: {}{config_state}",
+            sources.config_file.display()
+        );
+        let _ = writeln!(out, "DATABASE_URL={}", self.database_url);
+        let _ = writeln!(out, "JUDGE0_BASE_URL={}", self.judge0_base_urls.join(","));
+        let _ = writeln!(out, "JUDGE0_BALANCE={}", self.judge0_balance.as_str());
+        let _ = writeln!(
+            out,
+            "JUDGE0_EJECT_AFTER_FAILURES={}",
+            self.judge0_eject_after_failures
+        );
+        let _ = writeln!(
+            out,
+            "JUDGE0_HEALTH_INTERVAL_SECS={}",
+            self.judge0_health_interval_secs
+        );
+        let _ = writeln!(out, "JUDGE0_TIMEOUT_SECS={}", self.judge0_timeout_secs);
+        let _ = writeln!(out, "LANGUAGE_REFRESH_SECS={}", self.language_refresh_secs);
+        let _ = writeln!(out, "JUDGE0_QUEUE_SIZE={}", self.judge0_queue_size);
+        let _ = writeln!(out, "EXAM_STUDENT_CAPACITY={}", self.exam_student_capacity);
+        let _ = writeln!(out, "SQL_LOG={}", self.sql_log);
+        let _ = writeln!(out, "SQL_SLOW_QUERY_MS={}", self.sql_slow_query_ms);
+        let _ = writeln!(out, "GRADING_MAX_ATTEMPTS={}", self.grading_max_attempts);
+        let _ = writeln!(out, "JWT_SECRET={}", mask(self.jwt_secret.as_deref()));
+        let _ = writeln!(out, "JWT_TTL_HOURS={}", self.jwt_ttl_hours);
+        let _ = writeln!(
+            out,
+            "SECRET_ENCRYPTION_KEY={}",
+            mask(self.secret_encryption_key.as_deref())
+        );
+        let _ = writeln!(
+            out,
+            "CODE_ENCRYPTION_KEY={}",
+            mask(self.code_encryption_key.as_deref())
+        );
+        let _ = writeln!(
+            out,
+            "CODE_ENCRYPTION_PREVIOUS_KEYS=({} kunci)",
+            self.code_encryption_previous_keys.len()
+        );
+        let _ = writeln!(
+            out,
+            "SSE_MAX_CONNECTIONS_PER_USER={}",
+            self.sse_max_connections
+        );
+        let _ = writeln!(
+            out,
+            "SUBMISSION_RATE_LIMIT_PER_MINUTE={}",
+            self.submission_rate_limit_per_minute
+        );
+        let _ = writeln!(
+            out,
+            "SUBMISSION_RATE_LIMIT_MIN_PER_MINUTE={}",
+            self.submission_rate_limit_min_per_minute
+        );
+        let _ = writeln!(
+            out,
+            "JUDGE0_LATENCY_TARGET_MS={}",
+            self.judge0_latency_target_ms
+        );
+        let _ = writeln!(
+            out,
+            "EXAM_WARNING_THRESHOLDS={}",
+            self.exam_warning_thresholds
+        );
+        let _ = writeln!(out, "TASK_DUE_REMINDERS={}", self.task_due_reminders);
+        let _ = writeln!(
+            out,
+            "EXAM_CLOCK_TOLERANCE_SECS={}",
+            self.exam_clock_tolerance_secs
+        );
+        let _ = writeln!(
+            out,
+            "EXAM_SUBMIT_GRACE_SECS={}",
+            self.exam_submit_grace_secs
+        );
+        let _ = writeln!(
+            out,
+            "CLOCK_REFERENCE_URL={}",
+            self.clock_reference_url.as_deref().unwrap_or("(Judge0)")
+        );
+        let _ = writeln!(out, "EVENT_RETENTION_DAYS={}", self.event_retention_days);
+        let _ = writeln!(
+            out,
+            "FINGERPRINT_RETENTION_DAYS={}",
+            self.fingerprint_retention_days
+        );
+        let _ = writeln!(
+            out,
+            "EXPORT_STORAGE_DIR={}",
+            self.export_storage_dir.display()
+        );
+        let _ = writeln!(out, "EXPORT_URL_TTL_SECS={}", self.export_url_ttl_secs);
+        let _ = writeln!(out, "OUTPUT_LIMIT_KB={}", self.output_limit_kb);
+        let _ = writeln!(out, "MAX_CODE_CHARS={}", self.max_code_chars);
+        let _ = writeln!(out, "MAX_DESCRIPTION_CHARS={}", self.max_description_chars);
+        let _ = writeln!(out, "ACCOUNT_EMAIL_ENABLED={}", self.account_email_enabled);
+        let _ = writeln!(
+            out,
+            "ACCOUNT_APPROVAL_REQUIRED={}",
+            self.account_approval_required
+        );
+        let _ = writeln!(
+            out,
+            "MAIL_RELAY_URL={}",
+            self.mail_relay_url.as_deref().unwrap_or("(tidak diatur)")
+        );
+        let _ = writeln!(
+            out,
+            "WEBAUTHN_RP_ID={}",
+            self.webauthn_rp_id.as_deref().unwrap_or("(tidak diatur)")
+        );
+        let _ = writeln!(out, "WEBAUTHN_RP_NAME={}", self.webauthn_rp_name);
+        let _ = writeln!(out, "WEBAUTHN_ORIGINS={}", self.webauthn_origins.join(","));
+        let required_roles: Vec<&str> = self
+            .webauthn_required_roles
+            .iter()
+            .map(AccountRole::as_str)
+            .collect();
+        let _ = writeln!(out, "WEBAUTHN_REQUIRED_ROLES={}", required_roles.join(","));
+        let _ = writeln!(
+            out,
+            "OPENAPI_BASELINE={}",
+            self.openapi_baseline.as_deref().map_or_else(
+                || "(tidak diatur)".into(),
+                |path| path.display().to_string()
+            )
+        );
+        let _ = writeln!(
+            out,
+            "CLIENT_ERROR_SAMPLE_RATE={}",
+            self.client_error_sample_rate
+        );
+        let _ = writeln!(out, "DISPLAY_TIMEZONE={}", self.display_timezone);
+        let _ = writeln!(out, "CLIENT_BRAND_NAME={}", self.client_brand_name);
+        let _ = writeln!(
+            out,
+            "CLIENT_BRAND_LOGO_URL={}",
+            self.client_brand_logo_url
+                .as_deref()
+                .unwrap_or("(tidak diatur)")
+        );
+        let _ = writeln!(
+            out,
+            "CLIENT_SUPPORT_CONTACT={}",
+            self.client_support_contact
+                .as_deref()
+                .unwrap_or("(tidak diatur)")
+        );
+        let _ = writeln!(out, "ALERT_WINDOW_SECS={}", self.alert_window_secs);
+        let _ = writeln!(out, "ALERT_COOLDOWN_SECS={}", self.alert_cooldown_secs);
+        let _ = writeln!(
+            out,
+            "ALERT_ROUTE_5XX_THRESHOLD={}",
+            self.alert_route_5xx_threshold
+        );
+        let _ = writeln!(
+            out,
+            "ALERT_JUDGE0_FAILURE_THRESHOLD={}",
+            self.alert_judge0_failure_threshold
+        );
+        let _ = writeln!(
+            out,
+            "ALERT_DB_ERROR_THRESHOLD={}",
+            self.alert_db_error_threshold
+        );
+        let _ = writeln!(out, "REPLICATION_ROLE={}", self.replication_role.as_str());
+        let _ = writeln!(
+            out,
+            "REPLICATION_STANDBY_URL={}",
+            self.replication_standby_url
+                .as_deref()
+                .unwrap_or("(tidak diatur)")
+        );
+        let _ = writeln!(
+            out,
+            "REPLICATION_PRIMARY_URL={}",
+            self.replication_primary_url
+                .as_deref()
+                .unwrap_or("(tidak diatur)")
+        );
+        let _ = writeln!(
+            out,
+            "REPLICATION_SECRET={}",
+            mask(self.replication_secret.as_deref())
+        );
+        let _ = writeln!(
+            out,
+            "REPLICATION_INTERVAL_SECS={}",
+            self.replication_interval_secs
+        );
+        let _ = writeln!(
+            out,
+            "REPLICATION_SNAPSHOT_PATH={}",
+            self.replication_snapshot_path.display()
+        );
+        let _ = writeln!(
+            out,
+            "ARCHIVE_SINK={}",
+            self.archive_sink.as_deref().unwrap_or("(tidak aktif)")
+        );
+        let _ = writeln!(out, "SERVER_ADDR={}", self.server_addr);
+        out
+    }
+}
+
+fn parse_env<T>(key: &str, default: T, message: &str) -> T
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Debug,
+{
+    std::env::var(key)
+        .ok()
+        .map(|value| value.parse().expect(message))
+        .unwrap_or(default)
+}
+
+/// Non-empty items of a comma-separated list.
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+fn mask(secret: Option<&str>) -> &'static str {
+    match secret {
+        Some(_) => "(diatur, disembunyikan)",
+        None => "(tidak diatur)",
+    }
+}
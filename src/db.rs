@@ -1,12 +1,30 @@
-pub mod migration;
+pub mod data_migration;
 
+use std::path::PathBuf;
+
+use migration::{Migrator, MigratorTrait};
 use sea_orm::{
-    ConnectionTrait, Database, DatabaseBackend, DatabaseConnection, DbErr,Statement,
+    ConnectOptions, ConnectionTrait, Database, DatabaseBackend, DatabaseConnection, DbErr,
+    Statement,
 };
 use sea_query::TableCreateStatement;
 
 pub async fn connect(database_url: &str) -> Result<DatabaseConnection, DbErr> {
-    let db = Database::connect(database_url).await?;
+    connect_logged(database_url, false).await
+}
+
+/// Connects with sqlx's log of every statement (`SQL_LOG`) on or off.
+pub async fn connect_logged(
+    database_url: &str,
+    statement_log: bool,
+) -> Result<DatabaseConnection, DbErr> {
+    let mut options = ConnectOptions::new(database_url);
+    options.sqlx_logging(statement_log);
+    connect_with(options).await
+}
+
+pub async fn connect_with(options: ConnectOptions) -> Result<DatabaseConnection, DbErr> {
+    let db = Database::connect(options).await?;
 
     if db.get_database_backend() == DatabaseBackend::Sqlite {
         db.execute(Statement::from_string(
@@ -19,8 +37,39 @@ pub async fn connect(database_url: &str) -> Result<DatabaseConnection, DbErr> {
     Ok(db)
 }
 
+/// Applies pending schema migrations (see the `migration` crate), then data migrations.
 pub async fn init(db: &DatabaseConnection) -> Result<(), DbErr> {
-    migration::run(db).await
+    Migrator::up(db, None).await?;
+    data_migration::run(db).await
+}
+
+/// Writes a consistent copy of the SQLite database to `path`, which must not exist.
+/// Safe to run while the server is using the database.
+pub async fn backup(db: &DatabaseConnection, path: &str) -> Result<(), DbErr> {
+    if db.get_database_backend() != DatabaseBackend::Sqlite {
+        return Err(DbErr::Custom(
+            "backup hanya didukung untuk database SQLite".into(),
+        ));
+    }
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "VACUUM INTO ?",
+        [path.into()],
+    ))
+    .await?;
+    Ok(())
+}
+
+/// File behind a SQLite `database_url`; `None` for other backends and in-memory
+/// databases.
+pub fn sqlite_path(database_url: &str) -> Option<PathBuf> {
+    let rest = database_url
+        .strip_prefix("sqlite://")
+        .or_else(|| database_url.strip_prefix("sqlite:"))?;
+    let (path, options) = rest.split_once('?').unwrap_or((rest, ""));
+    let path = path.strip_prefix("file:").unwrap_or(path);
+    (!path.is_empty() && path != ":memory:" && !options.contains("mode=memory"))
+        .then(|| PathBuf::from(path))
 }
 
 #[allow(dead_code)]
@@ -32,4 +81,4 @@ pub(crate) async fn create_table_if_not_exists(
     let builder = db.get_database_backend();
     db.execute(builder.build(&table)).await?;
     Ok(())
-}
\ No newline at end of file
+}
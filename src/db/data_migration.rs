@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, TransactionTrait,
+};
+use serde_json::Value;
+
+use crate::entities::{classroom, migration_meta, task};
+
+/// Data migrations in application order. Versions are recorded in `migrations_meta`
+/// and never reused; append new entries at the end.
+pub const DATA_MIGRATIONS: &[(i32, &str)] = &[(1, "normalize_legacy_tasks")];
+
+pub async fn run(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let applied: HashSet<i32> = migration_meta::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|meta| meta.version)
+        .collect();
+
+    for &(version, name) in DATA_MIGRATIONS {
+        if applied.contains(&version) {
+            continue;
+        }
+
+        let txn = db.begin().await?;
+        match version {
+            1 => normalize_legacy_tasks(&txn).await?,
+            _ => unreachable!("data migration {version} has no implementation"),
+        }
+        migration_meta::ActiveModel {
+            version: Set(version),
+            name: Set(name.to_owned()),
+            applied_at: Set(Utc::now()),
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+
+        tracing::info!("applied data migration {version} ({name})");
+    }
+
+    Ok(())
+}
+
+/// Moves the JSON `classrooms.tasks` blobs into `tasks` rows, repairing the shapes
+/// older frontends produced instead of dropping them.
+async fn normalize_legacy_tasks(db: &impl sea_orm::ConnectionTrait) -> Result<(), DbErr> {
+    let classrooms = classroom::Entity::find().all(db).await?;
+
+    for classroom in classrooms {
+        let has_rows = task::Entity::find()
+            .filter(task::Column::ClassroomId.eq(classroom.id))
+            .count(db)
+            .await?
+            > 0;
+        if has_rows {
+            continue;
+        }
+
+        let entries = parse_legacy_tasks(&classroom.tasks).unwrap_or_else(|| {
+            tracing::warn!(
+                "classroom {} has unparseable tasks JSON, keeping it as a single task",
+                classroom.id
+            );
+            vec![(String::new(), classroom.tasks.trim().to_owned())]
+        });
+
+        let now = Utc::now();
+        for (position, (title, description)) in entries.into_iter().enumerate() {
+            task::ActiveModel {
+                classroom_id: Set(classroom.id),
+                position: Set(position as i32),
+                title: Set(title),
+                description: Set(description),
+                weight: Set(1),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns `None` only when the column holds non-empty text that is not JSON at all.
+fn parse_legacy_tasks(raw: &str) -> Option<Vec<(String, String)>> {
+    let raw = raw.trim().trim_start_matches('\u{feff}');
+    if raw.is_empty() {
+        return Some(Vec::new());
+    }
+
+    let value = serde_json::from_str::<Value>(raw).ok()?;
+    let mut entries = Vec::new();
+    collect_task_entries(value, &mut entries);
+    Some(entries)
+}
+
+fn collect_task_entries(value: Value, entries: &mut Vec<(String, String)>) {
+    match value {
+        Value::Null => {}
+        Value::Array(items) => {
+            for item in items {
+                collect_task_entries(item, entries);
+            }
+        }
+        // Some clients double-encoded the array into a JSON string.
+        Value::String(text) => match serde_json::from_str::<Value>(&text) {
+            Ok(inner @ Value::Array(_)) => collect_task_entries(inner, entries),
+            _ if text.trim().is_empty() => {}
+            _ => entries.push((String::new(), text)),
+        },
+        Value::Object(map) => {
+            let field = |keys: &[&str]| {
+                keys.iter()
+                    .find_map(|key| map.get(*key).and_then(Value::as_str))
+                    .map(str::to_owned)
+                    .unwrap_or_default()
+            };
+            let title = field(&["title", "name"]);
+            let description = field(&["description", "content", "text", "body", "markdown"]);
+
+            if title.trim().is_empty() && description.trim().is_empty() {
+                if !map.is_empty() {
+                    entries.push((String::new(), Value::Object(map).to_string()));
+                }
+            } else {
+                entries.push((title, description));
+            }
+        }
+        other => entries.push((String::new(), other.to_string())),
+    }
+}
@@ -0,0 +1,645 @@
+use std::collections::{BTreeSet, HashSet};
+
+use axum::{
+    Router,
+    extract::{Request, State},
+    http::{
+        HeaderMap, HeaderValue,
+        header::{COOKIE, SET_COOKIE},
+    },
+    middleware::{self, Next},
+    response::Response,
+};
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::{
+    auth::{self, AuthAccount},
+    dto,
+    error::AppError,
+    routes,
+    state::AppState,
+};
+
+/// Operations a student session may call. Everything else is only listed in the admin
+/// document; operation ids default to the handler's function name.
+const STUDENT_OPERATIONS: &[&str] = &[
+    "login",
+    "admin_exists",
+    "me",
+    "bootstrap",
+    "current_banner",
+    "save_run_config",
+    "list_run_configs",
+    "delete_run_config",
+    "submit_code",
+    "run_practice_tests",
+    "record_task_progress",
+    "ack_message",
+    "create_dispute",
+    "list_submission_disputes",
+    "highlight_submission",
+    "report_client_error",
+    "grading_status",
+];
+
+/// Cookie that carries the admin token for the Swagger UI, which cannot send headers
+/// when the browser loads the page and its document.
+const DOCS_TOKEN_COOKIE: &str = "asm_docs_token";
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        routes::classroom::list_classrooms,
+        routes::classroom::get_classroom,
+        routes::classroom::create_classroom,
+        routes::classroom::update_classroom,
+        routes::classroom::delete_classroom,
+        routes::classroom::delete_intent,
+        routes::classroom::deactivate_users_post_exam,
+        routes::classroom::grading_status,
+        routes::publish::validate_classroom,
+        routes::publish::publish_classroom,
+        routes::publish::check_exam_gate,
+        routes::exam::pause_exam,
+        routes::exam::resume_exam,
+        routes::exam::live_stats,
+        routes::exam::proctor_events,
+        routes::exam::exam_network_profile,
+        routes::classroom::list_classroom_users,
+        routes::classroom::add_user_to_classroom,
+        routes::classroom::update_user_in_classroom,
+        routes::classroom::update_user_presetup,
+        routes::classroom::delete_user_from_classroom,
+        routes::classroom::transfer_users,
+        routes::dispute::create_dispute,
+        routes::dispute::list_submission_disputes,
+        routes::dispute::list_classroom_disputes,
+        routes::dispute::respond_to_dispute,
+        routes::integrity::get_device_policy,
+        routes::integrity::put_device_policy,
+        routes::fingerprint::get_fingerprint_sharing,
+        routes::fingerprint::put_fingerprint_sharing,
+        routes::sandbox::get_sandbox_flags,
+        routes::sandbox::put_sandbox_flags,
+        routes::integrity::list_integrity_events,
+        routes::integrity::reset_device_binding,
+        routes::seat::get_seat_map,
+        routes::seat::put_seat_map,
+        routes::ticket::exam_tickets,
+        routes::verdict::list_verdict_messages,
+        routes::verdict::put_verdict_message,
+        routes::verdict::delete_verdict_message,
+        routes::grading::get_grading_script,
+        routes::grading::put_grading_script,
+        routes::grading::delete_grading_script,
+        routes::grading::export_grades,
+        routes::grading::grade_student,
+        routes::grading::exam_packet,
+        routes::settings_history::list_settings_history,
+        routes::settings_history::rollback_settings,
+        routes::message::send_message,
+        routes::message::list_messages,
+        routes::message::ack_message,
+        routes::task::list_tasks,
+        routes::task::create_task,
+        routes::task::get_task,
+        routes::task::update_task,
+        routes::task::delete_task,
+        routes::test_case::list_test_cases,
+        routes::task_sheet::rendered_task,
+        routes::test_case::create_test_case,
+        routes::test_case::extract_tests,
+        routes::test_case::update_test_case,
+        routes::test_case::delete_test_case,
+        routes::test_case::run_practice_tests,
+        routes::test_case::test_case_stats,
+        routes::task_time::record_task_progress,
+        routes::task_time::task_time_summary,
+        routes::task_time::export_task_times,
+        routes::judge::submit_code,
+        routes::judge::get_submission,
+        routes::account::list_accounts,
+        routes::account::get_account,
+        routes::account::create_account,
+        routes::account::update_account_role,
+        routes::account::delete_account,
+        routes::account::list_pending_accounts,
+        routes::account::approve_account,
+        routes::account::account_activity,
+        routes::account::export_account_activity,
+        routes::account::export_accounts,
+        routes::account::import_accounts,
+        routes::auth::login,
+        routes::auth::admin_exists,
+        routes::auth::me,
+        routes::account_email::update_email,
+        routes::account_email::verify_email,
+        routes::account_email::recover_npm,
+        routes::passkey::start_registration,
+        routes::passkey::finish_registration,
+        routes::passkey::start_login,
+        routes::passkey::finish_login,
+        routes::passkey::list_passkeys,
+        routes::passkey::delete_passkey,
+        routes::bootstrap::bootstrap,
+        routes::assistant::list_assistants,
+        routes::assistant::assign_assistant,
+        routes::assistant::unassign_assistant,
+        routes::run_config::save_run_config,
+        routes::run_config::list_run_configs,
+        routes::run_config::delete_run_config,
+        routes::time::server_time,
+        routes::client_config::client_config,
+        routes::export::create_export,
+        routes::export::list_exports,
+        routes::export::get_export,
+        routes::export::download_export,
+        routes::banner::current_banner,
+        routes::banner::get_banner,
+        routes::banner::put_banner,
+        routes::banner::delete_banner,
+        routes::admin::system_stats,
+        routes::admin::metrics,
+        routes::dead_letter::list_dead_letters,
+        routes::dead_letter::requeue_dead_letter,
+        routes::executor::get_executor_config,
+        routes::executor::put_executor_config,
+        routes::executor::delete_executor_config,
+        routes::fingerprint::get_fingerprint_settings,
+        routes::fingerprint::put_fingerprint_settings,
+        routes::admin::openapi_diff,
+        routes::admin::schema,
+        routes::client_error::report_client_error,
+        routes::client_error::list_client_errors,
+        routes::auth::list_login_events,
+        routes::replication::health,
+        routes::replication::replication_status,
+        routes::replication::receive_snapshot,
+        routes::schedule::schedule_conflicts,
+        routes::diagnostics::self_test,
+        routes::artifact::download_artifacts,
+        routes::analytics::daily_stats,
+        routes::analytics::language_stats,
+        routes::highlight::highlight_submission,
+        routes::submission::list_classroom_submissions,
+        routes::submission::list_user_submissions,
+        routes::rollover::rollover,
+        routes::purge::purge_student,
+        routes::purge::verify_purge_receipt,
+        routes::webhook::list_webhooks,
+        routes::webhook::create_webhook,
+        routes::webhook::delete_webhook,
+        routes::webhook::test_webhook
+    ),
+    components(
+        schemas(
+            dto::ClassroomResponse,
+            dto::ClassroomResponseV2,
+            dto::ClassroomStatus,
+            dto::PostFinishPolicy,
+            dto::PublishClassroomRequest,
+            dto::PublishClassroomResponse,
+            dto::ReadinessReport,
+            dto::SmokeTestResult,
+            dto::ExamGate,
+            dto::ExamGateCheckResponse,
+            dto::TaskResponse,
+            dto::TaskDetailResponse,
+            dto::CreateTaskRequest,
+            dto::UpdateTaskRequest,
+            dto::RenderedTaskResponse,
+            dto::TaskInput,
+            dto::UserResponse,
+            dto::CreateClassroomRequest,
+            dto::UpdateClassroomRequest,
+            dto::CreateClassroomRequestV2,
+            dto::UpdateClassroomRequestV2,
+            dto::ExamClockResponse,
+            dto::ServerTimeResponse,
+            dto::ClientConfigResponse,
+            dto::ExportKind,
+            dto::ExportStatus,
+            dto::CreateExportRequest,
+            dto::ExportJobResponse,
+            dto::ClientFeatures,
+            dto::ClientLimits,
+            dto::ClientBranding,
+            dto::ExecutorLanguage,
+            dto::BannerLevel,
+            dto::BannerResponse,
+            dto::UpdateBannerRequest,
+            dto::ExamWarningEvent,
+            dto::TaskDueEvent,
+            dto::ExamTimeUpEvent,
+            dto::SubmissionEvent,
+            dto::ProctorEvent,
+            dto::ProctorEventFilter,
+            dto::GradingStatus,
+            dto::GradingStatusResponse,
+            dto::SubmissionFeedback,
+            dto::SubmissionHistoryEntry,
+            dto::UserStatusEvent,
+            dto::UserJoinedEvent,
+            dto::UserSubmittedEvent,
+            dto::ExamFinishedEvent,
+            dto::LiveStatsResponse,
+            dto::ExamNetworkProfileResponse,
+            dto::NetworkRule,
+            dto::NetworkSource,
+            dto::UserConnectionCount,
+            dto::CreateUserRequest,
+            dto::UpdateUserRequest,
+            dto::UpdateUserPresetupRequest,
+            dto::TransferUsersRequest,
+            dto::TransferUsersResponse,
+            dto::TransferredUser,
+            dto::TransferMode,
+            dto::TransferConflict,
+            dto::Judge0SubmissionRequest,
+            dto::Judge0SubmissionRequestV2,
+            dto::Judge0SubmissionResponse,
+            dto::Judge0SubmissionStatus,
+            dto::Judge0RunStatus,
+            dto::CreateDisputeRequest,
+            dto::RespondDisputeRequest,
+            dto::DisputeResponse,
+            dto::DisputeStatus,
+            dto::DevicePolicy,
+            dto::DevicePolicyRequest,
+            dto::DevicePolicyResponse,
+            dto::FingerprintSharingRequest,
+            dto::FingerprintSharingResponse,
+            dto::SandboxFlags,
+            dto::SandboxFlagsResponse,
+            dto::IntegrityEventResponse,
+            dto::IntegrityAlertEvent,
+            dto::Seat,
+            dto::SeatMapRequest,
+            dto::SeatAssignment,
+            dto::SeatMapResponse,
+            dto::TicketFormat,
+            dto::UpdateVerdictMessageRequest,
+            dto::VerdictMessageResponse,
+            dto::GradingScriptRequest,
+            dto::GradingScriptResponse,
+            dto::GradingScriptInput,
+            dto::GradingScriptCase,
+            dto::GradeResultResponse,
+            dto::GradeCaseResult,
+            dto::ExamPacket,
+            dto::PacketFormat,
+            dto::PacketGrade,
+            dto::PacketTaskScore,
+            dto::PacketCaseResult,
+            dto::PacketVerdict,
+            dto::GradingScriptVerdict,
+            dto::GradeFormat,
+            dto::TimeDisplay,
+            dto::ClassroomSettings,
+            dto::SettingsChange,
+            dto::SettingsVersionResponse,
+            dto::SendMessageRequest,
+            dto::AckMessageRequest,
+            dto::MessageResponse,
+            dto::MessageRecipientResponse,
+            dto::MessageEvent,
+            dto::TestMode,
+            dto::TestCaseResponse,
+            dto::CreateTestCaseRequest,
+            dto::UpdateTestCaseRequest,
+            dto::ExtractTestsRequest,
+            dto::ProposedTestCase,
+            dto::ExtractTestsResponse,
+            dto::PracticeRunRequest,
+            dto::TestRunResponse,
+            dto::TestCaseResult,
+            dto::TestCaseFlag,
+            dto::TestCaseStats,
+            dto::TestCaseStatsResponse,
+            dto::TaskActivity,
+            dto::TaskProgressRequest,
+            dto::TaskTimeSummary,
+            dto::TaskTimeSummaryResponse,
+            dto::AccountResponse,
+            dto::AccountExport,
+            dto::ExportedAccount,
+            dto::ImportConflictPolicy,
+            dto::AccountImportResponse,
+            dto::AuditKind,
+            dto::AuditEntryResponse,
+            dto::ClassroomRowCounts,
+            dto::DeleteIntentResponse,
+            dto::DeletionOutcome,
+            dto::DeleteClassroomResponse,
+            dto::PurgeStudentRequest,
+            dto::PurgeRowCounts,
+            dto::PurgeReceipt,
+            dto::VerifyPurgeReceiptRequest,
+            dto::PurgeReceiptVerification,
+            dto::ClassroomActivity,
+            dto::SubmissionActivity,
+            dto::AccountActivityResponse,
+            dto::CreateAccountRequest,
+            dto::UpdateAccountRoleRequest,
+            dto::AccountRole,
+            dto::AccountStatus,
+            dto::LoginRequest,
+            dto::LoginResponse,
+            dto::AdminExistsResponse,
+            dto::CurrentAccountResponse,
+            dto::UpdateEmailRequest,
+            dto::RecoverNpmRequest,
+            dto::RecoverNpmResponse,
+            dto::PasskeyCreationOptions,
+            dto::RelyingParty,
+            dto::PasskeyUser,
+            dto::CredentialParameter,
+            dto::CredentialDescriptor,
+            dto::AuthenticatorSelection,
+            dto::PasskeyRequestOptions,
+            dto::PasskeyRegisterRequest,
+            dto::RegistrationCredential,
+            dto::AttestationResponse,
+            dto::PasskeyLoginStartRequest,
+            dto::PasskeyLoginRequest,
+            dto::AssertionCredential,
+            dto::AssertionResponse,
+            dto::PasskeyResponse,
+            dto::Permissions,
+            dto::BootstrapResponse,
+            dto::MembershipInfo,
+            dto::OwnCodeInfo,
+            dto::SubmissionCooldownInfo,
+            dto::RunConfigOptions,
+            dto::SaveRunConfigRequest,
+            dto::RunConfigResponse,
+            dto::SystemStatsResponse,
+            dto::RolloverRequest,
+            dto::RolloverReport,
+            dto::RolloverClassroom,
+            dto::ClonedClassroom,
+            dto::DatabaseStats,
+            dto::TableRowCount,
+            dto::StorageUsage,
+            dto::CodeBlobStats,
+            dto::MetricsResponse,
+            dto::ExecutorMetrics,
+            dto::GradingRetryMetrics,
+            dto::DatabaseMetrics,
+            dto::SlowQueryRoute,
+            dto::DeadLetterResponse,
+            dto::BalanceStrategy,
+            dto::ExecutorConfigSource,
+            dto::UpdateExecutorConfigRequest,
+            dto::UpdateFingerprintSettingsRequest,
+            dto::FingerprintSettingsResponse,
+            dto::ExecutorConfigResponse,
+            dto::ExecutorInstanceStatus,
+            dto::ExecutorQueueStatus,
+            dto::ErrorBudgetStatus,
+            dto::AlertSignalKind,
+            dto::OpenApiDiffResponse,
+            dto::SchemaResponse,
+            dto::TableSchema,
+            dto::ColumnSchema,
+            dto::MigrationStatus,
+            dto::SchemaMigrationStatus,
+            dto::ApiChange,
+            dto::ApiChangeKind,
+            dto::ClientErrorReport,
+            dto::ClientErrorKind,
+            dto::ClientErrorAck,
+            dto::ClientErrorResponse,
+            dto::LoginAnomaly,
+            dto::LoginEventResponse,
+            dto::ReplicationRole,
+            dto::ReplicationStatusResponse,
+            dto::HealthResponse,
+            dto::ScheduleConflictKind,
+            dto::ScheduleConflict,
+            dto::ScheduleConflictsResponse,
+            dto::DiagnosticCheckKind,
+            dto::DiagnosticStatus,
+            dto::DiagnosticCheck,
+            dto::SelfTestResponse,
+            dto::SubmissionArtifactMetadata,
+            dto::ErrorSource,
+            dto::DailyStatsResponse,
+            dto::DailyStatsEntry,
+            dto::LanguageStatsResponse,
+            dto::LanguageUsageSummary,
+            dto::LanguageUsageEntry,
+            dto::HighlightFormat,
+            dto::HighlightResponse,
+            dto::HighlightToken,
+            dto::RateLimitStatus,
+            dto::CreateWebhookRequest,
+            dto::WebhookResponse,
+            dto::WebhookCreatedResponse,
+            dto::WebhookTestResponse
+        )
+    ),
+    tags(
+        (name = "Classrooms", description = "Manajemen entitas kelas"),
+        (name = "Users", description = "Pengelolaan user di dalam kelas"),
+        (name = "Grading", description = "Penilaian submission final dan skrip penilai kustom"),
+        (name = "Disputes", description = "Keberatan mahasiswa atas nilai submission"),
+        (name = "Integrity", description = "Pengikatan perangkat ujian, denah tempat duduk, dan catatan kejanggalan untuk pengawas"),
+        (name = "Messages", description = "Pesan pengawas ke mahasiswa tertentu"),
+        (name = "Tasks", description = "Tugas per kelas: urutan, kode awal, output acuan dan bobot"),
+        (name = "Test Cases", description = "Test case per tugas dan uji coba latihan"),
+        (name = "Task Analytics", description = "Pencatatan waktu pengerjaan per tugas dan ringkasannya"),
+        (name = "Executor", description = "Proxy eksekusi kode ke Judge0"),
+        (name = "Run Configs", description = "Konfigurasi eksekusi tersimpan milik mahasiswa"),
+        (name = "Assistants", description = "Penugasan asisten ke kelas untuk membaca kode mahasiswa"),
+        (name = "Accounts", description = "Manajemen akun login"),
+        (name = "Auth", description = "Autentikasi pengguna"),
+        (name = "Admin", description = "Statistik dan pemeliharaan server"),
+        (name = "Webhooks", description = "Endpoint penerima webhook bertanda tangan HMAC"),
+        (name = "Diagnostics", description = "Daftar periksa untuk membantu asisten lab memecahkan masalah login dan ujian mahasiswa"),
+        (name = "Time", description = "Waktu server untuk menyelaraskan hitung mundur di klien"),
+        (name = "Client Config", description = "Fitur, bahasa, batas ukuran, dan branding deployment untuk frontend"),
+        (name = "Exports", description = "Ekspor kelas besar yang dibuat di latar belakang dan diunduh lewat tautan bertanda tangan"),
+        (name = "Banner", description = "Pengumuman global untuk semua klien, mis. jadwal pemeliharaan"),
+        (name = "Client Errors", description = "Laporan error frontend dan error server yang dikaitkan lewat request id"),
+        (name = "Analytics", description = "Rekap statistik harian untuk grafik dashboard")
+    ),
+    modifiers(&BearerSecurity)
+)]
+struct ApiDoc;
+
+/// Registers the `bearer` scheme referenced by authenticated endpoints.
+struct BearerSecurity;
+
+impl Modify for BearerSecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .build(),
+                ),
+            );
+        }
+    }
+}
+
+/// Swagger UIs: `/docs` with every operation, for admins only, and `/docs/student`
+/// with the student-facing operations, open to everyone.
+/// Release the served document describes: the crate version, plus the commit in
+/// `ASM_LAB_COMMIT` when the build set it, e.g. `0.1.0+3f2a9c1`.
+pub fn version_tag() -> String {
+    match option_env!("ASM_LAB_COMMIT").filter(|commit| !commit.is_empty()) {
+        Some(commit) => format!("{}+{commit}", env!("CARGO_PKG_VERSION")),
+        None => env!("CARGO_PKG_VERSION").to_owned(),
+    }
+}
+
+/// The full admin document, stamped with [`version_tag`].
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.info.version = version_tag();
+    openapi
+}
+
+pub fn router(state: AppState) -> Router<AppState> {
+    let admin_docs = Router::from(SwaggerUi::new("/docs").url("/api-doc/openapi.json", openapi()))
+        .layer(middleware::from_fn_with_state(state, require_admin));
+
+    let student_docs =
+        SwaggerUi::new("/docs/student").url("/api-doc/student/openapi.json", student_openapi());
+
+    admin_docs.merge(student_docs)
+}
+
+/// The full document reduced to [`STUDENT_OPERATIONS`], with the tags and schemas
+/// those operations no longer reference removed.
+fn student_openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+
+    for path_item in openapi.paths.paths.values_mut() {
+        path_item.operations.retain(|_, operation| {
+            operation
+                .operation_id
+                .as_deref()
+                .is_some_and(|id| STUDENT_OPERATIONS.contains(&id))
+        });
+    }
+    openapi
+        .paths
+        .paths
+        .retain(|_, path_item| !path_item.operations.is_empty());
+
+    let used_tags: HashSet<String> = openapi
+        .paths
+        .paths
+        .values()
+        .flat_map(|path_item| path_item.operations.values())
+        .flat_map(|operation| operation.tags.iter().flatten().cloned())
+        .collect();
+    if let Some(tags) = openapi.tags.as_mut() {
+        tags.retain(|tag| used_tags.contains(&tag.name));
+    }
+
+    if let Some(components) = openapi.components.as_mut() {
+        let paths = serde_json::to_value(&openapi.paths).expect("paths serialize to JSON");
+        let mut reachable = BTreeSet::new();
+        let mut pending = Vec::new();
+        collect_schema_refs(&paths, &mut pending);
+        while let Some(name) = pending.pop() {
+            if !reachable.insert(name.clone()) {
+                continue;
+            }
+            if let Some(schema) = components.schemas.get(&name) {
+                let schema = serde_json::to_value(schema).expect("schema serializes to JSON");
+                collect_schema_refs(&schema, &mut pending);
+            }
+        }
+        components
+            .schemas
+            .retain(|name, _| reachable.contains(name));
+    }
+
+    openapi
+}
+
+fn collect_schema_refs(value: &serde_json::Value, names: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                match value {
+                    serde_json::Value::String(reference) if key == "$ref" => {
+                        if let Some(name) = reference.strip_prefix("#/components/schemas/") {
+                            names.push(name.to_owned());
+                        }
+                    }
+                    _ => collect_schema_refs(value, names),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_schema_refs(item, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Lets admins through with a Bearer token, the docs cookie, or `?token=` on the UI
+/// page, which also stores the token in the cookie for the document request.
+async fn require_admin(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let query_token = request.uri().query().and_then(|query| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+            .filter(|token| !token.is_empty())
+            .map(str::to_owned)
+    });
+    let token = auth::bearer_token(request.headers())
+        .map(str::to_owned)
+        .or_else(|| query_token.clone())
+        .or_else(|| cookie_token(request.headers()))
+        .ok_or_else(|| AppError::Unauthorized("Dokumentasi admin memerlukan token.".into()))?;
+
+    let caller = AuthAccount::from_token(&state, &token).await?;
+    if !caller.is_admin() {
+        return Err(AppError::Forbidden(
+            "Dokumentasi lengkap hanya untuk admin; gunakan /docs/student.".into(),
+        ));
+    }
+
+    let mut response = next.run(request).await;
+    if let Some(token) = query_token {
+        let cookie = format!("{DOCS_TOKEN_COOKIE}={token}; Path=/; HttpOnly; SameSite=Strict");
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(SET_COOKIE, value);
+        }
+    }
+    Ok(response)
+}
+
+fn cookie_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| {
+            pair.trim()
+                .strip_prefix(&format!("{DOCS_TOKEN_COOKIE}="))
+                .map(str::to_owned)
+        })
+        .filter(|token| !token.is_empty())
+}
@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::entities::account;
 
@@ -9,6 +9,10 @@ use crate::entities::account;
 pub enum AccountRole {
     User,
     Admin,
+    /// Teaching assistant; reads the code of students in the classrooms assigned to it.
+    Assistant,
+    /// Read-only staff such as external proctors; student code is shown as lengths only.
+    Observer,
 }
 
 impl AccountRole {
@@ -16,6 +20,8 @@ impl AccountRole {
         match self {
             AccountRole::User => "user",
             AccountRole::Admin => "admin",
+            AccountRole::Assistant => "assistant",
+            AccountRole::Observer => "observer",
         }
     }
 
@@ -23,6 +29,34 @@ impl AccountRole {
         match value {
             "user" | "USER" | "User" => Some(AccountRole::User),
             "admin" | "ADMIN" | "Admin" => Some(AccountRole::Admin),
+            "assistant" | "ASSISTANT" | "Assistant" => Some(AccountRole::Assistant),
+            "observer" | "OBSERVER" | "Observer" => Some(AccountRole::Observer),
+            _ => None,
+        }
+    }
+}
+
+/// Whether an account may use the lab. New NPMs start `pending` when the deployment
+/// requires admin approval (`ACCOUNT_APPROVAL_REQUIRED`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountStatus {
+    Active,
+    Pending,
+}
+
+impl AccountStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountStatus::Active => "active",
+            AccountStatus::Pending => "pending",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "active" => Some(AccountStatus::Active),
+            "pending" => Some(AccountStatus::Pending),
             _ => None,
         }
     }
@@ -34,6 +68,13 @@ pub struct AccountResponse {
     pub id: i32,
     pub npm: String,
     pub role: AccountRole,
+    pub status: AccountStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -46,6 +87,10 @@ impl AccountResponse {
             id: model.id,
             npm: model.npm,
             role,
+            status: AccountStatus::from_str(&model.status).unwrap_or(AccountStatus::Active),
+            approved_at: model.approved_at,
+            email_verified: model.email.is_some() && model.email_verified_at.is_some(),
+            email: model.email,
             created_at: model.created_at,
             updated_at: model.updated_at,
         }
@@ -64,3 +109,78 @@ pub struct CreateAccountRequest {
 pub struct UpdateAccountRoleRequest {
     pub role: AccountRole,
 }
+
+/// Accounts and roles of a deployment as written by `GET /api/admin/accounts/export`
+/// and read by `POST /api/admin/accounts/import`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AccountExport {
+    /// Always `asm-lab/accounts`.
+    pub format: String,
+    /// Document version; this server reads and writes version 1.
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub accounts: Vec<ExportedAccount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ExportedAccount {
+    pub npm: String,
+    pub role: AccountRole,
+    pub status: AccountStatus,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub approved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<account::Model> for ExportedAccount {
+    fn from(model: account::Model) -> Self {
+        Self {
+            role: AccountRole::from_str(&model.role).unwrap_or(AccountRole::User),
+            status: AccountStatus::from_str(&model.status).unwrap_or(AccountStatus::Active),
+            npm: model.npm,
+            email: model.email,
+            email_verified_at: model.email_verified_at,
+            approved_at: model.approved_at,
+            created_at: model.created_at,
+        }
+    }
+}
+
+/// What an import does with an NPM that already has an account.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportConflictPolicy {
+    /// Keep the existing account untouched.
+    #[default]
+    Skip,
+    /// Replace role, status, email and approval with the document's, clearing what it
+    /// leaves empty.
+    Overwrite,
+    /// Take role and status from the document; email and approval only where the
+    /// document has them.
+    Merge,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AccountImportParams {
+    /// Conflict policy for NPMs that already exist; `skip` by default.
+    #[serde(default)]
+    pub policy: ImportConflictPolicy,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountImportResponse {
+    pub policy: ImportConflictPolicy,
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    /// Existing NPMs left as they were, by the `skip` policy or because nothing differed.
+    pub skipped: Vec<String>,
+}
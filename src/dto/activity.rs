@@ -0,0 +1,139 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::account::AccountResponse;
+use crate::entities::{audit_log, submission};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditKind {
+    /// Successful sign-in.
+    Login,
+    /// State-changing request made with a staff token.
+    Request,
+    /// Permanent classroom deletion, with the row counts it removed.
+    Deletion,
+    /// Student purge, with the receipt id and the row counts it removed.
+    Purge,
+}
+
+impl AuditKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditKind::Login => "login",
+            AuditKind::Request => "request",
+            AuditKind::Deletion => "deletion",
+            AuditKind::Purge => "purge",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "login" | "LOGIN" | "Login" => Some(AuditKind::Login),
+            "request" | "REQUEST" | "Request" => Some(AuditKind::Request),
+            "deletion" | "DELETION" | "Deletion" => Some(AuditKind::Deletion),
+            "purge" | "PURGE" | "Purge" => Some(AuditKind::Purge),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntryResponse {
+    pub id: i32,
+    pub kind: AuditKind,
+    /// Login method (`npm`, `passkey`), the request's method and path, or what a
+    /// deletion removed.
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classroom_id: Option<i32>,
+    /// Response status of a request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditEntryResponse {
+    pub fn from_model(model: audit_log::Model) -> Self {
+        Self {
+            id: model.id,
+            kind: AuditKind::from_str(&model.kind).unwrap_or(AuditKind::Request),
+            detail: model.detail,
+            classroom_id: model.classroom_id,
+            status: model.status,
+            request_id: model.request_id,
+            created_at: model.created_at,
+        }
+    }
+}
+
+/// Classroom the account belongs to, as a student on the roster or as an assistant.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassroomActivity {
+    pub classroom_id: i32,
+    pub classroom_name: String,
+    /// `student` or `assistant`.
+    pub membership: String,
+    /// Roster entry of a student membership.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<i32>,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionActivity {
+    pub id: i32,
+    pub classroom_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<i32>,
+    pub language_id: i32,
+    pub is_final: bool,
+    pub auto_submitted: bool,
+    pub late: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_score: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<submission::Model> for SubmissionActivity {
+    fn from(model: submission::Model) -> Self {
+        Self {
+            id: model.id,
+            classroom_id: model.classroom_id,
+            task_id: model.task_id,
+            language_id: model.language_id,
+            is_final: model.is_final,
+            auto_submitted: model.auto_submitted,
+            late: model.late,
+            status: model.status,
+            score: model.score,
+            max_score: model.max_score,
+            created_at: model.created_at,
+        }
+    }
+}
+
+/// Everything recorded about one account, oldest first in each list.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountActivityResponse {
+    pub account: AccountResponse,
+    pub generated_at: DateTime<Utc>,
+    pub logins: Vec<AuditEntryResponse>,
+    pub classrooms: Vec<ClassroomActivity>,
+    /// Runs and hand-ins of the roster entries with the account's NPM. Source code is
+    /// not included.
+    pub submissions: Vec<SubmissionActivity>,
+    /// State-changing requests made while signed in as staff.
+    pub admin_actions: Vec<AuditEntryResponse>,
+}
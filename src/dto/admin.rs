@@ -0,0 +1,356 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::{executor::ExecutorInstanceStatus, login_event::LoginEventResponse};
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStats {
+    pub backend: String,
+    /// Allocated database size; only reported for SQLite.
+    pub size_bytes: Option<i64>,
+    /// Space held by free pages that `VACUUM` would give back.
+    pub free_bytes: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TableRowCount {
+    pub table: String,
+    pub rows: u64,
+}
+
+/// Disk used by a file-backed store next to the database.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsage {
+    /// `exports` (`EXPORT_STORAGE_DIR`) or `archive` (`ARCHIVE_SINK`).
+    pub name: String,
+    pub location: String,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeBlobStats {
+    pub user_id: i32,
+    pub classroom_id: i32,
+    pub npm: String,
+    pub bytes: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemStatsResponse {
+    pub started_at: DateTime<Utc>,
+    pub uptime_seconds: i64,
+    pub database: DatabaseStats,
+    /// Every table in the database, including ones this build has no entity for.
+    pub tables: Vec<TableRowCount>,
+    pub storage: Vec<StorageUsage>,
+    /// Bytes of student code stored across all classrooms.
+    pub total_code_bytes: i64,
+    pub largest_code: Vec<CodeBlobStats>,
+    /// Newest login attempts flagged as anomalous, across all classrooms.
+    pub flagged_logins: Vec<LoginEventResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutorMetrics {
+    pub requests: u64,
+    pub succeeded: u64,
+    /// Judge0 answered with an error or could not be reached.
+    pub failed: u64,
+    pub timed_out: u64,
+    /// Dropped before completion, typically because the client disconnected.
+    pub cancelled: u64,
+    pub in_flight: u64,
+    /// Summed duration of completed calls (succeeded, failed or timed out).
+    pub total_latency_ms: u64,
+    pub average_latency_ms: u64,
+}
+
+/// Retries of final submissions whose grading failed.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GradingRetryMetrics {
+    /// Retries started by the worker since the server started.
+    pub retried: u64,
+    /// Submissions graded successfully after at least one failed attempt.
+    pub recovered: u64,
+    pub dead_lettered: u64,
+    pub requeued: u64,
+    /// Ungraded final submissions waiting for another attempt.
+    pub pending_retries: u64,
+    /// Submissions currently in the dead letters.
+    pub dead_letters: u64,
+}
+
+/// Final submission whose grading was given up on.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetterResponse {
+    pub id: i32,
+    pub submission_id: i32,
+    pub classroom_id: i32,
+    pub user_id: i32,
+    pub npm: Option<String>,
+    pub attempts: i32,
+    pub last_error: String,
+    pub submitted_at: DateTime<Utc>,
+    pub dead_lettered_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsResponse {
+    pub started_at: DateTime<Utc>,
+    pub judge0: ExecutorMetrics,
+    /// Per-instance health and counters; `judge0` sums them up.
+    pub judge0_instances: Vec<ExecutorInstanceStatus>,
+    pub grading: GradingRetryMetrics,
+    pub submission_rate_limit: RateLimitStatus,
+    pub executor_queue: ExecutorQueueStatus,
+    pub database: DatabaseMetrics,
+    /// Error-budget signals seen since the server started, busiest first.
+    pub error_budget: Vec<ErrorBudgetStatus>,
+}
+
+/// Error source watched by the alerting budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSignalKind {
+    /// 5xx responses of one route.
+    RouteServerErrors,
+    /// Failed or timed-out Judge0 calls, including background grading.
+    Judge0Failures,
+    /// Requests that failed on a database error.
+    DatabaseErrors,
+}
+
+impl AlertSignalKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertSignalKind::RouteServerErrors => "route_server_errors",
+            AlertSignalKind::Judge0Failures => "judge0_failures",
+            AlertSignalKind::DatabaseErrors => "database_errors",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "route_server_errors" => Some(AlertSignalKind::RouteServerErrors),
+            "judge0_failures" => Some(AlertSignalKind::Judge0Failures),
+            "database_errors" => Some(AlertSignalKind::DatabaseErrors),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorBudgetStatus {
+    pub signal: AlertSignalKind,
+    /// Route for `route_server_errors`, e.g. `POST /api/classrooms/{id}/finish`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Events within the current window.
+    pub count: usize,
+    /// Count that fires an alert; `0` means the signal is not alerted on.
+    pub threshold: u32,
+    pub window_secs: u64,
+    pub last_alert_at: Option<DateTime<Utc>>,
+}
+
+/// Operations currently holding a Judge0 slot. Requests arriving while every slot is
+/// taken get `503` with `Retry-After`.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutorQueueStatus {
+    pub capacity: usize,
+    pub depth: usize,
+    /// Background grading waiting for a slot.
+    pub waiting: u64,
+    /// Requests turned away as busy since the server started.
+    pub rejected: u64,
+}
+
+/// Database statements since the server started, timed through sea-orm.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseMetrics {
+    pub statements: u64,
+    pub failed: u64,
+    /// Statements that took at least `slowThresholdMs`.
+    pub slow: u64,
+    /// `SQL_SLOW_QUERY_MS`; null when slow statements are not logged.
+    pub slow_threshold_ms: Option<u64>,
+    pub total_duration_ms: u64,
+    pub slowest_ms: u64,
+    /// Routes issuing the most slow statements, busiest first.
+    pub slow_routes: Vec<SlowQueryRoute>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowQueryRoute {
+    /// Normalized route, e.g. `POST /api/classrooms/{id}/finish`, or `background` for
+    /// work outside a request.
+    pub route: String,
+    pub slow: u64,
+}
+
+/// Submission budget per student, tightened while Judge0 latency is above target.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitStatus {
+    pub enabled: bool,
+    pub current_per_minute: u32,
+    pub min_per_minute: u32,
+    pub max_per_minute: u32,
+    pub latency_target_ms: u64,
+    /// Average Judge0 latency over the last control interval.
+    pub last_average_latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiChangeKind {
+    RemovedPath,
+    RemovedOperation,
+    RemovedParameter,
+    ParameterNowRequired,
+    RemovedSchema,
+    RemovedField,
+    FieldTypeChanged,
+    FieldNowRequired,
+    RemovedEnumValue,
+    AddedPath,
+    AddedOperation,
+    AddedSchema,
+    AddedField,
+}
+
+impl ApiChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiChangeKind::RemovedPath => "removed_path",
+            ApiChangeKind::RemovedOperation => "removed_operation",
+            ApiChangeKind::RemovedParameter => "removed_parameter",
+            ApiChangeKind::ParameterNowRequired => "parameter_now_required",
+            ApiChangeKind::RemovedSchema => "removed_schema",
+            ApiChangeKind::RemovedField => "removed_field",
+            ApiChangeKind::FieldTypeChanged => "field_type_changed",
+            ApiChangeKind::FieldNowRequired => "field_now_required",
+            ApiChangeKind::RemovedEnumValue => "removed_enum_value",
+            ApiChangeKind::AddedPath => "added_path",
+            ApiChangeKind::AddedOperation => "added_operation",
+            ApiChangeKind::AddedSchema => "added_schema",
+            ApiChangeKind::AddedField => "added_field",
+        }
+    }
+
+    /// Whether clients written against the baseline may stop working. Dropping a
+    /// request parameter is tolerated because servers ignore unknown ones.
+    pub fn is_breaking(&self) -> bool {
+        !matches!(
+            self,
+            ApiChangeKind::RemovedParameter
+                | ApiChangeKind::AddedPath
+                | ApiChangeKind::AddedOperation
+                | ApiChangeKind::AddedSchema
+                | ApiChangeKind::AddedField
+        )
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiChange {
+    pub kind: ApiChangeKind,
+    pub breaking: bool,
+    /// `METHOD /path`, `Schema.field` or a schema name.
+    pub location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Difference between the stored baseline document and the one served now.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenApiDiffResponse {
+    pub baseline_version: String,
+    pub current_version: String,
+    /// At least one change is breaking.
+    pub breaking: bool,
+    pub changes: Vec<ApiChange>,
+}
+
+/// Schema the running build expects next to what the database actually has.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaResponse {
+    pub server_version: String,
+    pub backend: String,
+    /// Every table exists with every column, and every schema and data migration is
+    /// applied.
+    pub up_to_date: bool,
+    pub tables: Vec<TableSchema>,
+    /// Schema migrations of the `migration` crate known to this build, in application
+    /// order.
+    pub schema_migrations: Vec<SchemaMigrationStatus>,
+    /// Schema migrations recorded in `seaql_migrations` that this build does not know.
+    /// The server refuses to start on such a database until it is upgraded again.
+    pub unknown_schema_migrations: Vec<String>,
+    /// Data migrations known to this build, in application order.
+    pub migrations: Vec<MigrationStatus>,
+    /// Versions recorded in `migrations_meta` that this build does not know, e.g.
+    /// after a downgrade.
+    pub unknown_migrations: Vec<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TableSchema {
+    pub table: String,
+    pub exists: bool,
+    pub columns: Vec<ColumnSchema>,
+    /// Columns of the entity the table lacks.
+    pub missing_columns: Vec<String>,
+    /// Columns of the table the entity does not know, e.g. left by a newer build.
+    pub extra_columns: Vec<String>,
+    pub indexes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnSchema {
+    pub name: String,
+    /// Type declared by the entity.
+    pub column_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+    /// Type declared in the database; `None` when the column is missing.
+    pub database_type: Option<String>,
+    pub database_nullable: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaMigrationStatus {
+    pub name: String,
+    /// `None` while the migration is pending.
+    pub applied_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationStatus {
+    pub version: i32,
+    pub name: String,
+    /// `None` while the migration is pending.
+    pub applied_at: Option<DateTime<Utc>>,
+}
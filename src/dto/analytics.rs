@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    entities::{daily_stat, language_usage},
+    services::daily_stats::NO_CLASSROOM,
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct DailyStatsParams {
+    /// First day, inclusive; defaults to 29 days before `to`.
+    pub from: Option<NaiveDate>,
+    /// Last day, inclusive; defaults to today (UTC).
+    pub to: Option<NaiveDate>,
+    /// Only rows of this classroom.
+    pub classroom_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyStatsEntry {
+    pub day: NaiveDate,
+    /// Absent for activity not tied to a classroom, such as admin logins.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classroom_id: Option<i32>,
+    pub logins: i64,
+    pub submissions: i64,
+    pub final_submissions: i64,
+    pub active_users: i64,
+    /// Submission count per Judge0 status; ungraded runs count as `Pending`.
+    pub verdicts: BTreeMap<String, i64>,
+    /// When the submission counters were last recomputed; absent if only logins
+    /// were recorded so far.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rolled_up_at: Option<DateTime<Utc>>,
+}
+
+impl From<daily_stat::Model> for DailyStatsEntry {
+    fn from(model: daily_stat::Model) -> Self {
+        Self {
+            day: model.day,
+            classroom_id: (model.classroom_id != NO_CLASSROOM).then_some(model.classroom_id),
+            logins: model.logins,
+            submissions: model.submissions,
+            final_submissions: model.final_submissions,
+            active_users: model.active_users,
+            verdicts: serde_json::from_str(&model.verdicts).unwrap_or_default(),
+            rolled_up_at: model.rolled_up_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyStatsResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// One row per day and classroom with any activity, by day then classroom.
+    pub rows: Vec<DailyStatsEntry>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct LanguageStatsParams {
+    /// First day, inclusive; defaults to 29 days before `to`.
+    pub from: Option<NaiveDate>,
+    /// Last day, inclusive; defaults to today (UTC).
+    pub to: Option<NaiveDate>,
+    /// Only usage in this classroom.
+    pub classroom_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageUsageEntry {
+    pub day: NaiveDate,
+    /// Absent for runs not tied to a classroom.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classroom_id: Option<i32>,
+    pub language_id: i32,
+    /// Playground and practice executions.
+    pub runs: i64,
+    /// Stored submissions; recomputed by the daily rollup.
+    pub submissions: i64,
+}
+
+impl From<language_usage::Model> for LanguageUsageEntry {
+    fn from(model: language_usage::Model) -> Self {
+        Self {
+            day: model.day,
+            classroom_id: (model.classroom_id != NO_CLASSROOM).then_some(model.classroom_id),
+            language_id: model.language_id,
+            runs: model.runs,
+            submissions: model.submissions,
+        }
+    }
+}
+
+/// Use of one language over the requested range.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageUsageSummary {
+    pub language_id: i32,
+    /// Name reported by Judge0; absent when the executor no longer offers the language
+    /// or cannot be reached.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub runs: i64,
+    pub submissions: i64,
+    /// Classrooms the language was used in.
+    pub classroom_ids: Vec<i32>,
+    /// Last day the language was used at all, inside the range or not; absent if it
+    /// never was.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageStatsResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Every language used in the range or offered by Judge0, most used first; those
+    /// with no runs or submissions are candidates for pruning.
+    pub languages: Vec<LanguageUsageSummary>,
+    /// One row per day, classroom and language with any use.
+    pub rows: Vec<LanguageUsageEntry>,
+}
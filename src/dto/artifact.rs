@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// `metadata.json` of a submission artifact bundle.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionArtifactMetadata {
+    pub submission_id: i32,
+    pub classroom_id: i32,
+    pub user_id: i32,
+    pub npm: String,
+    pub name: String,
+    pub task_id: Option<i32>,
+    pub language_id: i32,
+    /// Name of the source file in the bundle.
+    pub source_file: String,
+    pub is_final: bool,
+    pub auto_submitted: bool,
+    pub late: bool,
+    /// Judge0 status of the run the grade is based on.
+    pub status: Option<String>,
+    pub exit_code: Option<i32>,
+    pub exit_signal: Option<i32>,
+    pub time: Option<String>,
+    pub memory: Option<i64>,
+    pub passed_cases: Option<i32>,
+    pub total_cases: Option<i32>,
+    pub score: Option<i32>,
+    pub max_score: Option<i32>,
+    pub grading_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub graded_at: Option<DateTime<Utc>>,
+}
@@ -1,14 +1,21 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 use super::{account::AccountResponse, classroom::LoginClassroomInfo};
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginRequest {
+    /// May be left empty when `joinCode` is given.
+    #[serde(default)]
     pub npm: String,
     #[serde(default)]
     pub as_admin: bool,
+    /// One-time join code from a printed exam ticket; it signs in as the student on
+    /// the ticket and is used up.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub join_code: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -18,6 +25,28 @@ pub struct LoginResponse {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub classroom: Option<LoginClassroomInfo>,
     pub is_new: bool,
+    /// Bearer token for authenticated endpoints.
+    pub token: String,
+    pub token_expires_at: DateTime<Utc>,
+    pub permissions: Permissions,
+}
+
+/// What the account may do, so clients can hide controls it cannot use.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Permissions {
+    pub manage_classrooms: bool,
+    pub manage_accounts: bool,
+    pub grade: bool,
+    pub view_code: bool,
+    pub submit: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentAccountResponse {
+    pub account: AccountResponse,
+    pub permissions: Permissions,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -25,3 +54,32 @@ pub struct LoginResponse {
 pub struct AdminExistsResponse {
     pub exists: bool,
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateEmailRequest {
+    #[schema(example = "mahasiswa@example.ac.id")]
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VerifyEmailParams {
+    /// Token from the emailed verification link.
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverNpmRequest {
+    #[schema(example = "mahasiswa@example.ac.id")]
+    pub email: String,
+}
+
+/// Same answer whether or not the address is known, so it cannot be used to probe
+/// for registered emails.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverNpmResponse {
+    pub message: String,
+}
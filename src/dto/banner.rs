@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::entities::banner;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BannerLevel {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl BannerLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BannerLevel::Info => "info",
+            BannerLevel::Warning => "warning",
+            BannerLevel::Critical => "critical",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "info" => Some(BannerLevel::Info),
+            "warning" => Some(BannerLevel::Warning),
+            "critical" => Some(BannerLevel::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// Replaces the banner. Omitting both instants shows it until it is cleared.
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBannerRequest {
+    pub message: String,
+    #[serde(default = "default_level")]
+    pub level: BannerLevel,
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+fn default_level() -> BannerLevel {
+    BannerLevel::Info
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BannerResponse {
+    pub message: String,
+    pub level: BannerLevel,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<banner::Model> for BannerResponse {
+    fn from(model: banner::Model) -> Self {
+        Self {
+            message: model.message,
+            level: BannerLevel::from_str(&model.level).unwrap_or(BannerLevel::Info),
+            starts_at: model.starts_at,
+            ends_at: model.ends_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{
+    account::AccountResponse, banner::BannerResponse, classroom::LoginClassroomInfo,
+    classroom::TaskResponse, exam::ExamClockResponse, message::MessageEvent,
+};
+use crate::entities::{classroom, user};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct BootstrapParams {
+    /// Membership to load; defaults to a running exam, then any active membership.
+    pub classroom_id: Option<i32>,
+}
+
+/// Classroom roster entry of the signed-in account.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MembershipInfo {
+    pub classroom_id: i32,
+    pub classroom_name: String,
+    pub user_id: i32,
+    pub active: bool,
+    pub is_exam: bool,
+}
+
+impl MembershipInfo {
+    pub fn from_models(user: &user::Model, classroom: &classroom::Model) -> Self {
+        Self {
+            classroom_id: classroom.id,
+            classroom_name: classroom.name.clone(),
+            user_id: user.id,
+            active: user.active,
+            is_exam: classroom.is_exam,
+        }
+    }
+}
+
+/// Saved code of a student; also the `current` copy returned with a `stale_code`
+/// conflict.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnCodeInfo {
+    pub code: String,
+    pub language_id: Option<i32>,
+    /// Version of the saved code; send it back as `client_updated_at` when running
+    /// code edited from this copy.
+    pub updated_at: DateTime<Utc>,
+    /// Revision of the saved code; send it back as `base_seq` when saving code
+    /// edited from this copy.
+    pub seq: i64,
+}
+
+impl From<user::Model> for OwnCodeInfo {
+    fn from(model: user::Model) -> Self {
+        Self {
+            updated_at: model.code_version(),
+            seq: model.code_seq,
+            code: model.code.into_inner(),
+            language_id: model.language_id,
+        }
+    }
+}
+
+/// Cooldown between graded submissions (practice runs and hand-ins) of the student.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionCooldownInfo {
+    pub cooldown_secs: i32,
+    /// When the next graded submission is accepted; null when it is now.
+    pub available_at: Option<DateTime<Utc>>,
+    pub remaining_secs: u64,
+}
+
+/// Everything the frontend needs for its first render after sign-in.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BootstrapResponse {
+    pub account: AccountResponse,
+    pub memberships: Vec<MembershipInfo>,
+    /// Selected membership's classroom; absent when the account has none.
+    pub classroom: Option<LoginClassroomInfo>,
+    pub exam: Option<ExamClockResponse>,
+    pub tasks: Vec<TaskResponse>,
+    pub own_code: Option<OwnCodeInfo>,
+    /// Present when the selected classroom sets a submission cooldown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submission_cooldown: Option<SubmissionCooldownInfo>,
+    pub unread_messages: Vec<MessageEvent>,
+    /// Announcement currently on display, if any.
+    pub banner: Option<BannerResponse>,
+    pub server_time: DateTime<Utc>,
+}
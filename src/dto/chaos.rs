@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// How often faults are injected. Rates are fractions of calls from 0 to 1; all zero
+/// turns injection off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChaosConfig {
+    /// Judge0 calls left hanging until `JUDGE0_TIMEOUT_SECS` runs out.
+    #[serde(default)]
+    pub judge0_timeout_rate: f64,
+    /// API requests answered with the 500 a failed database query gives.
+    #[serde(default)]
+    pub db_error_rate: f64,
+    /// API requests held back by `slowResponseMs` before they are handled.
+    #[serde(default)]
+    pub slow_response_rate: f64,
+    #[serde(default)]
+    pub slow_response_ms: u64,
+}
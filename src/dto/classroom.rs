@@ -2,9 +2,92 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::entities::{classroom, user};
+use axum::Json;
 
-use super::user::{CreateUserRequest, UserResponse};
+use crate::{
+    entities::{classroom, task, user},
+    error::AppError,
+    services::{exam_clock::ExamClock, field_limits::FieldLimits},
+};
+
+use super::{
+    publish::ExamGate,
+    schedule::ScheduleConflict,
+    test_case::TestMode,
+    user::{CreateUserRequest, UserResponse},
+    validated::LimitedFields,
+    version::{Downgrade, VersionedBody, VersionedJson},
+};
+
+/// Lifecycle of a classroom: prepared as a draft, published in one step, and
+/// archived by the semester rollover.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClassroomStatus {
+    /// Hidden from students until published.
+    Draft,
+    Published,
+    /// Closed with its term; kept for records but hidden from students.
+    Archived,
+}
+
+impl ClassroomStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClassroomStatus::Draft => "draft",
+            ClassroomStatus::Published => "published",
+            ClassroomStatus::Archived => "archived",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "draft" | "DRAFT" | "Draft" => Some(ClassroomStatus::Draft),
+            "published" | "PUBLISHED" | "Published" => Some(ClassroomStatus::Published),
+            "archived" | "ARCHIVED" | "Archived" => Some(ClassroomStatus::Archived),
+            _ => None,
+        }
+    }
+}
+
+/// What finishing an exam does to the student.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PostFinishPolicy {
+    /// The student is deactivated and cannot log in until reactivated.
+    Deactivate,
+    /// The student stays active and may keep submitting; each finish is graded.
+    KeepActive,
+    /// The student stays logged in but practice runs and hand-ins are refused until
+    /// reactivated.
+    LockSubmissions,
+}
+
+impl PostFinishPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostFinishPolicy::Deactivate => "deactivate",
+            PostFinishPolicy::KeepActive => "keep_active",
+            PostFinishPolicy::LockSubmissions => "lock_submissions",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "deactivate" | "DEACTIVATE" | "Deactivate" => Some(PostFinishPolicy::Deactivate),
+            "keep_active" | "KEEP_ACTIVE" | "KeepActive" => Some(PostFinishPolicy::KeepActive),
+            "lock_submissions" | "LOCK_SUBMISSIONS" | "LockSubmissions" => {
+                Some(PostFinishPolicy::LockSubmissions)
+            }
+            _ => None,
+        }
+    }
+
+    /// The classroom's policy; unknown values fall back to `deactivate`.
+    pub fn of(classroom: &classroom::Model) -> Self {
+        Self::from_str(&classroom.post_finish_policy).unwrap_or(PostFinishPolicy::Deactivate)
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -19,7 +102,7 @@ pub struct CreateClassroomRequest {
     #[serde(default)]
     pub tasks: Vec<String>,
     #[serde(default)]
-pub is_exam: Option<bool>,
+    pub is_exam: Option<bool>,
     #[serde(default)]
     pub test_code: Option<String>,
     #[serde(default)]
@@ -28,6 +111,35 @@ pub is_exam: Option<bool>,
     pub exam_end: Option<DateTime<Utc>>,
     #[serde(default)]
     pub presetup_code: Option<String>,
+    #[serde(default)]
+    pub test_mode: Option<TestMode>,
+    #[serde(default)]
+    pub test_sample_size: Option<i32>,
+    /// Full test-case feedback on every submission; defaults to on except for exams.
+    #[serde(default)]
+    pub practice_mode: Option<bool>,
+    /// `draft` to prepare the classroom before publishing it; defaults to `published`.
+    #[serde(default)]
+    pub status: Option<ClassroomStatus>,
+    #[serde(default)]
+    pub term: Option<String>,
+    /// Stdout/stderr cap per run in KB (at most 4096); `0` or absent uses the server default.
+    #[serde(default)]
+    pub output_limit_kb: Option<i32>,
+    /// Longest student code accepted, in characters; `0` or absent uses the server default.
+    #[serde(default)]
+    pub max_code_chars: Option<i32>,
+    /// Longest task description, in characters; `0` or absent uses the server default.
+    #[serde(default)]
+    pub max_description_chars: Option<i32>,
+    /// Minimum seconds between graded submissions of a student (at most 86400); `0`
+    /// or absent disables it.
+    #[serde(default)]
+    pub submission_cooldown_secs: Option<i32>,
+    /// What finishing the exam does to the student; absent keeps `deactivate` on
+    /// create and the current policy on update.
+    #[serde(default)]
+    pub post_finish_policy: Option<PostFinishPolicy>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -37,6 +149,8 @@ pub struct UpdateClassroomRequest {
     pub programming_language: Option<String>,
     #[serde(default)]
     pub lock_language: Option<bool>,
+    /// Roster matched by NPM: listed students keep their code and submissions, new NPMs are
+    /// added, and students left out are removed.
     #[serde(default)]
     pub users: Option<Vec<CreateUserRequest>>,
     pub tasks: Option<Vec<String>>,
@@ -50,6 +164,32 @@ pub struct UpdateClassroomRequest {
     pub exam_end: Option<DateTime<Utc>>,
     #[serde(default)]
     pub presetup_code: Option<String>,
+    #[serde(default)]
+    pub test_mode: Option<TestMode>,
+    #[serde(default)]
+    pub test_sample_size: Option<i32>,
+    /// Full test-case feedback on every submission; defaults to on except for exams.
+    #[serde(default)]
+    pub practice_mode: Option<bool>,
+    #[serde(default)]
+    pub term: Option<String>,
+    /// Stdout/stderr cap per run in KB (at most 4096); `0` or absent uses the server default.
+    #[serde(default)]
+    pub output_limit_kb: Option<i32>,
+    /// Longest student code accepted, in characters; `0` or absent uses the server default.
+    #[serde(default)]
+    pub max_code_chars: Option<i32>,
+    /// Longest task description, in characters; `0` or absent uses the server default.
+    #[serde(default)]
+    pub max_description_chars: Option<i32>,
+    /// Minimum seconds between graded submissions of a student (at most 86400); `0`
+    /// or absent disables it.
+    #[serde(default)]
+    pub submission_cooldown_secs: Option<i32>,
+    /// What finishing the exam does to the student; absent keeps `deactivate` on
+    /// create and the current policy on update.
+    #[serde(default)]
+    pub post_finish_policy: Option<PostFinishPolicy>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -65,7 +205,19 @@ pub struct LoginClassroomInfo {
     pub exam_start: Option<DateTime<Utc>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub exam_end: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub exam_paused: bool,
+    /// Server instant the exam fields were computed at; set for exam classrooms.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_time: Option<DateTime<Utc>>,
     pub presetup_code: String,
+    /// Whether submissions answer with test-case results or only a receipt.
+    pub practice_mode: bool,
+    /// Minimum seconds between graded submissions; absent when off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submission_cooldown_secs: Option<i32>,
+    /// What finishing the exam does to the student.
+    pub post_finish_policy: PostFinishPolicy,
 }
 
 impl LoginClassroomInfo {
@@ -86,9 +238,33 @@ impl LoginClassroomInfo {
             } else {
                 None
             },
+            exam_paused: false,
+            server_time: None,
             presetup_code: classroom.presetup_code,
+            practice_mode: classroom.practice_mode,
+            submission_cooldown_secs: classroom.submission_cooldown_secs,
+            post_finish_policy: PostFinishPolicy::from_str(&classroom.post_finish_policy)
+                .unwrap_or(PostFinishPolicy::Deactivate),
         }
     }
+
+    /// Replaces the classroom starter code with the student's own override, if any.
+    pub fn with_presetup_override(mut self, presetup_override: Option<String>) -> Self {
+        if let Some(presetup_code) = presetup_override {
+            self.presetup_code = presetup_code;
+        }
+        self
+    }
+
+    /// Reports the pause-adjusted end so client countdowns skip paused time.
+    pub fn with_clock(mut self, clock: &ExamClock, now: DateTime<Utc>) -> Self {
+        if self.is_exam {
+            self.exam_end = clock.effective_end(now);
+            self.exam_paused = clock.is_paused();
+            self.server_time = Some(now);
+        }
+        self
+    }
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -107,30 +283,495 @@ pub struct ClassroomResponse {
     pub exam_start: Option<DateTime<Utc>>,
     pub exam_end: Option<DateTime<Utc>>,
     pub presetup_code: String,
+    pub test_mode: TestMode,
+    pub test_sample_size: i32,
+    pub practice_mode: bool,
+    pub status: ClassroomStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub term: Option<String>,
+    /// Stdout/stderr cap per run in KB; null uses the server default.
+    pub output_limit_kb: Option<i32>,
+    /// Longest student code in characters; null uses the server default.
+    pub max_code_chars: Option<i32>,
+    /// Longest task description in characters; null uses the server default.
+    pub max_description_chars: Option<i32>,
+    /// Minimum seconds between graded submissions of a student; null when off.
+    pub submission_cooldown_secs: Option<i32>,
+    /// What finishing the exam does to the student.
+    pub post_finish_policy: PostFinishPolicy,
+    /// Schedule conflicts the saved exam window is part of; only set by an update.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedule_warnings: Vec<ScheduleConflict>,
+    /// Judge0 language id `programmingLanguage` maps to; null when unknown.
+    pub resolved_language_id: Option<i32>,
+    /// Set while the executor offers no language matching `programmingLanguage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_missing_since: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exam_gate: Option<ExamGate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Task as written by v2 clients; v1 clients send plain description strings.
+/// `starterCode`, `expectedOutput` and `weight` keep their stored value when left out.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInput {
+    /// Existing task this entry updates; leave out to add a new task.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// Judge0 language id overriding the classroom language for this task.
+    #[serde(default)]
+    pub language_id: Option<i32>,
+    /// Deadline of this task; submissions for it afterwards are tagged late.
+    #[serde(default)]
+    pub due_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub starter_code: Option<String>,
+    #[serde(default)]
+    pub expected_output: Option<String>,
+    /// Multiplier on the points of the task's test cases; defaults to 1.
+    #[serde(default)]
+    pub weight: Option<i32>,
+}
+
+impl From<String> for TaskInput {
+    fn from(description: String) -> Self {
+        Self {
+            id: None,
+            title: String::new(),
+            description,
+            language_id: None,
+            due_at: None,
+            starter_code: None,
+            expected_output: None,
+            weight: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskResponse {
+    pub id: i32,
+    pub position: i32,
+    pub title: String,
+    pub description: String,
+    /// Judge0 language id of the task when it overrides the classroom language, so the
+    /// editor can switch syntax highlighting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_id: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<DateTime<Utc>>,
+    /// Code the editor opens with for this task.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starter_code: Option<String>,
+    pub weight: i32,
+}
+
+impl From<task::Model> for TaskResponse {
+    fn from(model: task::Model) -> Self {
+        Self {
+            id: model.id,
+            position: model.position,
+            title: model.title,
+            description: model.description,
+            language_id: model.language_id,
+            due_at: model.due_at,
+            starter_code: model.starter_code,
+            weight: model.weight,
+        }
+    }
+}
+
+impl TaskResponse {
+    fn legacy_text(self) -> String {
+        if self.description.trim().is_empty() {
+            self.title
+        } else {
+            self.description
+        }
+    }
+}
+
+/// A task sheet rendered from its markdown description.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedTaskResponse {
+    pub classroom_id: i32,
+    pub task_id: i32,
+    pub title: String,
+    /// Sanitized HTML, safe to insert into a page as is.
+    pub html: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// v2 classroom shape: identical to v1 except that tasks are structured objects.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassroomResponseV2 {
+    pub id: i32,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub programming_language: Option<String>,
+    pub language_locked: bool,
+    pub users: Vec<UserResponse>,
+    #[serde(default)]
+    pub tasks: Vec<TaskResponse>,
+    pub is_exam: bool,
+    pub test_code: String,
+    pub exam_start: Option<DateTime<Utc>>,
+    pub exam_end: Option<DateTime<Utc>>,
+    pub presetup_code: String,
+    pub test_mode: TestMode,
+    pub test_sample_size: i32,
+    pub practice_mode: bool,
+    pub status: ClassroomStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub term: Option<String>,
+    /// Stdout/stderr cap per run in KB; null uses the server default.
+    pub output_limit_kb: Option<i32>,
+    /// Longest student code in characters; null uses the server default.
+    pub max_code_chars: Option<i32>,
+    /// Longest task description in characters; null uses the server default.
+    pub max_description_chars: Option<i32>,
+    /// Minimum seconds between graded submissions of a student; null when off.
+    pub submission_cooldown_secs: Option<i32>,
+    /// What finishing the exam does to the student.
+    pub post_finish_policy: PostFinishPolicy,
+    /// Schedule conflicts the saved exam window is part of; only set by an update.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedule_warnings: Vec<ScheduleConflict>,
+    /// Judge0 language id `programmingLanguage` maps to; null when unknown.
+    pub resolved_language_id: Option<i32>,
+    /// Set while the executor offers no language matching `programmingLanguage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_missing_since: Option<DateTime<Utc>>,
+    /// Present while the exam is held back by a failed executor smoke test.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exam_gate: Option<ExamGate>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-impl ClassroomResponse {
-    pub fn from_models(classroom: classroom::Model, users: Vec<user::Model>) -> Self {
+impl ClassroomResponseV2 {
+    pub fn from_models(
+        classroom: classroom::Model,
+        users: Vec<user::Model>,
+        tasks: Vec<task::Model>,
+    ) -> Self {
         Self {
             id: classroom.id,
             name: classroom.name,
             programming_language: normalize_language(&classroom.programming_language),
             language_locked: classroom.language_locked,
             users: users.into_iter().map(UserResponse::from).collect(),
-            tasks: deserialize_tasks(&classroom.tasks),
+            tasks: tasks.into_iter().map(TaskResponse::from).collect(),
             is_exam: classroom.is_exam,
             test_code: classroom.test_code,
             exam_start: classroom.exam_start,
             exam_end: classroom.exam_end,
             presetup_code: classroom.presetup_code,
+            test_mode: TestMode::from_str(&classroom.test_mode).unwrap_or(TestMode::Full),
+            test_sample_size: classroom.test_sample_size,
+            practice_mode: classroom.practice_mode,
+            status: ClassroomStatus::from_str(&classroom.status)
+                .unwrap_or(ClassroomStatus::Published),
+            published_at: classroom.published_at,
+            term: classroom.term,
+            output_limit_kb: classroom.output_limit_kb,
+            max_code_chars: classroom.max_code_chars,
+            max_description_chars: classroom.max_description_chars,
+            submission_cooldown_secs: classroom.submission_cooldown_secs,
+            post_finish_policy: PostFinishPolicy::from_str(&classroom.post_finish_policy)
+                .unwrap_or(PostFinishPolicy::Deactivate),
+            schedule_warnings: Vec::new(),
+            resolved_language_id: classroom.resolved_language_id,
+            language_missing_since: classroom.language_missing_since,
+            exam_gate: classroom.exam_gated_at.map(|gated_at| ExamGate {
+                gated_at,
+                reason: classroom.exam_gate_reason,
+            }),
             created_at: classroom.created_at,
             updated_at: classroom.updated_at,
         }
     }
 }
 
+impl Downgrade for ClassroomResponseV2 {
+    type V1 = ClassroomResponse;
+
+    fn downgrade(self) -> ClassroomResponse {
+        ClassroomResponse {
+            id: self.id,
+            name: self.name,
+            programming_language: self.programming_language,
+            language_locked: self.language_locked,
+            users: self.users,
+            tasks: self
+                .tasks
+                .into_iter()
+                .map(TaskResponse::legacy_text)
+                .collect(),
+            is_exam: self.is_exam,
+            test_code: self.test_code,
+            exam_start: self.exam_start,
+            exam_end: self.exam_end,
+            presetup_code: self.presetup_code,
+            test_mode: self.test_mode,
+            test_sample_size: self.test_sample_size,
+            practice_mode: self.practice_mode,
+            status: self.status,
+            published_at: self.published_at,
+            term: self.term,
+            output_limit_kb: self.output_limit_kb,
+            max_code_chars: self.max_code_chars,
+            max_description_chars: self.max_description_chars,
+            submission_cooldown_secs: self.submission_cooldown_secs,
+            post_finish_policy: self.post_finish_policy,
+            schedule_warnings: self.schedule_warnings,
+            resolved_language_id: self.resolved_language_id,
+            language_missing_since: self.language_missing_since,
+            exam_gate: self.exam_gate,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateClassroomRequestV2 {
+    pub name: String,
+    #[serde(default)]
+    pub programming_language: Option<String>,
+    #[serde(default)]
+    pub lock_language: Option<bool>,
+    #[serde(default)]
+    pub users: Vec<CreateUserRequest>,
+    #[serde(default)]
+    pub tasks: Vec<TaskInput>,
+    #[serde(default)]
+    pub is_exam: Option<bool>,
+    #[serde(default)]
+    pub test_code: Option<String>,
+    #[serde(default)]
+    pub exam_start: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub exam_end: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub presetup_code: Option<String>,
+    #[serde(default)]
+    pub test_mode: Option<TestMode>,
+    #[serde(default)]
+    pub test_sample_size: Option<i32>,
+    /// Full test-case feedback on every submission; defaults to on except for exams.
+    #[serde(default)]
+    pub practice_mode: Option<bool>,
+    /// `draft` to prepare the classroom before publishing it; defaults to `published`.
+    #[serde(default)]
+    pub status: Option<ClassroomStatus>,
+    #[serde(default)]
+    pub term: Option<String>,
+    /// Stdout/stderr cap per run in KB (at most 4096); `0` or absent uses the server default.
+    #[serde(default)]
+    pub output_limit_kb: Option<i32>,
+    /// Longest student code accepted, in characters; `0` or absent uses the server default.
+    #[serde(default)]
+    pub max_code_chars: Option<i32>,
+    /// Longest task description, in characters; `0` or absent uses the server default.
+    #[serde(default)]
+    pub max_description_chars: Option<i32>,
+    /// Minimum seconds between graded submissions of a student (at most 86400); `0`
+    /// or absent disables it.
+    #[serde(default)]
+    pub submission_cooldown_secs: Option<i32>,
+    /// What finishing the exam does to the student; absent keeps `deactivate` on
+    /// create and the current policy on update.
+    #[serde(default)]
+    pub post_finish_policy: Option<PostFinishPolicy>,
+}
+
+impl VersionedBody for CreateClassroomRequestV2 {
+    type V1 = CreateClassroomRequest;
+    type V2 = Self;
+
+    fn from_v1(body: CreateClassroomRequest) -> Self {
+        Self {
+            name: body.name,
+            programming_language: body.programming_language,
+            lock_language: body.lock_language,
+            users: body.users,
+            tasks: body.tasks.into_iter().map(TaskInput::from).collect(),
+            is_exam: body.is_exam,
+            test_code: body.test_code,
+            exam_start: body.exam_start,
+            exam_end: body.exam_end,
+            presetup_code: body.presetup_code,
+            test_mode: body.test_mode,
+            test_sample_size: body.test_sample_size,
+            practice_mode: body.practice_mode,
+            status: body.status,
+            term: body.term,
+            output_limit_kb: body.output_limit_kb,
+            max_code_chars: body.max_code_chars,
+            max_description_chars: body.max_description_chars,
+            submission_cooldown_secs: body.submission_cooldown_secs,
+            post_finish_policy: body.post_finish_policy,
+        }
+    }
+
+    fn from_v2(body: Self) -> Self {
+        body
+    }
+}
+
+impl LimitedFields for CreateClassroomRequestV2 {
+    type Body = VersionedJson<Self>;
+
+    fn own_limits(&self) -> (Option<i32>, Option<i32>) {
+        (self.max_code_chars, self.max_description_chars)
+    }
+
+    fn check(&self, limits: &FieldLimits) -> Result<(), AppError> {
+        check_classroom_fields(
+            limits,
+            self.presetup_code.as_deref(),
+            Some(&self.users),
+            Some(&self.tasks),
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateClassroomRequestV2 {
+    pub name: Option<String>,
+    pub programming_language: Option<String>,
+    #[serde(default)]
+    pub lock_language: Option<bool>,
+    /// Roster matched by NPM: listed students keep their code and submissions, new NPMs are
+    /// added, and students left out are removed.
+    #[serde(default)]
+    pub users: Option<Vec<CreateUserRequest>>,
+    pub tasks: Option<Vec<TaskInput>>,
+    #[serde(default)]
+    pub is_exam: Option<bool>,
+    #[serde(default)]
+    pub test_code: Option<String>,
+    #[serde(default)]
+    pub exam_start: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub exam_end: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub presetup_code: Option<String>,
+    #[serde(default)]
+    pub test_mode: Option<TestMode>,
+    #[serde(default)]
+    pub test_sample_size: Option<i32>,
+    /// Full test-case feedback on every submission; defaults to on except for exams.
+    #[serde(default)]
+    pub practice_mode: Option<bool>,
+    #[serde(default)]
+    pub term: Option<String>,
+    /// Stdout/stderr cap per run in KB (at most 4096); `0` or absent uses the server default.
+    #[serde(default)]
+    pub output_limit_kb: Option<i32>,
+    /// Longest student code accepted, in characters; `0` or absent uses the server default.
+    #[serde(default)]
+    pub max_code_chars: Option<i32>,
+    /// Longest task description, in characters; `0` or absent uses the server default.
+    #[serde(default)]
+    pub max_description_chars: Option<i32>,
+    /// Minimum seconds between graded submissions of a student (at most 86400); `0`
+    /// or absent disables it.
+    #[serde(default)]
+    pub submission_cooldown_secs: Option<i32>,
+    /// What finishing the exam does to the student; absent keeps `deactivate` on
+    /// create and the current policy on update.
+    #[serde(default)]
+    pub post_finish_policy: Option<PostFinishPolicy>,
+}
+
+impl VersionedBody for UpdateClassroomRequestV2 {
+    type V1 = UpdateClassroomRequest;
+    type V2 = Self;
+
+    fn from_v1(body: UpdateClassroomRequest) -> Self {
+        Self {
+            name: body.name,
+            programming_language: body.programming_language,
+            lock_language: body.lock_language,
+            users: body.users,
+            tasks: body
+                .tasks
+                .map(|tasks| tasks.into_iter().map(TaskInput::from).collect()),
+            is_exam: body.is_exam,
+            test_code: body.test_code,
+            exam_start: body.exam_start,
+            exam_end: body.exam_end,
+            presetup_code: body.presetup_code,
+            test_mode: body.test_mode,
+            test_sample_size: body.test_sample_size,
+            practice_mode: body.practice_mode,
+            term: body.term,
+            output_limit_kb: body.output_limit_kb,
+            max_code_chars: body.max_code_chars,
+            max_description_chars: body.max_description_chars,
+            submission_cooldown_secs: body.submission_cooldown_secs,
+            post_finish_policy: body.post_finish_policy,
+        }
+    }
+
+    fn from_v2(body: Self) -> Self {
+        body
+    }
+}
+
+impl LimitedFields for UpdateClassroomRequestV2 {
+    type Body = VersionedJson<Self>;
+
+    fn own_limits(&self) -> (Option<i32>, Option<i32>) {
+        (self.max_code_chars, self.max_description_chars)
+    }
+
+    fn check(&self, limits: &FieldLimits) -> Result<(), AppError> {
+        check_classroom_fields(
+            limits,
+            self.presetup_code.as_deref(),
+            self.users.as_deref(),
+            self.tasks.as_deref(),
+        )
+    }
+}
+
+/// Starter code and roster code count as code; task descriptions as descriptions.
+fn check_classroom_fields(
+    limits: &FieldLimits,
+    presetup_code: Option<&str>,
+    users: Option<&[CreateUserRequest]>,
+    tasks: Option<&[TaskInput]>,
+) -> Result<(), AppError> {
+    if let Some(presetup_code) = presetup_code {
+        limits.check_code("presetupCode", presetup_code)?;
+    }
+    for (index, user) in users.unwrap_or_default().iter().enumerate() {
+        limits.check_code(format!("users[{index}].code"), &user.code)?;
+    }
+    for (index, task) in tasks.unwrap_or_default().iter().enumerate() {
+        limits.check_description(format!("tasks[{index}].description"), &task.description)?;
+        if let Some(starter_code) = &task.starter_code {
+            limits.check_code(format!("tasks[{index}].starterCode"), starter_code)?;
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn normalize_language(value: &str) -> Option<String> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -140,20 +781,23 @@ pub(crate) fn normalize_language(value: &str) -> Option<String> {
     }
 }
 
-pub(crate) fn serialize_tasks(tasks: &[String]) -> String {
-    serde_json::to_string(tasks).unwrap_or_else(|_| "[]".to_string())
-}
-
-pub(crate) fn deserialize_tasks(value: &str) -> Vec<String> {
-    serde_json::from_str(value).unwrap_or_default()
-}
-
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FinishExamRequest {
     pub npm: String,
     pub code: String,
     pub language_id: Option<i32>,
+    /// Task the hand-in is for; decides which due date tags it late.
+    #[serde(default)]
+    pub task_id: Option<i32>,
+}
+
+impl LimitedFields for FinishExamRequest {
+    type Body = Json<Self>;
+
+    fn check(&self, limits: &FieldLimits) -> Result<(), AppError> {
+        limits.check_code("code", &self.code)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
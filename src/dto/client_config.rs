@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ClientConfigParams {
+    /// Report the character limits of this classroom instead of the server defaults.
+    #[serde(default)]
+    pub classroom_id: Option<i32>,
+}
+
+/// Deployment settings a single frontend build reads at startup instead of baking
+/// them in.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientConfigResponse {
+    /// Newest wire format revision the server speaks, for `Accept-Version`.
+    pub api_version: String,
+    pub features: ClientFeatures,
+    /// Languages offered by the executor; empty while Judge0 cannot be reached.
+    pub languages: Vec<ExecutorLanguage>,
+    /// Language used when a run names none.
+    pub default_language_id: i32,
+    pub limits: ClientLimits,
+    pub branding: ClientBranding,
+}
+
+/// Optional features that are switched on for this deployment.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientFeatures {
+    /// Passkey registration and login.
+    pub passkeys: bool,
+    /// Account email, verification and NPM recovery.
+    pub account_email: bool,
+    /// Unknown NPMs wait for an admin's approval after logging in.
+    pub account_approval: bool,
+    /// Share of frontend error reports without a request id that are stored; clients
+    /// may skip sending the rest.
+    pub client_error_sample_rate: f64,
+}
+
+/// A language as listed by Judge0's `/languages`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutorLanguage {
+    pub id: i32,
+    pub name: String,
+}
+
+/// Size caps the server enforces, so clients can check input before sending it.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientLimits {
+    pub max_stdin_bytes: usize,
+    pub max_command_line_arguments_bytes: usize,
+    pub max_client_error_report_bytes: usize,
+    /// Default stdout/stderr cap per run; classrooms may set their own.
+    pub output_limit_kb: u32,
+    pub max_seat_grid_size: usize,
+    /// Longest student code accepted, in characters.
+    pub max_code_chars: usize,
+    /// Longest task description accepted, in characters.
+    pub max_description_chars: usize,
+}
+
+/// Names and links shown by the frontend, set through `CLIENT_BRAND_*`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientBranding {
+    pub name: String,
+    pub logo_url: Option<String>,
+    pub support_contact: Option<String>,
+}
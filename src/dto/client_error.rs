@@ -0,0 +1,151 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::entities::client_error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientErrorKind {
+    /// Uncaught JS exception or rejected promise.
+    Exception,
+    /// API call that failed or returned an error status.
+    Request,
+}
+
+impl ClientErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClientErrorKind::Exception => "exception",
+            ClientErrorKind::Request => "request",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "exception" => Some(ClientErrorKind::Exception),
+            "request" => Some(ClientErrorKind::Request),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorSource {
+    Client,
+    Server,
+}
+
+impl ErrorSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorSource::Client => "client",
+            ErrorSource::Server => "server",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "client" => Some(ErrorSource::Client),
+            "server" => Some(ErrorSource::Server),
+            _ => None,
+        }
+    }
+}
+
+/// Failure seen by the frontend. Long fields are truncated rather than rejected.
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientErrorReport {
+    pub kind: ClientErrorKind,
+    pub message: String,
+    pub stack: Option<String>,
+    /// Page URL for exceptions, API URL for failed requests.
+    pub url: Option<String>,
+    pub method: Option<String>,
+    pub status: Option<i32>,
+    /// `X-Request-Id` response header of the failed request.
+    pub request_id: Option<String>,
+    pub classroom_id: Option<i32>,
+    pub npm: Option<String>,
+    /// Any extra JSON; dropped when its encoding exceeds the size cap.
+    #[schema(value_type = Option<Object>)]
+    pub context: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientErrorAck {
+    /// `false` when the report was sampled out.
+    pub stored: bool,
+    /// Fraction of reports without a request id that are kept; clients may sample
+    /// at this rate themselves to save the round trip.
+    pub sample_rate: f64,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ClientErrorListParams {
+    pub request_id: Option<String>,
+    pub classroom_id: Option<i32>,
+    pub npm: Option<String>,
+    pub source: Option<ErrorSource>,
+    /// Only errors recorded at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// At most this many of the newest errors; defaults to 100.
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientErrorResponse {
+    pub id: i32,
+    pub source: String,
+    pub kind: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classroom_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub npm: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub context: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<client_error::Model> for ClientErrorResponse {
+    fn from(model: client_error::Model) -> Self {
+        Self {
+            id: model.id,
+            source: model.source,
+            kind: model.kind,
+            message: model.message,
+            stack: model.stack,
+            url: model.url,
+            method: model.method,
+            status: model.status,
+            request_id: model.request_id,
+            classroom_id: model.classroom_id,
+            npm: model.npm,
+            user_agent: model.user_agent,
+            context: model
+                .context
+                .and_then(|context| serde_json::from_str(&context).ok()),
+            created_at: model.created_at,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Rows a classroom deletion removes along with the classroom.
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassroomRowCounts {
+    pub users: u64,
+    pub tasks: u64,
+    pub test_cases: u64,
+    pub submissions: u64,
+    pub messages: u64,
+    pub events: u64,
+    pub integrity_events: u64,
+    pub export_jobs: u64,
+}
+
+impl std::fmt::Display for ClassroomRowCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "users={}, tasks={}, test_cases={}, submissions={}, messages={}, events={}, integrity_events={}, export_jobs={}",
+            self.users,
+            self.tasks,
+            self.test_cases,
+            self.submissions,
+            self.messages,
+            self.events,
+            self.integrity_events,
+            self.export_jobs
+        )
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteIntentResponse {
+    pub classroom_id: i32,
+    /// Pass as `confirm_token` to `DELETE /api/classrooms/{id}?permanent=true`. Only
+    /// valid for the account that requested it.
+    pub confirm_token: String,
+    pub expires_at: DateTime<Utc>,
+    /// What the deletion would remove, counted now.
+    pub counts: ClassroomRowCounts,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteClassroomParams {
+    /// Delete a classroom with submissions instead of archiving it; needs `confirm_token`.
+    #[serde(default)]
+    pub permanent: bool,
+    /// Token from `POST /api/classrooms/{id}/delete-intent`.
+    pub confirm_token: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DeletionOutcome {
+    Deleted,
+    /// Hidden from students and kept with its submissions; the default for
+    /// classrooms with submissions.
+    Archived,
+}
+
+impl DeletionOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeletionOutcome::Deleted => "deleted",
+            DeletionOutcome::Archived => "archived",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "deleted" | "DELETED" | "Deleted" => Some(DeletionOutcome::Deleted),
+            "archived" | "ARCHIVED" | "Archived" => Some(DeletionOutcome::Archived),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteClassroomResponse {
+    pub classroom_id: i32,
+    pub outcome: DeletionOutcome,
+    /// Rows removed, or kept when archived.
+    pub counts: ClassroomRowCounts,
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct SelfTestParams {
+    pub npm: String,
+}
+
+/// Condition checked by `GET /api/diagnostics/self-test`, in the order login meets them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticCheckKind {
+    /// A login account exists for the NPM and is not waiting for approval.
+    Account,
+    /// The NPM is on the roster of a published classroom.
+    Classroom,
+    /// The roster entry is active.
+    UserActive,
+    /// The exam window is open and the exam is not held back or paused.
+    ExamWindow,
+    /// Judge0 answers its health check.
+    Executor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticStatus {
+    Pass,
+    /// Not blocking, but worth a look.
+    Warn,
+    /// Blocks the student.
+    Fail,
+    /// Does not apply, e.g. the exam window of a practice classroom.
+    Skipped,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCheck {
+    pub check: DiagnosticCheckKind,
+    pub status: DiagnosticStatus,
+    /// What was found, in words a lab assistant can act on.
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestResponse {
+    pub npm: String,
+    pub classroom_id: Option<i32>,
+    pub classroom_name: Option<String>,
+    pub checks: Vec<DiagnosticCheck>,
+    /// No check failed.
+    pub ok: bool,
+    pub checked_at: DateTime<Utc>,
+}
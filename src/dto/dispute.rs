@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::entities::{dispute, submission, user};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DisputeStatus {
+    /// Waiting for an instructor.
+    Open,
+    /// Accepted; the grade may have been regraded.
+    Resolved,
+    /// Declined with an explanation.
+    Rejected,
+}
+
+impl DisputeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisputeStatus::Open => "open",
+            DisputeStatus::Resolved => "resolved",
+            DisputeStatus::Rejected => "rejected",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "open" | "OPEN" | "Open" => Some(DisputeStatus::Open),
+            "resolved" | "RESOLVED" | "Resolved" => Some(DisputeStatus::Resolved),
+            "rejected" | "REJECTED" | "Rejected" => Some(DisputeStatus::Rejected),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDisputeRequest {
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RespondDisputeRequest {
+    pub response: String,
+    /// `resolved` or `rejected`.
+    pub status: DisputeStatus,
+    /// Grade the submission again with the current test cases and grading script.
+    #[serde(default)]
+    pub regrade: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct DisputeListParams {
+    /// Only disputes with this status.
+    pub status: Option<DisputeStatus>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeResponse {
+    pub id: i32,
+    pub submission_id: i32,
+    pub classroom_id: i32,
+    pub user_id: i32,
+    pub npm: String,
+    pub name: String,
+    pub message: String,
+    pub status: DisputeStatus,
+    pub response: Option<String>,
+    pub responded_by: Option<i32>,
+    pub regraded: bool,
+    pub previous_score: Option<i32>,
+    pub regraded_score: Option<i32>,
+    /// Current grade of the submission.
+    pub score: Option<i32>,
+    pub max_score: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+impl DisputeResponse {
+    pub fn from_models(
+        model: dispute::Model,
+        submission: &submission::Model,
+        user: &user::Model,
+    ) -> Self {
+        Self {
+            id: model.id,
+            submission_id: model.submission_id,
+            classroom_id: submission.classroom_id,
+            user_id: model.user_id,
+            npm: user.npm.clone(),
+            name: user.name.clone(),
+            message: model.message,
+            status: DisputeStatus::from_str(&model.status).unwrap_or(DisputeStatus::Open),
+            response: model.response,
+            responded_by: model.responded_by,
+            regraded: model.regraded,
+            previous_score: model.previous_score,
+            regraded_score: model.regraded_score,
+            score: submission.score,
+            max_score: submission.max_score,
+            created_at: model.created_at,
+            responded_at: model.responded_at,
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{admin::ExecutorQueueStatus, login_event::LoginEventResponse, seat::Seat};
+use crate::{entities::submission, services::exam_clock::ExamClock};
+
+/// Also the payload of the `clock` SSE event sent when the exam is paused or resumed.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExamClockResponse {
+    pub classroom_id: i32,
+    pub paused: bool,
+    pub paused_seconds: i64,
+    pub exam_end: Option<DateTime<Utc>>,
+    pub remaining_seconds: Option<i64>,
+    /// Instant the clock was read at, for computing the client's offset.
+    pub server_time: DateTime<Utc>,
+}
+
+impl ExamClockResponse {
+    pub fn from_clock(classroom_id: i32, clock: &ExamClock, now: DateTime<Utc>) -> Self {
+        Self {
+            classroom_id,
+            paused: clock.is_paused(),
+            paused_seconds: clock.paused_duration(now).num_seconds(),
+            exam_end: clock.effective_end(now),
+            remaining_seconds: clock.remaining(now).map(|left| left.num_seconds()),
+            server_time: now,
+        }
+    }
+}
+
+/// Response of `GET /api/time`.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerTimeResponse {
+    pub server_time: DateTime<Utc>,
+    /// `serverTime` as milliseconds since the Unix epoch.
+    pub unix_millis: i64,
+}
+
+/// Payload of the `warning` SSE event, sent once per threshold as the exam runs out.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExamWarningEvent {
+    pub classroom_id: i32,
+    pub threshold_seconds: i64,
+    pub remaining_seconds: i64,
+    pub exam_end: Option<DateTime<Utc>>,
+    pub server_time: DateTime<Utc>,
+}
+
+/// Payload of the final `timeup` SSE event.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExamTimeUpEvent {
+    pub classroom_id: i32,
+    pub exam_end: Option<DateTime<Utc>>,
+    pub server_time: DateTime<Utc>,
+}
+
+/// Payload of the `submission` SSE event, sent to the student when a final
+/// submission has been graded or regraded.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionEvent {
+    pub submission_id: i32,
+    pub classroom_id: i32,
+    pub user_id: i32,
+    pub auto_submitted: bool,
+    #[serde(default)]
+    pub late: bool,
+    pub passed_cases: Option<i32>,
+    pub total_cases: Option<i32>,
+    pub score: Option<i32>,
+    pub max_score: Option<i32>,
+    pub graded_at: Option<DateTime<Utc>>,
+}
+
+impl From<&submission::Model> for SubmissionEvent {
+    fn from(model: &submission::Model) -> Self {
+        Self {
+            submission_id: model.id,
+            classroom_id: model.classroom_id,
+            user_id: model.user_id,
+            auto_submitted: model.auto_submitted,
+            late: model.late,
+            passed_cases: model.passed_cases,
+            total_cases: model.total_cases,
+            score: model.score,
+            max_score: model.max_score,
+            graded_at: model.graded_at,
+        }
+    }
+}
+
+/// Where the grading of a final submission stands.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GradingStatus {
+    /// Waiting for a Judge0 slot, or for a retry after a failed attempt.
+    Queued,
+    Running,
+    Done,
+    /// Not graded: every attempt failed, or there was no code to grade.
+    Failed,
+}
+
+impl GradingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GradingStatus::Queued => "queued",
+            GradingStatus::Running => "running",
+            GradingStatus::Done => "done",
+            GradingStatus::Failed => "failed",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "queued" | "QUEUED" | "Queued" => Some(GradingStatus::Queued),
+            "running" | "RUNNING" | "Running" => Some(GradingStatus::Running),
+            "done" | "DONE" | "Done" => Some(GradingStatus::Done),
+            "failed" | "FAILED" | "Failed" => Some(GradingStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// Also the payload of the `grading-status` SSE event, sent to the student as their
+/// final submission is queued, runs each test case, and finishes.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GradingStatusResponse {
+    pub submission_id: i32,
+    pub classroom_id: i32,
+    pub user_id: i32,
+    pub task_id: Option<i32>,
+    pub status: GradingStatus,
+    /// Test case being run, counted from 1; set while `running` through test cases.
+    pub current_case: Option<i32>,
+    pub total_cases: Option<i32>,
+}
+
+impl From<&submission::Model> for GradingStatusResponse {
+    fn from(model: &submission::Model) -> Self {
+        let status = model
+            .grading_status
+            .as_deref()
+            .and_then(GradingStatus::from_str)
+            // Hand-ins from before progress was tracked.
+            .unwrap_or(if model.graded_at.is_some() {
+                GradingStatus::Done
+            } else {
+                GradingStatus::Queued
+            });
+        Self {
+            submission_id: model.id,
+            classroom_id: model.classroom_id,
+            user_id: model.user_id,
+            task_id: model.task_id,
+            status,
+            current_case: model.grading_case,
+            total_cases: model.grading_case_count,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct GradingStatusParams {
+    pub npm: String,
+    /// Only hand-ins for this task; otherwise the latest hand-in of any task.
+    #[serde(default)]
+    pub task_id: Option<i32>,
+}
+
+/// Payload of the `user-status` SSE event, sent when a proctor activates or
+/// deactivates the student.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserStatusEvent {
+    pub classroom_id: i32,
+    pub user_id: i32,
+    pub active: bool,
+}
+
+/// Payload of the `task-due` SSE event, sent to the whole classroom as a task due
+/// date approaches and once more when it passes.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskDueEvent {
+    pub classroom_id: i32,
+    pub task_id: i32,
+    pub title: String,
+    pub due_at: DateTime<Utc>,
+    /// Reminder threshold that was crossed; `0` once the task is due.
+    pub threshold_seconds: i64,
+    pub remaining_seconds: i64,
+    pub server_time: DateTime<Utc>,
+}
+
+/// Payload of the `user-joined` event, sent to proctors when a student first logs in
+/// to an exam.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserJoinedEvent {
+    pub classroom_id: i32,
+    pub user_id: i32,
+    pub npm: String,
+    pub name: String,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Payload of the `user-submitted` event, sent to proctors when an enrolled student
+/// runs code through `POST /api/judge0/submissions`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSubmittedEvent {
+    pub classroom_id: i32,
+    pub user_id: i32,
+    pub npm: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<i32>,
+    pub language_id: i32,
+    /// Judge0 status description; absent for a run queued with `wait=false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// Payload of the `exam-finished` event, sent to proctors when a student hands in, or
+/// is handed in by the finalizer once hand-ins close.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExamFinishedEvent {
+    pub classroom_id: i32,
+    pub user_id: i32,
+    pub npm: String,
+    pub name: String,
+    pub submission_id: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<i32>,
+    pub auto_submitted: bool,
+    pub late: bool,
+    pub finished_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserConnectionCount {
+    pub npm: String,
+    pub connections: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seat: Option<Seat>,
+}
+
+/// Live view of a classroom for proctors.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveStatsResponse {
+    pub classroom_id: i32,
+    pub server_time: DateTime<Utc>,
+    pub total_users: u64,
+    pub active_users: u64,
+    /// Users with at least one open event stream.
+    pub connected_users: usize,
+    pub open_connections: usize,
+    pub max_connections_per_user: usize,
+    pub connections: Vec<UserConnectionCount>,
+    /// Server-wide Judge0 queue, shared by every classroom.
+    pub executor_queue: ExecutorQueueStatus,
+    /// Newest login attempts of this classroom's students flagged as anomalous.
+    pub flagged_logins: Vec<LoginEventResponse>,
+}
+
+/// Which side of the lab firewall opens the connection.
+#[derive(Debug, Clone, Copy, Serialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkSource {
+    /// Student workstations.
+    Students,
+    /// The host running this server.
+    Server,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkRule {
+    pub source: NetworkSource,
+    /// `api`, `frontend` or `judge0`.
+    pub purpose: String,
+    pub host: String,
+    pub port: u16,
+    pub protocol: String,
+}
+
+/// Allow-list a lab firewall needs while the exam runs; everything else may be blocked.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExamNetworkProfileResponse {
+    pub classroom_id: i32,
+    pub exam_start: Option<DateTime<Utc>>,
+    pub exam_end: Option<DateTime<Utc>>,
+    pub api_base_url: String,
+    pub event_stream_url: String,
+    pub rules: Vec<NetworkRule>,
+}
+
+/// Event kinds a proctor stream can be narrowed to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProctorEventFilter {
+    /// `integrity` events only.
+    Integrity,
+    /// `submission`, `grading-status`, `user-submitted` and `exam-finished` events only.
+    Submissions,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProctorEventsParams {
+    /// Comma-separated classroom ids, e.g. `1,2,3`.
+    pub classroom_ids: String,
+    /// Only events of this group; every event when absent.
+    #[serde(default)]
+    pub only: Option<ProctorEventFilter>,
+    /// Replays stored events after this sequence number before going live.
+    #[serde(default)]
+    pub after_seq: Option<i32>,
+}
+
+/// SSE `data` of `GET /api/events`: the classroom event tagged with its classroom.
+/// The SSE event name and id are those of the classroom stream.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProctorEvent {
+    pub classroom_id: i32,
+    pub seq: i32,
+    /// Payload as sent on the classroom stream.
+    #[schema(value_type = Object)]
+    pub data: serde_json::Value,
+}
@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::admin::ExecutorMetrics;
+
+/// How Judge0 calls are spread over the healthy instances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum BalanceStrategy {
+    /// Each call goes to the next instance in turn.
+    RoundRobin,
+    /// Each call goes to the instance with the fewest calls in flight.
+    LeastPending,
+}
+
+impl BalanceStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BalanceStrategy::RoundRobin => "round-robin",
+            BalanceStrategy::LeastPending => "least-pending",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "round-robin" => Some(BalanceStrategy::RoundRobin),
+            "least-pending" => Some(BalanceStrategy::LeastPending),
+            _ => None,
+        }
+    }
+}
+
+/// Where the active executor configuration came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutorConfigSource {
+    /// `JUDGE0_*` environment variables.
+    Environment,
+    /// Stored through `PUT /api/admin/executor-config`.
+    Database,
+}
+
+/// Replaces the Judge0 instances without a restart. Omitted fields keep their
+/// current value.
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateExecutorConfigRequest {
+    pub base_urls: Vec<String>,
+    #[serde(default)]
+    pub strategy: Option<BalanceStrategy>,
+    /// Consecutive failed calls after which an instance is taken out of rotation.
+    #[serde(default)]
+    pub eject_after_failures: Option<u32>,
+    #[serde(default)]
+    pub health_check_interval_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutorConfigResponse {
+    pub base_urls: Vec<String>,
+    pub strategy: BalanceStrategy,
+    pub eject_after_failures: u32,
+    pub health_check_interval_secs: u64,
+    pub source: ExecutorConfigSource,
+    /// Set when the configuration is stored in the database.
+    pub updated_at: Option<DateTime<Utc>>,
+    pub instances: Vec<ExecutorInstanceStatus>,
+}
+
+/// Health and call counters of one Judge0 instance.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutorInstanceStatus {
+    pub base_url: String,
+    /// False while the instance is ejected; it only receives calls when no healthy
+    /// instance is left.
+    pub healthy: bool,
+    pub pending: u64,
+    pub consecutive_failures: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub calls: ExecutorMetrics,
+}
@@ -0,0 +1,177 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::grading::GradeFormat;
+use crate::entities::export_job;
+
+/// How timestamps are written in exports and reports.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeDisplay {
+    /// RFC 3339 in UTC only.
+    Utc,
+    /// RFC 3339 in UTC plus a readable column in the server's `DISPLAY_TIMEZONE`.
+    Local,
+}
+
+impl TimeDisplay {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeDisplay::Utc => "utc",
+            TimeDisplay::Local => "local",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "utc" | "UTC" | "Utc" => Some(TimeDisplay::Utc),
+            "local" | "LOCAL" | "Local" => Some(TimeDisplay::Local),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ExportTimeParams {
+    /// `utc` or `local`; without it, an `Accept-Language` starting with `id` selects
+    /// `local`.
+    #[serde(default)]
+    pub time: Option<TimeDisplay>,
+}
+
+/// What a background export job builds.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportKind {
+    /// The grade sheet, as `GET /api/classrooms/{id}/grades/export`.
+    Grades,
+    /// Time spent per task, as `GET /api/classrooms/{id}/task-times/export`.
+    TaskTimes,
+    /// ZIP with both sheets and each student's latest final submission.
+    Archive,
+}
+
+impl ExportKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportKind::Grades => "grades",
+            ExportKind::TaskTimes => "task_times",
+            ExportKind::Archive => "archive",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "grades" | "GRADES" | "Grades" => Some(ExportKind::Grades),
+            "task_times" | "TASK_TIMES" | "TaskTimes" => Some(ExportKind::TaskTimes),
+            "archive" | "ARCHIVE" | "Archive" => Some(ExportKind::Archive),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+}
+
+impl ExportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportStatus::Queued => "queued",
+            ExportStatus::Running => "running",
+            ExportStatus::Done => "done",
+            ExportStatus::Failed => "failed",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "queued" | "QUEUED" | "Queued" => Some(ExportStatus::Queued),
+            "running" | "RUNNING" | "Running" => Some(ExportStatus::Running),
+            "done" | "DONE" | "Done" => Some(ExportStatus::Done),
+            "failed" | "FAILED" | "Failed" => Some(ExportStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateExportRequest {
+    pub kind: ExportKind,
+    /// Grade sheet layout for `grades` and `archive`; defaults to `csv`.
+    #[serde(default)]
+    pub format: GradeFormat,
+    /// `utc` or `local`; without it, an `Accept-Language` starting with `id` selects
+    /// `local`.
+    #[serde(default)]
+    pub time: Option<TimeDisplay>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportJobResponse {
+    pub id: i32,
+    pub classroom_id: i32,
+    pub kind: ExportKind,
+    pub format: GradeFormat,
+    pub time: TimeDisplay,
+    pub status: ExportStatus,
+    /// Percent complete, 0 to 100.
+    pub progress: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub file_name: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// When the file is deleted.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Signed link to the file, valid until `downloadUrlExpiresAt`; set once the job
+    /// is done. Fetch the job again for a fresh link.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_url_expires_at: Option<DateTime<Utc>>,
+}
+
+impl ExportJobResponse {
+    pub fn from_model(model: export_job::Model) -> Self {
+        Self {
+            id: model.id,
+            classroom_id: model.classroom_id,
+            kind: ExportKind::from_str(&model.kind).unwrap_or(ExportKind::Grades),
+            format: GradeFormat::from_str(&model.format).unwrap_or_default(),
+            time: TimeDisplay::from_str(&model.time_display).unwrap_or(TimeDisplay::Utc),
+            status: ExportStatus::from_str(&model.status).unwrap_or(ExportStatus::Failed),
+            progress: model.progress,
+            error: model.error,
+            file_name: model.file_name,
+            size_bytes: model.size_bytes,
+            created_at: model.created_at,
+            started_at: model.started_at,
+            finished_at: model.finished_at,
+            expires_at: model.expires_at,
+            download_url: None,
+            download_url_expires_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ExportDownloadParams {
+    /// Unix seconds after which the link is refused.
+    pub expires: i64,
+    pub signature: String,
+}
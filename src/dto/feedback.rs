@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{judge::Judge0SubmissionResponse, test_case::TestCaseResult};
+
+/// Answer to a final submission. Outside practice mode only the receipt fields are
+/// set, so grades stay hidden until the instructor releases them.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionFeedback {
+    pub submission_id: i32,
+    pub received_at: DateTime<Utc>,
+    /// Whether the results below are included.
+    pub practice_mode: bool,
+    /// Handed in after the due date of its task.
+    pub late: bool,
+    /// Run the grade is based on. Stdout is withheld when that run is a hidden case.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Judge0SubmissionResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passed_cases: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_cases: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hidden_passed_cases: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hidden_cases: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_score: Option<i32>,
+    /// Per-case results; hidden cases only report whether they passed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cases: Option<Vec<TestCaseResult>>,
+}
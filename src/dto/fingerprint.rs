@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintSharingRequest {
+    /// Whether final submissions join the cross-classroom fingerprint store. Turning
+    /// it off also deletes the fingerprints already taken from this classroom.
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintSharingResponse {
+    pub classroom_id: i32,
+    pub enabled: bool,
+    /// Fingerprints deleted by this request; only non-zero when opting out.
+    pub removed_fingerprints: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateFingerprintSettingsRequest {
+    /// Days a fingerprint is kept; 0 keeps them forever.
+    pub retention_days: u32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintSettingsResponse {
+    pub retention_days: u32,
+    /// Fingerprints currently stored across all classrooms.
+    pub stored_fingerprints: u64,
+    /// When an admin last set the retention; `None` while `FINGERPRINT_RETENTION_DAYS` applies.
+    pub updated_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,150 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{dto::TimeDisplay, entities::classroom};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GradingScriptRequest {
+    pub source_code: String,
+    /// Judge0 language of the script, e.g. 71 for Python.
+    pub language_id: i32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GradingScriptResponse {
+    pub classroom_id: i32,
+    pub source_code: Option<String>,
+    pub language_id: Option<i32>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<classroom::Model> for GradingScriptResponse {
+    fn from(model: classroom::Model) -> Self {
+        Self {
+            classroom_id: model.id,
+            source_code: model.grading_script,
+            language_id: model.grading_script_language_id,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+/// Document a grading script receives as JSON on stdin.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GradingScriptInput {
+    pub source_code: String,
+    pub language_id: i32,
+    /// Run the built-in grade is based on.
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    pub compile_output: Option<String>,
+    pub status: Option<String>,
+    pub passed_cases: Option<i32>,
+    pub total_cases: Option<i32>,
+    pub score: Option<i32>,
+    pub max_score: Option<i32>,
+    pub cases: Vec<GradingScriptCase>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GradingScriptCase {
+    pub test_case_id: i32,
+    pub stdin: String,
+    pub expected_output: String,
+    pub stdout: Option<String>,
+    pub status: Option<String>,
+    pub passed: bool,
+    pub points: i32,
+}
+
+/// JSON object a grading script prints on stdout. `score` and `maxScore` override the
+/// built-in grade; any other fields (e.g. `feedback`) are stored with the verdict as-is.
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GradingScriptVerdict {
+    pub score: Option<i32>,
+    pub max_score: Option<i32>,
+}
+
+/// Column layout of the grade export.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GradeFormat {
+    /// Plain sheet with every grade column.
+    #[default]
+    Csv,
+    /// Moodle gradebook import; map `ID number` to the student's ID number.
+    Moodle,
+    /// Canvas gradebook import; students are matched on `SIS User ID`.
+    Canvas,
+}
+
+impl GradeFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GradeFormat::Csv => "csv",
+            GradeFormat::Moodle => "moodle",
+            GradeFormat::Canvas => "canvas",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "csv" | "CSV" | "Csv" => Some(GradeFormat::Csv),
+            "moodle" | "MOODLE" | "Moodle" => Some(GradeFormat::Moodle),
+            "canvas" | "CANVAS" | "Canvas" => Some(GradeFormat::Canvas),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct GradeExportParams {
+    /// `csv` (default), `moodle` or `canvas`.
+    #[serde(default)]
+    pub format: GradeFormat,
+    /// `utc` or `local`; see [`TimeDisplay`].
+    #[serde(default)]
+    pub time: Option<TimeDisplay>,
+}
+
+/// Outcome of grading a student's saved code on an instructor's request.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GradeResultResponse {
+    /// Final submission the grade is stored on.
+    pub submission_id: i32,
+    pub user_id: i32,
+    pub language_id: i32,
+    /// Judge0 status of the run the grade is based on.
+    pub status: Option<String>,
+    pub passed_cases: Option<i32>,
+    pub total_cases: Option<i32>,
+    /// Built-in grade, or the grading script's when the classroom has one.
+    pub score: Option<i32>,
+    pub max_score: Option<i32>,
+    pub graded_at: Option<DateTime<Utc>>,
+    /// Every test case, hidden ones included; empty when the classroom has none.
+    pub cases: Vec<GradeCaseResult>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GradeCaseResult {
+    pub test_case_id: i32,
+    pub task_id: i32,
+    pub hidden: bool,
+    pub passed: bool,
+    /// Points of the case after the task's weight; earned only when it passed.
+    pub points: i32,
+    pub status: Option<String>,
+    pub expected_output: String,
+    pub stdout: Option<String>,
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Shape of the highlighted source.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HighlightFormat {
+    /// `<pre>` block with inline styles, ready to embed.
+    #[default]
+    Html,
+    /// Styled byte ranges for clients that render the code themselves.
+    Tokens,
+}
+
+impl HighlightFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HighlightFormat::Html => "html",
+            HighlightFormat::Tokens => "tokens",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "html" | "HTML" | "Html" => Some(HighlightFormat::Html),
+            "tokens" | "TOKENS" | "Tokens" => Some(HighlightFormat::Tokens),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct HighlightParams {
+    /// Theme name, e.g. `InspiredGitHub` (default), `base16-ocean.dark` or
+    /// `Solarized (light)`.
+    pub theme: Option<String>,
+    /// `html` (default) or `tokens`.
+    #[serde(default)]
+    pub format: HighlightFormat,
+}
+
+/// Byte range `[start, end)` of the source drawn in one style.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HighlightToken {
+    pub start: usize,
+    pub end: usize,
+    /// Foreground as `#rrggbb`.
+    pub color: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl HighlightToken {
+    pub fn same_style(&self, other: &HighlightToken) -> bool {
+        self.color == other.color
+            && self.bold == other.bold
+            && self.italic == other.italic
+            && self.underline == other.underline
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HighlightResponse {
+    pub submission_id: i32,
+    pub language_id: i32,
+    /// Grammar used; `Plain Text` when the language has none.
+    pub syntax: String,
+    pub theme: String,
+    pub format: HighlightFormat,
+    /// Set for `format=html`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    /// Set for `format=tokens`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<Vec<HighlightToken>>,
+}
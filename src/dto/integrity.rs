@@ -0,0 +1,124 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    dto::Seat,
+    entities::{integrity_event, user},
+    services::seating::SeatIndex,
+};
+
+/// What happens when an exam request arrives from a device other than the bound one.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DevicePolicy {
+    /// No binding; fingerprints are ignored.
+    Off,
+    /// Mismatches are allowed but logged as integrity events.
+    Flag,
+    /// Mismatches are logged and the request is rejected.
+    Block,
+}
+
+impl DevicePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DevicePolicy::Off => "off",
+            DevicePolicy::Flag => "flag",
+            DevicePolicy::Block => "block",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "off" | "OFF" | "Off" => Some(DevicePolicy::Off),
+            "flag" | "FLAG" | "Flag" => Some(DevicePolicy::Flag),
+            "block" | "BLOCK" | "Block" => Some(DevicePolicy::Block),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DevicePolicyRequest {
+    pub policy: DevicePolicy,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DevicePolicyResponse {
+    pub classroom_id: i32,
+    pub policy: DevicePolicy,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityEventResponse {
+    pub id: i32,
+    pub classroom_id: i32,
+    pub user_id: i32,
+    pub npm: String,
+    pub name: String,
+    pub kind: String,
+    pub detail: String,
+    pub blocked: bool,
+    /// Seat of the student when the event was listed, if one is assigned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seat: Option<Seat>,
+    /// NPMs of the students seated directly around the student.
+    pub neighbors: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl IntegrityEventResponse {
+    pub fn from_models(
+        model: integrity_event::Model,
+        user_model: &user::Model,
+        seats: &SeatIndex,
+    ) -> Self {
+        Self {
+            id: model.id,
+            classroom_id: model.classroom_id,
+            user_id: model.user_id,
+            npm: user_model.npm.clone(),
+            name: user_model.name.clone(),
+            kind: model.kind,
+            detail: model.detail,
+            blocked: model.blocked,
+            seat: Seat::of(user_model),
+            neighbors: seats.neighbors(user_model),
+            created_at: model.created_at,
+        }
+    }
+}
+
+/// Payload of the `integrity` event, streamed to proctors only when an exam request
+/// arrives from a device other than the bound one.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityAlertEvent {
+    pub id: i32,
+    pub classroom_id: i32,
+    pub user_id: i32,
+    pub npm: String,
+    pub kind: String,
+    pub detail: String,
+    pub blocked: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl IntegrityAlertEvent {
+    pub fn from_models(model: &integrity_event::Model, user_model: &user::Model) -> Self {
+        Self {
+            id: model.id,
+            classroom_id: model.classroom_id,
+            user_id: model.user_id,
+            npm: user_model.npm.clone(),
+            kind: model.kind.clone(),
+            detail: model.detail.clone(),
+            blocked: model.blocked,
+            created_at: model.created_at,
+        }
+    }
+}
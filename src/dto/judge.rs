@@ -1,5 +1,16 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
+
+use super::{
+    validated::LimitedFields,
+    version::{VersionedBody, VersionedJson},
+};
+use crate::{
+    entities::submission,
+    error::AppError,
+    services::{field_limits::FieldLimits, judge0_poll, output},
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
@@ -14,13 +25,126 @@ pub struct Judge0SubmissionRequest {
     pub cpu_time_limit: Option<f32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub memory_limit: Option<u32>,
+    /// Judge0's output file cap in KB; lowered to the classroom's output limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size: Option<u32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub compiler_options: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub command_line_arguments: Option<String>,
+    /// Sandbox flags; the classroom's own settings win, and none may be sent while
+    /// the classroom's exam is running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enable_network: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_processes_and_or_threads: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_stderr_to_stdout: Option<bool>,
     #[serde(default, skip_serializing)]
     #[schema(example = "51422582")]
     pub npm: Option<String>,
+    /// Classroom whose roster entry receives the code; required whenever `npm` is sent.
+    #[serde(default, skip_serializing)]
+    pub classroom_id: Option<i32>,
+    /// Task being edited; counts the save as an edit for per-task time tracking.
+    #[serde(default, skip_serializing)]
+    pub task_id: Option<i32>,
+    /// `updatedAt` of the saved copy this code was edited from (bootstrap `ownCode`).
+    /// If the saved code has changed since, e.g. from another tab, it is not
+    /// overwritten and the request fails with `409` (`code: "stale_code"`).
+    #[serde(default, skip_serializing)]
+    pub client_updated_at: Option<DateTime<Utc>>,
+    /// `seq` of the saved copy this code was edited from (bootstrap `ownCode`, or the
+    /// `X-Code-Seq` header of the previous save). A save based on an older revision
+    /// fails with `409` (`code: "stale_code"`) and the saved copy in `current`.
+    #[serde(default, skip_serializing)]
+    pub base_seq: Option<i64>,
+}
+
+/// v2 submission body: the same fields in the camelCase used by every other endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Judge0SubmissionRequestV2 {
+    pub source_code: String,
+    pub language_id: i32,
+    #[serde(default)]
+    pub stdin: Option<String>,
+    #[serde(default)]
+    pub expected_output: Option<String>,
+    #[serde(default)]
+    pub cpu_time_limit: Option<f32>,
+    #[serde(default)]
+    pub memory_limit: Option<u32>,
+    #[serde(default)]
+    pub max_file_size: Option<u32>,
+    #[serde(default)]
+    pub compiler_options: Option<String>,
+    #[serde(default)]
+    pub command_line_arguments: Option<String>,
+    #[serde(default)]
+    pub enable_network: Option<bool>,
+    #[serde(default)]
+    pub max_processes_and_or_threads: Option<u32>,
+    #[serde(default)]
+    pub redirect_stderr_to_stdout: Option<bool>,
+    #[serde(default)]
+    #[schema(example = "51422582")]
+    pub npm: Option<String>,
+    #[serde(default)]
+    pub classroom_id: Option<i32>,
+    #[serde(default)]
+    pub task_id: Option<i32>,
+    #[serde(default)]
+    pub client_updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub base_seq: Option<i64>,
+}
+
+impl VersionedBody for Judge0SubmissionRequest {
+    type V1 = Self;
+    type V2 = Judge0SubmissionRequestV2;
+
+    fn from_v1(body: Self) -> Self {
+        body
+    }
+
+    fn from_v2(body: Judge0SubmissionRequestV2) -> Self {
+        Self {
+            source_code: body.source_code,
+            language_id: body.language_id,
+            stdin: body.stdin,
+            expected_output: body.expected_output,
+            cpu_time_limit: body.cpu_time_limit,
+            memory_limit: body.memory_limit,
+            max_file_size: body.max_file_size,
+            compiler_options: body.compiler_options,
+            command_line_arguments: body.command_line_arguments,
+            enable_network: body.enable_network,
+            max_processes_and_or_threads: body.max_processes_and_or_threads,
+            redirect_stderr_to_stdout: body.redirect_stderr_to_stdout,
+            npm: body.npm,
+            classroom_id: body.classroom_id,
+            task_id: body.task_id,
+            client_updated_at: body.client_updated_at,
+            base_seq: body.base_seq,
+        }
+    }
+}
+
+impl LimitedFields for Judge0SubmissionRequest {
+    type Body = VersionedJson<Self>;
+
+    fn classroom_id(&self) -> Option<i32> {
+        self.classroom_id
+    }
+
+    fn npm(&self) -> Option<&str> {
+        self.npm.as_deref()
+    }
+
+    fn check(&self, limits: &FieldLimits) -> Result<(), AppError> {
+        limits.check_code("source_code", &self.source_code)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -38,4 +162,74 @@ pub struct Judge0SubmissionResponse {
     pub message: Option<String>,
     pub status: Option<Judge0SubmissionStatus>,
     pub token: String,
+    #[serde(default)]
+    pub time: Option<String>,
+    #[serde(default)]
+    pub memory: Option<i64>,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub exit_signal: Option<i32>,
+    /// Whether stdout or stderr was cut at the output limit.
+    #[serde(default)]
+    pub truncated: bool,
+    /// Size of stdout before truncation, in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdout_bytes: Option<usize>,
+    /// Size of stderr before truncation, in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stderr_bytes: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct JudgeSubmitParams {
+    /// `false` queues the run on Judge0 and answers 202 with its token right away;
+    /// only for enrolled students. Defaults to `true`.
+    pub wait: Option<bool>,
+}
+
+/// A run submitted with `wait=false`, as last fetched from Judge0.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct Judge0RunStatus {
+    #[serde(flatten)]
+    pub result: Judge0SubmissionResponse,
+    /// Stored run in the student's submission history.
+    pub submission_id: i32,
+    /// Judge0 has not finished the run yet; ask again later.
+    pub pending: bool,
+}
+
+impl From<submission::Model> for Judge0RunStatus {
+    fn from(model: submission::Model) -> Self {
+        let status_id = model.judge0_status_id;
+        let cut = |text: &Option<String>| {
+            text.as_deref()
+                .is_some_and(|text| text.ends_with(output::TRUNCATION_MARKER))
+        };
+        let truncated = cut(&model.stdout) || cut(&model.stderr);
+        Self {
+            result: Judge0SubmissionResponse {
+                stdout: model.stdout,
+                stderr: model.stderr,
+                compile_output: model.compile_output,
+                message: None,
+                status: status_id.map(|id| Judge0SubmissionStatus {
+                    id,
+                    description: model.status.unwrap_or_default(),
+                }),
+                token: model.judge0_token.unwrap_or_default(),
+                time: model.time,
+                memory: model.memory,
+                exit_code: model.exit_code,
+                exit_signal: model.exit_signal,
+                truncated,
+                stdout_bytes: None,
+                stderr_bytes: None,
+            },
+            submission_id: model.id,
+            pending: status_id.is_some_and(|id| judge0_poll::PENDING_STATUS_IDS.contains(&id)),
+        }
+    }
 }
@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::entities::login_event;
+
+/// Why a login attempt was flagged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginAnomaly {
+    /// The same NPM tried to sign in from another address shortly before.
+    MultipleIps,
+    /// The NPM's exam classroom was not running at the time.
+    OutsideExamWindow,
+}
+
+impl LoginAnomaly {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoginAnomaly::MultipleIps => "multiple_ips",
+            LoginAnomaly::OutsideExamWindow => "outside_exam_window",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "multiple_ips" => Some(LoginAnomaly::MultipleIps),
+            "outside_exam_window" => Some(LoginAnomaly::OutsideExamWindow),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct LoginEventListParams {
+    pub npm: Option<String>,
+    pub classroom_id: Option<i32>,
+    /// `true` for flagged attempts only, `false` for unflagged ones only.
+    pub flagged: Option<bool>,
+    pub success: Option<bool>,
+    /// Only attempts made at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// At most this many of the newest attempts; defaults to 100.
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginEventResponse {
+    pub id: i32,
+    pub npm: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<i32>,
+    pub ip: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classroom_id: Option<i32>,
+    pub flags: Vec<LoginAnomaly>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<login_event::Model> for LoginEventResponse {
+    fn from(model: login_event::Model) -> Self {
+        Self {
+            id: model.id,
+            flags: model
+                .flags
+                .split(',')
+                .filter_map(LoginAnomaly::from_str)
+                .collect(),
+            npm: model.npm,
+            account_id: model.account_id,
+            ip: model.ip,
+            success: model.success,
+            failure: model.failure,
+            classroom_id: model.classroom_id,
+            created_at: model.created_at,
+        }
+    }
+}
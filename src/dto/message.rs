@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::entities::{message, message_recipient, user};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SendMessageRequest {
+    /// Users of the classroom that receive the message.
+    pub user_ids: Vec<i32>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AckMessageRequest {
+    pub npm: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageRecipientResponse {
+    pub user_id: i32,
+    pub npm: String,
+    pub name: String,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+impl MessageRecipientResponse {
+    pub fn from_models(recipient: message_recipient::Model, user: user::Model) -> Self {
+        Self {
+            user_id: user.id,
+            npm: user.npm,
+            name: user.name,
+            read_at: recipient.read_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageResponse {
+    pub id: i32,
+    pub classroom_id: i32,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    pub recipients: Vec<MessageRecipientResponse>,
+}
+
+/// Payload of the `message` SSE event; read receipts are not exposed to students.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageEvent {
+    pub id: i32,
+    pub classroom_id: i32,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<message::Model> for MessageEvent {
+    fn from(model: message::Model) -> Self {
+        Self {
+            id: model.id,
+            classroom_id: model.classroom_id,
+            message: model.body,
+            created_at: model.created_at,
+        }
+    }
+}
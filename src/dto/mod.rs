@@ -1,13 +1,188 @@
 pub mod account;
+pub mod activity;
+pub mod admin;
+pub mod analytics;
+pub mod artifact;
 pub mod auth;
+pub mod banner;
+pub mod bootstrap;
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod classroom;
+pub mod client_config;
+pub mod client_error;
+pub mod deletion;
+pub mod diagnostics;
+pub mod dispute;
+pub mod exam;
+pub mod executor;
+pub mod export;
+pub mod feedback;
+pub mod fingerprint;
+pub mod grading;
+pub mod highlight;
+pub mod integrity;
 pub mod judge;
+pub mod login_event;
+pub mod message;
+pub mod packet;
+pub mod passkey;
+pub mod publish;
+pub mod purge;
+pub mod replication;
+pub mod rollover;
+pub mod run_config;
+pub mod sandbox;
+pub mod schedule;
+pub mod seat;
+pub mod settings;
+pub mod submission;
+pub mod task;
+pub mod task_time;
+pub mod test_case;
+pub mod ticket;
 pub mod user;
+pub mod validated;
+pub mod verdict;
+pub mod version;
+pub mod webhook;
 
-pub use account::{AccountResponse, AccountRole, CreateAccountRequest, UpdateAccountRoleRequest};
-pub use auth::{AdminExistsResponse, LoginRequest, LoginResponse};
+pub use account::{
+    AccountExport, AccountImportParams, AccountImportResponse, AccountResponse, AccountRole,
+    AccountStatus, CreateAccountRequest, ExportedAccount, ImportConflictPolicy,
+    UpdateAccountRoleRequest,
+};
+pub use activity::{
+    AccountActivityResponse, AuditEntryResponse, AuditKind, ClassroomActivity, SubmissionActivity,
+};
+pub use admin::{
+    AlertSignalKind, ApiChange, ApiChangeKind, CodeBlobStats, ColumnSchema, DatabaseMetrics,
+    DatabaseStats, DeadLetterResponse, ErrorBudgetStatus, ExecutorMetrics, ExecutorQueueStatus,
+    GradingRetryMetrics, MetricsResponse, MigrationStatus, OpenApiDiffResponse, RateLimitStatus,
+    SchemaMigrationStatus, SchemaResponse, SlowQueryRoute, StorageUsage, SystemStatsResponse,
+    TableRowCount, TableSchema,
+};
+pub use analytics::{
+    DailyStatsEntry, DailyStatsParams, DailyStatsResponse, LanguageStatsParams,
+    LanguageStatsResponse, LanguageUsageEntry, LanguageUsageSummary,
+};
+pub use artifact::SubmissionArtifactMetadata;
+pub use auth::{
+    AdminExistsResponse, CurrentAccountResponse, LoginRequest, LoginResponse, Permissions,
+    RecoverNpmRequest, RecoverNpmResponse, UpdateEmailRequest, VerifyEmailParams,
+};
+pub use banner::{BannerLevel, BannerResponse, UpdateBannerRequest};
+pub use bootstrap::{
+    BootstrapParams, BootstrapResponse, MembershipInfo, OwnCodeInfo, SubmissionCooldownInfo,
+};
+#[cfg(feature = "chaos")]
+pub use chaos::ChaosConfig;
 pub use classroom::{
-    ClassroomResponse, CreateClassroomRequest, LoginClassroomInfo, UpdateClassroomRequest, FinishExamRequest, UpdateUsersStatusRequest,
+    ClassroomResponse, ClassroomResponseV2, ClassroomStatus, CreateClassroomRequest,
+    CreateClassroomRequestV2, FinishExamRequest, LoginClassroomInfo, PostFinishPolicy,
+    RenderedTaskResponse, TaskInput, TaskResponse, UpdateClassroomRequest,
+    UpdateClassroomRequestV2, UpdateUsersStatusRequest,
+};
+pub use client_config::{
+    ClientBranding, ClientConfigParams, ClientConfigResponse, ClientFeatures, ClientLimits,
+    ExecutorLanguage,
+};
+pub use client_error::{
+    ClientErrorAck, ClientErrorKind, ClientErrorListParams, ClientErrorReport, ClientErrorResponse,
+    ErrorSource,
+};
+pub use deletion::{
+    ClassroomRowCounts, DeleteClassroomParams, DeleteClassroomResponse, DeleteIntentResponse,
+    DeletionOutcome,
+};
+pub use diagnostics::{
+    DiagnosticCheck, DiagnosticCheckKind, DiagnosticStatus, SelfTestParams, SelfTestResponse,
+};
+pub use dispute::{
+    CreateDisputeRequest, DisputeListParams, DisputeResponse, DisputeStatus, RespondDisputeRequest,
+};
+pub use exam::{
+    ExamClockResponse, ExamFinishedEvent, ExamNetworkProfileResponse, ExamTimeUpEvent,
+    ExamWarningEvent, GradingStatus, GradingStatusParams, GradingStatusResponse, LiveStatsResponse,
+    NetworkRule, NetworkSource, ProctorEvent, ProctorEventFilter, ProctorEventsParams,
+    ServerTimeResponse, SubmissionEvent, TaskDueEvent, UserConnectionCount, UserJoinedEvent,
+    UserStatusEvent, UserSubmittedEvent,
+};
+pub use executor::{
+    BalanceStrategy, ExecutorConfigResponse, ExecutorConfigSource, ExecutorInstanceStatus,
+    UpdateExecutorConfigRequest,
+};
+pub use export::{
+    CreateExportRequest, ExportDownloadParams, ExportJobResponse, ExportKind, ExportStatus,
+    ExportTimeParams, TimeDisplay,
+};
+pub use feedback::SubmissionFeedback;
+pub use fingerprint::{
+    FingerprintSettingsResponse, FingerprintSharingRequest, FingerprintSharingResponse,
+    UpdateFingerprintSettingsRequest,
+};
+pub use grading::{
+    GradeCaseResult, GradeExportParams, GradeFormat, GradeResultResponse, GradingScriptCase,
+    GradingScriptInput, GradingScriptRequest, GradingScriptResponse, GradingScriptVerdict,
+};
+pub use highlight::{HighlightFormat, HighlightParams, HighlightResponse, HighlightToken};
+pub use integrity::{
+    DevicePolicy, DevicePolicyRequest, DevicePolicyResponse, IntegrityAlertEvent,
+    IntegrityEventResponse,
+};
+pub use judge::{
+    Judge0RunStatus, Judge0SubmissionRequest, Judge0SubmissionRequestV2, Judge0SubmissionResponse,
+    Judge0SubmissionStatus, JudgeSubmitParams,
+};
+pub use login_event::{LoginAnomaly, LoginEventListParams, LoginEventResponse};
+pub use message::{
+    AckMessageRequest, MessageEvent, MessageRecipientResponse, MessageResponse, SendMessageRequest,
+};
+pub use packet::{
+    ExamPacket, PacketCaseResult, PacketFormat, PacketGrade, PacketParams, PacketTaskScore,
+    PacketVerdict,
+};
+pub use passkey::{
+    AssertionCredential, AssertionResponse, AttestationResponse, AuthenticatorSelection,
+    CredentialDescriptor, CredentialParameter, PasskeyCreationOptions, PasskeyLoginRequest,
+    PasskeyLoginStartRequest, PasskeyRegisterRequest, PasskeyRequestOptions, PasskeyResponse,
+    PasskeyUser, RegistrationCredential, RelyingParty,
+};
+pub use publish::{
+    ExamGate, ExamGateCheckResponse, PublishClassroomRequest, PublishClassroomResponse,
+    ReadinessReport, SmokeTestResult,
+};
+pub use purge::{
+    PurgeReceipt, PurgeReceiptVerification, PurgeRowCounts, PurgeStudentRequest,
+    VerifyPurgeReceiptRequest,
+};
+pub use replication::{HealthResponse, ReplicationRole, ReplicationStatusResponse};
+pub use rollover::{ClonedClassroom, RolloverClassroom, RolloverReport, RolloverRequest};
+pub use run_config::{
+    RunConfigListParams, RunConfigOptions, RunConfigResponse, SaveRunConfigRequest,
+};
+pub use sandbox::{SandboxFlags, SandboxFlagsResponse};
+pub use schedule::{
+    ScheduleConflict, ScheduleConflictKind, ScheduleConflictParams, ScheduleConflictsResponse,
+};
+pub use seat::{Seat, SeatAssignment, SeatMapRequest, SeatMapResponse};
+pub use settings::{ClassroomSettings, SettingsChange, SettingsVersionResponse};
+pub use submission::{SubmissionHistoryEntry, SubmissionListParams};
+pub use task::{CreateTaskRequest, TaskDetailResponse, UpdateTaskRequest};
+pub use task_time::{TaskActivity, TaskProgressRequest, TaskTimeSummary, TaskTimeSummaryResponse};
+pub use test_case::{
+    CreateTestCaseRequest, ExtractTestsRequest, ExtractTestsResponse, PracticeRunRequest,
+    ProposedTestCase, TestCaseFlag, TestCaseResponse, TestCaseResult, TestCaseStats,
+    TestCaseStatsParams, TestCaseStatsResponse, TestMode, TestRunResponse, UpdateTestCaseRequest,
+};
+pub use ticket::{TicketFormat, TicketParams};
+pub use user::{
+    CreateUserRequest, TransferConflict, TransferMode, TransferUsersRequest, TransferUsersResponse,
+    TransferredUser, UpdateUserPresetupRequest, UpdateUserRequest, UserResponse,
+};
+pub use validated::{LimitedFields, ValidatedJson};
+pub use verdict::{UpdateVerdictMessageRequest, VerdictMessageResponse};
+pub use version::{ApiVersion, Versioned, VersionedJson};
+pub use webhook::{
+    CreateWebhookRequest, WebhookCreatedResponse, WebhookResponse, WebhookTestResponse,
 };
-pub use judge::{Judge0SubmissionRequest, Judge0SubmissionResponse};
-pub use user::{CreateUserRequest, UpdateUserRequest, UserResponse};
@@ -0,0 +1,124 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{DisputeResponse, IntegrityEventResponse, SubmissionHistoryEntry};
+use crate::entities::submission;
+
+/// How the exam packet is delivered.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PacketFormat {
+    #[default]
+    Json,
+    /// ZIP with `packet.json` and the code as source files.
+    Zip,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PacketParams {
+    /// `json` (default) or `zip`.
+    #[serde(default)]
+    pub format: PacketFormat,
+}
+
+/// Everything recorded about one student's exam, for the student's file.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExamPacket {
+    pub classroom_id: i32,
+    pub classroom_name: String,
+    pub user_id: i32,
+    pub npm: String,
+    pub name: String,
+    /// Code saved on the roster entry when the packet was made.
+    pub saved_code: String,
+    /// Language of the saved code; the classroom's when the student never picked one.
+    pub language_id: i32,
+    /// Latest hand-in, with its code and the run the grade is based on.
+    pub final_submission: Option<SubmissionHistoryEntry>,
+    /// Grade of the latest hand-in; absent before it was graded.
+    pub grade: Option<PacketGrade>,
+    /// Verdict of every run and hand-in, oldest first.
+    pub submissions: Vec<PacketVerdict>,
+    pub integrity_events: Vec<IntegrityEventResponse>,
+    /// Grade disputes with the instructor's answers.
+    pub comments: Vec<DisputeResponse>,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PacketGrade {
+    pub submission_id: i32,
+    pub score: Option<i32>,
+    pub max_score: Option<i32>,
+    pub passed_cases: Option<i32>,
+    pub total_cases: Option<i32>,
+    pub graded_at: Option<DateTime<Utc>>,
+    /// Score per task, in task order.
+    pub tasks: Vec<PacketTaskScore>,
+    /// Every test case of the grading, hidden ones included.
+    pub cases: Vec<PacketCaseResult>,
+    /// Verdict printed by the classroom's grading script, if it has one.
+    #[schema(value_type = Option<Object>)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script_verdict: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PacketTaskScore {
+    pub task_id: i32,
+    pub title: String,
+    pub weight: i32,
+    /// Weighted points of the task's passed cases.
+    pub earned: i32,
+    pub possible: i32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PacketCaseResult {
+    pub test_case_id: i32,
+    pub task_id: i32,
+    pub hidden: bool,
+    pub passed: bool,
+    /// Points of the case after the task's weight.
+    pub points: i32,
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PacketVerdict {
+    pub submission_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<i32>,
+    pub is_final: bool,
+    pub auto_submitted: bool,
+    pub late: bool,
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_score: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&submission::Model> for PacketVerdict {
+    fn from(model: &submission::Model) -> Self {
+        Self {
+            submission_id: model.id,
+            task_id: model.task_id,
+            is_final: model.is_final,
+            auto_submitted: model.auto_submitted,
+            late: model.late,
+            status: model.status.clone(),
+            score: model.score,
+            max_score: model.max_score,
+            created_at: model.created_at,
+        }
+    }
+}
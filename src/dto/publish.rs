@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::classroom::ClassroomStatus;
+
+/// Optional reference solution for the smoke test. Without one, the starter code is
+/// run once against the first test case to check that Judge0 accepts it.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishClassroomRequest {
+    #[serde(default)]
+    pub source_code: Option<String>,
+    #[serde(default)]
+    pub language_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SmokeTestResult {
+    /// Whether the reference solution was run against every test case.
+    pub reference_solution: bool,
+    pub language_id: i32,
+    pub executed_cases: usize,
+    pub passed_cases: usize,
+    /// Judge0 could not run the submission at all, as opposed to a failing case.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub executor_error: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessReport {
+    pub classroom_id: i32,
+    /// True when there are no issues and the classroom can be published.
+    pub ready: bool,
+    pub issues: Vec<String>,
+    pub task_count: usize,
+    pub test_case_count: usize,
+    pub user_count: u64,
+    /// Absent when the structural checks already failed.
+    pub smoke_test: Option<SmokeTestResult>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishClassroomResponse {
+    pub classroom_id: i32,
+    pub status: ClassroomStatus,
+    pub published_at: DateTime<Utc>,
+    pub report: ReadinessReport,
+}
+
+/// Exam held back because the executor failed its smoke test before the window
+/// opened; students cannot start it until a later check passes.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExamGate {
+    pub gated_at: DateTime<Utc>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExamGateCheckResponse {
+    pub classroom_id: i32,
+    pub gated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gated_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
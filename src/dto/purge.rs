@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeStudentRequest {
+    /// The student's NPM, repeated to confirm the purge; it cannot be undone.
+    pub confirm_npm: String,
+}
+
+/// Rows a student purge removed, across every classroom the NPM was on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeRowCounts {
+    pub roster_entries: u64,
+    pub accounts: u64,
+    pub submissions: u64,
+    pub case_results: u64,
+    pub fingerprints: u64,
+    pub dead_letters: u64,
+    pub disputes: u64,
+    pub integrity_events: u64,
+    pub task_progress: u64,
+    pub run_configs: u64,
+    pub message_receipts: u64,
+    /// Stored classroom events addressed to or about the student; events also
+    /// addressed to others only lose the student from their audience.
+    pub classroom_events: u64,
+    pub login_events: u64,
+    pub client_errors: u64,
+    pub audit_entries: u64,
+    pub passkeys: u64,
+}
+
+impl std::fmt::Display for PurgeRowCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "roster_entries={}, accounts={}, submissions={}, case_results={}, fingerprints={}, dead_letters={}, disputes={}, integrity_events={}, task_progress={}, run_configs={}, message_receipts={}, classroom_events={}, login_events={}, client_errors={}, audit_entries={}, passkeys={}",
+            self.roster_entries,
+            self.accounts,
+            self.submissions,
+            self.case_results,
+            self.fingerprints,
+            self.dead_letters,
+            self.disputes,
+            self.integrity_events,
+            self.task_progress,
+            self.run_configs,
+            self.message_receipts,
+            self.classroom_events,
+            self.login_events,
+            self.client_errors,
+            self.audit_entries,
+            self.passkeys
+        )
+    }
+}
+
+/// Proof of a student purge, signed with the server secret. It holds no personal
+/// data: the NPM is only kept as a keyed hash.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeReceipt {
+    pub receipt_id: String,
+    /// Keyed hash of the purged NPM; `POST /api/admin/purge-receipts/verify` tells
+    /// whether a given NPM matches it.
+    pub subject_hash: String,
+    pub classroom_ids: Vec<i32>,
+    pub counts: PurgeRowCounts,
+    /// Keys the purged code was encrypted with. Backups and replication snapshots
+    /// taken before the purge stay readable with them until they are rotated out
+    /// and removed from `CODE_ENCRYPTION_PREVIOUS_KEYS`.
+    pub sealed_key_ids: Vec<String>,
+    /// Whether the deleted rows, and stale copies left by earlier updates, were
+    /// overwritten in the database file.
+    pub secure_delete: bool,
+    /// Account that ran the purge.
+    pub purged_by: i32,
+    pub purged_at: DateTime<Utc>,
+    pub signing_key_id: String,
+    /// Hex HMAC-SHA256 over the receipt with an empty `signature`.
+    pub signature: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyPurgeReceiptRequest {
+    pub receipt: PurgeReceipt,
+    /// Checked against `subjectHash` when given.
+    pub npm: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeReceiptVerification {
+    /// The signature matches and was made with the current server secret.
+    pub valid: bool,
+    /// Whether `npm` is the purged student; absent when no NPM was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject_matches: Option<bool>,
+}
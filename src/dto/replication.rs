@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Part a server plays in snapshot replication (`REPLICATION_ROLE`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplicationRole {
+    /// Replication is disabled.
+    Off,
+    /// Ships database snapshots to the standby.
+    Primary,
+    /// Receives snapshots and refuses writes until it is promoted.
+    Standby,
+}
+
+impl ReplicationRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReplicationRole::Off => "off",
+            ReplicationRole::Primary => "primary",
+            ReplicationRole::Standby => "standby",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "off" => Some(ReplicationRole::Off),
+            "primary" => Some(ReplicationRole::Primary),
+            "standby" => Some(ReplicationRole::Standby),
+            _ => None,
+        }
+    }
+}
+
+/// Response of `GET /api/admin/replication-status`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicationStatusResponse {
+    pub role: ReplicationRole,
+    pub interval_secs: u64,
+    /// Standby snapshots are shipped to (primary), or the primary promotion checks (standby).
+    pub peer_url: Option<String>,
+    /// When the newest snapshot the standby holds was taken on the primary.
+    pub snapshot_taken_at: Option<DateTime<Utc>>,
+    /// When that snapshot was shipped (primary) or received (standby).
+    pub last_transfer_at: Option<DateTime<Utc>>,
+    pub snapshot_bytes: Option<u64>,
+    /// Seconds of writes the standby would lose if it were promoted now.
+    pub lag_seconds: Option<i64>,
+    /// Why the last shipment failed; cleared by the next successful one.
+    pub last_error: Option<String>,
+    /// The lag is within three intervals and the last shipment succeeded.
+    pub healthy: bool,
+}
+
+/// Response of `GET /api/health`.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthResponse {
+    pub status: String,
+    pub role: ReplicationRole,
+    pub server_time: DateTime<Utc>,
+}
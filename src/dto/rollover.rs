@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RolloverRequest {
+    /// Term being closed. When omitted, every classroom not already archived and not
+    /// in `newTerm` is archived.
+    #[serde(default)]
+    pub previous_term: Option<String>,
+    pub new_term: String,
+    /// Assistants leaving the lab; their admin accounts become regular users.
+    #[serde(default)]
+    pub graduated_npms: Vec<String>,
+    /// Classrooms copied into the new term as drafts, with tasks, test cases and
+    /// grading script but without students.
+    #[serde(default)]
+    pub template_classroom_ids: Vec<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RolloverClassroom {
+    pub id: i32,
+    pub name: String,
+    pub term: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClonedClassroom {
+    pub template_id: i32,
+    pub id: i32,
+    pub name: String,
+    pub tasks: usize,
+    pub test_cases: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RolloverReport {
+    pub new_term: String,
+    pub archived: Vec<RolloverClassroom>,
+    pub cloned: Vec<ClonedClassroom>,
+    /// Accounts whose admin role was removed.
+    pub demoted: Vec<String>,
+    /// Graduated NPMs that had no account or were not admins.
+    pub unchanged: Vec<String>,
+}
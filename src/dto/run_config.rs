@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::entities::run_config;
+
+/// Executor options passed through to Judge0 with the saved run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RunConfigOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_id: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_time_limit: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiler_options: Option<String>,
+}
+
+/// Saves a run configuration; one with the same name in the same scope is replaced.
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveRunConfigRequest {
+    pub classroom_id: i32,
+    /// Task the run belongs to; omitted for runs offered on every task.
+    #[serde(default)]
+    pub task_id: Option<i32>,
+    pub name: String,
+    #[serde(default)]
+    pub stdin: String,
+    #[serde(default)]
+    pub command_line_arguments: Option<String>,
+    #[serde(default)]
+    pub options: RunConfigOptions,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct RunConfigListParams {
+    pub classroom_id: i32,
+    /// Only runs of this task plus the classroom-wide ones; all runs when omitted.
+    pub task_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RunConfigResponse {
+    pub id: i32,
+    pub classroom_id: i32,
+    pub task_id: Option<i32>,
+    pub name: String,
+    pub stdin: String,
+    pub command_line_arguments: Option<String>,
+    pub options: RunConfigOptions,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RunConfigResponse {
+    pub fn from_model(model: run_config::Model, classroom_id: i32) -> Self {
+        Self {
+            id: model.id,
+            classroom_id,
+            task_id: model.task_id,
+            name: model.name,
+            stdin: model.stdin,
+            command_line_arguments: model.command_line_arguments,
+            options: serde_json::from_str(&model.options).unwrap_or_default(),
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
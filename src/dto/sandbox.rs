@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::entities::classroom;
+
+/// Judge0 sandbox flags for a classroom's runs. Unset flags use the Judge0 instance's
+/// defaults.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxFlags {
+    /// Lets programs open network connections. Never allowed in exam classrooms, and
+    /// Judge0 must also be started with `ALLOW_ENABLE_NETWORK`.
+    #[serde(default)]
+    pub enable_network: Option<bool>,
+    /// Processes and threads a program may run, 1 to 120.
+    #[serde(default)]
+    pub max_processes_and_or_threads: Option<u32>,
+    /// Merges stderr into stdout, e.g. so `int 0x80` write traces show in order.
+    #[serde(default)]
+    pub redirect_stderr_to_stdout: Option<bool>,
+}
+
+impl SandboxFlags {
+    pub fn of(classroom: &classroom::Model) -> Self {
+        Self {
+            enable_network: classroom.sandbox_enable_network,
+            max_processes_and_or_threads: classroom
+                .sandbox_max_processes
+                .and_then(|count| u32::try_from(count).ok()),
+            redirect_stderr_to_stdout: classroom.sandbox_redirect_stderr,
+        }
+    }
+
+    pub fn apply(self, classroom_am: &mut classroom::ActiveModel) {
+        classroom_am.sandbox_enable_network = sea_orm::ActiveValue::Set(self.enable_network);
+        classroom_am.sandbox_max_processes = sea_orm::ActiveValue::Set(
+            self.max_processes_and_or_threads
+                .and_then(|count| i32::try_from(count).ok()),
+        );
+        classroom_am.sandbox_redirect_stderr =
+            sea_orm::ActiveValue::Set(self.redirect_stderr_to_stdout);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxFlagsResponse {
+    pub classroom_id: i32,
+    pub flags: SandboxFlags,
+}
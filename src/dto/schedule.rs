@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ScheduleConflictParams {
+    /// Only conflicts involving this classroom.
+    pub classroom_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleConflictKind {
+    /// The same NPMs are on the rosters of two exams whose windows overlap.
+    NpmOverlap,
+    /// More students sit exams at once than `EXAM_STUDENT_CAPACITY`.
+    OverCapacity,
+}
+
+/// A stretch of time in which scheduled exams clash.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleConflict {
+    pub kind: ScheduleConflictKind,
+    /// Exams running during the conflict, by id.
+    pub classroom_ids: Vec<i32>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// NPMs on both rosters; `npm_overlap` only.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub npms: Vec<String>,
+    /// Distinct students sitting exams during the conflict; `over_capacity` only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub students: Option<usize>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleConflictsResponse {
+    /// `EXAM_STUDENT_CAPACITY`; null when the capacity check is off.
+    pub capacity: Option<usize>,
+    pub conflicts: Vec<ScheduleConflict>,
+    pub checked_at: DateTime<Utc>,
+}
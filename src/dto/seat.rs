@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{entities::user, services::seating};
+
+/// Physical seat of a student in the exam room.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Seat {
+    /// 1-based row, counted from the front of the room.
+    pub row: i32,
+    /// 1-based column, counted from the left as seen from the front.
+    pub column: i32,
+    /// Row letter and column number, e.g. `B4`.
+    pub label: String,
+}
+
+impl Seat {
+    pub fn of(model: &user::Model) -> Option<Self> {
+        let (row, column) = (model.seat_row?, model.seat_column?);
+        Some(Self {
+            row,
+            column,
+            label: seating::label(row, column),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeatMapRequest {
+    /// Rows from front to back; each cell holds the NPM seated there, or `null` for
+    /// an empty seat or aisle. Replaces every assignment in the classroom: students
+    /// missing from the grid lose their seat.
+    pub grid: Vec<Vec<Option<String>>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeatAssignment {
+    pub user_id: i32,
+    pub npm: String,
+    pub name: String,
+    pub seat: Seat,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeatMapResponse {
+    pub classroom_id: i32,
+    /// The seat map in the same shape as [`SeatMapRequest::grid`], trimmed to the
+    /// last occupied row and column.
+    pub grid: Vec<Vec<Option<String>>>,
+    /// Assignments ordered by row, then column.
+    pub assignments: Vec<SeatAssignment>,
+    /// NPMs of students without a seat.
+    pub unseated: Vec<String>,
+}
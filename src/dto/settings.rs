@@ -0,0 +1,199 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{
+    classroom::PostFinishPolicy, integrity::DevicePolicy, sandbox::SandboxFlags,
+    test_case::TestMode,
+};
+use crate::entities::{classroom, classroom_settings_version};
+
+/// Classroom configuration covered by the settings history. Roster, tasks and the
+/// publication status are managed separately and never rolled back.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassroomSettings {
+    pub name: String,
+    pub programming_language: String,
+    pub language_locked: bool,
+    pub is_exam: bool,
+    pub test_code: String,
+    pub exam_start: Option<DateTime<Utc>>,
+    pub exam_end: Option<DateTime<Utc>>,
+    pub presetup_code: String,
+    pub test_mode: TestMode,
+    pub test_sample_size: i32,
+    #[serde(default = "default_practice_mode")]
+    pub practice_mode: bool,
+    pub grading_script: Option<String>,
+    pub grading_script_language_id: Option<i32>,
+    pub device_policy: DevicePolicy,
+    pub term: Option<String>,
+    pub output_limit_kb: Option<i32>,
+    pub max_code_chars: Option<i32>,
+    pub max_description_chars: Option<i32>,
+    #[serde(default)]
+    pub submission_cooldown_secs: Option<i32>,
+    #[serde(default = "default_post_finish_policy")]
+    pub post_finish_policy: PostFinishPolicy,
+    #[serde(default = "default_fingerprint_sharing")]
+    pub fingerprint_sharing: bool,
+    #[serde(default)]
+    pub sandbox: SandboxFlags,
+}
+
+impl ClassroomSettings {
+    pub fn from_model(classroom: &classroom::Model) -> Self {
+        Self {
+            name: classroom.name.clone(),
+            programming_language: classroom.programming_language.clone(),
+            language_locked: classroom.language_locked,
+            is_exam: classroom.is_exam,
+            test_code: classroom.test_code.clone(),
+            exam_start: classroom.exam_start,
+            exam_end: classroom.exam_end,
+            presetup_code: classroom.presetup_code.clone(),
+            test_mode: TestMode::from_str(&classroom.test_mode).unwrap_or(TestMode::Full),
+            test_sample_size: classroom.test_sample_size,
+            practice_mode: classroom.practice_mode,
+            grading_script: classroom.grading_script.clone(),
+            grading_script_language_id: classroom.grading_script_language_id,
+            device_policy: DevicePolicy::from_str(&classroom.device_policy)
+                .unwrap_or(DevicePolicy::Off),
+            term: classroom.term.clone(),
+            output_limit_kb: classroom.output_limit_kb,
+            max_code_chars: classroom.max_code_chars,
+            max_description_chars: classroom.max_description_chars,
+            submission_cooldown_secs: classroom.submission_cooldown_secs,
+            post_finish_policy: PostFinishPolicy::of(classroom),
+            fingerprint_sharing: classroom.fingerprint_sharing,
+            sandbox: SandboxFlags::of(classroom),
+        }
+    }
+
+    pub fn apply(self, classroom_am: &mut classroom::ActiveModel) {
+        classroom_am.name = sea_orm::ActiveValue::Set(self.name);
+        classroom_am.programming_language = sea_orm::ActiveValue::Set(self.programming_language);
+        classroom_am.language_locked = sea_orm::ActiveValue::Set(self.language_locked);
+        classroom_am.is_exam = sea_orm::ActiveValue::Set(self.is_exam);
+        classroom_am.test_code = sea_orm::ActiveValue::Set(self.test_code);
+        classroom_am.exam_start = sea_orm::ActiveValue::Set(self.exam_start);
+        classroom_am.exam_end = sea_orm::ActiveValue::Set(self.exam_end);
+        classroom_am.presetup_code = sea_orm::ActiveValue::Set(self.presetup_code);
+        classroom_am.test_mode = sea_orm::ActiveValue::Set(self.test_mode.as_str().to_owned());
+        classroom_am.test_sample_size = sea_orm::ActiveValue::Set(self.test_sample_size);
+        classroom_am.practice_mode = sea_orm::ActiveValue::Set(self.practice_mode);
+        classroom_am.grading_script = sea_orm::ActiveValue::Set(self.grading_script);
+        classroom_am.grading_script_language_id =
+            sea_orm::ActiveValue::Set(self.grading_script_language_id);
+        classroom_am.device_policy =
+            sea_orm::ActiveValue::Set(self.device_policy.as_str().to_owned());
+        classroom_am.term = sea_orm::ActiveValue::Set(self.term);
+        classroom_am.output_limit_kb = sea_orm::ActiveValue::Set(self.output_limit_kb);
+        classroom_am.max_code_chars = sea_orm::ActiveValue::Set(self.max_code_chars);
+        classroom_am.max_description_chars = sea_orm::ActiveValue::Set(self.max_description_chars);
+        classroom_am.submission_cooldown_secs =
+            sea_orm::ActiveValue::Set(self.submission_cooldown_secs);
+        classroom_am.post_finish_policy =
+            sea_orm::ActiveValue::Set(self.post_finish_policy.as_str().to_owned());
+        classroom_am.fingerprint_sharing = sea_orm::ActiveValue::Set(self.fingerprint_sharing);
+        self.sandbox.apply(classroom_am);
+    }
+}
+
+/// Snapshots from before practice mode existed had it on.
+fn default_practice_mode() -> bool {
+    true
+}
+
+/// Snapshots from before fingerprint sharing existed had it on.
+fn default_fingerprint_sharing() -> bool {
+    true
+}
+
+/// Operation that produced a settings version.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingsChange {
+    Update,
+    GradingScript,
+    DevicePolicy,
+    FingerprintSharing,
+    Sandbox,
+    Rollback,
+}
+
+impl SettingsChange {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SettingsChange::Update => "update",
+            SettingsChange::GradingScript => "grading_script",
+            SettingsChange::DevicePolicy => "device_policy",
+            SettingsChange::FingerprintSharing => "fingerprint_sharing",
+            SettingsChange::Sandbox => "sandbox",
+            SettingsChange::Rollback => "rollback",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "update" | "UPDATE" | "Update" => Some(SettingsChange::Update),
+            "grading_script" | "GRADING_SCRIPT" | "GradingScript" => {
+                Some(SettingsChange::GradingScript)
+            }
+            "device_policy" | "DEVICE_POLICY" | "DevicePolicy" => {
+                Some(SettingsChange::DevicePolicy)
+            }
+            "fingerprint_sharing" | "FINGERPRINT_SHARING" | "FingerprintSharing" => {
+                Some(SettingsChange::FingerprintSharing)
+            }
+            "sandbox" | "SANDBOX" | "Sandbox" => Some(SettingsChange::Sandbox),
+            "rollback" | "ROLLBACK" | "Rollback" => Some(SettingsChange::Rollback),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsVersionResponse {
+    pub version: i32,
+    pub change: SettingsChange,
+    pub changed_by: Option<i32>,
+    /// camelCase names of the settings that differ between `before` and `after`.
+    pub changed_fields: Vec<String>,
+    pub before: ClassroomSettings,
+    pub after: ClassroomSettings,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SettingsVersionResponse {
+    /// `None` when a stored snapshot no longer parses.
+    pub fn from_model(model: classroom_settings_version::Model) -> Option<Self> {
+        let before: serde_json::Value = serde_json::from_str(&model.before).ok()?;
+        let after: serde_json::Value = serde_json::from_str(&model.after).ok()?;
+        let changed_fields = match (before.as_object(), after.as_object()) {
+            (Some(before), Some(after)) => after
+                .iter()
+                .filter(|(key, value)| before.get(key.as_str()) != Some(value))
+                .map(|(key, _)| key.clone())
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        Some(Self {
+            version: model.version,
+            change: SettingsChange::from_str(&model.change).unwrap_or(SettingsChange::Update),
+            changed_by: model.changed_by,
+            changed_fields,
+            before: serde_json::from_value(before).ok()?,
+            after: serde_json::from_value(after).ok()?,
+            created_at: model.created_at,
+        })
+    }
+}
+
+/// Snapshots from before the post-finish policy existed always deactivated.
+fn default_post_finish_policy() -> PostFinishPolicy {
+    PostFinishPolicy::Deactivate
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::entities::submission;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct SubmissionListParams {
+    pub task_id: Option<i32>,
+    /// `true` for exam hand-ins only, `false` for runs only.
+    pub is_final: Option<bool>,
+    /// Only submissions older than this id, to page back through the history.
+    pub before: Option<i32>,
+    /// At most this many of the newest submissions; defaults to 100.
+    pub limit: Option<u64>,
+}
+
+/// One stored execution: a run through `POST /api/judge0/submissions` or an exam
+/// hand-in.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionHistoryEntry {
+    pub id: i32,
+    pub classroom_id: i32,
+    pub user_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<i32>,
+    pub language_id: i32,
+    pub is_final: bool,
+    pub source_code: String,
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    pub compile_output: Option<String>,
+    /// Judge0 status description; empty while a hand-in is still being graded.
+    pub status: Option<String>,
+    pub time: Option<String>,
+    pub memory: Option<i64>,
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_score: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<submission::Model> for SubmissionHistoryEntry {
+    fn from(model: submission::Model) -> Self {
+        Self {
+            id: model.id,
+            classroom_id: model.classroom_id,
+            user_id: model.user_id,
+            task_id: model.task_id,
+            language_id: model.language_id,
+            is_final: model.is_final,
+            source_code: model.source_code.into_inner(),
+            stdout: model.stdout,
+            stderr: model.stderr,
+            compile_output: model.compile_output,
+            status: model.status,
+            time: model.time,
+            memory: model.memory,
+            exit_code: model.exit_code,
+            score: model.score,
+            max_score: model.max_score,
+            created_at: model.created_at,
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{classroom::TaskResponse, validated::LimitedFields};
+use crate::{entities::task, error::AppError, services::field_limits::FieldLimits};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTaskRequest {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// Place of the task in the list, from 0; later tasks move down. Defaults to the end.
+    #[serde(default)]
+    pub position: Option<i32>,
+    #[serde(default)]
+    pub starter_code: Option<String>,
+    #[serde(default)]
+    pub expected_output: Option<String>,
+    /// Multiplier on the points of the task's test cases; defaults to 1.
+    #[serde(default)]
+    pub weight: Option<i32>,
+    #[serde(default)]
+    pub language_id: Option<i32>,
+    #[serde(default)]
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+impl LimitedFields for CreateTaskRequest {
+    type Body = Json<Self>;
+
+    fn check(&self, limits: &FieldLimits) -> Result<(), AppError> {
+        check_task_fields(
+            limits,
+            Some(&self.description),
+            self.starter_code.as_deref(),
+        )
+    }
+}
+
+/// Fields left out keep their value; an empty `starterCode` or `expectedOutput`
+/// clears it.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTaskRequest {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// New place of the task in the list; the tasks in between shift by one.
+    pub position: Option<i32>,
+    pub starter_code: Option<String>,
+    pub expected_output: Option<String>,
+    pub weight: Option<i32>,
+    pub language_id: Option<i32>,
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+impl LimitedFields for UpdateTaskRequest {
+    type Body = Json<Self>;
+
+    fn check(&self, limits: &FieldLimits) -> Result<(), AppError> {
+        check_task_fields(
+            limits,
+            self.description.as_deref(),
+            self.starter_code.as_deref(),
+        )
+    }
+}
+
+fn check_task_fields(
+    limits: &FieldLimits,
+    description: Option<&str>,
+    starter_code: Option<&str>,
+) -> Result<(), AppError> {
+    if let Some(description) = description {
+        limits.check_description("description", description)?;
+    }
+    if let Some(starter_code) = starter_code {
+        limits.check_code("starterCode", starter_code)?;
+    }
+    Ok(())
+}
+
+/// Task as instructors see it, with its reference output.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskDetailResponse {
+    #[serde(flatten)]
+    pub task: TaskResponse,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_output: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<task::Model> for TaskDetailResponse {
+    fn from(model: task::Model) -> Self {
+        let expected_output = model.expected_output.clone();
+        let (created_at, updated_at) = (model.created_at, model.updated_at);
+        Self {
+            task: TaskResponse::from(model),
+            expected_output,
+            created_at,
+            updated_at,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Task activity reported by the client: opening a task tab or autosaving an edit.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskActivity {
+    Opened,
+    Edited,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskProgressRequest {
+    #[schema(example = "51422582")]
+    pub npm: String,
+    pub event: TaskActivity,
+}
+
+/// Time spent on one task across the students who opened it. A student's time is
+/// the span between first opening the task and the last edit.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskTimeSummary {
+    pub task_id: i32,
+    pub position: i32,
+    pub title: String,
+    pub students_opened: usize,
+    pub students_edited: usize,
+    pub total_edits: i64,
+    pub total_seconds: i64,
+    /// Average over students who edited the task.
+    pub average_seconds: i64,
+    pub median_seconds: i64,
+    pub max_seconds: i64,
+    /// Percentage of the classroom's total tracked time spent on this task.
+    pub share_of_total: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskTimeSummaryResponse {
+    pub classroom_id: i32,
+    pub tasks: Vec<TaskTimeSummary>,
+    pub generated_at: DateTime<Utc>,
+}
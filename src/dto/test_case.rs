@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use axum::Json;
+
+use super::validated::LimitedFields;
+use crate::{entities::test_case, error::AppError, services::field_limits::FieldLimits};
+
+/// How many test cases a practice run executes.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TestMode {
+    /// Every test case of the task.
+    Full,
+    /// A weighted sample of `testSampleSize` cases, fixed per student and task.
+    Sampled,
+}
+
+impl TestMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TestMode::Full => "full",
+            TestMode::Sampled => "sampled",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "full" | "FULL" | "Full" => Some(TestMode::Full),
+            "sampled" | "SAMPLED" | "Sampled" => Some(TestMode::Sampled),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTestCaseRequest {
+    #[serde(default)]
+    pub stdin: String,
+    pub expected_output: String,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub points: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateTestCaseRequest {
+    pub stdin: Option<String>,
+    pub expected_output: Option<String>,
+    pub hidden: Option<bool>,
+    pub points: Option<i32>,
+    pub position: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractTestsRequest {
+    /// Create the proposed cases; otherwise they are only returned for review.
+    #[serde(default)]
+    pub confirm: bool,
+    /// `index` values of the proposals to create; defaults to every proposal that is
+    /// not a duplicate.
+    #[serde(default)]
+    pub indexes: Option<Vec<usize>>,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub points: Option<i32>,
+}
+
+/// Test case read from a sample input/output pair in the task description.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposedTestCase {
+    pub index: usize,
+    pub stdin: String,
+    pub expected_output: String,
+    /// Line of the description the expected output block starts on.
+    pub line: usize,
+    /// The task already has a case with the same stdin and expected output.
+    pub duplicate: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractTestsResponse {
+    pub proposals: Vec<ProposedTestCase>,
+    /// Cases created by a confirmed request; empty for a preview.
+    pub created: Vec<TestCaseResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCaseResponse {
+    pub id: i32,
+    pub task_id: i32,
+    pub position: i32,
+    pub stdin: String,
+    pub expected_output: String,
+    pub hidden: bool,
+    pub points: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<test_case::Model> for TestCaseResponse {
+    fn from(model: test_case::Model) -> Self {
+        Self {
+            id: model.id,
+            task_id: model.task_id,
+            position: model.position,
+            stdin: model.stdin,
+            expected_output: model.expected_output,
+            hidden: model.hidden,
+            points: model.points,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PracticeRunRequest {
+    pub npm: String,
+    pub source_code: String,
+    /// Defaults to the task's language override, then the server default.
+    #[serde(default)]
+    pub language_id: Option<i32>,
+}
+
+impl LimitedFields for PracticeRunRequest {
+    type Body = Json<Self>;
+
+    fn check(&self, limits: &FieldLimits) -> Result<(), AppError> {
+        limits.check_code("sourceCode", &self.source_code)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCaseResult {
+    pub test_case_id: i32,
+    pub hidden: bool,
+    pub passed: bool,
+    pub status: Option<String>,
+    /// Input, output and expected output are only returned for visible cases.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdin: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdout: Option<String>,
+    /// Whether `stdout` was cut at the classroom's output limit.
+    pub truncated: bool,
+    /// Size of stdout before truncation, in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdout_bytes: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_output: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TestRunResponse {
+    pub task_id: i32,
+    pub mode: TestMode,
+    pub total_cases: usize,
+    pub executed_cases: usize,
+    pub passed_cases: usize,
+    pub hidden_cases: usize,
+    pub hidden_passed_cases: usize,
+    pub results: Vec<TestCaseResult>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct TestCaseStatsParams {
+    /// Students who must have run a case before it is flagged; defaults to 3.
+    pub min_students: Option<usize>,
+}
+
+/// Hint that a test case may need attention.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TestCaseFlag {
+    /// No student passed it; the case or its expected output may be wrong.
+    NeverPassed,
+    /// Every student passed it; it may not test anything.
+    AlwaysPassed,
+}
+
+/// Pass/fail counts of one test case over the graded submissions of a classroom.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCaseStats {
+    pub test_case_id: i32,
+    pub task_id: i32,
+    pub task_title: String,
+    pub position: i32,
+    pub hidden: bool,
+    pub points: i32,
+    pub runs: u64,
+    pub passed_runs: u64,
+    /// Share of runs that passed, from 0 to 1; `null` before the first run.
+    pub pass_rate: Option<f64>,
+    pub students_attempted: usize,
+    /// Students with at least one passing run.
+    pub students_passed: usize,
+    /// Judge0 status of the failing runs to how often it occurred.
+    pub failure_statuses: BTreeMap<String, u64>,
+    pub flag: Option<TestCaseFlag>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCaseStatsResponse {
+    pub classroom_id: i32,
+    pub min_students: usize,
+    /// In task order, then test case order.
+    pub test_cases: Vec<TestCaseStats>,
+    pub generated_at: DateTime<Utc>,
+}
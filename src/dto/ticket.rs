@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Rendering of printed exam tickets.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TicketFormat {
+    /// Plain text, one ticket after another between cut lines; reads well in screen
+    /// readers and prints on any printer.
+    #[default]
+    Txt,
+    /// A4 PDF with real text, whole tickets per page.
+    Pdf,
+}
+
+impl TicketFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TicketFormat::Txt => "txt",
+            TicketFormat::Pdf => "pdf",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "txt" | "TXT" | "Txt" => Some(TicketFormat::Txt),
+            "pdf" | "PDF" | "Pdf" => Some(TicketFormat::Pdf),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct TicketParams {
+    /// `txt` (default) or `pdf`.
+    #[serde(default)]
+    pub format: TicketFormat,
+    /// Only the ticket of this roster entry, e.g. to reprint one after its code was used.
+    pub user_id: Option<i32>,
+}
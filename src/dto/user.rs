@@ -2,7 +2,10 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::entities::user;
+use axum::Json;
+
+use super::{seat::Seat, validated::LimitedFields};
+use crate::{entities::user, error::AppError, services::field_limits::FieldLimits};
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -12,6 +15,14 @@ pub struct CreateUserRequest {
     pub code: String,
 }
 
+impl LimitedFields for CreateUserRequest {
+    type Body = Json<Self>;
+
+    fn check(&self, limits: &FieldLimits) -> Result<(), AppError> {
+        limits.check_code("code", &self.code)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateUserRequest {
@@ -21,28 +32,179 @@ pub struct UpdateUserRequest {
     pub active: Option<bool>,
 }
 
-#[derive(Debug, Serialize, ToSchema)]
+impl LimitedFields for UpdateUserRequest {
+    type Body = Json<Self>;
+
+    fn check(&self, limits: &FieldLimits) -> Result<(), AppError> {
+        match &self.code {
+            Some(code) => limits.check_code("code", code),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateUserPresetupRequest {
+    /// Starter code for this student only; `null` falls back to the classroom presetup.
+    #[serde(default)]
+    pub presetup_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserResponse {
     pub id: i32,
     pub name: String,
     pub npm: String,
+    /// Empty when the caller may not read it; see `codeRedacted`.
     pub code: String,
+    /// The code was withheld from the caller; only its length is reported.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub code_redacted: bool,
+    /// Length of the withheld code in characters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_length: Option<usize>,
     pub active: bool,
+    /// Set while practice runs and hand-ins are locked after finishing the exam.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submissions_locked_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presetup_override: Option<String>,
+    /// When the code was last saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_updated_at: Option<DateTime<Utc>>,
+    /// Revision of the saved code, raised by one on every save.
+    #[serde(default)]
+    pub code_seq: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seat: Option<Seat>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 impl From<user::Model> for UserResponse {
     fn from(model: user::Model) -> Self {
+        let seat = Seat::of(&model);
         Self {
             id: model.id,
             name: model.name,
             npm: model.npm,
-            code: model.code,
+            code: model.code.into_inner(),
+            code_redacted: false,
+            code_length: None,
             active: model.active,
+            submissions_locked_at: model.submissions_locked_at,
+            seat,
+            presetup_override: model.presetup_override,
+            code_updated_at: model.code_updated_at,
+            code_seq: model.code_seq,
             created_at: model.created_at,
             updated_at: model.updated_at,
         }
     }
 }
+
+impl UserResponse {
+    /// Withholds the code and the per-student starter code, keeping the code length.
+    pub fn redacted(mut self) -> Self {
+        self.code_length = Some(self.code.chars().count());
+        self.code = String::new();
+        self.code_redacted = true;
+        self.presetup_override = None;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferMode {
+    /// The roster entries leave the source classroom.
+    #[default]
+    Move,
+    /// The source classroom keeps its entries; the target gets copies.
+    Copy,
+}
+
+impl TransferMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferMode::Move => "move",
+            TransferMode::Copy => "copy",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "move" => Some(TransferMode::Move),
+            "copy" => Some(TransferMode::Copy),
+            _ => None,
+        }
+    }
+}
+
+/// What to do when the target classroom already enrolls a transferred NPM.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferConflict {
+    /// Abort the whole transfer with `409`.
+    #[default]
+    Fail,
+    /// Leave both entries as they are and report the NPM.
+    Skip,
+    /// Delete the target's entry, with its submissions, before transferring.
+    Replace,
+}
+
+impl TransferConflict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferConflict::Fail => "fail",
+            TransferConflict::Skip => "skip",
+            TransferConflict::Replace => "replace",
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "fail" => Some(TransferConflict::Fail),
+            "skip" => Some(TransferConflict::Skip),
+            "replace" => Some(TransferConflict::Replace),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferUsersRequest {
+    pub user_ids: Vec<i32>,
+    pub target_classroom_id: i32,
+    #[serde(default)]
+    pub mode: TransferMode,
+    #[serde(default)]
+    pub on_conflict: TransferConflict,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferredUser {
+    pub source_user_id: i32,
+    /// Same as the source id when moved.
+    pub target_user_id: i32,
+    pub npm: String,
+    pub submissions: u64,
+    /// The target's previous entry for this NPM was deleted.
+    pub replaced: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferUsersResponse {
+    pub mode: TransferMode,
+    pub source_classroom_id: i32,
+    pub target_classroom_id: i32,
+    pub transferred: Vec<TransferredUser>,
+    /// NPMs already enrolled in the target, left untouched under `skip`.
+    pub skipped: Vec<String>,
+}
@@ -0,0 +1,94 @@
+use axum::{
+    Json, async_trait,
+    extract::{FromRequest, FromRequestParts, RawPathParams, Request},
+    response::{IntoResponse, Response},
+};
+
+use super::version::VersionedJson;
+use crate::{error::AppError, services::field_limits::FieldLimits, state::AppState};
+
+/// Body extractor a [`LimitedFields`] request is read with.
+pub trait JsonBody<T> {
+    fn into_body(self) -> T;
+}
+
+impl<T> JsonBody<T> for Json<T> {
+    fn into_body(self) -> T {
+        self.0
+    }
+}
+
+impl<T> JsonBody<T> for VersionedJson<T> {
+    fn into_body(self) -> T {
+        self.0
+    }
+}
+
+/// Request DTO with code or descriptions held to the classroom's character limits.
+pub trait LimitedFields: Sized {
+    /// [`Json<Self>`], or [`VersionedJson<Self>`] for versioned bodies.
+    type Body: FromRequest<AppState> + JsonBody<Self>;
+
+    /// Classroom named in the body, for routes without one in the path.
+    fn classroom_id(&self) -> Option<i32> {
+        None
+    }
+
+    /// Student NPM in the body; picks the classroom when it is their only enrolment.
+    fn npm(&self) -> Option<&str> {
+        None
+    }
+
+    /// `maxCodeChars` and `maxDescriptionChars` the body sets for its own classroom.
+    fn own_limits(&self) -> (Option<i32>, Option<i32>) {
+        (None, None)
+    }
+
+    fn check(&self, limits: &FieldLimits) -> Result<(), AppError>;
+}
+
+/// JSON body extractor that rejects code and descriptions longer than the limits of
+/// the classroom in the path (`:id` or `:classroom_id`) or the body, answering
+/// `422 field_too_long`.
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T> FromRequest<AppState> for ValidatedJson<T>
+where
+    T: LimitedFields + Send,
+    <T::Body as FromRequest<AppState>>::Rejection: IntoResponse,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &AppState) -> Result<Self, Self::Rejection> {
+        let (mut parts, body) = req.into_parts();
+        let path_classroom = RawPathParams::from_request_parts(&mut parts, state)
+            .await
+            .ok()
+            .and_then(|params| {
+                params
+                    .iter()
+                    .find(|(name, _)| matches!(*name, "id" | "classroom_id"))
+                    .and_then(|(_, value)| value.parse::<i32>().ok())
+            });
+        let payload = T::Body::from_request(Request::from_parts(parts, body), state)
+            .await
+            .map_err(IntoResponse::into_response)?
+            .into_body();
+
+        let (code_chars, description_chars) = payload.own_limits();
+        let limits = FieldLimits::resolve(
+            state,
+            path_classroom.or_else(|| payload.classroom_id()),
+            payload.npm(),
+        )
+        .await
+        .map_err(IntoResponse::into_response)?
+        .with_overrides(code_chars, description_chars);
+        payload
+            .check(&limits)
+            .map_err(IntoResponse::into_response)?;
+
+        Ok(ValidatedJson(payload))
+    }
+}
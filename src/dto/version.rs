@@ -0,0 +1,159 @@
+use axum::{
+    Json, async_trait,
+    extract::{FromRequest, FromRequestParts, Request},
+    http::{
+        HeaderMap, HeaderName, HeaderValue,
+        header::{ACCEPT, CONTENT_TYPE},
+        request::Parts,
+    },
+    response::{IntoResponse, Response},
+};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::error::AppError;
+
+pub const ACCEPT_VERSION: HeaderName = HeaderName::from_static("accept-version");
+pub const API_VERSION: HeaderName = HeaderName::from_static("api-version");
+
+/// Wire format revision negotiated per request.
+///
+/// Clients pick a version with `Accept-Version: 2` or a `profile=v2` parameter on
+/// `Content-Type`/`Accept`; requests without either are served as v1 so existing
+/// clients keep working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "1",
+            ApiVersion::V2 => "2",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value
+            .trim()
+            .trim_matches('"')
+            .trim_start_matches(['v', 'V'])
+        {
+            "1" => Some(ApiVersion::V1),
+            "2" => Some(ApiVersion::V2),
+            _ => None,
+        }
+    }
+
+    fn from_headers(headers: &HeaderMap) -> Result<Self, AppError> {
+        if let Some(value) = headers.get(ACCEPT_VERSION) {
+            let value = value.to_str().unwrap_or_default();
+            return Self::parse(value).ok_or_else(|| {
+                AppError::BadRequest(format!("unsupported Accept-Version: {value}"))
+            });
+        }
+
+        for header in [CONTENT_TYPE, ACCEPT] {
+            let Some(profile) = headers
+                .get(header)
+                .and_then(|value| value.to_str().ok())
+                .and_then(media_type_profile)
+            else {
+                continue;
+            };
+            return Self::parse(profile)
+                .ok_or_else(|| AppError::BadRequest(format!("unsupported profile: {profile}")));
+        }
+
+        Ok(ApiVersion::V1)
+    }
+}
+
+fn media_type_profile(value: &str) -> Option<&str> {
+    value
+        .split([';', ','])
+        .filter_map(|param| param.trim().strip_prefix("profile="))
+        .next()
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ApiVersion
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Self::from_headers(&parts.headers)
+    }
+}
+
+/// Response DTO whose canonical shape is the latest version and can be converted
+/// down for older clients.
+pub trait Downgrade: Serialize {
+    type V1: Serialize;
+
+    fn downgrade(self) -> Self::V1;
+}
+
+impl<T: Downgrade> Downgrade for Vec<T> {
+    type V1 = Vec<T::V1>;
+
+    fn downgrade(self) -> Self::V1 {
+        self.into_iter().map(Downgrade::downgrade).collect()
+    }
+}
+
+/// JSON response rendered in the version the client negotiated.
+pub struct Versioned<T>(pub ApiVersion, pub T);
+
+impl<T: Downgrade> IntoResponse for Versioned<T> {
+    fn into_response(self) -> Response {
+        let Versioned(version, body) = self;
+        let mut response = match version {
+            ApiVersion::V1 => Json(body.downgrade()).into_response(),
+            ApiVersion::V2 => Json(body).into_response(),
+        };
+        response
+            .headers_mut()
+            .insert(API_VERSION, HeaderValue::from_static(version.as_str()));
+        response
+    }
+}
+
+/// Request DTO that can be built from every supported wire version.
+pub trait VersionedBody: Sized {
+    type V1: DeserializeOwned;
+    type V2: DeserializeOwned;
+
+    fn from_v1(body: Self::V1) -> Self;
+    fn from_v2(body: Self::V2) -> Self;
+}
+
+/// JSON body extractor that deserializes the negotiated version and converts it.
+pub struct VersionedJson<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequest<S> for VersionedJson<T>
+where
+    S: Send + Sync,
+    T: VersionedBody,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let version = ApiVersion::from_headers(req.headers())?;
+        let body = match version {
+            ApiVersion::V1 => Json::<T::V1>::from_request(req, state)
+                .await
+                .map(|Json(body)| T::from_v1(body)),
+            ApiVersion::V2 => Json::<T::V2>::from_request(req, state)
+                .await
+                .map(|Json(body)| T::from_v2(body)),
+        }
+        .map_err(|rejection| AppError::BadRequest(rejection.body_text()))?;
+
+        Ok(VersionedJson(body))
+    }
+}
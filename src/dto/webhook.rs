@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::entities::webhook_endpoint;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookRequest {
+    pub name: String,
+    pub url: String,
+    /// Signing secret; a random one is generated when omitted.
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookResponse {
+    pub id: i32,
+    pub name: String,
+    pub url: String,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<webhook_endpoint::Model> for WebhookResponse {
+    fn from(model: webhook_endpoint::Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            url: model.url,
+            active: model.active,
+            created_at: model.created_at,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+/// Returned once on creation; the secret cannot be read back afterwards.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookCreatedResponse {
+    #[serde(flatten)]
+    pub webhook: WebhookResponse,
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTestResponse {
+    pub success: bool,
+    /// HTTP status of the receiver, absent when it could not be reached.
+    pub status_code: Option<u16>,
+    pub duration_ms: u64,
+    /// `X-Signature` header that was sent.
+    pub signature: String,
+    pub error: Option<String>,
+}
@@ -8,6 +8,13 @@ pub struct Model {
     #[sea_orm(unique)]
     pub npm: String,
     pub role: String,
+    /// Contact address for verification and NPM recovery mails.
+    pub email: Option<String>,
+    /// Set once the current `email` was confirmed through the emailed link.
+    pub email_verified_at: Option<DateTimeUtc>,
+    /// `active`, or `pending` while an unknown NPM waits for admin approval.
+    pub status: String,
+    pub approved_at: Option<DateTimeUtc>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
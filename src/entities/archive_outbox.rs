@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+
+/// Final submission waiting to be copied to the archival sink, or already copied.
+/// Written in the same transaction as the submission; see `services::archive`.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "archive_outbox")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(unique)]
+    pub submission_id: i32,
+    pub created_at: DateTimeUtc,
+    /// When the sink acknowledged the copy; `None` while it is pending.
+    pub archived_at: Option<DateTimeUtc>,
+    pub attempts: i32,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub last_error: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::submission::Entity",
+        from = "Column::SubmissionId",
+        to = "super::submission::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Submission,
+}
+
+impl Related<super::submission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Submission.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,34 @@
+use sea_orm::entity::prelude::*;
+
+/// Action attributed to an account: its logins, every state-changing request made
+/// with a staff token, and the classrooms it deleted. Read back in the account activity report.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "audit_log")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    /// Kept when the account is deleted, so its history stays reportable by id.
+    pub account_id: i32,
+    /// `login`, `request` or `deletion`.
+    pub kind: String,
+    /// Login method (`npm`, `passkey`), the request's method and path, or what a
+    /// deletion removed.
+    pub detail: String,
+    /// Classroom the login resolved to, or the deleted classroom.
+    pub classroom_id: Option<i32>,
+    /// Response status of a recorded request.
+    pub status: Option<i32>,
+    pub request_id: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations defined for AuditLog entity");
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+
+/// Announcement shown to every client. There is at most one row, with id 1.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "banner")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i32,
+    #[sea_orm(column_type = "Text")]
+    pub message: String,
+    pub level: String,
+    /// Shown from this instant on; immediately when unset.
+    pub starts_at: Option<DateTimeUtc>,
+    /// Hidden after this instant; kept until cleared when unset.
+    pub ends_at: Option<DateTimeUtc>,
+    pub updated_by: Option<i32>,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
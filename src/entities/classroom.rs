@@ -8,12 +8,58 @@ pub struct Model {
     pub name: String,
     pub programming_language: String,
     pub language_locked: bool,
+    /// Legacy JSON task list; task content now lives in the `tasks` table.
     pub tasks: String,
     pub is_exam: bool,
     pub test_code: String,
     pub exam_start: Option<DateTimeUtc>,
     pub exam_end: Option<DateTimeUtc>,
     pub presetup_code: String,
+    pub test_mode: String,
+    pub test_sample_size: i32,
+    /// Submissions answer with full test-case results when on and with a receipt only
+    /// when off, as during exams; see `services::feedback`.
+    pub practice_mode: bool,
+    /// Instructor-supplied grader run on every graded submission, see `services::grading`.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub grading_script: Option<String>,
+    pub grading_script_language_id: Option<i32>,
+    /// `draft` classrooms are being prepared and hidden from students; `published` are live.
+    pub status: String,
+    pub published_at: Option<DateTimeUtc>,
+    /// Device binding during exams: `off`, `flag` or `block`.
+    pub device_policy: String,
+    /// Academic term label, e.g. `2025/2026 Ganjil`; used by the semester rollover.
+    pub term: Option<String>,
+    /// Stdout/stderr cap per run in KB; `None` uses the server's `OUTPUT_LIMIT_KB`.
+    pub output_limit_kb: Option<i32>,
+    /// Longest student code accepted, in characters; `None` uses `MAX_CODE_CHARS`.
+    pub max_code_chars: Option<i32>,
+    /// Longest task description, in characters; `None` uses `MAX_DESCRIPTION_CHARS`.
+    pub max_description_chars: Option<i32>,
+    /// Minimum seconds between graded submissions of one student; `None` disables it.
+    pub submission_cooldown_secs: Option<i32>,
+    /// What finishing the exam does to the student: `deactivate`, `keep_active` or
+    /// `lock_submissions`; see `services::post_finish`.
+    pub post_finish_policy: String,
+    /// Final submissions are fingerprinted into the cross-classroom store and checked
+    /// against earlier classrooms; see `services::fingerprint`.
+    pub fingerprint_sharing: bool,
+    /// Judge0 sandbox flags of student runs; `None` uses the Judge0 instance's default.
+    /// See `services::sandbox`.
+    pub sandbox_enable_network: Option<bool>,
+    pub sandbox_max_processes: Option<i32>,
+    pub sandbox_redirect_stderr: Option<bool>,
+    /// Set while the exam is held back because the executor failed its smoke test.
+    pub exam_gated_at: Option<DateTimeUtc>,
+    pub exam_gate_reason: Option<String>,
+    /// Last time a smoke submission ran for this classroom's exam window.
+    pub executor_checked_at: Option<DateTimeUtc>,
+    /// Judge0 id `programming_language` maps to on the executor; see
+    /// `services::language_map`.
+    pub resolved_language_id: Option<i32>,
+    /// Set while no Judge0 language matches `programming_language`.
+    pub language_missing_since: Option<DateTimeUtc>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
@@ -22,6 +68,10 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::user::Entity")]
     Users,
+    #[sea_orm(has_many = "super::exam_pause::Entity")]
+    ExamPauses,
+    #[sea_orm(has_many = "super::task::Entity")]
+    Tasks,
 }
 
 impl Related<super::user::Entity> for Entity {
@@ -30,4 +80,27 @@ impl Related<super::user::Entity> for Entity {
     }
 }
 
+impl Related<super::exam_pause::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ExamPauses.def()
+    }
+}
+
+impl Related<super::task::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Tasks.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub fn is_draft(&self) -> bool {
+        self.status == "draft"
+    }
+
+    /// Only published classrooms are visible to students; drafts and archived ones are not.
+    pub fn is_published(&self) -> bool {
+        self.status == "published"
+    }
+}
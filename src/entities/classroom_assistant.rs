@@ -0,0 +1,45 @@
+use sea_orm::entity::prelude::*;
+
+/// Assistant account assigned to a classroom; it may read the code of that
+/// classroom's students.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "classroom_assistants")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub classroom_id: i32,
+    pub account_id: i32,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::classroom::Entity",
+        from = "Column::ClassroomId",
+        to = "super::classroom::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Classroom,
+    #[sea_orm(
+        belongs_to = "super::account::Entity",
+        from = "Column::AccountId",
+        to = "super::account::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Account,
+}
+
+impl Related<super::classroom::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Classroom.def()
+    }
+}
+
+impl Related<super::account::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Account.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+
+/// Persisted classroom event; the id doubles as the replay sequence number.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "classroom_events")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub classroom_id: i32,
+    /// SSE event name, e.g. `message` or `submission`.
+    pub kind: String,
+    /// JSON array of recipient user ids; absent when addressed to the whole classroom.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub audience: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub payload: String,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::classroom::Entity",
+        from = "Column::ClassroomId",
+        to = "super::classroom::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Classroom,
+}
+
+impl Related<super::classroom::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Classroom.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,41 @@
+use sea_orm::entity::prelude::*;
+
+/// One change to a classroom's settings, with JSON snapshots (`ClassroomSettings`)
+/// from before and after it. Versions count up from 1 per classroom.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "classroom_settings_versions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub classroom_id: i32,
+    pub version: i32,
+    /// What made the change: `update`, `grading_script`, `device_policy`,
+    /// `fingerprint_sharing` or `rollback`.
+    pub change: String,
+    #[sea_orm(column_type = "Text")]
+    pub before: String,
+    #[sea_orm(column_type = "Text")]
+    pub after: String,
+    /// Account that made the change, when the request carried a token.
+    pub changed_by: Option<i32>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::classroom::Entity",
+        from = "Column::ClassroomId",
+        to = "super::classroom::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Classroom,
+}
+
+impl Related<super::classroom::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Classroom.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
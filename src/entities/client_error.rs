@@ -0,0 +1,35 @@
+use sea_orm::entity::prelude::*;
+
+/// A failure reported by the frontend, or a server error response, kept so both sides
+/// of an incident can be lined up by request id.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "client_errors")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    /// `client` for frontend reports, `server` for 5xx responses.
+    pub source: String,
+    /// `exception`, `request` or, for server rows, `response`.
+    pub kind: String,
+    #[sea_orm(column_type = "Text")]
+    pub message: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub stack: Option<String>,
+    pub url: Option<String>,
+    pub method: Option<String>,
+    pub status: Option<i32>,
+    /// `X-Request-Id` of the backend request involved, if any.
+    pub request_id: Option<String>,
+    pub classroom_id: Option<i32>,
+    pub npm: Option<String>,
+    pub user_agent: Option<String>,
+    /// Extra JSON the frontend attached, e.g. the failed request's payload shape.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub context: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
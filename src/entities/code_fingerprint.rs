@@ -0,0 +1,24 @@
+use sea_orm::entity::prelude::*;
+
+/// One winnowed k-gram hash of a final submission, kept across classrooms and
+/// semesters so copied solutions can be recognised later. Only keyed hashes are
+/// stored: neither the code nor the student's NPM can be read back from a row. Rows
+/// outlive the classroom and submission they came from until the retention period
+/// set in [`super::fingerprint_settings`] passes.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "code_fingerprints")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub hash: i64,
+    pub submission_id: i32,
+    pub classroom_id: i32,
+    /// Keyed hash of the author's NPM, so a student's own earlier work is not flagged.
+    pub owner: i64,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,27 @@
+use sea_orm::entity::prelude::*;
+
+/// Counters for one UTC day, rolled up from the raw tables so charts do not have to
+/// scan submissions. One row per day and classroom.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "daily_stats")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub day: Date,
+    /// `0` for activity not tied to a classroom, such as admin logins.
+    pub classroom_id: i32,
+    pub logins: i64,
+    pub submissions: i64,
+    pub final_submissions: i64,
+    /// Students who submitted, opened or edited a task that day.
+    pub active_users: i64,
+    /// JSON object of Judge0 status to submission count.
+    #[sea_orm(column_type = "Text")]
+    pub verdicts: String,
+    pub rolled_up_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
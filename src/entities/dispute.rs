@@ -0,0 +1,58 @@
+use sea_orm::entity::prelude::*;
+
+/// A student's objection to the grade of a submission and the instructor's answer.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "disputes")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub submission_id: i32,
+    /// Classroom user that raised the dispute.
+    pub user_id: i32,
+    #[sea_orm(column_type = "Text")]
+    pub message: String,
+    /// `open`, `resolved` or `rejected`.
+    pub status: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub response: Option<String>,
+    /// Account that answered the dispute.
+    pub responded_by: Option<i32>,
+    pub regraded: bool,
+    /// Score before and after the regrade triggered by the answer, if any.
+    pub previous_score: Option<i32>,
+    pub regraded_score: Option<i32>,
+    pub created_at: DateTimeUtc,
+    pub responded_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::submission::Entity",
+        from = "Column::SubmissionId",
+        to = "super::submission::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Submission,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::submission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Submission.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,30 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "exam_pauses")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub classroom_id: i32,
+    pub paused_at: DateTimeUtc,
+    pub resumed_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::classroom::Entity",
+        from = "Column::ClassroomId",
+        to = "super::classroom::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Classroom,
+}
+
+impl Related<super::classroom::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Classroom.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
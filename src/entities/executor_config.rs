@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+
+/// Judge0 instances set through the admin API, overriding the environment. There is
+/// at most one row, with id 1.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "executor_config")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i32,
+    /// JSON array of Judge0 base URLs.
+    #[sea_orm(column_type = "Text")]
+    pub base_urls: String,
+    pub strategy: String,
+    pub eject_after_failures: i32,
+    pub health_check_interval_secs: i64,
+    pub updated_by: Option<i32>,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,58 @@
+use sea_orm::entity::prelude::*;
+
+/// An export built in the background; the finished file lives in the export storage
+/// under [`Model::storage_key`] until `expires_at`.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "export_jobs")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub classroom_id: i32,
+    /// `grades`, `task_times` or `archive`.
+    pub kind: String,
+    /// Grade sheet layout for `grades` and `archive`: `csv`, `moodle` or `canvas`.
+    pub format: String,
+    /// `utc` or `local`, resolved when the job was requested.
+    pub time_display: String,
+    /// `queued`, `running`, `done` or `failed`.
+    pub status: String,
+    /// Percent complete, 0 to 100.
+    pub progress: i32,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub error: Option<String>,
+    pub file_name: Option<String>,
+    pub content_type: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub requested_by: i32,
+    pub created_at: DateTimeUtc,
+    pub started_at: Option<DateTimeUtc>,
+    pub finished_at: Option<DateTimeUtc>,
+    /// When the artifact is deleted; downloads fail afterwards.
+    pub expires_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::classroom::Entity",
+        from = "Column::ClassroomId",
+        to = "super::classroom::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Classroom,
+}
+
+impl Related<super::classroom::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Classroom.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Name of the artifact in the export storage.
+    pub fn storage_key(&self) -> String {
+        format!("export-{}", self.id)
+    }
+}
@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+
+/// Retention of shared code fingerprints set through the admin API, overriding
+/// `FINGERPRINT_RETENTION_DAYS`. There is at most one row, with id 1.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "fingerprint_settings")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: i32,
+    /// Days a fingerprint is kept; 0 keeps them forever.
+    pub retention_days: i32,
+    pub updated_by: Option<i32>,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
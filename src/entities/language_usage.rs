@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+
+/// How often each Judge0 language was used on one UTC day, per classroom. Runs are
+/// counted as they happen; submissions are rolled up with the daily stats.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "language_usage")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub day: Date,
+    /// `0` for runs not tied to a classroom, such as anonymous playground runs.
+    pub classroom_id: i32,
+    pub language_id: i32,
+    /// Executions through the playground and practice runs.
+    pub runs: i64,
+    /// Stored submissions, graded or not.
+    pub submissions: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,28 @@
+use sea_orm::entity::prelude::*;
+
+/// An attempt at NPM login, successful or not; see `services::login_audit`.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "login_events")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    /// NPM as sent, trimmed; kept even when no account has it.
+    pub npm: String,
+    pub account_id: Option<i32>,
+    /// Address of the client connection.
+    pub ip: String,
+    pub success: bool,
+    /// Error message of a refused attempt.
+    pub failure: Option<String>,
+    /// Classroom the NPM resolved to, if it is on a published roster.
+    pub classroom_id: Option<i32>,
+    /// Comma-separated anomaly flags (`multiple_ips`, `outside_exam_window`); empty
+    /// when the attempt looked normal.
+    pub flags: String,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,3 +1,34 @@
 pub mod account;
+pub mod archive_outbox;
+pub mod audit_log;
+pub mod banner;
 pub mod classroom;
+pub mod classroom_assistant;
+pub mod classroom_event;
+pub mod classroom_settings_version;
+pub mod client_error;
+pub mod code_fingerprint;
+pub mod daily_stat;
+pub mod dispute;
+pub mod exam_pause;
+pub mod executor_config;
+pub mod export_job;
+pub mod fingerprint_settings;
+pub mod grading_dead_letter;
+pub mod integrity_event;
+pub mod language_usage;
+pub mod login_event;
+pub mod message;
+pub mod message_recipient;
+pub mod migration_meta;
+pub mod run_config;
+pub mod sealed_code;
+pub mod submission;
+pub mod submission_case_result;
+pub mod task;
+pub mod task_progress;
+pub mod test_case;
 pub mod user;
+pub mod verdict_message;
+pub mod webauthn_credential;
+pub mod webhook_endpoint;
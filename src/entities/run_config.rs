@@ -0,0 +1,53 @@
+use sea_orm::entity::prelude::*;
+
+/// Stdin, arguments and executor options a student saved for quick reruns. Owned by a
+/// roster entry, so it is scoped to one classroom; `task_id` narrows it to one task.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "run_configs")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub user_id: i32,
+    pub task_id: Option<i32>,
+    pub name: String,
+    #[sea_orm(column_type = "Text")]
+    pub stdin: String,
+    pub command_line_arguments: Option<String>,
+    /// JSON object of [`crate::dto::RunConfigOptions`].
+    #[sea_orm(column_type = "Text")]
+    pub options: String,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(
+        belongs_to = "super::task::Entity",
+        from = "Column::TaskId",
+        to = "super::task::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Task,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl Related<super::task::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
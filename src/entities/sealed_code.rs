@@ -0,0 +1,92 @@
+use std::ops::Deref;
+
+use sea_orm::{
+    ColIdx, DbErr, QueryResult, TryGetError, TryGetable, Value,
+    sea_query::{ArrayType, ColumnType, Nullable, ValueType, ValueTypeErr},
+};
+
+use crate::services::code_encryption;
+
+/// Student code column that is encrypted on write and decrypted on read when
+/// `CODE_ENCRYPTION_KEY` is set. Plaintext rows written before encryption was turned
+/// on are read as is.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SealedCode(String);
+
+impl SealedCode {
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl Deref for SealedCode {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl From<String> for SealedCode {
+    fn from(plaintext: String) -> Self {
+        Self(plaintext)
+    }
+}
+
+impl From<&str> for SealedCode {
+    fn from(plaintext: &str) -> Self {
+        Self(plaintext.to_owned())
+    }
+}
+
+impl From<SealedCode> for String {
+    fn from(code: SealedCode) -> Self {
+        code.0
+    }
+}
+
+impl From<SealedCode> for Value {
+    fn from(code: SealedCode) -> Self {
+        Value::String(Some(Box::new(code_encryption::key_ring().seal(&code.0))))
+    }
+}
+
+impl TryGetable for SealedCode {
+    fn try_get_by<I: ColIdx>(res: &QueryResult, index: I) -> Result<Self, TryGetError> {
+        let stored = String::try_get_by(res, index)?;
+        code_encryption::key_ring()
+            .open(&stored)
+            .map(Self)
+            .map_err(|err| TryGetError::DbErr(DbErr::Type(err.to_string())))
+    }
+}
+
+impl ValueType for SealedCode {
+    fn try_from(value: Value) -> Result<Self, ValueTypeErr> {
+        match value {
+            Value::String(Some(stored)) => code_encryption::key_ring()
+                .open(&stored)
+                .map(Self)
+                .map_err(|_| ValueTypeErr),
+            _ => Err(ValueTypeErr),
+        }
+    }
+
+    fn type_name() -> String {
+        "SealedCode".to_owned()
+    }
+
+    fn array_type() -> ArrayType {
+        ArrayType::String
+    }
+
+    fn column_type() -> ColumnType {
+        ColumnType::Text
+    }
+}
+
+impl Nullable for SealedCode {
+    fn null() -> Value {
+        Value::String(None)
+    }
+}
@@ -0,0 +1,102 @@
+use sea_orm::entity::prelude::*;
+
+use super::sealed_code::SealedCode;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "submissions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub classroom_id: i32,
+    pub user_id: i32,
+    #[sea_orm(column_type = "Text")]
+    #[sea_orm(column_type = "Text")]
+    pub source_code: SealedCode,
+    pub language_id: i32,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub stdout: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub stderr: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub compile_output: Option<String>,
+    /// Judge0 status description of the run the grade is based on.
+    pub status: Option<String>,
+    pub time: Option<String>,
+    pub memory: Option<i64>,
+    /// Exit code of the program in that run, as reported by Judge0.
+    pub exit_code: Option<i32>,
+    /// Signal that terminated the program, if it did not exit on its own.
+    pub exit_signal: Option<i32>,
+    /// Exam hand-in, as opposed to an intermediate run.
+    pub is_final: bool,
+    /// Created by the expiry finalizer from the last saved code.
+    pub auto_submitted: bool,
+    /// Task the hand-in was made for, when the student picked one.
+    pub task_id: Option<i32>,
+    /// Handed in after the due date of its task.
+    pub late: bool,
+    pub passed_cases: Option<i32>,
+    pub total_cases: Option<i32>,
+    pub score: Option<i32>,
+    pub max_score: Option<i32>,
+    pub grading_error: Option<String>,
+    /// Gradings of this submission that failed; drives retries and dead-lettering.
+    pub grading_attempts: i32,
+    pub last_attempt_at: Option<DateTimeUtc>,
+    /// `queued`, `running`, `done` or `failed` for a final submission being graded;
+    /// empty for runs and for hand-ins from before progress was tracked.
+    pub grading_status: Option<String>,
+    /// Test case being run, counted from 1, while grading is `running`.
+    pub grading_case: Option<i32>,
+    /// Test cases the current grading goes through.
+    pub grading_case_count: Option<i32>,
+    /// JSON verdict printed by the classroom's grading script.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub script_verdict: Option<String>,
+    /// JSON response to the finish request that handed this in, returned again to
+    /// repeated finishes of the same attempt.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub finish_feedback: Option<String>,
+    /// Judge0 token of a run submitted without waiting; the polling worker fills in
+    /// the result.
+    pub judge0_token: Option<String>,
+    /// Base URL of the Judge0 instance holding `judge0_token`.
+    pub judge0_instance: Option<String>,
+    /// Judge0 status id of the run; 1 (In Queue) and 2 (Processing) mean it is still
+    /// being polled.
+    pub judge0_status_id: Option<i32>,
+    pub created_at: DateTimeUtc,
+    pub graded_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::classroom::Entity",
+        from = "Column::ClassroomId",
+        to = "super::classroom::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Classroom,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::classroom::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Classroom.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
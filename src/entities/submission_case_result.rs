@@ -0,0 +1,48 @@
+use sea_orm::entity::prelude::*;
+
+/// Outcome of one test case in a graded submission; replaced when the submission is
+/// regraded.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "submission_case_results")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub submission_id: i32,
+    pub test_case_id: i32,
+    pub passed: bool,
+    /// Judge0 status description of the run.
+    pub status: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::submission::Entity",
+        from = "Column::SubmissionId",
+        to = "super::submission::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Submission,
+    #[sea_orm(
+        belongs_to = "super::test_case::Entity",
+        from = "Column::TestCaseId",
+        to = "super::test_case::Column::Id",
+        on_delete = "Cascade"
+    )]
+    TestCase,
+}
+
+impl Related<super::submission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Submission.def()
+    }
+}
+
+impl Related<super::test_case::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TestCase.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
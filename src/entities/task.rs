@@ -0,0 +1,55 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "tasks")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub classroom_id: i32,
+    pub position: i32,
+    pub title: String,
+    pub description: String,
+    /// Judge0 language of the task when it differs from the classroom's, e.g. a C
+    /// interop task in a NASM classroom.
+    pub language_id: Option<i32>,
+    /// Deadline of the task in a non-exam classroom; submissions for it after this
+    /// instant are tagged late.
+    pub due_at: Option<DateTimeUtc>,
+    /// Code the editor opens with for this task.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub starter_code: Option<String>,
+    /// Reference output of the task for instructors; never sent to students.
+    #[sea_orm(column_type = "Text", nullable)]
+    pub expected_output: Option<String>,
+    /// Multiplier on the points of the task's test cases when grading.
+    pub weight: i32,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::classroom::Entity",
+        from = "Column::ClassroomId",
+        to = "super::classroom::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Classroom,
+    #[sea_orm(has_many = "super::test_case::Entity")]
+    TestCases,
+}
+
+impl Related<super::classroom::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Classroom.def()
+    }
+}
+
+impl Related<super::test_case::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TestCases.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,56 @@
+use sea_orm::entity::prelude::*;
+
+/// When a student first opened a task and last edited it; one row per task and user.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "task_progress")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub task_id: i32,
+    pub user_id: i32,
+    pub first_opened_at: DateTimeUtc,
+    pub last_edited_at: Option<DateTimeUtc>,
+    pub edit_count: i32,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::task::Entity",
+        from = "Column::TaskId",
+        to = "super::task::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Task,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::task::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Task.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl Model {
+    /// Seconds between opening the task and the last edit; zero when never edited.
+    pub fn seconds_spent(&self) -> i64 {
+        self.last_edited_at
+            .map(|edited| (edited - self.first_opened_at).num_seconds().max(0))
+            .unwrap_or(0)
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,5 +1,7 @@
 use sea_orm::entity::prelude::*;
 
+use super::sealed_code::SealedCode;
+
 #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
 #[sea_orm(table_name = "users")]
 pub struct Model {
@@ -8,9 +10,35 @@ pub struct Model {
     pub classroom_id: i32,
     pub name: String,
     pub npm: String,
-    pub code: String,
+    #[sea_orm(column_type = "Text")]
+    pub code: SealedCode,
     pub active: bool,
     pub exam_started_at: Option<DateTimeUtc>,
+    pub presetup_override: Option<String>,
+    /// Judge0 language of the last saved code, used when it is auto-submitted.
+    pub language_id: Option<i32>,
+    /// When `code` was last saved; `None` for rows saved before this was tracked.
+    pub code_updated_at: Option<DateTimeUtc>,
+    /// Revision of `code`, raised by one on every save; clients echo it back as
+    /// `base_seq` so a save from an outdated tab is refused.
+    pub code_seq: i64,
+    /// Last practice run or hand-in counted against the classroom's submission
+    /// cooldown; see `services::submission_cooldown`.
+    pub last_graded_at: Option<DateTimeUtc>,
+    /// Set when finishing under the `lock_submissions` policy; practice runs and
+    /// hand-ins are refused until staff reactivate the student.
+    pub submissions_locked_at: Option<DateTimeUtc>,
+    /// Client fingerprint the exam attempt was bound to at the first exam login.
+    pub device_fingerprint: Option<String>,
+    pub device_bound_at: Option<DateTimeUtc>,
+    /// Seat in the exam room: 1-based row (front to back) and column of the seat map.
+    pub seat_row: Option<i32>,
+    pub seat_column: Option<i32>,
+    /// Nonce the printed exam ticket's join code is derived from, and the keyed hash
+    /// of that code for lookup at login; both cleared once the code is used. See
+    /// `services::tickets`.
+    pub ticket_nonce: Option<String>,
+    pub ticket_code_hash: Option<String>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
@@ -32,4 +60,11 @@ impl Related<super::classroom::Entity> for Entity {
     }
 }
 
+impl Model {
+    /// Version of the saved code that clients echo back as `client_updated_at`.
+    pub fn code_version(&self) -> DateTimeUtc {
+        self.code_updated_at.unwrap_or(self.updated_at)
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,41 @@
+use sea_orm::entity::prelude::*;
+
+/// Passkey registered to an account through WebAuthn.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "webauthn_credentials")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub account_id: i32,
+    /// Credential id chosen by the authenticator, base64url without padding.
+    #[sea_orm(unique)]
+    pub credential_id: String,
+    /// COSE public key from the attested credential data, base64url without padding.
+    #[sea_orm(column_type = "Text")]
+    pub public_key: String,
+    /// Authenticator signature counter; 0 for authenticators that do not keep one.
+    pub sign_count: i64,
+    /// Label shown when listing the account's passkeys.
+    pub name: String,
+    pub created_at: DateTimeUtc,
+    pub last_used_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::account::Entity",
+        from = "Column::AccountId",
+        to = "super::account::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Account,
+}
+
+impl Related<super::account::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Account.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,28 @@
+use sea_orm::entity::prelude::*;
+
+/// Receiver of outgoing webhooks. The signing secret is stored sealed by the server's
+/// secret box and never returned after creation.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "webhook_endpoints")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub name: String,
+    pub url: String,
+    #[sea_orm(column_type = "Text")]
+    pub secret_encrypted: String,
+    pub active: bool,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        panic!("No relations defined for WebhookEndpoint entity");
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
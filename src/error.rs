@@ -1,6 +1,6 @@
 use axum::{
     Json,
-    http::StatusCode,
+    http::{HeaderValue, StatusCode, header::RETRY_AFTER},
     response::{IntoResponse, Response},
 };
 use reqwest::Error as ReqwestError;
@@ -8,20 +8,115 @@ use sea_orm::DbErr;
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::dto::{OwnCodeInfo, SubmissionFeedback};
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("classroom not found")]
     ClassroomNotFound,
     #[error("user not found")]
     UserNotFound,
+    #[error("task not found")]
+    TaskNotFound,
+    #[error("test case not found")]
+    TestCaseNotFound,
+    #[error("message not found")]
+    MessageNotFound,
+    #[error("submission not found")]
+    SubmissionNotFound,
+    #[error("dispute not found")]
+    DisputeNotFound,
+    #[error("webhook endpoint not found")]
+    WebhookNotFound,
+    #[error("settings version not found")]
+    SettingsVersionNotFound,
+    #[error("Passkey tidak ditemukan.")]
+    PasskeyNotFound,
+    #[error("run configuration not found")]
+    RunConfigNotFound,
+    #[error("dead letter not found")]
+    DeadLetterNotFound,
+    #[error("export job not found")]
+    ExportJobNotFound,
     #[error("invalid request: {0}")]
     BadRequest(String),
     #[error("database error: {0}")]
     Database(#[from] DbErr),
     #[error("external service error: {0}")]
     External(String),
+    #[error("executor timeout: {0}")]
+    ExecutorTimeout(String),
     #[error("unauthorized: {0}")]
     Unauthorized(String),
+    #[error("forbidden: {0}")]
+    Forbidden(String),
+    #[error("internal error: {0}")]
+    Internal(String),
+    #[error("classroom mismatch: {0}")]
+    ClassroomMismatch(String),
+    #[error("classroom not ready: {0}")]
+    ClassroomNotReady(String),
+    #[error("too many connections: {0}")]
+    TooManyConnections(String),
+    #[error("device mismatch: {0}")]
+    DeviceMismatch(String),
+    #[error("npm conflict: {0}")]
+    NpmConflict(String),
+    #[error("{0}")]
+    AccountPending(String),
+    #[error("too many submissions, retry in {retry_after_secs}s")]
+    RateLimited { retry_after_secs: u64 },
+    #[error("graded submissions are cooling down, retry in {retry_after_secs}s")]
+    SubmissionCooldown { retry_after_secs: u64 },
+    #[error("executor busy, retry in {retry_after_secs}s")]
+    ExecutorBusy { retry_after_secs: u64 },
+    #[error("the saved code changed after this copy was loaded")]
+    StaleCode(OwnCodeInfo),
+    #[error("confirmation required: {0}")]
+    ConfirmationRequired(String),
+    #[error("this attempt was already handed in")]
+    AlreadyFinished(Box<SubmissionFeedback>),
+    #[error("replayed request: {0}")]
+    ReplayedRequest(String),
+    #[error("{0}")]
+    LastAdmin(String),
+    #[error("{0}")]
+    Standby(String),
+    #[error("document does not match the schema: {}", .0.join("; "))]
+    InvalidDocument(Vec<String>),
+    #[error("{field} is {length} characters long; the limit is {limit}")]
+    FieldTooLong {
+        field: String,
+        limit: usize,
+        length: usize,
+    },
+}
+
+impl AppError {
+    /// Stable identifier for errors clients are expected to handle programmatically.
+    fn code(&self) -> Option<&'static str> {
+        match self {
+            AppError::ClassroomMismatch(_) => Some("classroom_mismatch"),
+            AppError::ExecutorTimeout(_) => Some("executor_timeout"),
+            AppError::TooManyConnections(_) => Some("too_many_connections"),
+            AppError::ClassroomNotReady(_) => Some("classroom_not_ready"),
+            AppError::DeviceMismatch(_) => Some("device_mismatch"),
+            AppError::NpmConflict(_) => Some("npm_conflict"),
+            AppError::AccountPending(_) => Some("account_pending"),
+            AppError::RateLimited { .. } => Some("rate_limited"),
+            AppError::SubmissionCooldown { .. } => Some("submission_cooldown"),
+            AppError::ExecutorBusy { .. } => Some("executor_busy"),
+            AppError::StaleCode(_) => Some("stale_code"),
+            AppError::ConfirmationRequired(_) => Some("confirmation_required"),
+            AppError::AlreadyFinished(_) => Some("already_finished"),
+            AppError::ReplayedRequest(_) => Some("replayed_request"),
+            AppError::LastAdmin(_) => Some("last_admin"),
+            AppError::Standby(_) => Some("standby"),
+            AppError::FieldTooLong { .. } => Some("field_too_long"),
+            AppError::InvalidDocument(_) => Some("invalid_document"),
+            _ => None,
+        }
+    }
 }
 
 impl IntoResponse for AppError {
@@ -29,8 +124,20 @@ impl IntoResponse for AppError {
         let (status, message) = match &self {
             AppError::ClassroomNotFound => (StatusCode::NOT_FOUND, self.to_string()),
             AppError::UserNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::TaskNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::TestCaseNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::MessageNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::SubmissionNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::DisputeNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::WebhookNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::SettingsVersionNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::PasskeyNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::RunConfigNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::DeadLetterNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::ExportJobNotFound => (StatusCode::NOT_FOUND, self.to_string()),
             AppError::BadRequest(_) => (StatusCode::BAD_REQUEST, self.to_string()),
             AppError::Unauthorized(_) => (StatusCode::UNAUTHORIZED, self.to_string()),
+            AppError::Forbidden(_) => (StatusCode::FORBIDDEN, self.to_string()),
             AppError::Database(err) => {
                 let status = match err {
                     DbErr::RecordNotFound(_) => StatusCode::NOT_FOUND,
@@ -39,13 +146,96 @@ impl IntoResponse for AppError {
                 (status, "internal server error".to_string())
             }
             AppError::External(_) => (StatusCode::BAD_GATEWAY, self.to_string()),
+            AppError::Internal(reason) => {
+                tracing::error!("{reason}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal server error".to_string(),
+                )
+            }
+            AppError::ExecutorTimeout(_) => (StatusCode::GATEWAY_TIMEOUT, self.to_string()),
+            AppError::ClassroomMismatch(_) => (StatusCode::FORBIDDEN, self.to_string()),
+            AppError::TooManyConnections(_) => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
+            AppError::ClassroomNotReady(_) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            AppError::DeviceMismatch(_) => (StatusCode::FORBIDDEN, self.to_string()),
+            AppError::NpmConflict(_) => (StatusCode::CONFLICT, self.to_string()),
+            AppError::AccountPending(_) => (StatusCode::FORBIDDEN, self.to_string()),
+            AppError::RateLimited { .. } => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
+            AppError::SubmissionCooldown { .. } => {
+                (StatusCode::TOO_MANY_REQUESTS, self.to_string())
+            }
+            AppError::ExecutorBusy { .. } => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
+            AppError::ConfirmationRequired(_) => {
+                (StatusCode::PRECONDITION_REQUIRED, self.to_string())
+            }
+            AppError::StaleCode(_) => (StatusCode::CONFLICT, self.to_string()),
+            AppError::AlreadyFinished(_) => (StatusCode::CONFLICT, self.to_string()),
+            AppError::ReplayedRequest(_) => (StatusCode::CONFLICT, self.to_string()),
+            AppError::LastAdmin(_) => (StatusCode::CONFLICT, self.to_string()),
+            AppError::Standby(_) => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
+            AppError::FieldTooLong { .. } => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            AppError::InvalidDocument(_) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "document does not match the schema".to_string(),
+            ),
         };
 
-        let body = Json(ErrorResponse { message });
-        (status, body).into_response()
+        let database_failure =
+            status == StatusCode::INTERNAL_SERVER_ERROR && matches!(self, AppError::Database(_));
+        let code = self.code();
+        let (retry_after_secs, current, original, violation, errors) = match self {
+            AppError::RateLimited { retry_after_secs }
+            | AppError::SubmissionCooldown { retry_after_secs }
+            | AppError::ExecutorBusy { retry_after_secs } => {
+                (Some(retry_after_secs), None, None, None, None)
+            }
+            AppError::StaleCode(current) => (None, Some(current), None, None, None),
+            AppError::AlreadyFinished(original) => (None, None, Some(original), None, None),
+            AppError::FieldTooLong {
+                field,
+                limit,
+                length,
+            } => (
+                None,
+                None,
+                None,
+                Some(FieldViolation {
+                    field,
+                    limit,
+                    length,
+                }),
+                None,
+            ),
+            AppError::InvalidDocument(errors) => (None, None, None, None, Some(errors)),
+            _ => (None, None, None, None, None),
+        };
+        let body = Json(ErrorResponse {
+            message,
+            code,
+            retry_after_secs,
+            current,
+            original,
+            violation,
+            errors,
+        });
+        let mut response = (status, body).into_response();
+        if database_failure {
+            response.extensions_mut().insert(DatabaseFailure);
+        }
+        if let Some(retry_after_secs) = retry_after_secs {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after_secs));
+        }
+        response
     }
 }
 
+/// Response extension marking a request that failed on a database error, so the
+/// request middleware can count it against the error budget.
+#[derive(Debug, Clone, Copy)]
+pub struct DatabaseFailure;
+
 impl From<&str> for AppError {
     fn from(value: &str) -> Self {
         Self::BadRequest(value.to_owned())
@@ -67,4 +257,27 @@ impl From<ReqwestError> for AppError {
 #[derive(Debug, Serialize)]
 struct ErrorResponse {
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code: Option<&'static str>,
+    #[serde(rename = "retryAfterSecs", skip_serializing_if = "Option::is_none")]
+    retry_after_secs: Option<u64>,
+    /// Saved code that a `stale_code` request would have overwritten.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current: Option<OwnCodeInfo>,
+    /// Response to the first hand-in of an `already_finished` attempt.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    original: Option<Box<SubmissionFeedback>>,
+    /// Field of a `field_too_long` request and the limit it broke.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    violation: Option<FieldViolation>,
+    /// Every problem of an `invalid_document` upload, each prefixed with its JSON path.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    errors: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+struct FieldViolation {
+    field: String,
+    limit: usize,
+    length: usize,
 }
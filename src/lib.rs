@@ -0,0 +1,17 @@
+// Status enums use an inherent `from_str` returning `Option` rather than `FromStr`.
+#![allow(clippy::should_implement_trait)]
+
+pub mod app;
+pub mod auth;
+pub mod config;
+pub mod db;
+pub mod docs;
+pub mod dto;
+pub mod entities;
+pub mod error;
+pub mod routes;
+pub mod services;
+pub mod setup;
+pub mod state;
+#[cfg(feature = "test-server")]
+pub mod testing;
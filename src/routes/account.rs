@@ -1,23 +1,41 @@
 use axum::{
     Json,
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{
+        HeaderMap, StatusCode,
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    },
+    response::IntoResponse,
 };
 use chrono::Utc;
 use sea_orm::{
     ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder,
 };
 
 use crate::{
-    dto::{AccountResponse, AccountRole, CreateAccountRequest, UpdateAccountRoleRequest},
+    auth::AuthAccount,
+    dto::{
+        AccountActivityResponse, AccountImportParams, AccountImportResponse, AccountResponse,
+        AccountRole, AccountStatus, CreateAccountRequest, ExportTimeParams,
+        UpdateAccountRoleRequest,
+    },
     entities::account,
     error::AppError,
+    services::{
+        account_transfer,
+        accounts::{self, AdminGuard},
+        activity,
+        display_time::TimeFormatter,
+    },
     state::AppState,
 };
 
 fn validate_role(role: AccountRole) -> Result<AccountRole, AppError> {
     match role {
-        AccountRole::User | AccountRole::Admin => Ok(role),
+        AccountRole::User | AccountRole::Admin | AccountRole::Assistant | AccountRole::Observer => {
+            Ok(role)
+        }
     }
 }
 
@@ -98,6 +116,7 @@ pub async fn create_account(
     let model = account::ActiveModel {
         npm: Set(npm.to_owned()),
         role: Set(role.as_str().to_owned()),
+        status: Set(AccountStatus::Active.as_str().to_owned()),
         created_at: Set(now),
         updated_at: Set(now),
         ..Default::default()
@@ -119,49 +138,262 @@ pub async fn create_account(
     request_body = UpdateAccountRoleRequest,
     responses(
         (status = 200, description = "Akun diperbarui", body = AccountResponse),
-        (status = 404, description = "Akun tidak ditemukan")
+        (status = 404, description = "Akun tidak ditemukan"),
+        (status = 409, description = "Admin aktif terakhir tidak dapat diturunkan (`code: \"last_admin\"`)")
     )
 )]
 pub async fn update_account_role(
     State(state): State<AppState>,
+    auth: Option<AuthAccount>,
     Path(id): Path<i32>,
     Json(payload): Json<UpdateAccountRoleRequest>,
 ) -> Result<Json<AccountResponse>, AppError> {
     let role = validate_role(payload.role)?;
 
+    let updated = accounts::set_role(
+        &state.db,
+        id,
+        role,
+        auth.map(|auth| auth.account.id),
+        AdminGuard::Enforce,
+    )
+    .await?;
+
+    Ok(Json(AccountResponse::from_model(updated)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/accounts/{id}",
+    params(("id" = i32, Path, description = "ID akun")),
+    tag = "Accounts",
+    responses(
+        (status = 204, description = "Akun dihapus"),
+        (status = 404, description = "Akun tidak ditemukan"),
+        (status = 409, description = "Admin aktif terakhir tidak dapat dihapus (`code: \"last_admin\"`)")
+    )
+)]
+pub async fn delete_account(
+    State(state): State<AppState>,
+    auth: Option<AuthAccount>,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    accounts::delete(
+        &state.db,
+        id,
+        auth.map(|auth| auth.account.id),
+        AdminGuard::Enforce,
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/accounts/pending",
+    tag = "Accounts",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Akun yang menunggu persetujuan, terlama lebih dulu", body = [AccountResponse]),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Bukan admin")
+    )
+)]
+pub async fn list_pending_accounts(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<Vec<AccountResponse>>, AppError> {
+    auth.require_admin()?;
+
+    let accounts = account::Entity::find()
+        .filter(account::Column::Status.eq(AccountStatus::Pending.as_str()))
+        .order_by_asc(account::Column::CreatedAt)
+        .order_by_asc(account::Column::Id)
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(AccountResponse::from_model)
+        .collect();
+
+    Ok(Json(accounts))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/accounts/{id}/approve",
+    params(("id" = i32, Path, description = "ID akun")),
+    tag = "Accounts",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Akun disetujui dan dapat masuk", body = AccountResponse),
+        (status = 400, description = "Akun tidak ditemukan atau tidak sedang menunggu persetujuan"),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Bukan admin")
+    )
+)]
+pub async fn approve_account(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<AccountResponse>, AppError> {
+    auth.require_admin()?;
+
     let account_model = account::Entity::find_by_id(id)
         .one(&state.db)
         .await?
         .ok_or(AppError::BadRequest("Akun tidak ditemukan".into()))?;
+    if AccountStatus::from_str(&account_model.status) != Some(AccountStatus::Pending) {
+        return Err(AppError::BadRequest(
+            "Akun tidak sedang menunggu persetujuan.".into(),
+        ));
+    }
 
+    let now = Utc::now();
     let mut active_model = account_model.into_active_model();
-    active_model.role = Set(role.as_str().to_owned());
-    active_model.updated_at = Set(Utc::now());
+    active_model.status = Set(AccountStatus::Active.as_str().to_owned());
+    active_model.approved_at = Set(Some(now));
+    active_model.updated_at = Set(now);
 
     let updated = active_model.update(&state.db).await?;
+    tracing::info!("account {} approved by {}", updated.npm, auth.account.npm);
 
     Ok(Json(AccountResponse::from_model(updated)))
 }
 
 #[utoipa::path(
-    delete,
-    path = "/api/accounts/{id}",
+    get,
+    path = "/api/accounts/{id}/activity",
     params(("id" = i32, Path, description = "ID akun")),
     tag = "Accounts",
+    security(("bearer" = [])),
     responses(
-        (status = 204, description = "Akun dihapus"),
-        (status = 404, description = "Akun tidak ditemukan")
+        (status = 200, description = "Riwayat login, kelas, submission, dan tindakan admin akun (tanpa kode sumber)", body = AccountActivityResponse),
+        (status = 400, description = "Akun tidak ditemukan"),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Bukan admin")
     )
 )]
-pub async fn delete_account(
+pub async fn account_activity(
     State(state): State<AppState>,
+    auth: AuthAccount,
     Path(id): Path<i32>,
-) -> Result<StatusCode, AppError> {
-    let result = account::Entity::delete_by_id(id).exec(&state.db).await?;
+) -> Result<Json<AccountActivityResponse>, AppError> {
+    auth.require_admin()?;
 
-    if result.rows_affected == 0 {
-        return Err(AppError::BadRequest("Akun tidak ditemukan".into()));
-    }
+    let account_model = find_account(&state, id).await?;
+    Ok(Json(activity::report(&state.db, account_model).await?))
+}
 
-    Ok(StatusCode::NO_CONTENT)
+#[utoipa::path(
+    get,
+    path = "/api/accounts/{id}/activity/export",
+    params(("id" = i32, Path, description = "ID akun"), ExportTimeParams),
+    tag = "Accounts",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Riwayat aktivitas akun sebagai CSV, satu baris per kejadian diurutkan menurut waktu (`category`: `login`, `classroom`, `submission`, `admin_action`)", content_type = "text/csv", body = String),
+        (status = 400, description = "Akun tidak ditemukan"),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Bukan admin")
+    )
+)]
+pub async fn export_account_activity(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Query(params): Query<ExportTimeParams>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_admin()?;
+
+    let times = TimeFormatter::resolve(params.time, &headers, state.display_timezone);
+    let account_model = find_account(&state, id).await?;
+    let report = activity::report(&state.db, account_model).await?;
+    let out = activity::to_csv(&report, &times);
+
+    Ok((
+        [
+            (CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"account-{id}-activity.csv\""),
+            ),
+        ],
+        out,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/accounts/export",
+    tag = "Accounts",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Semua akun beserta peran, status, email, dan persetujuan sebagai dokumen JSON berversi untuk diimpor di deployment lain", body = AccountExport),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Bukan admin")
+    )
+)]
+pub async fn export_accounts(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_admin()?;
+
+    let document = account_transfer::export(&state.db).await?;
+    let filename = format!(
+        "accounts-{}.json",
+        document.exported_at.format("%Y%m%d-%H%M%S")
+    );
+    Ok((
+        [(
+            CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{filename}\""),
+        )],
+        Json(document),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/accounts/import",
+    params(AccountImportParams),
+    tag = "Accounts",
+    security(("bearer" = [])),
+    request_body = AccountExport,
+    responses(
+        (status = 200, description = "Akun dibuat atau diperbarui sesuai kebijakan konflik dalam satu transaksi", body = AccountImportResponse),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Bukan admin"),
+        (status = 409, description = "Impor akan menyisakan tanpa admin aktif (`code: \"last_admin\"`)"),
+        (status = 422, description = "Dokumen tidak sesuai skema; `errors` memuat setiap masalah beserta path JSON-nya (`code: \"invalid_document\"`)")
+    )
+)]
+pub async fn import_accounts(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Query(params): Query<AccountImportParams>,
+    Json(document): Json<serde_json::Value>,
+) -> Result<Json<AccountImportResponse>, AppError> {
+    auth.require_admin()?;
+
+    let entries = account_transfer::validate(document)?;
+    let response = account_transfer::import(&state.db, entries, params.policy).await?;
+    tracing::info!(
+        "accounts imported by {} ({:?}): {} created, {} updated, {} skipped",
+        auth.account.npm,
+        params.policy,
+        response.created.len(),
+        response.updated.len(),
+        response.skipped.len()
+    );
+    Ok(Json(response))
+}
+
+async fn find_account(state: &AppState, id: i32) -> Result<account::Model, AppError> {
+    account::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::BadRequest("Akun tidak ditemukan".into()))
 }
@@ -0,0 +1,202 @@
+use std::net::SocketAddr;
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, Query, State},
+    http::{HeaderMap, StatusCode},
+};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{
+        AccountResponse, RecoverNpmRequest, RecoverNpmResponse, UpdateEmailRequest,
+        VerifyEmailParams,
+    },
+    entities::account,
+    error::AppError,
+    routes::exam::request_server_url,
+    services::mailer,
+    state::AppState,
+};
+
+#[utoipa::path(
+    put,
+    path = "/api/auth/me/email",
+    tag = "Auth",
+    security(("bearer" = [])),
+    request_body = UpdateEmailRequest,
+    responses(
+        (status = 200, description = "Email disimpan; tautan verifikasi dikirim jika alamat berubah atau belum terverifikasi", body = AccountResponse),
+        (status = 400, description = "Alamat email tidak valid"),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Fitur email akun tidak aktif (`ACCOUNT_EMAIL_ENABLED`)"),
+        (status = 429, description = "Terlalu banyak permintaan email; coba lagi setelah `Retry-After` detik"),
+        (status = 502, description = "Relay email menolak atau gagal dihubungi")
+    )
+)]
+pub async fn update_email(
+    State(state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    auth: AuthAccount,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateEmailRequest>,
+) -> Result<Json<AccountResponse>, AppError> {
+    require_enabled(&state)?;
+    let email = mailer::normalize_email(&payload.email)
+        .ok_or_else(|| AppError::BadRequest("Alamat email tidak valid.".into()))?;
+
+    let account_model = auth.account;
+    if account_model.email.as_deref() == Some(email.as_str())
+        && account_model.email_verified_at.is_some()
+    {
+        return Ok(Json(AccountResponse::from_model(account_model)));
+    }
+    check_rate_limit(&state, &client, &email)?;
+
+    let token = state.jwt.issue_email_verification(&account_model, &email)?;
+    let mut link = request_server_url(&headers)?
+        .join("/api/auth/verify-email")
+        .map_err(|err| AppError::Internal(format!("invalid verification link: {err}")))?;
+    link.query_pairs_mut().append_pair("token", &token);
+
+    let npm = account_model.npm.clone();
+    let mut account_am: account::ActiveModel = account_model.into();
+    account_am.email = Set(Some(email.clone()));
+    account_am.email_verified_at = Set(None);
+    account_am.updated_at = Set(Utc::now());
+    let updated = account_am.update(&state.db).await?;
+
+    mailer::send(
+        &state,
+        &email,
+        "Verifikasi email akun ASM Lab",
+        &format!(
+            "Email ini didaftarkan untuk akun ASM Lab dengan NPM {npm}.\n\
+             Buka tautan berikut dalam 24 jam untuk memverifikasinya:\n{link}\n\n\
+             Abaikan email ini jika Anda tidak merasa mendaftarkannya."
+        ),
+    )
+    .await?;
+
+    Ok(Json(AccountResponse::from_model(updated)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/auth/verify-email",
+    tag = "Auth",
+    params(VerifyEmailParams),
+    responses(
+        (status = 200, description = "Email akun terverifikasi", body = AccountResponse),
+        (status = 400, description = "Tautan tidak valid, kedaluwarsa, atau email akun sudah diganti"),
+        (status = 403, description = "Fitur email akun tidak aktif (`ACCOUNT_EMAIL_ENABLED`)")
+    )
+)]
+pub async fn verify_email(
+    State(state): State<AppState>,
+    Query(params): Query<VerifyEmailParams>,
+) -> Result<Json<AccountResponse>, AppError> {
+    require_enabled(&state)?;
+    let claims = state.jwt.verify_email_verification(&params.token)?;
+
+    let account_model = account::Entity::find_by_id(claims.sub)
+        .one(&state.db)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Akun tidak ditemukan.".into()))?;
+    if account_model.email.as_deref() != Some(claims.email.as_str()) {
+        return Err(AppError::BadRequest(
+            "Email akun sudah diganti; minta tautan verifikasi baru.".into(),
+        ));
+    }
+    if account_model.email_verified_at.is_some() {
+        return Ok(Json(AccountResponse::from_model(account_model)));
+    }
+
+    let now = Utc::now();
+    let mut account_am: account::ActiveModel = account_model.into();
+    account_am.email_verified_at = Set(Some(now));
+    account_am.updated_at = Set(now);
+    let updated = account_am.update(&state.db).await?;
+
+    Ok(Json(AccountResponse::from_model(updated)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/recover-npm",
+    tag = "Auth",
+    request_body = RecoverNpmRequest,
+    responses(
+        (status = 202, description = "Jika email terdaftar dan terverifikasi, NPM akun dikirim ke alamat tersebut", body = RecoverNpmResponse),
+        (status = 400, description = "Alamat email tidak valid"),
+        (status = 403, description = "Fitur email akun tidak aktif (`ACCOUNT_EMAIL_ENABLED`)"),
+        (status = 429, description = "Terlalu banyak permintaan email; coba lagi setelah `Retry-After` detik")
+    )
+)]
+pub async fn recover_npm(
+    State(state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    Json(payload): Json<RecoverNpmRequest>,
+) -> Result<(StatusCode, Json<RecoverNpmResponse>), AppError> {
+    require_enabled(&state)?;
+    let email = mailer::normalize_email(&payload.email)
+        .ok_or_else(|| AppError::BadRequest("Alamat email tidak valid.".into()))?;
+    check_rate_limit(&state, &client, &email)?;
+
+    let accounts = account::Entity::find()
+        .filter(account::Column::Email.eq(email.as_str()))
+        .filter(account::Column::EmailVerifiedAt.is_not_null())
+        .all(&state.db)
+        .await?;
+
+    if !accounts.is_empty() {
+        let npms: Vec<String> = accounts
+            .into_iter()
+            .map(|model| format!("- {}", model.npm))
+            .collect();
+        let sent = mailer::send(
+            &state,
+            &email,
+            "NPM akun ASM Lab Anda",
+            &format!(
+                "NPM yang terdaftar dengan email ini:\n{}\n\n\
+                 Gunakan NPM tersebut untuk masuk. Abaikan email ini jika Anda tidak memintanya.",
+                npms.join("\n")
+            ),
+        )
+        .await;
+        // The caller gets the same answer either way.
+        if let Err(err) = sent {
+            tracing::warn!("NPM recovery mail to {email} failed: {err}");
+        }
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(RecoverNpmResponse {
+            message:
+                "Jika email terdaftar dan terverifikasi, NPM akun telah dikirim ke alamat tersebut."
+                    .into(),
+        }),
+    ))
+}
+
+fn require_enabled(state: &AppState) -> Result<(), AppError> {
+    if !state.account_email_enabled {
+        return Err(AppError::Forbidden("Fitur email akun tidak aktif.".into()));
+    }
+    Ok(())
+}
+
+/// Each mail costs one request from both the address's and the client's budget.
+fn check_rate_limit(state: &AppState, client: &SocketAddr, email: &str) -> Result<(), AppError> {
+    for key in [format!("email:{email}"), format!("ip:{}", client.ip())] {
+        state
+            .email_rate_limit
+            .check(&key)
+            .map_err(|retry_after_secs| AppError::RateLimited { retry_after_secs })?;
+    }
+    Ok(())
+}
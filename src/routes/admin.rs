@@ -0,0 +1,292 @@
+use axum::{Json, extract::State};
+use chrono::Utc;
+use sea_orm::{
+    ConnectionTrait, DatabaseBackend, DatabaseConnection, EntityTrait, FromQueryResult, QueryOrder,
+    QuerySelect, Statement,
+    sea_query::{Alias, Asterisk, Expr, Order, Query, SimpleExpr},
+};
+
+use crate::{
+    auth::AuthAccount,
+    docs,
+    dto::{
+        CodeBlobStats, DatabaseStats, MetricsResponse, OpenApiDiffResponse, SchemaResponse,
+        StorageUsage, SystemStatsResponse, TableRowCount,
+    },
+    entities::user,
+    error::AppError,
+    services::{grading_retry, login_audit, openapi_diff, schema},
+    state::AppState,
+};
+
+/// How many of the biggest code submissions the stats report lists.
+const LARGEST_CODE_LIMIT: u64 = 10;
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/system-stats",
+    tag = "Admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Database size, row counts of every table, export and archive disk usage, largest code blobs, uptime and the latest flagged login attempts", body = SystemStatsResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn system_stats(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<SystemStatsResponse>, AppError> {
+    auth.require_admin()?;
+    let db = &state.db;
+    let now = Utc::now();
+
+    let mut tables = Vec::new();
+    for table in schema::table_names(db).await? {
+        tables.push(count_rows(db, table).await?);
+    }
+
+    let largest_code = user::Entity::find()
+        .select_only()
+        .column_as(user::Column::Id, "user_id")
+        .column(user::Column::ClassroomId)
+        .column(user::Column::Npm)
+        .column_as(code_bytes(), "bytes")
+        .order_by(code_bytes(), Order::Desc)
+        .limit(LARGEST_CODE_LIMIT)
+        .into_model::<CodeBlobRow>()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|row| CodeBlobStats {
+            user_id: row.user_id,
+            classroom_id: row.classroom_id,
+            npm: row.npm,
+            bytes: row.bytes,
+        })
+        .collect();
+
+    let total_code_bytes = user::Entity::find()
+        .select_only()
+        .column_as(Expr::expr(code_bytes()).sum(), "total")
+        .into_tuple::<Option<i64>>()
+        .one(db)
+        .await?
+        .flatten()
+        .unwrap_or(0);
+
+    Ok(Json(SystemStatsResponse {
+        started_at: state.started_at,
+        uptime_seconds: (now - state.started_at).num_seconds(),
+        database: database_stats(db).await?,
+        tables,
+        storage: storage_usage(&state).await?,
+        total_code_bytes,
+        largest_code,
+        flagged_logins: login_audit::recent_flagged(db, None).await?,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/metrics",
+    tag = "Admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Executor call counters since the server started, plus the grading retry backlog", body = MetricsResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn metrics(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<MetricsResponse>, AppError> {
+    auth.require_admin()?;
+    let (pending_retries, dead_letters) =
+        grading_retry::backlog(&state.db, state.grading_max_attempts).await?;
+    Ok(Json(MetricsResponse {
+        started_at: state.started_at,
+        judge0: state.metrics.judge0.snapshot(),
+        judge0_instances: state.executors.instance_statuses(),
+        grading: state
+            .metrics
+            .grading
+            .snapshot(pending_retries, dead_letters),
+        submission_rate_limit: state.rate_limit.status(),
+        executor_queue: state.executor_queue.status(),
+        database: state.metrics.database.snapshot(),
+        error_budget: state.error_budget.status(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/openapi/diff",
+    tag = "Admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Changes from the stored baseline to the served OpenAPI document, breaking ones flagged", body = OpenApiDiffResponse),
+        (status = 400, description = "OPENAPI_BASELINE is not configured"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 500, description = "Baseline file is missing or not an OpenAPI document")
+    )
+)]
+pub async fn openapi_diff(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<OpenApiDiffResponse>, AppError> {
+    auth.require_admin()?;
+
+    let path = state
+        .openapi_baseline
+        .as_deref()
+        .ok_or_else(|| AppError::BadRequest("OPENAPI_BASELINE is not configured".into()))?;
+    // Read on every call so a new baseline can be dropped in without a restart.
+    let raw = tokio::fs::read(path).await.map_err(|err| {
+        AppError::Internal(format!(
+            "cannot read OpenAPI baseline {}: {err}",
+            path.display()
+        ))
+    })?;
+    let baseline: serde_json::Value = serde_json::from_slice(&raw).map_err(|err| {
+        AppError::Internal(format!(
+            "OpenAPI baseline {} is not valid JSON: {err}",
+            path.display()
+        ))
+    })?;
+
+    let current = serde_json::to_value(docs::openapi())
+        .map_err(|err| AppError::Internal(format!("cannot serialize OpenAPI document: {err}")))?;
+    let changes = openapi_diff::diff(&baseline, &current);
+
+    Ok(Json(OpenApiDiffResponse {
+        baseline_version: baseline["info"]["version"]
+            .as_str()
+            .unwrap_or_default()
+            .to_owned(),
+        current_version: docs::version_tag(),
+        breaking: changes.iter().any(|change| change.breaking),
+        changes,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/schema",
+    tag = "Admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Tables and columns the server expects next to the ones in the database, indexes, and data migration versions, for checking a deployment's schema", body = SchemaResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn schema(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<SchemaResponse>, AppError> {
+    auth.require_admin()?;
+
+    Ok(Json(schema::inspect(&state.db).await?))
+}
+
+#[derive(Debug, FromQueryResult)]
+struct CodeBlobRow {
+    user_id: i32,
+    classroom_id: i32,
+    npm: String,
+    bytes: i64,
+}
+
+/// Byte length of the stored code (the ciphertext when code encryption is on); the
+/// cast keeps multi-byte characters honest.
+fn code_bytes() -> SimpleExpr {
+    Expr::cust("LENGTH(CAST(\"code\" AS BLOB))")
+}
+
+async fn count_rows(db: &DatabaseConnection, table: String) -> Result<TableRowCount, AppError> {
+    let query = Query::select()
+        .expr_as(Expr::col(Asterisk).count(), Alias::new("rows"))
+        .from(Alias::new(table.as_str()))
+        .to_owned();
+    let rows = db
+        .query_one(db.get_database_backend().build(&query))
+        .await?
+        .map(|row| row.try_get::<i64>("", "rows"))
+        .transpose()?
+        .unwrap_or(0);
+    Ok(TableRowCount {
+        table,
+        rows: rows as u64,
+    })
+}
+
+async fn storage_usage(state: &AppState) -> Result<Vec<StorageUsage>, AppError> {
+    let (files, bytes) = state.export_storage.usage().await?;
+    let mut storage = vec![StorageUsage {
+        name: "exports".into(),
+        location: state.export_storage.dir().display().to_string(),
+        files,
+        bytes,
+    }];
+
+    if let Some(path) = state
+        .archive
+        .as_ref()
+        .and_then(|archive| archive.local_path())
+    {
+        let bytes = match tokio::fs::metadata(&path).await {
+            Ok(metadata) => metadata.len(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(err) => {
+                return Err(AppError::Internal(format!(
+                    "cannot read {}: {err}",
+                    path.display()
+                )));
+            }
+        };
+        storage.push(StorageUsage {
+            name: "archive".into(),
+            location: path.display().to_string(),
+            files: u64::from(bytes > 0),
+            bytes,
+        });
+    }
+
+    Ok(storage)
+}
+
+async fn database_stats(db: &DatabaseConnection) -> Result<DatabaseStats, AppError> {
+    let backend = db.get_database_backend();
+    if backend != DatabaseBackend::Sqlite {
+        return Ok(DatabaseStats {
+            backend: format!("{backend:?}").to_lowercase(),
+            size_bytes: None,
+            free_bytes: None,
+        });
+    }
+
+    let row = db
+        .query_one(Statement::from_string(
+            backend,
+            "SELECT page_count * page_size AS size_bytes, freelist_count * page_size AS free_bytes \
+             FROM pragma_page_count(), pragma_freelist_count(), pragma_page_size()",
+        ))
+        .await?;
+
+    let (size_bytes, free_bytes) = match row {
+        Some(row) => (
+            row.try_get::<i64>("", "size_bytes").ok(),
+            row.try_get::<i64>("", "free_bytes").ok(),
+        ),
+        None => (None, None),
+    };
+
+    Ok(DatabaseStats {
+        backend: "sqlite".into(),
+        size_bytes,
+        free_bytes,
+    })
+}
@@ -0,0 +1,98 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::{NaiveDate, Utc};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{
+        DailyStatsEntry, DailyStatsParams, DailyStatsResponse, LanguageStatsParams,
+        LanguageStatsResponse, LanguageUsageEntry,
+    },
+    error::AppError,
+    services::{daily_stats, language_map, language_usage},
+    state::AppState,
+};
+
+const DEFAULT_RANGE_DAYS: i64 = 30;
+const MAX_RANGE_DAYS: i64 = 366;
+
+#[utoipa::path(
+    get,
+    path = "/api/analytics/daily",
+    params(DailyStatsParams),
+    tag = "Analytics",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Rolled-up daily counters", body = DailyStatsResponse),
+        (status = 400, description = "Range is reversed or longer than 366 days"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn daily_stats(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Query(params): Query<DailyStatsParams>,
+) -> Result<Json<DailyStatsResponse>, AppError> {
+    auth.require_admin()?;
+    let (from, to) = day_range(params.from, params.to)?;
+
+    let rows = daily_stats::load(&state.db, from, to, params.classroom_id).await?;
+    Ok(Json(DailyStatsResponse {
+        from,
+        to,
+        rows: rows.into_iter().map(DailyStatsEntry::from).collect(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/analytics/languages",
+    params(LanguageStatsParams),
+    tag = "Analytics",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Runs and submissions per Judge0 language, per day and in total, with executor languages nobody used", body = LanguageStatsResponse),
+        (status = 400, description = "Range is reversed or longer than 366 days"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn language_stats(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Query(params): Query<LanguageStatsParams>,
+) -> Result<Json<LanguageStatsResponse>, AppError> {
+    auth.require_admin()?;
+    let (from, to) = day_range(params.from, params.to)?;
+
+    let rows = language_usage::load(&state.db, from, to, params.classroom_id).await?;
+    let last_used = language_usage::last_used(&state.db, params.classroom_id).await?;
+    let installed = language_map::languages(&state).await;
+    Ok(Json(LanguageStatsResponse {
+        from,
+        to,
+        languages: language_usage::summarize(&rows, &installed, &last_used),
+        rows: rows.into_iter().map(LanguageUsageEntry::from).collect(),
+    }))
+}
+
+/// `from..=to` with the defaults filled in, at most [`MAX_RANGE_DAYS`] long.
+fn day_range(
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<(NaiveDate, NaiveDate), AppError> {
+    let to = to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = from.unwrap_or(to - chrono::Duration::days(DEFAULT_RANGE_DAYS - 1));
+    if from > to {
+        return Err(AppError::BadRequest("`from` must not be after `to`".into()));
+    }
+    if (to - from).num_days() >= MAX_RANGE_DAYS {
+        return Err(AppError::BadRequest(format!(
+            "range must not exceed {MAX_RANGE_DAYS} days"
+        )));
+    }
+    Ok((from, to))
+}
@@ -0,0 +1,55 @@
+use axum::{
+    extract::{Path, State},
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::IntoResponse,
+};
+
+use crate::{
+    auth::{AuthAccount, permissions::Permission},
+    error::AppError,
+    routes::dispute,
+    services::artifacts,
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/submissions/{id}/artifacts",
+    params(("id" = i32, Path, description = "Submission identifier")),
+    tag = "Grading",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "ZIP with `source.<ext>`, `metadata.json` (see `SubmissionArtifactMetadata`), and `compile_output.txt`, `stdout.txt` and `stderr.txt` when Judge0 produced them", content_type = "application/zip", body = Vec<u8>),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Submission belongs to another student"),
+        (status = 404, description = "Submission not found")
+    )
+)]
+pub async fn download_artifacts(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let (submission_model, user_model) = dispute::find_submission(&state.db, id).await?;
+
+    if user_model.npm != auth.account.npm && !auth.can(Permission::Grade) {
+        return Err(AppError::Forbidden(
+            "submissions are only visible to the submitting student and admins".into(),
+        ));
+    }
+
+    let bundle = artifacts::bundle(&submission_model, &user_model)?;
+    Ok((
+        [
+            (CONTENT_TYPE, "application/zip".to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"submission-{id}-{}.zip\"",
+                    user_model.npm
+                ),
+            ),
+        ],
+        bundle,
+    ))
+}
@@ -0,0 +1,150 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, QueryOrder,
+};
+use utoipa::IntoParams;
+
+use crate::{
+    auth::{AuthAccount, permissions::Permission},
+    dto::{AccountResponse, AccountRole},
+    entities::{account, classroom, classroom_assistant},
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    state::AppState,
+};
+
+#[allow(dead_code)]
+#[derive(Debug, IntoParams)]
+pub struct AssistantPath {
+    pub id: i32,
+    pub account_id: i32,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/assistants",
+    params(ClassroomPath),
+    tag = "Assistants",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Assistant accounts assigned to the classroom", body = [AccountResponse]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller may not manage classrooms"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn list_assistants(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<AccountResponse>>, AppError> {
+    auth.require(Permission::ManageClassrooms)?;
+    find_classroom(&state, id).await?;
+
+    let accounts = classroom_assistant::Entity::find()
+        .filter(classroom_assistant::Column::ClassroomId.eq(id))
+        .order_by_asc(classroom_assistant::Column::Id)
+        .find_also_related(account::Entity)
+        .all(&state.db)
+        .await?;
+
+    Ok(Json(
+        accounts
+            .into_iter()
+            .filter_map(|(_, account_model)| account_model.map(AccountResponse::from_model))
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/classrooms/{id}/assistants/{account_id}",
+    params(AssistantPath),
+    tag = "Assistants",
+    security(("bearer" = [])),
+    responses(
+        (status = 204, description = "Assistant assigned; assigning twice is a no-op"),
+        (status = 400, description = "Account does not exist or is not an assistant"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller may not manage classrooms"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn assign_assistant(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path((id, account_id)): Path<(i32, i32)>,
+) -> Result<StatusCode, AppError> {
+    auth.require(Permission::ManageClassrooms)?;
+    find_classroom(&state, id).await?;
+
+    let account_model = account::Entity::find_by_id(account_id)
+        .one(&state.db)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("account not found".into()))?;
+    if AccountRole::from_str(&account_model.role) != Some(AccountRole::Assistant) {
+        return Err(AppError::BadRequest(
+            "only accounts with the assistant role can be assigned".into(),
+        ));
+    }
+
+    let existing = classroom_assistant::Entity::find()
+        .filter(classroom_assistant::Column::ClassroomId.eq(id))
+        .filter(classroom_assistant::Column::AccountId.eq(account_id))
+        .one(&state.db)
+        .await?;
+    if existing.is_none() {
+        classroom_assistant::ActiveModel {
+            classroom_id: Set(id),
+            account_id: Set(account_id),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(&state.db)
+        .await?;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/classrooms/{id}/assistants/{account_id}",
+    params(AssistantPath),
+    tag = "Assistants",
+    security(("bearer" = [])),
+    responses(
+        (status = 204, description = "Assistant unassigned"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller may not manage classrooms"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn unassign_assistant(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path((id, account_id)): Path<(i32, i32)>,
+) -> Result<StatusCode, AppError> {
+    auth.require(Permission::ManageClassrooms)?;
+    find_classroom(&state, id).await?;
+
+    classroom_assistant::Entity::delete_many()
+        .filter(classroom_assistant::Column::ClassroomId.eq(id))
+        .filter(classroom_assistant::Column::AccountId.eq(account_id))
+        .exec(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn find_classroom(state: &AppState, id: i32) -> Result<classroom::Model, AppError> {
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)
+}
@@ -0,0 +1,50 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{
+    auth::bearer_token, dto::AccountRole, routes::request_id::RequestId, services::audit,
+    state::AppState,
+};
+
+/// Writes every state-changing request made with a valid staff token (any role but
+/// `user`) to the audit log, including rejected ones. Reads and student requests are
+/// not recorded.
+pub async fn record_staff_actions(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    if matches!(method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(request).await;
+    }
+    let Some(claims) = bearer_token(request.headers())
+        .and_then(|token| state.jwt.verify(token).ok())
+        .filter(|claims| {
+            AccountRole::from_str(&claims.role).is_some_and(|role| role != AccountRole::User)
+        })
+    else {
+        return next.run(request).await;
+    };
+
+    let path = request.uri().path().to_owned();
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|RequestId(id)| id.clone());
+    let response = next.run(request).await;
+    audit::record_request(
+        &state.db,
+        claims.sub,
+        &method,
+        &path,
+        response.status().as_u16(),
+        request_id,
+    )
+    .await;
+    response
+}
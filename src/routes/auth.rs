@@ -1,17 +1,33 @@
-use axum::{Json, extract::State};
+use std::net::SocketAddr;
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, Query, State},
+};
 use chrono::Utc;
 use sea_orm::{
-    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
-    PaginatorTrait, QueryFilter,
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect,
 };
+use serde_json::json;
 
 use crate::{
+    auth::{
+        AuthAccount,
+        device::{self, DeviceFingerprint},
+        ensure_approved, permissions,
+    },
     dto::{
-        AccountResponse, AccountRole, AdminExistsResponse, LoginClassroomInfo, LoginRequest,
-        LoginResponse,
+        AccountResponse, AccountRole, AccountStatus, AdminExistsResponse, ClassroomStatus,
+        CurrentAccountResponse, LoginClassroomInfo, LoginEventListParams, LoginEventResponse,
+        LoginRequest, LoginResponse,
     },
-    entities::{account, classroom, user},
+    entities::{account, classroom, login_event, user},
     error::AppError,
+    routes::passkey,
+    services::{
+        audit, daily_stats, exam_clock::ExamClock, login_audit, student_events, tickets, webhook,
+    },
     state::AppState,
 };
 
@@ -22,13 +38,51 @@ use crate::{
     request_body = LoginRequest,
     responses(
         (status = 200, description = "Login berhasil", body = LoginResponse),
-        (status = 400, description = "Permintaan tidak valid")
+        (status = 400, description = "Permintaan tidak valid"),
+        (status = 401, description = "Kode masuk kartu ujian tidak valid, sudah dipakai, kelasnya tidak aktif, atau dicetak untuk NPM lain"),
+        (status = 403, description = "Percobaan ujian terikat ke perangkat lain, akun wajib masuk dengan passkey, atau akun menunggu persetujuan admin (`code: \"account_pending\"`)"),
+        (status = 422, description = "Ujian ditahan karena uji eksekusi kode sebelum ujian dimulai gagal")
     )
 )]
 pub async fn login(
     State(state): State<AppState>,
-    Json(payload): Json<LoginRequest>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    DeviceFingerprint(fingerprint): DeviceFingerprint,
+    Json(mut payload): Json<LoginRequest>,
 ) -> Result<Json<LoginResponse>, AppError> {
+    let outcome = match redeem_join_code(&state, &mut payload).await {
+        Ok(()) => attempt_login(&state, fingerprint.as_deref(), &payload).await,
+        Err(err) => Err(err),
+    };
+    login_audit::record(&state, payload.npm.trim(), client.ip(), &outcome).await;
+    outcome.map(Json)
+}
+
+/// Replaces the NPM with the one on the exam ticket when a join code is given.
+async fn redeem_join_code(state: &AppState, payload: &mut LoginRequest) -> Result<(), AppError> {
+    let Some(code) = payload
+        .join_code
+        .as_deref()
+        .filter(|code| !code.trim().is_empty())
+    else {
+        return Ok(());
+    };
+    let npm = tickets::redeem(state, code, Utc::now()).await?;
+    if !payload.npm.trim().is_empty() && payload.npm.trim() != npm {
+        return Err(AppError::Unauthorized(
+            "join code was printed for another NPM".into(),
+        ));
+    }
+    payload.npm = npm;
+    payload.as_admin = false;
+    Ok(())
+}
+
+async fn attempt_login(
+    state: &AppState,
+    fingerprint: Option<&str>,
+    payload: &LoginRequest,
+) -> Result<LoginResponse, AppError> {
     let npm = payload.npm.trim();
 
     if npm.is_empty() {
@@ -41,12 +95,22 @@ pub async fn login(
         .await?;
 
     if let Some(model) = existing {
-        let classroom = find_classroom_for_npm(&state.db, npm).await?;
-        return Ok(Json(LoginResponse {
+        ensure_approved(&model)?;
+        passkey::check_npm_login_allowed(state, &model).await?;
+        let classroom = find_classroom_for_npm(state, npm, fingerprint).await?;
+        let (token, token_expires_at) = state.jwt.issue(&model)?;
+        let classroom_id = classroom.as_ref().map(|info| info.id);
+        daily_stats::record_login(&state.db, classroom_id).await;
+        audit::record_login(&state.db, model.id, "npm", classroom_id).await;
+        let permissions = permissions::for_role(AccountRole::from_str(&model.role));
+        return Ok(LoginResponse {
             account: AccountResponse::from_model(model),
             classroom,
             is_new: false,
-        }));
+            token,
+            token_expires_at,
+            permissions,
+        });
     }
 
     let admin_exists = account::Entity::find()
@@ -67,10 +131,25 @@ pub async fn login(
         AccountRole::User
     };
 
+    // NPMs already on a roster are known to the lab and skip the approval queue.
+    let pending = state.account_approval_required
+        && role != AccountRole::Admin
+        && user::Entity::find()
+            .filter(user::Column::Npm.eq(npm))
+            .count(&state.db)
+            .await?
+            == 0;
+    let status = if pending {
+        AccountStatus::Pending
+    } else {
+        AccountStatus::Active
+    };
+
     let now = Utc::now();
     let account = account::ActiveModel {
         npm: Set(npm.to_owned()),
         role: Set(role.as_str().to_owned()),
+        status: Set(status.as_str().to_owned()),
         created_at: Set(now),
         updated_at: Set(now),
         ..Default::default()
@@ -78,13 +157,85 @@ pub async fn login(
     .insert(&state.db)
     .await?;
 
-    let classroom = find_classroom_for_npm(&state.db, npm).await?;
+    if pending {
+        webhook::broadcast(
+            state,
+            "account.pending",
+            json!({ "accountId": account.id, "npm": account.npm, "createdAt": now }),
+        );
+        return Err(AppError::AccountPending(
+            "Akun dibuat dan menunggu persetujuan admin.".into(),
+        ));
+    }
+
+    let classroom = find_classroom_for_npm(state, npm, fingerprint).await?;
+    let (token, token_expires_at) = state.jwt.issue(&account)?;
+    let classroom_id = classroom.as_ref().map(|info| info.id);
+    daily_stats::record_login(&state.db, classroom_id).await;
+    audit::record_login(&state.db, account.id, "npm", classroom_id).await;
 
-    Ok(Json(LoginResponse {
+    Ok(LoginResponse {
         account: AccountResponse::from_model(account),
         classroom,
         is_new: true,
-    }))
+        token,
+        token_expires_at,
+        permissions: permissions::for_role(Some(role)),
+    })
+}
+
+const DEFAULT_LOGIN_EVENT_LIMIT: u64 = 100;
+const MAX_LOGIN_EVENT_LIMIT: u64 = 1000;
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/login-events",
+    params(LoginEventListParams),
+    tag = "Auth",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Percobaan login NPM beserta alamat IP, kelas, dan tanda anomali, terbaru lebih dulu", body = [LoginEventResponse]),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Pemanggil bukan admin")
+    )
+)]
+pub async fn list_login_events(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Query(params): Query<LoginEventListParams>,
+) -> Result<Json<Vec<LoginEventResponse>>, AppError> {
+    auth.require_admin()?;
+
+    let mut query = login_event::Entity::find()
+        .order_by_desc(login_event::Column::Id)
+        .limit(
+            params
+                .limit
+                .unwrap_or(DEFAULT_LOGIN_EVENT_LIMIT)
+                .clamp(1, MAX_LOGIN_EVENT_LIMIT),
+        );
+    if let Some(npm) = params.npm {
+        query = query.filter(login_event::Column::Npm.eq(npm.trim()));
+    }
+    if let Some(classroom_id) = params.classroom_id {
+        query = query.filter(login_event::Column::ClassroomId.eq(classroom_id));
+    }
+    match params.flagged {
+        Some(true) => query = query.filter(login_event::Column::Flags.ne("")),
+        Some(false) => query = query.filter(login_event::Column::Flags.eq("")),
+        None => {}
+    }
+    if let Some(success) = params.success {
+        query = query.filter(login_event::Column::Success.eq(success));
+    }
+    if let Some(since) = params.since {
+        query = query.filter(login_event::Column::CreatedAt.gte(since));
+    }
+    let rows = query.all(&state.db).await?;
+
+    Ok(Json(
+        rows.into_iter().map(LoginEventResponse::from).collect(),
+    ))
 }
 
 #[utoipa::path(
@@ -107,13 +258,34 @@ pub async fn admin_exists(
     Ok(Json(AdminExistsResponse { exists }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/auth/me",
+    tag = "Auth",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Akun pemilik token beserta izinnya", body = CurrentAccountResponse),
+        (status = 401, description = "Token tidak ada atau tidak valid")
+    )
+)]
+pub async fn me(auth: AuthAccount) -> Json<CurrentAccountResponse> {
+    let permissions = auth.permissions();
+    Json(CurrentAccountResponse {
+        account: AccountResponse::from_model(auth.account),
+        permissions,
+    })
+}
+
 async fn find_classroom_for_npm(
-    db: &DatabaseConnection,
+    state: &AppState,
     npm: &str,
+    fingerprint: Option<&str>,
 ) -> Result<Option<LoginClassroomInfo>, AppError> {
+    let db = &state.db;
     let record = user::Entity::find()
         .filter(user::Column::Npm.eq(npm))
         .find_also_related(classroom::Entity)
+        .filter(classroom::Column::Status.eq(ClassroomStatus::Published.as_str()))
         .one(db)
         .await?;
 
@@ -122,25 +294,55 @@ async fn find_classroom_for_npm(
             return Err(AppError::Unauthorized("Akun ini tidak aktif.".into()));
         }
 
+        let presetup_override = user_model.presetup_override.clone();
+
         if classroom_model.is_exam {
             let now = Utc::now();
-            if let (Some(start), Some(end)) = (classroom_model.exam_start, classroom_model.exam_end) {
-                if now < start {
+            let clock = ExamClock::load(db, &classroom_model).await?;
+            if clock.start.is_some() && clock.end.is_some() {
+                if !state.time_policy.has_started(&clock, now) {
                     return Err(AppError::Unauthorized("Ujian belum dimulai.".into()));
                 }
-                if now > end {
+                if state.time_policy.has_ended(&clock, now) {
                     return Err(AppError::Unauthorized("Ujian telah berakhir.".into()));
                 }
             }
+            if classroom_model.exam_gated_at.is_some() && user_model.exam_started_at.is_none() {
+                return Err(AppError::ClassroomNotReady(
+                    "Ujian ditahan karena layanan eksekusi kode sedang bermasalah; coba lagi beberapa saat lagi."
+                        .into(),
+                ));
+            }
+
+            let user_model = device::enforce(
+                state,
+                &classroom_model,
+                user_model,
+                fingerprint,
+                "login",
+                true,
+            )
+            .await?;
 
             if user_model.exam_started_at.is_none() {
                 let mut user_am: user::ActiveModel = user_model.into();
                 user_am.exam_started_at = Set(Some(now));
-                user_am.update(db).await?;
+                user_am.updated_at = Set(now);
+                let joined = user_am.update(db).await?;
+                student_events::joined(state, &joined, now).await;
             }
+
+            return Ok(Some(
+                LoginClassroomInfo::from_model(classroom_model)
+                    .with_clock(&clock, now)
+                    .with_presetup_override(presetup_override),
+            ));
         }
 
-        Ok(Some(LoginClassroomInfo::from_model(classroom_model)))
+        Ok(Some(
+            LoginClassroomInfo::from_model(classroom_model)
+                .with_presetup_override(presetup_override),
+        ))
     } else {
         Ok(None)
     }
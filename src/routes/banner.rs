@@ -0,0 +1,154 @@
+use axum::{
+    Json,
+    extract::State,
+    http::{
+        StatusCode,
+        header::{CACHE_CONTROL, HeaderValue},
+    },
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use sea_orm::{ActiveValue::Set, EntityTrait, sea_query::OnConflict};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{BannerResponse, UpdateBannerRequest},
+    entities::banner,
+    error::AppError,
+    services::banner::{self as banner_service, BANNER_ID},
+    state::AppState,
+};
+
+const MAX_MESSAGE_CHARS: usize = 1000;
+
+#[utoipa::path(
+    get,
+    path = "/api/banner",
+    tag = "Banner",
+    responses(
+        (status = 200, description = "Announcement currently on display", body = BannerResponse),
+        (status = 204, description = "No announcement is on display")
+    )
+)]
+pub async fn current_banner(State(state): State<AppState>) -> Result<Response, AppError> {
+    let current = banner_service::current(&state.db, Utc::now()).await?;
+    // Operators expect a change to reach clients on their next poll.
+    let no_cache = [(CACHE_CONTROL, HeaderValue::from_static("no-cache"))];
+    Ok(match current {
+        Some(model) => (no_cache, Json(BannerResponse::from(model))).into_response(),
+        None => (StatusCode::NO_CONTENT, no_cache).into_response(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/banner",
+    tag = "Banner",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Stored announcement, including one scheduled for later or already ended", body = BannerResponse),
+        (status = 204, description = "No announcement is stored"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn get_banner(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Response, AppError> {
+    auth.require_admin()?;
+
+    Ok(match banner_service::load(&state.db).await? {
+        Some(model) => Json(BannerResponse::from(model)).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    })
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/admin/banner",
+    tag = "Banner",
+    security(("bearer" = [])),
+    request_body = UpdateBannerRequest,
+    responses(
+        (status = 200, description = "Announcement stored; clients see it on their next bootstrap or banner poll", body = BannerResponse),
+        (status = 400, description = "Empty or oversized message, or the window ends before it starts"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn put_banner(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Json(payload): Json<UpdateBannerRequest>,
+) -> Result<Json<BannerResponse>, AppError> {
+    auth.require_admin()?;
+
+    let message = payload.message.trim().to_string();
+    if message.is_empty() {
+        return Err(AppError::BadRequest("message must not be empty".into()));
+    }
+    if message.chars().count() > MAX_MESSAGE_CHARS {
+        return Err(AppError::BadRequest(format!(
+            "message must be at most {MAX_MESSAGE_CHARS} characters"
+        )));
+    }
+    if let (Some(starts_at), Some(ends_at)) = (payload.starts_at, payload.ends_at)
+        && ends_at <= starts_at
+    {
+        return Err(AppError::BadRequest("endsAt must be after startsAt".into()));
+    }
+
+    let model = banner::ActiveModel {
+        id: Set(BANNER_ID),
+        message: Set(message),
+        level: Set(payload.level.as_str().to_owned()),
+        starts_at: Set(payload.starts_at),
+        ends_at: Set(payload.ends_at),
+        updated_by: Set(Some(auth.account.id)),
+        updated_at: Set(Utc::now()),
+    };
+    banner::Entity::insert(model)
+        .on_conflict(
+            OnConflict::column(banner::Column::Id)
+                .update_columns([
+                    banner::Column::Message,
+                    banner::Column::Level,
+                    banner::Column::StartsAt,
+                    banner::Column::EndsAt,
+                    banner::Column::UpdatedBy,
+                    banner::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(&state.db)
+        .await?;
+
+    let stored = banner_service::load(&state.db)
+        .await?
+        .ok_or_else(|| AppError::Internal("banner vanished after being stored".into()))?;
+    Ok(Json(BannerResponse::from(stored)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/banner",
+    tag = "Banner",
+    security(("bearer" = [])),
+    responses(
+        (status = 204, description = "Announcement cleared"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn delete_banner(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<StatusCode, AppError> {
+    auth.require_admin()?;
+
+    banner::Entity::delete_by_id(BANNER_ID)
+        .exec(&state.db)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,145 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::Utc;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{
+        AccountResponse, BannerResponse, BootstrapParams, BootstrapResponse, ExamClockResponse,
+        LoginClassroomInfo, MembershipInfo, OwnCodeInfo, SubmissionCooldownInfo, TaskResponse,
+    },
+    entities::{classroom, task, user},
+    error::AppError,
+    routes::message,
+    services::{banner, exam_clock::ExamClock, submission_cooldown},
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/bootstrap",
+    params(BootstrapParams),
+    tag = "Auth",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Account, memberships and the selected classroom's state in one payload", body = BootstrapResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "classroomId is not one of the account's memberships")
+    )
+)]
+pub async fn bootstrap(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Query(params): Query<BootstrapParams>,
+) -> Result<Json<BootstrapResponse>, AppError> {
+    let now = Utc::now();
+
+    let memberships: Vec<(user::Model, classroom::Model)> = user::Entity::find()
+        .filter(user::Column::Npm.eq(&auth.account.npm))
+        .find_also_related(classroom::Entity)
+        .order_by_asc(user::Column::Id)
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .filter_map(|(user_model, classroom_model)| classroom_model.map(|c| (user_model, c)))
+        .filter(|(_, classroom_model)| classroom_model.is_published())
+        .collect();
+
+    let mut clocks = Vec::with_capacity(memberships.len());
+    for (_, classroom_model) in &memberships {
+        clocks.push(ExamClock::load(&state.db, classroom_model).await?);
+    }
+
+    let selected = match params.classroom_id {
+        Some(classroom_id) => Some(
+            memberships
+                .iter()
+                .position(|(_, classroom_model)| classroom_model.id == classroom_id)
+                .ok_or(AppError::ClassroomNotFound)?,
+        ),
+        None => {
+            let running_exam = memberships.iter().zip(&clocks).position(
+                |((user_model, classroom_model), clock)| {
+                    user_model.active
+                        && classroom_model.is_exam
+                        && state.time_policy.is_running(clock, now)
+                },
+            );
+            running_exam
+                .or_else(|| {
+                    memberships
+                        .iter()
+                        .position(|(user_model, _)| user_model.active)
+                })
+                .or(if memberships.is_empty() {
+                    None
+                } else {
+                    Some(0)
+                })
+        }
+    };
+
+    let mut response = BootstrapResponse {
+        account: AccountResponse::from_model(auth.account),
+        memberships: memberships
+            .iter()
+            .map(|(user_model, classroom_model)| {
+                MembershipInfo::from_models(user_model, classroom_model)
+            })
+            .collect(),
+        classroom: None,
+        exam: None,
+        tasks: Vec::new(),
+        own_code: None,
+        submission_cooldown: None,
+        unread_messages: Vec::new(),
+        banner: banner::current(&state.db, now)
+            .await?
+            .map(BannerResponse::from),
+        server_time: now,
+    };
+
+    if let Some(index) = selected {
+        let (user_model, classroom_model) = memberships[index].clone();
+        let clock = &clocks[index];
+
+        response.tasks = task::Entity::find()
+            .filter(task::Column::ClassroomId.eq(classroom_model.id))
+            .order_by_asc(task::Column::Position)
+            .all(&state.db)
+            .await?
+            .into_iter()
+            .map(TaskResponse::from)
+            .collect();
+        response.unread_messages = message::load_unread_messages(&state.db, user_model.id).await?;
+        if classroom_model.is_exam {
+            response.exam = Some(ExamClockResponse::from_clock(
+                classroom_model.id,
+                clock,
+                now,
+            ));
+        }
+        if let Some(cooldown_secs) = classroom_model.submission_cooldown_secs {
+            let available_at =
+                submission_cooldown::available_at(&classroom_model, user_model.last_graded_at, now);
+            response.submission_cooldown = Some(SubmissionCooldownInfo {
+                cooldown_secs,
+                available_at,
+                remaining_secs: available_at
+                    .map_or(0, |at| submission_cooldown::remaining_secs(at, now)),
+            });
+        }
+        let presetup_override = user_model.presetup_override.clone();
+        response.own_code = Some(OwnCodeInfo::from(user_model));
+        response.classroom = Some(
+            LoginClassroomInfo::from_model(classroom_model)
+                .with_clock(clock, now)
+                .with_presetup_override(presetup_override),
+        );
+    }
+
+    Ok(Json(response))
+}
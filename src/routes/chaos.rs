@@ -0,0 +1,61 @@
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{auth::AuthAccount, dto::ChaosConfig, error::AppError, state::AppState};
+
+/// Path of the controls, which are never slowed down or failed so injection can
+/// always be turned off again.
+pub const CONTROL_PATH: &str = "/api/admin/chaos";
+
+/// Current fault injection rates.
+pub async fn get_chaos(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<ChaosConfig>, AppError> {
+    auth.require_admin()?;
+    Ok(Json(state.chaos.config()))
+}
+
+/// Replaces the fault injection rates; fields left out are set to zero.
+pub async fn put_chaos(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Json(payload): Json<ChaosConfig>,
+) -> Result<Json<ChaosConfig>, AppError> {
+    auth.require_admin()?;
+    state.chaos.set(payload)?;
+    tracing::warn!("chaos: fault injection set to {payload:?}");
+    Ok(Json(state.chaos.config()))
+}
+
+/// Turns every fault off.
+pub async fn delete_chaos(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<StatusCode, AppError> {
+    auth.require_admin()?;
+    state.chaos.set(ChaosConfig::default())?;
+    tracing::warn!("chaos: fault injection turned off");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Slows down or fails API requests at the configured rates; the docs and setup page
+/// are left alone.
+pub async fn inject(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let path = request.uri().path();
+    if !path.starts_with("/api/") || path == CONTROL_PATH {
+        return next.run(request).await;
+    }
+    if let Some(delay) = state.chaos.delay() {
+        tokio::time::sleep(delay).await;
+    }
+    if let Some(err) = state.chaos.db_error() {
+        return AppError::from(err).into_response();
+    }
+    next.run(request).await
+}
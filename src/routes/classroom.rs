@@ -1,27 +1,56 @@
 use axum::{
-    response::sse::{Event, Sse},
     Json,
-    extract::{Path, State, Query},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::sse::{Event, Sse},
 };
 use chrono::Utc;
+use futures_util::stream::Stream;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait, IntoActiveModel, QueryFilter,
-    QueryOrder, TransactionTrait,
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DatabaseTransaction, EntityTrait,
+    IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+    sea_query::{Expr, LikeExpr},
 };
-use utoipa::IntoParams;
 use serde::Deserialize;
-use futures_util::stream::{Stream};
-use std::time::Duration;
-
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+use tokio::{sync::broadcast::error::RecvError, time::MissedTickBehavior};
+use utoipa::IntoParams;
 
 use crate::{
+    auth::{
+        AuthAccount,
+        device::{self, DeviceFingerprint},
+        permissions::Permission,
+    },
     dto::{
-        ClassroomResponse, CreateClassroomRequest, CreateUserRequest, UpdateClassroomRequest,
-        UpdateUserRequest, UserResponse, classroom::serialize_tasks, FinishExamRequest, Judge0SubmissionRequest, Judge0SubmissionResponse, UpdateUsersStatusRequest,
+        ApiVersion, ClassroomResponseV2, ClassroomStatus, CreateClassroomRequestV2,
+        CreateUserRequest, DeleteClassroomParams, DeleteClassroomResponse, DeleteIntentResponse,
+        DeletionOutcome, DevicePolicy, ExamTimeUpEvent, ExamWarningEvent, FinishExamRequest,
+        GradingStatusParams, GradingStatusResponse, PostFinishPolicy, SettingsChange,
+        SubmissionFeedback, TaskInput, TestMode, TransferUsersRequest, TransferUsersResponse,
+        UpdateClassroomRequestV2, UpdateUserPresetupRequest, UpdateUserRequest,
+        UpdateUsersStatusRequest, UserResponse, UserStatusEvent, ValidatedJson, Versioned,
     },
-    entities::{classroom, user},
+    entities::{classroom, submission, task, user},
     error::AppError,
+    routes::{
+        etag::{self, Cached, ETag},
+        message,
+        task::{non_empty, validate_weight},
+        test_case::find_task,
+    },
+    services::{
+        audit, classroom_deletion,
+        code_visibility::CodeViewer,
+        event_bus::{self, Audience, ClassroomEvent, EventKind},
+        exam_clock::ExamClock,
+        feedback, field_limits, finalizer, grading, language_map, output, post_finish, roster,
+        schedule, settings_history, student_events, submission_cooldown, time_policy,
+        verdict_messages::VerdictMessages,
+    },
     state::AppState,
 };
 
@@ -31,7 +60,7 @@ use crate::{
     params(ClassroomPath),
     tag = "Classrooms",
     responses(
-        (status = 204, description = "Users deactivated after exam"),
+        (status = 204, description = "Unfinished exams auto-submitted from the last saved code (graded in the background) and the classroom's post-finish policy applied to every user"),
         (status = 404, description = "Classroom not found")
     )
 )]
@@ -45,17 +74,13 @@ pub async fn deactivate_users_post_exam(
         return Err(AppError::BadRequest("Not an exam classroom".into()));
     }
 
-    if let Some(end_time) = classroom.exam_end {
-        if Utc::now() > end_time {
-            let user_ids: Vec<i32> = users.into_iter().map(|u| u.id).collect();
-            if !user_ids.is_empty() {
-                user::Entity::update_many()
-                    .col_expr(user::Column::Active, false.into())
-                    .filter(user::Column::Id.is_in(user_ids))
-                    .exec(&state.db)
-                    .await?;
-            }
-        }
+    // Students who never finished get their last saved code handed in first.
+    finalizer::finalize_expired_exam(&state, &classroom).await?;
+
+    let clock = ExamClock::load(&state.db, &classroom).await?;
+    if clock.end.is_some() && state.time_policy.submissions_closed(&clock, Utc::now()) {
+        let user_ids: Vec<i32> = users.into_iter().map(|u| u.id).collect();
+        post_finish::apply_many(&state.db, &classroom, user_ids, Utc::now()).await?;
     }
 
     Ok(StatusCode::NO_CONTENT)
@@ -77,40 +102,135 @@ pub struct ClassroomUserPath {
 #[derive(Deserialize)]
 pub struct EventsParams {
     npm: String,
+    /// Replays stored events after this sequence number before going live.
+    after_seq: Option<i32>,
+}
+
+const MAX_USERS_PER_PAGE: u64 = 200;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct UserListParams {
+    /// Case-insensitive substring matched against name or NPM.
+    search: Option<String>,
+    /// `true` for users still taking part, `false` for finished/deactivated ones.
+    active: Option<bool>,
+    /// Whether the user has already logged in to the exam.
+    exam_started: Option<bool>,
+    /// Lowest score of the user's latest final submission; ungraded users are left out.
+    grade_min: Option<i32>,
+    /// Highest score of the user's latest final submission; ungraded users are left out.
+    grade_max: Option<i32>,
+    /// 1-based page number, only used together with `perPage`.
+    page: Option<u64>,
+    /// Page size (max 200); omit to return every matching user.
+    per_page: Option<u64>,
 }
 
 #[utoipa::path(
     get,
     path = "/api/classrooms",
+    params(
+        ("Accept-Version" = Option<String>, Header, description = "`2` for structured tasks (ClassroomResponseV2)"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag of a previous response")
+    ),
     tag = "Classrooms",
     responses(
-        (status = 200, description = "List all classrooms", body = [ClassroomResponse])
+        (status = 200, description = "List all classrooms. Student code the caller may not read is redacted (`codeRedacted`, `codeLength`)", body = [ClassroomResponse],
+            headers(("ETag" = String, description = "Changes whenever a classroom, user or task changes"))),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag")
     )
 )]
 pub async fn list_classrooms(
     State(state): State<AppState>,
-) -> Result<Json<Vec<ClassroomResponse>>, AppError> {
-    let data = classroom::Entity::find()
+    auth: Option<AuthAccount>,
+    version: ApiVersion,
+    headers: HeaderMap,
+) -> Result<Cached<Versioned<Vec<ClassroomResponseV2>>>, AppError> {
+    let viewer = CodeViewer::load(&state.db, auth.as_ref(), Utc::now()).await?;
+    let tag = ETag::new(&[
+        version.as_str().to_owned(),
+        viewer.cache_key(),
+        etag::fingerprint(
+            &state.db,
+            classroom::Entity::find(),
+            classroom::Column::Id,
+            classroom::Column::UpdatedAt,
+        )
+        .await?,
+        etag::fingerprint(
+            &state.db,
+            user::Entity::find(),
+            user::Column::Id,
+            user::Column::UpdatedAt,
+        )
+        .await?,
+        etag::fingerprint(
+            &state.db,
+            task::Entity::find(),
+            task::Column::Id,
+            task::Column::UpdatedAt,
+        )
+        .await?,
+    ]);
+    if tag.matches(&headers) {
+        return Ok(Cached::NotModified(tag));
+    }
+
+    let mut data = classroom::Entity::find()
         .order_by_asc(classroom::Column::Id)
         .find_with_related(user::Entity)
         .all(&state.db)
         .await?;
+    // Students only see the classrooms whose roster has them.
+    if let Some(auth) = auth.as_ref().filter(|auth| !auth.is_staff()) {
+        data.retain(|(_, users)| {
+            users
+                .iter()
+                .any(|user_model| user_model.npm == auth.account.npm)
+        });
+    }
+
+    let mut tasks_by_classroom: HashMap<i32, Vec<task::Model>> = HashMap::new();
+    for task_model in task::Entity::find()
+        .order_by_asc(task::Column::ClassroomId)
+        .order_by_asc(task::Column::Position)
+        .all(&state.db)
+        .await?
+    {
+        tasks_by_classroom
+            .entry(task_model.classroom_id)
+            .or_default()
+            .push(task_model);
+    }
 
     let payload = data
         .into_iter()
-        .map(|(classroom, users)| ClassroomResponse::from_models(classroom, users))
+        .map(|(classroom, users)| {
+            let tasks = tasks_by_classroom.remove(&classroom.id).unwrap_or_default();
+            let mut response = ClassroomResponseV2::from_models(classroom, users, tasks);
+            response.users = viewer.shape_all(response.id, response.users);
+            response
+        })
         .collect();
 
-    Ok(Json(payload))
+    Ok(Cached::Fresh(tag, Versioned(version, payload)))
 }
 
 #[utoipa::path(
     get,
     path = "/api/classrooms/{id}",
-    params(ClassroomPath),
+    params(
+        ClassroomPath,
+        ("Accept-Version" = Option<String>, Header, description = "`2` for structured tasks (ClassroomResponseV2)"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag of a previous response")
+    ),
     tag = "Classrooms",
     responses(
-        (status = 200, description = "Get classroom by id", body = ClassroomResponse),
+        (status = 200, description = "Get classroom by id. Student code the caller may not read is redacted (`codeRedacted`, `codeLength`)", body = ClassroomResponse,
+            headers(("ETag" = String, description = "Changes whenever the classroom, its users or its tasks change"))),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
         (status = 404, description = "Classroom not found")
     )
 )]
@@ -118,14 +238,68 @@ pub async fn list_classrooms(
 pub async fn get_classroom(
     State(state): State<AppState>,
     Path(id): Path<i32>,
-) -> Result<Json<ClassroomResponse>, AppError> {
+    auth: Option<AuthAccount>,
+    version: ApiVersion,
+    headers: HeaderMap,
+) -> Result<Cached<Versioned<ClassroomResponseV2>>, AppError> {
+    let viewer = CodeViewer::load(&state.db, auth.as_ref(), Utc::now()).await?;
+    let tag = ETag::new(&[
+        version.as_str().to_owned(),
+        viewer.cache_key(),
+        etag::fingerprint(
+            &state.db,
+            classroom::Entity::find().filter(classroom::Column::Id.eq(id)),
+            classroom::Column::Id,
+            classroom::Column::UpdatedAt,
+        )
+        .await?,
+        users_fingerprint(&state.db, id).await?,
+        etag::fingerprint(
+            &state.db,
+            task::Entity::find().filter(task::Column::ClassroomId.eq(id)),
+            task::Column::Id,
+            task::Column::UpdatedAt,
+        )
+        .await?,
+    ]);
+    if tag.matches(&headers) {
+        return Ok(Cached::NotModified(tag));
+    }
+
     let (classroom, users) = load_classroom_with_users(&state, id).await?;
-    Ok(Json(ClassroomResponse::from_models(classroom, users)))
+    let tasks = load_classroom_tasks(&state.db, id).await?;
+    let mut response = ClassroomResponseV2::from_models(classroom, users, tasks);
+    response.users = viewer.shape_all(id, response.users);
+    Ok(Cached::Fresh(tag, Versioned(version, response)))
+}
+
+/// Escapes the LIKE wildcards in `value` so it only matches literally, with a
+/// backslash as the escape character.
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if matches!(ch, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+async fn users_fingerprint(db: &DatabaseConnection, classroom_id: i32) -> Result<String, AppError> {
+    etag::fingerprint(
+        db,
+        user::Entity::find().filter(user::Column::ClassroomId.eq(classroom_id)),
+        user::Column::Id,
+        user::Column::UpdatedAt,
+    )
+    .await
 }
 
 #[utoipa::path(
     post,
     path = "/api/classrooms",
+    params(("Accept-Version" = Option<String>, Header, description = "`2` for structured tasks (CreateClassroomRequestV2)")),
     tag = "Classrooms",
     request_body = CreateClassroomRequest,
     responses(
@@ -135,12 +309,18 @@ pub async fn get_classroom(
 )]
 pub async fn create_classroom(
     State(state): State<AppState>,
-    Json(payload): Json<CreateClassroomRequest>,
-) -> Result<(StatusCode, Json<ClassroomResponse>), AppError> {
+    version: ApiVersion,
+    ValidatedJson(payload): ValidatedJson<CreateClassroomRequestV2>,
+) -> Result<(StatusCode, Versioned<ClassroomResponseV2>), AppError> {
+    let language = language_map::validate(
+        &state,
+        payload.programming_language.as_deref().unwrap_or_default(),
+    )
+    .await?;
     let txn = state.db.begin().await?;
     let now = Utc::now();
 
-    let CreateClassroomRequest {
+    let CreateClassroomRequestV2 {
         name,
         programming_language,
         lock_language,
@@ -151,21 +331,73 @@ pub async fn create_classroom(
         exam_start,
         exam_end,
         presetup_code,
+        test_mode,
+        test_sample_size,
+        practice_mode,
+        status,
+        term,
+        output_limit_kb,
+        max_code_chars,
+        max_description_chars,
+        submission_cooldown_secs,
+        post_finish_policy,
     } = payload;
 
+    let test_sample_size = validate_sample_size(test_sample_size.unwrap_or(3))?;
+    let status = status.unwrap_or(ClassroomStatus::Published);
+    if status == ClassroomStatus::Archived {
+        return Err(AppError::BadRequest(
+            "status must be draft or published".into(),
+        ));
+    }
+
     let programming_language = programming_language.unwrap_or_default().trim().to_string();
-    let tasks = serialize_tasks(&tasks);
 
     let classroom_model = classroom::ActiveModel {
         name: sea_orm::ActiveValue::Set(name),
         programming_language: sea_orm::ActiveValue::Set(programming_language),
         language_locked: sea_orm::ActiveValue::Set(lock_language.unwrap_or(false)),
-        tasks: sea_orm::ActiveValue::Set(tasks),
+        tasks: sea_orm::ActiveValue::Set("[]".to_string()),
         is_exam: sea_orm::ActiveValue::Set(is_exam.unwrap_or(false)),
+        practice_mode: sea_orm::ActiveValue::Set(
+            practice_mode.unwrap_or(!is_exam.unwrap_or(false)),
+        ),
         test_code: sea_orm::ActiveValue::Set(test_code.unwrap_or_default()),
         exam_start: sea_orm::ActiveValue::Set(exam_start),
         exam_end: sea_orm::ActiveValue::Set(exam_end),
         presetup_code: sea_orm::ActiveValue::Set(presetup_code.unwrap_or_default()),
+        test_mode: sea_orm::ActiveValue::Set(
+            test_mode.unwrap_or(TestMode::Full).as_str().to_owned(),
+        ),
+        test_sample_size: sea_orm::ActiveValue::Set(test_sample_size),
+        status: sea_orm::ActiveValue::Set(status.as_str().to_owned()),
+        published_at: sea_orm::ActiveValue::Set(
+            (status == ClassroomStatus::Published).then_some(now),
+        ),
+        device_policy: sea_orm::ActiveValue::Set(DevicePolicy::Off.as_str().to_owned()),
+        term: sea_orm::ActiveValue::Set(normalize_term(term)),
+        output_limit_kb: sea_orm::ActiveValue::Set(validate_output_limit(
+            output_limit_kb.unwrap_or(0),
+        )?),
+        max_code_chars: sea_orm::ActiveValue::Set(field_limits::validate_setting(
+            "maxCodeChars",
+            max_code_chars.unwrap_or(0),
+        )?),
+        max_description_chars: sea_orm::ActiveValue::Set(field_limits::validate_setting(
+            "maxDescriptionChars",
+            max_description_chars.unwrap_or(0),
+        )?),
+        submission_cooldown_secs: sea_orm::ActiveValue::Set(submission_cooldown::validate_setting(
+            submission_cooldown_secs.unwrap_or(0),
+        )?),
+        post_finish_policy: sea_orm::ActiveValue::Set(
+            post_finish_policy
+                .unwrap_or(PostFinishPolicy::Deactivate)
+                .as_str()
+                .to_owned(),
+        ),
+        fingerprint_sharing: sea_orm::ActiveValue::Set(true),
+        resolved_language_id: sea_orm::ActiveValue::Set(language.language_id()),
         created_at: sea_orm::ActiveValue::Set(now),
         updated_at: sea_orm::ActiveValue::Set(now),
         ..Default::default()
@@ -174,23 +406,28 @@ pub async fn create_classroom(
     .await?;
 
     insert_users(&txn, classroom_model.id, users).await?;
+    replace_tasks(&txn, classroom_model.id, tasks).await?;
     txn.commit().await?;
 
     let response = load_classroom_with_users(&state, classroom_model.id).await?;
+    let tasks = load_classroom_tasks(&state.db, classroom_model.id).await?;
     Ok((
         StatusCode::CREATED,
-        Json(ClassroomResponse::from_models(response.0, response.1)),
+        Versioned(
+            version,
+            ClassroomResponseV2::from_models(response.0, response.1, tasks),
+        ),
     ))
 }
 
 #[utoipa::path(
     put,
     path = "/api/classrooms/{id}",
-    params(ClassroomPath),
+    params(ClassroomPath, ("Accept-Version" = Option<String>, Header, description = "`2` for structured tasks (UpdateClassroomRequestV2)")),
     tag = "Classrooms",
     request_body = UpdateClassroomRequest,
     responses(
-        (status = 200, description = "Classroom updated", body = ClassroomResponse),
+        (status = 200, description = "Classroom updated; `scheduleWarnings` lists schedule conflicts the exam window is part of, which do not block the update", body = ClassroomResponse),
         (status = 404, description = "Classroom not found")
     )
 )]
@@ -198,10 +435,19 @@ pub async fn create_classroom(
 pub async fn update_classroom(
     State(state): State<AppState>,
     Path(id): Path<i32>,
-    Json(payload): Json<UpdateClassroomRequest>,
-) -> Result<Json<ClassroomResponse>, AppError> {
+    version: ApiVersion,
+    auth: Option<AuthAccount>,
+    ValidatedJson(payload): ValidatedJson<UpdateClassroomRequestV2>,
+) -> Result<Versioned<ClassroomResponseV2>, AppError> {
     let (classroom_model, _users) = load_classroom_with_users(&state, id).await?;
+    let language = match &payload.programming_language {
+        Some(programming_language) => {
+            Some(language_map::validate(&state, programming_language).await?)
+        }
+        None => None,
+    };
     let txn = state.db.begin().await?;
+    let before = classroom_model.clone();
     let mut classroom_am: classroom::ActiveModel = classroom_model.into_active_model();
 
     if let Some(name) = payload.name {
@@ -211,14 +457,18 @@ pub async fn update_classroom(
         let programming_language = programming_language.trim().to_string();
         classroom_am.programming_language = sea_orm::ActiveValue::Set(programming_language);
     }
+    if let Some(language) = language {
+        language_map::apply(&mut classroom_am, language);
+    }
     if let Some(lock_language) = payload.lock_language {
         classroom_am.language_locked = sea_orm::ActiveValue::Set(lock_language);
     }
-    if let Some(tasks) = payload.tasks {
-        classroom_am.tasks = sea_orm::ActiveValue::Set(serialize_tasks(&tasks));
-    }
     if let Some(is_exam) = payload.is_exam {
         classroom_am.is_exam = sea_orm::ActiveValue::Set(is_exam);
+        // Exams never run with networking.
+        if is_exam && before.sandbox_enable_network == Some(true) {
+            classroom_am.sandbox_enable_network = sea_orm::ActiveValue::Set(None);
+        }
     }
     if let Some(test_code) = payload.test_code {
         classroom_am.test_code = sea_orm::ActiveValue::Set(test_code);
@@ -232,71 +482,304 @@ pub async fn update_classroom(
     if let Some(presetup_code) = payload.presetup_code {
         classroom_am.presetup_code = sea_orm::ActiveValue::Set(presetup_code);
     }
+    if let Some(test_mode) = payload.test_mode {
+        classroom_am.test_mode = sea_orm::ActiveValue::Set(test_mode.as_str().to_owned());
+    }
+    if let Some(test_sample_size) = payload.test_sample_size {
+        classroom_am.test_sample_size =
+            sea_orm::ActiveValue::Set(validate_sample_size(test_sample_size)?);
+    }
+    if let Some(practice_mode) = payload.practice_mode {
+        classroom_am.practice_mode = sea_orm::ActiveValue::Set(practice_mode);
+    }
+    if let Some(term) = payload.term {
+        classroom_am.term = sea_orm::ActiveValue::Set(normalize_term(Some(term)));
+    }
+    if let Some(output_limit_kb) = payload.output_limit_kb {
+        classroom_am.output_limit_kb =
+            sea_orm::ActiveValue::Set(validate_output_limit(output_limit_kb)?);
+    }
+    if let Some(max_code_chars) = payload.max_code_chars {
+        classroom_am.max_code_chars = sea_orm::ActiveValue::Set(field_limits::validate_setting(
+            "maxCodeChars",
+            max_code_chars,
+        )?);
+    }
+    if let Some(max_description_chars) = payload.max_description_chars {
+        classroom_am.max_description_chars = sea_orm::ActiveValue::Set(
+            field_limits::validate_setting("maxDescriptionChars", max_description_chars)?,
+        );
+    }
+    if let Some(submission_cooldown_secs) = payload.submission_cooldown_secs {
+        classroom_am.submission_cooldown_secs = sea_orm::ActiveValue::Set(
+            submission_cooldown::validate_setting(submission_cooldown_secs)?,
+        );
+    }
+    if let Some(post_finish_policy) = payload.post_finish_policy {
+        classroom_am.post_finish_policy =
+            sea_orm::ActiveValue::Set(post_finish_policy.as_str().to_owned());
+    }
     classroom_am.updated_at = sea_orm::ActiveValue::Set(Utc::now());
 
     let updated_classroom = classroom_am.update(&txn).await?;
+    settings_history::record(
+        &txn,
+        &before,
+        &updated_classroom,
+        SettingsChange::Update,
+        auth.map(|auth| auth.account.id),
+    )
+    .await?;
 
     if let Some(users) = payload.users {
-        user::Entity::delete_many()
-            .filter(user::Column::ClassroomId.eq(id))
-            .exec(&txn)
-            .await?;
-        insert_users(&txn, id, users).await?;
+        sync_users(&txn, id, users).await?;
+    }
+
+    if let Some(tasks) = payload.tasks {
+        replace_tasks(&txn, id, tasks).await?;
     }
 
     txn.commit().await?;
 
     let response = load_classroom_with_users(&state, updated_classroom.id).await?;
+    let tasks = load_classroom_tasks(&state.db, updated_classroom.id).await?;
+    let schedule_warnings = if updated_classroom.is_exam {
+        schedule::conflicts_of(
+            &state.db,
+            state.exam_student_capacity,
+            updated_classroom.id,
+            Utc::now(),
+        )
+        .await?
+    } else {
+        Vec::new()
+    };
 
-    Ok(Json(ClassroomResponse::from_models(response.0, response.1)))
+    Ok(Versioned(
+        version,
+        ClassroomResponseV2 {
+            schedule_warnings,
+            ..ClassroomResponseV2::from_models(response.0, response.1, tasks)
+        },
+    ))
 }
 
 #[utoipa::path(
-    delete,
-    path = "/api/classrooms/{id}",
+    post,
+    path = "/api/classrooms/{id}/delete-intent",
     params(ClassroomPath),
     tag = "Classrooms",
+    security(("bearer" = [])),
     responses(
-        (status = 204, description = "Classroom deleted"),
+        (status = 200, description = "What deleting the classroom would remove, and a short-lived token confirming a permanent deletion", body = DeleteIntentResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
         (status = 404, description = "Classroom not found")
     )
 )]
+pub async fn delete_intent(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<DeleteIntentResponse>, AppError> {
+    auth.require_admin()?;
+    ensure_classroom_exists(&state, id).await?;
+
+    let counts = classroom_deletion::row_counts(&state.db, id).await?;
+    let (confirm_token, expires_at) =
+        classroom_deletion::issue_token(&state, id, auth.account.id, Utc::now());
+    Ok(Json(DeleteIntentResponse {
+        classroom_id: id,
+        confirm_token,
+        expires_at,
+        counts,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/classrooms/{id}",
+    params(ClassroomPath, DeleteClassroomParams),
+    tag = "Classrooms",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Classroom deleted, or archived when it has submissions and `permanent` is not set", body = DeleteClassroomResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found"),
+        (status = 428, description = "Permanent deletion of a classroom with submissions without a valid `confirm_token` (`code: \"confirmation_required\"`)")
+    )
+)]
 pub async fn delete_classroom(
     State(state): State<AppState>,
+    auth: AuthAccount,
     Path(id): Path<i32>,
-) -> Result<StatusCode, AppError> {
-    let result = classroom::Entity::delete_by_id(id).exec(&state.db).await?;
+    Query(params): Query<DeleteClassroomParams>,
+) -> Result<Json<DeleteClassroomResponse>, AppError> {
+    auth.require_admin()?;
+    let classroom_model = classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
 
+    let counts = classroom_deletion::row_counts(&state.db, id).await?;
+    if counts.submissions > 0 {
+        if !params.permanent {
+            let mut classroom_am = classroom_model.into_active_model();
+            classroom_am.status =
+                sea_orm::ActiveValue::Set(ClassroomStatus::Archived.as_str().to_owned());
+            classroom_am.updated_at = sea_orm::ActiveValue::Set(Utc::now());
+            classroom_am.update(&state.db).await?;
+            return Ok(Json(DeleteClassroomResponse {
+                classroom_id: id,
+                outcome: DeletionOutcome::Archived,
+                counts,
+            }));
+        }
+        classroom_deletion::verify_token(
+            &state,
+            id,
+            auth.account.id,
+            params.confirm_token.as_deref(),
+            Utc::now(),
+        )?;
+    }
+
+    let result = classroom::Entity::delete_by_id(id).exec(&state.db).await?;
     if result.rows_affected == 0 {
         return Err(AppError::ClassroomNotFound);
     }
+    audit::record_deletion(
+        &state.db,
+        auth.account.id,
+        id,
+        &classroom_model.name,
+        &counts,
+    )
+    .await;
 
-    Ok(StatusCode::NO_CONTENT)
+    Ok(Json(DeleteClassroomResponse {
+        classroom_id: id,
+        outcome: DeletionOutcome::Deleted,
+        counts,
+    }))
 }
 
 #[utoipa::path(
     get,
     path = "/api/classrooms/{id}/users",
-    params(ClassroomPath),
+    params(
+        ClassroomPath,
+        UserListParams,
+        ("If-None-Match" = Option<String>, Header, description = "ETag of a previous response")
+    ),
     tag = "Users",
     responses(
-        (status = 200, description = "List users for classroom", body = [UserResponse]),
+        (status = 200, description = "List users for classroom. Student code the caller may not read is redacted (`codeRedacted`, `codeLength`)", body = [UserResponse],
+            headers(
+                ("X-Total-Count" = u64, description = "Number of users matching the filters"),
+                ("ETag" = String, description = "Changes whenever a user of the classroom changes")
+            )),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
         (status = 404, description = "Classroom not found")
     )
 )]
 pub async fn list_classroom_users(
     State(state): State<AppState>,
     Path(id): Path<i32>,
-) -> Result<Json<Vec<UserResponse>>, AppError> {
+    auth: Option<AuthAccount>,
+    Query(params): Query<UserListParams>,
+    request_headers: HeaderMap,
+) -> Result<Cached<(HeaderMap, Json<Vec<UserResponse>>)>, AppError> {
     ensure_classroom_exists(&state, id).await?;
 
-    let users = user::Entity::find()
-        .filter(user::Column::ClassroomId.eq(id))
-        .order_by_asc(user::Column::Id)
-        .all(&state.db)
-        .await?;
+    let viewer = CodeViewer::load(&state.db, auth.as_ref(), Utc::now()).await?;
+    let filters_on_grade = params.grade_min.is_some() || params.grade_max.is_some();
+    let mut parts = vec![users_fingerprint(&state.db, id).await?, viewer.cache_key()];
+    if filters_on_grade {
+        parts.push(
+            etag::fingerprint(
+                &state.db,
+                submission::Entity::find().filter(submission::Column::ClassroomId.eq(id)),
+                submission::Column::Id,
+                submission::Column::GradedAt,
+            )
+            .await?,
+        );
+    }
+    let tag = ETag::new(&parts);
+    if tag.matches(&request_headers) {
+        return Ok(Cached::NotModified(tag));
+    }
+
+    let mut query = user::Entity::find().filter(user::Column::ClassroomId.eq(id));
+
+    if let Some(search) = params
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let pattern = || LikeExpr::new(format!("%{}%", escape_like(search))).escape('\\');
+        query = query.filter(
+            Condition::any()
+                .add(Expr::col(user::Column::Name).like(pattern()))
+                .add(Expr::col(user::Column::Npm).like(pattern())),
+        );
+    }
+    if let Some(active) = params.active {
+        query = query.filter(user::Column::Active.eq(active));
+    }
+    if let Some(exam_started) = params.exam_started {
+        query = if exam_started {
+            query.filter(user::Column::ExamStartedAt.is_not_null())
+        } else {
+            query.filter(user::Column::ExamStartedAt.is_null())
+        };
+    }
+
+    if filters_on_grade {
+        let latest_score = || {
+            Expr::expr(Expr::cust(
+                "(SELECT s.score FROM submissions s \
+                 WHERE s.user_id = users.id AND s.is_final = 1 \
+                 ORDER BY s.created_at DESC, s.id DESC LIMIT 1)",
+            ))
+        };
+        if let Some(grade_min) = params.grade_min {
+            query = query.filter(latest_score().gte(grade_min));
+        }
+        if let Some(grade_max) = params.grade_max {
+            query = query.filter(latest_score().lte(grade_max));
+        }
+    }
+
+    let total = query.clone().count(&state.db).await?;
 
-    Ok(Json(users.into_iter().map(UserResponse::from).collect()))
+    let mut query = query.order_by_asc(user::Column::Id);
+    if let Some(per_page) = params.per_page {
+        let per_page = per_page.clamp(1, MAX_USERS_PER_PAGE);
+        let page = params.page.unwrap_or(1).max(1);
+        query = query.limit(per_page).offset((page - 1) * per_page);
+    }
+
+    let users = query.all(&state.db).await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-total-count", HeaderValue::from(total));
+
+    Ok(Cached::Fresh(
+        tag,
+        (
+            headers,
+            Json(
+                users
+                    .into_iter()
+                    .map(|user_model| viewer.shape(id, UserResponse::from(user_model)))
+                    .collect(),
+            ),
+        ),
+    ))
 }
 
 #[utoipa::path(
@@ -313,7 +796,7 @@ pub async fn list_classroom_users(
 pub async fn add_user_to_classroom(
     State(state): State<AppState>,
     Path(id): Path<i32>,
-    Json(payload): Json<CreateUserRequest>,
+    ValidatedJson(payload): ValidatedJson<CreateUserRequest>,
 ) -> Result<(StatusCode, Json<UserResponse>), AppError> {
     ensure_classroom_exists(&state, id).await?;
 
@@ -322,7 +805,9 @@ pub async fn add_user_to_classroom(
         classroom_id: sea_orm::ActiveValue::Set(id),
         name: sea_orm::ActiveValue::Set(payload.name),
         npm: sea_orm::ActiveValue::Set(payload.npm),
-        code: sea_orm::ActiveValue::Set(payload.code),
+        code: sea_orm::ActiveValue::Set(payload.code.into()),
+        code_seq: sea_orm::ActiveValue::Set(0),
+        active: sea_orm::ActiveValue::Set(true),
         created_at: sea_orm::ActiveValue::Set(now),
         updated_at: sea_orm::ActiveValue::Set(now),
         ..Default::default()
@@ -341,13 +826,16 @@ pub async fn add_user_to_classroom(
     request_body = UpdateUserRequest,
     responses(
         (status = 200, description = "User updated", body = UserResponse),
+        (status = 401, description = "A deactivated student saved code"),
+        (status = 403, description = "A student updated another roster entry, a field other than `code`, saved code after finishing under `lock_submissions`, or after the exam closed hand-ins"),
         (status = 404, description = "Classroom or user not found")
     )
 )]
 pub async fn update_user_in_classroom(
     State(state): State<AppState>,
     Path((classroom_id, user_id)): Path<(i32, i32)>,
-    Json(payload): Json<UpdateUserRequest>,
+    auth: AuthAccount,
+    ValidatedJson(payload): ValidatedJson<UpdateUserRequest>,
 ) -> Result<Json<UserResponse>, AppError> {
     ensure_classroom_exists(&state, classroom_id).await?;
 
@@ -359,7 +847,27 @@ pub async fn update_user_in_classroom(
     if user_model.classroom_id != classroom_id {
         return Err(AppError::UserNotFound);
     }
+    // Students only autosave their own code, and only while their attempt and the exam
+    // still take hand-ins.
+    if !auth.can(Permission::ManageClassrooms) {
+        auth.require_acting_as(&user_model.npm)?;
+        if payload.name.is_some() || payload.npm.is_some() || payload.active.is_some() {
+            return Err(AppError::Forbidden(
+                "students can only update their own code".into(),
+            ));
+        }
+        if !user_model.active {
+            return Err(AppError::Unauthorized("User is not active".into()));
+        }
+        post_finish::ensure_unlocked(&user_model)?;
+        let classroom_model = classroom::Entity::find_by_id(classroom_id)
+            .one(&state.db)
+            .await?
+            .ok_or(AppError::ClassroomNotFound)?;
+        time_policy::ensure_submissions_open(&state, &classroom_model).await?;
+    }
 
+    let code_seq = user_model.code_seq + 1;
     let mut user_am = user_model.into_active_model();
     if let Some(name) = payload.name {
         user_am.name = sea_orm::ActiveValue::Set(name);
@@ -367,12 +875,54 @@ pub async fn update_user_in_classroom(
     if let Some(npm) = payload.npm {
         user_am.npm = sea_orm::ActiveValue::Set(npm);
     }
+    let now = Utc::now();
     if let Some(code) = payload.code {
-        user_am.code = sea_orm::ActiveValue::Set(code);
+        user_am.code = sea_orm::ActiveValue::Set(code.into());
+        user_am.code_updated_at = sea_orm::ActiveValue::Set(Some(now));
+        user_am.code_seq = sea_orm::ActiveValue::Set(code_seq);
     }
     if let Some(active) = payload.active {
         user_am.active = sea_orm::ActiveValue::Set(active);
+        if active {
+            user_am.submissions_locked_at = sea_orm::ActiveValue::Set(None);
+        }
     }
+    user_am.updated_at = sea_orm::ActiveValue::Set(now);
+
+    let updated_user = user_am.update(&state.db).await?;
+
+    Ok(Json(UserResponse::from(updated_user)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/classrooms/{classroom_id}/users/{user_id}/presetup",
+    params(ClassroomUserPath),
+    tag = "Users",
+    request_body = UpdateUserPresetupRequest,
+    responses(
+        (status = 200, description = "Student presetup override updated", body = UserResponse),
+        (status = 404, description = "Classroom or user not found")
+    )
+)]
+pub async fn update_user_presetup(
+    State(state): State<AppState>,
+    Path((classroom_id, user_id)): Path<(i32, i32)>,
+    Json(payload): Json<UpdateUserPresetupRequest>,
+) -> Result<Json<UserResponse>, AppError> {
+    ensure_classroom_exists(&state, classroom_id).await?;
+
+    let user_model = user::Entity::find_by_id(user_id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+
+    if user_model.classroom_id != classroom_id {
+        return Err(AppError::UserNotFound);
+    }
+
+    let mut user_am = user_model.into_active_model();
+    user_am.presetup_override = sea_orm::ActiveValue::Set(payload.presetup_code);
     user_am.updated_at = sea_orm::ActiveValue::Set(Utc::now());
 
     let updated_user = user_am.update(&state.db).await?;
@@ -416,39 +966,209 @@ pub async fn delete_user_from_classroom(
 #[utoipa::path(
     get,
     path = "/api/classrooms/{id}/events",
-    params(ClassroomPath, ("npm" = String, Query, description = "User NPM")),
+    params(
+        ClassroomPath,
+        ("npm" = String, Query, description = "User NPM"),
+        ("after_seq" = Option<i32>, Query, description = "Replay stored events with a higher sequence number first"),
+        ("Last-Event-ID" = Option<String>, Header, description = "Sent by reconnecting EventSource clients; used when `after_seq` is absent")
+    ),
     tag = "Classrooms",
     responses(
-        (status = 200, description = "Subscribe to classroom events"),
+        (status = 200, description = "Subscribe to classroom events: `message` (MessageEvent) for unread and new proctor messages, `submission` (SubmissionEvent) when a final submission is graded, `clock` (ExamClockResponse) when the exam is paused or resumed, `user-status` (UserStatusEvent) when the student is activated or deactivated, `task-due` (TaskDueEvent) as task due dates approach and pass, `grading-status` (GradingStatusResponse) as a final submission is queued, runs each test case and finishes. In exam classrooms also `warning` (ExamWarningEvent) at each configured threshold, then `timeup` (ExamTimeUpEvent) when the exam ends. Stored events carry their sequence number as the SSE `id`"),
+        (status = 403, description = "The exam attempt is bound to another device"),
+        (status = 429, description = "The user already has the maximum number of open event streams")
     )
 )]
 pub async fn classroom_events(
     State(state): State<AppState>,
     Path(id): Path<i32>,
     Query(params): Query<EventsParams>,
+    headers: HeaderMap,
+    auth: AuthAccount,
+    DeviceFingerprint(fingerprint): DeviceFingerprint,
 ) -> Result<Sse<impl Stream<Item = Result<Event, AppError>>>, AppError> {
-    let (classroom, _user) = find_classroom_and_user(&state.db, id, &params.npm).await?;
+    auth.require_acting_as(&params.npm)?;
+    let (classroom, user_model) = find_classroom_and_user(&state.db, id, &params.npm).await?;
 
-    if !classroom.is_exam {
-        return Err(AppError::BadRequest("Not an exam classroom".into()));
+    if !classroom.is_published() {
+        return Err(AppError::ClassroomNotFound);
     }
+    if classroom.is_exam && classroom.exam_end.is_none() {
+        return Err(AppError::BadRequest("Exam end time not set".into()));
+    }
+
+    let user_model = device::enforce(
+        &state,
+        &classroom,
+        user_model,
+        fingerprint.as_deref(),
+        "event stream",
+        false,
+    )
+    .await?;
 
-    let end_time = classroom.exam_end.ok_or_else(|| AppError::BadRequest("Exam end time not set".into()))?;
+    let connection = state
+        .connections
+        .try_acquire(classroom.id, &user_model.npm)
+        .ok_or_else(|| {
+            AppError::TooManyConnections(format!(
+                "at most {} event streams per user",
+                state.connections.max_per_user()
+            ))
+        })?;
+
+    let after_seq = params.after_seq.or_else(|| {
+        headers
+            .get("last-event-id")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+    });
+
+    // Subscribe before loading the backlog so nothing sent in between is lost.
+    let mut events = state.events.subscribe();
+    let unread = message::load_unread_messages(&state.db, user_model.id).await?;
+    let replay = match after_seq {
+        Some(after_seq) => event_bus::load_after(&state.db, classroom.id, after_seq).await?,
+        None => Vec::new(),
+    };
 
+    let db = state.db.clone();
+    let thresholds = state.exam_warning_thresholds.clone();
+    let time_policy = state.time_policy;
     let stream = async_stream::stream! {
+        // Released when the client disconnects and the stream is dropped.
+        let _connection = connection;
+        let mut delivered: HashSet<i32> = unread.iter().map(|message| message.id).collect();
+        for message in &unread {
+            yield Ok(sse_event("message", message));
+        }
+        // Live events up to here were already replayed from storage.
+        let replayed_seq = replay.last().map(|event| event.seq).unwrap_or(0);
+        for event in &replay {
+            if event.is_for(classroom.id, user_model.id)
+                && let Some(sse) = stored_sse_event(event, &mut delivered)
+            {
+                yield Ok(sse);
+            }
+        }
+
+        // Thresholds are sorted largest first; everything before `announced` was sent.
+        let mut announced = 0;
+        let mut ticker = tokio::time::interval(Duration::from_secs(1));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
         loop {
-            let now = Utc::now();
-            if now >= end_time {
-                yield Ok(Event::default().data("timeup"));
-                break;
+            let received = tokio::select! {
+                _ = ticker.tick() => None,
+                received = events.recv() => Some(received),
+            };
+
+            match received {
+                Some(Ok(event))
+                    if event.seq > replayed_seq && event.is_for(classroom.id, user_model.id) =>
+                {
+                    if let Some(sse) = stored_sse_event(&event, &mut delivered) {
+                        yield Ok(sse);
+                    }
+                }
+                // Missed messages stay unread and are replayed on the next connection.
+                Some(Ok(_)) | Some(Err(RecvError::Lagged(_))) => {}
+                Some(Err(RecvError::Closed)) => break,
+                // Without an exam clock only stored events are streamed.
+                None if !classroom.is_exam => {}
+                None => {
+                    let now = Utc::now();
+                    // Reload pauses every tick so a pause/resume by the proctor shifts the deadline.
+                    match ExamClock::load(&db, &classroom).await {
+                        Ok(clock) if time_policy.has_ended(&clock, now) => {
+                            yield Ok(sse_event("timeup", &ExamTimeUpEvent {
+                                classroom_id: classroom.id,
+                                exam_end: clock.effective_end(now),
+                                server_time: now,
+                            }));
+                            // Clients predating named events only listen for this message.
+                            yield Ok(Event::default().data("timeup"));
+                            break;
+                        }
+                        Ok(clock) => {
+                            if let Some(remaining) = clock.remaining(now) {
+                                // A late subscriber gets only the tightest threshold already crossed.
+                                let crossed = thresholds[announced..]
+                                    .iter()
+                                    .take_while(|threshold| remaining <= **threshold)
+                                    .count();
+                                if crossed > 0 {
+                                    announced += crossed;
+                                    yield Ok(sse_event("warning", &ExamWarningEvent {
+                                        classroom_id: classroom.id,
+                                        threshold_seconds: thresholds[announced - 1].num_seconds(),
+                                        remaining_seconds: remaining.num_seconds(),
+                                        exam_end: clock.effective_end(now),
+                                        server_time: now,
+                                    }));
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            yield Err(AppError::from(err));
+                            break;
+                        }
+                    }
+                }
             }
-            tokio::time::sleep(Duration::from_secs(1)).await;
         }
     };
 
     Ok(Sse::new(stream))
 }
 
+/// SSE frame for a stored event, skipping messages the stream already delivered.
+fn stored_sse_event(event: &ClassroomEvent, delivered: &mut HashSet<i32>) -> Option<Event> {
+    if let EventKind::Message(message) = &event.kind
+        && !delivered.insert(message.id)
+    {
+        return None;
+    }
+    Some(
+        Event::default()
+            .id(event.seq.to_string())
+            .event(event.kind.name())
+            .data(event.kind.payload()),
+    )
+}
+
+fn sse_event<T: serde::Serialize>(name: &str, payload: &T) -> Event {
+    Event::default()
+        .event(name)
+        .json_data(payload)
+        .expect("event payloads serialize to JSON")
+}
+
+fn validate_sample_size(sample_size: i32) -> Result<i32, AppError> {
+    if sample_size < 1 {
+        return Err(AppError::BadRequest(
+            "testSampleSize must be at least 1".into(),
+        ));
+    }
+    Ok(sample_size)
+}
+
+/// `0` clears the classroom's cap so the server default applies.
+fn validate_output_limit(limit_kb: i32) -> Result<Option<i32>, AppError> {
+    if !(0..=output::MAX_LIMIT_KB).contains(&limit_kb) {
+        return Err(AppError::BadRequest(format!(
+            "outputLimitKb must be between 0 and {}",
+            output::MAX_LIMIT_KB
+        )));
+    }
+    Ok((limit_kb > 0).then_some(limit_kb))
+}
+
+/// Trimmed term label; blank means no term.
+pub(crate) fn normalize_term(term: Option<String>) -> Option<String> {
+    term.map(|term| term.trim().to_string())
+        .filter(|term| !term.is_empty())
+}
+
 async fn ensure_classroom_exists(state: &AppState, id: i32) -> Result<(), AppError> {
     let exists = classroom::Entity::find_by_id(id)
         .one(&state.db)
@@ -476,6 +1196,133 @@ async fn load_classroom_with_users(
     }
 }
 
+async fn load_classroom_tasks(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+) -> Result<Vec<task::Model>, AppError> {
+    Ok(task::Entity::find()
+        .filter(task::Column::ClassroomId.eq(classroom_id))
+        .order_by_asc(task::Column::Position)
+        .all(db)
+        .await?)
+}
+
+/// Syncs the task list. Entries with an `id` update that task, entries without one
+/// are added, and tasks left out are removed with their test cases. A list without
+/// any ids (v1 clients) can only edit the tasks in place, so it must keep their count.
+async fn replace_tasks(
+    txn: &DatabaseTransaction,
+    classroom_id: i32,
+    tasks: Vec<TaskInput>,
+) -> Result<(), AppError> {
+    if let Some(position) = tasks
+        .iter()
+        .position(|task_input| task_input.language_id.is_some_and(|id| id <= 0))
+    {
+        return Err(AppError::BadRequest(format!(
+            "languageId of task {} must be a positive Judge0 language id",
+            position + 1
+        )));
+    }
+    for task_input in &tasks {
+        if let Some(weight) = task_input.weight {
+            validate_weight(weight)?;
+        }
+    }
+
+    let existing = task::Entity::find()
+        .filter(task::Column::ClassroomId.eq(classroom_id))
+        .order_by_asc(task::Column::Position)
+        .order_by_asc(task::Column::Id)
+        .all(txn)
+        .await?;
+
+    // Pair every entry with the row it updates, if any.
+    let mut targets: Vec<Option<task::Model>> = Vec::with_capacity(tasks.len());
+    let mut unlisted: HashMap<i32, task::Model> = existing
+        .iter()
+        .map(|task_model| (task_model.id, task_model.clone()))
+        .collect();
+    if tasks.iter().any(|task_input| task_input.id.is_some()) {
+        for task_input in &tasks {
+            match task_input.id {
+                Some(task_id) => {
+                    let task_model = unlisted.remove(&task_id).ok_or_else(|| {
+                        AppError::BadRequest(format!(
+                            "task {task_id} is not a task of this classroom or is listed twice"
+                        ))
+                    })?;
+                    targets.push(Some(task_model));
+                }
+                None => targets.push(None),
+            }
+        }
+    } else if existing.is_empty() {
+        targets.resize(tasks.len(), None);
+    } else if tasks.len() == existing.len() {
+        targets.extend(existing.into_iter().map(Some));
+        unlisted.clear();
+    } else {
+        return Err(AppError::BadRequest(
+            "tasks without ids cannot add or remove tasks; send each task's id, or use \
+             /api/classrooms/{id}/tasks to add and remove tasks"
+                .into(),
+        ));
+    }
+
+    let now = Utc::now();
+    for (position, (task_input, target)) in tasks.into_iter().zip(targets).enumerate() {
+        if let Some(task_model) = target {
+            let mut task_am = task_model.into_active_model();
+            task_am.position = sea_orm::ActiveValue::Set(position as i32);
+            task_am.title = sea_orm::ActiveValue::Set(task_input.title);
+            task_am.description = sea_orm::ActiveValue::Set(task_input.description);
+            task_am.language_id = sea_orm::ActiveValue::Set(task_input.language_id);
+            task_am.due_at = sea_orm::ActiveValue::Set(task_input.due_at);
+            if let Some(starter_code) = task_input.starter_code {
+                task_am.starter_code = sea_orm::ActiveValue::Set(non_empty(starter_code));
+            }
+            if let Some(expected_output) = task_input.expected_output {
+                task_am.expected_output = sea_orm::ActiveValue::Set(non_empty(expected_output));
+            }
+            if let Some(weight) = task_input.weight {
+                task_am.weight = sea_orm::ActiveValue::Set(weight);
+            }
+            task_am.updated_at = sea_orm::ActiveValue::Set(now);
+            task_am.update(txn).await?;
+            continue;
+        }
+
+        task::ActiveModel {
+            classroom_id: sea_orm::ActiveValue::Set(classroom_id),
+            position: sea_orm::ActiveValue::Set(position as i32),
+            title: sea_orm::ActiveValue::Set(task_input.title),
+            description: sea_orm::ActiveValue::Set(task_input.description),
+            language_id: sea_orm::ActiveValue::Set(task_input.language_id),
+            due_at: sea_orm::ActiveValue::Set(task_input.due_at),
+            starter_code: sea_orm::ActiveValue::Set(task_input.starter_code.and_then(non_empty)),
+            expected_output: sea_orm::ActiveValue::Set(
+                task_input.expected_output.and_then(non_empty),
+            ),
+            weight: sea_orm::ActiveValue::Set(task_input.weight.unwrap_or(1)),
+            created_at: sea_orm::ActiveValue::Set(now),
+            updated_at: sea_orm::ActiveValue::Set(now),
+            ..Default::default()
+        }
+        .insert(txn)
+        .await?;
+    }
+
+    if !unlisted.is_empty() {
+        task::Entity::delete_many()
+            .filter(task::Column::Id.is_in(unlisted.into_keys()))
+            .exec(txn)
+            .await?;
+    }
+
+    Ok(())
+}
+
 async fn insert_users(
     txn: &DatabaseTransaction,
     classroom_id: i32,
@@ -491,7 +1338,9 @@ async fn insert_users(
             classroom_id: sea_orm::ActiveValue::Set(classroom_id),
             name: sea_orm::ActiveValue::Set(payload.name),
             npm: sea_orm::ActiveValue::Set(payload.npm),
-            code: sea_orm::ActiveValue::Set(payload.code),
+            code: sea_orm::ActiveValue::Set(payload.code.into()),
+            code_seq: sea_orm::ActiveValue::Set(0),
+            active: sea_orm::ActiveValue::Set(true),
             created_at: sea_orm::ActiveValue::Set(now),
             updated_at: sea_orm::ActiveValue::Set(now),
             ..Default::default()
@@ -503,7 +1352,56 @@ async fn insert_users(
     Ok(())
 }
 
-async fn find_classroom_and_user(db: &DatabaseConnection, classroom_id: i32, npm: &str) -> Result<(classroom::Model, user::Model), AppError> {
+/// Makes the roster match `users` by NPM. Students already on it keep their roster
+/// entry, and with it their saved code and submissions; only the name is updated.
+/// New NPMs are added and entries whose NPM is left out are removed.
+async fn sync_users(
+    txn: &DatabaseTransaction,
+    classroom_id: i32,
+    users: Vec<CreateUserRequest>,
+) -> Result<(), AppError> {
+    let mut existing: HashMap<String, user::Model> = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(classroom_id))
+        .all(txn)
+        .await?
+        .into_iter()
+        .map(|user_model| (user_model.npm.trim().to_owned(), user_model))
+        .collect();
+
+    let mut added = Vec::new();
+    let mut listed = HashSet::new();
+    let now = Utc::now();
+    for payload in users {
+        let npm = payload.npm.trim().to_owned();
+        if npm.is_empty() || !listed.insert(npm.clone()) {
+            continue;
+        }
+        match existing.remove(&npm) {
+            Some(user_model) if user_model.name != payload.name => {
+                let mut user_am = user_model.into_active_model();
+                user_am.name = sea_orm::ActiveValue::Set(payload.name);
+                user_am.updated_at = sea_orm::ActiveValue::Set(now);
+                user_am.update(txn).await?;
+            }
+            Some(_) => {}
+            None => added.push(payload),
+        }
+    }
+
+    if !existing.is_empty() {
+        user::Entity::delete_many()
+            .filter(user::Column::Id.is_in(existing.into_values().map(|user_model| user_model.id)))
+            .exec(txn)
+            .await?;
+    }
+    insert_users(txn, classroom_id, added).await
+}
+
+async fn find_classroom_and_user(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+    npm: &str,
+) -> Result<(classroom::Model, user::Model), AppError> {
     let user = user::Entity::find()
         .filter(user::Column::Npm.eq(npm))
         .filter(user::Column::ClassroomId.eq(classroom_id))
@@ -526,64 +1424,207 @@ async fn find_classroom_and_user(db: &DatabaseConnection, classroom_id: i32, npm
     tag = "Classrooms",
     request_body = FinishExamRequest,
     responses(
-        (status = 200, description = "Exam finished, code executed", body = Judge0SubmissionResponse),
-        (status = 404, description = "Classroom or user not found")
+        (status = 200, description = "Exam finished; code stored as the final submission, tagged late when past the due date of `taskId` (or of every task without one), and graded against the classroom's test cases (or executed once when there are none). Progress is sent as `grading-status` events and can be polled at `grading-status`. Results are included in practice mode only", body = SubmissionFeedback),
+        (status = 400, description = "A token was sent without `X-Request-Nonce` and `X-Request-Timestamp`"),
+        (status = 403, description = "The exam attempt is bound to another device, the exam ended longer ago than the clock tolerance and hand-in grace, or the student was deactivated or locked without a hand-in"),
+        (status = 404, description = "Classroom, user or task not found"),
+        (status = 409, description = "The attempt was already handed in, for any task (`code: \"already_finished\"`, latest response in `original`), or a signed-in request was replayed (`code: \"replayed_request\"`)"),
+        (status = 429, description = "The classroom's submission cooldown has not passed since the student's last graded submission (`code: \"submission_cooldown\"`); retry after `Retry-After` seconds"),
+        (status = 502, description = "Judge0 request failed"),
+        (status = 504, description = "Judge0 did not answer within the timeout")
     )
 )]
 pub async fn finish_exam(
     State(state): State<AppState>,
     Path(id): Path<i32>,
-    Json(payload): Json<FinishExamRequest>,
-) -> Result<Json<Judge0SubmissionResponse>, AppError> {
+    headers: HeaderMap,
+    auth: AuthAccount,
+    DeviceFingerprint(fingerprint): DeviceFingerprint,
+    ValidatedJson(payload): ValidatedJson<FinishExamRequest>,
+) -> Result<Json<SubmissionFeedback>, AppError> {
+    auth.require_acting_as(&payload.npm)?;
+    // Each finish must be unique, so a captured request cannot be sent again.
+    state
+        .finish_nonces
+        .check(auth.account.id, &headers, Utc::now())?;
+
+    let classroom_model = classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+    if !classroom_model.is_published() {
+        return Err(AppError::ClassroomNotFound);
+    }
+
     let user_model = user::Entity::find()
         .filter(user::Column::ClassroomId.eq(id))
         .filter(user::Column::Npm.eq(&payload.npm))
         .one(&state.db)
         .await?
         .ok_or(AppError::UserNotFound)?;
+    let user_model = device::enforce(
+        &state,
+        &classroom_model,
+        user_model,
+        fingerprint.as_deref(),
+        "finish",
+        false,
+    )
+    .await?;
 
-    let mut user_am = user_model.into_active_model();
-    user_am.active = sea_orm::ActiveValue::Set(false);
-    user_am.code = sea_orm::ActiveValue::Set(payload.code.clone());
-    user_am.update(&state.db).await?;
-
-    let submission_payload = Judge0SubmissionRequest {
-        source_code: payload.code,
-        language_id: payload.language_id.unwrap_or(63),
-        npm: Some(payload.npm),
-        stdin: None,
-        expected_output: None,
-        cpu_time_limit: None,
-        memory_limit: None,
-        compiler_options: None,
-        command_line_arguments: None,
-    };
+    if let Some(task_id) = payload.task_id {
+        find_task(&state.db, id, task_id).await?;
+    }
 
-    let endpoint = format!(
-        "{}/submissions?base64_encoded=false&wait=true",
-        state.judge0_base_url
-    );
+    // Checking for an earlier hand-in and recording this one happen under the lock, so
+    // two copies of the same request cannot both be graded.
+    let guard = FINISH_LOCK.lock().await;
+    let user_model = user::Entity::find_by_id(user_model.id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+    if let Some(original) = finished_attempt(&state.db, &classroom_model, &user_model).await? {
+        return Err(AppError::AlreadyFinished(Box::new(original)));
+    }
+    time_policy::ensure_submissions_open(&state, &classroom_model).await?;
+    submission_cooldown::claim(&state.db, &classroom_model, user_model.id).await?;
 
-    let response = state
-        .http_client
-        .post(endpoint)
-        .json(&submission_payload)
-        .send()
-        .await?;
+    let user_id = user_model.id;
+    let language_id = payload
+        .language_id
+        .unwrap_or_else(|| grading::classroom_language_id(&classroom_model));
 
-    let status = response.status();
+    let code_seq = user_model.code_seq + 1;
+    let mut user_am = user_model.into_active_model();
+    let now = Utc::now();
+    post_finish::apply(&classroom_model, &mut user_am, now);
+    user_am.code = sea_orm::ActiveValue::Set(payload.code.clone().into());
+    user_am.code_updated_at = sea_orm::ActiveValue::Set(Some(now));
+    user_am.code_seq = sea_orm::ActiveValue::Set(code_seq);
+    user_am.language_id = sea_orm::ActiveValue::Set(Some(language_id));
+    user_am.updated_at = sea_orm::ActiveValue::Set(now);
+    let user_model = user_am.update(&state.db).await?;
+
+    // Recorded before queueing so the student sees it as `queued` while waiting.
+    let submission_model = grading::record_final(
+        &state,
+        &classroom_model,
+        user_id,
+        payload.code,
+        language_id,
+        payload.task_id,
+        false,
+    )
+    .await?;
+    drop(guard);
+    student_events::finished(&state, &user_model, &submission_model).await;
+
+    // The hand-in is already recorded, so grading waits for a Judge0 slot instead of failing.
+    let grading_state = state.clone();
+    let grading_classroom = classroom_model.clone();
+    let result = grading::detached(async move {
+        let _slot = grading_state.executor_queue.admit().await;
+        grading::grade_final(&grading_state, &grading_classroom, submission_model).await
+    })
+    .await?;
+    let submission_id = result.submission.id;
+    let verdicts = VerdictMessages::load(&state.db, id).await?;
+    let response = feedback::final_submission(&classroom_model, result, &verdicts);
+
+    match serde_json::to_string(&response) {
+        Ok(stored) => {
+            submission::Entity::update_many()
+                .col_expr(submission::Column::FinishFeedback, Expr::value(stored))
+                .filter(submission::Column::Id.eq(submission_id))
+                .exec(&state.db)
+                .await?;
+        }
+        Err(err) => tracing::warn!("failed to serialize finish response {submission_id}: {err}"),
+    }
+    Ok(Json(response))
+}
 
-    if !status.is_success() {
-        let error_body = response.text().await.unwrap_or_default();
-        return Err(AppError::External(format!(
-            "status {} dari Judge0: {}",
-            status.as_u16(),
-            error_body
-        )));
+/// Serializes the duplicate check of [`finish_exam`] with recording the hand-in.
+static FINISH_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Response to the latest hand-in once the student's attempt has ended, whatever task
+/// a new finish names. Under the `deactivate` and `lock_submissions` policies an attempt
+/// ends with an inactive or locked user; reactivating the student starts a new one.
+/// Under `keep_active` attempts never end, so every finish is graded. Hand-ins still
+/// being graded, or made before responses were stored, are answered with their receipt;
+/// an attempt ended without any hand-in is refused.
+async fn finished_attempt(
+    db: &DatabaseConnection,
+    classroom_model: &classroom::Model,
+    user_model: &user::Model,
+) -> Result<Option<SubmissionFeedback>, AppError> {
+    if !post_finish::attempt_ended(user_model) {
+        return Ok(None);
+    }
+    let finished = submission::Entity::find()
+        .filter(submission::Column::UserId.eq(user_model.id))
+        .filter(submission::Column::IsFinal.eq(true))
+        .order_by_desc(submission::Column::Id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::Forbidden("exam attempt has ended; hand-ins are closed".into()))?;
+
+    Ok(Some(
+        finished
+            .finish_feedback
+            .as_deref()
+            .and_then(|stored| serde_json::from_str(stored).ok())
+            .unwrap_or_else(|| feedback::receipt(classroom_model, &finished)),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/grading-status",
+    params(ClassroomPath, GradingStatusParams),
+    tag = "Classrooms",
+    responses(
+        (status = 200, description = "Grading progress of the student's latest final submission, as sent in `grading-status` events; for clients without an event stream", body = GradingStatusResponse),
+        (status = 403, description = "The exam attempt is bound to another device"),
+        (status = 404, description = "Classroom or user not found, or nothing handed in yet")
+    )
+)]
+pub async fn grading_status(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(params): Query<GradingStatusParams>,
+    auth: AuthAccount,
+    DeviceFingerprint(fingerprint): DeviceFingerprint,
+) -> Result<Json<GradingStatusResponse>, AppError> {
+    auth.require_acting_as(&params.npm)?;
+    let (classroom_model, user_model) = find_classroom_and_user(&state.db, id, &params.npm).await?;
+    if !classroom_model.is_published() {
+        return Err(AppError::ClassroomNotFound);
     }
+    let user_model = device::enforce(
+        &state,
+        &classroom_model,
+        user_model,
+        fingerprint.as_deref(),
+        "grading status",
+        false,
+    )
+    .await?;
 
-    let result = response.json::<Judge0SubmissionResponse>().await?;
-    Ok(Json(result))
+    let mut query = submission::Entity::find()
+        .filter(submission::Column::ClassroomId.eq(id))
+        .filter(submission::Column::UserId.eq(user_model.id))
+        .filter(submission::Column::IsFinal.eq(true));
+    if let Some(task_id) = params.task_id {
+        query = query.filter(submission::Column::TaskId.eq(task_id));
+    }
+    let submission_model = query
+        .order_by_desc(submission::Column::Id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::SubmissionNotFound)?;
+
+    Ok(Json(GradingStatusResponse::from(&submission_model)))
 }
 
 #[utoipa::path(
@@ -604,12 +1645,73 @@ pub async fn update_users_status(
 ) -> Result<StatusCode, AppError> {
     ensure_classroom_exists(&state, id).await?;
 
-    user::Entity::update_many()
-        .col_expr(user::Column::Active, payload.active.into())
+    let user_ids: Vec<i32> = user::Entity::find()
+        .select_only()
+        .column(user::Column::Id)
         .filter(user::Column::Id.is_in(payload.user_ids))
         .filter(user::Column::ClassroomId.eq(id))
+        .into_tuple()
+        .all(&state.db)
+        .await?;
+
+    // Reactivating also lifts a lock left by the `lock_submissions` policy.
+    let mut update = user::Entity::update_many()
+        .col_expr(user::Column::Active, payload.active.into())
+        .col_expr(user::Column::UpdatedAt, Expr::value(Utc::now()));
+    if payload.active {
+        update = update.col_expr(
+            user::Column::SubmissionsLockedAt,
+            Expr::value(Option::<chrono::DateTime<Utc>>::None),
+        );
+    }
+    update
+        .filter(user::Column::Id.is_in(user_ids.iter().copied()))
         .exec(&state.db)
         .await?;
 
+    for user_id in user_ids {
+        state
+            .events
+            .publish(
+                &state.db,
+                id,
+                Audience::Users(vec![user_id]),
+                EventKind::UserStatus(UserStatusEvent {
+                    classroom_id: id,
+                    user_id,
+                    active: payload.active,
+                }),
+            )
+            .await;
+    }
+
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/users/transfer",
+    params(ClassroomPath),
+    tag = "Users",
+    security(("bearer" = [])),
+    request_body = TransferUsersRequest,
+    responses(
+        (status = 200, description = "Users moved or copied with their code, attempts and submissions", body = TransferUsersResponse),
+        (status = 400, description = "No users given, or the target is the source classroom"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found, or a user is not in the source classroom"),
+        (status = 409, description = "An NPM is already enrolled in the target and `onConflict` is `fail`")
+    )
+)]
+pub async fn transfer_users(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    auth: AuthAccount,
+    Json(payload): Json<TransferUsersRequest>,
+) -> Result<Json<TransferUsersResponse>, AppError> {
+    auth.require_admin()?;
+
+    let report = roster::transfer(&state.db, id, payload).await?;
+    Ok(Json(report))
+}
@@ -0,0 +1,39 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::header::{CACHE_CONTROL, HeaderValue},
+    response::IntoResponse,
+};
+
+use crate::{
+    dto::ClientConfigParams,
+    error::AppError,
+    services::{client_config, field_limits::FieldLimits},
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/client-config",
+    tag = "Client Config",
+    params(ClientConfigParams),
+    responses(
+        (status = 200, description = "Feature flags, executor languages, size limits and branding of this deployment, so one frontend build can serve any lab", body = ClientConfigResponse)
+    )
+)]
+pub async fn client_config(
+    State(state): State<AppState>,
+    Query(params): Query<ClientConfigParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let field_limits = FieldLimits::resolve(&state, params.classroom_id, None).await?;
+    // Settings only change on restart; a short cache spares page loads the trip.
+    // Classroom limits can be edited at any time, so those are revalidated.
+    let cache_control = match params.classroom_id {
+        Some(_) => HeaderValue::from_static("no-cache"),
+        None => HeaderValue::from_static("public, max-age=60"),
+    };
+    Ok((
+        [(CACHE_CONTROL, cache_control)],
+        Json(client_config::build(&state, field_limits).await),
+    ))
+}
@@ -0,0 +1,103 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode, header::USER_AGENT},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{ClientErrorAck, ClientErrorListParams, ClientErrorReport, ClientErrorResponse},
+    entities::client_error,
+    error::AppError,
+    services::client_errors,
+    state::AppState,
+};
+
+const DEFAULT_LIST_LIMIT: u64 = 100;
+const MAX_LIST_LIMIT: u64 = 1000;
+
+#[utoipa::path(
+    post,
+    path = "/api/client-errors",
+    tag = "Client Errors",
+    request_body = ClientErrorReport,
+    responses(
+        (status = 202, description = "Report accepted; `stored` is false when it was sampled out", body = ClientErrorAck),
+        (status = 413, description = "Report larger than 32 KiB")
+    )
+)]
+pub async fn report_client_error(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<ClientErrorReport>,
+) -> Result<(StatusCode, Json<ClientErrorAck>), AppError> {
+    let user_agent = headers
+        .get(USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+    let stored = client_errors::record_report(
+        &state.db,
+        payload,
+        user_agent,
+        state.client_error_sample_rate,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ClientErrorAck {
+            stored,
+            sample_rate: state.client_error_sample_rate,
+        }),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/client-errors",
+    params(ClientErrorListParams),
+    tag = "Client Errors",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Frontend reports and server errors, newest first", body = [ClientErrorResponse]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn list_client_errors(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Query(params): Query<ClientErrorListParams>,
+) -> Result<Json<Vec<ClientErrorResponse>>, AppError> {
+    auth.require_admin()?;
+
+    let mut query = client_error::Entity::find()
+        .order_by_desc(client_error::Column::Id)
+        .limit(
+            params
+                .limit
+                .unwrap_or(DEFAULT_LIST_LIMIT)
+                .clamp(1, MAX_LIST_LIMIT),
+        );
+    if let Some(request_id) = params.request_id {
+        query = query.filter(client_error::Column::RequestId.eq(request_id));
+    }
+    if let Some(classroom_id) = params.classroom_id {
+        query = query.filter(client_error::Column::ClassroomId.eq(classroom_id));
+    }
+    if let Some(npm) = params.npm {
+        query = query.filter(client_error::Column::Npm.eq(npm));
+    }
+    if let Some(source) = params.source {
+        query = query.filter(client_error::Column::Source.eq(source.as_str()));
+    }
+    if let Some(since) = params.since {
+        query = query.filter(client_error::Column::CreatedAt.gte(since));
+    }
+    let rows = query.all(&state.db).await?;
+
+    Ok(Json(
+        rows.into_iter().map(ClientErrorResponse::from).collect(),
+    ))
+}
@@ -0,0 +1,95 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    auth::AuthAccount,
+    dto::DeadLetterResponse,
+    entities::{grading_dead_letter, submission, user},
+    error::AppError,
+    services::grading_retry,
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/dead-letters",
+    tag = "Admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Final submissions whose grading failed `GRADING_MAX_ATTEMPTS` times, newest first", body = [DeadLetterResponse]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn list_dead_letters(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<Vec<DeadLetterResponse>>, AppError> {
+    auth.require_admin()?;
+
+    let dead_letters = grading_dead_letter::Entity::find()
+        .order_by_desc(grading_dead_letter::Column::Id)
+        .find_also_related(submission::Entity)
+        .all(&state.db)
+        .await?;
+    let user_ids: Vec<i32> = dead_letters
+        .iter()
+        .filter_map(|(_, submission_model)| submission_model.as_ref())
+        .map(|submission_model| submission_model.user_id)
+        .collect();
+    let users = user::Entity::find()
+        .filter(user::Column::Id.is_in(user_ids))
+        .all(&state.db)
+        .await?;
+
+    Ok(Json(
+        dead_letters
+            .into_iter()
+            .filter_map(|(dead_letter, submission_model)| {
+                let submission_model = submission_model?;
+                let npm = users
+                    .iter()
+                    .find(|user_model| user_model.id == submission_model.user_id)
+                    .map(|user_model| user_model.npm.clone());
+                Some(DeadLetterResponse {
+                    id: dead_letter.id,
+                    submission_id: dead_letter.submission_id,
+                    classroom_id: dead_letter.classroom_id,
+                    user_id: submission_model.user_id,
+                    npm,
+                    attempts: dead_letter.attempts,
+                    last_error: dead_letter.last_error,
+                    submitted_at: submission_model.created_at,
+                    dead_lettered_at: dead_letter.created_at,
+                })
+            })
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/dead-letters/{id}/requeue",
+    params(("id" = i32, Path, description = "Dead letter identifier")),
+    tag = "Admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 202, description = "Removed from the dead letters and queued for grading with a fresh attempt budget"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Dead letter not found")
+    )
+)]
+pub async fn requeue_dead_letter(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    auth.require_admin()?;
+    grading_retry::requeue(&state, id).await?;
+    Ok(StatusCode::ACCEPTED)
+}
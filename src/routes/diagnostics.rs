@@ -0,0 +1,44 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+
+use crate::{
+    auth::{AuthAccount, permissions::Permission},
+    dto::{SelfTestParams, SelfTestResponse},
+    error::AppError,
+    services::diagnostics,
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/diagnostics/self-test",
+    params(SelfTestParams),
+    tag = "Diagnostics",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Daftar periksa untuk satu NPM: akun, kelas yang dipublikasikan, status aktif peserta, jendela ujian, dan keterjangkauan Judge0", body = SelfTestResponse),
+        (status = 400, description = "NPM kosong"),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Mahasiswa hanya dapat memeriksa NPM-nya sendiri")
+    )
+)]
+pub async fn self_test(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Query(params): Query<SelfTestParams>,
+) -> Result<Json<SelfTestResponse>, AppError> {
+    let npm = params.npm.trim();
+    if npm.is_empty() {
+        return Err(AppError::BadRequest("NPM wajib diisi".into()));
+    }
+    // Admins and assistants troubleshoot anyone; everyone else only themselves.
+    if !auth.can(Permission::ViewCode) && auth.account.npm != npm {
+        return Err(AppError::Forbidden(
+            "only staff can run the self-test for another NPM".into(),
+        ));
+    }
+
+    Ok(Json(diagnostics::self_test(&state, npm).await?))
+}
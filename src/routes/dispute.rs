@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel,
+    PaginatorTrait, QueryFilter, QueryOrder,
+};
+
+use crate::{
+    auth::{AuthAccount, permissions::Permission},
+    dto::{
+        CreateDisputeRequest, DisputeListParams, DisputeResponse, DisputeStatus,
+        RespondDisputeRequest,
+    },
+    entities::{classroom, dispute, submission, user},
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::grading,
+    state::AppState,
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/submissions/{id}/disputes",
+    params(("id" = i32, Path, description = "Submission identifier")),
+    tag = "Disputes",
+    security(("bearer" = [])),
+    request_body = CreateDisputeRequest,
+    responses(
+        (status = 201, description = "Dispute raised", body = DisputeResponse),
+        (status = 400, description = "Empty message, ungraded submission or a dispute is already open"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Submission belongs to another student"),
+        (status = 404, description = "Submission not found")
+    )
+)]
+pub async fn create_dispute(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Json(payload): Json<CreateDisputeRequest>,
+) -> Result<(StatusCode, Json<DisputeResponse>), AppError> {
+    let (submission_model, user_model) = find_submission(&state.db, id).await?;
+
+    if user_model.npm != auth.account.npm {
+        return Err(AppError::Forbidden(
+            "only the student who submitted can dispute its grade".into(),
+        ));
+    }
+    if submission_model.graded_at.is_none() {
+        return Err(AppError::BadRequest(
+            "submission has not been graded yet".into(),
+        ));
+    }
+
+    let message = payload.message.trim().to_string();
+    if message.is_empty() {
+        return Err(AppError::BadRequest("message must not be empty".into()));
+    }
+
+    let open_disputes = dispute::Entity::find()
+        .filter(dispute::Column::SubmissionId.eq(id))
+        .filter(dispute::Column::Status.eq(DisputeStatus::Open.as_str()))
+        .count(&state.db)
+        .await?;
+    if open_disputes > 0 {
+        return Err(AppError::BadRequest(
+            "an open dispute already exists for this submission".into(),
+        ));
+    }
+
+    let model = dispute::ActiveModel {
+        submission_id: sea_orm::ActiveValue::Set(id),
+        user_id: sea_orm::ActiveValue::Set(user_model.id),
+        message: sea_orm::ActiveValue::Set(message),
+        status: sea_orm::ActiveValue::Set(DisputeStatus::Open.as_str().to_owned()),
+        regraded: sea_orm::ActiveValue::Set(false),
+        created_at: sea_orm::ActiveValue::Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(DisputeResponse::from_models(
+            model,
+            &submission_model,
+            &user_model,
+        )),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/submissions/{id}/disputes",
+    params(("id" = i32, Path, description = "Submission identifier")),
+    tag = "Disputes",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Disputes of the submission, oldest first", body = [DisputeResponse]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Submission belongs to another student"),
+        (status = 404, description = "Submission not found")
+    )
+)]
+pub async fn list_submission_disputes(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<DisputeResponse>>, AppError> {
+    let (submission_model, user_model) = find_submission(&state.db, id).await?;
+
+    if user_model.npm != auth.account.npm && !auth.can(Permission::Grade) {
+        return Err(AppError::Forbidden(
+            "disputes are only visible to the submitting student and admins".into(),
+        ));
+    }
+
+    let disputes = dispute::Entity::find()
+        .filter(dispute::Column::SubmissionId.eq(id))
+        .order_by_asc(dispute::Column::Id)
+        .all(&state.db)
+        .await?;
+
+    Ok(Json(
+        disputes
+            .into_iter()
+            .map(|model| DisputeResponse::from_models(model, &submission_model, &user_model))
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/disputes",
+    params(ClassroomPath, DisputeListParams),
+    tag = "Disputes",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Disputes raised in the classroom, oldest first", body = [DisputeResponse]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn list_classroom_disputes(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Query(params): Query<DisputeListParams>,
+) -> Result<Json<Vec<DisputeResponse>>, AppError> {
+    auth.require_admin()?;
+
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+
+    let mut query = dispute::Entity::find()
+        .find_also_related(submission::Entity)
+        .filter(submission::Column::ClassroomId.eq(id))
+        .order_by_asc(dispute::Column::Id);
+    if let Some(status) = params.status {
+        query = query.filter(dispute::Column::Status.eq(status.as_str()));
+    }
+    let rows = query.all(&state.db).await?;
+
+    let users: HashMap<i32, user::Model> = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(id))
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|user_model| (user_model.id, user_model))
+        .collect();
+
+    let response = rows
+        .into_iter()
+        .filter_map(|(model, submission_model)| {
+            let submission_model = submission_model?;
+            let user_model = users.get(&model.user_id)?;
+            Some(DisputeResponse::from_models(
+                model,
+                &submission_model,
+                user_model,
+            ))
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/disputes/{id}/respond",
+    params(("id" = i32, Path, description = "Dispute identifier")),
+    tag = "Disputes",
+    security(("bearer" = [])),
+    request_body = RespondDisputeRequest,
+    responses(
+        (status = 200, description = "Dispute answered, with the regraded score when requested", body = DisputeResponse),
+        (status = 400, description = "Empty response, open status or the dispute was already answered"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller lacks the grade permission"),
+        (status = 404, description = "Dispute not found"),
+        (status = 502, description = "Judge0 request failed during the regrade"),
+        (status = 503, description = "Judge0 queue is full; retry after `Retry-After` seconds"),
+        (status = 504, description = "Judge0 did not answer within the timeout")
+    )
+)]
+pub async fn respond_to_dispute(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Json(payload): Json<RespondDisputeRequest>,
+) -> Result<Json<DisputeResponse>, AppError> {
+    auth.require(Permission::Grade)?;
+
+    let model = dispute::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::DisputeNotFound)?;
+
+    if DisputeStatus::from_str(&model.status) != Some(DisputeStatus::Open) {
+        return Err(AppError::BadRequest(
+            "dispute has already been answered".into(),
+        ));
+    }
+    if payload.status == DisputeStatus::Open {
+        return Err(AppError::BadRequest(
+            "status must be resolved or rejected".into(),
+        ));
+    }
+    let response = payload.response.trim().to_string();
+    if response.is_empty() {
+        return Err(AppError::BadRequest("response must not be empty".into()));
+    }
+
+    let (mut submission_model, user_model) =
+        find_submission(&state.db, model.submission_id).await?;
+
+    let mut dispute_am = model.into_active_model();
+    if payload.regrade {
+        dispute_am.previous_score = sea_orm::ActiveValue::Set(submission_model.score);
+
+        // Regrade in a detached task so a client disconnect cannot leave it half done.
+        let slot = state.executor_queue.try_admit(&state.metrics)?;
+        let grading_state = state.clone();
+        submission_model = grading::detached(async move {
+            let _slot = slot;
+            grading::regrade(&grading_state, submission_model).await
+        })
+        .await?;
+
+        dispute_am.regraded = sea_orm::ActiveValue::Set(true);
+        dispute_am.regraded_score = sea_orm::ActiveValue::Set(submission_model.score);
+    }
+    dispute_am.status = sea_orm::ActiveValue::Set(payload.status.as_str().to_owned());
+    dispute_am.response = sea_orm::ActiveValue::Set(Some(response));
+    dispute_am.responded_by = sea_orm::ActiveValue::Set(Some(auth.account.id));
+    dispute_am.responded_at = sea_orm::ActiveValue::Set(Some(Utc::now()));
+
+    let updated = dispute_am.update(&state.db).await?;
+
+    Ok(Json(DisputeResponse::from_models(
+        updated,
+        &submission_model,
+        &user_model,
+    )))
+}
+
+pub(crate) async fn find_submission(
+    db: &DatabaseConnection,
+    submission_id: i32,
+) -> Result<(submission::Model, user::Model), AppError> {
+    match submission::Entity::find_by_id(submission_id)
+        .find_also_related(user::Entity)
+        .one(db)
+        .await?
+    {
+        Some((submission_model, Some(user_model))) => Ok((submission_model, user_model)),
+        _ => Err(AppError::SubmissionNotFound),
+    }
+}
@@ -0,0 +1,80 @@
+use axum::{
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{ETAG, IF_NONE_MATCH},
+    },
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{ConnectionTrait, EntityTrait, QuerySelect, Select, sea_query::Expr};
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+
+/// Validator for a response, derived from what the response is built from rather
+/// than from the (possibly multi-megabyte) body itself.
+pub struct ETag(String);
+
+impl ETag {
+    pub fn new(parts: &[String]) -> Self {
+        let digest = Sha256::digest(parts.join("|").as_bytes());
+        Self(format!("\"{}\"", hex::encode(&digest[..16])))
+    }
+
+    /// True when the request's `If-None-Match` lists this tag or `*`.
+    pub fn matches(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get_all(IF_NONE_MATCH)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == "*" || tag == self.0)
+    }
+}
+
+/// A body with its ETag, or `304 Not Modified` when the client already has it.
+pub enum Cached<T> {
+    NotModified(ETag),
+    Fresh(ETag, T),
+}
+
+impl<T: IntoResponse> IntoResponse for Cached<T> {
+    fn into_response(self) -> Response {
+        let (tag, mut response) = match self {
+            Cached::NotModified(tag) => (tag, StatusCode::NOT_MODIFIED.into_response()),
+            Cached::Fresh(tag, body) => (tag, body.into_response()),
+        };
+        if let Ok(value) = HeaderValue::from_str(&tag.0) {
+            response.headers_mut().insert(ETAG, value);
+        }
+        response
+    }
+}
+
+/// Row count, highest id and latest `updated_at` of the query's rows. Any insert,
+/// delete or update that bumps `updated_at` changes at least one of them.
+pub async fn fingerprint<E: EntityTrait>(
+    db: &impl ConnectionTrait,
+    query: Select<E>,
+    id: E::Column,
+    updated_at: E::Column,
+) -> Result<String, AppError> {
+    let (count, max_id, last_updated): (i64, Option<i32>, Option<DateTime<Utc>>) = query
+        .select_only()
+        .column_as(Expr::col((E::default(), id)).count(), "count")
+        .column_as(Expr::col((E::default(), id)).max(), "max_id")
+        .column_as(Expr::col((E::default(), updated_at)).max(), "last_updated")
+        .into_tuple()
+        .one(db)
+        .await?
+        .unwrap_or((0, None, None));
+
+    Ok(format!(
+        "{count}:{}:{}",
+        max_id.unwrap_or_default(),
+        last_updated
+            .map(|updated| updated.to_rfc3339())
+            .unwrap_or_default()
+    ))
+}
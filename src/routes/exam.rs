@@ -0,0 +1,426 @@
+use std::{collections::HashMap, convert::Infallible};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, header::HOST},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use chrono::Utc;
+use futures_util::stream::Stream;
+use reqwest::Url;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter,
+};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    auth::AuthAccount,
+    config,
+    dto::{
+        ExamClockResponse, ExamNetworkProfileResponse, LiveStatsResponse, NetworkRule,
+        NetworkSource, ProctorEvent, ProctorEventFilter, ProctorEventsParams, Seat,
+        UserConnectionCount,
+    },
+    entities::{classroom, exam_pause, user},
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::{
+        event_bus::{self, Audience, ClassroomEvent, EventKind},
+        exam_clock::ExamClock,
+        login_audit,
+    },
+    state::AppState,
+};
+
+/// Connection-tracker key of proctor streams; real classroom ids start at 1.
+const PROCTOR_STREAMS: i32 = 0;
+/// Most classrooms one proctor stream may watch.
+const MAX_PROCTOR_CLASSROOMS: usize = 50;
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/exam/pause",
+    params(ClassroomPath),
+    tag = "Classrooms",
+    responses(
+        (status = 200, description = "Exam countdown paused", body = ExamClockResponse),
+        (status = 400, description = "Exam is not running"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn pause_exam(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<ExamClockResponse>, AppError> {
+    let classroom = find_exam_classroom(&state, id).await?;
+    let now = Utc::now();
+    let clock = ExamClock::load(&state.db, &classroom).await?;
+
+    if clock.is_paused() {
+        return Err(AppError::BadRequest("Exam is already paused".into()));
+    }
+    if !clock.has_started(now) {
+        return Err(AppError::BadRequest("Exam has not started yet".into()));
+    }
+    if clock.has_expired(now) {
+        return Err(AppError::BadRequest("Exam has already ended".into()));
+    }
+
+    exam_pause::ActiveModel {
+        classroom_id: sea_orm::ActiveValue::Set(id),
+        paused_at: sea_orm::ActiveValue::Set(now),
+        resumed_at: sea_orm::ActiveValue::Set(None),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await?;
+
+    let clock = ExamClock::load(&state.db, &classroom).await?;
+    let response = ExamClockResponse::from_clock(id, &clock, now);
+    publish_clock(&state, &response).await;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/exam/resume",
+    params(ClassroomPath),
+    tag = "Classrooms",
+    responses(
+        (status = 200, description = "Exam countdown resumed", body = ExamClockResponse),
+        (status = 400, description = "Exam is not paused"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn resume_exam(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<ExamClockResponse>, AppError> {
+    let classroom = find_exam_classroom(&state, id).await?;
+    let now = Utc::now();
+
+    let open_pause = exam_pause::Entity::find()
+        .filter(exam_pause::Column::ClassroomId.eq(id))
+        .filter(exam_pause::Column::ResumedAt.is_null())
+        .one(&state.db)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Exam is not paused".into()))?;
+
+    let mut pause_am = open_pause.into_active_model();
+    pause_am.resumed_at = sea_orm::ActiveValue::Set(Some(now));
+    pause_am.update(&state.db).await?;
+
+    let clock = ExamClock::load(&state.db, &classroom).await?;
+    let response = ExamClockResponse::from_clock(id, &clock, now);
+    publish_clock(&state, &response).await;
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/exam-network-profile",
+    params(ClassroomPath),
+    tag = "Classrooms",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Hosts and ports the lab firewall must allow during the exam", body = ExamNetworkProfileResponse),
+        (status = 400, description = "Not an exam classroom or the request has no Host header"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn exam_network_profile(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    headers: HeaderMap,
+) -> Result<Json<ExamNetworkProfileResponse>, AppError> {
+    auth.require_admin()?;
+    let classroom = find_exam_classroom(&state, id).await?;
+    let clock = ExamClock::load(&state.db, &classroom).await?;
+
+    // Students reach the server under the address this request was sent to.
+    let server_url = request_server_url(&headers)?;
+
+    let mut rules = vec![tcp_rule(NetworkSource::Students, "api", &server_url)];
+    // The local development origin needs no firewall rule.
+    for url in config::ALLOWED_ORIGINS
+        .iter()
+        .filter_map(|origin| Url::parse(origin).ok())
+        .filter(|url| url.host_str() != Some("localhost"))
+    {
+        rules.push(tcp_rule(NetworkSource::Students, "frontend", &url));
+    }
+    for base_url in state.executors.settings().base_urls {
+        let judge0_url = Url::parse(&base_url)
+            .map_err(|err| AppError::Internal(format!("invalid Judge0 base URL: {err}")))?;
+        rules.push(tcp_rule(NetworkSource::Server, "judge0", &judge0_url));
+    }
+
+    let api_base_url = format!("{}api", server_url);
+    Ok(Json(ExamNetworkProfileResponse {
+        classroom_id: id,
+        exam_start: clock.start,
+        exam_end: clock.effective_end(Utc::now()),
+        event_stream_url: format!("{api_base_url}/classrooms/{id}/events"),
+        api_base_url,
+        rules,
+    }))
+}
+
+fn tcp_rule(source: NetworkSource, purpose: &str, url: &Url) -> NetworkRule {
+    NetworkRule {
+        source,
+        purpose: purpose.to_owned(),
+        host: url.host_str().unwrap_or_default().to_owned(),
+        port: url.port_or_known_default().unwrap_or(80),
+        protocol: "tcp".to_owned(),
+    }
+}
+
+/// Tells every connected student about the new countdown.
+async fn publish_clock(state: &AppState, clock: &ExamClockResponse) {
+    state
+        .events
+        .publish(
+            &state.db,
+            clock.classroom_id,
+            Audience::Everyone,
+            EventKind::Clock(clock.clone()),
+        )
+        .await;
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/live-stats",
+    params(ClassroomPath),
+    tag = "Classrooms",
+    responses(
+        (status = 200, description = "User counts and open event streams per student, with their seats, and the latest flagged login attempts", body = LiveStatsResponse),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn live_stats(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<LiveStatsResponse>, AppError> {
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+
+    let total_users = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(id))
+        .count(&state.db)
+        .await?;
+    let active_users = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(id))
+        .filter(user::Column::Active.eq(true))
+        .count(&state.db)
+        .await?;
+
+    let seats: HashMap<String, Seat> = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(id))
+        .filter(user::Column::SeatRow.is_not_null())
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .filter_map(|user_model| Some((user_model.npm.clone(), Seat::of(&user_model)?)))
+        .collect();
+    let connections: Vec<UserConnectionCount> = state
+        .connections
+        .classroom_counts(id)
+        .into_iter()
+        .map(|(npm, connections)| UserConnectionCount {
+            seat: seats.get(&npm).cloned(),
+            npm,
+            connections,
+        })
+        .collect();
+
+    Ok(Json(LiveStatsResponse {
+        classroom_id: id,
+        server_time: Utc::now(),
+        total_users,
+        active_users,
+        connected_users: connections.len(),
+        open_connections: connections.iter().map(|entry| entry.connections).sum(),
+        max_connections_per_user: state.connections.max_per_user(),
+        connections,
+        executor_queue: state.executor_queue.status(),
+        flagged_logins: login_audit::recent_flagged(&state.db, Some(id)).await?,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    params(
+        ProctorEventsParams,
+        ("Last-Event-ID" = Option<String>, Header, description = "Sent by reconnecting EventSource clients; used when `after_seq` is absent")
+    ),
+    tag = "Classrooms",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Events of several classrooms in one stream for proctors. Every event of the classroom streams plus, for proctors only, `integrity` (IntegrityAlertEvent) on device mismatches, `user-joined` (UserJoinedEvent) when a student first logs in to an exam, `user-submitted` (UserSubmittedEvent) for every run through the judge proxy and `exam-finished` (ExamFinishedEvent) for every hand-in, manual or automatic, under the same event name and sequence id, with the payload wrapped in ProctorEvent to tag its classroom"),
+        (status = 400, description = "No or too many classroom ids"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "A classroom does not exist"),
+        (status = 429, description = "The admin already has the maximum number of open event streams")
+    )
+)]
+pub async fn proctor_events(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Query(params): Query<ProctorEventsParams>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    auth.require_admin()?;
+
+    let classroom_ids = parse_classroom_ids(&params.classroom_ids)?;
+    let found = classroom::Entity::find()
+        .filter(classroom::Column::Id.is_in(classroom_ids.iter().copied()))
+        .count(&state.db)
+        .await?;
+    if found != classroom_ids.len() as u64 {
+        return Err(AppError::ClassroomNotFound);
+    }
+
+    let connection = state
+        .connections
+        .try_acquire(PROCTOR_STREAMS, &auth.account.npm)
+        .ok_or_else(|| {
+            AppError::TooManyConnections(format!(
+                "at most {} event streams per user",
+                state.connections.max_per_user()
+            ))
+        })?;
+
+    let after_seq = params.after_seq.or_else(|| {
+        headers
+            .get("last-event-id")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+    });
+
+    // Subscribe before loading the backlog so nothing sent in between is lost.
+    let mut events = state.events.subscribe();
+    let replay = match after_seq {
+        Some(after_seq) => event_bus::load_after_in(&state.db, &classroom_ids, after_seq).await?,
+        None => Vec::new(),
+    };
+
+    let only = params.only;
+    let stream = async_stream::stream! {
+        // Released when the client disconnects and the stream is dropped.
+        let _connection = connection;
+        let replayed_seq = replay.last().map(|event| event.seq).unwrap_or(0);
+        for event in &replay {
+            if wanted(&event.kind, only) {
+                yield Ok(proctor_sse_event(event));
+            }
+        }
+        loop {
+            match events.recv().await {
+                Ok(event)
+                    if event.seq > replayed_seq
+                        && classroom_ids.contains(&event.classroom_id)
+                        && wanted(&event.kind, only) =>
+                {
+                    yield Ok(proctor_sse_event(&event));
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Parses `1,2,3`, dropping duplicates.
+fn parse_classroom_ids(raw: &str) -> Result<Vec<i32>, AppError> {
+    let mut classroom_ids = Vec::new();
+    for part in raw
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+    {
+        let classroom_id: i32 = part
+            .parse()
+            .map_err(|_| AppError::BadRequest(format!("invalid classroom id: {part}")))?;
+        if !classroom_ids.contains(&classroom_id) {
+            classroom_ids.push(classroom_id);
+        }
+    }
+    if classroom_ids.is_empty() {
+        return Err(AppError::BadRequest("classroom_ids is required".into()));
+    }
+    if classroom_ids.len() > MAX_PROCTOR_CLASSROOMS {
+        return Err(AppError::BadRequest(format!(
+            "at most {MAX_PROCTOR_CLASSROOMS} classrooms per event stream"
+        )));
+    }
+    Ok(classroom_ids)
+}
+
+fn wanted(kind: &EventKind, only: Option<ProctorEventFilter>) -> bool {
+    match only {
+        None => true,
+        Some(ProctorEventFilter::Integrity) => matches!(kind, EventKind::Integrity(_)),
+        Some(ProctorEventFilter::Submissions) => {
+            matches!(
+                kind,
+                EventKind::Submission(_)
+                    | EventKind::GradingStatus(_)
+                    | EventKind::UserSubmitted(_)
+                    | EventKind::ExamFinished(_)
+            )
+        }
+    }
+}
+
+fn proctor_sse_event(event: &ClassroomEvent) -> Event {
+    let payload = ProctorEvent {
+        classroom_id: event.classroom_id,
+        seq: event.seq,
+        data: serde_json::from_str(&event.kind.payload()).unwrap_or_default(),
+    };
+    Event::default()
+        .id(event.seq.to_string())
+        .event(event.kind.name())
+        .json_data(payload)
+        .expect("event payloads serialize to JSON")
+}
+
+async fn find_exam_classroom(state: &AppState, id: i32) -> Result<classroom::Model, AppError> {
+    let classroom = classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+
+    if !classroom.is_exam {
+        return Err(AppError::BadRequest("Not an exam classroom".into()));
+    }
+
+    Ok(classroom)
+}
+
+/// Server origin the request was addressed to, honouring `X-Forwarded-Proto` from a
+/// TLS-terminating proxy.
+pub(crate) fn request_server_url(headers: &HeaderMap) -> Result<Url, AppError> {
+    let host = headers
+        .get(HOST)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| AppError::BadRequest("Host header is required".into()))?;
+    let scheme = headers
+        .get("x-forwarded-proto")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("http");
+    Url::parse(&format!("{scheme}://{host}"))
+        .map_err(|_| AppError::BadRequest(format!("invalid Host header: {host}")))
+}
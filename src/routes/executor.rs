@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use axum::{Json, extract::State};
+use chrono::Utc;
+
+use crate::{
+    auth::AuthAccount,
+    dto::{ExecutorConfigResponse, ExecutorConfigSource, UpdateExecutorConfigRequest},
+    error::AppError,
+    services::executor_pool::{self, PoolSettings},
+    state::AppState,
+};
+
+const MAX_HEALTH_CHECK_INTERVAL_SECS: u64 = 3600;
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/executor-config",
+    tag = "Admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Judge0 instances in use, with their health and call counters", body = ExecutorConfigResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn get_executor_config(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<ExecutorConfigResponse>, AppError> {
+    auth.require_admin()?;
+    Ok(Json(state.executors.describe()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/admin/executor-config",
+    tag = "Admin",
+    security(("bearer" = [])),
+    request_body = UpdateExecutorConfigRequest,
+    responses(
+        (status = 200, description = "Configuration stored and applied without a restart; instances that stay keep their health and counters", body = ExecutorConfigResponse),
+        (status = 400, description = "No or invalid base URL, or a limit out of range"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn put_executor_config(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Json(payload): Json<UpdateExecutorConfigRequest>,
+) -> Result<Json<ExecutorConfigResponse>, AppError> {
+    auth.require_admin()?;
+
+    let current = state.executors.settings();
+    let base_urls =
+        executor_pool::normalize_base_urls(payload.base_urls.iter().map(String::as_str))
+            .map_err(AppError::BadRequest)?;
+    let eject_after_failures = payload
+        .eject_after_failures
+        .unwrap_or(current.eject_after_failures);
+    if eject_after_failures == 0 {
+        return Err(AppError::BadRequest(
+            "ejectAfterFailures must be at least 1".into(),
+        ));
+    }
+    let health_check_interval_secs = payload
+        .health_check_interval_secs
+        .unwrap_or(current.health_check_interval.as_secs());
+    if !(1..=MAX_HEALTH_CHECK_INTERVAL_SECS).contains(&health_check_interval_secs) {
+        return Err(AppError::BadRequest(format!(
+            "healthCheckIntervalSecs must be between 1 and {MAX_HEALTH_CHECK_INTERVAL_SECS}"
+        )));
+    }
+
+    let settings = PoolSettings {
+        base_urls,
+        strategy: payload.strategy.unwrap_or(current.strategy),
+        eject_after_failures,
+        health_check_interval: Duration::from_secs(health_check_interval_secs),
+    };
+    let now = Utc::now();
+    executor_pool::store(&state.db, &settings, auth.account.id, now).await?;
+    state
+        .executors
+        .reconfigure(settings, ExecutorConfigSource::Database, Some(now));
+
+    Ok(Json(state.executors.describe()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/executor-config",
+    tag = "Admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Stored configuration removed; the `JUDGE0_*` environment settings apply again", body = ExecutorConfigResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn delete_executor_config(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<ExecutorConfigResponse>, AppError> {
+    auth.require_admin()?;
+
+    executor_pool::clear_stored(&state.db).await?;
+    state.executors.reset_to_environment();
+    Ok(Json(state.executors.describe()))
+}
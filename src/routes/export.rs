@@ -0,0 +1,203 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE},
+    },
+    response::IntoResponse,
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, QueryOrder,
+};
+use utoipa::IntoParams;
+
+use crate::{
+    auth::AuthAccount,
+    dto::{CreateExportRequest, ExportDownloadParams, ExportJobResponse, ExportStatus},
+    entities::{classroom, export_job},
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::{display_time, export_jobs},
+    state::AppState,
+};
+
+#[allow(dead_code)]
+#[derive(Debug, IntoParams)]
+pub struct ExportJobPath {
+    pub id: i32,
+    pub job_id: i32,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, IntoParams)]
+pub struct ExportDownloadPath {
+    pub job_id: i32,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/exports",
+    params(ClassroomPath),
+    tag = "Exports",
+    security(("bearer" = [])),
+    request_body = CreateExportRequest,
+    responses(
+        (status = 202, description = "Export queued; poll the job until it is done", body = ExportJobResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn create_export(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateExportRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_admin()?;
+
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+    let display = display_time::display_for(payload.time, &headers);
+    let job = export_job::ActiveModel {
+        classroom_id: Set(id),
+        kind: Set(payload.kind.as_str().to_owned()),
+        format: Set(payload.format.as_str().to_owned()),
+        time_display: Set(display.as_str().to_owned()),
+        status: Set(ExportStatus::Queued.as_str().to_owned()),
+        progress: Set(0),
+        requested_by: Set(auth.account.id),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(export_jobs::response(&state, job)),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/exports",
+    params(ClassroomPath),
+    tag = "Exports",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Export jobs of the classroom that have not expired, newest first", body = [ExportJobResponse]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn list_exports(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<ExportJobResponse>>, AppError> {
+    auth.require_admin()?;
+
+    let jobs = export_job::Entity::find()
+        .filter(export_job::Column::ClassroomId.eq(id))
+        .order_by_desc(export_job::Column::Id)
+        .all(&state.db)
+        .await?;
+    Ok(Json(
+        jobs.into_iter()
+            .map(|job| export_jobs::response(&state, job))
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/exports/{job_id}",
+    params(ExportJobPath),
+    tag = "Exports",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Progress of the job, with a signed download link once it is done", body = ExportJobResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Export job not found")
+    )
+)]
+pub async fn get_export(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path((id, job_id)): Path<(i32, i32)>,
+) -> Result<Json<ExportJobResponse>, AppError> {
+    auth.require_admin()?;
+
+    let job = export_job::Entity::find_by_id(job_id)
+        .filter(export_job::Column::ClassroomId.eq(id))
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ExportJobNotFound)?;
+    Ok(Json(export_jobs::response(&state, job)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/exports/{job_id}/download",
+    params(ExportDownloadPath, ExportDownloadParams),
+    tag = "Exports",
+    responses(
+        (status = 200, description = "The exported file; the signature in the link stands in for a token", content_type = "application/octet-stream"),
+        (status = 403, description = "Signature is invalid or the link has expired"),
+        (status = 404, description = "Export job not found or its file was deleted")
+    )
+)]
+pub async fn download_export(
+    State(state): State<AppState>,
+    Path(job_id): Path<i32>,
+    Query(params): Query<ExportDownloadParams>,
+) -> Result<impl IntoResponse, AppError> {
+    export_jobs::verify(
+        &state,
+        job_id,
+        params.expires,
+        &params.signature,
+        Utc::now(),
+    )?;
+
+    let job = export_job::Entity::find_by_id(job_id)
+        .one(&state.db)
+        .await?
+        .filter(|job| job.status == ExportStatus::Done.as_str())
+        .ok_or(AppError::ExportJobNotFound)?;
+    let bytes = state
+        .export_storage
+        .get(&job.storage_key())
+        .await?
+        .ok_or(AppError::ExportJobNotFound)?;
+
+    let file_name = job
+        .file_name
+        .unwrap_or_else(|| format!("export-{}", job.id));
+    let content_type = job
+        .content_type
+        .unwrap_or_else(|| "application/octet-stream".to_owned());
+    Ok((
+        [
+            (
+                CONTENT_TYPE,
+                HeaderValue::from_str(&content_type)
+                    .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+            ),
+            (
+                CONTENT_DISPOSITION,
+                HeaderValue::from_str(&format!("attachment; filename=\"{file_name}\""))
+                    .unwrap_or(HeaderValue::from_static("attachment")),
+            ),
+            (CACHE_CONTROL, HeaderValue::from_static("private, no-store")),
+        ],
+        bytes,
+    ))
+}
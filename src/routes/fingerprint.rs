@@ -0,0 +1,172 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, EntityTrait, IntoActiveModel, TransactionTrait};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{
+        FingerprintSettingsResponse, FingerprintSharingRequest, FingerprintSharingResponse,
+        SettingsChange, UpdateFingerprintSettingsRequest,
+    },
+    entities::classroom,
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::{fingerprint, settings_history},
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/fingerprint-sharing",
+    params(ClassroomPath),
+    tag = "Integrity",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Whether the classroom takes part in cross-classroom fingerprint matching", body = FingerprintSharingResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn get_fingerprint_sharing(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<FingerprintSharingResponse>, AppError> {
+    auth.require_admin()?;
+
+    let classroom_model = find_classroom(&state, id).await?;
+    Ok(Json(FingerprintSharingResponse {
+        classroom_id: classroom_model.id,
+        enabled: classroom_model.fingerprint_sharing,
+        removed_fingerprints: 0,
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/classrooms/{id}/fingerprint-sharing",
+    params(ClassroomPath),
+    tag = "Integrity",
+    security(("bearer" = [])),
+    request_body = FingerprintSharingRequest,
+    responses(
+        (status = 200, description = "Fingerprint sharing updated; opting out deletes the classroom's stored fingerprints", body = FingerprintSharingResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn put_fingerprint_sharing(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Json(payload): Json<FingerprintSharingRequest>,
+) -> Result<Json<FingerprintSharingResponse>, AppError> {
+    auth.require_admin()?;
+
+    let classroom_model = find_classroom(&state, id).await?;
+    let txn = state.db.begin().await?;
+    let before = classroom_model.clone();
+    let mut classroom_am = classroom_model.into_active_model();
+    classroom_am.fingerprint_sharing = sea_orm::ActiveValue::Set(payload.enabled);
+    classroom_am.updated_at = sea_orm::ActiveValue::Set(Utc::now());
+    let updated = classroom_am.update(&txn).await?;
+    settings_history::record(
+        &txn,
+        &before,
+        &updated,
+        SettingsChange::FingerprintSharing,
+        Some(auth.account.id),
+    )
+    .await?;
+    let removed_fingerprints = if payload.enabled {
+        0
+    } else {
+        fingerprint::forget_classroom(&txn, id).await?
+    };
+    txn.commit().await?;
+
+    Ok(Json(FingerprintSharingResponse {
+        classroom_id: updated.id,
+        enabled: updated.fingerprint_sharing,
+        removed_fingerprints,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/fingerprint-settings",
+    tag = "Admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Retention of the shared code fingerprints", body = FingerprintSettingsResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn get_fingerprint_settings(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<FingerprintSettingsResponse>, AppError> {
+    auth.require_admin()?;
+
+    Ok(Json(settings_response(&state).await?))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/admin/fingerprint-settings",
+    tag = "Admin",
+    security(("bearer" = [])),
+    request_body = UpdateFingerprintSettingsRequest,
+    responses(
+        (status = 200, description = "Retention stored; fingerprints past it are deleted by the next hourly prune", body = FingerprintSettingsResponse),
+        (status = 400, description = "Retention is longer than ten years"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn put_fingerprint_settings(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Json(payload): Json<UpdateFingerprintSettingsRequest>,
+) -> Result<Json<FingerprintSettingsResponse>, AppError> {
+    auth.require_admin()?;
+
+    if payload.retention_days > fingerprint::MAX_RETENTION_DAYS {
+        return Err(AppError::BadRequest(format!(
+            "retentionDays must be at most {}",
+            fingerprint::MAX_RETENTION_DAYS
+        )));
+    }
+    fingerprint::store_retention(
+        &state.db,
+        payload.retention_days,
+        auth.account.id,
+        Utc::now(),
+    )
+    .await?;
+
+    Ok(Json(settings_response(&state).await?))
+}
+
+async fn settings_response(state: &AppState) -> Result<FingerprintSettingsResponse, AppError> {
+    Ok(FingerprintSettingsResponse {
+        retention_days: fingerprint::retention_days(state).await?,
+        stored_fingerprints: fingerprint::count(&state.db).await?,
+        updated_at: fingerprint::stored(&state.db)
+            .await?
+            .map(|model| model.updated_at),
+    })
+}
+
+async fn find_classroom(state: &AppState, id: i32) -> Result<classroom::Model, AppError> {
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)
+}
@@ -0,0 +1,323 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{
+        HeaderMap, StatusCode,
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    },
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, EntityTrait, IntoActiveModel, TransactionTrait};
+use utoipa::IntoParams;
+
+use crate::{
+    auth::AuthAccount,
+    dto::{
+        ExamPacket, GradeCaseResult, GradeExportParams, GradeResultResponse, GradingScriptRequest,
+        GradingScriptResponse, PacketFormat, PacketParams, SettingsChange,
+    },
+    entities::{classroom, user},
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::{
+        display_time::TimeFormatter,
+        grades,
+        grading::{self, FinalSubmission},
+        packet, settings_history,
+    },
+    state::AppState,
+};
+
+#[allow(dead_code)]
+#[derive(Debug, IntoParams)]
+pub struct StudentPath {
+    pub id: i32,
+    pub user_id: i32,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/grading-script",
+    params(ClassroomPath),
+    tag = "Grading",
+    responses(
+        (status = 200, description = "Grading script of the classroom; fields are null when none is set", body = GradingScriptResponse),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn get_grading_script(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<GradingScriptResponse>, AppError> {
+    let classroom_model = find_classroom(&state, id).await?;
+    Ok(Json(GradingScriptResponse::from(classroom_model)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/classrooms/{id}/grading-script",
+    params(ClassroomPath),
+    tag = "Grading",
+    request_body = GradingScriptRequest,
+    responses(
+        (status = 200, description = "Grading script stored; it runs on every graded submission, reading GradingScriptInput as JSON on stdin and printing a GradingScriptVerdict", body = GradingScriptResponse),
+        (status = 400, description = "Empty script"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn put_grading_script(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    auth: Option<AuthAccount>,
+    Json(payload): Json<GradingScriptRequest>,
+) -> Result<Json<GradingScriptResponse>, AppError> {
+    if payload.source_code.trim().is_empty() {
+        return Err(AppError::BadRequest("sourceCode must not be empty".into()));
+    }
+
+    let classroom_model = find_classroom(&state, id).await?;
+    let txn = state.db.begin().await?;
+    let before = classroom_model.clone();
+    let mut classroom_am = classroom_model.into_active_model();
+    classroom_am.grading_script = sea_orm::ActiveValue::Set(Some(payload.source_code));
+    classroom_am.grading_script_language_id = sea_orm::ActiveValue::Set(Some(payload.language_id));
+    classroom_am.updated_at = sea_orm::ActiveValue::Set(Utc::now());
+    let updated = classroom_am.update(&txn).await?;
+    settings_history::record(
+        &txn,
+        &before,
+        &updated,
+        SettingsChange::GradingScript,
+        auth.map(|auth| auth.account.id),
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(Json(GradingScriptResponse::from(updated)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/classrooms/{id}/grading-script",
+    params(ClassroomPath),
+    tag = "Grading",
+    responses(
+        (status = 204, description = "Grading script removed"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn delete_grading_script(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    auth: Option<AuthAccount>,
+) -> Result<StatusCode, AppError> {
+    let classroom_model = find_classroom(&state, id).await?;
+    let txn = state.db.begin().await?;
+    let before = classroom_model.clone();
+    let mut classroom_am = classroom_model.into_active_model();
+    classroom_am.grading_script = sea_orm::ActiveValue::Set(None);
+    classroom_am.grading_script_language_id = sea_orm::ActiveValue::Set(None);
+    classroom_am.updated_at = sea_orm::ActiveValue::Set(Utc::now());
+    let updated = classroom_am.update(&txn).await?;
+    settings_history::record(
+        &txn,
+        &before,
+        &updated,
+        SettingsChange::GradingScript,
+        auth.map(|auth| auth.account.id),
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/grades/export",
+    params(ClassroomPath, GradeExportParams),
+    tag = "Grading",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "CSV with each student's latest final grade and a feedback comment, laid out for a plain sheet or for Moodle/Canvas gradebook import. Timestamps are RFC 3339 UTC; with local time display the plain sheet adds `_local` columns in the display timezone", content_type = "text/csv", body = String),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn export_grades(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Query(params): Query<GradeExportParams>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_admin()?;
+
+    let times = TimeFormatter::resolve(params.time, &headers, state.display_timezone);
+    let out = grades::export(&state.db, id, params.format, times).await?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"classroom-{id}-grades-{}.csv\"",
+                    params.format.as_str()
+                ),
+            ),
+        ],
+        out,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/users/{user_id}/grade",
+    params(StudentPath),
+    tag = "Grading",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "The student's saved code stored as a final submission and graded against every test case of the classroom (or executed once when there are none), with the verdict of each case", body = GradeResultResponse),
+        (status = 400, description = "The student has no saved code"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom or user not found"),
+        (status = 502, description = "Judge0 request failed"),
+        (status = 504, description = "Judge0 did not answer within the timeout")
+    )
+)]
+pub async fn grade_student(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path((id, user_id)): Path<(i32, i32)>,
+) -> Result<Json<GradeResultResponse>, AppError> {
+    auth.require_admin()?;
+    let classroom_model = find_classroom(&state, id).await?;
+    let user_model = user::Entity::find_by_id(user_id)
+        .one(&state.db)
+        .await?
+        .filter(|model| model.classroom_id == id)
+        .ok_or(AppError::UserNotFound)?;
+
+    let source_code = user_model.code.to_string();
+    if source_code.trim().is_empty() {
+        return Err(AppError::BadRequest("student has no saved code".into()));
+    }
+    let language_id = user_model
+        .language_id
+        .unwrap_or_else(|| grading::classroom_language_id(&classroom_model));
+
+    let submission_model = grading::record_final(
+        &state,
+        &classroom_model,
+        user_id,
+        source_code,
+        language_id,
+        None,
+        false,
+    )
+    .await?;
+    // Graded in a detached task like a hand-in, so a dropped request cannot leave the
+    // submission half graded.
+    let grading_state = state.clone();
+    let graded = grading::detached(async move {
+        let _slot = grading_state.executor_queue.admit().await;
+        grading::grade_final(&grading_state, &classroom_model, submission_model).await
+    })
+    .await?;
+
+    Ok(Json(grade_result(graded)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/users/{user_id}/packet",
+    params(StudentPath, PacketParams),
+    tag = "Grading",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "The student's exam packet: saved and handed-in code, the verdict of every run, the grade per task and test case, integrity flags and grade disputes with the instructor's answers. With `format=zip`, a ZIP with `packet.json`, `final.<ext>` and `saved.<ext>`", body = ExamPacket),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom or user not found")
+    )
+)]
+pub async fn exam_packet(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path((id, user_id)): Path<(i32, i32)>,
+    Query(params): Query<PacketParams>,
+) -> Result<Response, AppError> {
+    auth.require_admin()?;
+    let classroom_model = find_classroom(&state, id).await?;
+    let user_model = user::Entity::find_by_id(user_id)
+        .one(&state.db)
+        .await?
+        .filter(|model| model.classroom_id == id)
+        .ok_or(AppError::UserNotFound)?;
+
+    let packet: ExamPacket =
+        packet::build(&state.db, &classroom_model, &user_model, Utc::now()).await?;
+    match params.format {
+        PacketFormat::Json => Ok(Json(packet).into_response()),
+        PacketFormat::Zip => {
+            let bundle = packet::bundle(&packet)?;
+            Ok((
+                [
+                    (CONTENT_TYPE, "application/zip".to_string()),
+                    (
+                        CONTENT_DISPOSITION,
+                        format!(
+                            "attachment; filename=\"packet-{id}-{}.zip\"",
+                            user_model.npm
+                        ),
+                    ),
+                ],
+                bundle,
+            )
+                .into_response())
+        }
+    }
+}
+
+fn grade_result(graded: FinalSubmission) -> GradeResultResponse {
+    let FinalSubmission {
+        submission,
+        response,
+        cases,
+    } = graded;
+    GradeResultResponse {
+        submission_id: submission.id,
+        user_id: submission.user_id,
+        language_id: submission.language_id,
+        status: response.status.map(|status| status.description),
+        passed_cases: submission.passed_cases,
+        total_cases: submission.total_cases,
+        score: submission.score,
+        max_score: submission.max_score,
+        graded_at: submission.graded_at,
+        cases: cases
+            .into_iter()
+            .map(|outcome| GradeCaseResult {
+                test_case_id: outcome.test_case.id,
+                task_id: outcome.test_case.task_id,
+                hidden: outcome.test_case.hidden,
+                passed: outcome.passed,
+                points: outcome.test_case.points,
+                status: outcome.response.status.map(|status| status.description),
+                expected_output: outcome.test_case.expected_output,
+                stdout: outcome.response.stdout,
+            })
+            .collect(),
+    }
+}
+
+async fn find_classroom(state: &AppState, id: i32) -> Result<classroom::Model, AppError> {
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)
+}
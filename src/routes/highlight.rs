@@ -0,0 +1,72 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+
+use crate::{
+    auth::{AuthAccount, permissions::Permission},
+    dto::{HighlightFormat, HighlightParams, HighlightResponse},
+    error::AppError,
+    routes::dispute,
+    services::highlight,
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/submissions/{id}/highlight",
+    params(("id" = i32, Path, description = "Submission identifier"), HighlightParams),
+    tag = "Grading",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Highlighted source of the submission", body = HighlightResponse),
+        (status = 400, description = "Unknown theme"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Submission belongs to another student"),
+        (status = 404, description = "Submission not found")
+    )
+)]
+pub async fn highlight_submission(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Query(params): Query<HighlightParams>,
+) -> Result<Json<HighlightResponse>, AppError> {
+    let (submission_model, user_model) = dispute::find_submission(&state.db, id).await?;
+
+    if user_model.npm != auth.account.npm && !auth.can(Permission::Grade) {
+        return Err(AppError::Forbidden(
+            "submissions are only visible to the submitting student and admins".into(),
+        ));
+    }
+
+    let theme = params
+        .theme
+        .unwrap_or_else(|| highlight::DEFAULT_THEME.to_owned());
+    let language_id = submission_model.language_id;
+    let source = submission_model.source_code;
+    let format = params.format;
+    // Highlighting is regex-heavy; keep it off the async workers.
+    let (html, tokens) = {
+        let theme = theme.clone();
+        tokio::task::spawn_blocking(move || match format {
+            HighlightFormat::Html => {
+                highlight::to_html(&source, language_id, &theme).map(|html| (Some(html), None))
+            }
+            HighlightFormat::Tokens => highlight::to_tokens(&source, language_id, &theme)
+                .map(|tokens| (None, Some(tokens))),
+        })
+        .await
+        .map_err(|err| AppError::Internal(format!("highlighting task failed: {err}")))??
+    };
+
+    Ok(Json(HighlightResponse {
+        submission_id: id,
+        language_id,
+        syntax: highlight::syntax_name(language_id).to_owned(),
+        theme,
+        format,
+        html,
+        tokens,
+    }))
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{
+        DevicePolicy, DevicePolicyRequest, DevicePolicyResponse, IntegrityEventResponse,
+        SettingsChange,
+    },
+    entities::{classroom, integrity_event, user},
+    error::AppError,
+    routes::classroom::{ClassroomPath, ClassroomUserPath},
+    services::{seating::SeatIndex, settings_history},
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/device-policy",
+    params(ClassroomPath),
+    tag = "Integrity",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Device binding policy of the classroom", body = DevicePolicyResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn get_device_policy(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<DevicePolicyResponse>, AppError> {
+    auth.require_admin()?;
+
+    let classroom_model = find_classroom(&state.db, id).await?;
+    Ok(Json(policy_response(&classroom_model)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/classrooms/{id}/device-policy",
+    params(ClassroomPath),
+    tag = "Integrity",
+    security(("bearer" = [])),
+    request_body = DevicePolicyRequest,
+    responses(
+        (status = 200, description = "Device binding policy updated; existing bindings are kept", body = DevicePolicyResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn put_device_policy(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Json(payload): Json<DevicePolicyRequest>,
+) -> Result<Json<DevicePolicyResponse>, AppError> {
+    auth.require_admin()?;
+
+    let classroom_model = find_classroom(&state.db, id).await?;
+    let txn = state.db.begin().await?;
+    let before = classroom_model.clone();
+    let mut classroom_am = classroom_model.into_active_model();
+    classroom_am.device_policy = sea_orm::ActiveValue::Set(payload.policy.as_str().to_owned());
+    classroom_am.updated_at = sea_orm::ActiveValue::Set(Utc::now());
+    let updated = classroom_am.update(&txn).await?;
+    settings_history::record(
+        &txn,
+        &before,
+        &updated,
+        SettingsChange::DevicePolicy,
+        Some(auth.account.id),
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(Json(policy_response(&updated)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/integrity-events",
+    params(ClassroomPath),
+    tag = "Integrity",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Integrity events of the classroom, newest first, with the seat of the student and who sat next to them", body = [IntegrityEventResponse]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn list_integrity_events(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<IntegrityEventResponse>>, AppError> {
+    auth.require_admin()?;
+
+    find_classroom(&state.db, id).await?;
+
+    let events = integrity_event::Entity::find()
+        .filter(integrity_event::Column::ClassroomId.eq(id))
+        .order_by_desc(integrity_event::Column::Id)
+        .all(&state.db)
+        .await?;
+
+    let users: HashMap<i32, user::Model> = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(id))
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|user_model| (user_model.id, user_model))
+        .collect();
+
+    let seats = SeatIndex::new(users.values());
+    let response = events
+        .into_iter()
+        .filter_map(|model| {
+            let user_model = users.get(&model.user_id)?;
+            Some(IntegrityEventResponse::from_models(
+                model, user_model, &seats,
+            ))
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/classrooms/{classroom_id}/users/{user_id}/device",
+    params(ClassroomUserPath),
+    tag = "Integrity",
+    security(("bearer" = [])),
+    responses(
+        (status = 204, description = "Device binding cleared; the next exam login binds again"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom or user not found")
+    )
+)]
+pub async fn reset_device_binding(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path((classroom_id, user_id)): Path<(i32, i32)>,
+) -> Result<StatusCode, AppError> {
+    auth.require_admin()?;
+
+    find_classroom(&state.db, classroom_id).await?;
+    let user_model = user::Entity::find_by_id(user_id)
+        .filter(user::Column::ClassroomId.eq(classroom_id))
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+
+    let mut user_am = user_model.into_active_model();
+    user_am.device_fingerprint = sea_orm::ActiveValue::Set(None);
+    user_am.device_bound_at = sea_orm::ActiveValue::Set(None);
+    user_am.updated_at = sea_orm::ActiveValue::Set(Utc::now());
+    user_am.update(&state.db).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn find_classroom(db: &DatabaseConnection, id: i32) -> Result<classroom::Model, AppError> {
+    classroom::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)
+}
+
+fn policy_response(classroom_model: &classroom::Model) -> DevicePolicyResponse {
+    DevicePolicyResponse {
+        classroom_id: classroom_model.id,
+        policy: DevicePolicy::from_str(&classroom_model.device_policy).unwrap_or(DevicePolicy::Off),
+    }
+}
@@ -1,65 +1,309 @@
-use axum::{Json, extract::State};
+use std::net::SocketAddr;
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
 use chrono::Utc;
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use serde_json::Value;
 
-use crate::{dto::Judge0SubmissionRequest, entities::user, error::AppError, state::AppState};
+use crate::{
+    auth::{
+        AuthAccount,
+        device::{self, DeviceFingerprint},
+    },
+    dto::{
+        ClassroomStatus, Judge0RunStatus, Judge0SubmissionRequest, JudgeSubmitParams, OwnCodeInfo,
+        SandboxFlags, TaskActivity, ValidatedJson,
+    },
+    entities::{classroom, submission, task, user},
+    error::AppError,
+    services::{
+        grading, judge0, judge0_poll, language_usage, output, post_finish, sandbox, student_events,
+        submission_history, task_time, time_policy, verdict_messages::VerdictMessages,
+    },
+    state::AppState,
+};
 
 #[utoipa::path(
     post,
     path = "/api/judge0/submissions",
+    params(
+        JudgeSubmitParams,
+        ("Accept-Version" = Option<String>, Header, description = "`2` for camelCase bodies (Judge0SubmissionRequestV2)")
+    ),
     tag = "Executor",
     request_body = Judge0SubmissionRequest,
     responses(
-        (status = 200, description = "Hasil eksekusi dari Judge0", body = serde_json::Value),
+        (status = 200, description = "Hasil eksekusi dari Judge0; stdout/stderr dipotong sesuai batas output kelas (`truncated`, `stdout_bytes`, `stderr_bytes`). Eksekusi mahasiswa terdaftar disimpan di riwayat submission, dan revisi kode tersimpan dikirim di header `X-Code-Seq`", body = serde_json::Value),
+        (status = 202, description = "Dengan `wait=false`: eksekusi masuk antrean Judge0; hasilnya diambil lewat `GET /api/judge0/submissions/{token}`", body = Judge0RunStatus),
+        (status = 400, description = "`npm` dikirim tanpa `classroom_id`, kelas mengunci bahasa dan tugas (`task_id`) memakai bahasa lain, `max_processes_and_or_threads` di luar 1 sampai 120, atau `wait=false` tanpa NPM mahasiswa terdaftar"),
+        (status = 401, description = "Mahasiswa pada NPM tersebut nonaktif"),
+        (status = 403, description = "NPM tidak terdaftar di classroom_id yang dikirim, mahasiswa dikunci setelah *finish*, percobaan ujian terikat ke perangkat lain, ujian sudah tidak menerima submission (lewat akhir ujian, toleransi jam, dan masa tenggang), `enable_network` dikirim, atau flag sandbox dikirim selama ujian berlangsung"),
+        (status = 409, description = "Kode tersimpan lebih baru dari `base_seq`/`client_updated_at` (`stale_code`, salinan server dan revisinya di `current`); kode tidak disimpan dan tidak dijalankan"),
+        (status = 429, description = "Batas submission per menit terlampaui; coba lagi setelah `Retry-After` detik"),
         (status = 502, description = "Permintaan ke Judge0 gagal"),
+        (status = 503, description = "Antrean Judge0 penuh; coba lagi setelah `Retry-After` detik"),
+        (status = 504, description = "Judge0 tidak merespons dalam batas waktu"),
     )
 )]
 pub async fn submit_code(
     State(state): State<AppState>,
-    Json(payload): Json<Judge0SubmissionRequest>,
-) -> Result<Json<Value>, AppError> {
-    let endpoint = format!(
-        "{}/submissions?base64_encoded=false&wait=true",
-        state.judge0_base_url
-    );
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    auth: AuthAccount,
+    DeviceFingerprint(fingerprint): DeviceFingerprint,
+    Query(params): Query<JudgeSubmitParams>,
+    ValidatedJson(mut payload): ValidatedJson<Judge0SubmissionRequest>,
+) -> Result<Response, AppError> {
+    if let Some(npm) = payload.npm.as_deref().filter(|npm| !npm.trim().is_empty()) {
+        auth.require_acting_as(npm)?;
+        if payload.classroom_id.is_none() {
+            return Err(AppError::BadRequest(
+                "classroom_id is required when npm is sent".into(),
+            ));
+        }
+    }
+    sandbox::check_client(&payload)?;
+    let _slot = state.executor_queue.try_admit(&state.metrics)?;
+
+    // Anonymous runs share a budget per client address.
+    let rate_key = payload
+        .npm
+        .as_deref()
+        .map(str::trim)
+        .filter(|npm| !npm.is_empty())
+        .map(str::to_owned)
+        .unwrap_or_else(|| client.ip().to_string());
+    state
+        .rate_limit
+        .check(&rate_key)
+        .map_err(|retry_after_secs| AppError::RateLimited { retry_after_secs })?;
 
+    let mut output_limit_kb = state.output_limit_kb;
+    let mut verdicts = VerdictMessages::default();
+    let mut classroom_id = None;
+    let mut code_seq = None;
+    let mut run_owner = None;
+    let mut submitter = None;
     if let Some(npm) = payload
         .npm
         .as_ref()
         .map(|npm| npm.trim())
         .filter(|npm| !npm.is_empty())
+        && let Some(roster_classroom_id) = payload.classroom_id
     {
-        if let Some(user_model) = user::Entity::find()
-            .filter(user::Column::Npm.eq(npm))
-            .one(&state.db)
-            .await?
+        let (user_model, classroom_model) =
+            find_submitting_user(&state, npm, roster_classroom_id).await?;
+        // Staff may still run a student's code, e.g. to review a dispute after the exam.
+        if !auth.is_staff() {
+            if !user_model.active {
+                return Err(AppError::Unauthorized("User is not active".into()));
+            }
+            post_finish::ensure_unlocked(&user_model)?;
+            time_policy::ensure_submissions_open(&state, &classroom_model).await?;
+        }
+        let mut run_task_id = None;
+        let user_model = device::enforce(
+            &state,
+            &classroom_model,
+            user_model,
+            fingerprint.as_deref(),
+            "submission",
+            false,
+        )
+        .await?;
+        sandbox::check_client_in(&state, &classroom_model, &payload, Utc::now()).await?;
+        sandbox::apply(SandboxFlags::of(&classroom_model), &mut payload);
+        output_limit_kb = output::limit_kb(&state, &classroom_model);
+        verdicts = VerdictMessages::load(&state.db, classroom_model.id).await?;
+        if let Some(task_id) = payload.task_id
+            && let Some(task_model) = task::Entity::find_by_id(task_id).one(&state.db).await?
+            && task_model.classroom_id == classroom_model.id
         {
-            let mut user_am = user_model.into_active_model();
-            user_am.code = sea_orm::ActiveValue::Set(payload.source_code.clone());
-            user_am.updated_at = sea_orm::ActiveValue::Set(Utc::now());
-            user_am.update(&state.db).await?;
+            grading::task_language_id(&classroom_model, &task_model, Some(payload.language_id))?;
+            task_time::record(&state.db, task_id, user_model.id, TaskActivity::Edited).await?;
+            run_task_id = Some(task_id);
         }
+
+        code_seq = Some(save_code(&state, user_model.id, &payload).await?);
+        classroom_id = Some(classroom_model.id);
+        run_owner = Some((classroom_model.id, user_model.id, run_task_id));
+        submitter = Some(user_model);
     }
 
-    let response = state
-        .http_client
-        .post(endpoint)
-        .json(&payload)
-        .send()
+    payload.max_file_size = Some(
+        payload
+            .max_file_size
+            .map_or(output_limit_kb, |requested| requested.min(output_limit_kb)),
+    );
+    let mut headers = HeaderMap::new();
+    if let Some(code_seq) = code_seq {
+        headers.insert("x-code-seq", HeaderValue::from(code_seq));
+    }
+
+    // The result of a queued run is only reachable through its stored row.
+    if !params.wait.unwrap_or(true) {
+        let Some((classroom_id, user_id, task_id)) = run_owner else {
+            return Err(AppError::BadRequest(
+                "wait=false needs the npm of an enrolled student".into(),
+            ));
+        };
+        let (token, instance) = judge0::submit(&state, &payload).await?;
+        language_usage::record_run(&state.db, Some(classroom_id), payload.language_id).await;
+        let run = submission_history::record_pending(
+            &state.db,
+            classroom_id,
+            user_id,
+            task_id,
+            &payload,
+            &token,
+            &instance,
+        )
         .await?;
+        judge0_poll::wake(&state);
+        if let Some(user_model) = &submitter {
+            student_events::submitted(&state, user_model, task_id, payload.language_id, None).await;
+        }
+
+        let mut queued = Judge0RunStatus::from(run);
+        if let Some(status) = queued.result.status.as_mut() {
+            verdicts.apply(status);
+        }
+        return Ok((StatusCode::ACCEPTED, headers, Json(queued)).into_response());
+    }
+
+    let mut result: Value = judge0::execute(&state, &payload).await?;
+    language_usage::record_run(&state.db, classroom_id, payload.language_id).await;
+    output::limit_value(&mut result, output_limit_kb);
+    if let Some((classroom_id, user_id, task_id)) = run_owner {
+        submission_history::record_run(
+            &state.db,
+            classroom_id,
+            user_id,
+            task_id,
+            &payload,
+            &result,
+        )
+        .await;
+    }
+    if let Some(user_model) = &submitter {
+        let status = result
+            .pointer("/status/description")
+            .and_then(Value::as_str)
+            .map(str::to_owned);
+        let task_id = run_owner.and_then(|(_, _, task_id)| task_id);
+        student_events::submitted(&state, user_model, task_id, payload.language_id, status).await;
+    }
+    verdicts.apply_value(&mut result);
 
-    let status = response.status();
+    Ok((headers, Json(result)).into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/judge0/submissions/{token}",
+    params(("token" = String, Path, description = "Token Judge0 dari submission dengan `wait=false`")),
+    tag = "Executor",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Status terakhir eksekusi; `pending` bernilai true selama Judge0 belum selesai. Hasil diambil server di latar belakang, jadi cukup tanya ulang setiap beberapa detik", body = Judge0RunStatus),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Eksekusi milik mahasiswa lain"),
+        (status = 404, description = "Token tidak dikenal")
+    )
+)]
+pub async fn get_submission(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(token): Path<String>,
+) -> Result<Json<Judge0RunStatus>, AppError> {
+    let run = submission::Entity::find()
+        .filter(submission::Column::Judge0Token.eq(&token))
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::SubmissionNotFound)?;
+    let owner = find_user(&state, run.user_id).await?;
+    auth.require_acting_as(&owner.npm)?;
+
+    let verdicts = VerdictMessages::load(&state.db, run.classroom_id).await?;
+    let mut run = Judge0RunStatus::from(run);
+    if let Some(status) = run.result.status.as_mut() {
+        verdicts.apply(status);
+    }
+    Ok(Json(run))
+}
 
-    if !status.is_success() {
-        let error_body = response.text().await.unwrap_or_default();
-        return Err(AppError::External(format!(
-            "status {} dari Judge0: {}",
-            status.as_u16(),
-            error_body
-        )));
+/// Stores the submitted code as the student's saved code and returns its new
+/// revision, unless the client says it edited an older copy than the one saved, in
+/// which case the saved copy is returned for the client to reconcile. Identical code
+/// never conflicts.
+async fn save_code(
+    state: &AppState,
+    user_id: i32,
+    payload: &Judge0SubmissionRequest,
+) -> Result<i64, AppError> {
+    let user_model = find_user(state, user_id).await?;
+    let stale = match (payload.base_seq, payload.client_updated_at) {
+        (Some(base_seq), _) => base_seq < user_model.code_seq,
+        (None, Some(client_updated_at)) => client_updated_at < user_model.code_version(),
+        (None, None) => false,
+    };
+    if stale && *user_model.code != payload.source_code {
+        return Err(AppError::StaleCode(OwnCodeInfo::from(user_model)));
     }
 
-    let result = response.json::<Value>().await?;
-    Ok(Json(result))
+    // Written only while the revision is still the one checked, so of two tabs saving
+    // the same revision at once the second gets the conflict instead of overwriting.
+    let now = Utc::now();
+    let code_seq = user_model.code_seq + 1;
+    let saved = user::Entity::update_many()
+        .set(user::ActiveModel {
+            code: sea_orm::ActiveValue::Set(payload.source_code.clone().into()),
+            code_updated_at: sea_orm::ActiveValue::Set(Some(now)),
+            code_seq: sea_orm::ActiveValue::Set(code_seq),
+            language_id: sea_orm::ActiveValue::Set(Some(payload.language_id)),
+            updated_at: sea_orm::ActiveValue::Set(now),
+            ..Default::default()
+        })
+        .filter(user::Column::Id.eq(user_id))
+        .filter(user::Column::CodeSeq.eq(user_model.code_seq))
+        .exec(&state.db)
+        .await?;
+    if saved.rows_affected == 0 {
+        let current = find_user(state, user_id).await?;
+        return Err(AppError::StaleCode(OwnCodeInfo::from(current)));
+    }
+    Ok(code_seq)
+}
+
+async fn find_user(state: &AppState, user_id: i32) -> Result<user::Model, AppError> {
+    user::Entity::find_by_id(user_id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::UserNotFound)
+}
+
+/// Resolves the roster entry of `npm` in the published classroom `classroom_id`.
+async fn find_submitting_user(
+    state: &AppState,
+    npm: &str,
+    classroom_id: i32,
+) -> Result<(user::Model, classroom::Model), AppError> {
+    let found = user::Entity::find()
+        .find_also_related(classroom::Entity)
+        .filter(user::Column::Npm.eq(npm))
+        .filter(user::Column::ClassroomId.eq(classroom_id))
+        .filter(classroom::Column::Status.eq(ClassroomStatus::Published.as_str()))
+        .one(&state.db)
+        .await?;
+
+    match found {
+        Some((user_model, Some(classroom_model))) => Ok((user_model, classroom_model)),
+        _ => Err(AppError::ClassroomMismatch(format!(
+            "NPM {npm} is not enrolled in classroom {classroom_id}"
+        ))),
+    }
 }
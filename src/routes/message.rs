@@ -0,0 +1,252 @@
+use std::collections::{BTreeSet, HashMap};
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{
+        AckMessageRequest, MessageEvent, MessageRecipientResponse, MessageResponse,
+        SendMessageRequest,
+    },
+    entities::{classroom, message, message_recipient, user},
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::event_bus::{Audience, EventKind},
+    state::AppState,
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/messages",
+    params(ClassroomPath),
+    tag = "Messages",
+    request_body = SendMessageRequest,
+    responses(
+        (status = 201, description = "Message stored and pushed to the selected students", body = MessageResponse),
+        (status = 400, description = "Empty message or users outside the classroom"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn send_message(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Json(payload): Json<SendMessageRequest>,
+) -> Result<(StatusCode, Json<MessageResponse>), AppError> {
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+
+    let body = payload.message.trim().to_string();
+    if body.is_empty() {
+        return Err(AppError::BadRequest("message must not be empty".into()));
+    }
+
+    let user_ids: BTreeSet<i32> = payload.user_ids.into_iter().collect();
+    if user_ids.is_empty() {
+        return Err(AppError::BadRequest("userIds must not be empty".into()));
+    }
+
+    let users = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(id))
+        .filter(user::Column::Id.is_in(user_ids.iter().copied()))
+        .order_by_asc(user::Column::Id)
+        .all(&state.db)
+        .await?;
+
+    if users.len() != user_ids.len() {
+        let unknown: Vec<String> = user_ids
+            .iter()
+            .filter(|user_id| !users.iter().any(|user| user.id == **user_id))
+            .map(ToString::to_string)
+            .collect();
+        return Err(AppError::BadRequest(format!(
+            "users not in classroom: {}",
+            unknown.join(", ")
+        )));
+    }
+
+    let txn = state.db.begin().await?;
+
+    let message_model = message::ActiveModel {
+        classroom_id: sea_orm::ActiveValue::Set(id),
+        body: sea_orm::ActiveValue::Set(body),
+        created_at: sea_orm::ActiveValue::Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    let mut recipients = Vec::with_capacity(users.len());
+    for user_model in users {
+        let recipient = message_recipient::ActiveModel {
+            message_id: sea_orm::ActiveValue::Set(message_model.id),
+            user_id: sea_orm::ActiveValue::Set(user_model.id),
+            read_at: sea_orm::ActiveValue::Set(None),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        recipients.push(MessageRecipientResponse::from_models(recipient, user_model));
+    }
+
+    txn.commit().await?;
+
+    state
+        .events
+        .publish(
+            &state.db,
+            id,
+            Audience::Users(user_ids.into_iter().collect()),
+            EventKind::Message(MessageEvent::from(message_model.clone())),
+        )
+        .await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(MessageResponse {
+            id: message_model.id,
+            classroom_id: id,
+            message: message_model.body,
+            created_at: message_model.created_at,
+            recipients,
+        }),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/messages",
+    params(ClassroomPath),
+    tag = "Messages",
+    responses(
+        (status = 200, description = "Messages of the classroom with read receipts, newest first", body = [MessageResponse]),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn list_messages(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<MessageResponse>>, AppError> {
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+
+    let messages = message::Entity::find()
+        .filter(message::Column::ClassroomId.eq(id))
+        .order_by_desc(message::Column::Id)
+        .all(&state.db)
+        .await?;
+
+    let mut recipients: HashMap<i32, Vec<MessageRecipientResponse>> = HashMap::new();
+    let rows = message_recipient::Entity::find()
+        .filter(
+            message_recipient::Column::MessageId
+                .is_in(messages.iter().map(|message_model| message_model.id)),
+        )
+        .order_by_asc(message_recipient::Column::UserId)
+        .find_also_related(user::Entity)
+        .all(&state.db)
+        .await?;
+    for (recipient, user_model) in rows {
+        if let Some(user_model) = user_model {
+            recipients
+                .entry(recipient.message_id)
+                .or_default()
+                .push(MessageRecipientResponse::from_models(recipient, user_model));
+        }
+    }
+
+    let response = messages
+        .into_iter()
+        .map(|message_model| MessageResponse {
+            id: message_model.id,
+            classroom_id: message_model.classroom_id,
+            message: message_model.body,
+            created_at: message_model.created_at,
+            recipients: recipients.remove(&message_model.id).unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/messages/{id}/ack",
+    params(("id" = i32, Path, description = "Message identifier")),
+    tag = "Messages",
+    security(("bearer" = [])),
+    request_body = AckMessageRequest,
+    responses(
+        (status = 204, description = "Message marked as read"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Students can only acknowledge their own messages"),
+        (status = 404, description = "Message not found or not addressed to this user")
+    )
+)]
+pub async fn ack_message(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Json(payload): Json<AckMessageRequest>,
+) -> Result<StatusCode, AppError> {
+    auth.require_acting_as(&payload.npm)?;
+
+    let message_model = message::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::MessageNotFound)?;
+
+    let user_model = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(message_model.classroom_id))
+        .filter(user::Column::Npm.eq(payload.npm.trim()))
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::MessageNotFound)?;
+
+    let recipient = message_recipient::Entity::find()
+        .filter(message_recipient::Column::MessageId.eq(id))
+        .filter(message_recipient::Column::UserId.eq(user_model.id))
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::MessageNotFound)?;
+
+    // Acknowledging twice keeps the first read time.
+    if recipient.read_at.is_none() {
+        let mut recipient_am = recipient.into_active_model();
+        recipient_am.read_at = sea_orm::ActiveValue::Set(Some(Utc::now()));
+        recipient_am.update(&state.db).await?;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Messages addressed to the user that have not been acknowledged yet, oldest first.
+pub async fn load_unread_messages(
+    db: &DatabaseConnection,
+    user_id: i32,
+) -> Result<Vec<MessageEvent>, AppError> {
+    let rows = message_recipient::Entity::find()
+        .filter(message_recipient::Column::UserId.eq(user_id))
+        .filter(message_recipient::Column::ReadAt.is_null())
+        .order_by_asc(message_recipient::Column::MessageId)
+        .find_also_related(message::Entity)
+        .all(db)
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(_, message_model)| message_model.map(MessageEvent::from))
+        .collect())
+}
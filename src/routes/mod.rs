@@ -1,12 +1,58 @@
 use axum::Router;
-use axum::routing::{get, post, put};
+use axum::extract::DefaultBodyLimit;
+use axum::middleware;
+use axum::routing::{delete, get, post, put};
 
 use crate::state::AppState;
 
 pub mod account;
+pub mod account_email;
+pub mod admin;
+pub mod analytics;
+pub mod artifact;
+pub mod assistant;
+pub mod audit;
 pub mod auth;
+pub mod banner;
+pub mod bootstrap;
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod classroom;
+pub mod client_config;
+pub mod client_error;
+pub mod dead_letter;
+pub mod diagnostics;
+pub mod dispute;
+pub mod etag;
+pub mod exam;
+pub mod executor;
+pub mod export;
+pub mod fingerprint;
+pub mod grading;
+pub mod highlight;
+pub mod integrity;
 pub mod judge;
+pub mod message;
+pub mod passkey;
+pub mod publish;
+pub mod purge;
+pub mod replication;
+pub mod request_id;
+pub mod rollover;
+pub mod run_config;
+pub mod sandbox;
+pub mod schedule;
+pub mod seat;
+pub mod settings_history;
+pub mod submission;
+pub mod task;
+pub mod task_sheet;
+pub mod task_time;
+pub mod test_case;
+pub mod ticket;
+pub mod time;
+pub mod verdict;
+pub mod webhook;
 
 pub fn classroom_router() -> Router<AppState> {
     Router::new()
@@ -20,34 +66,355 @@ pub fn classroom_router() -> Router<AppState> {
                 .put(classroom::update_classroom)
                 .delete(classroom::delete_classroom),
         )
+        .route(
+            "/classrooms/:id/delete-intent",
+            post(classroom::delete_intent),
+        )
         .route("/classrooms/:id/events", get(classroom::classroom_events))
         .route("/classrooms/:id/finish", post(classroom::finish_exam))
-        .route("/classrooms/:id/deactivate-post-exam", post(classroom::deactivate_users_post_exam))
+        .route(
+            "/classrooms/:id/validate",
+            post(publish::validate_classroom),
+        )
+        .route(
+            "/classrooms/:id/exam-gate/check",
+            post(publish::check_exam_gate),
+        )
+        .route("/classrooms/:id/publish", post(publish::publish_classroom))
+        .route(
+            "/classrooms/:id/grading-script",
+            get(grading::get_grading_script)
+                .put(grading::put_grading_script)
+                .delete(grading::delete_grading_script),
+        )
+        .route("/classrooms/:id/grades/export", get(grading::export_grades))
+        .route(
+            "/classrooms/:id/users/:user_id/grade",
+            post(grading::grade_student),
+        )
+        .route(
+            "/classrooms/:id/users/:user_id/packet",
+            get(grading::exam_packet),
+        )
+        .route(
+            "/classrooms/:id/settings/history",
+            get(settings_history::list_settings_history),
+        )
+        .route(
+            "/classrooms/:id/settings/rollback/:version",
+            post(settings_history::rollback_settings),
+        )
+        .route(
+            "/classrooms/:id/disputes",
+            get(dispute::list_classroom_disputes),
+        )
+        .route(
+            "/classrooms/:id/device-policy",
+            get(integrity::get_device_policy).put(integrity::put_device_policy),
+        )
+        .route(
+            "/classrooms/:id/fingerprint-sharing",
+            get(fingerprint::get_fingerprint_sharing).put(fingerprint::put_fingerprint_sharing),
+        )
+        .route(
+            "/classrooms/:id/sandbox",
+            get(sandbox::get_sandbox_flags).put(sandbox::put_sandbox_flags),
+        )
+        .route(
+            "/classrooms/:id/integrity-events",
+            get(integrity::list_integrity_events),
+        )
+        .route(
+            "/classrooms/:id/verdict-messages",
+            get(verdict::list_verdict_messages),
+        )
+        .route(
+            "/classrooms/:id/verdict-messages/:status_id",
+            put(verdict::put_verdict_message).delete(verdict::delete_verdict_message),
+        )
+        .route(
+            "/classrooms/:id/seats",
+            get(seat::get_seat_map).put(seat::put_seat_map),
+        )
+        .route("/classrooms/:id/tickets", get(ticket::exam_tickets))
+        .route(
+            "/classrooms/:id/task-times",
+            get(task_time::task_time_summary),
+        )
+        .route(
+            "/classrooms/:id/task-times/export",
+            get(task_time::export_task_times),
+        )
+        .route(
+            "/classrooms/:id/exports",
+            get(export::list_exports).post(export::create_export),
+        )
+        .route("/classrooms/:id/exports/:job_id", get(export::get_export))
+        .route(
+            "/classrooms/:id/messages",
+            get(message::list_messages).post(message::send_message),
+        )
+        .route(
+            "/classrooms/:id/grading-status",
+            get(classroom::grading_status),
+        )
+        .route(
+            "/classrooms/:id/submissions",
+            get(submission::list_classroom_submissions),
+        )
+        .route("/classrooms/:id/exam/pause", post(exam::pause_exam))
+        .route("/classrooms/:id/exam/resume", post(exam::resume_exam))
+        .route("/classrooms/:id/live-stats", get(exam::live_stats))
+        .route(
+            "/classrooms/:id/exam-network-profile",
+            get(exam::exam_network_profile),
+        )
+        .route(
+            "/classrooms/:id/deactivate-post-exam",
+            post(classroom::deactivate_users_post_exam),
+        )
+        .route(
+            "/classrooms/:id/assistants",
+            get(assistant::list_assistants),
+        )
+        .route(
+            "/classrooms/:id/assistants/:account_id",
+            put(assistant::assign_assistant).delete(assistant::unassign_assistant),
+        )
         .route(
             "/classrooms/:id/users",
             get(classroom::list_classroom_users).post(classroom::add_user_to_classroom),
         )
-        .route("/classrooms/:id/users/status", put(classroom::update_users_status))
+        .route(
+            "/classrooms/:id/users/status",
+            put(classroom::update_users_status),
+        )
+        .route(
+            "/classrooms/:id/users/transfer",
+            post(classroom::transfer_users),
+        )
         .route(
             "/classrooms/:classroom_id/users/:user_id",
             put(classroom::update_user_in_classroom).delete(classroom::delete_user_from_classroom),
         )
+        .route(
+            "/classrooms/:classroom_id/users/:user_id/device",
+            delete(integrity::reset_device_binding),
+        )
+        .route(
+            "/classrooms/:classroom_id/users/:user_id/presetup",
+            put(classroom::update_user_presetup),
+        )
+        .route(
+            "/classrooms/:id/test-cases/stats",
+            get(test_case::test_case_stats),
+        )
+        .route(
+            "/classrooms/:id/tasks",
+            get(task::list_tasks).post(task::create_task),
+        )
+        .route(
+            "/classrooms/:id/tasks/:task_id",
+            get(task::get_task)
+                .put(task::update_task)
+                .delete(task::delete_task),
+        )
+        .route(
+            "/classrooms/:id/tasks/:task_id/test-cases",
+            get(test_case::list_test_cases).post(test_case::create_test_case),
+        )
+        .route(
+            "/classrooms/:id/tasks/:task_id/extract-tests",
+            post(test_case::extract_tests),
+        )
+        .route(
+            "/classrooms/:id/tasks/:task_id/test-cases/:case_id",
+            put(test_case::update_test_case).delete(test_case::delete_test_case),
+        )
+        .route(
+            "/classrooms/:id/tasks/:task_id/rendered",
+            get(task_sheet::rendered_task),
+        )
+        .route(
+            "/classrooms/:id/tasks/:task_id/run",
+            post(test_case::run_practice_tests),
+        )
+        .route(
+            "/classrooms/:id/tasks/:task_id/progress",
+            post(task_time::record_task_progress),
+        )
 }
 
-pub fn api_router() -> Router<AppState> {
+/// Classroom, roster, account and judge routes, refused without a valid token and checked
+/// against the role's access in [`crate::auth::permissions::route_access`].
+fn token_router() -> Router<AppState> {
     Router::new()
         .merge(classroom_router())
         .route("/judge0/submissions", post(judge::submit_code))
+        .route("/judge0/submissions/:token", get(judge::get_submission))
+        .route("/events", get(exam::proctor_events))
+        .route(
+            "/users/:id/submissions",
+            get(submission::list_user_submissions),
+        )
         .route(
             "/accounts",
             get(account::list_accounts).post(account::create_account),
         )
+        .route("/accounts/pending", get(account::list_pending_accounts))
+        .route("/admin/accounts/export", get(account::export_accounts))
+        .route("/admin/accounts/import", post(account::import_accounts))
         .route(
             "/accounts/:id",
             get(account::get_account)
                 .patch(account::update_account_role)
                 .delete(account::delete_account),
         )
+        .route("/accounts/:id/approve", post(account::approve_account))
+        .route("/accounts/:id/activity", get(account::account_activity))
+        .route(
+            "/accounts/:id/activity/export",
+            get(account::export_account_activity),
+        )
+}
+
+pub fn api_router(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .merge(
+            token_router()
+                .route_layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    crate::auth::authorize,
+                ))
+                .route_layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    crate::auth::require_token,
+                )),
+        )
+        .route("/messages/:id/ack", post(message::ack_message))
+        // Signed, short-lived link opened by the browser without a token.
+        .route("/exports/:job_id/download", get(export::download_export))
+        .route(
+            "/submissions/:id/disputes",
+            get(dispute::list_submission_disputes).post(dispute::create_dispute),
+        )
+        .route(
+            "/submissions/:id/highlight",
+            get(highlight::highlight_submission),
+        )
+        .route(
+            "/submissions/:id/artifacts",
+            get(artifact::download_artifacts),
+        )
+        .route("/disputes/:id/respond", post(dispute::respond_to_dispute))
         .route("/auth/login", post(auth::login))
         .route("/auth/admin-exists", get(auth::admin_exists))
+        .route("/admin/login-events", get(auth::list_login_events))
+        .route("/auth/me", get(auth::me))
+        .route("/auth/me/email", put(account_email::update_email))
+        .route("/auth/verify-email", get(account_email::verify_email))
+        .route("/auth/recover-npm", post(account_email::recover_npm))
+        .route("/auth/passkeys", get(passkey::list_passkeys))
+        .route("/auth/passkeys/:id", delete(passkey::delete_passkey))
+        .route(
+            "/auth/passkeys/register/start",
+            post(passkey::start_registration),
+        )
+        .route(
+            "/auth/passkeys/register/finish",
+            post(passkey::finish_registration),
+        )
+        .route("/auth/passkeys/login/start", post(passkey::start_login))
+        .route("/auth/passkeys/login/finish", post(passkey::finish_login))
+        .route("/bootstrap", get(bootstrap::bootstrap))
+        .route(
+            "/me/run-configs",
+            get(run_config::list_run_configs).post(run_config::save_run_config),
+        )
+        .route("/me/run-configs/:id", delete(run_config::delete_run_config))
+        .route("/time", get(time::server_time))
+        .route("/health", get(replication::health))
+        .route("/diagnostics/self-test", get(diagnostics::self_test))
+        .route(
+            "/admin/schedule-conflicts",
+            get(schedule::schedule_conflicts),
+        )
+        .route(
+            "/admin/replication-status",
+            get(replication::replication_status),
+        )
+        .route(
+            "/replication/snapshot",
+            post(replication::receive_snapshot).layer(DefaultBodyLimit::max(
+                crate::services::replication::MAX_SNAPSHOT_BYTES,
+            )),
+        )
+        .route("/client-config", get(client_config::client_config))
+        .route("/banner", get(banner::current_banner))
+        .route(
+            "/admin/banner",
+            get(banner::get_banner)
+                .put(banner::put_banner)
+                .delete(banner::delete_banner),
+        )
+        .route(
+            "/client-errors",
+            post(client_error::report_client_error).layer(DefaultBodyLimit::max(
+                crate::services::client_errors::MAX_REPORT_BYTES,
+            )),
+        )
+        .route(
+            "/admin/client-errors",
+            get(client_error::list_client_errors),
+        )
+        .route("/analytics/daily", get(analytics::daily_stats))
+        .route("/analytics/languages", get(analytics::language_stats))
+        .route("/admin/system-stats", get(admin::system_stats))
+        .route("/admin/metrics", get(admin::metrics))
+        .route("/admin/dead-letters", get(dead_letter::list_dead_letters))
+        .route(
+            "/admin/executor-config",
+            get(executor::get_executor_config)
+                .put(executor::put_executor_config)
+                .delete(executor::delete_executor_config),
+        )
+        .route(
+            "/admin/fingerprint-settings",
+            get(fingerprint::get_fingerprint_settings).put(fingerprint::put_fingerprint_settings),
+        )
+        .route(
+            "/admin/dead-letters/:id/requeue",
+            post(dead_letter::requeue_dead_letter),
+        )
+        .route("/admin/openapi/diff", get(admin::openapi_diff))
+        .route("/admin/schema", get(admin::schema))
+        .route("/admin/rollover", post(rollover::rollover))
+        .route("/admin/users/:id/purge", post(purge::purge_student))
+        .route(
+            "/admin/purge-receipts/verify",
+            post(purge::verify_purge_receipt),
+        )
+        .route(
+            "/admin/webhooks",
+            get(webhook::list_webhooks).post(webhook::create_webhook),
+        )
+        .route("/admin/webhooks/:id", delete(webhook::delete_webhook))
+        .route("/admin/webhooks/:id/test", post(webhook::test_webhook))
+        .merge(chaos_router())
+}
+
+#[cfg(feature = "chaos")]
+fn chaos_router() -> Router<AppState> {
+    Router::new().route(
+        "/admin/chaos",
+        get(chaos::get_chaos)
+            .put(chaos::put_chaos)
+            .delete(chaos::delete_chaos),
+    )
+}
+
+/// Fault injection controls exist only in builds with the `chaos` feature.
+#[cfg(not(feature = "chaos"))]
+fn chaos_router() -> Router<AppState> {
+    Router::new()
 }
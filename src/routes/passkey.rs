@@ -0,0 +1,372 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, ModelTrait, PaginatorTrait,
+    QueryFilter, QueryOrder,
+};
+
+use crate::{
+    auth::{
+        AuthAccount, ensure_approved, permissions,
+        webauthn::{CEREMONY_TIMEOUT, Ceremony, ES256, RS256, WebAuthn},
+    },
+    dto::{
+        AccountResponse, AccountRole, AuthenticatorSelection, CredentialDescriptor,
+        CredentialParameter, LoginResponse, PasskeyCreationOptions, PasskeyLoginRequest,
+        PasskeyLoginStartRequest, PasskeyRegisterRequest, PasskeyRequestOptions, PasskeyResponse,
+        PasskeyUser, RelyingParty,
+    },
+    entities::{account, webauthn_credential},
+    error::AppError,
+    services::{audit, daily_stats},
+    state::AppState,
+};
+
+const PUBLIC_KEY: &str = "public-key";
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/passkeys/register/start",
+    tag = "Auth",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Opsi `publicKey` untuk `navigator.credentials.create()`", body = PasskeyCreationOptions),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Bukan akun staf atau peran di `WEBAUTHN_REQUIRED_ROLES`, atau passkey tidak aktif (`WEBAUTHN_RP_ID`)")
+    )
+)]
+pub async fn start_registration(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<PasskeyCreationOptions>, AppError> {
+    let webauthn = require_enabled(&state)?;
+    require_may_register(webauthn, &auth)?;
+
+    let account_model = auth.account;
+    let exclude_credentials = credentials_of(&state, account_model.id)
+        .await?
+        .into_iter()
+        .map(|model| descriptor(model.credential_id))
+        .collect();
+
+    Ok(Json(PasskeyCreationOptions {
+        challenge: webauthn.challenge(Ceremony::Register, account_model.id),
+        rp: RelyingParty {
+            id: webauthn.rp_id.clone(),
+            name: webauthn.rp_name.clone(),
+        },
+        user: PasskeyUser {
+            id: URL_SAFE_NO_PAD.encode(account_model.id.to_string()),
+            name: account_model.npm.clone(),
+            display_name: account_model.npm,
+        },
+        pub_key_cred_params: [ES256, RS256]
+            .into_iter()
+            .map(|alg| CredentialParameter {
+                kind: PUBLIC_KEY.into(),
+                alg,
+            })
+            .collect(),
+        timeout: CEREMONY_TIMEOUT.as_millis() as u64,
+        attestation: "none".into(),
+        exclude_credentials,
+        authenticator_selection: AuthenticatorSelection {
+            resident_key: "preferred".into(),
+            user_verification: "preferred".into(),
+        },
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/passkeys/register/finish",
+    tag = "Auth",
+    security(("bearer" = [])),
+    request_body = PasskeyRegisterRequest,
+    responses(
+        (status = 201, description = "Passkey tersimpan untuk akun", body = PasskeyResponse),
+        (status = 400, description = "Respons passkey tidak valid, challenge kedaluwarsa, atau passkey sudah terdaftar"),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Bukan akun staf atau peran di `WEBAUTHN_REQUIRED_ROLES`, atau passkey tidak aktif (`WEBAUTHN_RP_ID`)")
+    )
+)]
+pub async fn finish_registration(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Json(payload): Json<PasskeyRegisterRequest>,
+) -> Result<(StatusCode, Json<PasskeyResponse>), AppError> {
+    let webauthn = require_enabled(&state)?;
+    require_may_register(webauthn, &auth)?;
+
+    let response = payload.credential.response;
+    let credential = webauthn.verify_registration(
+        auth.account.id,
+        &response.client_data_json,
+        &response.attestation_object,
+    )?;
+
+    let registered = webauthn_credential::Entity::find()
+        .filter(webauthn_credential::Column::CredentialId.eq(credential.credential_id.as_str()))
+        .count(&state.db)
+        .await?
+        > 0;
+    if registered {
+        return Err(AppError::BadRequest("Passkey sudah terdaftar.".into()));
+    }
+
+    let name = payload
+        .name
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "Passkey".into());
+    let model = webauthn_credential::ActiveModel {
+        account_id: Set(auth.account.id),
+        credential_id: Set(credential.credential_id),
+        public_key: Set(credential.public_key),
+        sign_count: Set(i64::from(credential.sign_count)),
+        name: Set(name),
+        created_at: Set(Utc::now()),
+        last_used_at: Set(None),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(PasskeyResponse::from_model(model)),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/passkeys/login/start",
+    tag = "Auth",
+    request_body = PasskeyLoginStartRequest,
+    responses(
+        (status = 200, description = "Opsi `publicKey` untuk `navigator.credentials.get()`", body = PasskeyRequestOptions),
+        (status = 400, description = "Akun tidak ada atau belum memiliki passkey"),
+        (status = 403, description = "Passkey tidak aktif (`WEBAUTHN_RP_ID`)")
+    )
+)]
+pub async fn start_login(
+    State(state): State<AppState>,
+    Json(payload): Json<PasskeyLoginStartRequest>,
+) -> Result<Json<PasskeyRequestOptions>, AppError> {
+    let webauthn = require_enabled(&state)?;
+    let account_model = find_account(&state, &payload.npm).await?;
+    let credentials = credentials_of(&state, account_model.id).await?;
+    if credentials.is_empty() {
+        return Err(no_passkey());
+    }
+
+    Ok(Json(PasskeyRequestOptions {
+        challenge: webauthn.challenge(Ceremony::Login, account_model.id),
+        rp_id: webauthn.rp_id.clone(),
+        allow_credentials: credentials
+            .into_iter()
+            .map(|model| descriptor(model.credential_id))
+            .collect(),
+        timeout: CEREMONY_TIMEOUT.as_millis() as u64,
+        user_verification: "preferred".into(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/passkeys/login/finish",
+    tag = "Auth",
+    request_body = PasskeyLoginRequest,
+    responses(
+        (status = 200, description = "Login dengan passkey berhasil", body = LoginResponse),
+        (status = 400, description = "Respons passkey tidak valid atau challenge kedaluwarsa"),
+        (status = 401, description = "Passkey tidak dikenal atau tanda tangan tidak valid"),
+        (status = 403, description = "Passkey tidak aktif (`WEBAUTHN_RP_ID`)")
+    )
+)]
+pub async fn finish_login(
+    State(state): State<AppState>,
+    Json(payload): Json<PasskeyLoginRequest>,
+) -> Result<Json<LoginResponse>, AppError> {
+    let webauthn = require_enabled(&state)?;
+    let account_model = find_account(&state, &payload.npm).await?;
+    ensure_approved(&account_model)?;
+
+    let credential_id = payload.credential.id.trim_end_matches('=');
+    let stored = webauthn_credential::Entity::find()
+        .filter(webauthn_credential::Column::AccountId.eq(account_model.id))
+        .filter(webauthn_credential::Column::CredentialId.eq(credential_id))
+        .one(&state.db)
+        .await?
+        .ok_or_else(|| AppError::Unauthorized("Passkey tidak dikenal.".into()))?;
+
+    let response = payload.credential.response;
+    let sign_count = webauthn.verify_assertion(
+        account_model.id,
+        &stored.public_key,
+        stored.sign_count,
+        &response.client_data_json,
+        &response.authenticator_data,
+        &response.signature,
+    )?;
+
+    let mut credential_am: webauthn_credential::ActiveModel = stored.into();
+    credential_am.sign_count = Set(i64::from(sign_count));
+    credential_am.last_used_at = Set(Some(Utc::now()));
+    credential_am.update(&state.db).await?;
+
+    let (token, token_expires_at) = state.jwt.issue(&account_model)?;
+    daily_stats::record_login(&state.db, None).await;
+    audit::record_login(&state.db, account_model.id, "passkey", None).await;
+    let permissions = permissions::for_role(AccountRole::from_str(&account_model.role));
+    Ok(Json(LoginResponse {
+        account: AccountResponse::from_model(account_model),
+        classroom: None,
+        is_new: false,
+        token,
+        token_expires_at,
+        permissions,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/auth/passkeys",
+    tag = "Auth",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Passkey milik akun", body = [PasskeyResponse]),
+        (status = 401, description = "Token tidak ada atau tidak valid")
+    )
+)]
+pub async fn list_passkeys(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<Vec<PasskeyResponse>>, AppError> {
+    let credentials = credentials_of(&state, auth.account.id).await?;
+    Ok(Json(
+        credentials
+            .into_iter()
+            .map(PasskeyResponse::from_model)
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/auth/passkeys/{id}",
+    tag = "Auth",
+    security(("bearer" = [])),
+    params(("id" = i32, Path, description = "Passkey id")),
+    responses(
+        (status = 204, description = "Passkey dihapus"),
+        (status = 400, description = "Passkey terakhir tidak dapat dihapus karena passkey diwajibkan untuk peran akun"),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 404, description = "Passkey tidak ditemukan")
+    )
+)]
+pub async fn delete_passkey(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    let credentials = credentials_of(&state, auth.account.id).await?;
+    let count = credentials.len();
+    let model = credentials
+        .into_iter()
+        .find(|model| model.id == id)
+        .ok_or(AppError::PasskeyNotFound)?;
+
+    let required = state
+        .webauthn
+        .as_ref()
+        .is_some_and(|webauthn| webauthn.required_for(AccountRole::from_str(&auth.account.role)));
+    if required && count == 1 {
+        return Err(AppError::BadRequest(
+            "Passkey terakhir tidak dapat dihapus karena passkey diwajibkan untuk peran akun ini."
+                .into(),
+        ));
+    }
+
+    model.delete(&state.db).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Refuses NPM login for roles that must use a passkey once the account has one.
+/// Accounts without a passkey can still log in by NPM so they can register one.
+pub(crate) async fn check_npm_login_allowed(
+    state: &AppState,
+    account_model: &account::Model,
+) -> Result<(), AppError> {
+    let Some(webauthn) = &state.webauthn else {
+        return Ok(());
+    };
+    if !webauthn.required_for(AccountRole::from_str(&account_model.role)) {
+        return Ok(());
+    }
+
+    let has_passkey = webauthn_credential::Entity::find()
+        .filter(webauthn_credential::Column::AccountId.eq(account_model.id))
+        .count(&state.db)
+        .await?
+        > 0;
+    if has_passkey {
+        return Err(AppError::Forbidden(
+            "Akun ini wajib masuk menggunakan passkey.".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn require_enabled(state: &AppState) -> Result<&WebAuthn, AppError> {
+    state
+        .webauthn
+        .as_deref()
+        .ok_or_else(|| AppError::Forbidden("Passkey tidak aktif.".into()))
+}
+
+/// Staff accounts, and accounts whose role has to log in with a passkey, may register one.
+fn require_may_register(webauthn: &WebAuthn, auth: &AuthAccount) -> Result<(), AppError> {
+    if auth.is_staff() || webauthn.required_for(AccountRole::from_str(&auth.account.role)) {
+        return Ok(());
+    }
+    Err(AppError::Forbidden(
+        "Passkey hanya dapat didaftarkan oleh akun staf.".into(),
+    ))
+}
+
+async fn find_account(state: &AppState, npm: &str) -> Result<account::Model, AppError> {
+    account::Entity::find()
+        .filter(account::Column::Npm.eq(npm.trim()))
+        .one(&state.db)
+        .await?
+        .ok_or_else(no_passkey)
+}
+
+async fn credentials_of(
+    state: &AppState,
+    account_id: i32,
+) -> Result<Vec<webauthn_credential::Model>, AppError> {
+    Ok(webauthn_credential::Entity::find()
+        .filter(webauthn_credential::Column::AccountId.eq(account_id))
+        .order_by_asc(webauthn_credential::Column::Id)
+        .all(&state.db)
+        .await?)
+}
+
+fn descriptor(credential_id: String) -> CredentialDescriptor {
+    CredentialDescriptor {
+        kind: PUBLIC_KEY.into(),
+        id: credential_id,
+    }
+}
+
+/// Same answer for unknown NPMs and accounts without passkeys.
+fn no_passkey() -> AppError {
+    AppError::BadRequest("Akun tidak memiliki passkey.".into())
+}
@@ -0,0 +1,148 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::Utc;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, sea_query::Expr};
+
+use crate::{
+    dto::{
+        ClassroomStatus, ExamGateCheckResponse, PublishClassroomRequest, PublishClassroomResponse,
+        ReadinessReport,
+    },
+    entities::classroom,
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::{exam_gate, readiness},
+    state::AppState,
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/validate",
+    params(ClassroomPath),
+    tag = "Classrooms",
+    request_body(content = PublishClassroomRequest, description = "Optional reference solution for the smoke test"),
+    responses(
+        (status = 200, description = "Readiness report; `ready` tells whether publishing would succeed", body = ReadinessReport),
+        (status = 404, description = "Classroom not found"),
+        (status = 503, description = "Judge0 queue is full; retry after `Retry-After` seconds")
+    )
+)]
+pub async fn validate_classroom(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    payload: Option<Json<PublishClassroomRequest>>,
+) -> Result<Json<ReadinessReport>, AppError> {
+    let classroom_model = find_classroom(&state, id).await?;
+    let smoke = payload.map(|Json(payload)| payload).unwrap_or_default();
+    let _slot = state.executor_queue.try_admit(&state.metrics)?;
+
+    Ok(Json(
+        readiness::check(&state, &classroom_model, &smoke).await?,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/publish",
+    params(ClassroomPath),
+    tag = "Classrooms",
+    request_body(content = PublishClassroomRequest, description = "Optional reference solution for the smoke test"),
+    responses(
+        (status = 200, description = "Draft validated and made visible to students", body = PublishClassroomResponse),
+        (status = 400, description = "Classroom is already published"),
+        (status = 404, description = "Classroom not found"),
+        (status = 422, description = "Validation found issues; nothing was changed"),
+        (status = 503, description = "Judge0 queue is full; retry after `Retry-After` seconds")
+    )
+)]
+pub async fn publish_classroom(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    payload: Option<Json<PublishClassroomRequest>>,
+) -> Result<Json<PublishClassroomResponse>, AppError> {
+    let classroom_model = find_classroom(&state, id).await?;
+    if !classroom_model.is_draft() {
+        return Err(AppError::BadRequest(
+            "Classroom is already published".into(),
+        ));
+    }
+
+    let smoke = payload.map(|Json(payload)| payload).unwrap_or_default();
+    let _slot = state.executor_queue.try_admit(&state.metrics)?;
+    let report = readiness::check(&state, &classroom_model, &smoke).await?;
+    let executor_error = report
+        .smoke_test
+        .as_ref()
+        .and_then(|smoke_test| smoke_test.executor_error.clone());
+    if classroom_model.is_exam
+        && let Some(executor_error) = executor_error
+    {
+        exam_gate::record(&state, classroom_model.clone(), Err(executor_error)).await?;
+    }
+    if !report.ready {
+        return Err(AppError::ClassroomNotReady(report.issues.join("; ")));
+    }
+
+    // Conditional on the draft status so concurrent publishes flip it only once.
+    let now = Utc::now();
+    let result = classroom::Entity::update_many()
+        .col_expr(
+            classroom::Column::Status,
+            Expr::value(ClassroomStatus::Published.as_str()),
+        )
+        .col_expr(classroom::Column::PublishedAt, Expr::value(now))
+        .col_expr(classroom::Column::UpdatedAt, Expr::value(now))
+        .filter(classroom::Column::Id.eq(id))
+        .filter(classroom::Column::Status.eq(ClassroomStatus::Draft.as_str()))
+        .exec(&state.db)
+        .await?;
+    if result.rows_affected == 0 {
+        return Err(AppError::BadRequest(
+            "Classroom is already published".into(),
+        ));
+    }
+    if classroom_model.is_exam {
+        exam_gate::record(&state, classroom_model, Ok(())).await?;
+    }
+
+    Ok(Json(PublishClassroomResponse {
+        classroom_id: id,
+        status: ClassroomStatus::Published,
+        published_at: now,
+        report,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/exam-gate/check",
+    params(ClassroomPath),
+    tag = "Classrooms",
+    responses(
+        (status = 200, description = "Executor smoke-tested now; the exam is gated or released accordingly", body = ExamGateCheckResponse),
+        (status = 400, description = "Classroom is not an exam"),
+        (status = 404, description = "Classroom not found"),
+        (status = 503, description = "Judge0 queue is full; retry after `Retry-After` seconds")
+    )
+)]
+pub async fn check_exam_gate(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Result<Json<ExamGateCheckResponse>, AppError> {
+    let classroom_model = find_classroom(&state, id).await?;
+    if !classroom_model.is_exam {
+        return Err(AppError::BadRequest("Classroom is not an exam".into()));
+    }
+
+    let _slot = state.executor_queue.try_admit(&state.metrics)?;
+    Ok(Json(exam_gate::check(&state, classroom_model).await?))
+}
+
+async fn find_classroom(state: &AppState, id: i32) -> Result<classroom::Model, AppError> {
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)
+}
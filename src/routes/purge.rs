@@ -0,0 +1,83 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::Utc;
+
+use crate::{
+    auth::AuthAccount,
+    dto::{PurgeReceipt, PurgeReceiptVerification, PurgeStudentRequest, VerifyPurgeReceiptRequest},
+    error::AppError,
+    services::{audit, purge},
+    state::AppState,
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/purge",
+    tag = "Admin",
+    security(("bearer" = [])),
+    params(("id" = i32, Path, description = "Roster entry of the student; every entry with the same NPM is purged")),
+    request_body = PurgeStudentRequest,
+    responses(
+        (status = 200, description = "Student's roster entries, account, code, submissions, events and logs deleted and overwritten; keep the signed receipt", body = PurgeReceipt),
+        (status = 400, description = "The NPM belongs to a staff account or to the caller"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Roster entry not found"),
+        (status = 428, description = "confirmNpm does not repeat the student's NPM")
+    )
+)]
+pub async fn purge_student(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Json(payload): Json<PurgeStudentRequest>,
+) -> Result<Json<PurgeReceipt>, AppError> {
+    auth.require_admin()?;
+
+    let receipt = purge::purge_student(
+        &state,
+        id,
+        &payload.confirm_npm,
+        auth.account.id,
+        Utc::now(),
+    )
+    .await?;
+    tracing::info!(
+        receipt_id = %receipt.receipt_id,
+        counts = %receipt.counts,
+        "student purged"
+    );
+    audit::record_purge(&state.db, &receipt).await;
+
+    Ok(Json(receipt))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/purge-receipts/verify",
+    tag = "Admin",
+    security(("bearer" = [])),
+    request_body = VerifyPurgeReceiptRequest,
+    responses(
+        (status = 200, description = "Whether the receipt is authentic and, when an NPM is given, whether it names that student", body = PurgeReceiptVerification),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn verify_purge_receipt(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Json(payload): Json<VerifyPurgeReceiptRequest>,
+) -> Result<Json<PurgeReceiptVerification>, AppError> {
+    auth.require_admin()?;
+
+    let receipt = payload.receipt;
+    Ok(Json(PurgeReceiptVerification {
+        valid: purge::verify(&state, &receipt),
+        subject_matches: payload
+            .npm
+            .map(|npm| purge::subject_hash(&state, &npm) == receipt.subject_hash),
+    }))
+}
@@ -0,0 +1,109 @@
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{Request, State},
+    http::{HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+
+use crate::{
+    auth::AuthAccount,
+    dto::{HealthResponse, ReplicationRole, ReplicationStatusResponse},
+    error::AppError,
+    services::{replication, signing},
+    state::AppState,
+};
+
+/// Writes a standby still accepts: snapshots from the primary, and logins so an admin
+/// can read the replication status.
+const STANDBY_WRITABLE_PATHS: &[&str] = &["/api/replication/snapshot", "/api/auth/login"];
+
+/// Refuses writes on a standby. Its own database is replaced on promotion, so anything
+/// written there before would be lost.
+pub async fn refuse_writes_on_standby(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if state.replication.role() == ReplicationRole::Standby
+        && !matches!(
+            *request.method(),
+            Method::GET | Method::HEAD | Method::OPTIONS
+        )
+        && !STANDBY_WRITABLE_PATHS.contains(&request.uri().path())
+    {
+        return AppError::Standby(
+            "this server is a replication standby; send writes to the primary".into(),
+        )
+        .into_response();
+    }
+    next.run(request).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    tag = "Admin",
+    responses(
+        (status = 200, description = "Server berjalan dan database dapat dijangkau; dipakai `asmctl promote` untuk memeriksa primary", body = HealthResponse),
+        (status = 500, description = "Database tidak dapat dijangkau")
+    )
+)]
+pub async fn health(State(state): State<AppState>) -> Result<Json<HealthResponse>, AppError> {
+    state.db.ping().await?;
+    Ok(Json(HealthResponse {
+        status: "ok".into(),
+        role: state.replication.role(),
+        server_time: Utc::now(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/replication-status",
+    tag = "Admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Peran replikasi, snapshot terbaru yang dipegang standby, dan lag dalam detik", body = ReplicationStatusResponse),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Pemanggil bukan admin")
+    )
+)]
+pub async fn replication_status(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<ReplicationStatusResponse>, AppError> {
+    auth.require_admin()?;
+    Ok(Json(state.replication.status(Utc::now())))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/replication/snapshot",
+    tag = "Admin",
+    request_body(content = Vec<u8>, content_type = "application/octet-stream", description = "Salinan database SQLite primary"),
+    responses(
+        (status = 204, description = "Snapshot disimpan di standby"),
+        (status = 401, description = "Tanda tangan `X-Signature` tidak valid"),
+        (status = 403, description = "Server ini bukan standby"),
+        (status = 409, description = "Standby sudah memegang snapshot yang sama atau lebih baru")
+    )
+)]
+pub async fn receive_snapshot(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, AppError> {
+    let signature = headers
+        .get(signing::SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok());
+    let manifest = replication::receive(&state.replication, signature, &body).await?;
+    tracing::info!(
+        "stored replication snapshot taken at {} ({} bytes)",
+        manifest.taken_at,
+        manifest.bytes
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,81 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+
+use crate::{
+    error::DatabaseFailure,
+    services::{
+        alerting::{self, Signal},
+        client_errors, sql_trace,
+    },
+    state::AppState,
+};
+
+pub const REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied id that is reused instead of replaced.
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+/// Id of the request being handled, for handlers that want to log or store it.
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Tags every request with an id: the caller's `X-Request-Id` when it looks sane,
+/// otherwise a random one. The id is attached to the request's log span and echoed
+/// in the response so the frontend can quote it when reporting a failure; server
+/// errors are stored under it as well.
+pub async fn assign(State(state): State<AppState>, mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| is_valid(value))
+        .map(str::to_owned)
+        .unwrap_or_else(|| hex::encode(rand::random::<[u8; 12]>()));
+    let method = request.method().to_string();
+    let path = request.uri().path().to_owned();
+    request.extensions_mut().insert(RequestId(id.clone()));
+
+    let span = tracing::info_span!("request", id = %id, %method, %path);
+    let route = alerting::route_key(&method, &path);
+    let mut response = sql_trace::with_route(route, next.run(request))
+        .instrument(span)
+        .await;
+
+    let status = response.status();
+    // `503` is deliberate load shedding rather than a failure worth keeping.
+    if status.is_server_error() && status != StatusCode::SERVICE_UNAVAILABLE {
+        if let Err(err) =
+            client_errors::record_server_error(&state.db, &id, &method, &path, status.as_u16())
+                .await
+        {
+            tracing::warn!(request_id = %id, "failed to record server error: {err}");
+        }
+        alerting::record(
+            &state,
+            Signal::ServerError {
+                route: alerting::route_key(&method, &path),
+            },
+        );
+    }
+    if response.extensions().get::<DatabaseFailure>().is_some() {
+        alerting::record(&state, Signal::DatabaseError);
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID, value);
+    }
+    response
+}
+
+fn is_valid(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.'))
+}
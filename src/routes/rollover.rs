@@ -0,0 +1,257 @@
+use std::collections::HashSet;
+
+use axum::{Json, extract::State};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseTransaction, EntityTrait, QueryFilter,
+    QueryOrder, TransactionTrait, sea_query::Expr,
+};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{
+        AccountRole, ClassroomStatus, ClonedClassroom, RolloverClassroom, RolloverReport,
+        RolloverRequest,
+    },
+    entities::{account, classroom, task, test_case, verdict_message},
+    error::AppError,
+    routes::classroom::normalize_term,
+    state::AppState,
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/rollover",
+    tag = "Admin",
+    security(("bearer" = [])),
+    request_body = RolloverRequest,
+    responses(
+        (status = 200, description = "Previous term archived, templates cloned as drafts and graduated assistants demoted", body = RolloverReport),
+        (status = 400, description = "Missing or repeated term, or the caller is among the graduated assistants"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Template classroom not found")
+    )
+)]
+pub async fn rollover(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Json(payload): Json<RolloverRequest>,
+) -> Result<Json<RolloverReport>, AppError> {
+    auth.require_admin()?;
+
+    let new_term = normalize_term(Some(payload.new_term))
+        .ok_or_else(|| AppError::BadRequest("newTerm must not be empty".into()))?;
+    let previous_term = normalize_term(payload.previous_term);
+    if previous_term.as_deref() == Some(new_term.as_str()) {
+        return Err(AppError::BadRequest(
+            "previousTerm and newTerm must differ".into(),
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    let graduated: Vec<String> = payload
+        .graduated_npms
+        .into_iter()
+        .map(|npm| npm.trim().to_string())
+        .filter(|npm| !npm.is_empty() && seen.insert(npm.clone()))
+        .collect();
+    if graduated.contains(&auth.account.npm) {
+        return Err(AppError::BadRequest(
+            "you cannot demote your own account".into(),
+        ));
+    }
+
+    let now = Utc::now();
+    let txn = state.db.begin().await?;
+
+    // Clone first so templates from the term being closed are copied before archiving.
+    let mut cloned = Vec::new();
+    let mut seen = HashSet::new();
+    for template_id in payload.template_classroom_ids {
+        if seen.insert(template_id) {
+            cloned.push(clone_classroom(&txn, template_id, &new_term, now).await?);
+        }
+    }
+
+    let term_filter = match &previous_term {
+        Some(term) => Condition::all().add(classroom::Column::Term.eq(term.as_str())),
+        None => Condition::any()
+            .add(classroom::Column::Term.is_null())
+            .add(classroom::Column::Term.ne(new_term.as_str())),
+    };
+    let to_archive = classroom::Entity::find()
+        .filter(classroom::Column::Status.ne(ClassroomStatus::Archived.as_str()))
+        .filter(term_filter)
+        .order_by_asc(classroom::Column::Id)
+        .all(&txn)
+        .await?;
+    if !to_archive.is_empty() {
+        classroom::Entity::update_many()
+            .col_expr(
+                classroom::Column::Status,
+                Expr::value(ClassroomStatus::Archived.as_str()),
+            )
+            .col_expr(classroom::Column::UpdatedAt, Expr::value(now))
+            .filter(classroom::Column::Id.is_in(to_archive.iter().map(|model| model.id)))
+            .exec(&txn)
+            .await?;
+    }
+
+    let mut demoted = Vec::new();
+    let mut unchanged = Vec::new();
+    for npm in graduated {
+        let account_model = account::Entity::find()
+            .filter(account::Column::Npm.eq(npm.as_str()))
+            .one(&txn)
+            .await?;
+        match account_model {
+            Some(model) if AccountRole::from_str(&model.role) == Some(AccountRole::Admin) => {
+                let mut account_am: account::ActiveModel = model.into();
+                account_am.role = sea_orm::ActiveValue::Set(AccountRole::User.as_str().to_owned());
+                account_am.updated_at = sea_orm::ActiveValue::Set(now);
+                account_am.update(&txn).await?;
+                demoted.push(npm);
+            }
+            _ => unchanged.push(npm),
+        }
+    }
+
+    txn.commit().await?;
+
+    Ok(Json(RolloverReport {
+        new_term,
+        archived: to_archive
+            .into_iter()
+            .map(|model| RolloverClassroom {
+                id: model.id,
+                name: model.name,
+                term: model.term,
+            })
+            .collect(),
+        cloned,
+        demoted,
+        unchanged,
+    }))
+}
+
+/// Copies the classroom's settings, tasks and test cases into a new draft classroom
+/// of `term`. Students, submissions and exam history stay with the original.
+async fn clone_classroom(
+    txn: &DatabaseTransaction,
+    template_id: i32,
+    term: &str,
+    now: DateTime<Utc>,
+) -> Result<ClonedClassroom, AppError> {
+    let template = classroom::Entity::find_by_id(template_id)
+        .one(txn)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+
+    let copy = classroom::ActiveModel {
+        name: sea_orm::ActiveValue::Set(template.name),
+        programming_language: sea_orm::ActiveValue::Set(template.programming_language),
+        language_locked: sea_orm::ActiveValue::Set(template.language_locked),
+        tasks: sea_orm::ActiveValue::Set(template.tasks),
+        is_exam: sea_orm::ActiveValue::Set(template.is_exam),
+        test_code: sea_orm::ActiveValue::Set(template.test_code),
+        // Exam dates belong to the old term and are set again for the new one.
+        exam_start: sea_orm::ActiveValue::Set(None),
+        exam_end: sea_orm::ActiveValue::Set(None),
+        presetup_code: sea_orm::ActiveValue::Set(template.presetup_code),
+        test_mode: sea_orm::ActiveValue::Set(template.test_mode),
+        test_sample_size: sea_orm::ActiveValue::Set(template.test_sample_size),
+        practice_mode: sea_orm::ActiveValue::Set(template.practice_mode),
+        grading_script: sea_orm::ActiveValue::Set(template.grading_script),
+        grading_script_language_id: sea_orm::ActiveValue::Set(template.grading_script_language_id),
+        status: sea_orm::ActiveValue::Set(ClassroomStatus::Draft.as_str().to_owned()),
+        published_at: sea_orm::ActiveValue::Set(None),
+        device_policy: sea_orm::ActiveValue::Set(template.device_policy),
+        term: sea_orm::ActiveValue::Set(Some(term.to_owned())),
+        output_limit_kb: sea_orm::ActiveValue::Set(template.output_limit_kb),
+        max_code_chars: sea_orm::ActiveValue::Set(template.max_code_chars),
+        max_description_chars: sea_orm::ActiveValue::Set(template.max_description_chars),
+        submission_cooldown_secs: sea_orm::ActiveValue::Set(template.submission_cooldown_secs),
+        post_finish_policy: sea_orm::ActiveValue::Set(template.post_finish_policy),
+        resolved_language_id: sea_orm::ActiveValue::Set(template.resolved_language_id),
+        language_missing_since: sea_orm::ActiveValue::Set(template.language_missing_since),
+        fingerprint_sharing: sea_orm::ActiveValue::Set(template.fingerprint_sharing),
+        sandbox_enable_network: sea_orm::ActiveValue::Set(template.sandbox_enable_network),
+        sandbox_max_processes: sea_orm::ActiveValue::Set(template.sandbox_max_processes),
+        sandbox_redirect_stderr: sea_orm::ActiveValue::Set(template.sandbox_redirect_stderr),
+        created_at: sea_orm::ActiveValue::Set(now),
+        updated_at: sea_orm::ActiveValue::Set(now),
+        ..Default::default()
+    }
+    .insert(txn)
+    .await?;
+
+    let tasks = task::Entity::find()
+        .filter(task::Column::ClassroomId.eq(template_id))
+        .order_by_asc(task::Column::Position)
+        .find_with_related(test_case::Entity)
+        .all(txn)
+        .await?;
+
+    let task_count = tasks.len();
+    let mut test_case_count = 0;
+    for (task_model, cases) in tasks {
+        let task_copy = task::ActiveModel {
+            classroom_id: sea_orm::ActiveValue::Set(copy.id),
+            position: sea_orm::ActiveValue::Set(task_model.position),
+            title: sea_orm::ActiveValue::Set(task_model.title),
+            description: sea_orm::ActiveValue::Set(task_model.description),
+            language_id: sea_orm::ActiveValue::Set(task_model.language_id),
+            starter_code: sea_orm::ActiveValue::Set(task_model.starter_code),
+            expected_output: sea_orm::ActiveValue::Set(task_model.expected_output),
+            weight: sea_orm::ActiveValue::Set(task_model.weight),
+            created_at: sea_orm::ActiveValue::Set(now),
+            updated_at: sea_orm::ActiveValue::Set(now),
+            ..Default::default()
+        }
+        .insert(txn)
+        .await?;
+
+        test_case_count += cases.len();
+        for case in cases {
+            test_case::ActiveModel {
+                task_id: sea_orm::ActiveValue::Set(task_copy.id),
+                position: sea_orm::ActiveValue::Set(case.position),
+                stdin: sea_orm::ActiveValue::Set(case.stdin),
+                expected_output: sea_orm::ActiveValue::Set(case.expected_output),
+                hidden: sea_orm::ActiveValue::Set(case.hidden),
+                points: sea_orm::ActiveValue::Set(case.points),
+                created_at: sea_orm::ActiveValue::Set(now),
+                updated_at: sea_orm::ActiveValue::Set(now),
+                ..Default::default()
+            }
+            .insert(txn)
+            .await?;
+        }
+    }
+
+    let verdicts = verdict_message::Entity::find()
+        .filter(verdict_message::Column::ClassroomId.eq(template_id))
+        .all(txn)
+        .await?;
+    for verdict in verdicts {
+        verdict_message::ActiveModel {
+            classroom_id: sea_orm::ActiveValue::Set(copy.id),
+            status_id: sea_orm::ActiveValue::Set(verdict.status_id),
+            message: sea_orm::ActiveValue::Set(verdict.message),
+            created_at: sea_orm::ActiveValue::Set(now),
+            updated_at: sea_orm::ActiveValue::Set(now),
+            ..Default::default()
+        }
+        .insert(txn)
+        .await?;
+    }
+
+    Ok(ClonedClassroom {
+        template_id,
+        id: copy.id,
+        name: copy.name,
+        tasks: task_count,
+        test_cases: test_case_count,
+    })
+}
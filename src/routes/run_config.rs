@@ -0,0 +1,228 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, IntoActiveModel,
+    PaginatorTrait, QueryFilter, QueryOrder,
+};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{RunConfigListParams, RunConfigResponse, SaveRunConfigRequest},
+    entities::{classroom, run_config, user},
+    error::AppError,
+    routes::test_case::find_task,
+    state::AppState,
+};
+
+/// Saved runs a student may keep per classroom.
+const MAX_RUN_CONFIGS: u64 = 50;
+const MAX_NAME_CHARS: usize = 100;
+pub const MAX_STDIN_BYTES: usize = 64 * 1024;
+pub const MAX_ARGUMENTS_BYTES: usize = 1024;
+
+#[utoipa::path(
+    post,
+    path = "/api/me/run-configs",
+    tag = "Run Configs",
+    security(("bearer" = [])),
+    request_body = SaveRunConfigRequest,
+    responses(
+        (status = 201, description = "Run configuration saved", body = RunConfigResponse),
+        (status = 200, description = "Run configuration with the same name and scope replaced", body = RunConfigResponse),
+        (status = 400, description = "Empty or oversized fields, or too many saved runs"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Classroom or task not found among the account's memberships")
+    )
+)]
+pub async fn save_run_config(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Json(payload): Json<SaveRunConfigRequest>,
+) -> Result<(StatusCode, Json<RunConfigResponse>), AppError> {
+    let user_model = find_membership(&state.db, &auth, payload.classroom_id).await?;
+    if let Some(task_id) = payload.task_id {
+        find_task(&state.db, payload.classroom_id, task_id).await?;
+    }
+
+    let name = payload.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("name must not be empty".into()));
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        return Err(AppError::BadRequest(format!(
+            "name must be at most {MAX_NAME_CHARS} characters"
+        )));
+    }
+    if payload.stdin.len() > MAX_STDIN_BYTES {
+        return Err(AppError::BadRequest(format!(
+            "stdin must be at most {MAX_STDIN_BYTES} bytes"
+        )));
+    }
+    let arguments = payload
+        .command_line_arguments
+        .map(|arguments| arguments.trim().to_string())
+        .filter(|arguments| !arguments.is_empty());
+    if arguments
+        .as_ref()
+        .is_some_and(|arguments| arguments.len() > MAX_ARGUMENTS_BYTES)
+    {
+        return Err(AppError::BadRequest(format!(
+            "commandLineArguments must be at most {MAX_ARGUMENTS_BYTES} bytes"
+        )));
+    }
+    let options = serde_json::to_string(&payload.options)
+        .map_err(|err| AppError::Internal(format!("failed to encode run options: {err}")))?;
+
+    let task_scope = match payload.task_id {
+        Some(task_id) => run_config::Column::TaskId.eq(task_id),
+        None => run_config::Column::TaskId.is_null(),
+    };
+    let existing = run_config::Entity::find()
+        .filter(run_config::Column::UserId.eq(user_model.id))
+        .filter(task_scope)
+        .filter(run_config::Column::Name.eq(&name))
+        .one(&state.db)
+        .await?;
+
+    let now = Utc::now();
+    let (status, model) = match existing {
+        Some(model) => {
+            let mut active = model.into_active_model();
+            active.stdin = sea_orm::ActiveValue::Set(payload.stdin);
+            active.command_line_arguments = sea_orm::ActiveValue::Set(arguments);
+            active.options = sea_orm::ActiveValue::Set(options);
+            active.updated_at = sea_orm::ActiveValue::Set(now);
+            (StatusCode::OK, active.update(&state.db).await?)
+        }
+        None => {
+            let saved = run_config::Entity::find()
+                .filter(run_config::Column::UserId.eq(user_model.id))
+                .count(&state.db)
+                .await?;
+            if saved >= MAX_RUN_CONFIGS {
+                return Err(AppError::BadRequest(format!(
+                    "at most {MAX_RUN_CONFIGS} run configurations can be saved per classroom"
+                )));
+            }
+            let model = run_config::ActiveModel {
+                user_id: sea_orm::ActiveValue::Set(user_model.id),
+                task_id: sea_orm::ActiveValue::Set(payload.task_id),
+                name: sea_orm::ActiveValue::Set(name),
+                stdin: sea_orm::ActiveValue::Set(payload.stdin),
+                command_line_arguments: sea_orm::ActiveValue::Set(arguments),
+                options: sea_orm::ActiveValue::Set(options),
+                created_at: sea_orm::ActiveValue::Set(now),
+                updated_at: sea_orm::ActiveValue::Set(now),
+                ..Default::default()
+            }
+            .insert(&state.db)
+            .await?;
+            (StatusCode::CREATED, model)
+        }
+    };
+
+    Ok((
+        status,
+        Json(RunConfigResponse::from_model(
+            model,
+            user_model.classroom_id,
+        )),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/me/run-configs",
+    params(RunConfigListParams),
+    tag = "Run Configs",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Saved runs of the account in the classroom, classroom-wide ones first, then by name", body = [RunConfigResponse]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Classroom not found among the account's memberships")
+    )
+)]
+pub async fn list_run_configs(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Query(params): Query<RunConfigListParams>,
+) -> Result<Json<Vec<RunConfigResponse>>, AppError> {
+    let user_model = find_membership(&state.db, &auth, params.classroom_id).await?;
+
+    let mut query = run_config::Entity::find().filter(run_config::Column::UserId.eq(user_model.id));
+    if let Some(task_id) = params.task_id {
+        query = query.filter(
+            Condition::any()
+                .add(run_config::Column::TaskId.is_null())
+                .add(run_config::Column::TaskId.eq(task_id)),
+        );
+    }
+    let configs = query
+        .order_by_asc(run_config::Column::TaskId)
+        .order_by_asc(run_config::Column::Name)
+        .all(&state.db)
+        .await?;
+
+    Ok(Json(
+        configs
+            .into_iter()
+            .map(|model| RunConfigResponse::from_model(model, user_model.classroom_id))
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/me/run-configs/{id}",
+    params(("id" = i32, Path, description = "Run configuration identifier")),
+    tag = "Run Configs",
+    security(("bearer" = [])),
+    responses(
+        (status = 204, description = "Run configuration deleted"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 404, description = "Run configuration not found among the account's own")
+    )
+)]
+pub async fn delete_run_config(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    let (model, owner) = run_config::Entity::find_by_id(id)
+        .find_also_related(user::Entity)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::RunConfigNotFound)?;
+    if owner.is_none_or(|owner| owner.npm != auth.account.npm) {
+        return Err(AppError::RunConfigNotFound);
+    }
+
+    run_config::Entity::delete_by_id(model.id)
+        .exec(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Roster entry of the signed-in account in a published classroom.
+async fn find_membership(
+    db: &DatabaseConnection,
+    auth: &AuthAccount,
+    classroom_id: i32,
+) -> Result<user::Model, AppError> {
+    let (user_model, classroom_model) = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(classroom_id))
+        .filter(user::Column::Npm.eq(&auth.account.npm))
+        .find_also_related(classroom::Entity)
+        .one(db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+    if classroom_model.is_none_or(|classroom_model| !classroom_model.is_published()) {
+        return Err(AppError::ClassroomNotFound);
+    }
+    Ok(user_model)
+}
@@ -0,0 +1,98 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, EntityTrait, IntoActiveModel, TransactionTrait};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{SandboxFlags, SandboxFlagsResponse, SettingsChange},
+    entities::classroom,
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::{sandbox, settings_history},
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/sandbox",
+    params(ClassroomPath),
+    tag = "Executor",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Judge0 sandbox flags applied to the classroom's runs", body = SandboxFlagsResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn get_sandbox_flags(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<SandboxFlagsResponse>, AppError> {
+    auth.require_admin()?;
+
+    let classroom_model = find_classroom(&state, id).await?;
+    Ok(Json(SandboxFlagsResponse {
+        classroom_id: classroom_model.id,
+        flags: SandboxFlags::of(&classroom_model),
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/classrooms/{id}/sandbox",
+    params(ClassroomPath),
+    tag = "Executor",
+    security(("bearer" = [])),
+    request_body = SandboxFlags,
+    responses(
+        (status = 200, description = "Sandbox flags replaced; unset flags fall back to the Judge0 defaults", body = SandboxFlagsResponse),
+        (status = 400, description = "maxProcessesAndOrThreads outside 1 to 120, or enableNetwork in an exam classroom"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn put_sandbox_flags(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Json(payload): Json<SandboxFlags>,
+) -> Result<Json<SandboxFlagsResponse>, AppError> {
+    auth.require_admin()?;
+
+    let classroom_model = find_classroom(&state, id).await?;
+    sandbox::validate(&payload, classroom_model.is_exam)?;
+
+    let txn = state.db.begin().await?;
+    let before = classroom_model.clone();
+    let mut classroom_am = classroom_model.into_active_model();
+    payload.apply(&mut classroom_am);
+    classroom_am.updated_at = sea_orm::ActiveValue::Set(Utc::now());
+    let updated = classroom_am.update(&txn).await?;
+    settings_history::record(
+        &txn,
+        &before,
+        &updated,
+        SettingsChange::Sandbox,
+        Some(auth.account.id),
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(Json(SandboxFlagsResponse {
+        classroom_id: updated.id,
+        flags: SandboxFlags::of(&updated),
+    }))
+}
+
+async fn find_classroom(state: &AppState, id: i32) -> Result<classroom::Model, AppError> {
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)
+}
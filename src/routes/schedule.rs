@@ -0,0 +1,46 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::Utc;
+
+use crate::{
+    auth::AuthAccount,
+    dto::{ScheduleConflictParams, ScheduleConflictsResponse},
+    error::AppError,
+    services::schedule,
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/schedule-conflicts",
+    params(ScheduleConflictParams),
+    tag = "Admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Bentrokan jadwal ujian yang belum berakhir: NPM yang terdaftar di dua ujian yang waktunya tumpang tindih, dan rentang waktu dengan mahasiswa ujian melebihi `EXAM_STUDENT_CAPACITY`", body = ScheduleConflictsResponse),
+        (status = 401, description = "Token tidak ada atau tidak valid"),
+        (status = 403, description = "Pemanggil bukan admin")
+    )
+)]
+pub async fn schedule_conflicts(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Query(params): Query<ScheduleConflictParams>,
+) -> Result<Json<ScheduleConflictsResponse>, AppError> {
+    auth.require_admin()?;
+    let now = Utc::now();
+    let capacity = state.exam_student_capacity;
+    let conflicts = match params.classroom_id {
+        Some(classroom_id) => {
+            schedule::conflicts_of(&state.db, capacity, classroom_id, now).await?
+        }
+        None => schedule::conflicts(&state.db, capacity, now).await?,
+    };
+    Ok(Json(ScheduleConflictsResponse {
+        capacity,
+        conflicts,
+        checked_at: now,
+    }))
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+    TransactionTrait,
+};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{Seat, SeatAssignment, SeatMapRequest, SeatMapResponse},
+    entities::{classroom, user},
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::seating::MAX_GRID_SIZE,
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/seats",
+    params(ClassroomPath),
+    tag = "Integrity",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Seat map of the classroom", body = SeatMapResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn get_seat_map(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<SeatMapResponse>, AppError> {
+    auth.require_admin()?;
+
+    ensure_classroom(&state, id).await?;
+    let users = classroom_users(&state, id).await?;
+    Ok(Json(seat_map_response(id, users)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/classrooms/{id}/seats",
+    params(ClassroomPath),
+    tag = "Integrity",
+    security(("bearer" = [])),
+    request_body = SeatMapRequest,
+    responses(
+        (status = 200, description = "Seats assigned from the grid; students missing from it are unseated", body = SeatMapResponse),
+        (status = 400, description = "Grid too large, or an NPM that is unknown or listed twice"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn put_seat_map(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Json(payload): Json<SeatMapRequest>,
+) -> Result<Json<SeatMapResponse>, AppError> {
+    auth.require_admin()?;
+
+    if payload.grid.len() > MAX_GRID_SIZE
+        || payload.grid.iter().any(|row| row.len() > MAX_GRID_SIZE)
+    {
+        return Err(AppError::BadRequest(format!(
+            "Seat map may have at most {MAX_GRID_SIZE} rows and {MAX_GRID_SIZE} columns"
+        )));
+    }
+
+    ensure_classroom(&state, id).await?;
+    let txn = state.db.begin().await?;
+    let users = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(id))
+        .all(&txn)
+        .await?;
+    let by_npm: HashMap<&str, i32> = users
+        .iter()
+        .map(|user_model| (user_model.npm.as_str(), user_model.id))
+        .collect();
+
+    let mut seats: HashMap<i32, (i32, i32)> = HashMap::new();
+    for (row_index, row) in payload.grid.iter().enumerate() {
+        for (column_index, cell) in row.iter().enumerate() {
+            let Some(npm) = cell.as_deref().map(str::trim).filter(|npm| !npm.is_empty()) else {
+                continue;
+            };
+            let user_id = *by_npm.get(npm).ok_or_else(|| {
+                AppError::BadRequest(format!("NPM {npm} is not in this classroom"))
+            })?;
+            let seat = (row_index as i32 + 1, column_index as i32 + 1);
+            if seats.insert(user_id, seat).is_some() {
+                return Err(AppError::BadRequest(format!(
+                    "NPM {npm} is seated more than once"
+                )));
+            }
+        }
+    }
+
+    let now = Utc::now();
+    for user_model in users {
+        let seat = seats.get(&user_model.id).copied();
+        if (user_model.seat_row, user_model.seat_column) == (seat.map(|s| s.0), seat.map(|s| s.1)) {
+            continue;
+        }
+        let mut user_am = user_model.into_active_model();
+        user_am.seat_row = sea_orm::ActiveValue::Set(seat.map(|(row, _)| row));
+        user_am.seat_column = sea_orm::ActiveValue::Set(seat.map(|(_, column)| column));
+        user_am.updated_at = sea_orm::ActiveValue::Set(now);
+        user_am.update(&txn).await?;
+    }
+    txn.commit().await?;
+
+    let users = classroom_users(&state, id).await?;
+    Ok(Json(seat_map_response(id, users)))
+}
+
+async fn ensure_classroom(state: &AppState, id: i32) -> Result<(), AppError> {
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+    Ok(())
+}
+
+async fn classroom_users(state: &AppState, id: i32) -> Result<Vec<user::Model>, AppError> {
+    Ok(user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(id))
+        .order_by_asc(user::Column::Npm)
+        .all(&state.db)
+        .await?)
+}
+
+fn seat_map_response(classroom_id: i32, users: Vec<user::Model>) -> SeatMapResponse {
+    let mut assignments = Vec::new();
+    let mut unseated = Vec::new();
+    for user_model in users {
+        match Seat::of(&user_model) {
+            Some(seat) => assignments.push(SeatAssignment {
+                user_id: user_model.id,
+                npm: user_model.npm,
+                name: user_model.name,
+                seat,
+            }),
+            None => unseated.push(user_model.npm),
+        }
+    }
+    assignments.sort_by_key(|assignment| (assignment.seat.row, assignment.seat.column));
+
+    let rows = assignments.iter().map(|a| a.seat.row).max().unwrap_or(0) as usize;
+    let columns = assignments.iter().map(|a| a.seat.column).max().unwrap_or(0) as usize;
+    let mut grid = vec![vec![None; columns]; rows];
+    for assignment in &assignments {
+        grid[assignment.seat.row as usize - 1][assignment.seat.column as usize - 1] =
+            Some(assignment.npm.clone());
+    }
+
+    SeatMapResponse {
+        classroom_id,
+        grid,
+        assignments,
+        unseated,
+    }
+}
@@ -0,0 +1,128 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+use utoipa::IntoParams;
+
+use crate::{
+    auth::AuthAccount,
+    dto::{ClassroomSettings, SettingsChange, SettingsVersionResponse},
+    entities::{classroom, classroom_settings_version},
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::{language_map, sandbox, settings_history},
+    state::AppState,
+};
+
+#[allow(dead_code)]
+#[derive(Debug, IntoParams)]
+pub struct SettingsVersionPath {
+    pub id: i32,
+    /// Version to restore; `0` is the configuration before the first recorded change.
+    pub version: i32,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/settings/history",
+    params(ClassroomPath),
+    tag = "Classrooms",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Settings versions of the classroom, newest first, with before/after snapshots", body = [SettingsVersionResponse]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn list_settings_history(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<SettingsVersionResponse>>, AppError> {
+    auth.require_admin()?;
+    find_classroom(&state, id).await?;
+
+    let versions = classroom_settings_version::Entity::find()
+        .filter(classroom_settings_version::Column::ClassroomId.eq(id))
+        .order_by_desc(classroom_settings_version::Column::Version)
+        .all(&state.db)
+        .await?;
+
+    Ok(Json(
+        versions
+            .into_iter()
+            .filter_map(SettingsVersionResponse::from_model)
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/settings/rollback/{version}",
+    params(SettingsVersionPath),
+    tag = "Classrooms",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Settings restored to the version; the rollback is recorded as a new version, which is returned", body = SettingsVersionResponse),
+        (status = 400, description = "Settings already match the version, or the version enables networking in an exam classroom"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom or version not found")
+    )
+)]
+pub async fn rollback_settings(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path((id, version)): Path<(i32, i32)>,
+) -> Result<Json<SettingsVersionResponse>, AppError> {
+    auth.require_admin()?;
+    let classroom_model = find_classroom(&state, id).await?;
+
+    let txn = state.db.begin().await?;
+    let settings = settings_history::settings_at(&txn, id, version)
+        .await?
+        .ok_or(AppError::SettingsVersionNotFound)?;
+    if settings == ClassroomSettings::from_model(&classroom_model) {
+        return Err(AppError::BadRequest(format!(
+            "settings already match version {version}"
+        )));
+    }
+
+    sandbox::validate(&settings.sandbox, settings.is_exam)?;
+
+    // A rolled-back language the executor no longer offers is flagged, not refused.
+    let language = language_map::map(&state, &settings.programming_language).await;
+    let before = classroom_model.clone();
+    let mut classroom_am = classroom_model.into_active_model();
+    settings.apply(&mut classroom_am);
+    language_map::apply(&mut classroom_am, language);
+    classroom_am.updated_at = Set(Utc::now());
+    let updated = classroom_am.update(&txn).await?;
+    let recorded = settings_history::record(
+        &txn,
+        &before,
+        &updated,
+        SettingsChange::Rollback,
+        Some(auth.account.id),
+    )
+    .await?
+    .ok_or_else(|| AppError::Internal("rollback left the settings unchanged".into()))?;
+    txn.commit().await?;
+
+    SettingsVersionResponse::from_model(recorded)
+        .map(Json)
+        .ok_or_else(|| AppError::Internal("recorded settings version is unreadable".into()))
+}
+
+async fn find_classroom(state: &AppState, id: i32) -> Result<classroom::Model, AppError> {
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)
+}
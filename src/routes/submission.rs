@@ -0,0 +1,79 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use sea_orm::{ColumnTrait, EntityTrait};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{SubmissionHistoryEntry, SubmissionListParams},
+    entities::{classroom, submission, user},
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::submission_history,
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/submissions",
+    params(ClassroomPath, SubmissionListParams),
+    tag = "Grading",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Runs and hand-ins of the classroom's students with their output, newest first", body = [SubmissionHistoryEntry]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn list_classroom_submissions(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Query(params): Query<SubmissionListParams>,
+) -> Result<Json<Vec<SubmissionHistoryEntry>>, AppError> {
+    auth.require_admin()?;
+
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+    let entries =
+        submission_history::list(&state.db, submission::Column::ClassroomId.eq(id), &params)
+            .await?;
+    Ok(Json(entries))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users/{id}/submissions",
+    params(
+        ("id" = i32, Path, description = "Roster entry of the student"),
+        SubmissionListParams
+    ),
+    tag = "Grading",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Runs and hand-ins of the roster entry with their output, newest first", body = [SubmissionHistoryEntry]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Roster entry not found")
+    )
+)]
+pub async fn list_user_submissions(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Query(params): Query<SubmissionListParams>,
+) -> Result<Json<Vec<SubmissionHistoryEntry>>, AppError> {
+    auth.require_admin()?;
+
+    user::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+    let entries =
+        submission_history::list(&state.db, submission::Column::UserId.eq(id), &params).await?;
+    Ok(Json(entries))
+}
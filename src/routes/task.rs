@@ -0,0 +1,288 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+
+use crate::{
+    dto::{CreateTaskRequest, TaskDetailResponse, UpdateTaskRequest, ValidatedJson},
+    entities::{classroom, task},
+    error::AppError,
+    routes::{
+        classroom::ClassroomPath,
+        test_case::{TaskPath, find_task},
+    },
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/tasks",
+    params(ClassroomPath),
+    tag = "Tasks",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Tasks of the classroom in order, with their reference output", body = [TaskDetailResponse]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller may not manage classrooms"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn list_tasks(
+    State(state): State<AppState>,
+    Path(classroom_id): Path<i32>,
+) -> Result<Json<Vec<TaskDetailResponse>>, AppError> {
+    find_classroom(&state.db, classroom_id).await?;
+
+    let tasks = ordered_tasks(&state.db, classroom_id).await?;
+    Ok(Json(
+        tasks.into_iter().map(TaskDetailResponse::from).collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/tasks",
+    params(ClassroomPath),
+    tag = "Tasks",
+    security(("bearer" = [])),
+    request_body = CreateTaskRequest,
+    responses(
+        (status = 201, description = "Task created at `position`, or after the last task", body = TaskDetailResponse),
+        (status = 400, description = "Invalid weight or language id"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller may not manage classrooms"),
+        (status = 404, description = "Classroom not found"),
+        (status = 422, description = "Description or starter code longer than the classroom allows")
+    )
+)]
+pub async fn create_task(
+    State(state): State<AppState>,
+    Path(classroom_id): Path<i32>,
+    ValidatedJson(payload): ValidatedJson<CreateTaskRequest>,
+) -> Result<(StatusCode, Json<TaskDetailResponse>), AppError> {
+    find_classroom(&state.db, classroom_id).await?;
+    let weight = validate_weight(payload.weight.unwrap_or(1))?;
+    validate_language_id(payload.language_id)?;
+
+    let txn = state.db.begin().await?;
+    let now = Utc::now();
+    let model = task::ActiveModel {
+        classroom_id: sea_orm::ActiveValue::Set(classroom_id),
+        position: sea_orm::ActiveValue::Set(i32::MAX),
+        title: sea_orm::ActiveValue::Set(payload.title),
+        description: sea_orm::ActiveValue::Set(payload.description),
+        language_id: sea_orm::ActiveValue::Set(payload.language_id),
+        due_at: sea_orm::ActiveValue::Set(payload.due_at),
+        starter_code: sea_orm::ActiveValue::Set(payload.starter_code.and_then(non_empty)),
+        expected_output: sea_orm::ActiveValue::Set(payload.expected_output.and_then(non_empty)),
+        weight: sea_orm::ActiveValue::Set(weight),
+        created_at: sea_orm::ActiveValue::Set(now),
+        updated_at: sea_orm::ActiveValue::Set(now),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    let model = renumber(
+        &txn,
+        classroom_id,
+        Some((model.id, payload.position.unwrap_or(i32::MAX))),
+        now,
+    )
+    .await?
+    .expect("created task is in its classroom");
+    txn.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(TaskDetailResponse::from(model))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/tasks/{task_id}",
+    params(TaskPath),
+    tag = "Tasks",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "The task with its reference output", body = TaskDetailResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller may not manage classrooms"),
+        (status = 404, description = "Classroom or task not found")
+    )
+)]
+pub async fn get_task(
+    State(state): State<AppState>,
+    Path((classroom_id, task_id)): Path<(i32, i32)>,
+) -> Result<Json<TaskDetailResponse>, AppError> {
+    let model = find_task(&state.db, classroom_id, task_id).await?;
+    Ok(Json(TaskDetailResponse::from(model)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/classrooms/{id}/tasks/{task_id}",
+    params(TaskPath),
+    tag = "Tasks",
+    security(("bearer" = [])),
+    request_body = UpdateTaskRequest,
+    responses(
+        (status = 200, description = "Task updated; moving it shifts the tasks in between", body = TaskDetailResponse),
+        (status = 400, description = "Invalid weight or language id"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller may not manage classrooms"),
+        (status = 404, description = "Classroom or task not found"),
+        (status = 422, description = "Description or starter code longer than the classroom allows")
+    )
+)]
+pub async fn update_task(
+    State(state): State<AppState>,
+    Path((classroom_id, task_id)): Path<(i32, i32)>,
+    ValidatedJson(payload): ValidatedJson<UpdateTaskRequest>,
+) -> Result<Json<TaskDetailResponse>, AppError> {
+    let task_model = find_task(&state.db, classroom_id, task_id).await?;
+    validate_language_id(payload.language_id)?;
+
+    let mut task_am = task_model.into_active_model();
+    if let Some(title) = payload.title {
+        task_am.title = sea_orm::ActiveValue::Set(title);
+    }
+    if let Some(description) = payload.description {
+        task_am.description = sea_orm::ActiveValue::Set(description);
+    }
+    if let Some(starter_code) = payload.starter_code {
+        task_am.starter_code = sea_orm::ActiveValue::Set(non_empty(starter_code));
+    }
+    if let Some(expected_output) = payload.expected_output {
+        task_am.expected_output = sea_orm::ActiveValue::Set(non_empty(expected_output));
+    }
+    if let Some(weight) = payload.weight {
+        task_am.weight = sea_orm::ActiveValue::Set(validate_weight(weight)?);
+    }
+    if let Some(language_id) = payload.language_id {
+        task_am.language_id = sea_orm::ActiveValue::Set(Some(language_id));
+    }
+    if let Some(due_at) = payload.due_at {
+        task_am.due_at = sea_orm::ActiveValue::Set(Some(due_at));
+    }
+    let now = Utc::now();
+    task_am.updated_at = sea_orm::ActiveValue::Set(now);
+
+    let txn = state.db.begin().await?;
+    let mut updated = task_am.update(&txn).await?;
+    if let Some(position) = payload.position {
+        updated = renumber(&txn, classroom_id, Some((task_id, position)), now)
+            .await?
+            .expect("updated task is in its classroom");
+    }
+    txn.commit().await?;
+
+    Ok(Json(TaskDetailResponse::from(updated)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/classrooms/{id}/tasks/{task_id}",
+    params(TaskPath),
+    tag = "Tasks",
+    security(("bearer" = [])),
+    responses(
+        (status = 204, description = "Task and its test cases deleted; later tasks move up"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller may not manage classrooms"),
+        (status = 404, description = "Classroom or task not found")
+    )
+)]
+pub async fn delete_task(
+    State(state): State<AppState>,
+    Path((classroom_id, task_id)): Path<(i32, i32)>,
+) -> Result<StatusCode, AppError> {
+    find_task(&state.db, classroom_id, task_id).await?;
+
+    let txn = state.db.begin().await?;
+    task::Entity::delete_by_id(task_id).exec(&txn).await?;
+    renumber(&txn, classroom_id, None, Utc::now()).await?;
+    txn.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn validate_weight(weight: i32) -> Result<i32, AppError> {
+    if weight < 1 {
+        return Err(AppError::BadRequest("weight must be at least 1".into()));
+    }
+    Ok(weight)
+}
+
+fn validate_language_id(language_id: Option<i32>) -> Result<(), AppError> {
+    if language_id.is_some_and(|id| id <= 0) {
+        return Err(AppError::BadRequest(
+            "languageId must be a positive Judge0 language id".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// `None` for blank starter code or reference output, which clears it.
+pub fn non_empty(value: String) -> Option<String> {
+    (!value.trim().is_empty()).then_some(value)
+}
+
+async fn find_classroom(
+    db: &impl ConnectionTrait,
+    classroom_id: i32,
+) -> Result<classroom::Model, AppError> {
+    classroom::Entity::find_by_id(classroom_id)
+        .one(db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)
+}
+
+async fn ordered_tasks(
+    db: &impl ConnectionTrait,
+    classroom_id: i32,
+) -> Result<Vec<task::Model>, AppError> {
+    Ok(task::Entity::find()
+        .filter(task::Column::ClassroomId.eq(classroom_id))
+        .order_by_asc(task::Column::Position)
+        .order_by_asc(task::Column::Id)
+        .all(db)
+        .await?)
+}
+
+/// Numbers the classroom's tasks 0, 1, 2… in their current order, after placing
+/// `moved` (task id, wanted position) at its position. Returns the moved task.
+async fn renumber(
+    db: &impl ConnectionTrait,
+    classroom_id: i32,
+    moved: Option<(i32, i32)>,
+    now: DateTime<Utc>,
+) -> Result<Option<task::Model>, AppError> {
+    let mut tasks = ordered_tasks(db, classroom_id).await?;
+    if let Some((task_id, position)) = moved
+        && let Some(index) = tasks.iter().position(|model| model.id == task_id)
+    {
+        let model = tasks.remove(index);
+        let position = usize::try_from(position.max(0)).unwrap_or_default();
+        tasks.insert(position.min(tasks.len()), model);
+    }
+
+    let mut moved_model = None;
+    for (position, model) in tasks.into_iter().enumerate() {
+        let model = if model.position == position as i32 {
+            model
+        } else {
+            let mut task_am = model.into_active_model();
+            task_am.position = sea_orm::ActiveValue::Set(position as i32);
+            task_am.updated_at = sea_orm::ActiveValue::Set(now);
+            task_am.update(db).await?
+        };
+        if moved.is_some_and(|(task_id, _)| task_id == model.id) {
+            moved_model = Some(model);
+        }
+    }
+    Ok(moved_model)
+}
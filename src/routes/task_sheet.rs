@@ -0,0 +1,54 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::HeaderMap,
+};
+
+use crate::{
+    dto::RenderedTaskResponse,
+    error::AppError,
+    routes::{
+        etag::{Cached, ETag},
+        test_case::{TaskPath, find_task},
+    },
+    services::markdown,
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/tasks/{task_id}/rendered",
+    params(TaskPath),
+    tag = "Classrooms",
+    responses(
+        (status = 200, description = "Task description rendered from markdown to sanitized HTML", body = RenderedTaskResponse),
+        (status = 304, description = "Task unchanged since the `If-None-Match` ETag"),
+        (status = 404, description = "Classroom or task not found")
+    )
+)]
+pub async fn rendered_task(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((classroom_id, task_id)): Path<(i32, i32)>,
+) -> Result<Cached<Json<RenderedTaskResponse>>, AppError> {
+    let task_model = find_task(&state.db, classroom_id, task_id).await?;
+
+    let tag = ETag::new(&[
+        task_model.id.to_string(),
+        task_model.updated_at.to_rfc3339(),
+    ]);
+    if tag.matches(&headers) {
+        return Ok(Cached::NotModified(tag));
+    }
+
+    Ok(Cached::Fresh(
+        tag,
+        Json(RenderedTaskResponse {
+            classroom_id,
+            task_id,
+            html: markdown::to_safe_html(&task_model.description),
+            title: task_model.title,
+            updated_at: task_model.updated_at,
+        }),
+    ))
+}
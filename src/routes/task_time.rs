@@ -0,0 +1,132 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{
+        HeaderMap, StatusCode,
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    },
+    response::IntoResponse,
+};
+use chrono::Utc;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{ExportTimeParams, TaskProgressRequest, TaskTimeSummaryResponse},
+    entities::{classroom, user},
+    error::AppError,
+    routes::{
+        classroom::ClassroomPath,
+        test_case::{TaskPath, find_task},
+    },
+    services::{display_time::TimeFormatter, task_time},
+    state::AppState,
+};
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/tasks/{task_id}/progress",
+    params(TaskPath),
+    tag = "Task Analytics",
+    request_body = TaskProgressRequest,
+    responses(
+        (status = 204, description = "Task opening or edit recorded"),
+        (status = 404, description = "Classroom, task or user not found")
+    )
+)]
+pub async fn record_task_progress(
+    State(state): State<AppState>,
+    Path((classroom_id, task_id)): Path<(i32, i32)>,
+    auth: AuthAccount,
+    Json(payload): Json<TaskProgressRequest>,
+) -> Result<StatusCode, AppError> {
+    auth.require_acting_as(&payload.npm)?;
+    let classroom_model = classroom::Entity::find_by_id(classroom_id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+    if !classroom_model.is_published() {
+        return Err(AppError::ClassroomNotFound);
+    }
+    let task_model = find_task(&state.db, classroom_id, task_id).await?;
+
+    let user_model = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(classroom_id))
+        .filter(user::Column::Npm.eq(payload.npm.trim()))
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+
+    task_time::record(&state.db, task_model.id, user_model.id, payload.event).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/task-times",
+    params(ClassroomPath),
+    tag = "Task Analytics",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Time spent per task, in task order", body = TaskTimeSummaryResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn task_time_summary(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<TaskTimeSummaryResponse>, AppError> {
+    auth.require_admin()?;
+
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+
+    Ok(Json(TaskTimeSummaryResponse {
+        classroom_id: id,
+        tasks: task_time::summarize(&state.db, id).await?,
+        generated_at: Utc::now(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/task-times/export",
+    params(ClassroomPath, ExportTimeParams),
+    tag = "Task Analytics",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "CSV with one row per student and task: first opening, last edit, seconds spent and edit count. Timestamps are RFC 3339 UTC; with local time display `_local` columns in the display timezone are added", content_type = "text/csv", body = String),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn export_task_times(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Query(params): Query<ExportTimeParams>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_admin()?;
+    let times = TimeFormatter::resolve(params.time, &headers, state.display_timezone);
+
+    let out = task_time::export(&state.db, id, times).await?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"classroom-{id}-task-times.csv\""),
+            ),
+        ],
+        out,
+    ))
+}
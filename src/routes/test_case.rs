@@ -0,0 +1,465 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+use utoipa::IntoParams;
+
+use crate::{
+    auth::{
+        AuthAccount,
+        device::{self, DeviceFingerprint},
+        permissions::Permission,
+    },
+    dto::{
+        CreateTestCaseRequest, ExtractTestsRequest, ExtractTestsResponse, PracticeRunRequest,
+        ProposedTestCase, SandboxFlags, TaskActivity, TestCaseResponse, TestCaseStatsParams,
+        TestCaseStatsResponse, TestMode, TestRunResponse, UpdateTestCaseRequest, ValidatedJson,
+    },
+    entities::{classroom, task, test_case, user},
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::{
+        case_stats, feedback, grading, language_usage, markdown, output, post_finish,
+        submission_cooldown, task_time, test_runner, verdict_messages::VerdictMessages,
+    },
+    state::AppState,
+};
+
+#[allow(dead_code)]
+#[derive(Debug, IntoParams)]
+pub struct TaskPath {
+    pub id: i32,
+    pub task_id: i32,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, IntoParams)]
+pub struct TestCasePath {
+    pub id: i32,
+    pub task_id: i32,
+    pub case_id: i32,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/tasks/{task_id}/test-cases",
+    params(TaskPath),
+    tag = "Test Cases",
+    responses(
+        (status = 200, description = "Test cases of the task, hidden ones included", body = [TestCaseResponse]),
+        (status = 404, description = "Classroom or task not found")
+    )
+)]
+pub async fn list_test_cases(
+    State(state): State<AppState>,
+    Path((classroom_id, task_id)): Path<(i32, i32)>,
+) -> Result<Json<Vec<TestCaseResponse>>, AppError> {
+    find_task(&state.db, classroom_id, task_id).await?;
+
+    let cases = load_test_cases(&state.db, task_id).await?;
+
+    Ok(Json(
+        cases.into_iter().map(TestCaseResponse::from).collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/tasks/{task_id}/test-cases",
+    params(TaskPath),
+    tag = "Test Cases",
+    request_body = CreateTestCaseRequest,
+    responses(
+        (status = 201, description = "Test case created", body = TestCaseResponse),
+        (status = 400, description = "Invalid payload"),
+        (status = 404, description = "Classroom or task not found")
+    )
+)]
+pub async fn create_test_case(
+    State(state): State<AppState>,
+    Path((classroom_id, task_id)): Path<(i32, i32)>,
+    Json(payload): Json<CreateTestCaseRequest>,
+) -> Result<(StatusCode, Json<TestCaseResponse>), AppError> {
+    find_task(&state.db, classroom_id, task_id).await?;
+
+    let points = validate_points(payload.points.unwrap_or(1))?;
+    let position = next_position(&state.db, task_id).await?;
+
+    let now = Utc::now();
+    let model = test_case::ActiveModel {
+        task_id: sea_orm::ActiveValue::Set(task_id),
+        position: sea_orm::ActiveValue::Set(position),
+        stdin: sea_orm::ActiveValue::Set(payload.stdin),
+        expected_output: sea_orm::ActiveValue::Set(payload.expected_output),
+        hidden: sea_orm::ActiveValue::Set(payload.hidden),
+        points: sea_orm::ActiveValue::Set(points),
+        created_at: sea_orm::ActiveValue::Set(now),
+        updated_at: sea_orm::ActiveValue::Set(now),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(TestCaseResponse::from(model))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/tasks/{task_id}/extract-tests",
+    params(TaskPath),
+    tag = "Test Cases",
+    request_body = ExtractTestsRequest,
+    responses(
+        (status = 200, description = "Sample input/output blocks of the task description as proposed test cases; with `confirm` the selected ones are created", body = ExtractTestsResponse),
+        (status = 400, description = "Unknown proposal index or invalid points"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller may not manage classrooms"),
+        (status = 404, description = "Classroom or task not found")
+    )
+)]
+pub async fn extract_tests(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path((classroom_id, task_id)): Path<(i32, i32)>,
+    Json(payload): Json<ExtractTestsRequest>,
+) -> Result<Json<ExtractTestsResponse>, AppError> {
+    auth.require(Permission::ManageClassrooms)?;
+    let task_model = find_task(&state.db, classroom_id, task_id).await?;
+
+    let existing = load_test_cases(&state.db, task_id).await?;
+    let proposals: Vec<ProposedTestCase> = markdown::sample_cases(&task_model.description)
+        .into_iter()
+        .enumerate()
+        .map(|(index, sample)| ProposedTestCase {
+            index,
+            duplicate: existing.iter().any(|case| {
+                case.stdin == sample.stdin && case.expected_output == sample.expected_output
+            }),
+            stdin: sample.stdin,
+            expected_output: sample.expected_output,
+            line: sample.line,
+        })
+        .collect();
+    if !payload.confirm {
+        return Ok(Json(ExtractTestsResponse {
+            proposals,
+            created: Vec::new(),
+        }));
+    }
+
+    let points = validate_points(payload.points.unwrap_or(1))?;
+    let selected: Vec<&ProposedTestCase> = match &payload.indexes {
+        Some(indexes) => indexes
+            .iter()
+            .map(|index| {
+                proposals.get(*index).ok_or_else(|| {
+                    AppError::BadRequest(format!("no proposed test case with index {index}"))
+                })
+            })
+            .collect::<Result<_, _>>()?,
+        None => proposals
+            .iter()
+            .filter(|proposal| !proposal.duplicate)
+            .collect(),
+    };
+
+    let txn = state.db.begin().await?;
+    let mut position = next_position(&txn, task_id).await?;
+    let now = Utc::now();
+    let mut created = Vec::with_capacity(selected.len());
+    for proposal in selected {
+        let model = test_case::ActiveModel {
+            task_id: sea_orm::ActiveValue::Set(task_id),
+            position: sea_orm::ActiveValue::Set(position),
+            stdin: sea_orm::ActiveValue::Set(proposal.stdin.clone()),
+            expected_output: sea_orm::ActiveValue::Set(proposal.expected_output.clone()),
+            hidden: sea_orm::ActiveValue::Set(payload.hidden),
+            points: sea_orm::ActiveValue::Set(points),
+            created_at: sea_orm::ActiveValue::Set(now),
+            updated_at: sea_orm::ActiveValue::Set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        created.push(TestCaseResponse::from(model));
+        position += 1;
+    }
+    txn.commit().await?;
+
+    Ok(Json(ExtractTestsResponse { proposals, created }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/classrooms/{id}/tasks/{task_id}/test-cases/{case_id}",
+    params(TestCasePath),
+    tag = "Test Cases",
+    request_body = UpdateTestCaseRequest,
+    responses(
+        (status = 200, description = "Test case updated", body = TestCaseResponse),
+        (status = 404, description = "Classroom, task or test case not found")
+    )
+)]
+pub async fn update_test_case(
+    State(state): State<AppState>,
+    Path((classroom_id, task_id, case_id)): Path<(i32, i32, i32)>,
+    Json(payload): Json<UpdateTestCaseRequest>,
+) -> Result<Json<TestCaseResponse>, AppError> {
+    let case_model = find_test_case(&state.db, classroom_id, task_id, case_id).await?;
+
+    let mut case_am = case_model.into_active_model();
+    if let Some(stdin) = payload.stdin {
+        case_am.stdin = sea_orm::ActiveValue::Set(stdin);
+    }
+    if let Some(expected_output) = payload.expected_output {
+        case_am.expected_output = sea_orm::ActiveValue::Set(expected_output);
+    }
+    if let Some(hidden) = payload.hidden {
+        case_am.hidden = sea_orm::ActiveValue::Set(hidden);
+    }
+    if let Some(points) = payload.points {
+        case_am.points = sea_orm::ActiveValue::Set(validate_points(points)?);
+    }
+    if let Some(position) = payload.position {
+        case_am.position = sea_orm::ActiveValue::Set(position);
+    }
+    case_am.updated_at = sea_orm::ActiveValue::Set(Utc::now());
+
+    let updated = case_am.update(&state.db).await?;
+
+    Ok(Json(TestCaseResponse::from(updated)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/classrooms/{id}/tasks/{task_id}/test-cases/{case_id}",
+    params(TestCasePath),
+    tag = "Test Cases",
+    responses(
+        (status = 204, description = "Test case deleted"),
+        (status = 404, description = "Classroom, task or test case not found")
+    )
+)]
+pub async fn delete_test_case(
+    State(state): State<AppState>,
+    Path((classroom_id, task_id, case_id)): Path<(i32, i32, i32)>,
+) -> Result<StatusCode, AppError> {
+    find_test_case(&state.db, classroom_id, task_id, case_id).await?;
+
+    test_case::Entity::delete_by_id(case_id)
+        .exec(&state.db)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/classrooms/{id}/tasks/{task_id}/run",
+    params(TaskPath),
+    tag = "Test Cases",
+    request_body = PracticeRunRequest,
+    responses(
+        (status = 200, description = "Practice run against the task's test cases", body = TestRunResponse),
+        (status = 400, description = "The classroom locks its language and the task requires another one"),
+        (status = 401, description = "User is not active"),
+        (status = 403, description = "The exam attempt is bound to another device, or the classroom is not in practice mode"),
+        (status = 404, description = "Classroom, task or user not found"),
+        (status = 429, description = "Submission rate limit reached, or the classroom's submission cooldown has not passed since the student's last graded submission (`code: \"submission_cooldown\"`); retry after `Retry-After` seconds"),
+        (status = 502, description = "Judge0 request failed"),
+        (status = 503, description = "Judge0 queue is full; retry after `Retry-After` seconds"),
+        (status = 504, description = "Judge0 did not answer within the timeout")
+    )
+)]
+pub async fn run_practice_tests(
+    State(state): State<AppState>,
+    Path((classroom_id, task_id)): Path<(i32, i32)>,
+    auth: AuthAccount,
+    DeviceFingerprint(fingerprint): DeviceFingerprint,
+    ValidatedJson(payload): ValidatedJson<PracticeRunRequest>,
+) -> Result<Json<TestRunResponse>, AppError> {
+    auth.require_acting_as(&payload.npm)?;
+    let classroom = classroom::Entity::find_by_id(classroom_id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+    if !classroom.is_published() {
+        return Err(AppError::ClassroomNotFound);
+    }
+    let task = find_task(&state.db, classroom_id, task_id).await?;
+
+    let npm = payload.npm.trim();
+    let user_model = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(classroom_id))
+        .filter(user::Column::Npm.eq(npm))
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+
+    if !user_model.active {
+        return Err(AppError::Unauthorized("User is not active".into()));
+    }
+    post_finish::ensure_unlocked(&user_model)?;
+    let user_model = device::enforce(
+        &state,
+        &classroom,
+        user_model,
+        fingerprint.as_deref(),
+        "practice run",
+        false,
+    )
+    .await?;
+
+    feedback::ensure_practice_runs(&classroom)?;
+    let language_id = grading::task_language_id(&classroom, &task, payload.language_id)?;
+    let _slot = state.executor_queue.try_admit(&state.metrics)?;
+    state
+        .rate_limit
+        .check(&user_model.npm)
+        .map_err(|retry_after_secs| AppError::RateLimited { retry_after_secs })?;
+    submission_cooldown::claim(&state.db, &classroom, user_model.id).await?;
+    task_time::record(&state.db, task.id, user_model.id, TaskActivity::Edited).await?;
+
+    let cases = load_test_cases(&state.db, task.id).await?;
+    let total_cases = cases.len();
+    let mode = TestMode::from_str(&classroom.test_mode).unwrap_or(TestMode::Full);
+    let selected = test_runner::select_cases(
+        cases,
+        mode,
+        classroom.test_sample_size.max(1) as usize,
+        test_runner::practice_seed(&user_model.npm, task.id),
+    );
+
+    let outcomes = test_runner::run_cases(
+        &state,
+        &payload.source_code,
+        language_id,
+        selected,
+        output::limit_kb(&state, &classroom),
+        SandboxFlags::of(&classroom),
+        None,
+    )
+    .await?;
+    language_usage::record_run(&state.db, Some(classroom.id), language_id).await;
+
+    let verdicts = VerdictMessages::load(&state.db, classroom.id).await?;
+    Ok(Json(feedback::test_run(
+        task.id,
+        mode,
+        total_cases,
+        outcomes,
+        &verdicts,
+    )))
+}
+
+fn validate_points(points: i32) -> Result<i32, AppError> {
+    if points < 1 {
+        return Err(AppError::BadRequest("points must be at least 1".into()));
+    }
+    Ok(points)
+}
+
+pub async fn find_task(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+    task_id: i32,
+) -> Result<task::Model, AppError> {
+    let task_model = task::Entity::find_by_id(task_id)
+        .one(db)
+        .await?
+        .ok_or(AppError::TaskNotFound)?;
+
+    if task_model.classroom_id != classroom_id {
+        return Err(AppError::TaskNotFound);
+    }
+
+    Ok(task_model)
+}
+
+async fn find_test_case(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+    task_id: i32,
+    case_id: i32,
+) -> Result<test_case::Model, AppError> {
+    find_task(db, classroom_id, task_id).await?;
+
+    let case_model = test_case::Entity::find_by_id(case_id)
+        .one(db)
+        .await?
+        .ok_or(AppError::TestCaseNotFound)?;
+
+    if case_model.task_id != task_id {
+        return Err(AppError::TestCaseNotFound);
+    }
+
+    Ok(case_model)
+}
+
+/// Position after the last test case of the task.
+async fn next_position(db: &impl ConnectionTrait, task_id: i32) -> Result<i32, AppError> {
+    Ok(test_case::Entity::find()
+        .filter(test_case::Column::TaskId.eq(task_id))
+        .select_only()
+        .column_as(test_case::Column::Position.max(), "max_position")
+        .into_tuple::<Option<i32>>()
+        .one(db)
+        .await?
+        .flatten()
+        .map_or(0, |max| max + 1))
+}
+
+async fn load_test_cases(
+    db: &DatabaseConnection,
+    task_id: i32,
+) -> Result<Vec<test_case::Model>, AppError> {
+    Ok(test_case::Entity::find()
+        .filter(test_case::Column::TaskId.eq(task_id))
+        .order_by_asc(test_case::Column::Position)
+        .order_by_asc(test_case::Column::Id)
+        .all(db)
+        .await?)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/test-cases/stats",
+    params(ClassroomPath, TestCaseStatsParams),
+    tag = "Test Cases",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Pass/fail counts per test case over graded submissions, with cases nobody or everybody passes flagged", body = TestCaseStatsResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller lacks the grade permission"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn test_case_stats(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Query(params): Query<TestCaseStatsParams>,
+) -> Result<Json<TestCaseStatsResponse>, AppError> {
+    auth.require(Permission::Grade)?;
+
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+
+    let min_students = params
+        .min_students
+        .unwrap_or(case_stats::DEFAULT_MIN_STUDENTS);
+    Ok(Json(TestCaseStatsResponse {
+        classroom_id: id,
+        min_students,
+        test_cases: case_stats::summarize(&state.db, id, min_students).await?,
+        generated_at: Utc::now(),
+    }))
+}
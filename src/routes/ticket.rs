@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::IntoResponse,
+};
+use sea_orm::EntityTrait;
+
+use crate::{
+    auth::AuthAccount,
+    dto::{TicketFormat, TicketParams},
+    entities::classroom,
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::tickets,
+    state::AppState,
+};
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/tickets",
+    params(ClassroomPath, TicketParams),
+    tag = "Classrooms",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Printable exam tickets with each student's name, NPM, seat, exam window in the display timezone and one-time join code. Codes are issued on the first print and stay the same until used; `format=pdf` gives an A4 PDF", content(("text/plain" = String), ("application/pdf" = Vec<u8>))),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom or roster entry not found")
+    )
+)]
+pub async fn exam_tickets(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+    Query(params): Query<TicketParams>,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_admin()?;
+
+    let classroom_model = classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+    let tickets = tickets::issue(&state, id, params.user_id).await?;
+
+    let zone = state.display_timezone;
+    let (content_type, body) = match params.format {
+        TicketFormat::Txt => (
+            "text/plain; charset=utf-8",
+            tickets::render_text(&classroom_model, &tickets, zone).into_bytes(),
+        ),
+        TicketFormat::Pdf => (
+            "application/pdf",
+            tickets::render_pdf(&classroom_model, &tickets, zone),
+        ),
+    };
+    Ok((
+        [
+            (CONTENT_TYPE, content_type.to_owned()),
+            (
+                CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"classroom-{id}-tickets.{}\"",
+                    params.format.as_str()
+                ),
+            ),
+        ],
+        body,
+    ))
+}
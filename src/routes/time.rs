@@ -0,0 +1,28 @@
+use axum::{
+    Json,
+    http::header::{CACHE_CONTROL, HeaderValue},
+    response::IntoResponse,
+};
+use chrono::Utc;
+
+use crate::dto::ServerTimeResponse;
+
+#[utoipa::path(
+    get,
+    path = "/api/time",
+    tag = "Time",
+    responses(
+        (status = 200, description = "Server's current UTC instant. Clients estimate their offset as `serverTime + rtt/2 - local time` and apply it to exam countdowns", body = ServerTimeResponse)
+    )
+)]
+pub async fn server_time() -> impl IntoResponse {
+    let now = Utc::now();
+    (
+        // Any cached copy would report a stale instant.
+        [(CACHE_CONTROL, HeaderValue::from_static("no-store"))],
+        Json(ServerTimeResponse {
+            server_time: now,
+            unix_millis: now.timestamp_millis(),
+        }),
+    )
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, TransactionTrait,
+};
+use utoipa::IntoParams;
+
+use crate::{
+    auth::AuthAccount,
+    dto::{UpdateVerdictMessageRequest, VerdictMessageResponse},
+    entities::{classroom, verdict_message},
+    error::AppError,
+    routes::classroom::ClassroomPath,
+    services::verdict_messages::{self, JUDGE0_STATUSES},
+    state::AppState,
+};
+
+/// Longest verdict message accepted, in characters.
+const MAX_MESSAGE_CHARS: usize = 500;
+
+#[allow(dead_code)]
+#[derive(Debug, IntoParams)]
+pub struct VerdictPath {
+    pub id: i32,
+    /// Judge0 status id.
+    pub status_id: i32,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/classrooms/{id}/verdict-messages",
+    params(ClassroomPath),
+    tag = "Classrooms",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Every Judge0 status with its default description and the classroom's message, if set", body = [VerdictMessageResponse]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn list_verdict_messages(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<VerdictMessageResponse>>, AppError> {
+    auth.require_admin()?;
+
+    ensure_classroom(&state, id).await?;
+    let mut custom: HashMap<i32, verdict_message::Model> = verdict_message::Entity::find()
+        .filter(verdict_message::Column::ClassroomId.eq(id))
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|model| (model.status_id, model))
+        .collect();
+
+    let response = JUDGE0_STATUSES
+        .iter()
+        .map(|(status_id, description)| {
+            let model = custom.remove(status_id);
+            VerdictMessageResponse {
+                status_id: *status_id,
+                default_description: (*description).to_owned(),
+                updated_at: model.as_ref().map(|model| model.updated_at),
+                message: model.map(|model| model.message),
+            }
+        })
+        .collect();
+    Ok(Json(response))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/classrooms/{id}/verdict-messages/{status_id}",
+    params(VerdictPath),
+    tag = "Classrooms",
+    security(("bearer" = [])),
+    request_body = UpdateVerdictMessageRequest,
+    responses(
+        (status = 200, description = "Message stored; students see it for this status from now on", body = VerdictMessageResponse),
+        (status = 400, description = "Unknown status id, or an empty or overlong message"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn put_verdict_message(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path((id, status_id)): Path<(i32, i32)>,
+    Json(payload): Json<UpdateVerdictMessageRequest>,
+) -> Result<Json<VerdictMessageResponse>, AppError> {
+    auth.require_admin()?;
+
+    let default_description = known_status(status_id)?;
+    let message = payload.message.trim().to_owned();
+    if message.is_empty() {
+        return Err(AppError::BadRequest("message must not be empty".into()));
+    }
+    if message.chars().count() > MAX_MESSAGE_CHARS {
+        return Err(AppError::BadRequest(format!(
+            "message must be at most {MAX_MESSAGE_CHARS} characters"
+        )));
+    }
+    ensure_classroom(&state, id).await?;
+
+    let now = Utc::now();
+    let txn = state.db.begin().await?;
+    let existing = verdict_message::Entity::find()
+        .filter(verdict_message::Column::ClassroomId.eq(id))
+        .filter(verdict_message::Column::StatusId.eq(status_id))
+        .one(&txn)
+        .await?;
+    let saved = match existing {
+        Some(model) => {
+            let mut verdict_am = model.into_active_model();
+            verdict_am.message = sea_orm::ActiveValue::Set(message);
+            verdict_am.updated_at = sea_orm::ActiveValue::Set(now);
+            verdict_am.update(&txn).await?
+        }
+        None => {
+            verdict_message::ActiveModel {
+                classroom_id: sea_orm::ActiveValue::Set(id),
+                status_id: sea_orm::ActiveValue::Set(status_id),
+                message: sea_orm::ActiveValue::Set(message),
+                created_at: sea_orm::ActiveValue::Set(now),
+                updated_at: sea_orm::ActiveValue::Set(now),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?
+        }
+    };
+    txn.commit().await?;
+
+    Ok(Json(VerdictMessageResponse {
+        status_id,
+        default_description: default_description.to_owned(),
+        message: Some(saved.message),
+        updated_at: Some(saved.updated_at),
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/classrooms/{id}/verdict-messages/{status_id}",
+    params(VerdictPath),
+    tag = "Classrooms",
+    security(("bearer" = [])),
+    responses(
+        (status = 204, description = "Message removed; students see Judge0's description again"),
+        (status = 400, description = "Unknown status id"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Classroom not found")
+    )
+)]
+pub async fn delete_verdict_message(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path((id, status_id)): Path<(i32, i32)>,
+) -> Result<StatusCode, AppError> {
+    auth.require_admin()?;
+
+    known_status(status_id)?;
+    ensure_classroom(&state, id).await?;
+    verdict_message::Entity::delete_many()
+        .filter(verdict_message::Column::ClassroomId.eq(id))
+        .filter(verdict_message::Column::StatusId.eq(status_id))
+        .exec(&state.db)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn known_status(status_id: i32) -> Result<&'static str, AppError> {
+    verdict_messages::default_description(status_id)
+        .ok_or_else(|| AppError::BadRequest(format!("unknown Judge0 status id {status_id}")))
+}
+
+async fn ensure_classroom(state: &AppState, id: i32) -> Result<(), AppError> {
+    classroom::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, EntityTrait, QueryOrder};
+
+use crate::{
+    auth::AuthAccount,
+    dto::{CreateWebhookRequest, WebhookCreatedResponse, WebhookResponse, WebhookTestResponse},
+    entities::webhook_endpoint,
+    error::AppError,
+    services::webhook,
+    state::AppState,
+};
+
+/// Event name of deliveries sent by the test endpoint.
+const TEST_EVENT: &str = "webhook.test";
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/webhooks",
+    tag = "Webhooks",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Registered webhook endpoints, without secrets", body = [WebhookResponse]),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+) -> Result<Json<Vec<WebhookResponse>>, AppError> {
+    auth.require_admin()?;
+
+    let endpoints = webhook_endpoint::Entity::find()
+        .order_by_asc(webhook_endpoint::Column::Id)
+        .all(&state.db)
+        .await?;
+
+    Ok(Json(
+        endpoints.into_iter().map(WebhookResponse::from).collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/webhooks",
+    tag = "Webhooks",
+    security(("bearer" = [])),
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 201, description = "Endpoint registered; the secret is only shown here", body = WebhookCreatedResponse),
+        (status = 400, description = "Invalid name, URL or secret"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin")
+    )
+)]
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<WebhookCreatedResponse>), AppError> {
+    auth.require_admin()?;
+
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("name must not be empty".into()));
+    }
+    let url = payload.url.trim();
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(AppError::BadRequest(
+            "url must start with http:// or https://".into(),
+        ));
+    }
+    let secret = match payload.secret {
+        Some(secret) if secret.len() < 16 => {
+            return Err(AppError::BadRequest(
+                "secret must be at least 16 characters".into(),
+            ));
+        }
+        Some(secret) => secret,
+        None => {
+            let bytes: [u8; 32] = rand::random();
+            hex::encode(bytes)
+        }
+    };
+
+    let now = Utc::now();
+    let model = webhook_endpoint::ActiveModel {
+        name: sea_orm::ActiveValue::Set(name.to_owned()),
+        url: sea_orm::ActiveValue::Set(url.to_owned()),
+        secret_encrypted: sea_orm::ActiveValue::Set(state.secrets.seal(&secret)),
+        active: sea_orm::ActiveValue::Set(true),
+        created_at: sea_orm::ActiveValue::Set(now),
+        updated_at: sea_orm::ActiveValue::Set(now),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(WebhookCreatedResponse {
+            webhook: WebhookResponse::from(model),
+            secret,
+        }),
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/webhooks/{id}",
+    params(("id" = i32, Path, description = "Webhook endpoint identifier")),
+    tag = "Webhooks",
+    security(("bearer" = [])),
+    responses(
+        (status = 204, description = "Endpoint removed"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Webhook endpoint not found")
+    )
+)]
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<StatusCode, AppError> {
+    auth.require_admin()?;
+
+    let result = webhook_endpoint::Entity::delete_by_id(id)
+        .exec(&state.db)
+        .await?;
+    if result.rows_affected == 0 {
+        return Err(AppError::WebhookNotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/webhooks/{id}/test",
+    params(("id" = i32, Path, description = "Webhook endpoint identifier")),
+    tag = "Webhooks",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Signed `webhook.test` delivery attempted; see `success` for the outcome", body = WebhookTestResponse),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Webhook endpoint not found")
+    )
+)]
+pub async fn test_webhook(
+    State(state): State<AppState>,
+    auth: AuthAccount,
+    Path(id): Path<i32>,
+) -> Result<Json<WebhookTestResponse>, AppError> {
+    auth.require_admin()?;
+
+    let endpoint = webhook_endpoint::Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::WebhookNotFound)?;
+
+    let delivery = webhook::deliver(
+        &state,
+        &endpoint,
+        TEST_EVENT,
+        serde_json::json!({ "requestedBy": auth.account.npm }),
+    )
+    .await?;
+
+    Ok(Json(WebhookTestResponse {
+        success: delivery.succeeded(),
+        status_code: delivery.status_code,
+        duration_ms: delivery.duration.as_millis() as u64,
+        signature: delivery.signature,
+        error: delivery.error,
+    }))
+}
@@ -0,0 +1,243 @@
+//! Accounts and their roles as a versioned JSON document, for moving them between
+//! deployments or restoring them after a wipe. Classroom enrolments and assistant
+//! assignments refer to local ids and are not part of the document.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, TransactionTrait,
+};
+use serde_json::Value;
+
+use crate::{
+    dto::{
+        AccountExport, AccountImportResponse, AccountRole, AccountStatus, ExportedAccount,
+        ImportConflictPolicy,
+    },
+    entities::account,
+    error::AppError,
+    services::{accounts, mailer},
+};
+
+pub const FORMAT: &str = "asm-lab/accounts";
+/// Document version written by this server; also the only one it reads.
+pub const VERSION: u32 = 1;
+
+/// Errors reported per document; the rest are summarized.
+const MAX_REPORTED_ERRORS: usize = 50;
+
+pub async fn export(db: &DatabaseConnection) -> Result<AccountExport, AppError> {
+    let accounts = account::Entity::find()
+        .order_by_asc(account::Column::Npm)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(ExportedAccount::from)
+        .collect();
+    Ok(AccountExport {
+        format: FORMAT.to_owned(),
+        version: VERSION,
+        exported_at: Utc::now(),
+        accounts,
+    })
+}
+
+/// Checks an uploaded document against the schema of its version, reporting every
+/// problem with its JSON path rather than only the first.
+pub fn validate(document: Value) -> Result<Vec<ExportedAccount>, AppError> {
+    let mut errors = Vec::new();
+    let Value::Object(mut fields) = document else {
+        return Err(AppError::InvalidDocument(vec![
+            "$: expected an object".to_owned(),
+        ]));
+    };
+
+    match fields.get("format") {
+        Some(Value::String(format)) if format == FORMAT => {}
+        Some(_) => errors.push(format!("$.format: expected \"{FORMAT}\"")),
+        None => errors.push("$.format: missing".to_owned()),
+    }
+    match fields.get("version").and_then(Value::as_u64) {
+        Some(version) if version == u64::from(VERSION) => {}
+        Some(version) => errors.push(format!(
+            "$.version: version {version} is not supported; this server reads version {VERSION}"
+        )),
+        None => errors.push("$.version: expected an integer".to_owned()),
+    }
+    if let Some(exported_at) = fields.get("exportedAt")
+        && serde_json::from_value::<chrono::DateTime<Utc>>(exported_at.clone()).is_err()
+    {
+        errors.push("$.exportedAt: expected an RFC 3339 timestamp".to_owned());
+    }
+    for name in fields.keys() {
+        if !matches!(
+            name.as_str(),
+            "format" | "version" | "exportedAt" | "accounts"
+        ) {
+            errors.push(format!("$.{name}: unknown field"));
+        }
+    }
+
+    let entries = match fields.remove("accounts") {
+        Some(Value::Array(entries)) => entries,
+        Some(_) => {
+            errors.push("$.accounts: expected an array".to_owned());
+            Vec::new()
+        }
+        None => {
+            errors.push("$.accounts: missing".to_owned());
+            Vec::new()
+        }
+    };
+
+    let mut accounts = Vec::with_capacity(entries.len());
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let path = format!("$.accounts[{index}]");
+        let mut entry: ExportedAccount = match serde_json::from_value(entry) {
+            Ok(entry) => entry,
+            Err(err) => {
+                errors.push(format!("{path}: {err}"));
+                continue;
+            }
+        };
+
+        entry.npm = entry.npm.trim().to_owned();
+        if entry.npm.is_empty() {
+            errors.push(format!("{path}.npm: must not be empty"));
+        } else if let Some(first) = seen.insert(entry.npm.clone(), index) {
+            errors.push(format!(
+                "{path}.npm: {} already appears at $.accounts[{first}]",
+                entry.npm
+            ));
+        }
+        if let Some(email) = &entry.email {
+            match mailer::normalize_email(email) {
+                Some(email) => entry.email = Some(email),
+                None => errors.push(format!("{path}.email: not a valid address")),
+            }
+        } else if entry.email_verified_at.is_some() {
+            errors.push(format!("{path}.emailVerifiedAt: set without an email"));
+        }
+        accounts.push(entry);
+    }
+
+    if errors.is_empty() {
+        return Ok(accounts);
+    }
+    if errors.len() > MAX_REPORTED_ERRORS {
+        let more = errors.len() - MAX_REPORTED_ERRORS;
+        errors.truncate(MAX_REPORTED_ERRORS);
+        errors.push(format!("... and {more} more"));
+    }
+    Err(AppError::InvalidDocument(errors))
+}
+
+/// Creates the document's accounts and resolves NPMs that already exist by `policy`,
+/// all in one transaction. Refused when it would leave no active admin.
+pub async fn import(
+    db: &DatabaseConnection,
+    entries: Vec<ExportedAccount>,
+    policy: ImportConflictPolicy,
+) -> Result<AccountImportResponse, AppError> {
+    let _lock = accounts::lock_admin_changes().await;
+    let txn = db.begin().await?;
+    let admins_before = count_active_admins(&txn).await?;
+
+    let npms: HashSet<&str> = entries.iter().map(|entry| entry.npm.as_str()).collect();
+    let mut existing: HashMap<String, account::Model> = account::Entity::find()
+        .filter(account::Column::Npm.is_in(npms))
+        .all(&txn)
+        .await?
+        .into_iter()
+        .map(|model| (model.npm.clone(), model))
+        .collect();
+
+    let now = Utc::now();
+    let mut response = AccountImportResponse {
+        policy,
+        created: Vec::new(),
+        updated: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for entry in entries {
+        let Some(current) = existing.remove(&entry.npm) else {
+            account::ActiveModel {
+                npm: Set(entry.npm.clone()),
+                role: Set(entry.role.as_str().to_owned()),
+                status: Set(entry.status.as_str().to_owned()),
+                email: Set(entry.email),
+                email_verified_at: Set(entry.email_verified_at),
+                approved_at: Set(entry.approved_at),
+                created_at: Set(entry.created_at),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+            response.created.push(entry.npm);
+            continue;
+        };
+
+        let (email, email_verified_at, approved_at) = match policy {
+            ImportConflictPolicy::Skip => {
+                response.skipped.push(entry.npm);
+                continue;
+            }
+            ImportConflictPolicy::Overwrite => {
+                (entry.email, entry.email_verified_at, entry.approved_at)
+            }
+            // The verification belongs to whichever address is kept.
+            ImportConflictPolicy::Merge => {
+                let (email, email_verified_at) = match entry.email {
+                    Some(email) => (Some(email), entry.email_verified_at),
+                    None => (current.email.clone(), current.email_verified_at),
+                };
+                (
+                    email,
+                    email_verified_at,
+                    entry.approved_at.or(current.approved_at),
+                )
+            }
+        };
+
+        let unchanged = current.role == entry.role.as_str()
+            && current.status == entry.status.as_str()
+            && current.email == email
+            && current.email_verified_at == email_verified_at
+            && current.approved_at == approved_at;
+        if unchanged {
+            response.skipped.push(entry.npm);
+            continue;
+        }
+
+        let mut active_model = current.into_active_model();
+        active_model.role = Set(entry.role.as_str().to_owned());
+        active_model.status = Set(entry.status.as_str().to_owned());
+        active_model.email = Set(email);
+        active_model.email_verified_at = Set(email_verified_at);
+        active_model.approved_at = Set(approved_at);
+        active_model.updated_at = Set(now);
+        active_model.update(&txn).await?;
+        response.updated.push(entry.npm);
+    }
+
+    if admins_before > 0 && count_active_admins(&txn).await? == 0 {
+        return Err(AppError::LastAdmin(
+            "Impor akan menurunkan atau menonaktifkan semua admin; sertakan minimal satu admin aktif atau gunakan kebijakan skip."
+                .into(),
+        ));
+    }
+    txn.commit().await?;
+    Ok(response)
+}
+
+async fn count_active_admins(db: &impl sea_orm::ConnectionTrait) -> Result<u64, AppError> {
+    Ok(account::Entity::find()
+        .filter(account::Column::Role.eq(AccountRole::Admin.as_str()))
+        .filter(account::Column::Status.eq(AccountStatus::Active.as_str()))
+        .count(db)
+        .await?)
+}
@@ -0,0 +1,117 @@
+//! Role changes and deletions of accounts. Both refuse to leave the server without an
+//! active admin; only `asmctl` may override that, since it works on the database
+//! directly and can always create a new admin.
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel,
+    PaginatorTrait, QueryFilter,
+};
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::{
+    dto::{AccountRole, AccountStatus},
+    entities::account,
+    error::AppError,
+};
+
+/// Serializes admin removals so two concurrent demotions cannot each see the other
+/// admin as the one that remains.
+static ADMIN_CHANGES: Mutex<()> = Mutex::const_new(());
+
+/// Holds off other admin changes until the guard is dropped, for bulk changes that
+/// check the remaining admins themselves.
+pub async fn lock_admin_changes() -> MutexGuard<'static, ()> {
+    ADMIN_CHANGES.lock().await
+}
+
+/// Whether the last-admin check applies to a change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminGuard {
+    Enforce,
+    /// Skip the check; for the offline CLI only.
+    Override,
+}
+
+/// Gives account `id` a new role. `actor_id` is the account making the change, used
+/// to word the refusal when admins demote themselves.
+pub async fn set_role(
+    db: &impl ConnectionTrait,
+    id: i32,
+    role: AccountRole,
+    actor_id: Option<i32>,
+    guard: AdminGuard,
+) -> Result<account::Model, AppError> {
+    let _lock = ADMIN_CHANGES.lock().await;
+    let account_model = find(db, id).await?;
+    if role != AccountRole::Admin && guard == AdminGuard::Enforce {
+        ensure_admin_remains(db, &account_model, actor_id, "diturunkan").await?;
+    }
+
+    let mut active_model = account_model.into_active_model();
+    active_model.role = Set(role.as_str().to_owned());
+    active_model.updated_at = Set(Utc::now());
+    Ok(active_model.update(db).await?)
+}
+
+/// Deletes account `id`.
+pub async fn delete(
+    db: &impl ConnectionTrait,
+    id: i32,
+    actor_id: Option<i32>,
+    guard: AdminGuard,
+) -> Result<(), AppError> {
+    let _lock = ADMIN_CHANGES.lock().await;
+    let account_model = find(db, id).await?;
+    if guard == AdminGuard::Enforce {
+        ensure_admin_remains(db, &account_model, actor_id, "dihapus").await?;
+    }
+
+    account::Entity::delete_by_id(account_model.id)
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+async fn find(db: &impl ConnectionTrait, id: i32) -> Result<account::Model, AppError> {
+    account::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(AppError::BadRequest("Akun tidak ditemukan".into()))
+}
+
+/// Refuses when `account_model` is an active admin and no other active admin exists.
+async fn ensure_admin_remains(
+    db: &impl ConnectionTrait,
+    account_model: &account::Model,
+    actor_id: Option<i32>,
+    action: &str,
+) -> Result<(), AppError> {
+    if !is_active_admin(account_model) {
+        return Ok(());
+    }
+    let other_admins = account::Entity::find()
+        .filter(account::Column::Role.eq(AccountRole::Admin.as_str()))
+        .filter(account::Column::Status.eq(AccountStatus::Active.as_str()))
+        .filter(account::Column::Id.ne(account_model.id))
+        .count(db)
+        .await?;
+    if other_admins > 0 {
+        return Ok(());
+    }
+
+    Err(AppError::LastAdmin(if actor_id == Some(account_model.id) {
+        format!(
+            "Akun admin Anda tidak dapat {action} karena tidak ada admin lain; jadikan akun lain admin terlebih dahulu."
+        )
+    } else {
+        format!(
+            "Admin terakhir tidak dapat {action}; jadikan akun lain admin terlebih dahulu atau gunakan asmctl."
+        )
+    }))
+}
+
+fn is_active_admin(account_model: &account::Model) -> bool {
+    AccountRole::from_str(&account_model.role) == Some(AccountRole::Admin)
+        && AccountStatus::from_str(&account_model.status) == Some(AccountStatus::Active)
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    dto::{
+        AccountActivityResponse, AccountResponse, AuditEntryResponse, AuditKind, ClassroomActivity,
+        SubmissionActivity,
+    },
+    entities::{account, audit_log, classroom, classroom_assistant, submission, user},
+    error::AppError,
+    services::{csv, display_time::TimeFormatter},
+};
+
+/// Collects the activity report of `account_model`. Roster entries are matched on the
+/// account's NPM.
+pub async fn report(
+    db: &DatabaseConnection,
+    account_model: account::Model,
+) -> Result<AccountActivityResponse, AppError> {
+    let (logins, admin_actions): (Vec<_>, Vec<_>) = audit_log::Entity::find()
+        .filter(audit_log::Column::AccountId.eq(account_model.id))
+        .order_by_asc(audit_log::Column::Id)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(AuditEntryResponse::from_model)
+        .partition(|entry| entry.kind == AuditKind::Login);
+
+    let roster = user::Entity::find()
+        .filter(user::Column::Npm.eq(&account_model.npm))
+        .all(db)
+        .await?;
+    let assignments = classroom_assistant::Entity::find()
+        .filter(classroom_assistant::Column::AccountId.eq(account_model.id))
+        .all(db)
+        .await?;
+    let classroom_ids: Vec<i32> = roster
+        .iter()
+        .map(|user_model| user_model.classroom_id)
+        .chain(assignments.iter().map(|assignment| assignment.classroom_id))
+        .collect();
+    let names: HashMap<i32, String> = classroom::Entity::find()
+        .filter(classroom::Column::Id.is_in(classroom_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|classroom_model| (classroom_model.id, classroom_model.name))
+        .collect();
+
+    let mut classrooms: Vec<ClassroomActivity> = roster
+        .iter()
+        .map(|user_model| ClassroomActivity {
+            classroom_id: user_model.classroom_id,
+            classroom_name: names
+                .get(&user_model.classroom_id)
+                .cloned()
+                .unwrap_or_default(),
+            membership: "student".to_owned(),
+            user_id: Some(user_model.id),
+            joined_at: user_model.created_at,
+        })
+        .chain(assignments.into_iter().map(|assignment| {
+            ClassroomActivity {
+                classroom_id: assignment.classroom_id,
+                classroom_name: names
+                    .get(&assignment.classroom_id)
+                    .cloned()
+                    .unwrap_or_default(),
+                membership: "assistant".to_owned(),
+                user_id: None,
+                joined_at: assignment.created_at,
+            }
+        }))
+        .collect();
+    classrooms.sort_by_key(|entry| entry.joined_at);
+
+    let submissions = submission::Entity::find()
+        .filter(submission::Column::UserId.is_in(roster.iter().map(|user_model| user_model.id)))
+        .order_by_asc(submission::Column::Id)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(SubmissionActivity::from)
+        .collect();
+
+    Ok(AccountActivityResponse {
+        account: AccountResponse::from_model(account_model),
+        generated_at: Utc::now(),
+        logins,
+        classrooms,
+        submissions,
+        admin_actions,
+    })
+}
+
+/// One line of the CSV export.
+struct Row {
+    at: DateTime<Utc>,
+    category: &'static str,
+    classroom_id: Option<i32>,
+    reference: String,
+    detail: String,
+}
+
+/// Flattens the report into one CSV row per entry, ordered by time.
+pub fn to_csv(report: &AccountActivityResponse, times: &TimeFormatter) -> String {
+    let mut rows = Vec::new();
+    for entry in &report.logins {
+        rows.push(Row {
+            at: entry.created_at,
+            category: "login",
+            classroom_id: entry.classroom_id,
+            reference: entry.id.to_string(),
+            detail: entry.detail.clone(),
+        });
+    }
+    for entry in &report.classrooms {
+        rows.push(Row {
+            at: entry.joined_at,
+            category: "classroom",
+            classroom_id: Some(entry.classroom_id),
+            reference: entry.user_id.map(|id| id.to_string()).unwrap_or_default(),
+            detail: format!("{} ({})", entry.classroom_name, entry.membership),
+        });
+    }
+    for entry in &report.submissions {
+        let mut detail = if entry.is_final { "final" } else { "run" }.to_owned();
+        if let Some(task_id) = entry.task_id {
+            detail.push_str(&format!(" task {task_id}"));
+        }
+        if let (Some(score), Some(max_score)) = (entry.score, entry.max_score) {
+            detail.push_str(&format!(" score {score}/{max_score}"));
+        }
+        if let Some(status) = &entry.status {
+            detail.push_str(&format!(" {status}"));
+        }
+        if entry.late {
+            detail.push_str(" late");
+        }
+        rows.push(Row {
+            at: entry.created_at,
+            category: "submission",
+            classroom_id: Some(entry.classroom_id),
+            reference: entry.id.to_string(),
+            detail,
+        });
+    }
+    for entry in &report.admin_actions {
+        rows.push(Row {
+            at: entry.created_at,
+            category: "admin_action",
+            classroom_id: None,
+            reference: entry.request_id.clone().unwrap_or_default(),
+            detail: match entry.status {
+                Some(status) => format!("{} -> {status}", entry.detail),
+                None => entry.detail.clone(),
+            },
+        });
+    }
+    rows.sort_by_key(|row| row.at);
+
+    let mut header = vec!["npm".to_string(), "category".to_string()];
+    header.extend(times.columns("at"));
+    header.extend(["classroom_id", "reference", "detail"].map(String::from));
+    let mut out = header.join(",");
+    out.push('\n');
+    for row in rows {
+        let mut fields = vec![csv::field(&report.account.npm), row.category.to_owned()];
+        fields.extend(times.cells(Some(row.at)));
+        fields.extend([
+            row.classroom_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            csv::field(&row.reference),
+            csv::field(&row.detail),
+        ]);
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
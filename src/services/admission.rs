@@ -0,0 +1,76 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{dto::ExecutorQueueStatus, error::AppError, services::metrics::Metrics};
+
+/// Longest retry hint handed to turned-away clients.
+const MAX_RETRY_AFTER_SECS: u64 = 60;
+
+/// Bounded queue of work that talks to Judge0. Each admitted operation holds one slot
+/// for all of its executor calls. Interactive requests are turned away while every
+/// slot is taken, so a burst fails fast instead of piling onto Judge0; background
+/// grading waits for a slot instead.
+pub struct ExecutorQueue {
+    capacity: usize,
+    slots: Arc<Semaphore>,
+    waiting: AtomicU64,
+    rejected: AtomicU64,
+}
+
+impl ExecutorQueue {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            slots: Arc::new(Semaphore::new(capacity)),
+            waiting: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// Takes a slot for a request, or fails with a busy error whose retry hint is the
+    /// average executor latency, i.e. roughly when the next slot frees up.
+    pub fn try_admit(&self, metrics: &Metrics) -> Result<OwnedSemaphorePermit, AppError> {
+        Arc::clone(&self.slots).try_acquire_owned().map_err(|_| {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            let average_latency_ms = metrics.judge0.snapshot().average_latency_ms;
+            AppError::ExecutorBusy {
+                retry_after_secs: average_latency_ms
+                    .div_ceil(1000)
+                    .clamp(1, MAX_RETRY_AFTER_SECS),
+            }
+        })
+    }
+
+    /// Waits for a slot; for work that must not be dropped, such as grading a hand-in.
+    pub async fn admit(&self) -> OwnedSemaphorePermit {
+        self.waiting.fetch_add(1, Ordering::Relaxed);
+        let _waiting = WaitingGuard(&self.waiting);
+        Arc::clone(&self.slots)
+            .acquire_owned()
+            .await
+            .expect("executor queue semaphore is never closed")
+    }
+
+    pub fn status(&self) -> ExecutorQueueStatus {
+        ExecutorQueueStatus {
+            capacity: self.capacity,
+            depth: self.capacity - self.slots.available_permits(),
+            waiting: self.waiting.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Stops counting a waiter once it is admitted or gives up.
+struct WaitingGuard<'a>(&'a AtomicU64);
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
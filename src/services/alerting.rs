@@ -0,0 +1,199 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use crate::{
+    dto::{AlertSignalKind, ErrorBudgetStatus},
+    services::webhook,
+    state::AppState,
+};
+
+/// Thresholds of the error budget. A threshold of zero disables that signal.
+#[derive(Debug, Clone)]
+pub struct AlertRules {
+    pub window: Duration,
+    pub cooldown: Duration,
+    /// 5xx responses of a single route within the window.
+    pub route_server_errors: u32,
+    pub judge0_failures: u32,
+    pub database_errors: u32,
+}
+
+impl AlertRules {
+    fn threshold(&self, kind: AlertSignalKind) -> u32 {
+        match kind {
+            AlertSignalKind::RouteServerErrors => self.route_server_errors,
+            AlertSignalKind::Judge0Failures => self.judge0_failures,
+            AlertSignalKind::DatabaseErrors => self.database_errors,
+        }
+    }
+}
+
+/// Something that spends error budget.
+pub enum Signal {
+    /// A 5xx response; `route` is the method and normalized path.
+    ServerError {
+        route: String,
+    },
+    Judge0Failure,
+    DatabaseError,
+}
+
+impl Signal {
+    fn kind(&self) -> AlertSignalKind {
+        match self {
+            Signal::ServerError { .. } => AlertSignalKind::RouteServerErrors,
+            Signal::Judge0Failure => AlertSignalKind::Judge0Failures,
+            Signal::DatabaseError => AlertSignalKind::DatabaseErrors,
+        }
+    }
+
+    fn subject(self) -> Option<String> {
+        match self {
+            Signal::ServerError { route } => Some(route),
+            Signal::Judge0Failure | Signal::DatabaseError => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Window {
+    events: VecDeque<Instant>,
+    last_alert: Option<(Instant, DateTime<Utc>)>,
+}
+
+/// Sliding-window error counters that raise an alert when a signal crosses its
+/// threshold, then stay quiet for the cooldown.
+pub struct ErrorBudget {
+    rules: AlertRules,
+    windows: Mutex<HashMap<(AlertSignalKind, Option<String>), Window>>,
+}
+
+struct Alert {
+    kind: AlertSignalKind,
+    subject: Option<String>,
+    count: usize,
+}
+
+impl ErrorBudget {
+    pub fn new(rules: AlertRules) -> Self {
+        Self {
+            rules,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn spend(&self, signal: Signal) -> Option<Alert> {
+        let kind = signal.kind();
+        let threshold = self.rules.threshold(kind);
+        if threshold == 0 {
+            return None;
+        }
+
+        let now = Instant::now();
+        let key = (kind, signal.subject());
+        let mut windows = self.windows.lock().expect("error budget lock poisoned");
+        let window = windows.entry(key.clone()).or_default();
+        window.events.push_back(now);
+        prune(&mut window.events, now, self.rules.window);
+
+        let count = window.events.len();
+        let cooling_down = window
+            .last_alert
+            .is_some_and(|(at, _)| now.duration_since(at) < self.rules.cooldown);
+        if count < threshold as usize || cooling_down {
+            return None;
+        }
+        window.last_alert = Some((now, Utc::now()));
+        Some(Alert {
+            kind,
+            subject: key.1,
+            count,
+        })
+    }
+
+    /// Current counts of every signal seen since the server started, busiest first.
+    pub fn status(&self) -> Vec<ErrorBudgetStatus> {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().expect("error budget lock poisoned");
+        let mut statuses: Vec<ErrorBudgetStatus> = windows
+            .iter_mut()
+            .map(|((kind, subject), window)| {
+                prune(&mut window.events, now, self.rules.window);
+                ErrorBudgetStatus {
+                    signal: *kind,
+                    subject: subject.clone(),
+                    count: window.events.len(),
+                    threshold: self.rules.threshold(*kind),
+                    window_secs: self.rules.window.as_secs(),
+                    last_alert_at: window.last_alert.map(|(_, at)| at),
+                }
+            })
+            .collect();
+        statuses.sort_by_key(|status| std::cmp::Reverse(status.count));
+        statuses
+    }
+}
+
+fn prune(events: &mut VecDeque<Instant>, now: Instant, window: Duration) {
+    while events
+        .front()
+        .is_some_and(|at| now.duration_since(*at) > window)
+    {
+        events.pop_front();
+    }
+}
+
+/// Counts `signal` against the error budget and sends an `alert.error_budget`
+/// webhook when it crosses its threshold.
+pub fn record(state: &AppState, signal: Signal) {
+    let Some(alert) = state.error_budget.spend(signal) else {
+        return;
+    };
+    let window_secs = state.error_budget.rules.window.as_secs();
+    let threshold = state.error_budget.rules.threshold(alert.kind);
+    tracing::warn!(
+        "error budget exceeded: {} {} in {window_secs}s{}",
+        alert.count,
+        alert.kind.as_str(),
+        alert
+            .subject
+            .as_deref()
+            .map(|subject| format!(" on {subject}"))
+            .unwrap_or_default()
+    );
+    webhook::broadcast(
+        state,
+        "alert.error_budget",
+        json!({
+            "signal": alert.kind.as_str(),
+            "subject": alert.subject,
+            "count": alert.count,
+            "threshold": threshold,
+            "windowSecs": window_secs,
+            "cooldownSecs": state.error_budget.rules.cooldown.as_secs(),
+            "firedAt": Utc::now(),
+        }),
+    );
+}
+
+/// Method and path with numeric segments replaced by `{id}`, so 5xx responses of the
+/// same route share one budget.
+pub fn route_key(method: &str, path: &str) -> String {
+    let normalized: Vec<&str> = path
+        .split('/')
+        .map(|segment| {
+            if !segment.is_empty() && segment.bytes().all(|byte| byte.is_ascii_digit()) {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect();
+    format!("{method} {}", normalized.join("/"))
+}
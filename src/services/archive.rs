@@ -0,0 +1,501 @@
+//! Immutable copy of every final submission in an external archival sink
+//! (`ARCHIVE_SINK`): an append-only JSONL file, e.g. on a mounted object storage
+//! bucket, or a second SQLite database whose table refuses updates and deletes.
+//!
+//! Outbox pattern: the hand-in and its `archive_outbox` row are written in one
+//! transaction, and after the commit a worker copies pending rows to the sink and marks
+//! them archived. A crash between the two writes repeats the copy, so the sink may hold
+//! a submission twice; readers and `asmctl verify-archive` key records by
+//! `submissionId`. The record is the hand-in as received; grades are left out because
+//! they change on regrading.
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    time::Duration as StdDuration,
+};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, Database, DatabaseConnection,
+    DbBackend, EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+    Statement, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{io::AsyncWriteExt, sync::Notify};
+
+use crate::{
+    dto::ReplicationRole,
+    entities::{archive_outbox, classroom, submission, user},
+    error::AppError,
+    state::AppState,
+};
+
+/// Pending rows copied per pass.
+const BATCH_SIZE: u64 = 100;
+/// How often the worker looks for pending rows it was not woken for, e.g. after a
+/// failed pass or a restart.
+const POLL_INTERVAL: StdDuration = StdDuration::from_secs(30);
+/// Submission ids listed by the verification before it stops counting them out.
+const MAX_LISTED_IDS: usize = 20;
+
+/// Where archived submissions go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveTarget {
+    /// `file:///path/archive.jsonl`: one JSON record per line, opened in append mode.
+    File(PathBuf),
+    /// `sqlite://...`: the `archived_submissions` table of a second database.
+    Database(String),
+}
+
+impl ArchiveTarget {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let url = url.trim();
+        if let Some(path) = url.strip_prefix("file://") {
+            if path.is_empty() {
+                return Err("ARCHIVE_SINK file:// membutuhkan path file".into());
+            }
+            return Ok(Self::File(PathBuf::from(path)));
+        }
+        if url.starts_with("sqlite:") {
+            return Ok(Self::Database(url.to_owned()));
+        }
+        Err(format!(
+            "ARCHIVE_SINK harus diawali file:// atau sqlite:, bukan {url}"
+        ))
+    }
+}
+
+pub struct Archive {
+    pub target: ArchiveTarget,
+    wake: Notify,
+}
+
+impl Archive {
+    pub fn new(target: ArchiveTarget) -> Self {
+        Self {
+            target,
+            wake: Notify::new(),
+        }
+    }
+
+    /// The sink's file on this server's disk: the JSONL file, or the SQLite database
+    /// file of a `sqlite:` sink. `None` for an in-memory database.
+    pub fn local_path(&self) -> Option<PathBuf> {
+        match &self.target {
+            ArchiveTarget::File(path) => Some(path.clone()),
+            ArchiveTarget::Database(url) => {
+                let path = url
+                    .trim_start_matches("sqlite:")
+                    .trim_start_matches("//")
+                    .split('?')
+                    .next()
+                    .unwrap_or_default();
+                (!path.is_empty() && path != ":memory:").then(|| PathBuf::from(path))
+            }
+        }
+    }
+}
+
+/// One line of the JSONL sink, or one row of the database sink.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveRecord {
+    pub submission_id: i32,
+    pub classroom_id: i32,
+    pub classroom_name: String,
+    pub is_exam: bool,
+    pub user_id: i32,
+    pub npm: String,
+    pub task_id: Option<i32>,
+    pub language_id: i32,
+    pub auto_submitted: bool,
+    pub late: bool,
+    pub submitted_at: DateTime<Utc>,
+    /// Hex SHA-256 of `sourceCode`, for comparing copies without the code.
+    pub source_sha256: String,
+    pub source_code: String,
+    pub archived_at: DateTime<Utc>,
+}
+
+/// Queues `submission_id` for the sink; call inside the transaction that stores the
+/// final submission and [`wake`] after it commits. Does nothing without a sink.
+pub async fn enqueue(
+    db: &impl ConnectionTrait,
+    state: &AppState,
+    submission_id: i32,
+) -> Result<(), AppError> {
+    if state.archive.is_none() {
+        return Ok(());
+    }
+    archive_outbox::ActiveModel {
+        submission_id: Set(submission_id),
+        created_at: Set(Utc::now()),
+        archived_at: Set(None),
+        attempts: Set(0),
+        last_error: Set(None),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok(())
+}
+
+/// Tells the worker a committed submission is waiting.
+pub fn wake(state: &AppState) {
+    if let Some(archive) = &state.archive {
+        archive.wake.notify_one();
+    }
+}
+
+/// Starts the worker that copies pending submissions to the sink. A standby keeps its
+/// outbox untouched until it is promoted.
+pub fn spawn(state: AppState) {
+    let Some(archive) = state.archive.clone() else {
+        return;
+    };
+    if state.replication.role() == ReplicationRole::Standby {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut sink_db: Option<DatabaseConnection> = None;
+        loop {
+            match flush(&state, &archive.target, &mut sink_db).await {
+                // A full batch may have more behind it.
+                Ok(copied) if copied as u64 == BATCH_SIZE => continue,
+                Ok(_) => {}
+                Err(err) => tracing::warn!("archiving final submissions failed: {err}"),
+            }
+            let _ = tokio::time::timeout(POLL_INTERVAL, archive.wake.notified()).await;
+        }
+    });
+}
+
+/// Copies one batch of pending rows; returns how many reached the sink.
+async fn flush(
+    state: &AppState,
+    target: &ArchiveTarget,
+    sink_db: &mut Option<DatabaseConnection>,
+) -> Result<usize, AppError> {
+    let pending = archive_outbox::Entity::find()
+        .filter(archive_outbox::Column::ArchivedAt.is_null())
+        .order_by_asc(archive_outbox::Column::Id)
+        .limit(BATCH_SIZE)
+        .all(&state.db)
+        .await?;
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    let now = Utc::now();
+    let records = match records(&state.db, &pending, now).await {
+        Ok(records) => records,
+        Err(err) => {
+            record_failure(&state.db, pending, &err).await;
+            return Err(err);
+        }
+    };
+    let written = match target {
+        ArchiveTarget::File(path) => append_file(path, &records).await,
+        ArchiveTarget::Database(url) => match connect_sink(sink_db, url).await {
+            Ok(db) => insert_rows(db, &records).await,
+            Err(err) => Err(err),
+        },
+    };
+    if let Err(err) = written {
+        // The connection may be the problem; open a fresh one next pass.
+        *sink_db = None;
+        record_failure(&state.db, pending, &err).await;
+        return Err(err);
+    }
+
+    let copied = pending.len();
+    for row in pending {
+        let attempts = row.attempts + 1;
+        let mut row_am = row.into_active_model();
+        row_am.archived_at = Set(Some(now));
+        row_am.attempts = Set(attempts);
+        row_am.last_error = Set(None);
+        row_am.update(&state.db).await?;
+    }
+    Ok(copied)
+}
+
+async fn records(
+    db: &DatabaseConnection,
+    pending: &[archive_outbox::Model],
+    archived_at: DateTime<Utc>,
+) -> Result<Vec<ArchiveRecord>, AppError> {
+    let ids: Vec<i32> = pending.iter().map(|row| row.submission_id).collect();
+    let submissions = submission::Entity::find()
+        .filter(submission::Column::Id.is_in(ids))
+        .find_also_related(user::Entity)
+        .all(db)
+        .await?;
+    let classroom_ids: BTreeSet<i32> = submissions
+        .iter()
+        .map(|(model, _)| model.classroom_id)
+        .collect();
+    let classrooms = classroom::Entity::find()
+        .filter(classroom::Column::Id.is_in(classroom_ids))
+        .all(db)
+        .await?;
+
+    let mut records = Vec::with_capacity(submissions.len());
+    for (model, user_model) in submissions {
+        let classroom_model = classrooms
+            .iter()
+            .find(|classroom_model| classroom_model.id == model.classroom_id);
+        let source_code = model.source_code.into_inner();
+        records.push(ArchiveRecord {
+            submission_id: model.id,
+            classroom_id: model.classroom_id,
+            classroom_name: classroom_model
+                .map(|classroom_model| classroom_model.name.clone())
+                .unwrap_or_default(),
+            is_exam: classroom_model.is_some_and(|classroom_model| classroom_model.is_exam),
+            user_id: model.user_id,
+            npm: user_model
+                .map(|user_model| user_model.npm)
+                .unwrap_or_default(),
+            task_id: model.task_id,
+            language_id: model.language_id,
+            auto_submitted: model.auto_submitted,
+            late: model.late,
+            submitted_at: model.created_at,
+            source_sha256: hex::encode(Sha256::digest(source_code.as_bytes())),
+            source_code,
+            archived_at,
+        });
+    }
+    records.sort_by_key(|record| record.submission_id);
+    Ok(records)
+}
+
+async fn record_failure(
+    db: &DatabaseConnection,
+    pending: Vec<archive_outbox::Model>,
+    err: &AppError,
+) {
+    for row in pending {
+        let row_id = row.id;
+        let attempts = row.attempts + 1;
+        let mut row_am = row.into_active_model();
+        row_am.attempts = Set(attempts);
+        row_am.last_error = Set(Some(err.to_string()));
+        if let Err(err) = row_am.update(db).await {
+            tracing::warn!("failed to record archive failure of outbox row {row_id}: {err}");
+        }
+    }
+}
+
+/// Appends the records and syncs the file before they count as archived.
+async fn append_file(path: &Path, records: &[ArchiveRecord]) -> Result<(), AppError> {
+    let mut lines = Vec::new();
+    for record in records {
+        serde_json::to_writer(&mut lines, record).expect("archive record serializes");
+        lines.push(b'\n');
+    }
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|err| AppError::Internal(format!("cannot create {}: {err}", dir.display())))?;
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .map_err(|err| AppError::Internal(format!("cannot open {}: {err}", path.display())))?;
+    file.write_all(&lines)
+        .await
+        .map_err(|err| AppError::Internal(format!("cannot write {}: {err}", path.display())))?;
+    file.sync_data()
+        .await
+        .map_err(|err| AppError::Internal(format!("cannot sync {}: {err}", path.display())))
+}
+
+async fn connect_sink<'a>(
+    sink_db: &'a mut Option<DatabaseConnection>,
+    url: &str,
+) -> Result<&'a DatabaseConnection, AppError> {
+    if sink_db.is_none() {
+        let db = Database::connect(url).await?;
+        prepare_sink(&db).await?;
+        *sink_db = Some(db);
+    }
+    Ok(sink_db.as_ref().expect("sink connection was just opened"))
+}
+
+/// Creates the sink table, with triggers that refuse changing or removing a record.
+async fn prepare_sink(db: &impl ConnectionTrait) -> Result<(), AppError> {
+    for sql in [
+        "CREATE TABLE IF NOT EXISTS archived_submissions (
+            submission_id INTEGER PRIMARY KEY,
+            source_sha256 TEXT NOT NULL,
+            record TEXT NOT NULL,
+            archived_at TEXT NOT NULL
+        )",
+        "CREATE TRIGGER IF NOT EXISTS archived_submissions_no_update
+            BEFORE UPDATE ON archived_submissions
+            BEGIN SELECT RAISE(ABORT, 'archived submissions are immutable'); END",
+        "CREATE TRIGGER IF NOT EXISTS archived_submissions_no_delete
+            BEFORE DELETE ON archived_submissions
+            BEGIN SELECT RAISE(ABORT, 'archived submissions are immutable'); END",
+    ] {
+        db.execute(Statement::from_string(DbBackend::Sqlite, sql))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Inserts the records in one transaction; a record already in the sink is kept as it is.
+async fn insert_rows(db: &DatabaseConnection, records: &[ArchiveRecord]) -> Result<(), AppError> {
+    let txn = db.begin().await?;
+    for record in records {
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT OR IGNORE INTO archived_submissions
+                (submission_id, source_sha256, record, archived_at)
+                VALUES (?, ?, ?, ?)",
+            [
+                record.submission_id.into(),
+                record.source_sha256.clone().into(),
+                serde_json::to_string(record)
+                    .expect("archive record serializes")
+                    .into(),
+                record.archived_at.to_rfc3339().into(),
+            ],
+        ))
+        .await?;
+    }
+    txn.commit().await?;
+    Ok(())
+}
+
+/// Counts compared by `asmctl verify-archive`.
+#[derive(Debug, Default)]
+pub struct ArchiveReport {
+    pub final_submissions: u64,
+    /// Final submissions from before the sink was configured, with no outbox row.
+    pub untracked: u64,
+    pub pending: u64,
+    /// Pending rows whose last copy attempt failed.
+    pub failing: u64,
+    pub archived: u64,
+    /// Distinct submissions found in the sink.
+    pub in_sink: u64,
+    /// Records in the sink beyond the first for their submission.
+    pub duplicates: u64,
+    /// Marked archived but absent from the sink.
+    pub missing: Vec<i32>,
+    pub missing_count: usize,
+}
+
+impl ArchiveReport {
+    pub fn consistent(&self) -> bool {
+        self.missing_count == 0
+    }
+}
+
+/// Compares the outbox with what the sink holds.
+pub async fn verify(
+    db: &DatabaseConnection,
+    target: &ArchiveTarget,
+) -> Result<ArchiveReport, AppError> {
+    let final_submissions = submission::Entity::find()
+        .filter(submission::Column::IsFinal.eq(true))
+        .count(db)
+        .await?;
+    let outbox: Vec<(i32, Option<DateTime<Utc>>, Option<String>)> = archive_outbox::Entity::find()
+        .select_only()
+        .column(archive_outbox::Column::SubmissionId)
+        .column(archive_outbox::Column::ArchivedAt)
+        .column(archive_outbox::Column::LastError)
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    let (sink_ids, duplicates) = sink_ids(target).await?;
+    let mut report = ArchiveReport {
+        final_submissions,
+        untracked: final_submissions.saturating_sub(outbox.len() as u64),
+        in_sink: sink_ids.len() as u64,
+        duplicates,
+        ..Default::default()
+    };
+    for (submission_id, archived_at, last_error) in outbox {
+        match archived_at {
+            Some(_) => {
+                report.archived += 1;
+                if !sink_ids.contains(&submission_id) {
+                    report.missing_count += 1;
+                    if report.missing.len() < MAX_LISTED_IDS {
+                        report.missing.push(submission_id);
+                    }
+                }
+            }
+            None => {
+                report.pending += 1;
+                if last_error.is_some() {
+                    report.failing += 1;
+                }
+            }
+        }
+    }
+    report.missing.sort_unstable();
+    Ok(report)
+}
+
+/// Distinct submission ids in the sink, and how many records repeat one.
+async fn sink_ids(target: &ArchiveTarget) -> Result<(BTreeSet<i32>, u64), AppError> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Key {
+        submission_id: i32,
+    }
+
+    let mut ids = BTreeSet::new();
+    let mut duplicates = 0;
+    match target {
+        ArchiveTarget::File(path) => {
+            let contents = match tokio::fs::read_to_string(path).await {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+                Err(err) => {
+                    return Err(AppError::Internal(format!(
+                        "cannot read {}: {err}",
+                        path.display()
+                    )));
+                }
+            };
+            for (index, line) in contents.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let key: Key = serde_json::from_str(line).map_err(|err| {
+                    AppError::Internal(format!("{} line {}: {err}", path.display(), index + 1))
+                })?;
+                if !ids.insert(key.submission_id) {
+                    duplicates += 1;
+                }
+            }
+        }
+        ArchiveTarget::Database(url) => {
+            let db = Database::connect(url.as_str()).await?;
+            prepare_sink(&db).await?;
+            let rows = db
+                .query_all(Statement::from_string(
+                    DbBackend::Sqlite,
+                    "SELECT submission_id FROM archived_submissions",
+                ))
+                .await?;
+            for row in rows {
+                ids.insert(row.try_get::<i32>("", "submission_id")?);
+            }
+        }
+    }
+    Ok((ids, duplicates))
+}
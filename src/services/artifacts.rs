@@ -0,0 +1,75 @@
+//! Downloadable bundle of one submission: the source, Judge0's compile output, the
+//! program's stdout and stderr, and the run's metadata. For assembly courses the
+//! assembler and linker messages are as much a part of the work as the code.
+
+use std::io::Write;
+
+use zip::{ZipWriter, write::FileOptions};
+
+use crate::{
+    dto::SubmissionArtifactMetadata,
+    entities::{submission, user},
+    error::AppError,
+    services::highlight,
+};
+
+/// Builds the ZIP for `submission_model`. Output streams Judge0 did not produce are
+/// left out rather than stored as empty files.
+pub fn bundle(
+    submission_model: &submission::Model,
+    user_model: &user::Model,
+) -> Result<Vec<u8>, AppError> {
+    let extension = highlight::extension_for(submission_model.language_id).unwrap_or("txt");
+    let source_file = format!("source.{extension}");
+    let metadata = SubmissionArtifactMetadata {
+        submission_id: submission_model.id,
+        classroom_id: submission_model.classroom_id,
+        user_id: submission_model.user_id,
+        npm: user_model.npm.clone(),
+        name: user_model.name.clone(),
+        task_id: submission_model.task_id,
+        language_id: submission_model.language_id,
+        source_file: source_file.clone(),
+        is_final: submission_model.is_final,
+        auto_submitted: submission_model.auto_submitted,
+        late: submission_model.late,
+        status: submission_model.status.clone(),
+        exit_code: submission_model.exit_code,
+        exit_signal: submission_model.exit_signal,
+        time: submission_model.time.clone(),
+        memory: submission_model.memory,
+        passed_cases: submission_model.passed_cases,
+        total_cases: submission_model.total_cases,
+        score: submission_model.score,
+        max_score: submission_model.max_score,
+        grading_error: submission_model.grading_error.clone(),
+        created_at: submission_model.created_at,
+        graded_at: submission_model.graded_at,
+    };
+    let metadata = serde_json::to_vec_pretty(&metadata)
+        .map_err(|err| AppError::Internal(format!("artifact metadata: {err}")))?;
+
+    let mut files: Vec<(&str, &[u8])> = vec![
+        (&source_file, submission_model.source_code.as_bytes()),
+        ("metadata.json", &metadata),
+    ];
+    for (name, contents) in [
+        ("compile_output.txt", &submission_model.compile_output),
+        ("stdout.txt", &submission_model.stdout),
+        ("stderr.txt", &submission_model.stderr),
+    ] {
+        if let Some(contents) = contents {
+            files.push((name, contents.as_bytes()));
+        }
+    }
+
+    let zip_error = |err: zip::result::ZipError| AppError::Internal(format!("zip: {err}"));
+    let io_error = |err: std::io::Error| AppError::Internal(format!("zip: {err}"));
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, contents) in files {
+        zip.start_file(name, options).map_err(zip_error)?;
+        zip.write_all(contents).map_err(io_error)?;
+    }
+    Ok(zip.finish().map_err(zip_error)?.into_inner())
+}
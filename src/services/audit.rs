@@ -0,0 +1,109 @@
+use axum::http::Method;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, DatabaseConnection};
+
+use crate::{
+    dto::{AuditKind, ClassroomRowCounts, PurgeReceipt},
+    entities::audit_log,
+};
+
+/// Records a successful sign-in. Failures are logged, never surfaced to the caller.
+pub async fn record_login(
+    db: &DatabaseConnection,
+    account_id: i32,
+    method: &str,
+    classroom_id: Option<i32>,
+) {
+    insert(
+        db,
+        audit_log::ActiveModel {
+            account_id: Set(account_id),
+            kind: Set(AuditKind::Login.as_str().to_owned()),
+            detail: Set(method.to_owned()),
+            classroom_id: Set(classroom_id),
+            status: Set(None),
+            request_id: Set(None),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        },
+    )
+    .await;
+}
+
+/// Records a state-changing request made with a staff token, whatever its outcome.
+pub async fn record_request(
+    db: &DatabaseConnection,
+    account_id: i32,
+    method: &Method,
+    path: &str,
+    status: u16,
+    request_id: Option<String>,
+) {
+    insert(
+        db,
+        audit_log::ActiveModel {
+            account_id: Set(account_id),
+            kind: Set(AuditKind::Request.as_str().to_owned()),
+            detail: Set(format!("{method} {path}")),
+            classroom_id: Set(None),
+            status: Set(Some(i32::from(status))),
+            request_id: Set(request_id),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        },
+    )
+    .await;
+}
+
+/// Records a permanent classroom deletion and what it removed.
+pub async fn record_deletion(
+    db: &DatabaseConnection,
+    account_id: i32,
+    classroom_id: i32,
+    classroom_name: &str,
+    counts: &ClassroomRowCounts,
+) {
+    insert(
+        db,
+        audit_log::ActiveModel {
+            account_id: Set(account_id),
+            kind: Set(AuditKind::Deletion.as_str().to_owned()),
+            detail: Set(format!(
+                "classroom {classroom_id} \"{classroom_name}\": {counts}"
+            )),
+            classroom_id: Set(Some(classroom_id)),
+            status: Set(None),
+            request_id: Set(None),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        },
+    )
+    .await;
+}
+
+/// Records a student purge by its receipt; the entry names no student.
+pub async fn record_purge(db: &DatabaseConnection, receipt: &PurgeReceipt) {
+    insert(
+        db,
+        audit_log::ActiveModel {
+            account_id: Set(receipt.purged_by),
+            kind: Set(AuditKind::Purge.as_str().to_owned()),
+            detail: Set(format!(
+                "receipt {}: {}",
+                receipt.receipt_id, receipt.counts
+            )),
+            classroom_id: Set(None),
+            status: Set(None),
+            request_id: Set(None),
+            created_at: Set(receipt.purged_at),
+            ..Default::default()
+        },
+    )
+    .await;
+}
+
+async fn insert(db: &DatabaseConnection, model: audit_log::ActiveModel) {
+    if let Err(err) = model.insert(db).await {
+        tracing::warn!("failed to write audit log: {err}");
+    }
+}
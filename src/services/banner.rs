@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use sea_orm::{ConnectionTrait, EntityTrait};
+
+use crate::{entities::banner, error::AppError};
+
+/// Id of the single banner row.
+pub const BANNER_ID: i32 = 1;
+
+/// The stored banner, whether or not it is currently on display.
+pub async fn load(db: &impl ConnectionTrait) -> Result<Option<banner::Model>, AppError> {
+    Ok(banner::Entity::find_by_id(BANNER_ID).one(db).await?)
+}
+
+/// The banner clients should show at `now`: stored and inside its display window.
+pub async fn current(
+    db: &impl ConnectionTrait,
+    now: DateTime<Utc>,
+) -> Result<Option<banner::Model>, AppError> {
+    Ok(load(db).await?.filter(|model| {
+        model.starts_at.is_none_or(|starts_at| starts_at <= now)
+            && model.ends_at.is_none_or(|ends_at| now < ends_at)
+    }))
+}
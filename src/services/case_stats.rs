@@ -0,0 +1,165 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, FromQueryResult, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait, TransactionTrait, sea_query::JoinType,
+};
+
+use crate::{
+    dto::{TestCaseFlag, TestCaseStats},
+    entities::{submission, submission_case_result, task, test_case},
+    error::AppError,
+    services::test_runner::CaseOutcome,
+};
+
+/// Students who must have run a case before it is flagged as never or always passed.
+pub const DEFAULT_MIN_STUDENTS: usize = 3;
+
+/// Stores the per-case outcomes of a graded submission, replacing those of an earlier
+/// grading.
+pub async fn record(
+    db: &DatabaseConnection,
+    submission_id: i32,
+    outcomes: &[CaseOutcome],
+) -> Result<(), AppError> {
+    let now = Utc::now();
+    let txn = db.begin().await?;
+    submission_case_result::Entity::delete_many()
+        .filter(submission_case_result::Column::SubmissionId.eq(submission_id))
+        .exec(&txn)
+        .await?;
+    if !outcomes.is_empty() {
+        submission_case_result::Entity::insert_many(outcomes.iter().map(|outcome| {
+            submission_case_result::ActiveModel {
+                submission_id: Set(submission_id),
+                test_case_id: Set(outcome.test_case.id),
+                passed: Set(outcome.passed),
+                status: Set(outcome
+                    .response
+                    .status
+                    .as_ref()
+                    .map(|status| status.description.clone())),
+                created_at: Set(now),
+                ..Default::default()
+            }
+        }))
+        .exec_without_returning(&txn)
+        .await?;
+    }
+    txn.commit().await?;
+    Ok(())
+}
+
+#[derive(FromQueryResult)]
+struct ResultRow {
+    test_case_id: i32,
+    passed: bool,
+    status: Option<String>,
+    user_id: i32,
+}
+
+#[derive(Default)]
+struct Tally {
+    runs: u64,
+    passed_runs: u64,
+    attempted: HashSet<i32>,
+    passed: HashSet<i32>,
+    failure_statuses: BTreeMap<String, u64>,
+}
+
+/// Pass/fail counts of every test case in the classroom, in task order, then case
+/// order. Cases run by at least `min_students` students are flagged when nobody or
+/// everybody passed them.
+pub async fn summarize(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+    min_students: usize,
+) -> Result<Vec<TestCaseStats>, AppError> {
+    let tasks = task::Entity::find()
+        .filter(task::Column::ClassroomId.eq(classroom_id))
+        .order_by_asc(task::Column::Position)
+        .order_by_asc(task::Column::Id)
+        .all(db)
+        .await?;
+    let mut cases = test_case::Entity::find()
+        .filter(test_case::Column::TaskId.is_in(tasks.iter().map(|task_model| task_model.id)))
+        .order_by_asc(test_case::Column::Position)
+        .order_by_asc(test_case::Column::Id)
+        .all(db)
+        .await?;
+    cases.sort_by_key(|case| {
+        tasks
+            .iter()
+            .position(|task_model| task_model.id == case.task_id)
+    });
+
+    let rows = submission_case_result::Entity::find()
+        .select_only()
+        .column(submission_case_result::Column::TestCaseId)
+        .column(submission_case_result::Column::Passed)
+        .column(submission_case_result::Column::Status)
+        .column(submission::Column::UserId)
+        .join(
+            JoinType::InnerJoin,
+            submission_case_result::Relation::Submission.def(),
+        )
+        .filter(submission::Column::ClassroomId.eq(classroom_id))
+        .into_model::<ResultRow>()
+        .all(db)
+        .await?;
+
+    let mut tallies: HashMap<i32, Tally> = HashMap::new();
+    for row in rows {
+        let tally = tallies.entry(row.test_case_id).or_default();
+        tally.runs += 1;
+        tally.attempted.insert(row.user_id);
+        if row.passed {
+            tally.passed_runs += 1;
+            tally.passed.insert(row.user_id);
+        } else {
+            *tally
+                .failure_statuses
+                .entry(row.status.unwrap_or_else(|| "Unknown".into()))
+                .or_default() += 1;
+        }
+    }
+
+    Ok(cases
+        .into_iter()
+        .map(|case| {
+            let tally = tallies.remove(&case.id).unwrap_or_default();
+            let students_attempted = tally.attempted.len();
+            let students_passed = tally.passed.len();
+            let flag = if students_attempted < min_students.max(1) {
+                None
+            } else if students_passed == 0 {
+                Some(TestCaseFlag::NeverPassed)
+            } else if students_passed == students_attempted {
+                Some(TestCaseFlag::AlwaysPassed)
+            } else {
+                None
+            };
+            let task_title = tasks
+                .iter()
+                .find(|task_model| task_model.id == case.task_id)
+                .map(|task_model| task_model.title.clone())
+                .unwrap_or_default();
+            TestCaseStats {
+                test_case_id: case.id,
+                task_id: case.task_id,
+                task_title,
+                position: case.position,
+                hidden: case.hidden,
+                points: case.points,
+                runs: tally.runs,
+                passed_runs: tally.passed_runs,
+                pass_rate: (tally.runs > 0).then(|| tally.passed_runs as f64 / tally.runs as f64),
+                students_attempted,
+                students_passed,
+                failure_statuses: tally.failure_statuses,
+                flag,
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,77 @@
+//! Fault injection for failure rehearsals before exam day, compiled only with the
+//! `chaos` feature. Admins set how often Judge0 calls hang until they time out, how
+//! often API requests fail as if the database did, and how often they are slowed
+//! down. Every rate starts at zero, so a chaos build behaves normally until told
+//! otherwise.
+
+use std::{future::Future, sync::RwLock, time::Duration};
+
+use sea_orm::{DbErr, RuntimeErr};
+
+use crate::{dto::ChaosConfig, error::AppError};
+
+/// Longest delay `slowResponseMs` may ask for.
+pub const MAX_SLOW_RESPONSE_MS: u64 = 60_000;
+
+#[derive(Debug, Default)]
+pub struct Chaos {
+    config: RwLock<ChaosConfig>,
+}
+
+impl Chaos {
+    pub fn config(&self) -> ChaosConfig {
+        *self.config.read().expect("chaos config lock poisoned")
+    }
+
+    pub fn set(&self, config: ChaosConfig) -> Result<(), AppError> {
+        for (field, rate) in [
+            ("judge0TimeoutRate", config.judge0_timeout_rate),
+            ("dbErrorRate", config.db_error_rate),
+            ("slowResponseRate", config.slow_response_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(AppError::BadRequest(format!(
+                    "{field} must be between 0 and 1"
+                )));
+            }
+        }
+        if config.slow_response_ms > MAX_SLOW_RESPONSE_MS {
+            return Err(AppError::BadRequest(format!(
+                "slowResponseMs must be at most {MAX_SLOW_RESPONSE_MS}"
+            )));
+        }
+        *self.config.write().expect("chaos config lock poisoned") = config;
+        Ok(())
+    }
+
+    /// Runs the Judge0 call `call`, or, at the configured rate, never finishes so the
+    /// caller's timeout fires as it would against a stuck instance.
+    pub async fn judge0<F: Future>(&self, call: F) -> F::Output {
+        if roll(self.config().judge0_timeout_rate) {
+            tracing::info!("chaos: holding a Judge0 call until it times out");
+            std::future::pending::<()>().await;
+        }
+        call.await
+    }
+
+    /// Error to fail the current request with, at the configured rate.
+    pub fn db_error(&self) -> Option<DbErr> {
+        roll(self.config().db_error_rate).then(|| {
+            tracing::info!("chaos: failing a request with a database error");
+            DbErr::Conn(RuntimeErr::Internal(
+                "chaos: injected database error".into(),
+            ))
+        })
+    }
+
+    /// How long to hold the current request back, at the configured rate.
+    pub fn delay(&self) -> Option<Duration> {
+        let config = self.config();
+        (config.slow_response_ms > 0 && roll(config.slow_response_rate))
+            .then(|| Duration::from_millis(config.slow_response_ms))
+    }
+}
+
+fn roll(rate: f64) -> bool {
+    rate > 0.0 && rand::random::<f64>() < rate
+}
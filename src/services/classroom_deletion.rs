@@ -0,0 +1,120 @@
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait, QueryFilter, QuerySelect,
+};
+
+use crate::{
+    dto::ClassroomRowCounts,
+    entities::{
+        classroom_event, export_job, integrity_event, message, submission, task, test_case, user,
+    },
+    error::AppError,
+    services::signing,
+    state::AppState,
+};
+
+/// How long a delete-intent token stays valid.
+pub const INTENT_TTL: Duration = Duration::minutes(5);
+
+/// Counts what deleting classroom `id` would remove through the cascades.
+pub async fn row_counts(
+    db: &impl ConnectionTrait,
+    id: i32,
+) -> Result<ClassroomRowCounts, AppError> {
+    let task_ids: Vec<i32> = task::Entity::find()
+        .filter(task::Column::ClassroomId.eq(id))
+        .select_only()
+        .column(task::Column::Id)
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    Ok(ClassroomRowCounts {
+        users: user::Entity::find()
+            .filter(user::Column::ClassroomId.eq(id))
+            .count(db)
+            .await?,
+        tasks: task_ids.len() as u64,
+        test_cases: test_case::Entity::find()
+            .filter(test_case::Column::TaskId.is_in(task_ids))
+            .count(db)
+            .await?,
+        submissions: submission::Entity::find()
+            .filter(submission::Column::ClassroomId.eq(id))
+            .count(db)
+            .await?,
+        messages: message::Entity::find()
+            .filter(message::Column::ClassroomId.eq(id))
+            .count(db)
+            .await?,
+        events: classroom_event::Entity::find()
+            .filter(classroom_event::Column::ClassroomId.eq(id))
+            .count(db)
+            .await?,
+        integrity_events: integrity_event::Entity::find()
+            .filter(integrity_event::Column::ClassroomId.eq(id))
+            .count(db)
+            .await?,
+        export_jobs: export_job::Entity::find()
+            .filter(export_job::Column::ClassroomId.eq(id))
+            .count(db)
+            .await?,
+    })
+}
+
+/// Token confirming that `account_id` means to delete classroom `classroom_id`:
+/// `<expires>.<signature>`, valid for [`INTENT_TTL`].
+pub fn issue_token(
+    state: &AppState,
+    classroom_id: i32,
+    account_id: i32,
+    now: DateTime<Utc>,
+) -> (String, DateTime<Utc>) {
+    let expires_at = now + INTENT_TTL;
+    let expires = expires_at.timestamp();
+    (
+        format!(
+            "{expires}.{}",
+            signature(state, classroom_id, account_id, expires)
+        ),
+        expires_at,
+    )
+}
+
+fn signature(state: &AppState, classroom_id: i32, account_id: i32, expires: i64) -> String {
+    hex::encode(
+        state.secrets.keyed_hash(
+            format!("classroom-delete:{classroom_id}:{account_id}:{expires}").as_bytes(),
+        ),
+    )
+}
+
+/// Checks a token from [`issue_token`] for the same classroom and account.
+pub fn verify_token(
+    state: &AppState,
+    classroom_id: i32,
+    account_id: i32,
+    token: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    let Some(token) = token.filter(|token| !token.is_empty()) else {
+        return Err(AppError::ConfirmationRequired(
+            "request a confirm_token from POST /api/classrooms/{id}/delete-intent first".into(),
+        ));
+    };
+    let valid = token.split_once('.').is_some_and(|(expires, given)| {
+        expires.parse::<i64>().is_ok_and(|expires| {
+            now.timestamp() <= expires
+                && signing::constant_time_eq(
+                    &signature(state, classroom_id, account_id, expires),
+                    given,
+                )
+        })
+    });
+    if !valid {
+        return Err(AppError::ConfirmationRequired(
+            "confirm_token is invalid or has expired".into(),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::{
+    dto::{ApiVersion, ClientConfigResponse, ClientFeatures, ClientLimits},
+    routes::run_config,
+    services::{client_errors, field_limits::FieldLimits, grading, language_map, seating},
+    state::AppState,
+};
+
+/// Settings of the deployment, with `field_limits` as the character limits to report.
+pub async fn build(state: &AppState, field_limits: FieldLimits) -> ClientConfigResponse {
+    ClientConfigResponse {
+        api_version: ApiVersion::V2.as_str().to_owned(),
+        features: ClientFeatures {
+            passkeys: state.webauthn.is_some(),
+            account_email: state.account_email_enabled,
+            account_approval: state.account_approval_required,
+            client_error_sample_rate: state.client_error_sample_rate,
+        },
+        languages: language_map::languages(state).await,
+        default_language_id: grading::DEFAULT_LANGUAGE_ID,
+        limits: ClientLimits {
+            max_stdin_bytes: run_config::MAX_STDIN_BYTES,
+            max_command_line_arguments_bytes: run_config::MAX_ARGUMENTS_BYTES,
+            max_client_error_report_bytes: client_errors::MAX_REPORT_BYTES,
+            output_limit_kb: state.output_limit_kb,
+            max_seat_grid_size: seating::MAX_GRID_SIZE,
+            max_code_chars: field_limits.code_chars,
+            max_description_chars: field_limits.description_chars,
+        },
+        branding: state.branding.as_ref().clone(),
+    }
+}
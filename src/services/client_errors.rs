@@ -0,0 +1,95 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+use crate::{
+    dto::{ClientErrorReport, ErrorSource},
+    entities::client_error,
+    error::AppError,
+    services::output,
+};
+
+/// Largest request body accepted by the intake endpoint.
+pub const MAX_REPORT_BYTES: usize = 32 * 1024;
+const MAX_MESSAGE_BYTES: usize = 2 * 1024;
+const MAX_STACK_BYTES: usize = 8 * 1024;
+const MAX_URL_BYTES: usize = 1024;
+const MAX_CONTEXT_BYTES: usize = 8 * 1024;
+const MAX_SHORT_FIELD_BYTES: usize = 128;
+
+/// Stores a frontend report unless it is sampled out. Reports that name a backend
+/// request are always kept, since those are the ones worth correlating.
+pub async fn record_report(
+    db: &DatabaseConnection,
+    report: ClientErrorReport,
+    user_agent: Option<String>,
+    sample_rate: f64,
+) -> Result<bool, AppError> {
+    if report.request_id.is_none() && rand::random::<f64>() >= sample_rate {
+        return Ok(false);
+    }
+
+    let context = report
+        .context
+        .map(|context| context.to_string())
+        .filter(|context| context.len() <= MAX_CONTEXT_BYTES);
+    client_error::ActiveModel {
+        source: sea_orm::ActiveValue::Set(ErrorSource::Client.as_str().to_owned()),
+        kind: sea_orm::ActiveValue::Set(report.kind.as_str().to_owned()),
+        message: sea_orm::ActiveValue::Set(
+            capped(Some(report.message), MAX_MESSAGE_BYTES).unwrap_or_default(),
+        ),
+        stack: sea_orm::ActiveValue::Set(capped(report.stack, MAX_STACK_BYTES)),
+        url: sea_orm::ActiveValue::Set(capped(report.url, MAX_URL_BYTES)),
+        method: sea_orm::ActiveValue::Set(capped(report.method, MAX_SHORT_FIELD_BYTES)),
+        status: sea_orm::ActiveValue::Set(report.status),
+        request_id: sea_orm::ActiveValue::Set(capped(report.request_id, MAX_SHORT_FIELD_BYTES)),
+        classroom_id: sea_orm::ActiveValue::Set(report.classroom_id),
+        npm: sea_orm::ActiveValue::Set(capped(report.npm, MAX_SHORT_FIELD_BYTES)),
+        user_agent: sea_orm::ActiveValue::Set(capped(user_agent, MAX_URL_BYTES)),
+        context: sea_orm::ActiveValue::Set(context),
+        created_at: sea_orm::ActiveValue::Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok(true)
+}
+
+/// Stores a 5xx response next to the frontend reports under the same request id.
+pub async fn record_server_error(
+    db: &DatabaseConnection,
+    request_id: &str,
+    method: &str,
+    path: &str,
+    status: u16,
+) -> Result<(), AppError> {
+    client_error::ActiveModel {
+        source: sea_orm::ActiveValue::Set(ErrorSource::Server.as_str().to_owned()),
+        kind: sea_orm::ActiveValue::Set("response".into()),
+        message: sea_orm::ActiveValue::Set(format!("{method} {path} returned {status}")),
+        url: sea_orm::ActiveValue::Set(capped(Some(path.to_owned()), MAX_URL_BYTES)),
+        method: sea_orm::ActiveValue::Set(Some(method.to_owned())),
+        status: sea_orm::ActiveValue::Set(Some(i32::from(status))),
+        request_id: sea_orm::ActiveValue::Set(Some(request_id.to_owned())),
+        created_at: sea_orm::ActiveValue::Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok(())
+}
+
+/// Deletes errors recorded more than `retention_days` ago.
+pub async fn prune(db: &DatabaseConnection, retention_days: u32) -> Result<u64, AppError> {
+    let cutoff = Utc::now() - chrono::Duration::days(i64::from(retention_days));
+    let result = client_error::Entity::delete_many()
+        .filter(client_error::Column::CreatedAt.lt(cutoff))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+fn capped(mut value: Option<String>, max_bytes: usize) -> Option<String> {
+    output::truncate(&mut value, max_bytes);
+    value
+}
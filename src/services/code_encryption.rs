@@ -0,0 +1,277 @@
+use std::sync::OnceLock;
+
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, IdenStatic, QueryFilter, QueryOrder, QuerySelect,
+    sea_query::Expr,
+};
+
+use crate::{
+    entities::{sealed_code::SealedCode, submission, user},
+    error::AppError,
+    services::secret_box::SecretBox,
+};
+
+/// Starts stored code sealed by [`CodeKeyRing`]: `$code$<key id>$<sealed>`. Rows
+/// without it are legacy plaintext and are read as is.
+const ENVELOPE_PREFIX: &str = "$code$";
+/// Length of [`SecretBox::key_id`]: four bytes in hex.
+const KEY_ID_LEN: usize = 8;
+
+/// Rows re-encrypted per query by [`reencrypt_all`].
+const REENCRYPT_BATCH: u64 = 500;
+
+/// Keys for student code at rest. New writes use the current key; previous keys are
+/// only used to read rows sealed before a rotation.
+pub struct CodeKeyRing {
+    current: Option<SecretBox>,
+    previous: Vec<SecretBox>,
+}
+
+impl CodeKeyRing {
+    pub fn new(current: Option<&str>, previous: &[String]) -> Self {
+        Self {
+            current: current.map(SecretBox::new),
+            previous: previous.iter().map(|key| SecretBox::new(key)).collect(),
+        }
+    }
+
+    /// Key id new writes are sealed with; `None` when code is stored in plaintext.
+    pub fn current_key_id(&self) -> Option<&str> {
+        self.current.as_ref().map(SecretBox::key_id)
+    }
+
+    pub fn seal(&self, plaintext: &str) -> String {
+        match &self.current {
+            Some(secret_box) => format!(
+                "{ENVELOPE_PREFIX}{}${}",
+                secret_box.key_id(),
+                secret_box.seal(plaintext)
+            ),
+            None => plaintext.to_owned(),
+        }
+    }
+
+    pub fn open(&self, stored: &str) -> Result<String, AppError> {
+        let Some(envelope) = stored.strip_prefix(ENVELOPE_PREFIX) else {
+            return Ok(stored.to_owned());
+        };
+        let (key_id, sealed) = envelope
+            .split_once('$')
+            .ok_or_else(|| AppError::Internal("stored code envelope is malformed".into()))?;
+        let secret_box = self
+            .current
+            .iter()
+            .chain(&self.previous)
+            .find(|secret_box| secret_box.key_id() == key_id)
+            .ok_or_else(|| {
+                AppError::Internal(format!(
+                    "stored code is encrypted with unknown key {key_id}; \
+                     add it to CODE_ENCRYPTION_PREVIOUS_KEYS"
+                ))
+            })?;
+        secret_box.open(sealed)
+    }
+
+    fn knows(&self, key_id: &str) -> bool {
+        self.current
+            .iter()
+            .chain(&self.previous)
+            .any(|secret_box| secret_box.key_id() == key_id)
+    }
+
+    /// Whether `stored` is already in the form [`seal`](Self::seal) writes now.
+    fn is_current(&self, stored: &str) -> bool {
+        sealed_key_id(stored) == self.current_key_id()
+    }
+}
+
+/// Id of the key `stored` code was sealed with; `None` for plaintext.
+pub fn sealed_key_id(stored: &str) -> Option<&str> {
+    stored
+        .strip_prefix(ENVELOPE_PREFIX)
+        .and_then(|envelope| envelope.split_once('$'))
+        .map(|(key_id, _)| key_id)
+}
+
+static KEY_RING: OnceLock<CodeKeyRing> = OnceLock::new();
+
+/// Installs the keys used by [`SealedCode`] columns. Must run before the database is
+/// first read; without it code is stored in plaintext.
+pub fn install(key_ring: CodeKeyRing) {
+    if KEY_RING.set(key_ring).is_err() {
+        tracing::warn!("code encryption keys were already installed; keeping the first set");
+    }
+}
+
+pub fn key_ring() -> &'static CodeKeyRing {
+    KEY_RING.get_or_init(|| CodeKeyRing::new(None, &[]))
+}
+
+/// Fails when stored code is sealed with a key that is not configured, so a missing
+/// key is reported at startup instead of as errors on every read.
+pub async fn check_keys(db: &DatabaseConnection) -> Result<(), AppError> {
+    let key_ring = key_ring();
+    let mut key_ids = stored_key_ids::<user::Entity>(db, user::Column::Code).await?;
+    key_ids.extend(stored_key_ids::<submission::Entity>(db, submission::Column::SourceCode).await?);
+    key_ids.sort_unstable();
+    key_ids.dedup();
+
+    let unknown: Vec<_> = key_ids
+        .iter()
+        .filter(|key_id| !key_ring.knows(key_id))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(AppError::Internal(format!(
+            "stored code is encrypted with key(s) {unknown:?} that are not configured; \
+             set CODE_ENCRYPTION_KEY or CODE_ENCRYPTION_PREVIOUS_KEYS"
+        )));
+    }
+    match key_ring.current_key_id() {
+        Some(key_id) => tracing::info!("student code is encrypted at rest with key {key_id}"),
+        None if !key_ids.is_empty() => tracing::warn!(
+            "CODE_ENCRYPTION_KEY is not set; new code is stored in plaintext next to encrypted rows"
+        ),
+        None => {}
+    }
+    Ok(())
+}
+
+async fn stored_key_ids<E: EntityTrait>(
+    db: &DatabaseConnection,
+    code: E::Column,
+) -> Result<Vec<String>, AppError> {
+    let key_id = Expr::cust(format!(
+        "substr(\"{}\", {}, {KEY_ID_LEN})",
+        code.as_str(),
+        ENVELOPE_PREFIX.len() + 1
+    ));
+    Ok(E::find()
+        .select_only()
+        .column_as(key_id, "key_id")
+        .filter(code.starts_with(ENVELOPE_PREFIX))
+        .distinct()
+        .into_tuple()
+        .all(db)
+        .await?)
+}
+
+/// Number of rows rewritten by [`reencrypt_all`].
+pub struct ReencryptReport {
+    pub users: u64,
+    pub submissions: u64,
+}
+
+/// Rewrites every stored roster code and submission that is not sealed with the
+/// current key: plaintext rows are encrypted, rows under a previous key are
+/// re-encrypted, and with no current key everything is decrypted back to plaintext.
+pub async fn reencrypt_all(db: &DatabaseConnection) -> Result<ReencryptReport, AppError> {
+    let users = reencrypt_column::<user::Entity>(db, user::Column::Id, user::Column::Code).await?;
+    let submissions = reencrypt_column::<submission::Entity>(
+        db,
+        submission::Column::Id,
+        submission::Column::SourceCode,
+    )
+    .await?;
+    Ok(ReencryptReport { users, submissions })
+}
+
+async fn reencrypt_column<E: EntityTrait>(
+    db: &DatabaseConnection,
+    id: E::Column,
+    code: E::Column,
+) -> Result<u64, AppError> {
+    let key_ring = key_ring();
+    let mut after = 0;
+    let mut rewritten = 0;
+    loop {
+        // Selected as raw strings so the envelope stays visible.
+        let rows: Vec<(i32, String)> = E::find()
+            .select_only()
+            .column(id)
+            .column(code)
+            .filter(id.gt(after))
+            .order_by_asc(id)
+            .limit(REENCRYPT_BATCH)
+            .into_tuple()
+            .all(db)
+            .await?;
+        let Some(&(last, _)) = rows.last() else {
+            return Ok(rewritten);
+        };
+        for (row_id, stored) in rows {
+            if key_ring.is_current(&stored) {
+                continue;
+            }
+            let plaintext = key_ring.open(&stored)?;
+            E::update_many()
+                .col_expr(code, Expr::value(SealedCode::from(plaintext)))
+                .filter(id.eq(row_id))
+                .exec(db)
+                .await?;
+            rewritten += 1;
+        }
+        after = last;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD_KEY: &str = "old code key";
+    const NEW_KEY: &str = "new code key";
+
+    #[test]
+    fn seals_with_the_current_key_and_opens_again() {
+        let key_ring = CodeKeyRing::new(Some(NEW_KEY), &[]);
+        let sealed = key_ring.seal("mov ax, 1");
+
+        assert!(sealed.starts_with(ENVELOPE_PREFIX));
+        assert!(!sealed.contains("mov ax"));
+        assert!(sealed.starts_with(&format!(
+            "{ENVELOPE_PREFIX}{}$",
+            key_ring.current_key_id().unwrap()
+        )));
+        assert!(key_ring.is_current(&sealed));
+        assert_eq!(key_ring.open(&sealed).unwrap(), "mov ax, 1");
+    }
+
+    #[test]
+    fn stores_plaintext_without_a_key() {
+        let key_ring = CodeKeyRing::new(None, &[]);
+        assert_eq!(key_ring.current_key_id(), None);
+        assert_eq!(key_ring.seal("mov ax, 1"), "mov ax, 1");
+        assert_eq!(key_ring.open("mov ax, 1").unwrap(), "mov ax, 1");
+        assert!(key_ring.is_current("mov ax, 1"));
+    }
+
+    #[test]
+    fn reads_code_sealed_before_a_rotation() {
+        let old_ring = CodeKeyRing::new(Some(OLD_KEY), &[]);
+        let sealed_before = old_ring.seal("mov bx, 2");
+        let plaintext_before = "mov cx, 3";
+
+        let rotated = CodeKeyRing::new(Some(NEW_KEY), &[OLD_KEY.to_owned()]);
+        assert_ne!(rotated.current_key_id(), old_ring.current_key_id());
+        assert_eq!(rotated.open(&sealed_before).unwrap(), "mov bx, 2");
+        assert_eq!(rotated.open(plaintext_before).unwrap(), plaintext_before);
+        assert!(!rotated.is_current(&sealed_before));
+        assert!(!rotated.is_current(plaintext_before));
+        assert!(rotated.knows(old_ring.current_key_id().unwrap()));
+
+        let resealed = rotated.seal(&rotated.open(&sealed_before).unwrap());
+        assert!(rotated.is_current(&resealed));
+        assert!(old_ring.open(&resealed).is_err());
+    }
+
+    #[test]
+    fn refuses_code_under_an_unknown_key_or_a_broken_envelope() {
+        let sealed = CodeKeyRing::new(Some(OLD_KEY), &[]).seal("mov ax, 1");
+        let key_ring = CodeKeyRing::new(Some(NEW_KEY), &[]);
+        assert!(matches!(key_ring.open(&sealed), Err(AppError::Internal(_))));
+        assert!(matches!(
+            key_ring.open(&format!("{ENVELOPE_PREFIX}no-separator")),
+            Err(AppError::Internal(_))
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect};
+
+use crate::{
+    auth::{AuthAccount, permissions::Permission},
+    dto::{AccountRole, UserResponse},
+    entities::{classroom, classroom_assistant},
+    error::AppError,
+};
+
+/// Decides whose code a caller may read in roster responses:
+///
+/// - admins read everything;
+/// - assistants read the classrooms assigned to them;
+/// - students read only their own roster entries;
+/// - observers, and assistants outside their classrooms, get lengths only;
+/// - callers without a token (legacy clients) read everything except while a
+///   classroom's exam is running.
+pub struct CodeViewer {
+    npm: Option<String>,
+    role: Option<AccountRole>,
+    full_access: bool,
+    assigned: HashSet<i32>,
+    running_exams: HashSet<i32>,
+}
+
+impl CodeViewer {
+    pub async fn load(
+        db: &DatabaseConnection,
+        auth: Option<&AuthAccount>,
+        now: DateTime<Utc>,
+    ) -> Result<Self, AppError> {
+        let role = auth.and_then(|auth| AccountRole::from_str(&auth.account.role));
+        let full_access = role == Some(AccountRole::Admin);
+        let assigned = match auth {
+            Some(auth) if !full_access && auth.can(Permission::ViewCode) => {
+                assigned_classrooms(db, auth.account.id).await?
+            }
+            _ => HashSet::new(),
+        };
+        let running_exams = if auth.is_none() {
+            running_exams(db, now).await?
+        } else {
+            HashSet::new()
+        };
+
+        Ok(Self {
+            npm: auth.map(|auth| auth.account.npm.clone()),
+            role,
+            full_access,
+            assigned,
+            running_exams,
+        })
+    }
+
+    /// Whether the caller may read the code of `npm`'s roster entry in the classroom.
+    pub fn can_read(&self, classroom_id: i32, npm: &str) -> bool {
+        if self.full_access || self.assigned.contains(&classroom_id) {
+            return true;
+        }
+        match &self.npm {
+            Some(own) => own == npm,
+            None => !self.running_exams.contains(&classroom_id),
+        }
+    }
+
+    pub fn shape(&self, classroom_id: i32, user: UserResponse) -> UserResponse {
+        if self.can_read(classroom_id, &user.npm) {
+            user
+        } else {
+            user.redacted()
+        }
+    }
+
+    pub fn shape_all(&self, classroom_id: i32, users: Vec<UserResponse>) -> Vec<UserResponse> {
+        users
+            .into_iter()
+            .map(|user| self.shape(classroom_id, user))
+            .collect()
+    }
+
+    /// Distinguishes cached responses of callers that see different code.
+    pub fn cache_key(&self) -> String {
+        let mut running: Vec<_> = self.running_exams.iter().copied().collect();
+        running.sort_unstable();
+        format!(
+            "{}:{}:{running:?}",
+            self.role.as_ref().map_or("anonymous", AccountRole::as_str),
+            self.npm.as_deref().unwrap_or_default(),
+        )
+    }
+}
+
+async fn assigned_classrooms(
+    db: &DatabaseConnection,
+    account_id: i32,
+) -> Result<HashSet<i32>, AppError> {
+    let ids: Vec<i32> = classroom_assistant::Entity::find()
+        .select_only()
+        .column(classroom_assistant::Column::ClassroomId)
+        .filter(classroom_assistant::Column::AccountId.eq(account_id))
+        .into_tuple()
+        .all(db)
+        .await?;
+    Ok(ids.into_iter().collect())
+}
+
+/// Exam classrooms whose window contains `now`.
+async fn running_exams(
+    db: &DatabaseConnection,
+    now: DateTime<Utc>,
+) -> Result<HashSet<i32>, AppError> {
+    let ids: Vec<i32> = classroom::Entity::find()
+        .select_only()
+        .column(classroom::Column::Id)
+        .filter(classroom::Column::IsExam.eq(true))
+        .filter(
+            Condition::any()
+                .add(classroom::Column::ExamStart.is_null())
+                .add(classroom::Column::ExamStart.lte(now)),
+        )
+        .filter(classroom::Column::ExamEnd.gt(now))
+        .into_tuple()
+        .all(db)
+        .await?;
+    Ok(ids.into_iter().collect())
+}
@@ -0,0 +1,70 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// Open event streams per classroom user, capped so one client cannot hold
+/// dozens of connections (and file descriptors) open.
+pub struct ConnectionTracker {
+    max_per_user: usize,
+    open: Mutex<HashMap<(i32, String), usize>>,
+}
+
+impl ConnectionTracker {
+    pub fn new(max_per_user: usize) -> Self {
+        Self {
+            max_per_user,
+            open: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn max_per_user(&self) -> usize {
+        self.max_per_user
+    }
+
+    /// Registers a connection, or returns `None` when the user is at the cap. The
+    /// connection counts until the returned guard is dropped.
+    pub fn try_acquire(self: &Arc<Self>, classroom_id: i32, npm: &str) -> Option<ConnectionGuard> {
+        let key = (classroom_id, npm.to_owned());
+        let mut open = self.open.lock().expect("connection map poisoned");
+        let count = open.entry(key.clone()).or_default();
+        if *count >= self.max_per_user {
+            return None;
+        }
+        *count += 1;
+
+        Some(ConnectionGuard {
+            tracker: Arc::clone(self),
+            key,
+        })
+    }
+
+    /// Open connections of the classroom by NPM, sorted by NPM.
+    pub fn classroom_counts(&self, classroom_id: i32) -> Vec<(String, usize)> {
+        let open = self.open.lock().expect("connection map poisoned");
+        let mut counts: Vec<(String, usize)> = open
+            .iter()
+            .filter(|((id, _), _)| *id == classroom_id)
+            .map(|((_, npm), count)| (npm.clone(), *count))
+            .collect();
+        counts.sort();
+        counts
+    }
+}
+
+pub struct ConnectionGuard {
+    tracker: Arc<ConnectionTracker>,
+    key: (i32, String),
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        let mut open = self.tracker.open.lock().expect("connection map poisoned");
+        if let Some(count) = open.get_mut(&self.key) {
+            *count -= 1;
+            if *count == 0 {
+                open.remove(&self.key);
+            }
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    time::Duration,
+};
+
+use chrono::{NaiveDate, Utc};
+use sea_orm::{
+    ActiveValue::Set,
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, JoinType, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait, TransactionTrait,
+    sea_query::{Expr, OnConflict},
+};
+
+use crate::{
+    entities::{daily_stat, submission, task, task_progress},
+    error::AppError,
+    services::language_usage,
+    state::AppState,
+};
+
+/// How often today's and yesterday's counters are recomputed.
+const ROLLUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Classroom id of the row holding activity that is not tied to a classroom.
+pub const NO_CLASSROOM: i32 = 0;
+
+/// Counts a successful login for today. Failures are logged and never fail the login.
+pub async fn record_login(db: &DatabaseConnection, classroom_id: Option<i32>) {
+    let model = daily_stat::ActiveModel {
+        day: Set(Utc::now().date_naive()),
+        classroom_id: Set(classroom_id.unwrap_or(NO_CLASSROOM)),
+        logins: Set(1),
+        submissions: Set(0),
+        final_submissions: Set(0),
+        active_users: Set(0),
+        verdicts: Set("{}".to_owned()),
+        rolled_up_at: Set(None),
+        ..Default::default()
+    };
+    let result = daily_stat::Entity::insert(model)
+        .on_conflict(
+            OnConflict::columns([daily_stat::Column::Day, daily_stat::Column::ClassroomId])
+                .value(
+                    daily_stat::Column::Logins,
+                    Expr::col((daily_stat::Entity, daily_stat::Column::Logins)).add(1),
+                )
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await;
+    if let Err(err) = result {
+        tracing::warn!("failed to count login in daily stats: {err}");
+    }
+}
+
+/// Starts the rollup job. On startup it backfills every day since the last rollup, then
+/// keeps today and yesterday fresh so late grading is reflected.
+pub fn spawn(state: AppState) {
+    tokio::spawn(async move {
+        if let Err(err) = backfill(&state.db).await {
+            tracing::warn!("daily stats backfill failed: {err}");
+        }
+
+        let mut ticker = tokio::time::interval(ROLLUP_INTERVAL);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let today = Utc::now().date_naive();
+            for day in [today.pred_opt(), Some(today)].into_iter().flatten() {
+                if let Err(err) = roll_up_day(&state.db, day).await {
+                    tracing::warn!("daily stats rollup for {day} failed: {err}");
+                }
+            }
+        }
+    });
+}
+
+async fn backfill(db: &DatabaseConnection) -> Result<(), AppError> {
+    let today = Utc::now().date_naive();
+    let last_rolled_up = daily_stat::Entity::find()
+        .filter(daily_stat::Column::RolledUpAt.is_not_null())
+        .order_by_desc(daily_stat::Column::Day)
+        .one(db)
+        .await?
+        .map(|row| row.day);
+    let start = match last_rolled_up {
+        Some(day) => day,
+        None => submission::Entity::find()
+            .order_by_asc(submission::Column::CreatedAt)
+            .one(db)
+            .await?
+            .map(|row| row.created_at.date_naive())
+            .unwrap_or(today),
+    };
+
+    let mut rolled_up = 0;
+    for day in start.iter_days().take_while(|day| *day <= today) {
+        roll_up_day(db, day).await?;
+        rolled_up += 1;
+    }
+    if rolled_up > 1 {
+        tracing::info!("rolled up daily stats for {rolled_up} day(s) since {start}");
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct DayCounters {
+    submissions: i64,
+    final_submissions: i64,
+    active_users: HashSet<i32>,
+    verdicts: BTreeMap<String, i64>,
+}
+
+/// Recomputes the submission and activity counters of one UTC day. Login counts are
+/// kept as recorded, since logins leave no other trace to rebuild them from.
+pub async fn roll_up_day(db: &DatabaseConnection, day: NaiveDate) -> Result<(), AppError> {
+    let Some(next_day) = day.succ_opt() else {
+        return Ok(());
+    };
+    let start = day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    let end = next_day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+
+    let mut counters: HashMap<i32, DayCounters> = HashMap::new();
+
+    let submissions: Vec<(i32, i32, Option<String>, bool)> = submission::Entity::find()
+        .select_only()
+        .column(submission::Column::ClassroomId)
+        .column(submission::Column::UserId)
+        .column(submission::Column::Status)
+        .column(submission::Column::IsFinal)
+        .filter(submission::Column::CreatedAt.gte(start))
+        .filter(submission::Column::CreatedAt.lt(end))
+        .into_tuple()
+        .all(db)
+        .await?;
+    for (classroom_id, user_id, status, is_final) in submissions {
+        let entry = counters.entry(classroom_id).or_default();
+        entry.submissions += 1;
+        entry.final_submissions += i64::from(is_final);
+        entry.active_users.insert(user_id);
+        *entry
+            .verdicts
+            .entry(status.unwrap_or_else(|| "Pending".to_owned()))
+            .or_default() += 1;
+    }
+
+    let opened_or_edited = task_progress::Column::FirstOpenedAt
+        .gte(start)
+        .and(task_progress::Column::FirstOpenedAt.lt(end))
+        .or(task_progress::Column::LastEditedAt
+            .gte(start)
+            .and(task_progress::Column::LastEditedAt.lt(end)));
+    let progress: Vec<(i32, i32)> = task_progress::Entity::find()
+        .select_only()
+        .column(task::Column::ClassroomId)
+        .column(task_progress::Column::UserId)
+        .join(JoinType::InnerJoin, task_progress::Relation::Task.def())
+        .filter(opened_or_edited)
+        .into_tuple()
+        .all(db)
+        .await?;
+    for (classroom_id, user_id) in progress {
+        counters
+            .entry(classroom_id)
+            .or_default()
+            .active_users
+            .insert(user_id);
+    }
+
+    let now = Utc::now();
+    let txn = db.begin().await?;
+    // Rows whose activity has since been deleted drop back to zero rather than keep
+    // stale counts.
+    daily_stat::Entity::update_many()
+        .col_expr(daily_stat::Column::Submissions, Expr::value(0))
+        .col_expr(daily_stat::Column::FinalSubmissions, Expr::value(0))
+        .col_expr(daily_stat::Column::ActiveUsers, Expr::value(0))
+        .col_expr(daily_stat::Column::Verdicts, Expr::value("{}"))
+        .col_expr(daily_stat::Column::RolledUpAt, Expr::value(now))
+        .filter(daily_stat::Column::Day.eq(day))
+        .exec(&txn)
+        .await?;
+    for (classroom_id, day_counters) in counters {
+        upsert(&txn, day, classroom_id, day_counters, now).await?;
+    }
+    if counters_missing(&txn, day).await? {
+        // Keep a marker row so the backfill knows this day was covered.
+        upsert(&txn, day, NO_CLASSROOM, DayCounters::default(), now).await?;
+    }
+    language_usage::roll_up_day(&txn, day, start, end).await?;
+    txn.commit().await?;
+    Ok(())
+}
+
+async fn counters_missing(db: &impl ConnectionTrait, day: NaiveDate) -> Result<bool, AppError> {
+    Ok(daily_stat::Entity::find()
+        .filter(daily_stat::Column::Day.eq(day))
+        .one(db)
+        .await?
+        .is_none())
+}
+
+async fn upsert(
+    db: &impl ConnectionTrait,
+    day: NaiveDate,
+    classroom_id: i32,
+    counters: DayCounters,
+    now: chrono::DateTime<Utc>,
+) -> Result<(), AppError> {
+    let verdicts = serde_json::to_string(&counters.verdicts).unwrap_or_else(|_| "{}".into());
+    let model = daily_stat::ActiveModel {
+        day: Set(day),
+        classroom_id: Set(classroom_id),
+        logins: Set(0),
+        submissions: Set(counters.submissions),
+        final_submissions: Set(counters.final_submissions),
+        active_users: Set(counters.active_users.len() as i64),
+        verdicts: Set(verdicts),
+        rolled_up_at: Set(Some(now)),
+        ..Default::default()
+    };
+    daily_stat::Entity::insert(model)
+        .on_conflict(
+            OnConflict::columns([daily_stat::Column::Day, daily_stat::Column::ClassroomId])
+                .update_columns([
+                    daily_stat::Column::Submissions,
+                    daily_stat::Column::FinalSubmissions,
+                    daily_stat::Column::ActiveUsers,
+                    daily_stat::Column::Verdicts,
+                    daily_stat::Column::RolledUpAt,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    Ok(())
+}
+
+/// Stored rows between `from` and `to`, both inclusive, optionally for one classroom.
+pub async fn load(
+    db: &DatabaseConnection,
+    from: NaiveDate,
+    to: NaiveDate,
+    classroom_id: Option<i32>,
+) -> Result<Vec<daily_stat::Model>, AppError> {
+    let mut query = daily_stat::Entity::find()
+        .filter(daily_stat::Column::Day.gte(from))
+        .filter(daily_stat::Column::Day.lte(to));
+    if let Some(classroom_id) = classroom_id {
+        query = query.filter(daily_stat::Column::ClassroomId.eq(classroom_id));
+    }
+    Ok(query
+        .order_by_asc(daily_stat::Column::Day)
+        .order_by_asc(daily_stat::Column::ClassroomId)
+        .all(db)
+        .await?)
+}
@@ -0,0 +1,257 @@
+//! The checklist behind `GET /api/diagnostics/self-test`: the conditions login and the
+//! exam pages depend on, evaluated for one NPM without logging it in or changing
+//! anything, so a lab assistant can see at once why a student is stuck.
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    dto::{
+        AccountStatus, ClassroomStatus, DiagnosticCheck, DiagnosticCheckKind, DiagnosticStatus,
+        SelfTestResponse,
+    },
+    entities::{account, classroom, user},
+    error::AppError,
+    services::{exam_clock::ExamClock, executor_pool},
+    state::AppState,
+};
+
+pub async fn self_test(state: &AppState, npm: &str) -> Result<SelfTestResponse, AppError> {
+    let db = &state.db;
+    let now = Utc::now();
+    let mut checks = Vec::new();
+
+    let account_model = account::Entity::find()
+        .filter(account::Column::Npm.eq(npm))
+        .one(db)
+        .await?;
+    let rosters = user::Entity::find()
+        .filter(user::Column::Npm.eq(npm))
+        .find_also_related(classroom::Entity)
+        .order_by_desc(user::Column::Id)
+        .all(db)
+        .await?;
+    let published = rosters.iter().find_map(|(user_model, classroom_model)| {
+        classroom_model
+            .as_ref()
+            .filter(|classroom_model| classroom_model.status == ClassroomStatus::Published.as_str())
+            .map(|classroom_model| (user_model, classroom_model))
+    });
+
+    checks.push(match &account_model {
+        Some(model) if AccountStatus::from_str(&model.status) == Some(AccountStatus::Pending) => {
+            check(
+                DiagnosticCheckKind::Account,
+                DiagnosticStatus::Fail,
+                "Akun menunggu persetujuan admin.".into(),
+            )
+        }
+        Some(model) => check(
+            DiagnosticCheckKind::Account,
+            DiagnosticStatus::Pass,
+            format!("Akun #{} terdaftar dengan peran {}.", model.id, model.role),
+        ),
+        None if state.account_approval_required && rosters.is_empty() => check(
+            DiagnosticCheckKind::Account,
+            DiagnosticStatus::Fail,
+            "Belum ada akun; login pertama akan menunggu persetujuan admin karena NPM tidak ada di roster mana pun.".into(),
+        ),
+        None => check(
+            DiagnosticCheckKind::Account,
+            DiagnosticStatus::Warn,
+            "Belum ada akun; akun dibuat saat login pertama.".into(),
+        ),
+    });
+
+    let Some((user_model, classroom_model)) = published else {
+        let detail = if rosters.is_empty() {
+            "NPM tidak terdaftar di roster kelas mana pun.".to_owned()
+        } else {
+            "NPM hanya terdaftar di kelas yang belum dipublikasikan.".to_owned()
+        };
+        checks.push(check(
+            DiagnosticCheckKind::Classroom,
+            DiagnosticStatus::Fail,
+            detail,
+        ));
+        for kind in [
+            DiagnosticCheckKind::UserActive,
+            DiagnosticCheckKind::ExamWindow,
+        ] {
+            checks.push(check(
+                kind,
+                DiagnosticStatus::Skipped,
+                "Tidak ada kelas yang dipublikasikan.".into(),
+            ));
+        }
+        checks.push(executor_check(state).await);
+        return Ok(response(npm, None, checks, now));
+    };
+
+    checks.push(check(
+        DiagnosticCheckKind::Classroom,
+        DiagnosticStatus::Pass,
+        format!(
+            "Terdaftar di kelas {} (#{}).",
+            classroom_model.name, classroom_model.id
+        ),
+    ));
+    checks.push(if let Some(locked_at) = user_model.submissions_locked_at
+        && user_model.active
+    {
+        check(
+            DiagnosticCheckKind::UserActive,
+            DiagnosticStatus::Warn,
+            format!(
+                "Peserta aktif, tetapi pengumpulan dikunci sejak {} setelah menyelesaikan ujian.",
+                display(state, locked_at)
+            ),
+        )
+    } else if user_model.active {
+        check(
+            DiagnosticCheckKind::UserActive,
+            DiagnosticStatus::Pass,
+            "Peserta aktif.".into(),
+        )
+    } else {
+        check(
+            DiagnosticCheckKind::UserActive,
+            DiagnosticStatus::Fail,
+            "Peserta dinonaktifkan di roster kelas.".into(),
+        )
+    });
+    checks.push(exam_window_check(state, classroom_model, user_model, now).await?);
+    checks.push(executor_check(state).await);
+
+    Ok(response(npm, Some(classroom_model), checks, now))
+}
+
+async fn exam_window_check(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+    user_model: &user::Model,
+    now: DateTime<Utc>,
+) -> Result<DiagnosticCheck, AppError> {
+    let kind = DiagnosticCheckKind::ExamWindow;
+    if !classroom_model.is_exam {
+        return Ok(check(
+            kind,
+            DiagnosticStatus::Skipped,
+            "Kelas latihan tanpa jendela ujian.".into(),
+        ));
+    }
+
+    let clock = ExamClock::load(&state.db, classroom_model).await?;
+    if classroom_model.exam_gated_at.is_some() && user_model.exam_started_at.is_none() {
+        return Ok(check(
+            kind,
+            DiagnosticStatus::Fail,
+            "Ujian ditahan karena uji eksekusi kode sebelum ujian gagal.".into(),
+        ));
+    }
+    let (Some(start), Some(end)) = (clock.start, clock.end) else {
+        return Ok(check(
+            kind,
+            DiagnosticStatus::Pass,
+            "Ujian tanpa jadwal; login selalu dibuka.".into(),
+        ));
+    };
+    if !state.time_policy.has_started(&clock, now) {
+        return Ok(check(
+            kind,
+            DiagnosticStatus::Fail,
+            format!("Ujian belum dimulai; dibuka {}.", display(state, start)),
+        ));
+    }
+    if state.time_policy.has_ended(&clock, now) {
+        let end = clock.effective_end(now).unwrap_or(end);
+        return Ok(check(
+            kind,
+            DiagnosticStatus::Fail,
+            format!("Ujian telah berakhir pada {}.", display(state, end)),
+        ));
+    }
+    if clock.is_paused() {
+        return Ok(check(
+            kind,
+            DiagnosticStatus::Warn,
+            "Ujian sedang dijeda pengawas.".into(),
+        ));
+    }
+    let end = clock.effective_end(now).unwrap_or(end);
+    Ok(check(
+        kind,
+        DiagnosticStatus::Pass,
+        format!("Ujian berjalan sampai {}.", display(state, end)),
+    ))
+}
+
+async fn executor_check(state: &AppState) -> DiagnosticCheck {
+    let kind = DiagnosticCheckKind::Executor;
+    let results = executor_pool::probe_all(state).await;
+    let failures: Vec<String> = results
+        .iter()
+        .filter_map(|(base_url, result)| {
+            result
+                .as_ref()
+                .err()
+                .map(|err| format!("{base_url}: {err}"))
+        })
+        .collect();
+    if failures.is_empty() {
+        check(
+            kind,
+            DiagnosticStatus::Pass,
+            format!("{} instance Judge0 menjawab.", results.len()),
+        )
+    } else if failures.len() < results.len() {
+        check(
+            kind,
+            DiagnosticStatus::Warn,
+            format!(
+                "{} dari {} instance Judge0 tidak menjawab: {}",
+                failures.len(),
+                results.len(),
+                failures.join("; ")
+            ),
+        )
+    } else {
+        check(
+            kind,
+            DiagnosticStatus::Fail,
+            format!("Judge0 tidak dapat dijangkau: {}", failures.join("; ")),
+        )
+    }
+}
+
+fn check(check: DiagnosticCheckKind, status: DiagnosticStatus, detail: String) -> DiagnosticCheck {
+    DiagnosticCheck {
+        check,
+        status,
+        detail,
+    }
+}
+
+fn display(state: &AppState, at: DateTime<Utc>) -> String {
+    at.with_timezone(&state.display_timezone)
+        .format("%Y-%m-%d %H:%M %Z")
+        .to_string()
+}
+
+fn response(
+    npm: &str,
+    classroom_model: Option<&classroom::Model>,
+    checks: Vec<DiagnosticCheck>,
+    now: DateTime<Utc>,
+) -> SelfTestResponse {
+    SelfTestResponse {
+        npm: npm.to_owned(),
+        classroom_id: classroom_model.map(|classroom_model| classroom_model.id),
+        classroom_name: classroom_model.map(|classroom_model| classroom_model.name.clone()),
+        ok: checks
+            .iter()
+            .all(|check| check.status != DiagnosticStatus::Fail),
+        checks,
+        checked_at: now,
+    }
+}
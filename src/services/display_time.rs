@@ -0,0 +1,85 @@
+use axum::http::{HeaderMap, header::ACCEPT_LANGUAGE};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::dto::TimeDisplay;
+
+/// Display timezone used when `DISPLAY_TIMEZONE` is not set.
+pub const DEFAULT_TIMEZONE: &str = "Asia/Jakarta";
+
+/// Renders export timestamps. Machine columns always stay RFC 3339 UTC; in local
+/// mode a readable column in the display timezone is added next to each of them.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeFormatter {
+    local: Option<Tz>,
+}
+
+/// The mode [`TimeFormatter::resolve`] picks, for callers that store it and format later.
+pub fn display_for(requested: Option<TimeDisplay>, headers: &HeaderMap) -> TimeDisplay {
+    requested.unwrap_or_else(|| {
+        if prefers_indonesian(headers) {
+            TimeDisplay::Local
+        } else {
+            TimeDisplay::Utc
+        }
+    })
+}
+
+impl TimeFormatter {
+    pub fn new(display: TimeDisplay, zone: Tz) -> Self {
+        Self {
+            local: (display == TimeDisplay::Local).then_some(zone),
+        }
+    }
+
+    /// Picks the mode from the `time` query parameter, falling back to the first
+    /// language of `Accept-Language`: Indonesian readers get local times.
+    pub fn resolve(requested: Option<TimeDisplay>, headers: &HeaderMap, zone: Tz) -> Self {
+        Self::new(display_for(requested, headers), zone)
+    }
+
+    pub fn is_local(&self) -> bool {
+        self.local.is_some()
+    }
+
+    /// Header cells for a timestamp column: the column itself, plus `<name>_local`
+    /// in local mode.
+    pub fn columns(&self, name: &str) -> Vec<String> {
+        let mut columns = vec![name.to_string()];
+        if self.is_local() {
+            columns.push(format!("{name}_local"));
+        }
+        columns
+    }
+
+    /// Cells for a timestamp, matching [`Self::columns`]; empty when absent.
+    pub fn cells(&self, value: Option<DateTime<Utc>>) -> Vec<String> {
+        let mut cells = vec![value.map(|value| value.to_rfc3339()).unwrap_or_default()];
+        if let Some(zone) = self.local {
+            cells.push(
+                value
+                    .map(|value| {
+                        value
+                            .with_timezone(&zone)
+                            .format("%Y-%m-%d %H:%M:%S %Z")
+                            .to_string()
+                    })
+                    .unwrap_or_default(),
+            );
+        }
+        cells
+    }
+}
+
+fn prefers_indonesian(headers: &HeaderMap) -> bool {
+    headers
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .map(|first| first.split(';').next().unwrap_or_default().trim())
+        .is_some_and(|tag| {
+            tag.split('-')
+                .next()
+                .is_some_and(|primary| primary.eq_ignore_ascii_case("id"))
+        })
+}
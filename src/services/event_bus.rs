@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder,
+};
+use serde::{Serialize, de::DeserializeOwned};
+use tokio::sync::broadcast;
+
+use crate::{
+    dto::{
+        ExamClockResponse, ExamFinishedEvent, GradingStatusResponse, IntegrityAlertEvent,
+        MessageEvent, SubmissionEvent, TaskDueEvent, UserJoinedEvent, UserStatusEvent,
+        UserSubmittedEvent,
+    },
+    entities::classroom_event,
+    error::AppError,
+};
+
+/// Events buffered per subscriber before a slow stream starts missing them.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Stored `audience` of events only proctors see.
+const STAFF_AUDIENCE: &str = "staff";
+
+/// Who inside the classroom an event is addressed to.
+#[derive(Debug, Clone)]
+pub enum Audience {
+    Everyone,
+    Users(Vec<i32>),
+    /// Proctors watching `GET /api/events`; never sent to student streams.
+    Staff,
+}
+
+#[derive(Debug, Clone)]
+pub enum EventKind {
+    Message(MessageEvent),
+    Submission(SubmissionEvent),
+    Clock(ExamClockResponse),
+    UserStatus(UserStatusEvent),
+    TaskDue(TaskDueEvent),
+    GradingStatus(GradingStatusResponse),
+    Integrity(IntegrityAlertEvent),
+    UserJoined(UserJoinedEvent),
+    UserSubmitted(UserSubmittedEvent),
+    ExamFinished(ExamFinishedEvent),
+}
+
+impl EventKind {
+    /// SSE event name, also stored with the persisted event.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Message(_) => "message",
+            EventKind::Submission(_) => "submission",
+            EventKind::Clock(_) => "clock",
+            EventKind::UserStatus(_) => "user-status",
+            EventKind::TaskDue(_) => "task-due",
+            EventKind::GradingStatus(_) => "grading-status",
+            EventKind::Integrity(_) => "integrity",
+            EventKind::UserJoined(_) => "user-joined",
+            EventKind::UserSubmitted(_) => "user-submitted",
+            EventKind::ExamFinished(_) => "exam-finished",
+        }
+    }
+
+    /// JSON payload, as sent in the SSE `data` field.
+    pub fn payload(&self) -> String {
+        match self {
+            EventKind::Message(payload) => to_json(payload),
+            EventKind::Submission(payload) => to_json(payload),
+            EventKind::Clock(payload) => to_json(payload),
+            EventKind::UserStatus(payload) => to_json(payload),
+            EventKind::TaskDue(payload) => to_json(payload),
+            EventKind::GradingStatus(payload) => to_json(payload),
+            EventKind::Integrity(payload) => to_json(payload),
+            EventKind::UserJoined(payload) => to_json(payload),
+            EventKind::UserSubmitted(payload) => to_json(payload),
+            EventKind::ExamFinished(payload) => to_json(payload),
+        }
+    }
+
+    fn from_stored(name: &str, payload: &str) -> Option<Self> {
+        match name {
+            "message" => from_json(payload).map(EventKind::Message),
+            "submission" => from_json(payload).map(EventKind::Submission),
+            "clock" => from_json(payload).map(EventKind::Clock),
+            "user-status" => from_json(payload).map(EventKind::UserStatus),
+            "task-due" => from_json(payload).map(EventKind::TaskDue),
+            "grading-status" => from_json(payload).map(EventKind::GradingStatus),
+            "integrity" => from_json(payload).map(EventKind::Integrity),
+            "user-joined" => from_json(payload).map(EventKind::UserJoined),
+            "user-submitted" => from_json(payload).map(EventKind::UserSubmitted),
+            "exam-finished" => from_json(payload).map(EventKind::ExamFinished),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClassroomEvent {
+    /// Sequence number for replay; increases with every persisted event.
+    pub seq: i32,
+    pub classroom_id: i32,
+    pub audience: Audience,
+    pub kind: EventKind,
+}
+
+impl ClassroomEvent {
+    pub fn is_for(&self, classroom_id: i32, user_id: i32) -> bool {
+        self.classroom_id == classroom_id
+            && match &self.audience {
+                Audience::Everyone => true,
+                Audience::Users(user_ids) => user_ids.contains(&user_id),
+                Audience::Staff => false,
+            }
+    }
+
+    fn from_model(model: classroom_event::Model) -> Option<Self> {
+        let audience = match model.audience {
+            Some(raw) if raw == STAFF_AUDIENCE => Audience::Staff,
+            Some(raw) => Audience::Users(serde_json::from_str(&raw).ok()?),
+            None => Audience::Everyone,
+        };
+        Some(Self {
+            seq: model.id,
+            classroom_id: model.classroom_id,
+            audience,
+            kind: EventKind::from_stored(&model.kind, &model.payload)?,
+        })
+    }
+}
+
+/// Fan-out of classroom events to open event streams. Events are stored first so
+/// a reconnecting client can replay what it missed; live delivery is best effort.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<Arc<ClassroomEvent>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Persists the event and broadcasts it. A failed insert is logged rather than
+    /// returned: the change the event reports has already happened.
+    pub async fn publish(
+        &self,
+        db: &DatabaseConnection,
+        classroom_id: i32,
+        audience: Audience,
+        kind: EventKind,
+    ) {
+        let stored_audience = match &audience {
+            Audience::Everyone => None,
+            Audience::Users(user_ids) => Some(to_json(user_ids)),
+            Audience::Staff => Some(STAFF_AUDIENCE.to_owned()),
+        };
+        let inserted = classroom_event::ActiveModel {
+            classroom_id: Set(classroom_id),
+            kind: Set(kind.name().to_owned()),
+            audience: Set(stored_audience),
+            payload: Set(kind.payload()),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await;
+
+        let seq = match inserted {
+            Ok(model) => model.id,
+            Err(err) => {
+                tracing::warn!("failed to persist {} event: {err}", kind.name());
+                return;
+            }
+        };
+
+        // Sending only fails when nobody is subscribed, which is not an error here.
+        let _ = self.sender.send(Arc::new(ClassroomEvent {
+            seq,
+            classroom_id,
+            audience,
+            kind,
+        }));
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<ClassroomEvent>> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Persisted events of the classroom after `after_seq`, oldest first.
+pub async fn load_after(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+    after_seq: i32,
+) -> Result<Vec<ClassroomEvent>, AppError> {
+    load_after_in(db, &[classroom_id], after_seq).await
+}
+
+/// Persisted events of any of the classrooms after `after_seq`, oldest first.
+pub async fn load_after_in(
+    db: &DatabaseConnection,
+    classroom_ids: &[i32],
+    after_seq: i32,
+) -> Result<Vec<ClassroomEvent>, AppError> {
+    let models = classroom_event::Entity::find()
+        .filter(classroom_event::Column::ClassroomId.is_in(classroom_ids.iter().copied()))
+        .filter(classroom_event::Column::Id.gt(after_seq))
+        .order_by_asc(classroom_event::Column::Id)
+        .all(db)
+        .await?;
+
+    Ok(models
+        .into_iter()
+        .filter_map(ClassroomEvent::from_model)
+        .collect())
+}
+
+fn to_json<T: Serialize>(payload: &T) -> String {
+    serde_json::to_string(payload).expect("event payloads serialize to JSON")
+}
+
+fn from_json<T: DeserializeOwned>(payload: &str) -> Option<T> {
+    serde_json::from_str(payload).ok()
+}
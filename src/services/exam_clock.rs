@@ -0,0 +1,147 @@
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::entities::{classroom, exam_pause};
+
+/// Exam window of a classroom with every pause interval excluded from the countdown.
+#[derive(Debug, Clone)]
+pub struct ExamClock {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pauses: Vec<exam_pause::Model>,
+}
+
+impl ExamClock {
+    pub async fn load(
+        db: &impl ConnectionTrait,
+        classroom: &classroom::Model,
+    ) -> Result<Self, DbErr> {
+        let pauses = exam_pause::Entity::find()
+            .filter(exam_pause::Column::ClassroomId.eq(classroom.id))
+            .order_by_asc(exam_pause::Column::PausedAt)
+            .all(db)
+            .await?;
+
+        Ok(Self {
+            start: classroom.exam_start,
+            end: classroom.exam_end,
+            pauses,
+        })
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pauses.iter().any(|pause| pause.resumed_at.is_none())
+    }
+
+    /// Total time spent paused; an open pause counts up to `now`.
+    pub fn paused_duration(&self, now: DateTime<Utc>) -> Duration {
+        self.pauses
+            .iter()
+            .map(|pause| pause.resumed_at.unwrap_or(now) - pause.paused_at)
+            .fold(Duration::zero(), |total, span| {
+                total + span.max(Duration::zero())
+            })
+    }
+
+    /// Scheduled end pushed back by the paused time.
+    pub fn effective_end(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.end.map(|end| end + self.paused_duration(now))
+    }
+
+    pub fn remaining(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.effective_end(now)
+            .map(|end| (end - now).max(Duration::zero()))
+    }
+
+    pub fn has_started(&self, now: DateTime<Utc>) -> bool {
+        self.start.is_none_or(|start| now >= start)
+    }
+
+    pub fn has_expired(&self, now: DateTime<Utc>) -> bool {
+        !self.is_paused() && self.effective_end(now).is_some_and(|end| now >= end)
+    }
+}
+
+/// Warning thresholds used when `EXAM_WARNING_THRESHOLDS` is not set.
+pub const DEFAULT_WARNING_THRESHOLDS: &str = "15m,5m,1m";
+
+/// Parses a comma-separated threshold list such as `15m,5m,30s` (`s`, `m` or `h`;
+/// bare numbers are minutes) into durations sorted largest first.
+pub fn parse_warning_thresholds(value: &str) -> Result<Vec<Duration>, String> {
+    let mut thresholds = value
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let (amount, unit) = match part.find(|c: char| !c.is_ascii_digit()) {
+                Some(index) => part.split_at(index),
+                None => (part, "m"),
+            };
+            let amount: i64 = amount
+                .parse()
+                .map_err(|_| format!("invalid threshold: {part}"))?;
+            let threshold = match unit.trim() {
+                "s" => Duration::seconds(amount),
+                "m" => Duration::minutes(amount),
+                "h" => Duration::hours(amount),
+                _ => return Err(format!("invalid threshold unit: {part}")),
+            };
+            if threshold <= Duration::zero() {
+                return Err(format!("threshold must be positive: {part}"));
+            }
+            Ok(threshold)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    thresholds.sort_by(|a, b| b.cmp(a));
+    thresholds.dedup();
+    Ok(thresholds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_units_largest_first_without_duplicates() {
+        assert_eq!(
+            parse_warning_thresholds("30s, 1h,15m,,900s").unwrap(),
+            [
+                Duration::hours(1),
+                Duration::minutes(15),
+                Duration::seconds(30)
+            ]
+        );
+    }
+
+    #[test]
+    fn bare_numbers_are_minutes() {
+        assert_eq!(
+            parse_warning_thresholds("5").unwrap(),
+            [Duration::minutes(5)]
+        );
+        assert_eq!(
+            parse_warning_thresholds(DEFAULT_WARNING_THRESHOLDS).unwrap(),
+            [
+                Duration::minutes(15),
+                Duration::minutes(5),
+                Duration::minutes(1)
+            ]
+        );
+    }
+
+    #[test]
+    fn an_empty_list_disables_warnings() {
+        assert!(parse_warning_thresholds(" ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_thresholds() {
+        for value in ["0m", "10d", "m", "-5m", "1.5m"] {
+            assert!(
+                parse_warning_thresholds(value).is_err(),
+                "{value} should be rejected"
+            );
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, sea_query::Expr};
+use serde_json::json;
+
+use crate::{
+    dto::{ClassroomStatus, ExamGateCheckResponse},
+    entities::classroom,
+    error::AppError,
+    services::{grading, readiness, webhook},
+    state::AppState,
+};
+
+/// How often the watcher looks for exam windows about to open.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long before an exam starts the executor is smoke-tested, leaving time to fix
+/// Judge0 before students arrive.
+const LEAD_MINUTES: i64 = 5;
+
+fn lead_time() -> chrono::Duration {
+    chrono::Duration::minutes(LEAD_MINUTES)
+}
+
+/// Starts the watcher that smoke-tests the executor ahead of every exam window and
+/// keeps retrying exams that are gated.
+pub fn spawn(state: AppState) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(err) = sweep(&state).await {
+                tracing::warn!("exam gate sweep failed: {err}");
+            }
+        }
+    });
+}
+
+async fn sweep(state: &AppState) -> Result<(), AppError> {
+    let now = Utc::now();
+    let classrooms = classroom::Entity::find()
+        .filter(classroom::Column::IsExam.eq(true))
+        .filter(classroom::Column::Status.eq(ClassroomStatus::Published.as_str()))
+        .filter(classroom::Column::ExamStart.lte(now + lead_time()))
+        .filter(classroom::Column::ExamEnd.gt(now))
+        .all(&state.db)
+        .await?;
+
+    for classroom_model in classrooms {
+        if needs_check(&classroom_model) {
+            let _slot = state.executor_queue.admit().await;
+            check(state, classroom_model).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Gated exams are retried on every sweep; others are checked once per window.
+fn needs_check(classroom_model: &classroom::Model) -> bool {
+    if classroom_model.exam_gated_at.is_some() {
+        return true;
+    }
+    match (
+        classroom_model.executor_checked_at,
+        classroom_model.exam_start,
+    ) {
+        (Some(checked_at), Some(start)) => checked_at < start - lead_time(),
+        _ => true,
+    }
+}
+
+/// Runs a smoke submission for the classroom and gates or releases its exam. The
+/// caller is expected to hold an executor slot.
+pub async fn check(
+    state: &AppState,
+    classroom_model: classroom::Model,
+) -> Result<ExamGateCheckResponse, AppError> {
+    let language_id = grading::classroom_language_id(&classroom_model);
+    let result = readiness::run_starter_code(state, &classroom_model, language_id, None)
+        .await
+        .map_err(|err| err.to_string());
+    record(state, classroom_model, result).await
+}
+
+/// Stores the outcome of an executor smoke test. The first failure gates the exam and
+/// alerts the webhooks; the first success after that releases it again.
+pub async fn record(
+    state: &AppState,
+    classroom_model: classroom::Model,
+    result: Result<(), String>,
+) -> Result<ExamGateCheckResponse, AppError> {
+    let now = Utc::now();
+    let was_gated = classroom_model.exam_gated_at;
+    let classroom_id = classroom_model.id;
+    let name = classroom_model.name.clone();
+    let exam_start = classroom_model.exam_start;
+
+    let reason = result.err();
+    let gated_at = reason.is_some().then(|| was_gated.unwrap_or(now));
+    // Only the check columns are written so a concurrent settings edit is not undone.
+    classroom::Entity::update_many()
+        .col_expr(classroom::Column::ExecutorCheckedAt, Expr::value(now))
+        .col_expr(classroom::Column::ExamGatedAt, Expr::value(gated_at))
+        .col_expr(
+            classroom::Column::ExamGateReason,
+            Expr::value(reason.clone()),
+        )
+        .filter(classroom::Column::Id.eq(classroom_id))
+        .exec(&state.db)
+        .await?;
+
+    match (&reason, was_gated) {
+        (Some(reason), None) => {
+            tracing::warn!("exam in classroom {classroom_id} gated: {reason}");
+            webhook::broadcast(
+                state,
+                "exam.gated",
+                json!({
+                    "classroomId": classroom_id,
+                    "name": name,
+                    "examStart": exam_start,
+                    "reason": reason,
+                }),
+            );
+        }
+        (None, Some(gated_at)) => {
+            tracing::info!("exam in classroom {classroom_id} released after executor recovered");
+            webhook::broadcast(
+                state,
+                "exam.ungated",
+                json!({
+                    "classroomId": classroom_id,
+                    "name": name,
+                    "gatedAt": gated_at,
+                    "releasedAt": now,
+                }),
+            );
+        }
+        _ => {}
+    }
+
+    Ok(ExamGateCheckResponse {
+        classroom_id,
+        gated: reason.is_some(),
+        gated_at,
+        reason,
+        checked_at: now,
+    })
+}
@@ -0,0 +1,417 @@
+use std::{
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveValue::Set, ConnectionTrait, EntityTrait, sea_query::OnConflict};
+
+use crate::{
+    dto::{BalanceStrategy, ExecutorConfigResponse, ExecutorConfigSource, ExecutorInstanceStatus},
+    entities::executor_config,
+    error::AppError,
+    services::metrics::ExecutorCounters,
+    state::AppState,
+};
+
+/// Id of the single stored configuration row.
+const CONFIG_ID: i32 = 1;
+
+/// Upper bound for one health probe, independent of `JUDGE0_TIMEOUT_SECS`.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct PoolSettings {
+    pub base_urls: Vec<String>,
+    pub strategy: BalanceStrategy,
+    pub eject_after_failures: u32,
+    pub health_check_interval: Duration,
+}
+
+/// Validates Judge0 base URLs and strips trailing slashes and duplicates.
+pub fn normalize_base_urls<'a>(
+    raw: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<String>, String> {
+    let mut base_urls: Vec<String> = Vec::new();
+    for raw in raw {
+        let base_url = raw.trim().trim_end_matches('/');
+        let parsed = reqwest::Url::parse(base_url).map_err(|err| format!("{base_url:?}: {err}"))?;
+        if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+            return Err(format!("{base_url:?} is not an http(s) URL"));
+        }
+        if !base_urls.iter().any(|existing| existing == base_url) {
+            base_urls.push(base_url.to_owned());
+        }
+    }
+    if base_urls.is_empty() {
+        return Err("at least one Judge0 base URL is required".into());
+    }
+    Ok(base_urls)
+}
+
+/// One Judge0 backend with its health and call counters.
+pub struct ExecutorInstance {
+    pub base_url: String,
+    healthy: AtomicBool,
+    pending: AtomicU64,
+    consecutive_failures: AtomicU32,
+    pub counters: ExecutorCounters,
+    health: Mutex<HealthNote>,
+}
+
+#[derive(Default)]
+struct HealthNote {
+    last_error: Option<String>,
+    last_checked_at: Option<DateTime<Utc>>,
+}
+
+impl ExecutorInstance {
+    fn new(base_url: String) -> Self {
+        Self {
+            base_url,
+            healthy: AtomicBool::new(true),
+            pending: AtomicU64::new(0),
+            consecutive_failures: AtomicU32::new(0),
+            counters: ExecutorCounters::default(),
+            health: Mutex::new(HealthNote::default()),
+        }
+    }
+
+    /// Counts a call in flight until the guard is dropped.
+    pub fn begin(&self) -> PendingCall<'_> {
+        self.pending.fetch_add(1, Ordering::Relaxed);
+        PendingCall(&self.pending)
+    }
+
+    pub fn record_success(&self) {
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        if !self.healthy.swap(true, Ordering::Relaxed) {
+            tracing::info!("Judge0 instance {} is back in rotation", self.base_url);
+        }
+    }
+
+    /// Counts a failed call; the instance is ejected once `eject_after` calls in a row
+    /// have failed.
+    pub fn record_failure(&self, error: &str, eject_after: u32) {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        self.note(Some(error.to_owned()), false);
+        if failures >= eject_after && self.healthy.swap(false, Ordering::Relaxed) {
+            tracing::warn!(
+                "Judge0 instance {} ejected after {failures} failed call(s): {error}",
+                self.base_url
+            );
+        }
+    }
+
+    fn record_health(&self, result: Result<(), String>) {
+        match result {
+            Ok(()) => {
+                self.note(None, true);
+                self.record_success();
+            }
+            Err(error) => {
+                self.note(Some(error.clone()), true);
+                if self.healthy.swap(false, Ordering::Relaxed) {
+                    tracing::warn!(
+                        "Judge0 instance {} ejected by health check: {error}",
+                        self.base_url
+                    );
+                }
+            }
+        }
+    }
+
+    fn note(&self, error: Option<String>, checked: bool) {
+        let mut health = self.health.lock().expect("health note lock poisoned");
+        if error.is_some() || checked {
+            health.last_error = error;
+        }
+        if checked {
+            health.last_checked_at = Some(Utc::now());
+        }
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    fn status(&self) -> ExecutorInstanceStatus {
+        let health = self.health.lock().expect("health note lock poisoned");
+        ExecutorInstanceStatus {
+            base_url: self.base_url.clone(),
+            healthy: self.is_healthy(),
+            pending: self.pending.load(Ordering::Relaxed),
+            consecutive_failures: self.consecutive_failures.load(Ordering::Relaxed),
+            last_error: health.last_error.clone(),
+            last_checked_at: health.last_checked_at,
+            calls: self.counters.snapshot(),
+        }
+    }
+}
+
+pub struct PendingCall<'a>(&'a AtomicU64);
+
+impl Drop for PendingCall<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+struct PoolState {
+    settings: PoolSettings,
+    source: ExecutorConfigSource,
+    updated_at: Option<DateTime<Utc>>,
+    instances: Vec<Arc<ExecutorInstance>>,
+}
+
+/// The Judge0 instances calls are balanced over. Can be reconfigured while running;
+/// instances that stay keep their health and counters.
+pub struct ExecutorPool {
+    state: RwLock<Arc<PoolState>>,
+    cursor: AtomicUsize,
+    /// Settings from the environment, restored when the stored override is removed.
+    environment: PoolSettings,
+}
+
+impl ExecutorPool {
+    pub fn new(settings: PoolSettings) -> Self {
+        let instances = settings
+            .base_urls
+            .iter()
+            .map(|base_url| Arc::new(ExecutorInstance::new(base_url.clone())))
+            .collect();
+        Self {
+            state: RwLock::new(Arc::new(PoolState {
+                settings: settings.clone(),
+                source: ExecutorConfigSource::Environment,
+                updated_at: None,
+                instances,
+            })),
+            cursor: AtomicUsize::new(0),
+            environment: settings,
+        }
+    }
+
+    pub fn reset_to_environment(&self) {
+        self.reconfigure(
+            self.environment.clone(),
+            ExecutorConfigSource::Environment,
+            None,
+        );
+    }
+
+    fn current(&self) -> Arc<PoolState> {
+        Arc::clone(&self.state.read().expect("executor pool lock poisoned"))
+    }
+
+    pub fn reconfigure(
+        &self,
+        settings: PoolSettings,
+        source: ExecutorConfigSource,
+        updated_at: Option<DateTime<Utc>>,
+    ) {
+        let mut state = self.state.write().expect("executor pool lock poisoned");
+        let instances = settings
+            .base_urls
+            .iter()
+            .map(|base_url| {
+                state
+                    .instances
+                    .iter()
+                    .find(|instance| &instance.base_url == base_url)
+                    .cloned()
+                    .unwrap_or_else(|| Arc::new(ExecutorInstance::new(base_url.clone())))
+            })
+            .collect();
+        tracing::info!(
+            "Judge0 instances set to {} ({})",
+            settings.base_urls.join(", "),
+            settings.strategy.as_str()
+        );
+        *state = Arc::new(PoolState {
+            settings,
+            source,
+            updated_at,
+            instances,
+        });
+    }
+
+    pub fn settings(&self) -> PoolSettings {
+        self.current().settings.clone()
+    }
+
+    /// Instances in the order a call should try them: the one picked by the strategy,
+    /// the other healthy ones, then ejected ones as a last resort.
+    pub fn candidates(&self) -> Vec<Arc<ExecutorInstance>> {
+        let state = self.current();
+        let (mut healthy, ejected): (Vec<_>, Vec<_>) = state
+            .instances
+            .iter()
+            .cloned()
+            .partition(|instance| instance.is_healthy());
+
+        if !healthy.is_empty() {
+            let turn = self.cursor.fetch_add(1, Ordering::Relaxed) % healthy.len();
+            healthy.rotate_left(turn);
+            if state.settings.strategy == BalanceStrategy::LeastPending {
+                // Stable, so instances with equal load still take turns.
+                healthy.sort_by_key(|instance| instance.pending.load(Ordering::Relaxed));
+            }
+        }
+        healthy.extend(ejected);
+        healthy
+    }
+
+    pub fn instance_statuses(&self) -> Vec<ExecutorInstanceStatus> {
+        self.current()
+            .instances
+            .iter()
+            .map(|instance| instance.status())
+            .collect()
+    }
+
+    pub fn describe(&self) -> ExecutorConfigResponse {
+        let state = self.current();
+        ExecutorConfigResponse {
+            base_urls: state.settings.base_urls.clone(),
+            strategy: state.settings.strategy,
+            eject_after_failures: state.settings.eject_after_failures,
+            health_check_interval_secs: state.settings.health_check_interval.as_secs(),
+            source: state.source,
+            updated_at: state.updated_at,
+            instances: state
+                .instances
+                .iter()
+                .map(|instance| instance.status())
+                .collect(),
+        }
+    }
+}
+
+/// Applies the configuration stored through the admin API, if any.
+pub async fn load_stored(db: &impl ConnectionTrait, pool: &ExecutorPool) -> Result<(), AppError> {
+    let Some(model) = executor_config::Entity::find_by_id(CONFIG_ID)
+        .one(db)
+        .await?
+    else {
+        return Ok(());
+    };
+    let base_urls: Vec<String> = serde_json::from_str(&model.base_urls)
+        .map_err(|err| AppError::Internal(format!("stored Judge0 instances are invalid: {err}")))?;
+    let defaults = pool.settings();
+    pool.reconfigure(
+        PoolSettings {
+            base_urls,
+            strategy: BalanceStrategy::from_str(&model.strategy).unwrap_or(defaults.strategy),
+            eject_after_failures: u32::try_from(model.eject_after_failures)
+                .unwrap_or(defaults.eject_after_failures),
+            health_check_interval: u64::try_from(model.health_check_interval_secs)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.health_check_interval),
+        },
+        ExecutorConfigSource::Database,
+        Some(model.updated_at),
+    );
+    Ok(())
+}
+
+pub async fn store(
+    db: &impl ConnectionTrait,
+    settings: &PoolSettings,
+    updated_by: i32,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    let base_urls = serde_json::to_string(&settings.base_urls)
+        .map_err(|err| AppError::Internal(format!("cannot serialize Judge0 instances: {err}")))?;
+    executor_config::Entity::insert(executor_config::ActiveModel {
+        id: Set(CONFIG_ID),
+        base_urls: Set(base_urls),
+        strategy: Set(settings.strategy.as_str().to_owned()),
+        eject_after_failures: Set(i32::try_from(settings.eject_after_failures).unwrap_or(i32::MAX)),
+        health_check_interval_secs: Set(
+            i64::try_from(settings.health_check_interval.as_secs()).unwrap_or(i64::MAX)
+        ),
+        updated_by: Set(Some(updated_by)),
+        updated_at: Set(now),
+    })
+    .on_conflict(
+        OnConflict::column(executor_config::Column::Id)
+            .update_columns([
+                executor_config::Column::BaseUrls,
+                executor_config::Column::Strategy,
+                executor_config::Column::EjectAfterFailures,
+                executor_config::Column::HealthCheckIntervalSecs,
+                executor_config::Column::UpdatedBy,
+                executor_config::Column::UpdatedAt,
+            ])
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn clear_stored(db: &impl ConnectionTrait) -> Result<(), AppError> {
+    executor_config::Entity::delete_by_id(CONFIG_ID)
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Starts the loop that probes every instance's `/about` endpoint, ejecting the ones
+/// that do not answer and readmitting the ones that do.
+pub fn spawn_health_checks(state: AppState) {
+    tokio::spawn(async move {
+        loop {
+            let interval = state.executors.settings().health_check_interval;
+            tokio::time::sleep(interval.max(Duration::from_secs(1))).await;
+            let probes = state
+                .executors
+                .current()
+                .instances
+                .iter()
+                .cloned()
+                .map(|instance| {
+                    let client = state.http_client.clone();
+                    async move {
+                        let result = probe(&client, &instance.base_url).await;
+                        instance.record_health(result);
+                    }
+                })
+                .collect::<Vec<_>>();
+            futures_util::future::join_all(probes).await;
+        }
+    });
+}
+
+/// Probes every instance once for an on-demand check. Health records are left to the
+/// periodic loop.
+pub async fn probe_all(state: &AppState) -> Vec<(String, Result<(), String>)> {
+    let pool = state.executors.current();
+    let probes = pool
+        .instances
+        .iter()
+        .map(|instance| async move {
+            let result = probe(&state.http_client, &instance.base_url).await;
+            (instance.base_url.clone(), result)
+        })
+        .collect::<Vec<_>>();
+    futures_util::future::join_all(probes).await
+}
+
+async fn probe(client: &reqwest::Client, base_url: &str) -> Result<(), String> {
+    let response = client
+        .get(format!("{base_url}/about"))
+        .timeout(HEALTH_CHECK_TIMEOUT)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("health check answered {}", response.status()))
+    }
+}
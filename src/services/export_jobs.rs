@@ -0,0 +1,315 @@
+use std::{collections::HashMap, io::Write as _, time::Duration as StdDuration};
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, QueryOrder, sea_query::Expr,
+};
+use zip::{ZipWriter, write::FileOptions};
+
+use crate::{
+    dto::{ExportJobResponse, ExportKind, ExportStatus, GradeFormat, TimeDisplay},
+    entities::{export_job, submission, user},
+    error::AppError,
+    services::{display_time::TimeFormatter, grades, highlight, signing, task_time},
+    state::AppState,
+};
+
+/// How often the worker looks for queued jobs when idle.
+const POLL_INTERVAL: StdDuration = StdDuration::from_secs(1);
+/// How often expired artifacts are deleted.
+const PRUNE_INTERVAL: StdDuration = StdDuration::from_secs(10 * 60);
+/// How long a finished file is kept for download.
+const ARTIFACT_TTL_HOURS: i64 = 24;
+/// Students written to an archive between progress updates.
+const PROGRESS_STEP: usize = 25;
+
+/// Starts the worker that builds queued exports one at a time. Jobs left running by
+/// a previous process are queued again first.
+pub fn spawn(state: AppState) {
+    tokio::spawn(async move {
+        match requeue_interrupted(&state.db).await {
+            Ok(0) => {}
+            Ok(requeued) => tracing::info!("requeued {requeued} interrupted export job(s)"),
+            Err(err) => tracing::warn!("failed to requeue interrupted export jobs: {err}"),
+        }
+
+        let mut last_prune = tokio::time::Instant::now() - PRUNE_INTERVAL;
+        loop {
+            if last_prune.elapsed() >= PRUNE_INTERVAL {
+                last_prune = tokio::time::Instant::now();
+                match prune(&state).await {
+                    Ok(0) => {}
+                    Ok(pruned) => tracing::info!("deleted {pruned} expired export file(s)"),
+                    Err(err) => tracing::warn!("export pruning failed: {err}"),
+                }
+            }
+
+            match next_job(&state.db).await {
+                Ok(Some(job)) => run(&state, job).await,
+                Ok(None) => tokio::time::sleep(POLL_INTERVAL).await,
+                Err(err) => {
+                    tracing::warn!("failed to poll export jobs: {err}");
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    });
+}
+
+async fn requeue_interrupted(db: &DatabaseConnection) -> Result<u64, AppError> {
+    let result = export_job::Entity::update_many()
+        .col_expr(
+            export_job::Column::Status,
+            Expr::value(ExportStatus::Queued.as_str()),
+        )
+        .col_expr(export_job::Column::Progress, Expr::value(0))
+        .filter(export_job::Column::Status.eq(ExportStatus::Running.as_str()))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+async fn next_job(db: &DatabaseConnection) -> Result<Option<export_job::Model>, AppError> {
+    Ok(export_job::Entity::find()
+        .filter(export_job::Column::Status.eq(ExportStatus::Queued.as_str()))
+        .order_by_asc(export_job::Column::Id)
+        .one(db)
+        .await?)
+}
+
+async fn run(state: &AppState, job: export_job::Model) {
+    let job_id = job.id;
+    let mut job_am = job.clone().into_active_model();
+    job_am.status = Set(ExportStatus::Running.as_str().to_owned());
+    job_am.started_at = Set(Some(Utc::now()));
+    if let Err(err) = job_am.update(&state.db).await {
+        tracing::warn!("failed to start export job {job_id}: {err}");
+        return;
+    }
+
+    let outcome = match build(state, &job).await {
+        Ok(artifact) => store(state, &job, artifact).await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = outcome {
+        tracing::warn!("export job {job_id} failed: {err}");
+        let failed = export_job::Entity::update_many()
+            .col_expr(
+                export_job::Column::Status,
+                Expr::value(ExportStatus::Failed.as_str()),
+            )
+            .col_expr(export_job::Column::Error, Expr::value(err.to_string()))
+            .col_expr(export_job::Column::FinishedAt, Expr::value(Utc::now()))
+            .filter(export_job::Column::Id.eq(job_id))
+            .exec(&state.db)
+            .await;
+        if let Err(err) = failed {
+            tracing::warn!("failed to record export job {job_id} failure: {err}");
+        }
+    }
+}
+
+struct Artifact {
+    file_name: String,
+    content_type: &'static str,
+    bytes: Vec<u8>,
+}
+
+async fn build(state: &AppState, job: &export_job::Model) -> Result<Artifact, AppError> {
+    let kind = ExportKind::from_str(&job.kind)
+        .ok_or_else(|| AppError::Internal(format!("unknown export kind {}", job.kind)))?;
+    let format = GradeFormat::from_str(&job.format).unwrap_or_default();
+    let times = TimeFormatter::new(
+        TimeDisplay::from_str(&job.time_display).unwrap_or(TimeDisplay::Utc),
+        state.display_timezone,
+    );
+    let id = job.classroom_id;
+
+    Ok(match kind {
+        ExportKind::Grades => Artifact {
+            file_name: format!("classroom-{id}-grades-{}.csv", format.as_str()),
+            content_type: "text/csv; charset=utf-8",
+            bytes: grades::export(&state.db, id, format, times)
+                .await?
+                .into_bytes(),
+        },
+        ExportKind::TaskTimes => Artifact {
+            file_name: format!("classroom-{id}-task-times.csv"),
+            content_type: "text/csv; charset=utf-8",
+            bytes: task_time::export(&state.db, id, times).await?.into_bytes(),
+        },
+        ExportKind::Archive => Artifact {
+            file_name: format!("classroom-{id}-export.zip"),
+            content_type: "application/zip",
+            bytes: archive(state, job, format, times).await?,
+        },
+    })
+}
+
+/// Both sheets plus `submissions/<npm>.<ext>` with each student's latest final code.
+async fn archive(
+    state: &AppState,
+    job: &export_job::Model,
+    format: GradeFormat,
+    times: TimeFormatter,
+) -> Result<Vec<u8>, AppError> {
+    let id = job.classroom_id;
+    let grade_sheet = grades::export(&state.db, id, format, times).await?;
+    set_progress(&state.db, job.id, 10).await?;
+    let task_times = task_time::export(&state.db, id, times).await?;
+    set_progress(&state.db, job.id, 20).await?;
+
+    let users = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(id))
+        .order_by_asc(user::Column::Npm)
+        .all(&state.db)
+        .await?;
+    // Ascending order lets later submissions overwrite earlier ones.
+    let mut latest: HashMap<i32, submission::Model> = HashMap::new();
+    for model in submission::Entity::find()
+        .filter(submission::Column::ClassroomId.eq(id))
+        .filter(submission::Column::IsFinal.eq(true))
+        .order_by_asc(submission::Column::Id)
+        .all(&state.db)
+        .await?
+    {
+        latest.insert(model.user_id, model);
+    }
+
+    let zip_error = |err: zip::result::ZipError| AppError::Internal(format!("zip: {err}"));
+    let io_error = |err: std::io::Error| AppError::Internal(format!("zip: {err}"));
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    zip.start_file(format!("grades-{}.csv", format.as_str()), options)
+        .map_err(zip_error)?;
+    zip.write_all(grade_sheet.as_bytes()).map_err(io_error)?;
+    zip.start_file("task-times.csv", options)
+        .map_err(zip_error)?;
+    zip.write_all(task_times.as_bytes()).map_err(io_error)?;
+
+    let total = users.len().max(1);
+    for (index, user_model) in users.iter().enumerate() {
+        if let Some(submission_model) = latest.remove(&user_model.id) {
+            let extension = highlight::extension_for(submission_model.language_id).unwrap_or("txt");
+            zip.start_file(
+                format!("submissions/{}.{extension}", file_safe(&user_model.npm)),
+                options,
+            )
+            .map_err(zip_error)?;
+            zip.write_all(submission_model.source_code.as_bytes())
+                .map_err(io_error)?;
+        }
+        if (index + 1) % PROGRESS_STEP == 0 {
+            let progress = 20 + (index + 1) * 75 / total;
+            set_progress(&state.db, job.id, progress as i32).await?;
+        }
+    }
+
+    Ok(zip.finish().map_err(zip_error)?.into_inner())
+}
+
+/// NPMs are digits in practice; anything else is replaced so it cannot leave the folder.
+fn file_safe(name: &str) -> String {
+    name.chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+async fn set_progress(db: &DatabaseConnection, job_id: i32, progress: i32) -> Result<(), AppError> {
+    export_job::Entity::update_many()
+        .col_expr(export_job::Column::Progress, Expr::value(progress))
+        .filter(export_job::Column::Id.eq(job_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+async fn store(
+    state: &AppState,
+    job: &export_job::Model,
+    artifact: Artifact,
+) -> Result<(), AppError> {
+    state
+        .export_storage
+        .put(&job.storage_key(), &artifact.bytes)
+        .await?;
+
+    let now = Utc::now();
+    let mut job_am = job.clone().into_active_model();
+    job_am.status = Set(ExportStatus::Done.as_str().to_owned());
+    job_am.progress = Set(100);
+    job_am.file_name = Set(Some(artifact.file_name));
+    job_am.content_type = Set(Some(artifact.content_type.to_owned()));
+    job_am.size_bytes = Set(Some(artifact.bytes.len() as i64));
+    job_am.finished_at = Set(Some(now));
+    job_am.expires_at = Set(Some(now + Duration::hours(ARTIFACT_TTL_HOURS)));
+    job_am.update(&state.db).await?;
+    Ok(())
+}
+
+/// Deletes expired files and their jobs.
+async fn prune(state: &AppState) -> Result<usize, AppError> {
+    let expired = export_job::Entity::find()
+        .filter(export_job::Column::ExpiresAt.lt(Utc::now()))
+        .all(&state.db)
+        .await?;
+    for job in &expired {
+        state.export_storage.delete(&job.storage_key()).await?;
+        export_job::Entity::delete_by_id(job.id)
+            .exec(&state.db)
+            .await?;
+    }
+    Ok(expired.len())
+}
+
+/// The job as returned to admins, with a freshly signed download link once it is done.
+pub fn response(state: &AppState, model: export_job::Model) -> ExportJobResponse {
+    let done = model.status == ExportStatus::Done.as_str();
+    let job_id = model.id;
+    let artifact_expires_at = model.expires_at;
+    let mut response = ExportJobResponse::from_model(model);
+    if done {
+        let now = Utc::now();
+        let expires_at = (now + Duration::seconds(state.export_url_ttl_secs as i64))
+            .min(artifact_expires_at.unwrap_or(now));
+        response.download_url = Some(format!(
+            "/api/exports/{job_id}/download?expires={}&signature={}",
+            expires_at.timestamp(),
+            signature(state, job_id, expires_at.timestamp())
+        ));
+        response.download_url_expires_at = Some(expires_at);
+    }
+    response
+}
+
+fn signature(state: &AppState, job_id: i32, expires: i64) -> String {
+    hex::encode(
+        state
+            .secrets
+            .keyed_hash(format!("export-download:{job_id}:{expires}").as_bytes()),
+    )
+}
+
+/// Checks a download link issued by [`response`].
+pub fn verify(
+    state: &AppState,
+    job_id: i32,
+    expires: i64,
+    given: &str,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    let matches = signing::constant_time_eq(&signature(state, job_id, expires), given);
+    if !matches || now.timestamp() > expires {
+        return Err(AppError::Forbidden(
+            "download link is invalid or has expired".into(),
+        ));
+    }
+    Ok(())
+}
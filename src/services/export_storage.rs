@@ -0,0 +1,77 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+
+/// Where finished export files are kept between the job that builds them and the
+/// signed download: a directory on the server's disk.
+pub struct ExportStorage {
+    dir: PathBuf,
+}
+
+impl ExportStorage {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub async fn put(&self, key: &str, bytes: &[u8]) -> Result<(), AppError> {
+        tokio::fs::create_dir_all(&self.dir).await.map_err(|err| {
+            AppError::Internal(format!("cannot create {}: {err}", self.dir.display()))
+        })?;
+        let path = self.path(key);
+        tokio::fs::write(&path, bytes)
+            .await
+            .map_err(|err| AppError::Internal(format!("cannot write {}: {err}", path.display())))
+    }
+
+    /// The stored bytes, or `None` when nothing is stored under `key`.
+    pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, AppError> {
+        let path = self.path(key);
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(AppError::Internal(format!(
+                "cannot read {}: {err}",
+                path.display()
+            ))),
+        }
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<(), AppError> {
+        let path = self.path(key);
+        match tokio::fs::remove_file(&path).await {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(AppError::Internal(
+                format!("cannot delete {}: {err}", path.display()),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Number of stored files and their total size; an absent directory holds nothing.
+    pub async fn usage(&self) -> Result<(u64, u64), AppError> {
+        let read_error = |err: std::io::Error| {
+            AppError::Internal(format!("cannot read {}: {err}", self.dir.display()))
+        };
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok((0, 0)),
+            Err(err) => return Err(read_error(err)),
+        };
+        let (mut files, mut bytes) = (0, 0);
+        while let Some(entry) = entries.next_entry().await.map_err(read_error)? {
+            let metadata = entry.metadata().await.map_err(read_error)?;
+            if metadata.is_file() {
+                files += 1;
+                bytes += metadata.len();
+            }
+        }
+        Ok((files, bytes))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(key)
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{
+    dto::{SubmissionEvent, SubmissionFeedback, TestCaseResult, TestMode, TestRunResponse},
+    entities::{classroom, submission},
+    error::AppError,
+    services::{
+        grading::FinalSubmission, test_runner::CaseOutcome, verdict_messages::VerdictMessages,
+    },
+};
+
+/// Practice runs exist only for their feedback, so they are refused outside practice
+/// mode instead of burning executor time on results nobody may see.
+pub fn ensure_practice_runs(classroom_model: &classroom::Model) -> Result<(), AppError> {
+    if classroom_model.practice_mode {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(
+            "practice runs are disabled while the classroom is not in practice mode".into(),
+        ))
+    }
+}
+
+/// Per-case results as students see them: input, output and expected output only for
+/// visible cases, pass/fail for all. Statuses use the classroom's verdict messages.
+pub fn case_results(outcomes: Vec<CaseOutcome>, verdicts: &VerdictMessages) -> Vec<TestCaseResult> {
+    outcomes
+        .into_iter()
+        .map(|outcome| {
+            let visible = !outcome.test_case.hidden;
+            TestCaseResult {
+                test_case_id: outcome.test_case.id,
+                hidden: outcome.test_case.hidden,
+                passed: outcome.passed,
+                status: outcome.response.status.map(|mut status| {
+                    verdicts.apply(&mut status);
+                    status.description
+                }),
+                stdin: visible.then_some(outcome.test_case.stdin),
+                truncated: visible && outcome.response.truncated,
+                stdout_bytes: outcome.response.stdout_bytes.filter(|_| visible),
+                stdout: if visible {
+                    outcome.response.stdout
+                } else {
+                    None
+                },
+                expected_output: visible.then_some(outcome.test_case.expected_output),
+            }
+        })
+        .collect()
+}
+
+fn hidden_counts(results: &[TestCaseResult]) -> (usize, usize) {
+    let hidden = results.iter().filter(|result| result.hidden);
+    let (total, passed) = hidden.fold((0, 0), |(total, passed), result| {
+        (total + 1, passed + usize::from(result.passed))
+    });
+    (passed, total)
+}
+
+/// Response to a practice run.
+pub fn test_run(
+    task_id: i32,
+    mode: TestMode,
+    total_cases: usize,
+    outcomes: Vec<CaseOutcome>,
+    verdicts: &VerdictMessages,
+) -> TestRunResponse {
+    let results = case_results(outcomes, verdicts);
+    let (hidden_passed_cases, hidden_cases) = hidden_counts(&results);
+    TestRunResponse {
+        task_id,
+        mode,
+        total_cases,
+        executed_cases: results.len(),
+        passed_cases: results.iter().filter(|result| result.passed).count(),
+        hidden_cases,
+        hidden_passed_cases,
+        results,
+    }
+}
+
+/// Receipt fields only, as returned outside practice mode.
+pub fn receipt(
+    classroom_model: &classroom::Model,
+    submission_model: &submission::Model,
+) -> SubmissionFeedback {
+    SubmissionFeedback {
+        submission_id: submission_model.id,
+        received_at: submission_model.created_at,
+        practice_mode: classroom_model.practice_mode,
+        late: submission_model.late,
+        result: None,
+        passed_cases: None,
+        total_cases: None,
+        hidden_passed_cases: None,
+        hidden_cases: None,
+        score: None,
+        max_score: None,
+        cases: None,
+    }
+}
+
+/// Response to a final submission.
+pub fn final_submission(
+    classroom_model: &classroom::Model,
+    graded: FinalSubmission,
+    verdicts: &VerdictMessages,
+) -> SubmissionFeedback {
+    let FinalSubmission {
+        submission,
+        mut response,
+        cases,
+    } = graded;
+    let mut feedback = receipt(classroom_model, &submission);
+    if !classroom_model.practice_mode {
+        return feedback;
+    }
+
+    let representative_hidden = cases
+        .iter()
+        .find(|outcome| !outcome.passed)
+        .or(cases.last())
+        .is_some_and(|outcome| outcome.test_case.hidden);
+    if representative_hidden {
+        response.stdout = None;
+        response.stdout_bytes = None;
+    }
+
+    if let Some(status) = &mut response.status {
+        verdicts.apply(status);
+    }
+    let results = case_results(cases, verdicts);
+    let (hidden_passed_cases, hidden_cases) = hidden_counts(&results);
+    feedback.result = Some(response);
+    feedback.passed_cases = submission.passed_cases;
+    feedback.total_cases = submission.total_cases;
+    feedback.score = submission.score;
+    feedback.max_score = submission.max_score;
+    if !results.is_empty() {
+        feedback.hidden_passed_cases = Some(hidden_passed_cases);
+        feedback.hidden_cases = Some(hidden_cases);
+        feedback.cases = Some(results);
+    }
+    feedback
+}
+
+/// `submission` SSE event for the student; grade fields are left out outside practice
+/// mode, so the event only says the hand-in was processed.
+pub fn submission_event(
+    classroom_model: &classroom::Model,
+    submission_model: &submission::Model,
+) -> SubmissionEvent {
+    let mut event = SubmissionEvent::from(submission_model);
+    if !classroom_model.practice_mode {
+        event.passed_cases = None;
+        event.total_cases = None;
+        event.score = None;
+        event.max_score = None;
+    }
+    event
+}
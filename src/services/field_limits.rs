@@ -0,0 +1,127 @@
+//! Character caps on student code and task descriptions. The server defaults come from
+//! `MAX_CODE_CHARS` and `MAX_DESCRIPTION_CHARS`; a classroom may set its own. Bodies are
+//! checked by [`ValidatedJson`](crate::dto::ValidatedJson), and `GET /api/client-config`
+//! reports the same numbers so editors can count against them.
+
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
+
+use crate::{
+    entities::{classroom, user},
+    error::AppError,
+    state::AppState,
+};
+
+/// Largest cap a deployment or classroom may set, in characters.
+pub const MAX_LIMIT_CHARS: i32 = 1_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldLimits {
+    pub code_chars: usize,
+    pub description_chars: usize,
+}
+
+impl FieldLimits {
+    pub fn new(code_chars: usize, description_chars: usize) -> Self {
+        let clamp = |chars: usize| chars.clamp(1, MAX_LIMIT_CHARS as usize);
+        Self {
+            code_chars: clamp(code_chars),
+            description_chars: clamp(description_chars),
+        }
+    }
+
+    /// These limits with the positive values among `code_chars` and
+    /// `description_chars` taking their place.
+    pub fn with_overrides(self, code_chars: Option<i32>, description_chars: Option<i32>) -> Self {
+        let positive = |chars: Option<i32>| {
+            chars
+                .and_then(|chars| usize::try_from(chars).ok())
+                .filter(|chars| *chars > 0)
+        };
+        Self::new(
+            positive(code_chars).unwrap_or(self.code_chars),
+            positive(description_chars).unwrap_or(self.description_chars),
+        )
+    }
+
+    /// Limits of the classroom: its own settings or the server defaults.
+    pub fn for_classroom(state: &AppState, classroom_model: &classroom::Model) -> Self {
+        state.field_limits.with_overrides(
+            classroom_model.max_code_chars,
+            classroom_model.max_description_chars,
+        )
+    }
+
+    /// Limits of classroom `classroom_id`, or of the only classroom `npm` is enrolled in.
+    /// Falls back to the server defaults when neither names a classroom.
+    pub async fn resolve(
+        state: &AppState,
+        classroom_id: Option<i32>,
+        npm: Option<&str>,
+    ) -> Result<Self, AppError> {
+        let classroom_id = match (classroom_id, npm.map(str::trim)) {
+            (Some(classroom_id), _) => Some(classroom_id),
+            (None, Some(npm)) if !npm.is_empty() => {
+                let enrolled: Vec<i32> = user::Entity::find()
+                    .filter(user::Column::Npm.eq(npm))
+                    .select_only()
+                    .column(user::Column::ClassroomId)
+                    .limit(2)
+                    .into_tuple()
+                    .all(&state.db)
+                    .await?;
+                match enrolled.as_slice() {
+                    [classroom_id] => Some(*classroom_id),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        let Some(classroom_id) = classroom_id else {
+            return Ok(state.field_limits);
+        };
+        Ok(classroom::Entity::find_by_id(classroom_id)
+            .one(&state.db)
+            .await?
+            .map_or(state.field_limits, |classroom_model| {
+                Self::for_classroom(state, &classroom_model)
+            }))
+    }
+
+    pub fn check_code(&self, field: impl Into<String>, code: &str) -> Result<(), AppError> {
+        check(field, code, self.code_chars)
+    }
+
+    pub fn check_description(
+        &self,
+        field: impl Into<String>,
+        description: &str,
+    ) -> Result<(), AppError> {
+        check(field, description, self.description_chars)
+    }
+}
+
+fn check(field: impl Into<String>, value: &str, limit: usize) -> Result<(), AppError> {
+    // Cheap bound first: a string has at most as many characters as bytes.
+    if value.len() <= limit {
+        return Ok(());
+    }
+    let length = value.chars().count();
+    if length > limit {
+        return Err(AppError::FieldTooLong {
+            field: field.into(),
+            limit,
+            length,
+        });
+    }
+    Ok(())
+}
+
+/// Validates a classroom's `maxCodeChars`/`maxDescriptionChars`; `0` clears it.
+pub fn validate_setting(name: &str, chars: i32) -> Result<Option<i32>, AppError> {
+    if !(0..=MAX_LIMIT_CHARS).contains(&chars) {
+        return Err(AppError::BadRequest(format!(
+            "{name} must be between 0 and {MAX_LIMIT_CHARS}"
+        )));
+    }
+    Ok((chars > 0).then_some(chars))
+}
@@ -0,0 +1,191 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QuerySelect,
+    TransactionTrait,
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    dto::{ClassroomStatus, GradingStatus},
+    entities::{classroom, submission, user},
+    error::AppError,
+    services::{archive, exam_clock::ExamClock, grading, post_finish, student_events},
+    state::AppState,
+};
+
+/// How often the background task looks for expired exams.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Serializes finalization so the background sweep and the admin endpoint never
+/// auto-submit the same student twice.
+static FINALIZE_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Starts the background sweep that finalizes exams as soon as they expire.
+pub fn spawn(state: AppState) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(err) = sweep(&state).await {
+                tracing::warn!("exam finalizer sweep failed: {err}");
+            }
+        }
+    });
+}
+
+async fn sweep(state: &AppState) -> Result<(), AppError> {
+    // Pauses only push the deadline back, so the scheduled end is a safe pre-filter.
+    let classrooms = classroom::Entity::find()
+        .filter(classroom::Column::IsExam.eq(true))
+        .filter(classroom::Column::Status.eq(ClassroomStatus::Published.as_str()))
+        .filter(classroom::Column::ExamEnd.lte(Utc::now()))
+        .all(&state.db)
+        .await?;
+
+    for classroom_model in classrooms {
+        let finalized = finalize_expired_exam(state, &classroom_model).await?;
+        if finalized > 0 {
+            tracing::info!(
+                "auto-submitted {finalized} exam(s) in classroom {}",
+                classroom_model.id
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Once hand-ins have closed (the end plus the time policy's tolerance and grace),
+/// applies the classroom's post-finish policy to every still-active student without a
+/// final submission and hands in their last saved code as an `auto_submitted` final
+/// submission. Students already deactivated (finished, or removed by a proctor) are
+/// left alone, which also keeps exams from before this existed untouched.
+///
+/// The hand-ins are graded in the background once recorded, so neither the lock nor
+/// the caller waits for Judge0. Returns how many students were finalized.
+pub async fn finalize_expired_exam(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+) -> Result<usize, AppError> {
+    let clock = ExamClock::load(&state.db, classroom_model).await?;
+    if clock.end.is_none() || !state.time_policy.submissions_closed(&clock, Utc::now()) {
+        return Ok(0);
+    }
+
+    let guard = FINALIZE_LOCK.lock().await;
+
+    let finalized_user_ids: Vec<i32> = submission::Entity::find()
+        .filter(submission::Column::ClassroomId.eq(classroom_model.id))
+        .filter(submission::Column::IsFinal.eq(true))
+        .select_only()
+        .column(submission::Column::UserId)
+        .into_tuple()
+        .all(&state.db)
+        .await?;
+
+    let pending = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(classroom_model.id))
+        .filter(user::Column::Active.eq(true))
+        .filter(user::Column::Id.is_not_in(finalized_user_ids))
+        .all(&state.db)
+        .await?;
+
+    let mut count = 0;
+    let mut recorded = Vec::new();
+    for user_model in pending {
+        let user_id = user_model.id;
+        let source_code = user_model.code.clone();
+        let language_id = user_model
+            .language_id
+            .unwrap_or_else(|| grading::classroom_language_id(classroom_model));
+
+        let now = Utc::now();
+        let mut user_am = user_model.into_active_model();
+        post_finish::apply(classroom_model, &mut user_am, now);
+        user_am.updated_at = sea_orm::ActiveValue::Set(now);
+
+        if source_code.trim().is_empty() {
+            let txn = state.db.begin().await?;
+            let submission_model = submission::ActiveModel {
+                classroom_id: sea_orm::ActiveValue::Set(classroom_model.id),
+                user_id: sea_orm::ActiveValue::Set(user_id),
+                source_code: sea_orm::ActiveValue::Set(source_code),
+                language_id: sea_orm::ActiveValue::Set(language_id),
+                is_final: sea_orm::ActiveValue::Set(true),
+                auto_submitted: sea_orm::ActiveValue::Set(true),
+                task_id: sea_orm::ActiveValue::Set(None),
+                late: sea_orm::ActiveValue::Set(false),
+                grading_attempts: sea_orm::ActiveValue::Set(0),
+                grading_error: sea_orm::ActiveValue::Set(Some(
+                    "no code was saved before the deadline".into(),
+                )),
+                grading_status: sea_orm::ActiveValue::Set(Some(
+                    GradingStatus::Failed.as_str().to_owned(),
+                )),
+                created_at: sea_orm::ActiveValue::Set(Utc::now()),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+            archive::enqueue(&txn, state, submission_model.id).await?;
+            let user_model = user_am.update(&txn).await?;
+            txn.commit().await?;
+            archive::wake(state);
+            student_events::finished(state, &user_model, &submission_model).await;
+            count += 1;
+            continue;
+        }
+
+        // Until the hand-in is stored the student stays active, so a failed insert is
+        // retried by the next sweep.
+        match grading::record_final(
+            state,
+            classroom_model,
+            user_id,
+            source_code.into_inner(),
+            language_id,
+            None,
+            true,
+        )
+        .await
+        {
+            Ok(submission_model) => {
+                let user_model = user_am.update(&state.db).await?;
+                count += 1;
+                student_events::finished(state, &user_model, &submission_model).await;
+                recorded.push(submission_model);
+            }
+            Err(err) => {
+                tracing::warn!("recording auto-submission of user {user_id} failed: {err}")
+            }
+        }
+    }
+    drop(guard);
+
+    spawn_grading(state.clone(), classroom_model.clone(), recorded);
+    Ok(count)
+}
+
+/// Grades recorded auto-submissions one Judge0 slot at a time. A Judge0 failure is kept
+/// on the submission for the retry worker; the other students still get graded.
+fn spawn_grading(
+    state: AppState,
+    classroom_model: classroom::Model,
+    submissions: Vec<submission::Model>,
+) {
+    if submissions.is_empty() {
+        return;
+    }
+    tokio::spawn(async move {
+        for submission_model in submissions {
+            let submission_id = submission_model.id;
+            let _slot = state.executor_queue.admit().await;
+            if let Err(err) = grading::grade_final(&state, &classroom_model, submission_model).await
+            {
+                tracing::warn!("grading auto-submission {submission_id} failed: {err}");
+            }
+        }
+    });
+}
@@ -0,0 +1,335 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, EntityTrait, PaginatorTrait,
+    QueryFilter, QuerySelect, QueryTrait, sea_query::OnConflict,
+};
+
+use crate::{
+    dto::IntegrityAlertEvent,
+    entities::{
+        classroom, code_fingerprint, fingerprint_settings, integrity_event, submission, user,
+    },
+    error::AppError,
+    services::{
+        event_bus::{Audience, EventKind},
+        secret_box::SecretBox,
+    },
+    state::AppState,
+};
+
+/// Retention used until an admin sets one: two years, long enough to span the
+/// semesters a course is repeated in.
+pub const DEFAULT_RETENTION_DAYS: u32 = 730;
+pub const MAX_RETENTION_DAYS: u32 = 3650;
+const SETTINGS_ID: i32 = 1;
+
+/// Tokens per hashed k-gram; shorter matches are too common to mean anything.
+const KGRAM_TOKENS: usize = 8;
+/// Winnowing window: every run of this many consecutive k-grams keeps at least one
+/// fingerprint, so any shared stretch of `KGRAM_TOKENS + WINDOW - 1` tokens is found.
+const WINDOW: usize = 4;
+/// Submissions with fewer fingerprints are neither stored nor compared.
+const MIN_FINGERPRINTS: usize = 8;
+/// Share of a submission's fingerprints found in one earlier submission that raises
+/// an integrity event.
+const FLAG_RATIO: f64 = 0.6;
+/// Hashes per `IN (...)` query and rows per insert, under SQLite's variable limit.
+const BATCH: usize = 500;
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Keyed, winnowed k-gram hashes of `source`. Comments and whitespace are dropped
+/// and case is folded first, so reformatting or recommenting a copy does not hide it.
+pub fn fingerprints(secrets: &SecretBox, source: &str, language_id: i32) -> Vec<i64> {
+    let tokens = tokens(source, language_id);
+    if tokens.len() < KGRAM_TOKENS {
+        return Vec::new();
+    }
+    let hashes: Vec<i64> = tokens
+        .windows(KGRAM_TOKENS)
+        .map(|kgram| truncate(&secrets.keyed_hash(kgram.join(" ").as_bytes())))
+        .collect();
+
+    let mut selected = HashSet::new();
+    for window in hashes.windows(WINDOW.min(hashes.len())) {
+        // Rightmost minimum, as in the winnowing paper, so runs of equal hashes
+        // keep one fingerprint each.
+        let (_, &min) = window
+            .iter()
+            .enumerate()
+            .min_by_key(|&(position, hash)| (*hash, std::cmp::Reverse(position)))
+            .expect("windows are never empty");
+        selected.insert(min);
+    }
+    let mut fingerprints: Vec<i64> = selected.into_iter().collect();
+    fingerprints.sort_unstable();
+    fingerprints
+}
+
+fn tokens(source: &str, language_id: i32) -> Vec<String> {
+    let comment = line_comment(language_id);
+    let mut tokens = Vec::new();
+    for line in source.lines() {
+        let code = line.split(comment).next().unwrap_or_default();
+        let mut word = String::new();
+        for ch in code.chars() {
+            if ch.is_alphanumeric() || ch == '_' || ch == '.' {
+                word.extend(ch.to_lowercase());
+                continue;
+            }
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            if !ch.is_whitespace() {
+                tokens.push(ch.to_string());
+            }
+        }
+        if !word.is_empty() {
+            tokens.push(word);
+        }
+    }
+    tokens
+}
+
+/// Line comment marker of a Judge0 language.
+fn line_comment(language_id: i32) -> &'static str {
+    match language_id {
+        45 => ";",
+        46 | 70 | 71 | 72 | 80 => "#",
+        64 => "--",
+        _ => "//",
+    }
+}
+
+fn owner(secrets: &SecretBox, npm: &str) -> i64 {
+    truncate(&secrets.keyed_hash(format!("owner:{npm}").as_bytes()))
+}
+
+fn truncate(hash: &[u8; 32]) -> i64 {
+    i64::from_be_bytes(hash[..8].try_into().expect("hash is longer than 8 bytes"))
+}
+
+/// Fingerprints a final submission into the shared store and flags it when most of
+/// it matches another student's submission from a different classroom. Classrooms
+/// that opted out are skipped. Failures are logged, never surfaced to the caller.
+pub async fn record(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+    submission_model: &submission::Model,
+) {
+    if !classroom_model.fingerprint_sharing {
+        return;
+    }
+    if let Err(err) = check_and_store(state, classroom_model, submission_model).await {
+        tracing::warn!(
+            "failed to fingerprint submission {}: {err}",
+            submission_model.id
+        );
+    }
+}
+
+async fn check_and_store(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+    submission_model: &submission::Model,
+) -> Result<(), AppError> {
+    let fingerprints = fingerprints(
+        &state.secrets,
+        &submission_model.source_code,
+        submission_model.language_id,
+    );
+    if fingerprints.len() < MIN_FINGERPRINTS {
+        return Ok(());
+    }
+    let Some(user_model) = user::Entity::find_by_id(submission_model.user_id)
+        .one(&state.db)
+        .await?
+    else {
+        return Ok(());
+    };
+    let owner = owner(&state.secrets, &user_model.npm);
+    let cutoff = cutoff(retention_days(state).await?, Utc::now());
+
+    // Distinct matched hashes per earlier submission.
+    let mut shared: HashMap<(i32, i32), HashSet<i64>> = HashMap::new();
+    for batch in fingerprints.chunks(BATCH) {
+        let rows: Vec<(i32, i32, i64)> = code_fingerprint::Entity::find()
+            .select_only()
+            .column(code_fingerprint::Column::SubmissionId)
+            .column(code_fingerprint::Column::ClassroomId)
+            .column(code_fingerprint::Column::Hash)
+            .filter(code_fingerprint::Column::Hash.is_in(batch.iter().copied()))
+            .filter(code_fingerprint::Column::ClassroomId.ne(classroom_model.id))
+            .filter(code_fingerprint::Column::Owner.ne(owner))
+            .apply_if(cutoff, |query, cutoff| {
+                query.filter(code_fingerprint::Column::CreatedAt.gte(cutoff))
+            })
+            .into_tuple()
+            .all(&state.db)
+            .await?;
+        for (submission_id, classroom_id, hash) in rows {
+            shared
+                .entry((submission_id, classroom_id))
+                .or_default()
+                .insert(hash);
+        }
+    }
+
+    let now = Utc::now();
+    for batch in fingerprints.chunks(BATCH) {
+        code_fingerprint::Entity::insert_many(batch.iter().map(|&hash| {
+            code_fingerprint::ActiveModel {
+                hash: Set(hash),
+                submission_id: Set(submission_model.id),
+                classroom_id: Set(classroom_model.id),
+                owner: Set(owner),
+                created_at: Set(now),
+                ..Default::default()
+            }
+        }))
+        .exec_without_returning(&state.db)
+        .await?;
+    }
+
+    let Some(((earlier_submission, earlier_classroom), hashes)) = shared
+        .into_iter()
+        .max_by_key(|(key, hashes)| (hashes.len(), std::cmp::Reverse(*key)))
+    else {
+        return Ok(());
+    };
+    let ratio = hashes.len() as f64 / fingerprints.len() as f64;
+    if ratio < FLAG_RATIO {
+        return Ok(());
+    }
+
+    let source = classroom::Entity::find_by_id(earlier_classroom)
+        .one(&state.db)
+        .await?
+        .map(|model| match model.term {
+            Some(term) => format!("\"{}\" ({term})", model.name),
+            None => format!("\"{}\"", model.name),
+        })
+        .unwrap_or_else(|| format!("deleted classroom {earlier_classroom}"));
+    let detail = format!(
+        "{:.0}% of submission {}'s fingerprints match submission {earlier_submission} from classroom {source}",
+        ratio * 100.0,
+        submission_model.id
+    );
+    tracing::warn!("integrity event for {}: {detail}", user_model.npm);
+
+    let recorded = integrity_event::ActiveModel {
+        classroom_id: Set(classroom_model.id),
+        user_id: Set(user_model.id),
+        kind: Set("similar_code".to_owned()),
+        detail: Set(detail),
+        blocked: Set(false),
+        created_at: Set(now),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await?;
+    state
+        .events
+        .publish(
+            &state.db,
+            classroom_model.id,
+            Audience::Staff,
+            EventKind::Integrity(IntegrityAlertEvent::from_models(&recorded, &user_model)),
+        )
+        .await;
+    Ok(())
+}
+
+/// Retention in days: the stored setting, else `FINGERPRINT_RETENTION_DAYS`.
+pub async fn retention_days(state: &AppState) -> Result<u32, AppError> {
+    Ok(stored(&state.db)
+        .await?
+        .and_then(|model| u32::try_from(model.retention_days).ok())
+        .unwrap_or(state.fingerprint_retention_days))
+}
+
+pub async fn stored(
+    db: &impl ConnectionTrait,
+) -> Result<Option<fingerprint_settings::Model>, AppError> {
+    Ok(fingerprint_settings::Entity::find_by_id(SETTINGS_ID)
+        .one(db)
+        .await?)
+}
+
+pub async fn store_retention(
+    db: &impl ConnectionTrait,
+    retention_days: u32,
+    updated_by: i32,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    fingerprint_settings::Entity::insert(fingerprint_settings::ActiveModel {
+        id: Set(SETTINGS_ID),
+        retention_days: Set(i32::try_from(retention_days).unwrap_or(i32::MAX)),
+        updated_by: Set(Some(updated_by)),
+        updated_at: Set(now),
+    })
+    .on_conflict(
+        OnConflict::column(fingerprint_settings::Column::Id)
+            .update_columns([
+                fingerprint_settings::Column::RetentionDays,
+                fingerprint_settings::Column::UpdatedBy,
+                fingerprint_settings::Column::UpdatedAt,
+            ])
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn count(db: &impl ConnectionTrait) -> Result<u64, AppError> {
+    Ok(code_fingerprint::Entity::find().count(db).await?)
+}
+
+/// Drops every fingerprint taken from a classroom, e.g. when it opts out.
+pub async fn forget_classroom(
+    db: &impl ConnectionTrait,
+    classroom_id: i32,
+) -> Result<u64, AppError> {
+    let result = code_fingerprint::Entity::delete_many()
+        .filter(code_fingerprint::Column::ClassroomId.eq(classroom_id))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+fn cutoff(retention_days: u32, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    (retention_days > 0).then(|| now - chrono::Duration::days(i64::from(retention_days)))
+}
+
+/// Deletes fingerprints older than the retention period.
+pub async fn prune(state: &AppState) -> Result<u64, AppError> {
+    let Some(cutoff) = cutoff(retention_days(state).await?, Utc::now()) else {
+        return Ok(0);
+    };
+    let result = code_fingerprint::Entity::delete_many()
+        .filter(code_fingerprint::Column::CreatedAt.lt(cutoff))
+        .exec(&state.db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+/// Starts the background job that applies the retention period. It is read on
+/// every run, so a change through the admin API applies without a restart.
+pub fn spawn_pruning(state: AppState) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(PRUNE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            match prune(&state).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("pruned {deleted} expired code fingerprint(s)"),
+                Err(err) => tracing::warn!("code fingerprint pruning failed: {err}"),
+            }
+        }
+    });
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    dto::GradeFormat,
+    entities::{account, classroom, submission, user},
+    error::AppError,
+    services::{csv, display_time::TimeFormatter},
+};
+
+/// Grade sheet of the classroom: one row per student with their latest final
+/// submission, or empty grade columns when they have not handed in.
+pub async fn export(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+    format: GradeFormat,
+    times: TimeFormatter,
+) -> Result<String, AppError> {
+    let classroom_model = classroom::Entity::find_by_id(classroom_id)
+        .one(db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+
+    let users = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(classroom_id))
+        .order_by_asc(user::Column::Npm)
+        .all(db)
+        .await?;
+
+    // Ascending order lets later submissions overwrite earlier ones.
+    let mut latest: HashMap<i32, submission::Model> = HashMap::new();
+    for model in submission::Entity::find()
+        .filter(submission::Column::ClassroomId.eq(classroom_id))
+        .filter(submission::Column::IsFinal.eq(true))
+        .order_by_asc(submission::Column::Id)
+        .all(db)
+        .await?
+    {
+        latest.insert(model.user_id, model);
+    }
+
+    // Only verified addresses are trusted for matching students in an LMS.
+    let emails: HashMap<String, String> = account::Entity::find()
+        .filter(account::Column::Npm.is_in(users.iter().map(|user_model| user_model.npm.clone())))
+        .filter(account::Column::EmailVerifiedAt.is_not_null())
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|model| Some((model.npm, model.email?)))
+        .collect();
+
+    let rows: Vec<(user::Model, Option<submission::Model>)> = users
+        .into_iter()
+        .map(|user_model| {
+            let submission_model = latest.remove(&user_model.id);
+            (user_model, submission_model)
+        })
+        .collect();
+
+    Ok(match format {
+        GradeFormat::Csv => plain_csv(&rows, times),
+        GradeFormat::Moodle => moodle_csv(&classroom_model, &rows, &emails),
+        GradeFormat::Canvas => canvas_csv(&classroom_model, &rows),
+    })
+}
+
+fn plain_csv(rows: &[(user::Model, Option<submission::Model>)], times: TimeFormatter) -> String {
+    let mut out = String::new();
+    let mut header: Vec<String> = [
+        "npm",
+        "name",
+        "score",
+        "max_score",
+        "passed_cases",
+        "total_cases",
+        "auto_submitted",
+    ]
+    .map(String::from)
+    .to_vec();
+    header.extend(times.columns("submitted_at"));
+    header.extend(times.columns("graded_at"));
+    header.push("feedback".to_string());
+    push_row(&mut out, &header);
+
+    for (user_model, submission_model) in rows {
+        let submission_model = submission_model.as_ref();
+        let mut fields = vec![
+            csv::field(&user_model.npm),
+            csv::field(&user_model.name),
+            optional(submission_model.and_then(|model| model.score)),
+            optional(submission_model.and_then(|model| model.max_score)),
+            optional(submission_model.and_then(|model| model.passed_cases)),
+            optional(submission_model.and_then(|model| model.total_cases)),
+            submission_model
+                .map(|model| model.auto_submitted.to_string())
+                .unwrap_or_default(),
+        ];
+        fields.extend(times.cells(submission_model.map(|model| model.created_at)));
+        fields.extend(times.cells(submission_model.and_then(|model| model.graded_at)));
+        fields.push(csv::field(&feedback(submission_model)));
+        push_row(&mut out, &fields);
+    }
+    out
+}
+
+/// Moodle's gradebook import: `<item> (Real)` grade column plus a feedback column,
+/// with the NPM as ID number and the verified email so the import can match users
+/// on either.
+fn moodle_csv(
+    classroom_model: &classroom::Model,
+    rows: &[(user::Model, Option<submission::Model>)],
+    emails: &HashMap<String, String>,
+) -> String {
+    let mut out = String::new();
+    push_row(
+        &mut out,
+        &[
+            "First name".to_string(),
+            "Last name".to_string(),
+            "ID number".to_string(),
+            "Email address".to_string(),
+            csv::field(&format!("{} (Real)", classroom_model.name)),
+            csv::field(&format!("{} (Feedback)", classroom_model.name)),
+        ],
+    );
+    for (user_model, submission_model) in rows {
+        let (first_name, last_name) = split_name(&user_model.name);
+        push_row(
+            &mut out,
+            &[
+                csv::field(first_name),
+                csv::field(last_name),
+                csv::field(&user_model.npm),
+                emails
+                    .get(&user_model.npm)
+                    .map(|email| csv::field(email))
+                    .unwrap_or_default(),
+                optional(submission_model.as_ref().and_then(|model| model.score)),
+                csv::field(&feedback(submission_model.as_ref())),
+            ],
+        );
+    }
+    out
+}
+
+/// Canvas's gradebook import: students matched on `SIS User ID`, with the points
+/// possible on the row after the header.
+fn canvas_csv(
+    classroom_model: &classroom::Model,
+    rows: &[(user::Model, Option<submission::Model>)],
+) -> String {
+    let points_possible = rows
+        .iter()
+        .filter_map(|(_, submission_model)| submission_model.as_ref()?.max_score)
+        .max();
+
+    let mut out = String::new();
+    push_row(
+        &mut out,
+        &[
+            "Student".to_string(),
+            "ID".to_string(),
+            "SIS User ID".to_string(),
+            "SIS Login ID".to_string(),
+            "Section".to_string(),
+            csv::field(&classroom_model.name),
+        ],
+    );
+    push_row(
+        &mut out,
+        &[
+            "Points Possible".to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            optional(points_possible),
+        ],
+    );
+    for (user_model, submission_model) in rows {
+        push_row(
+            &mut out,
+            &[
+                csv::field(&user_model.name),
+                String::new(),
+                csv::field(&user_model.npm),
+                csv::field(&user_model.npm),
+                String::new(),
+                optional(submission_model.as_ref().and_then(|model| model.score)),
+            ],
+        );
+    }
+    out
+}
+
+/// Short grading comment: passed test cases, the grading script's `feedback` and
+/// any grading error.
+fn feedback(submission_model: Option<&submission::Model>) -> String {
+    let Some(model) = submission_model else {
+        return "No submission".to_string();
+    };
+
+    let mut parts = Vec::new();
+    if let (Some(passed), Some(total)) = (model.passed_cases, model.total_cases) {
+        parts.push(format!("{passed}/{total} test cases passed"));
+    }
+    if let Some(script_feedback) = model
+        .script_verdict
+        .as_deref()
+        .and_then(|verdict| serde_json::from_str::<serde_json::Value>(verdict).ok())
+        .and_then(|verdict| verdict.get("feedback")?.as_str().map(str::to_owned))
+    {
+        parts.push(script_feedback);
+    }
+    if model.auto_submitted {
+        parts.push("Auto-submitted when the exam ended".to_string());
+    }
+    if let Some(error) = &model.grading_error {
+        parts.push(format!("Grading error: {error}"));
+    }
+    parts.join("; ")
+}
+
+/// Splits at the last space; single-word names go into the first name.
+fn split_name(name: &str) -> (&str, &str) {
+    match name.trim().rsplit_once(' ') {
+        Some((first, last)) => (first.trim(), last),
+        None => (name.trim(), ""),
+    }
+}
+
+fn optional(value: Option<i32>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn push_row(out: &mut String, fields: &[String]) {
+    out.push_str(&fields.join(","));
+    out.push('\n');
+}
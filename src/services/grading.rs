@@ -0,0 +1,499 @@
+use std::{future::Future, sync::atomic::Ordering};
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+
+use crate::{
+    dto::{
+        GradingScriptCase, GradingScriptInput, GradingScriptVerdict, GradingStatus,
+        Judge0SubmissionRequest, Judge0SubmissionResponse, SandboxFlags,
+    },
+    entities::{classroom, submission, task, test_case},
+    error::AppError,
+    services::{
+        archive, case_stats,
+        event_bus::{Audience, EventKind},
+        feedback, fingerprint, grading_progress, grading_retry, judge0, output, task_deadline,
+        test_runner::{self, CaseOutcome},
+    },
+    state::AppState,
+};
+
+/// Judge0 language used when neither the request nor the student's last save names one
+/// and the classroom language does not map to one.
+pub const DEFAULT_LANGUAGE_ID: i32 = 63;
+
+/// Judge0 language the classroom's `programming_language` maps to, else the default.
+pub fn classroom_language_id(classroom_model: &classroom::Model) -> i32 {
+    classroom_model
+        .resolved_language_id
+        .unwrap_or(DEFAULT_LANGUAGE_ID)
+}
+
+/// Language of a run scoped to a task: the requested one, else the task's override,
+/// else the classroom's. In a classroom with a locked language, a task that overrides it
+/// only accepts its own language.
+pub fn task_language_id(
+    classroom_model: &classroom::Model,
+    task_model: &task::Model,
+    requested: Option<i32>,
+) -> Result<i32, AppError> {
+    match (task_model.language_id, requested) {
+        (Some(task_language), Some(requested))
+            if classroom_model.language_locked && requested != task_language =>
+        {
+            Err(AppError::BadRequest(format!(
+                "task {} must be run with language {task_language}",
+                task_model.id
+            )))
+        }
+        (task_language, requested) => Ok(requested
+            .or(task_language)
+            .unwrap_or_else(|| classroom_language_id(classroom_model))),
+    }
+}
+
+/// A graded final submission with everything the feedback layer may show the student.
+pub struct FinalSubmission {
+    pub submission: submission::Model,
+    /// Run the grade is based on: the first failing case, otherwise the last one.
+    pub response: Judge0SubmissionResponse,
+    /// Outcome of every test case; empty when the classroom has none.
+    pub cases: Vec<CaseOutcome>,
+}
+
+/// Stores a hand-in as `queued` and tells the student, so they see it waiting while it
+/// queues for a Judge0 slot; [`grade_final`] grades it. The hand-in is tagged late
+/// when it comes after the due date of `task_id` (see [`task_deadline::is_late`]).
+pub async fn record_final(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+    user_id: i32,
+    source_code: String,
+    language_id: i32,
+    task_id: Option<i32>,
+    auto_submitted: bool,
+) -> Result<submission::Model, AppError> {
+    let classroom_id = classroom_model.id;
+    let now = Utc::now();
+    let late = task_deadline::is_late(&state.db, classroom_id, task_id, now).await?;
+
+    let txn = state.db.begin().await?;
+    let submission_model = submission::ActiveModel {
+        classroom_id: sea_orm::ActiveValue::Set(classroom_id),
+        user_id: sea_orm::ActiveValue::Set(user_id),
+        source_code: sea_orm::ActiveValue::Set(source_code.into()),
+        language_id: sea_orm::ActiveValue::Set(language_id),
+        is_final: sea_orm::ActiveValue::Set(true),
+        auto_submitted: sea_orm::ActiveValue::Set(auto_submitted),
+        task_id: sea_orm::ActiveValue::Set(task_id),
+        late: sea_orm::ActiveValue::Set(late),
+        grading_attempts: sea_orm::ActiveValue::Set(0),
+        grading_status: sea_orm::ActiveValue::Set(Some(GradingStatus::Queued.as_str().to_owned())),
+        created_at: sea_orm::ActiveValue::Set(now),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+    archive::enqueue(&txn, state, submission_model.id).await?;
+    txn.commit().await?;
+    archive::wake(state);
+    grading_progress::publish(state, &submission_model).await;
+    Ok(submission_model)
+}
+
+/// Runs `grading` in a task of its own, so a client disconnect cannot leave a submission
+/// half graded. A panic in the task is raised again here; a task cancelled by the
+/// runtime shutting down is reported as an internal error.
+pub async fn detached<T, F>(grading: F) -> Result<T, AppError>
+where
+    F: Future<Output = Result<T, AppError>> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::spawn(grading).await {
+        Ok(result) => result,
+        Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+        Err(err) => Err(AppError::Internal(format!(
+            "grading task did not finish: {err}"
+        ))),
+    }
+}
+
+/// Grades a hand-in stored by [`record_final`] against the test cases of its task, or
+/// of the whole classroom when it names no task, or executes it once when there are
+/// none. Judge0 failures are recorded on the submission before being returned.
+///
+/// When the classroom has a grading script, it runs afterwards and its verdict is
+/// merged into the grade; a failing script is recorded but keeps the built-in grade.
+pub async fn grade_final(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+    submission_model: submission::Model,
+) -> Result<FinalSubmission, AppError> {
+    fingerprint::record(state, classroom_model, &submission_model).await;
+    grade_submission(state, classroom_model, submission_model).await
+}
+
+/// Grades an existing submission again with the classroom's current test cases and
+/// grading script, replacing its previous result. Returns the updated row.
+pub async fn regrade(
+    state: &AppState,
+    submission_model: submission::Model,
+) -> Result<submission::Model, AppError> {
+    let classroom_model = classroom::Entity::find_by_id(submission_model.classroom_id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+
+    let graded = grade_submission(state, &classroom_model, submission_model).await?;
+    Ok(graded.submission)
+}
+
+async fn grade_submission(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+    submission_model: submission::Model,
+) -> Result<FinalSubmission, AppError> {
+    let cases = load_test_cases(&state.db, classroom_model.id, submission_model.task_id).await?;
+    let output_limit_kb = output::limit_kb(state, classroom_model);
+    let sandbox = SandboxFlags::of(classroom_model);
+    grading_progress::set(state, &submission_model, GradingStatus::Running, None).await;
+    let graded = if cases.is_empty() {
+        execute_once(state, &submission_model, output_limit_kb, sandbox).await
+    } else {
+        execute_cases(state, &submission_model, cases, output_limit_kb, sandbox).await
+    };
+
+    let graded = match graded {
+        Ok(graded) => graded,
+        Err(err) => {
+            grading_retry::record_failure(state, submission_model, &err).await?;
+            return Err(err);
+        }
+    };
+    let recovered = submission_model.grading_attempts > 0 && submission_model.graded_at.is_none();
+
+    let script_result = match (
+        &classroom_model.grading_script,
+        classroom_model.grading_script_language_id,
+    ) {
+        (Some(script), Some(script_language_id)) if !script.trim().is_empty() => Some(
+            run_grading_script(
+                state,
+                script,
+                script_language_id,
+                &submission_model,
+                &graded,
+            )
+            .await,
+        ),
+        _ => None,
+    };
+
+    let Graded {
+        response,
+        grade,
+        cases,
+    } = graded;
+
+    let mut submission_am = submission_model.into_active_model();
+    submission_am.stdout = sea_orm::ActiveValue::Set(response.stdout.clone());
+    submission_am.stderr = sea_orm::ActiveValue::Set(response.stderr.clone());
+    submission_am.compile_output = sea_orm::ActiveValue::Set(response.compile_output.clone());
+    submission_am.status = sea_orm::ActiveValue::Set(
+        response
+            .status
+            .as_ref()
+            .map(|status| status.description.clone()),
+    );
+    submission_am.time = sea_orm::ActiveValue::Set(response.time.clone());
+    submission_am.memory = sea_orm::ActiveValue::Set(response.memory);
+    submission_am.exit_code = sea_orm::ActiveValue::Set(response.exit_code);
+    submission_am.exit_signal = sea_orm::ActiveValue::Set(response.exit_signal);
+    submission_am.passed_cases =
+        sea_orm::ActiveValue::Set(grade.as_ref().map(|grade| grade.passed_cases));
+    submission_am.total_cases =
+        sea_orm::ActiveValue::Set(grade.as_ref().map(|grade| grade.total_cases));
+    submission_am.score = sea_orm::ActiveValue::Set(grade.as_ref().map(|grade| grade.score));
+    submission_am.max_score =
+        sea_orm::ActiveValue::Set(grade.as_ref().map(|grade| grade.max_score));
+    submission_am.grading_error = sea_orm::ActiveValue::Set(None);
+    submission_am.script_verdict = sea_orm::ActiveValue::Set(None);
+    match script_result {
+        Some(Ok((raw_verdict, verdict))) => {
+            if let Some(score) = verdict.score {
+                submission_am.score = sea_orm::ActiveValue::Set(Some(score));
+            }
+            if let Some(max_score) = verdict.max_score {
+                submission_am.max_score = sea_orm::ActiveValue::Set(Some(max_score));
+            }
+            submission_am.script_verdict = sea_orm::ActiveValue::Set(Some(raw_verdict));
+        }
+        Some(Err(err)) => {
+            submission_am.grading_error =
+                sea_orm::ActiveValue::Set(Some(format!("grading script: {err}")));
+        }
+        None => {}
+    }
+    let now = Utc::now();
+    submission_am.graded_at = sea_orm::ActiveValue::Set(Some(now));
+    submission_am.last_attempt_at = sea_orm::ActiveValue::Set(Some(now));
+    submission_am.grading_status =
+        sea_orm::ActiveValue::Set(Some(GradingStatus::Done.as_str().to_owned()));
+    submission_am.grading_case = sea_orm::ActiveValue::Set(None);
+    submission_am.grading_case_count = sea_orm::ActiveValue::Set(None);
+    let updated = submission_am.update(&state.db).await?;
+    if recovered {
+        state
+            .metrics
+            .grading
+            .recovered
+            .fetch_add(1, Ordering::Relaxed);
+        tracing::info!(
+            "submission {} graded after {} failed attempt(s)",
+            updated.id,
+            updated.grading_attempts
+        );
+    }
+    if let Err(err) = case_stats::record(&state.db, updated.id, &cases).await {
+        tracing::warn!(
+            "failed to store case results of submission {}: {err}",
+            updated.id
+        );
+    }
+
+    state
+        .events
+        .publish(
+            &state.db,
+            updated.classroom_id,
+            Audience::Users(vec![updated.user_id]),
+            EventKind::Submission(feedback::submission_event(classroom_model, &updated)),
+        )
+        .await;
+    grading_progress::publish(state, &updated).await;
+
+    Ok(FinalSubmission {
+        submission: updated,
+        response,
+        cases,
+    })
+}
+
+struct Grade {
+    passed_cases: i32,
+    total_cases: i32,
+    score: i32,
+    max_score: i32,
+}
+
+struct Graded {
+    response: Judge0SubmissionResponse,
+    grade: Option<Grade>,
+    cases: Vec<CaseOutcome>,
+}
+
+async fn execute_once(
+    state: &AppState,
+    submission_model: &submission::Model,
+    output_limit_kb: u32,
+    sandbox: SandboxFlags,
+) -> Result<Graded, AppError> {
+    let payload = Judge0SubmissionRequest {
+        source_code: submission_model.source_code.to_string(),
+        language_id: submission_model.language_id,
+        stdin: None,
+        expected_output: None,
+        cpu_time_limit: None,
+        memory_limit: None,
+        max_file_size: Some(output_limit_kb),
+        compiler_options: None,
+        command_line_arguments: None,
+        enable_network: sandbox.enable_network,
+        max_processes_and_or_threads: sandbox.max_processes_and_or_threads,
+        redirect_stderr_to_stdout: sandbox.redirect_stderr_to_stdout,
+        npm: None,
+        classroom_id: None,
+        task_id: None,
+        client_updated_at: None,
+        base_seq: None,
+    };
+
+    let mut response = judge0::execute(state, &payload).await?;
+    output::limit_response(&mut response, output_limit_kb);
+    Ok(Graded {
+        response,
+        grade: None,
+        cases: Vec::new(),
+    })
+}
+
+async fn execute_cases(
+    state: &AppState,
+    submission_model: &submission::Model,
+    cases: Vec<test_case::Model>,
+    output_limit_kb: u32,
+    sandbox: SandboxFlags,
+) -> Result<Graded, AppError> {
+    let outcomes = test_runner::run_cases(
+        state,
+        &submission_model.source_code,
+        submission_model.language_id,
+        cases,
+        output_limit_kb,
+        sandbox,
+        Some(submission_model),
+    )
+    .await?;
+
+    let mut grade = Grade {
+        passed_cases: 0,
+        total_cases: outcomes.len() as i32,
+        score: 0,
+        max_score: 0,
+    };
+    for outcome in &outcomes {
+        grade.max_score += outcome.test_case.points;
+        if outcome.passed {
+            grade.passed_cases += 1;
+            grade.score += outcome.test_case.points;
+        }
+    }
+
+    let representative = outcomes
+        .iter()
+        .find(|outcome| !outcome.passed)
+        .or(outcomes.last())
+        .expect("test case list is not empty");
+
+    Ok(Graded {
+        response: representative.response.clone(),
+        grade: Some(grade),
+        cases: outcomes,
+    })
+}
+
+/// Runs the classroom's grading script with the graded run as JSON on stdin and
+/// parses the JSON verdict it prints. Returns the verdict text and its known fields.
+async fn run_grading_script(
+    state: &AppState,
+    script: &str,
+    script_language_id: i32,
+    submission_model: &submission::Model,
+    graded: &Graded,
+) -> Result<(String, GradingScriptVerdict), AppError> {
+    let input = GradingScriptInput {
+        source_code: submission_model.source_code.to_string(),
+        language_id: submission_model.language_id,
+        stdout: graded.response.stdout.clone(),
+        stderr: graded.response.stderr.clone(),
+        compile_output: graded.response.compile_output.clone(),
+        status: graded
+            .response
+            .status
+            .as_ref()
+            .map(|status| status.description.clone()),
+        passed_cases: graded.grade.as_ref().map(|grade| grade.passed_cases),
+        total_cases: graded.grade.as_ref().map(|grade| grade.total_cases),
+        score: graded.grade.as_ref().map(|grade| grade.score),
+        max_score: graded.grade.as_ref().map(|grade| grade.max_score),
+        cases: graded
+            .cases
+            .iter()
+            .map(|outcome| GradingScriptCase {
+                test_case_id: outcome.test_case.id,
+                stdin: outcome.test_case.stdin.clone(),
+                expected_output: outcome.test_case.expected_output.clone(),
+                stdout: outcome.response.stdout.clone(),
+                status: outcome
+                    .response
+                    .status
+                    .as_ref()
+                    .map(|status| status.description.clone()),
+                passed: outcome.passed,
+                points: outcome.test_case.points,
+            })
+            .collect(),
+    };
+
+    let payload = Judge0SubmissionRequest {
+        source_code: script.to_owned(),
+        language_id: script_language_id,
+        stdin: Some(serde_json::to_string(&input).expect("grading input serializes to JSON")),
+        expected_output: None,
+        cpu_time_limit: None,
+        memory_limit: None,
+        max_file_size: None,
+        compiler_options: None,
+        command_line_arguments: None,
+        enable_network: None,
+        max_processes_and_or_threads: None,
+        redirect_stderr_to_stdout: None,
+        npm: None,
+        classroom_id: None,
+        task_id: None,
+        client_updated_at: None,
+        base_seq: None,
+    };
+
+    let response: Judge0SubmissionResponse = judge0::execute(state, &payload).await?;
+    let stdout = response.stdout.unwrap_or_default();
+    let raw_verdict = stdout.trim();
+
+    let value: serde_json::Value = serde_json::from_str(raw_verdict).map_err(|_| {
+        let reason = response
+            .compile_output
+            .or(response.stderr)
+            .or(response.status.map(|status| status.description))
+            .unwrap_or_default();
+        AppError::External(format!("no JSON verdict on stdout: {reason}"))
+    })?;
+    if !value.is_object() {
+        return Err(AppError::External("verdict must be a JSON object".into()));
+    }
+    let verdict: GradingScriptVerdict = serde_json::from_value(value)
+        .map_err(|err| AppError::External(format!("invalid verdict: {err}")))?;
+
+    Ok((raw_verdict.to_owned(), verdict))
+}
+
+/// Test cases of `task_id`, or of every task of the classroom for a hand-in not tied
+/// to a task, in task order and then case order, with their points multiplied by the
+/// weight of their task.
+async fn load_test_cases(
+    db: &impl ConnectionTrait,
+    classroom_id: i32,
+    task_id: Option<i32>,
+) -> Result<Vec<test_case::Model>, AppError> {
+    let mut query = task::Entity::find().filter(task::Column::ClassroomId.eq(classroom_id));
+    if let Some(task_id) = task_id {
+        query = query.filter(task::Column::Id.eq(task_id));
+    }
+    let tasks: Vec<(i32, i32)> = query
+        .order_by_asc(task::Column::Position)
+        .order_by_asc(task::Column::Id)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|task_model| (task_model.id, task_model.weight.max(1)))
+        .collect();
+    let task_ids: Vec<i32> = tasks.iter().map(|(id, _)| *id).collect();
+
+    let mut cases = test_case::Entity::find()
+        .filter(test_case::Column::TaskId.is_in(task_ids.iter().copied()))
+        .order_by_asc(test_case::Column::Position)
+        .order_by_asc(test_case::Column::Id)
+        .all(db)
+        .await?;
+    cases.sort_by_key(|case| task_ids.iter().position(|id| *id == case.task_id));
+    for case in &mut cases {
+        if let Some((_, weight)) = tasks.iter().find(|(id, _)| *id == case.task_id) {
+            case.points = case.points.saturating_mul(*weight);
+        }
+    }
+
+    Ok(cases)
+}
@@ -0,0 +1,65 @@
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, sea_query::Expr};
+
+use crate::{
+    dto::{GradingStatus, GradingStatusResponse},
+    entities::submission,
+    services::event_bus::{Audience, EventKind},
+    state::AppState,
+};
+
+/// Stores where the grading of `submission_model` stands and sends it to the student.
+/// `case` is the test case being run and the number of cases. Failures are logged:
+/// progress is informational and must not break the grading it reports on.
+pub async fn set(
+    state: &AppState,
+    submission_model: &submission::Model,
+    status: GradingStatus,
+    case: Option<(i32, i32)>,
+) {
+    let updated = submission::Entity::update_many()
+        .col_expr(
+            submission::Column::GradingStatus,
+            Expr::value(status.as_str()),
+        )
+        .col_expr(
+            submission::Column::GradingCase,
+            Expr::value(case.map(|(current, _)| current)),
+        )
+        .col_expr(
+            submission::Column::GradingCaseCount,
+            Expr::value(case.map(|(_, total)| total)),
+        )
+        .filter(submission::Column::Id.eq(submission_model.id))
+        .exec(&state.db)
+        .await;
+    if let Err(err) = updated {
+        tracing::warn!(
+            "failed to store grading status of submission {}: {err}",
+            submission_model.id
+        );
+        return;
+    }
+
+    let mut event = GradingStatusResponse::from(submission_model);
+    event.status = status;
+    event.current_case = case.map(|(current, _)| current);
+    event.total_cases = case.map(|(_, total)| total);
+    send(state, event).await;
+}
+
+/// Sends the status already stored on `submission_model`.
+pub async fn publish(state: &AppState, submission_model: &submission::Model) {
+    send(state, GradingStatusResponse::from(submission_model)).await;
+}
+
+async fn send(state: &AppState, event: GradingStatusResponse) {
+    state
+        .events
+        .publish(
+            &state.db,
+            event.classroom_id,
+            Audience::Users(vec![event.user_id]),
+            EventKind::GradingStatus(event),
+        )
+        .await;
+}
@@ -0,0 +1,191 @@
+use std::{sync::atomic::Ordering, time::Duration};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, Select, sea_query::OnConflict,
+};
+
+use crate::{
+    dto::GradingStatus,
+    entities::{grading_dead_letter, submission},
+    error::AppError,
+    services::{grading, grading_progress},
+    state::AppState,
+};
+
+/// How often the worker looks for failed gradings that are due for another attempt.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Wait after the first failure; doubled after every further one.
+const BASE_BACKOFF_SECS: i64 = 30;
+const MAX_BACKOFF_SECS: i64 = 30 * 60;
+
+fn backoff(attempts: i32) -> chrono::Duration {
+    let exponent = u32::try_from(attempts.saturating_sub(1))
+        .unwrap_or(0)
+        .min(16);
+    chrono::Duration::seconds((BASE_BACKOFF_SECS << exponent).min(MAX_BACKOFF_SECS))
+}
+
+/// Records a failed grading on the submission. An ungraded final submission that has
+/// used up `GRADING_MAX_ATTEMPTS` is moved to the dead letters.
+pub async fn record_failure(
+    state: &AppState,
+    submission_model: submission::Model,
+    err: &AppError,
+) -> Result<(), AppError> {
+    let now = Utc::now();
+    let attempts = submission_model.grading_attempts.saturating_add(1);
+    let exhausted = submission_model.is_final
+        && submission_model.graded_at.is_none()
+        && attempts >= i32::try_from(state.grading_max_attempts).unwrap_or(i32::MAX);
+
+    // A regrade that fails leaves the earlier grade standing; anything else waits
+    // for the next retry.
+    let status = if exhausted {
+        GradingStatus::Failed
+    } else if submission_model.graded_at.is_some() {
+        GradingStatus::Done
+    } else {
+        GradingStatus::Queued
+    };
+
+    let mut submission_am = submission_model.into_active_model();
+    submission_am.grading_error = Set(Some(err.to_string()));
+    submission_am.grading_attempts = Set(attempts);
+    submission_am.last_attempt_at = Set(Some(now));
+    submission_am.grading_status = Set(Some(status.as_str().to_owned()));
+    submission_am.grading_case = Set(None);
+    submission_am.grading_case_count = Set(None);
+    let updated = submission_am.update(&state.db).await?;
+    grading_progress::publish(state, &updated).await;
+
+    if exhausted {
+        grading_dead_letter::Entity::insert(grading_dead_letter::ActiveModel {
+            submission_id: Set(updated.id),
+            classroom_id: Set(updated.classroom_id),
+            attempts: Set(attempts),
+            last_error: Set(err.to_string()),
+            created_at: Set(now),
+            ..Default::default()
+        })
+        .on_conflict(
+            OnConflict::column(grading_dead_letter::Column::SubmissionId)
+                .update_columns([
+                    grading_dead_letter::Column::Attempts,
+                    grading_dead_letter::Column::LastError,
+                ])
+                .to_owned(),
+        )
+        .exec(&state.db)
+        .await?;
+        state
+            .metrics
+            .grading
+            .dead_lettered
+            .fetch_add(1, Ordering::Relaxed);
+        tracing::error!(
+            "grading submission {} failed {attempts} time(s), moved to dead letters: {err}",
+            updated.id
+        );
+    }
+    Ok(())
+}
+
+/// Ungraded final submissions with failed attempts left.
+fn pending(max_attempts: u32) -> Select<submission::Entity> {
+    submission::Entity::find()
+        .filter(submission::Column::IsFinal.eq(true))
+        .filter(submission::Column::GradedAt.is_null())
+        .filter(submission::Column::GradingAttempts.gt(0))
+        .filter(
+            submission::Column::GradingAttempts.lt(i32::try_from(max_attempts).unwrap_or(i32::MAX)),
+        )
+}
+
+/// Backlog sizes for the metrics endpoint: submissions waiting for a retry, and dead
+/// letters.
+pub async fn backlog(db: &DatabaseConnection, max_attempts: u32) -> Result<(u64, u64), AppError> {
+    let pending_retries = pending(max_attempts).count(db).await?;
+    let dead_letters = grading_dead_letter::Entity::find().count(db).await?;
+    Ok((pending_retries, dead_letters))
+}
+
+/// Starts the worker that regrades failed final submissions with exponential backoff
+/// until they succeed or are dead-lettered.
+pub fn spawn(state: AppState) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Err(err) = sweep(&state, Utc::now()).await {
+                tracing::warn!("grading retry sweep failed: {err}");
+            }
+        }
+    });
+}
+
+async fn sweep(state: &AppState, now: DateTime<Utc>) -> Result<(), AppError> {
+    let due: Vec<_> = pending(state.grading_max_attempts)
+        .order_by_asc(submission::Column::Id)
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .filter(|model| {
+            model
+                .last_attempt_at
+                .is_none_or(|attempted| attempted + backoff(model.grading_attempts) <= now)
+        })
+        .collect();
+
+    for submission_model in due {
+        state
+            .metrics
+            .grading
+            .retried
+            .fetch_add(1, Ordering::Relaxed);
+        let submission_id = submission_model.id;
+        let _slot = state.executor_queue.admit().await;
+        // The failure is already recorded on the submission by the grading itself.
+        if let Err(err) = grading::regrade(state, submission_model).await {
+            tracing::warn!("retrying grading of submission {submission_id} failed: {err}");
+        }
+    }
+    Ok(())
+}
+
+/// Takes a submission out of the dead letters and grades it again in the background,
+/// with a fresh attempt budget.
+pub async fn requeue(state: &AppState, dead_letter_id: i32) -> Result<i32, AppError> {
+    let dead_letter = grading_dead_letter::Entity::find_by_id(dead_letter_id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::DeadLetterNotFound)?;
+    let submission_model = submission::Entity::find_by_id(dead_letter.submission_id)
+        .one(&state.db)
+        .await?
+        .ok_or(AppError::SubmissionNotFound)?;
+
+    grading_dead_letter::Entity::delete_by_id(dead_letter.id)
+        .exec(&state.db)
+        .await?;
+    let mut submission_am = submission_model.into_active_model();
+    submission_am.grading_attempts = Set(0);
+    let submission_model = submission_am.update(&state.db).await?;
+    state
+        .metrics
+        .grading
+        .requeued
+        .fetch_add(1, Ordering::Relaxed);
+
+    let submission_id = submission_model.id;
+    let grading_state = state.clone();
+    tokio::spawn(async move {
+        let _slot = grading_state.executor_queue.admit().await;
+        if let Err(err) = grading::regrade(&grading_state, submission_model).await {
+            tracing::warn!("grading requeued submission {submission_id} failed: {err}");
+        }
+    });
+    Ok(submission_id)
+}
@@ -0,0 +1,135 @@
+use std::sync::OnceLock;
+
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{FontStyle, Style, Theme, ThemeSet},
+    html::highlighted_html_for_string,
+    parsing::{SyntaxDefinition, SyntaxReference, SyntaxSet},
+    util::LinesWithEndings,
+};
+
+use crate::{dto::HighlightToken, error::AppError};
+
+/// Theme used when the caller does not name one.
+pub const DEFAULT_THEME: &str = "InspiredGitHub";
+
+const NASM_SYNTAX: &str = include_str!("nasm.sublime-syntax");
+
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(|| {
+        let mut builder = SyntaxSet::load_defaults_newlines().into_builder();
+        match SyntaxDefinition::load_from_str(NASM_SYNTAX, true, None) {
+            Ok(nasm) => builder.add(nasm),
+            Err(err) => tracing::warn!("bundled NASM syntax failed to load: {err}"),
+        }
+        builder.build()
+    })
+}
+
+fn themes() -> &'static ThemeSet {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    THEMES.get_or_init(ThemeSet::load_defaults)
+}
+
+/// Names of the themes a caller can ask for.
+pub fn theme_names() -> Vec<String> {
+    themes().themes.keys().cloned().collect()
+}
+
+/// File extension of the grammar used for a Judge0 language id. Languages without a
+/// bundled grammar are shown as plain text.
+pub(crate) fn extension_for(language_id: i32) -> Option<&'static str> {
+    Some(match language_id {
+        45 => "asm",
+        46 => "sh",
+        48..=50 | 75 => "c",
+        52..=54 | 76 => "cpp",
+        51 => "cs",
+        60 => "go",
+        61 => "hs",
+        62 => "java",
+        63 => "js",
+        64 => "lua",
+        65 => "ml",
+        67 => "pas",
+        68 => "php",
+        70 | 71 => "py",
+        72 => "rb",
+        73 => "rs",
+        80 => "r",
+        81 => "scala",
+        82 => "sql",
+        85 => "pl",
+        _ => return None,
+    })
+}
+
+fn syntax_for(language_id: i32) -> &'static SyntaxReference {
+    let syntaxes = syntaxes();
+    extension_for(language_id)
+        .and_then(|extension| syntaxes.find_syntax_by_extension(extension))
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text())
+}
+
+fn theme(name: &str) -> Result<&'static Theme, AppError> {
+    themes().themes.get(name).ok_or_else(|| {
+        AppError::BadRequest(format!(
+            "unknown theme `{name}`; available: {}",
+            theme_names().join(", ")
+        ))
+    })
+}
+
+/// Name of the grammar used for a Judge0 language id.
+pub fn syntax_name(language_id: i32) -> &'static str {
+    &syntax_for(language_id).name
+}
+
+/// Source rendered as a `<pre>` block with inline styles, so it can be embedded in
+/// reports without a stylesheet.
+pub fn to_html(source: &str, language_id: i32, theme_name: &str) -> Result<String, AppError> {
+    let theme = theme(theme_name)?;
+    highlighted_html_for_string(source, syntaxes(), syntax_for(language_id), theme)
+        .map_err(|err| AppError::Internal(format!("highlighting failed: {err}")))
+}
+
+/// Styled byte ranges covering the whole source, for clients that render it themselves.
+/// Adjacent ranges with the same style are merged.
+pub fn to_tokens(
+    source: &str,
+    language_id: i32,
+    theme_name: &str,
+) -> Result<Vec<HighlightToken>, AppError> {
+    let theme = theme(theme_name)?;
+    let mut highlighter = HighlightLines::new(syntax_for(language_id), theme);
+    let mut tokens: Vec<HighlightToken> = Vec::new();
+    let mut offset = 0;
+    for line in LinesWithEndings::from(source) {
+        let regions = highlighter
+            .highlight_line(line, syntaxes())
+            .map_err(|err| AppError::Internal(format!("highlighting failed: {err}")))?;
+        for (style, text) in regions {
+            let end = offset + text.len();
+            let token = token(style, offset, end);
+            match tokens.last_mut() {
+                Some(last) if last.end == offset && last.same_style(&token) => last.end = end,
+                _ => tokens.push(token),
+            }
+            offset = end;
+        }
+    }
+    Ok(tokens)
+}
+
+fn token(style: Style, start: usize, end: usize) -> HighlightToken {
+    let color = style.foreground;
+    HighlightToken {
+        start,
+        end,
+        color: format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b),
+        bold: style.font_style.contains(FontStyle::BOLD),
+        italic: style.font_style.contains(FontStyle::ITALIC),
+        underline: style.font_style.contains(FontStyle::UNDERLINE),
+    }
+}
@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::{
+    error::AppError,
+    services::{
+        alerting::{self, Signal},
+        metrics::CallOutcome,
+    },
+    state::AppState,
+};
+
+/// Runs a submission synchronously (`wait=true`) and decodes Judge0's reply.
+///
+/// The call is bounded by `state.judge0_timeout`. If the caller's future is dropped,
+/// as axum does when the client disconnects, the outgoing request is dropped with it
+/// and Judge0 sees the connection close.
+pub async fn execute<P, R>(state: &AppState, payload: &P) -> Result<R, AppError>
+where
+    P: Serialize + ?Sized,
+    R: DeserializeOwned,
+{
+    call(state, payload, true)
+        .await
+        .map(|(result, _base_url)| result)
+}
+
+#[derive(Deserialize)]
+struct QueuedSubmission {
+    token: String,
+}
+
+/// Queues a submission without waiting for it (`wait=false`). Returns Judge0's token
+/// and the base URL of the instance holding it, which is the one to ask for the result.
+pub async fn submit<P>(state: &AppState, payload: &P) -> Result<(String, String), AppError>
+where
+    P: Serialize + ?Sized,
+{
+    call::<P, QueuedSubmission>(state, payload, false)
+        .await
+        .map(|(queued, base_url)| (queued.token, base_url))
+}
+
+/// Current state of submission `token` on the instance at `base_url`, with the same
+/// fields as a synchronous run; `None` once Judge0 no longer knows the token.
+pub async fn fetch(
+    state: &AppState,
+    base_url: &str,
+    token: &str,
+) -> Result<Option<Value>, AppError> {
+    let endpoint =
+        format!("{base_url}/submissions/{token}?base64_encoded=false&fields={RESPONSE_FIELDS}");
+    let request = state.http_client.get(endpoint).send();
+    #[cfg(feature = "chaos")]
+    let request = state.chaos.judge0(request);
+    let response = tokio::time::timeout(state.judge0_timeout, request)
+        .await
+        .map_err(|_| {
+            AppError::ExecutorTimeout(format!(
+                "Judge0 tidak merespons dalam {} detik",
+                state.judge0_timeout.as_secs()
+            ))
+        })??;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        let error_body = response.text().await.unwrap_or_default();
+        return Err(AppError::External(format!(
+            "status {} dari Judge0: {}",
+            status.as_u16(),
+            error_body
+        )));
+    }
+    Ok(Some(response.json::<Value>().await?))
+}
+
+async fn call<P, R>(state: &AppState, payload: &P, wait: bool) -> Result<(R, String), AppError>
+where
+    P: Serialize + ?Sized,
+    R: DeserializeOwned,
+{
+    let call = state.metrics.judge0.start();
+
+    let send = send(state, payload, wait);
+    #[cfg(feature = "chaos")]
+    let send = state.chaos.judge0(send);
+
+    match tokio::time::timeout(state.judge0_timeout, send).await {
+        Ok(Ok(result)) => {
+            call.finish(CallOutcome::Succeeded);
+            Ok(result)
+        }
+        Ok(Err(err)) => {
+            call.finish(CallOutcome::Failed);
+            alerting::record(state, Signal::Judge0Failure);
+            Err(err)
+        }
+        Err(_) => {
+            call.finish(CallOutcome::TimedOut);
+            alerting::record(state, Signal::Judge0Failure);
+            Err(AppError::ExecutorTimeout(format!(
+                "Judge0 tidak merespons dalam {} detik",
+                state.judge0_timeout.as_secs()
+            )))
+        }
+    }
+}
+
+/// Tries the pool's instances in order. Only an instance that could not be reached
+/// is skipped for the next one; once Judge0 has seen the submission its answer, or
+/// error, is final.
+async fn send<P, R>(state: &AppState, payload: &P, wait: bool) -> Result<(R, String), AppError>
+where
+    P: Serialize + ?Sized,
+    R: DeserializeOwned,
+{
+    let eject_after = state.executors.settings().eject_after_failures;
+    let mut unreachable = None;
+
+    for instance in state.executors.candidates() {
+        let call = instance.counters.start();
+        let _pending = instance.begin();
+        match send_to(state, &instance.base_url, payload, wait).await {
+            Ok(result) => {
+                call.finish(CallOutcome::Succeeded);
+                instance.record_success();
+                return Ok((result, instance.base_url.clone()));
+            }
+            Err(Failure::Rejected(err)) => {
+                // The payload was refused; the instance itself is fine.
+                call.finish(CallOutcome::Failed);
+                return Err(err);
+            }
+            Err(Failure::Instance(err)) => {
+                call.finish(CallOutcome::Failed);
+                instance.record_failure(&err.to_string(), eject_after);
+                return Err(err);
+            }
+            Err(Failure::Unreachable(err)) => {
+                call.finish(CallOutcome::Failed);
+                instance.record_failure(&err.to_string(), eject_after);
+                tracing::warn!("Judge0 instance {} unreachable: {err}", instance.base_url);
+                unreachable = Some(err);
+            }
+        }
+    }
+
+    Err(unreachable.unwrap_or_else(|| {
+        AppError::External("tidak ada instance Judge0 yang dikonfigurasi".into())
+    }))
+}
+
+/// Judge0 leaves the exit code and signal out unless they are asked for.
+const RESPONSE_FIELDS: &str =
+    "token,stdout,stderr,compile_output,message,status,time,memory,exit_code,exit_signal";
+
+enum Failure {
+    /// The request never reached Judge0; another instance may take it.
+    Unreachable(AppError),
+    /// Judge0 failed while handling the request.
+    Instance(AppError),
+    /// Judge0 refused the request itself, e.g. an unknown language.
+    Rejected(AppError),
+}
+
+async fn send_to<P, R>(
+    state: &AppState,
+    base_url: &str,
+    payload: &P,
+    wait: bool,
+) -> Result<R, Failure>
+where
+    P: Serialize + ?Sized,
+    R: DeserializeOwned,
+{
+    let endpoint =
+        format!("{base_url}/submissions?base64_encoded=false&wait={wait}&fields={RESPONSE_FIELDS}");
+
+    let response = state
+        .http_client
+        .post(endpoint)
+        .json(payload)
+        .send()
+        .await
+        .map_err(|err| {
+            if err.is_connect() {
+                Failure::Unreachable(err.into())
+            } else {
+                Failure::Instance(err.into())
+            }
+        })?;
+
+    let status = response.status();
+
+    if !status.is_success() {
+        let error_body = response.text().await.unwrap_or_default();
+        let err = AppError::External(format!(
+            "status {} dari Judge0: {}",
+            status.as_u16(),
+            error_body
+        ));
+        return Err(if status.is_server_error() {
+            Failure::Instance(err)
+        } else {
+            Failure::Rejected(err)
+        });
+    }
+
+    response
+        .json::<R>()
+        .await
+        .map_err(|err| Failure::Instance(err.into()))
+}
@@ -0,0 +1,148 @@
+//! Completes runs submitted through the judge proxy with `wait=false`. Their Judge0
+//! token is stored on the submission row; this worker asks the instance holding it
+//! for the result once a second while any run is pending, and sleeps otherwise until
+//! a new one is queued.
+
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use serde_json::{Value, json};
+
+use crate::{
+    dto::ReplicationRole,
+    entities::{classroom, submission},
+    error::AppError,
+    services::{judge0, output, submission_history},
+    state::AppState,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Pending runs asked about per round.
+const BATCH_SIZE: u64 = 50;
+
+/// Runs Judge0 has not finished after this long are marked as an internal error and
+/// no longer polled.
+const MAX_PENDING_SECS: i64 = 60 * 60;
+
+/// Judge0 statuses of a run that has not finished yet: In Queue and Processing.
+pub const PENDING_STATUS_IDS: [i32; 2] = [1, 2];
+
+const INTERNAL_ERROR: (i32, &str) = (13, "Internal Error");
+
+/// Wakes the worker after a run was queued.
+pub fn wake(state: &AppState) {
+    state.judge0_polls.notify_one();
+}
+
+/// Starts the worker. A standby leaves the runs to the primary.
+pub fn spawn(state: AppState) {
+    if state.replication.role() == ReplicationRole::Standby {
+        return;
+    }
+
+    tokio::spawn(async move {
+        loop {
+            let pending = match poll(&state, Utc::now()).await {
+                Ok(pending) => pending,
+                Err(err) => {
+                    tracing::warn!("polling Judge0 for queued runs failed: {err}");
+                    1
+                }
+            };
+            if pending == 0 {
+                state.judge0_polls.notified().await;
+            } else {
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        }
+    });
+}
+
+/// Asks Judge0 about one batch of pending runs and stores the finished ones. Returns
+/// how many are still pending.
+async fn poll(state: &AppState, now: DateTime<Utc>) -> Result<usize, AppError> {
+    let pending = submission::Entity::find()
+        .filter(submission::Column::Judge0Token.is_not_null())
+        .filter(submission::Column::Judge0StatusId.is_in(PENDING_STATUS_IDS))
+        .order_by_asc(submission::Column::Id)
+        .limit(BATCH_SIZE)
+        .all(&state.db)
+        .await?;
+
+    let mut output_limits: HashMap<i32, u32> = HashMap::new();
+    let mut still_pending = 0;
+    for run in pending {
+        let (Some(token), Some(instance)) = (run.judge0_token.clone(), run.judge0_instance.clone())
+        else {
+            continue;
+        };
+
+        if (now - run.created_at).num_seconds() > MAX_PENDING_SECS {
+            tracing::warn!("Judge0 run {token} of submission {} never finished", run.id);
+            give_up(state, run).await?;
+            continue;
+        }
+
+        let mut result = match judge0::fetch(state, &instance, &token).await {
+            Ok(Some(result)) => result,
+            Ok(None) => {
+                tracing::warn!("Judge0 at {instance} no longer knows run {token}");
+                give_up(state, run).await?;
+                continue;
+            }
+            Err(err) => {
+                tracing::warn!("fetching Judge0 run {token} failed: {err}");
+                still_pending += 1;
+                continue;
+            }
+        };
+
+        let status_id = result
+            .pointer("/status/id")
+            .and_then(Value::as_i64)
+            .and_then(|id| i32::try_from(id).ok());
+        if status_id.is_some_and(|id| PENDING_STATUS_IDS.contains(&id)) {
+            still_pending += 1;
+            if status_id != run.judge0_status_id {
+                let mut run_am = run.into_active_model();
+                submission_history::apply_result(&mut run_am, &result);
+                run_am.update(&state.db).await?;
+            }
+            continue;
+        }
+
+        let limit_kb = match output_limits.get(&run.classroom_id) {
+            Some(limit_kb) => *limit_kb,
+            None => {
+                let limit_kb = classroom::Entity::find_by_id(run.classroom_id)
+                    .one(&state.db)
+                    .await?
+                    .map_or(state.output_limit_kb, |classroom_model| {
+                        output::limit_kb(state, &classroom_model)
+                    });
+                output_limits.insert(run.classroom_id, limit_kb);
+                limit_kb
+            }
+        };
+        output::limit_value(&mut result, limit_kb);
+        let mut run_am = run.into_active_model();
+        submission_history::apply_result(&mut run_am, &result);
+        run_am.update(&state.db).await?;
+    }
+    Ok(still_pending)
+}
+
+async fn give_up(state: &AppState, run: submission::Model) -> Result<(), AppError> {
+    let mut run_am = run.into_active_model();
+    submission_history::apply_result(
+        &mut run_am,
+        &json!({ "status": { "id": INTERNAL_ERROR.0, "description": INTERNAL_ERROR.1 } }),
+    );
+    run_am.update(&state.db).await?;
+    Ok(())
+}
@@ -0,0 +1,263 @@
+//! Maps the free-text `programming_language` of classrooms (e.g. `NASM 64-bit`) to
+//! Judge0 language ids, which differ between Judge0 versions. The executor's language
+//! list is refreshed periodically; every refresh re-resolves all classrooms and flags
+//! those whose language the executor no longer offers.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait,
+    ActiveValue::{self, Set},
+    EntityTrait, IntoActiveModel,
+};
+
+use crate::{dto::ExecutorLanguage, entities::classroom, error::AppError, state::AppState};
+
+/// How long a fetched language list is served before Judge0 is asked again.
+const LANGUAGES_TTL: Duration = Duration::from_secs(600);
+/// Retry delay after Judge0 could not list its languages.
+const LANGUAGES_RETRY: Duration = Duration::from_secs(30);
+const LANGUAGES_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct CachedLanguages {
+    fetched_at: Instant,
+    ttl: Duration,
+    languages: Vec<ExecutorLanguage>,
+}
+
+static LANGUAGES: Mutex<Option<CachedLanguages>> = Mutex::new(None);
+
+/// Languages of the first executor instance that answers, cached so page loads do
+/// not reach Judge0. A failed fetch is cached briefly as an empty list.
+pub async fn languages(state: &AppState) -> Vec<ExecutorLanguage> {
+    if let Some(cached) = LANGUAGES.lock().expect("language cache poisoned").as_ref()
+        && cached.fetched_at.elapsed() < cached.ttl
+    {
+        return cached.languages.clone();
+    }
+    refresh(state).await.unwrap_or_default()
+}
+
+/// Fetches the language list now and caches it.
+async fn refresh(state: &AppState) -> Result<Vec<ExecutorLanguage>, String> {
+    let fetched = fetch_languages(state).await;
+    let (languages, ttl) = match &fetched {
+        Ok(languages) => (languages.clone(), LANGUAGES_TTL),
+        Err(err) => {
+            tracing::warn!("failed to list Judge0 languages: {err}");
+            (Vec::new(), LANGUAGES_RETRY)
+        }
+    };
+    *LANGUAGES.lock().expect("language cache poisoned") = Some(CachedLanguages {
+        fetched_at: Instant::now(),
+        ttl,
+        languages,
+    });
+    fetched
+}
+
+async fn fetch_languages(state: &AppState) -> Result<Vec<ExecutorLanguage>, String> {
+    let mut last_error = "no Judge0 instance is configured".to_owned();
+    for instance in state.executors.candidates() {
+        let response = state
+            .http_client
+            .get(format!("{}/languages", instance.base_url))
+            .timeout(LANGUAGES_TIMEOUT)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match response {
+            Ok(response) => {
+                let mut languages: Vec<ExecutorLanguage> =
+                    response.json().await.map_err(|err| err.to_string())?;
+                languages.sort_by_key(|language| language.id);
+                return Ok(languages);
+            }
+            Err(err) => last_error = format!("{}: {err}", instance.base_url),
+        }
+    }
+    Err(last_error)
+}
+
+/// The language `name` refers to: a Judge0 id, a full Judge0 name, or words naming
+/// the language or its toolchain ignoring versions and word sizes, so `NASM 64-bit`
+/// matches `Assembly (NASM 2.14.02)`. Several matches pick the newest (highest id).
+pub fn resolve<'a>(name: &str, languages: &'a [ExecutorLanguage]) -> Option<&'a ExecutorLanguage> {
+    let name = name.trim();
+    if let Ok(id) = name.parse::<i32>() {
+        return languages.iter().find(|language| language.id == id);
+    }
+    if let Some(language) = languages
+        .iter()
+        .find(|language| language.name.eq_ignore_ascii_case(name))
+    {
+        return Some(language);
+    }
+
+    let wanted = keywords(name);
+    if wanted.is_empty() {
+        return None;
+    }
+    languages
+        .iter()
+        .filter(|language| {
+            let offered = keywords(&language.name);
+            wanted.iter().all(|word| offered.contains(word))
+        })
+        .max_by_key(|language| language.id)
+}
+
+/// Lowercase words of a language name without version numbers, word sizes and
+/// punctuation other than the `+` and `#` of `C++` and `C#`.
+fn keywords(name: &str) -> Vec<String> {
+    name.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '+' || c == '#'))
+        .filter(|word| !word.is_empty())
+        .filter(|word| !word.chars().any(|c| c.is_ascii_digit()))
+        .filter(|word| !matches!(*word, "bit" | "bits" | "x"))
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Outcome of mapping a classroom language against the executor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mapping {
+    /// No language is set, or the executor could not be asked.
+    Unknown,
+    Resolved(i32),
+    Missing,
+}
+
+impl Mapping {
+    pub fn language_id(self) -> Option<i32> {
+        match self {
+            Mapping::Resolved(language_id) => Some(language_id),
+            Mapping::Unknown | Mapping::Missing => None,
+        }
+    }
+}
+
+/// Maps `name` with the cached language list.
+pub async fn map(state: &AppState, name: &str) -> Mapping {
+    if name.trim().is_empty() {
+        return Mapping::Unknown;
+    }
+    let languages = languages(state).await;
+    if languages.is_empty() {
+        return Mapping::Unknown;
+    }
+    match resolve(name, &languages) {
+        Some(language) => Mapping::Resolved(language.id),
+        None => Mapping::Missing,
+    }
+}
+
+/// Rejects a classroom language the executor does not offer. Accepted when the
+/// executor cannot be asked; the periodic check flags it later if needed.
+pub async fn validate(state: &AppState, name: &str) -> Result<Mapping, AppError> {
+    let mapping = map(state, name).await;
+    if mapping == Mapping::Missing {
+        return Err(AppError::BadRequest(format!(
+            "programmingLanguage {:?} does not match any Judge0 language; use a Judge0 language id or name from GET /api/client-config",
+            name.trim()
+        )));
+    }
+    Ok(mapping)
+}
+
+/// Stores `mapping` on the classroom. A language found missing keeps the time it was
+/// first flagged.
+pub fn apply(classroom_am: &mut classroom::ActiveModel, mapping: Mapping) {
+    let flagged_since = match &classroom_am.language_missing_since {
+        ActiveValue::Set(since) | ActiveValue::Unchanged(since) => *since,
+        ActiveValue::NotSet => None,
+    };
+    match mapping {
+        Mapping::Unknown => {
+            classroom_am.resolved_language_id = Set(None);
+            classroom_am.language_missing_since = Set(None);
+        }
+        Mapping::Resolved(language_id) => {
+            classroom_am.resolved_language_id = Set(Some(language_id));
+            classroom_am.language_missing_since = Set(None);
+        }
+        Mapping::Missing => {
+            classroom_am.resolved_language_id = Set(None);
+            classroom_am.language_missing_since = Set(flagged_since.or(Some(Utc::now())));
+        }
+    }
+}
+
+/// Refreshes the language list at startup and every `interval`, then re-maps every
+/// classroom.
+pub fn spawn(state: AppState, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match refresh(&state).await {
+                Ok(languages) if !languages.is_empty() => {
+                    if let Err(err) = check_classrooms(&state, &languages).await {
+                        tracing::warn!("classroom language check failed: {err}");
+                    }
+                }
+                Ok(_) => tracing::warn!("Judge0 reported no languages; classroom check skipped"),
+                Err(_) => {}
+            }
+        }
+    });
+}
+
+/// Re-maps the language of every classroom, storing changes and logging classrooms
+/// whose language is missing. Returns how many are flagged.
+pub async fn check_classrooms(
+    state: &AppState,
+    languages: &[ExecutorLanguage],
+) -> Result<usize, AppError> {
+    let mut flagged = 0;
+    for classroom_model in classroom::Entity::find().all(&state.db).await? {
+        let mapping = if classroom_model.programming_language.trim().is_empty() {
+            Mapping::Unknown
+        } else {
+            match resolve(&classroom_model.programming_language, languages) {
+                Some(language) => Mapping::Resolved(language.id),
+                None => Mapping::Missing,
+            }
+        };
+        if mapping == Mapping::Missing {
+            flagged += 1;
+            if classroom_model.language_missing_since.is_none() {
+                tracing::warn!(
+                    "classroom {} uses {:?}, which no Judge0 language matches",
+                    classroom_model.id,
+                    classroom_model.programming_language
+                );
+            }
+        }
+        let unchanged = match mapping {
+            Mapping::Unknown => {
+                classroom_model.resolved_language_id.is_none()
+                    && classroom_model.language_missing_since.is_none()
+            }
+            Mapping::Resolved(language_id) => {
+                classroom_model.resolved_language_id == Some(language_id)
+                    && classroom_model.language_missing_since.is_none()
+            }
+            Mapping::Missing => {
+                classroom_model.resolved_language_id.is_none()
+                    && classroom_model.language_missing_since.is_some()
+            }
+        };
+        if unchanged {
+            continue;
+        }
+        let mut classroom_am = classroom_model.into_active_model();
+        apply(&mut classroom_am, mapping);
+        classroom_am.update(&state.db).await?;
+    }
+    Ok(flagged)
+}
@@ -0,0 +1,194 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::NaiveDate;
+use sea_orm::{
+    ActiveValue::Set,
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+    sea_query::{Expr, OnConflict},
+};
+
+use crate::{
+    dto::{ExecutorLanguage, LanguageUsageSummary},
+    entities::{language_usage, submission},
+    error::AppError,
+    services::daily_stats::NO_CLASSROOM,
+};
+
+/// Counts an execution in `language_id` for today. Failures are logged and never fail
+/// the run.
+pub async fn record_run(db: &DatabaseConnection, classroom_id: Option<i32>, language_id: i32) {
+    let model = language_usage::ActiveModel {
+        day: Set(chrono::Utc::now().date_naive()),
+        classroom_id: Set(classroom_id.unwrap_or(NO_CLASSROOM)),
+        language_id: Set(language_id),
+        runs: Set(1),
+        submissions: Set(0),
+        ..Default::default()
+    };
+    let result = language_usage::Entity::insert(model)
+        .on_conflict(
+            OnConflict::columns(key_columns())
+                .value(
+                    language_usage::Column::Runs,
+                    Expr::col((language_usage::Entity, language_usage::Column::Runs)).add(1),
+                )
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await;
+    if let Err(err) = result {
+        tracing::warn!("failed to count run in language usage: {err}");
+    }
+}
+
+/// Recomputes the submission counts of one UTC day from the submissions table, keeping
+/// the recorded run counts. Part of the daily stats rollup.
+pub async fn roll_up_day(
+    db: &impl ConnectionTrait,
+    day: NaiveDate,
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>,
+) -> Result<(), AppError> {
+    let submissions: Vec<(i32, i32)> = submission::Entity::find()
+        .select_only()
+        .column(submission::Column::ClassroomId)
+        .column(submission::Column::LanguageId)
+        .filter(submission::Column::CreatedAt.gte(start))
+        .filter(submission::Column::CreatedAt.lt(end))
+        .into_tuple()
+        .all(db)
+        .await?;
+    let mut counts: HashMap<(i32, i32), i64> = HashMap::new();
+    for key in submissions {
+        *counts.entry(key).or_default() += 1;
+    }
+
+    language_usage::Entity::update_many()
+        .col_expr(language_usage::Column::Submissions, Expr::value(0))
+        .filter(language_usage::Column::Day.eq(day))
+        .exec(db)
+        .await?;
+    for ((classroom_id, language_id), count) in counts {
+        let model = language_usage::ActiveModel {
+            day: Set(day),
+            classroom_id: Set(classroom_id),
+            language_id: Set(language_id),
+            runs: Set(0),
+            submissions: Set(count),
+            ..Default::default()
+        };
+        language_usage::Entity::insert(model)
+            .on_conflict(
+                OnConflict::columns(key_columns())
+                    .update_column(language_usage::Column::Submissions)
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await?;
+    }
+    Ok(())
+}
+
+fn key_columns() -> [language_usage::Column; 3] {
+    [
+        language_usage::Column::Day,
+        language_usage::Column::ClassroomId,
+        language_usage::Column::LanguageId,
+    ]
+}
+
+/// Stored rows between `from` and `to`, both inclusive, optionally for one classroom.
+pub async fn load(
+    db: &DatabaseConnection,
+    from: NaiveDate,
+    to: NaiveDate,
+    classroom_id: Option<i32>,
+) -> Result<Vec<language_usage::Model>, AppError> {
+    let mut query = language_usage::Entity::find()
+        .filter(language_usage::Column::Day.gte(from))
+        .filter(language_usage::Column::Day.lte(to));
+    if let Some(classroom_id) = classroom_id {
+        query = query.filter(language_usage::Column::ClassroomId.eq(classroom_id));
+    }
+    Ok(query
+        .order_by_asc(language_usage::Column::Day)
+        .order_by_asc(language_usage::Column::ClassroomId)
+        .order_by_asc(language_usage::Column::LanguageId)
+        .all(db)
+        .await?)
+}
+
+/// Last day each language was used at all, over every recorded day.
+pub async fn last_used(
+    db: &DatabaseConnection,
+    classroom_id: Option<i32>,
+) -> Result<HashMap<i32, NaiveDate>, AppError> {
+    let mut query = language_usage::Entity::find()
+        .select_only()
+        .column(language_usage::Column::LanguageId)
+        .column_as(language_usage::Column::Day.max(), "last_used")
+        .filter(
+            language_usage::Column::Runs
+                .gt(0)
+                .or(language_usage::Column::Submissions.gt(0)),
+        )
+        .group_by(language_usage::Column::LanguageId);
+    if let Some(classroom_id) = classroom_id {
+        query = query.filter(language_usage::Column::ClassroomId.eq(classroom_id));
+    }
+    let rows: Vec<(i32, Option<NaiveDate>)> = query.into_tuple().all(db).await?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(language_id, day)| Some((language_id, day?)))
+        .collect())
+}
+
+/// Totals per language over `rows`, including executor languages that were not used
+/// at all, most used first.
+pub fn summarize(
+    rows: &[language_usage::Model],
+    installed: &[ExecutorLanguage],
+    last_used: &HashMap<i32, NaiveDate>,
+) -> Vec<LanguageUsageSummary> {
+    #[derive(Default)]
+    struct Totals {
+        runs: i64,
+        submissions: i64,
+        classroom_ids: BTreeSet<i32>,
+    }
+
+    let mut totals: BTreeMap<i32, Totals> = installed
+        .iter()
+        .map(|language| (language.id, Totals::default()))
+        .collect();
+    for row in rows {
+        let entry = totals.entry(row.language_id).or_default();
+        entry.runs += row.runs;
+        entry.submissions += row.submissions;
+        if row.classroom_id != NO_CLASSROOM && (row.runs > 0 || row.submissions > 0) {
+            entry.classroom_ids.insert(row.classroom_id);
+        }
+    }
+
+    let mut summaries: Vec<LanguageUsageSummary> = totals
+        .into_iter()
+        .map(|(language_id, totals)| LanguageUsageSummary {
+            language_id,
+            name: installed
+                .iter()
+                .find(|language| language.id == language_id)
+                .map(|language| language.name.clone()),
+            runs: totals.runs,
+            submissions: totals.submissions,
+            classroom_ids: totals.classroom_ids.into_iter().collect(),
+            last_used: last_used.get(&language_id).copied(),
+        })
+        .collect();
+    summaries.sort_by(|a, b| {
+        (b.runs + b.submissions)
+            .cmp(&(a.runs + a.submissions))
+            .then(a.language_id.cmp(&b.language_id))
+    });
+    summaries
+}
@@ -0,0 +1,154 @@
+//! Every attempt at NPM login, successful or not, with the address it came from and
+//! the classroom the NPM resolves to. Attempts are flagged when the same NPM shows up
+//! from several addresses within a few minutes, or when its exam is not running.
+
+use std::net::IpAddr;
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+
+use crate::{
+    dto::{ClassroomStatus, LoginAnomaly, LoginEventResponse, LoginResponse},
+    entities::{account, classroom, login_event, user},
+    error::AppError,
+    services::exam_clock::ExamClock,
+    state::AppState,
+};
+
+/// Attempts from another address within this window flag the NPM as shared.
+const MULTIPLE_IP_WINDOW_MINUTES: i64 = 10;
+
+/// Flagged attempts listed on the admin dashboard and a classroom's live stats.
+const RECENT_FLAGGED_LIMIT: u64 = 20;
+
+/// Records a login attempt and its outcome. Failures are logged, never surfaced to
+/// the caller.
+pub async fn record(
+    state: &AppState,
+    npm: &str,
+    ip: IpAddr,
+    outcome: &Result<LoginResponse, AppError>,
+) {
+    if let Err(err) = try_record(state, npm, ip, outcome).await {
+        tracing::warn!("failed to record login attempt of {npm:?}: {err}");
+    }
+}
+
+async fn try_record(
+    state: &AppState,
+    npm: &str,
+    ip: IpAddr,
+    outcome: &Result<LoginResponse, AppError>,
+) -> Result<(), AppError> {
+    let db = &state.db;
+    let now = Utc::now();
+    let ip = ip.to_string();
+
+    let (account_id, classroom_model, failure) = match outcome {
+        Ok(response) => {
+            let classroom_model = match &response.classroom {
+                Some(info) => classroom::Entity::find_by_id(info.id).one(db).await?,
+                None => None,
+            };
+            (Some(response.account.id), classroom_model, None)
+        }
+        Err(err) => {
+            let account_id = account::Entity::find()
+                .filter(account::Column::Npm.eq(npm))
+                .select_only()
+                .column(account::Column::Id)
+                .into_tuple::<i32>()
+                .one(db)
+                .await?;
+            let classroom_model = user::Entity::find()
+                .filter(user::Column::Npm.eq(npm))
+                .find_also_related(classroom::Entity)
+                .filter(classroom::Column::Status.eq(ClassroomStatus::Published.as_str()))
+                .one(db)
+                .await?
+                .and_then(|(_, classroom_model)| classroom_model);
+            (account_id, classroom_model, Some(err.to_string()))
+        }
+    };
+
+    let mut flags = Vec::new();
+    if !npm.is_empty() && used_elsewhere(db, npm, &ip, now).await? {
+        flags.push(LoginAnomaly::MultipleIps);
+    }
+    if let Some(classroom_model) = &classroom_model
+        && outside_exam_window(state, classroom_model, now).await?
+    {
+        flags.push(LoginAnomaly::OutsideExamWindow);
+    }
+
+    login_event::ActiveModel {
+        npm: Set(npm.to_owned()),
+        account_id: Set(account_id),
+        ip: Set(ip),
+        success: Set(outcome.is_ok()),
+        failure: Set(failure),
+        classroom_id: Set(classroom_model.map(|classroom_model| classroom_model.id)),
+        flags: Set(flags
+            .iter()
+            .map(LoginAnomaly::as_str)
+            .collect::<Vec<_>>()
+            .join(",")),
+        created_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok(())
+}
+
+async fn used_elsewhere(
+    db: &DatabaseConnection,
+    npm: &str,
+    ip: &str,
+    now: DateTime<Utc>,
+) -> Result<bool, AppError> {
+    let since = now - Duration::minutes(MULTIPLE_IP_WINDOW_MINUTES);
+    Ok(login_event::Entity::find()
+        .filter(login_event::Column::Npm.eq(npm))
+        .filter(login_event::Column::Ip.ne(ip))
+        .filter(login_event::Column::CreatedAt.gte(since))
+        .count(db)
+        .await?
+        > 0)
+}
+
+/// Whether the classroom is a scheduled exam that is not running at `now`.
+async fn outside_exam_window(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+    now: DateTime<Utc>,
+) -> Result<bool, AppError> {
+    if !classroom_model.is_exam {
+        return Ok(false);
+    }
+    let clock = ExamClock::load(&state.db, classroom_model).await?;
+    Ok(clock.start.is_some() && clock.end.is_some() && !state.time_policy.is_running(&clock, now))
+}
+
+/// Newest flagged attempts, optionally of one classroom.
+pub async fn recent_flagged(
+    db: &DatabaseConnection,
+    classroom_id: Option<i32>,
+) -> Result<Vec<LoginEventResponse>, AppError> {
+    let mut query = login_event::Entity::find()
+        .filter(login_event::Column::Flags.ne(""))
+        .order_by_desc(login_event::Column::Id)
+        .limit(RECENT_FLAGGED_LIMIT);
+    if let Some(classroom_id) = classroom_id {
+        query = query.filter(login_event::Column::ClassroomId.eq(classroom_id));
+    }
+    Ok(query
+        .all(db)
+        .await?
+        .into_iter()
+        .map(LoginEventResponse::from)
+        .collect())
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{error::AppError, state::AppState};
+
+/// Verification and recovery mails allowed per address, and per client, each minute.
+pub const REQUESTS_PER_MINUTE: u32 = 3;
+
+/// Upper bound for one relay request.
+const RELAY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Body posted to `MAIL_RELAY_URL`; the relay turns it into an email.
+#[derive(Serialize)]
+struct RelayMail<'a> {
+    to: &'a str,
+    subject: &'a str,
+    text: &'a str,
+}
+
+/// Hands the mail to the configured relay. Without one the mail is logged instead,
+/// which is enough for development.
+pub async fn send(state: &AppState, to: &str, subject: &str, text: &str) -> Result<(), AppError> {
+    let Some(relay_url) = &state.mail_relay_url else {
+        tracing::info!(
+            "MAIL_RELAY_URL tidak diatur; email untuk {to} tidak dikirim: {subject}\n{text}"
+        );
+        return Ok(());
+    };
+
+    let response = state
+        .http_client
+        .post(relay_url)
+        .timeout(RELAY_TIMEOUT)
+        .json(&RelayMail { to, subject, text })
+        .send()
+        .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::External(format!(
+            "status {} dari relay email",
+            status.as_u16()
+        )));
+    }
+    Ok(())
+}
+
+/// Normalized address, or `None` when it is not a plausible email.
+pub fn normalize_email(raw: &str) -> Option<String> {
+    let email = raw.trim().to_lowercase();
+    let (local, domain) = email.split_once('@')?;
+    let plausible = !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !domain.contains('@')
+        && email.len() <= 254
+        && !email.chars().any(char::is_whitespace);
+    plausible.then_some(email)
+}
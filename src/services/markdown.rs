@@ -0,0 +1,114 @@
+use std::{collections::HashSet, sync::OnceLock};
+
+use ammonia::Builder;
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd, html};
+
+fn sanitizer() -> &'static Builder<'static> {
+    static SANITIZER: OnceLock<Builder<'static>> = OnceLock::new();
+    SANITIZER.get_or_init(|| {
+        let mut builder = Builder::default();
+        // Fenced code blocks carry their language as `class="language-nasm"`, which
+        // clients use for highlighting; classes cannot run script.
+        builder
+            .add_tag_attributes("code", ["class"])
+            .add_tag_attributes("input", ["type", "checked", "disabled"])
+            .add_tags(["input"])
+            .url_schemes(HashSet::from(["http", "https", "mailto"]));
+        builder
+    })
+}
+
+/// Renders a task description written in CommonMark (with tables, strikethrough and
+/// task lists) to HTML that is safe to inject into a page: scripts, event handlers,
+/// `javascript:` links and other unsafe markup are stripped, links get
+/// `rel="noopener noreferrer"`.
+pub fn to_safe_html(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+    let mut rendered = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut rendered, Parser::new_ext(markdown, options));
+    sanitizer().clean(&rendered).to_string()
+}
+
+/// Sample input/output pair found in a task description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SampleCase {
+    pub stdin: String,
+    pub expected_output: String,
+    /// 1-based line of the block the expected output was read from.
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SampleKind {
+    Input,
+    Output,
+}
+
+/// Pairs fenced sample blocks into test cases. A block is an input when its info
+/// string or the heading/paragraph right before it says `input`, `stdin` or
+/// `masukan`, and an output for `output`, `stdout`, `expected` or `keluaran`; other
+/// code blocks are skipped. Each output closes a case with the input before it, or
+/// with empty stdin when there is none.
+pub fn sample_cases(markdown: &str) -> Vec<SampleCase> {
+    let mut cases = Vec::new();
+    let mut label = String::new();
+    let mut in_label = false;
+    let mut block: Option<(Option<SampleKind>, usize, String)> = None;
+    let mut pending_input: Option<String> = None;
+
+    for (event, range) in Parser::new(markdown).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { .. } | Tag::Paragraph) => {
+                label.clear();
+                in_label = true;
+            }
+            Event::End(TagEnd::Heading(_) | TagEnd::Paragraph) => in_label = false,
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let info = match kind {
+                    CodeBlockKind::Fenced(info) => info.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                let kind = sample_kind(&info).or_else(|| sample_kind(&label));
+                let line = markdown[..range.start].matches('\n').count() + 1;
+                block = Some((kind, line, String::new()));
+                label.clear();
+            }
+            Event::Text(text) => match block.as_mut() {
+                Some((_, _, content)) => content.push_str(&text),
+                None if in_label => label.push_str(&text),
+                None => {}
+            },
+            Event::Code(text) if in_label => label.push_str(&text),
+            Event::End(TagEnd::CodeBlock) => match block.take() {
+                Some((Some(SampleKind::Input), _, content)) => pending_input = Some(content),
+                Some((Some(SampleKind::Output), line, content)) => cases.push(SampleCase {
+                    stdin: pending_input.take().unwrap_or_default(),
+                    expected_output: content,
+                    line,
+                }),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    cases
+}
+
+fn sample_kind(label: &str) -> Option<SampleKind> {
+    let label = label.to_lowercase();
+    let words: Vec<&str> = label
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let has = |names: &[&str]| words.iter().any(|word| names.contains(word));
+    if has(&["output", "stdout", "expected", "keluaran"]) {
+        Some(SampleKind::Output)
+    } else if has(&["input", "stdin", "masukan"]) {
+        Some(SampleKind::Input)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,200 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use crate::dto::{DatabaseMetrics, ExecutorMetrics, GradingRetryMetrics, SlowQueryRoute};
+
+/// Routes listed under `slowRoutes`.
+const SLOW_ROUTES_LISTED: usize = 10;
+
+/// Distinct routes tracked for slow statements; later ones are counted as `other`.
+const MAX_SLOW_ROUTES: usize = 256;
+
+/// Process-wide counters, reset on restart.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    pub judge0: ExecutorCounters,
+    pub grading: GradingCounters,
+    pub database: DatabaseCounters,
+}
+
+/// Statements timed by sea-orm's metric callback; see `services::sql_trace`.
+#[derive(Debug, Default)]
+pub struct DatabaseCounters {
+    statements: AtomicU64,
+    failed: AtomicU64,
+    slow: AtomicU64,
+    /// `0` while slow statements are not logged.
+    slow_threshold_ms: AtomicU64,
+    duration_us: AtomicU64,
+    slowest_us: AtomicU64,
+    slow_routes: Mutex<HashMap<String, u64>>,
+}
+
+impl DatabaseCounters {
+    pub fn set_slow_threshold(&self, threshold: Option<Duration>) {
+        let millis = threshold.map_or(0, |threshold| threshold.as_millis() as u64);
+        self.slow_threshold_ms.store(millis, Ordering::Relaxed);
+    }
+
+    /// Counts one statement; `slow_route` is set when it was over the threshold.
+    pub fn record(&self, elapsed: Duration, failed: bool, slow_route: Option<&str>) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        self.statements.fetch_add(1, Ordering::Relaxed);
+        self.duration_us.fetch_add(micros, Ordering::Relaxed);
+        self.slowest_us.fetch_max(micros, Ordering::Relaxed);
+        if failed {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+        if let Some(route) = slow_route {
+            self.slow.fetch_add(1, Ordering::Relaxed);
+            let mut routes = self
+                .slow_routes
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let key = if routes.len() < MAX_SLOW_ROUTES || routes.contains_key(route) {
+                route
+            } else {
+                "other"
+            };
+            *routes.entry(key.to_owned()).or_default() += 1;
+        }
+    }
+
+    pub fn snapshot(&self) -> DatabaseMetrics {
+        let mut slow_routes: Vec<SlowQueryRoute> = self
+            .slow_routes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|(route, slow)| SlowQueryRoute {
+                route: route.clone(),
+                slow: *slow,
+            })
+            .collect();
+        slow_routes.sort_by(|a, b| b.slow.cmp(&a.slow).then_with(|| a.route.cmp(&b.route)));
+        slow_routes.truncate(SLOW_ROUTES_LISTED);
+
+        let slow_threshold_ms = self.slow_threshold_ms.load(Ordering::Relaxed);
+        DatabaseMetrics {
+            statements: self.statements.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            slow: self.slow.load(Ordering::Relaxed),
+            slow_threshold_ms: (slow_threshold_ms > 0).then_some(slow_threshold_ms),
+            total_duration_ms: self.duration_us.load(Ordering::Relaxed) / 1000,
+            slowest_ms: self.slowest_us.load(Ordering::Relaxed) / 1000,
+            slow_routes,
+        }
+    }
+}
+
+/// Outcomes of the grading retry worker and dead-letter handling.
+#[derive(Debug, Default)]
+pub struct GradingCounters {
+    pub retried: AtomicU64,
+    pub recovered: AtomicU64,
+    pub dead_lettered: AtomicU64,
+    pub requeued: AtomicU64,
+}
+
+impl GradingCounters {
+    /// Counters plus the current backlog, which the caller reads from the database.
+    pub fn snapshot(&self, pending_retries: u64, dead_letters: u64) -> GradingRetryMetrics {
+        GradingRetryMetrics {
+            retried: self.retried.load(Ordering::Relaxed),
+            recovered: self.recovered.load(Ordering::Relaxed),
+            dead_lettered: self.dead_lettered.load(Ordering::Relaxed),
+            requeued: self.requeued.load(Ordering::Relaxed),
+            pending_retries,
+            dead_letters,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ExecutorCounters {
+    requests: AtomicU64,
+    succeeded: AtomicU64,
+    failed: AtomicU64,
+    timed_out: AtomicU64,
+    cancelled: AtomicU64,
+    in_flight: AtomicU64,
+    /// Summed duration of every completed call, in milliseconds.
+    latency_ms: AtomicU64,
+}
+
+pub enum CallOutcome {
+    Succeeded,
+    Failed,
+    TimedOut,
+}
+
+impl ExecutorCounters {
+    /// Counts a new outgoing call; the returned guard records how it ended.
+    pub fn start(&self) -> ExecutorCall<'_> {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        ExecutorCall {
+            counters: self,
+            started: Instant::now(),
+            finished: false,
+        }
+    }
+
+    pub fn snapshot(&self) -> ExecutorMetrics {
+        let succeeded = self.succeeded.load(Ordering::Relaxed);
+        let failed = self.failed.load(Ordering::Relaxed);
+        let timed_out = self.timed_out.load(Ordering::Relaxed);
+        let total_latency_ms = self.latency_ms.load(Ordering::Relaxed);
+        let completed = succeeded + failed + timed_out;
+        ExecutorMetrics {
+            requests: self.requests.load(Ordering::Relaxed),
+            succeeded,
+            failed,
+            timed_out,
+            cancelled: self.cancelled.load(Ordering::Relaxed),
+            in_flight: self.in_flight.load(Ordering::Relaxed),
+            total_latency_ms,
+            average_latency_ms: total_latency_ms.checked_div(completed).unwrap_or(0),
+        }
+    }
+}
+
+/// In-flight executor call. Dropping it unfinished means the caller went away, e.g.
+/// the client disconnected and axum dropped the handler, and counts as cancelled.
+pub struct ExecutorCall<'a> {
+    counters: &'a ExecutorCounters,
+    started: Instant,
+    finished: bool,
+}
+
+impl ExecutorCall<'_> {
+    pub fn finish(mut self, outcome: CallOutcome) {
+        let counter = match outcome {
+            CallOutcome::Succeeded => &self.counters.succeeded,
+            CallOutcome::Failed => &self.counters.failed,
+            CallOutcome::TimedOut => &self.counters.timed_out,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        let elapsed_ms = u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.counters
+            .latency_ms
+            .fetch_add(elapsed_ms, Ordering::Relaxed);
+        self.finished = true;
+    }
+}
+
+impl Drop for ExecutorCall<'_> {
+    fn drop(&mut self) {
+        self.counters.in_flight.fetch_sub(1, Ordering::Relaxed);
+        if !self.finished {
+            self.counters.cancelled.fetch_add(1, Ordering::Relaxed);
+            tracing::info!("Judge0 request cancelled before it completed");
+        }
+    }
+}
@@ -0,0 +1,75 @@
+pub mod account_transfer;
+pub mod accounts;
+pub mod activity;
+pub mod admission;
+pub mod alerting;
+pub mod archive;
+pub mod artifacts;
+pub mod audit;
+pub mod banner;
+pub mod case_stats;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+pub mod classroom_deletion;
+pub mod client_config;
+pub mod client_errors;
+pub mod code_encryption;
+pub mod code_visibility;
+pub mod connections;
+pub mod csv;
+pub mod daily_stats;
+pub mod diagnostics;
+pub mod display_time;
+pub mod event_bus;
+pub mod exam_clock;
+pub mod exam_gate;
+pub mod executor_pool;
+pub mod export_jobs;
+pub mod export_storage;
+pub mod feedback;
+pub mod field_limits;
+pub mod finalizer;
+pub mod fingerprint;
+pub mod grades;
+pub mod grading;
+pub mod grading_progress;
+pub mod grading_retry;
+pub mod highlight;
+pub mod judge0;
+pub mod judge0_poll;
+pub mod language_map;
+pub mod language_usage;
+pub mod login_audit;
+pub mod mailer;
+pub mod markdown;
+pub mod metrics;
+pub mod openapi_diff;
+pub mod output;
+pub mod packet;
+pub mod pdf;
+pub mod post_finish;
+pub mod purge;
+pub mod rate_limit;
+pub mod readiness;
+pub mod replay_guard;
+pub mod replication;
+pub mod retention;
+pub mod roster;
+pub mod sandbox;
+pub mod schedule;
+pub mod schema;
+pub mod seating;
+pub mod secret_box;
+pub mod settings_history;
+pub mod signing;
+pub mod sql_trace;
+pub mod student_events;
+pub mod submission_cooldown;
+pub mod submission_history;
+pub mod task_deadline;
+pub mod task_time;
+pub mod test_runner;
+pub mod tickets;
+pub mod time_policy;
+pub mod verdict_messages;
+pub mod webhook;
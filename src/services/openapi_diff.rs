@@ -0,0 +1,215 @@
+use serde_json::{Map, Value};
+
+use crate::dto::{ApiChange, ApiChangeKind};
+
+const HTTP_METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch"];
+
+/// Changes from `baseline` to `current`, both OpenAPI 3 documents as JSON. Removals,
+/// type changes and newly required inputs are breaking; additions are not.
+pub fn diff(baseline: &Value, current: &Value) -> Vec<ApiChange> {
+    let mut changes = Vec::new();
+    diff_paths(
+        object(baseline, "paths"),
+        object(current, "paths"),
+        &mut changes,
+    );
+    diff_schemas(
+        object(&baseline["components"], "schemas"),
+        object(&current["components"], "schemas"),
+        &mut changes,
+    );
+    changes
+}
+
+fn diff_paths(
+    baseline: &Map<String, Value>,
+    current: &Map<String, Value>,
+    changes: &mut Vec<ApiChange>,
+) {
+    for (path, baseline_item) in baseline {
+        let Some(current_item) = current.get(path) else {
+            changes.push(change(ApiChangeKind::RemovedPath, path.clone(), None));
+            continue;
+        };
+
+        for method in HTTP_METHODS {
+            let location = format!("{} {path}", method.to_uppercase());
+            match (baseline_item.get(*method), current_item.get(*method)) {
+                (Some(_), None) => {
+                    changes.push(change(ApiChangeKind::RemovedOperation, location, None));
+                }
+                (None, Some(_)) => {
+                    changes.push(change(ApiChangeKind::AddedOperation, location, None));
+                }
+                (Some(before), Some(after)) => {
+                    diff_parameters(before, after, &location, changes);
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    for path in current.keys().filter(|path| !baseline.contains_key(*path)) {
+        changes.push(change(ApiChangeKind::AddedPath, path.clone(), None));
+    }
+}
+
+fn diff_parameters(before: &Value, after: &Value, location: &str, changes: &mut Vec<ApiChange>) {
+    let parameters = |operation: &Value| -> Vec<(String, String, bool)> {
+        operation["parameters"]
+            .as_array()
+            .map(|parameters| {
+                parameters
+                    .iter()
+                    .filter_map(|parameter| {
+                        Some((
+                            parameter["name"].as_str()?.to_owned(),
+                            parameter["in"].as_str()?.to_owned(),
+                            parameter["required"].as_bool().unwrap_or(false),
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let before = parameters(before);
+    let after = parameters(after);
+
+    for (name, place, _) in &before {
+        if !after.iter().any(|(n, p, _)| n == name && p == place) {
+            changes.push(change(
+                ApiChangeKind::RemovedParameter,
+                format!("{location} {place}:{name}"),
+                None,
+            ));
+        }
+    }
+    for (name, place, required) in &after {
+        let was_required = before
+            .iter()
+            .find(|(n, p, _)| n == name && p == place)
+            .map(|(_, _, required)| *required);
+        if *required && was_required != Some(true) {
+            changes.push(change(
+                ApiChangeKind::ParameterNowRequired,
+                format!("{location} {place}:{name}"),
+                None,
+            ));
+        }
+    }
+}
+
+fn diff_schemas(
+    baseline: &Map<String, Value>,
+    current: &Map<String, Value>,
+    changes: &mut Vec<ApiChange>,
+) {
+    for (name, before) in baseline {
+        let Some(after) = current.get(name) else {
+            changes.push(change(ApiChangeKind::RemovedSchema, name.clone(), None));
+            continue;
+        };
+
+        let before_properties = object(before, "properties");
+        let after_properties = object(after, "properties");
+        for (field, before_field) in before_properties {
+            let location = format!("{name}.{field}");
+            let Some(after_field) = after_properties.get(field) else {
+                changes.push(change(ApiChangeKind::RemovedField, location, None));
+                continue;
+            };
+            let (before_type, after_type) = (type_of(before_field), type_of(after_field));
+            if before_type.is_some() && after_type.is_some() && before_type != after_type {
+                changes.push(change(
+                    ApiChangeKind::FieldTypeChanged,
+                    location,
+                    Some(format!(
+                        "{} -> {}",
+                        before_type.unwrap_or_default(),
+                        after_type.unwrap_or_default()
+                    )),
+                ));
+            }
+        }
+        for field in after_properties.keys() {
+            if !before_properties.contains_key(field) {
+                changes.push(change(
+                    ApiChangeKind::AddedField,
+                    format!("{name}.{field}"),
+                    None,
+                ));
+            }
+        }
+
+        let before_required = strings(&before["required"]);
+        for field in strings(&after["required"]) {
+            if !before_required.contains(&field) {
+                changes.push(change(
+                    ApiChangeKind::FieldNowRequired,
+                    format!("{name}.{field}"),
+                    None,
+                ));
+            }
+        }
+
+        let after_values = strings(&after["enum"]);
+        for value in strings(&before["enum"]) {
+            if !after_values.contains(&value) {
+                changes.push(change(
+                    ApiChangeKind::RemovedEnumValue,
+                    name.clone(),
+                    Some(value),
+                ));
+            }
+        }
+    }
+
+    for name in current.keys().filter(|name| !baseline.contains_key(*name)) {
+        changes.push(change(ApiChangeKind::AddedSchema, name.clone(), None));
+    }
+}
+
+/// Referenced schema or JSON type of a property, with array items in brackets.
+fn type_of(schema: &Value) -> Option<String> {
+    if let Some(reference) = schema["$ref"].as_str() {
+        return Some(reference.rsplit('/').next().unwrap_or(reference).to_owned());
+    }
+    if let Some([only]) = schema["allOf"].as_array().map(Vec::as_slice) {
+        return type_of(only);
+    }
+    match schema["type"].as_str()? {
+        "array" => Some(format!(
+            "[{}]",
+            type_of(&schema["items"]).unwrap_or_default()
+        )),
+        kind => Some(kind.to_owned()),
+    }
+}
+
+fn object<'a>(value: &'a Value, key: &str) -> &'a Map<String, Value> {
+    static EMPTY: std::sync::OnceLock<Map<String, Value>> = std::sync::OnceLock::new();
+    value[key]
+        .as_object()
+        .unwrap_or_else(|| EMPTY.get_or_init(Map::new))
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(str::to_owned))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn change(kind: ApiChangeKind, location: String, detail: Option<String>) -> ApiChange {
+    ApiChange {
+        breaking: kind.is_breaking(),
+        kind,
+        location,
+        detail,
+    }
+}
@@ -0,0 +1,75 @@
+use serde_json::Value;
+
+use crate::{dto::Judge0SubmissionResponse, entities::classroom, state::AppState};
+
+/// Largest per-classroom cap; Judge0 rejects `max_file_size` above its own
+/// `MAX_MAX_FILE_SIZE`, which defaults to 4096 KB.
+pub const MAX_LIMIT_KB: i32 = 4096;
+
+/// Appended to output that was cut at the cap.
+pub const TRUNCATION_MARKER: &str = "\n…[output truncated]";
+
+/// Output cap of runs in the classroom, in KB: its own setting or the server default.
+pub fn limit_kb(state: &AppState, classroom_model: &classroom::Model) -> u32 {
+    classroom_model
+        .output_limit_kb
+        .and_then(|limit| u32::try_from(limit).ok())
+        .filter(|limit| *limit > 0)
+        .unwrap_or(state.output_limit_kb)
+}
+
+/// Caps stdout and stderr of a Judge0 result, recording their original sizes.
+pub fn limit_response(response: &mut Judge0SubmissionResponse, limit_kb: u32) {
+    let max_bytes = max_bytes(limit_kb);
+    response.stdout_bytes = response.stdout.as_ref().map(String::len);
+    response.stderr_bytes = response.stderr.as_ref().map(String::len);
+    let stdout_cut = truncate(&mut response.stdout, max_bytes);
+    let stderr_cut = truncate(&mut response.stderr, max_bytes);
+    response.truncated = stdout_cut || stderr_cut;
+}
+
+/// [`limit_response`] for results passed through as raw JSON.
+pub fn limit_value(value: &mut Value, limit_kb: u32) {
+    let Some(object) = value.as_object_mut() else {
+        return;
+    };
+    let max_bytes = max_bytes(limit_kb);
+
+    let mut truncated = false;
+    for field in ["stdout", "stderr"] {
+        let Some(Value::String(text)) = object.get(field) else {
+            continue;
+        };
+        let original_bytes = text.len();
+        let mut text = Some(text.clone());
+        if truncate(&mut text, max_bytes) {
+            truncated = true;
+            object.insert(field.to_owned(), Value::from(text));
+        }
+        object.insert(format!("{field}_bytes"), Value::from(original_bytes));
+    }
+    object.insert("truncated".to_owned(), Value::Bool(truncated));
+}
+
+fn max_bytes(limit_kb: u32) -> usize {
+    limit_kb as usize * 1024
+}
+
+/// Cuts `text` to at most `max_bytes` on a character boundary and appends the
+/// marker. Returns whether anything was removed.
+pub fn truncate(text: &mut Option<String>, max_bytes: usize) -> bool {
+    let Some(text) = text else {
+        return false;
+    };
+    if text.len() <= max_bytes {
+        return false;
+    }
+
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    text.push_str(TRUNCATION_MARKER);
+    true
+}
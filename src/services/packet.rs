@@ -0,0 +1,200 @@
+//! Per-student exam packet: the final code, every verdict, the grade broken down by
+//! task and test case, integrity flags and dispute threads, gathered from what is
+//! already stored. Nothing is re-run, so the packet shows the grade as it stands.
+
+use std::{collections::HashMap, io::Write};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use zip::{ZipWriter, write::FileOptions};
+
+use crate::{
+    dto::{
+        DisputeResponse, ExamPacket, IntegrityEventResponse, PacketCaseResult, PacketGrade,
+        PacketTaskScore, PacketVerdict, SubmissionHistoryEntry,
+    },
+    entities::{
+        classroom, dispute, integrity_event, submission, submission_case_result, task, test_case,
+        user,
+    },
+    error::AppError,
+    services::{grading, highlight, seating::SeatIndex},
+};
+
+pub async fn build(
+    db: &DatabaseConnection,
+    classroom_model: &classroom::Model,
+    user_model: &user::Model,
+    now: DateTime<Utc>,
+) -> Result<ExamPacket, AppError> {
+    let submissions = submission::Entity::find()
+        .filter(submission::Column::UserId.eq(user_model.id))
+        .order_by_asc(submission::Column::Id)
+        .all(db)
+        .await?;
+    let final_submission = submissions.iter().rev().find(|model| model.is_final);
+    let grade = match final_submission {
+        Some(model) if model.graded_at.is_some() => Some(grade(db, classroom_model, model).await?),
+        _ => None,
+    };
+
+    let classroom_users = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(classroom_model.id))
+        .all(db)
+        .await?;
+    let seats = SeatIndex::new(&classroom_users);
+    let integrity_events = integrity_event::Entity::find()
+        .filter(integrity_event::Column::ClassroomId.eq(classroom_model.id))
+        .filter(integrity_event::Column::UserId.eq(user_model.id))
+        .order_by_asc(integrity_event::Column::Id)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|model| IntegrityEventResponse::from_models(model, user_model, &seats))
+        .collect();
+
+    let by_id: HashMap<i32, &submission::Model> =
+        submissions.iter().map(|model| (model.id, model)).collect();
+    let comments = dispute::Entity::find()
+        .filter(dispute::Column::UserId.eq(user_model.id))
+        .order_by_asc(dispute::Column::Id)
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|model| {
+            let submission_model = by_id.get(&model.submission_id)?;
+            Some(DisputeResponse::from_models(
+                model,
+                submission_model,
+                user_model,
+            ))
+        })
+        .collect();
+
+    Ok(ExamPacket {
+        classroom_id: classroom_model.id,
+        classroom_name: classroom_model.name.clone(),
+        user_id: user_model.id,
+        npm: user_model.npm.clone(),
+        name: user_model.name.clone(),
+        saved_code: user_model.code.to_string(),
+        language_id: user_model
+            .language_id
+            .unwrap_or_else(|| grading::classroom_language_id(classroom_model)),
+        final_submission: final_submission.cloned().map(SubmissionHistoryEntry::from),
+        grade,
+        submissions: submissions.iter().map(PacketVerdict::from).collect(),
+        integrity_events,
+        comments,
+        generated_at: now,
+    })
+}
+
+/// Stored case results of the graded `submission_model`, with the points each case
+/// carries after its task's weight.
+async fn grade(
+    db: &DatabaseConnection,
+    classroom_model: &classroom::Model,
+    submission_model: &submission::Model,
+) -> Result<PacketGrade, AppError> {
+    let results = submission_case_result::Entity::find()
+        .filter(submission_case_result::Column::SubmissionId.eq(submission_model.id))
+        .order_by_asc(submission_case_result::Column::Id)
+        .all(db)
+        .await?;
+    let test_cases: HashMap<i32, test_case::Model> = test_case::Entity::find()
+        .filter(test_case::Column::Id.is_in(results.iter().map(|result| result.test_case_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|model| (model.id, model))
+        .collect();
+    let tasks = task::Entity::find()
+        .filter(task::Column::ClassroomId.eq(classroom_model.id))
+        .order_by_asc(task::Column::Position)
+        .order_by_asc(task::Column::Id)
+        .all(db)
+        .await?;
+    let weights: HashMap<i32, i32> = tasks.iter().map(|model| (model.id, model.weight)).collect();
+
+    // Cases deleted since the grading keep their result but no longer have points.
+    let cases: Vec<PacketCaseResult> = results
+        .into_iter()
+        .filter_map(|result| {
+            let test_case = test_cases.get(&result.test_case_id)?;
+            Some(PacketCaseResult {
+                test_case_id: test_case.id,
+                task_id: test_case.task_id,
+                hidden: test_case.hidden,
+                passed: result.passed,
+                points: test_case.points * weights.get(&test_case.task_id).copied().unwrap_or(1),
+                status: result.status,
+            })
+        })
+        .collect();
+    let task_scores = tasks
+        .into_iter()
+        .filter_map(|task_model| {
+            let task_cases: Vec<&PacketCaseResult> = cases
+                .iter()
+                .filter(|case| case.task_id == task_model.id)
+                .collect();
+            (!task_cases.is_empty()).then(|| PacketTaskScore {
+                task_id: task_model.id,
+                title: task_model.title,
+                weight: task_model.weight,
+                earned: task_cases
+                    .iter()
+                    .filter(|case| case.passed)
+                    .map(|case| case.points)
+                    .sum(),
+                possible: task_cases.iter().map(|case| case.points).sum(),
+            })
+        })
+        .collect();
+
+    Ok(PacketGrade {
+        submission_id: submission_model.id,
+        score: submission_model.score,
+        max_score: submission_model.max_score,
+        passed_cases: submission_model.passed_cases,
+        total_cases: submission_model.total_cases,
+        graded_at: submission_model.graded_at,
+        tasks: task_scores,
+        cases,
+        script_verdict: submission_model
+            .script_verdict
+            .as_deref()
+            .and_then(|raw| serde_json::from_str(raw).ok()),
+    })
+}
+
+/// ZIP of the packet: `packet.json`, plus `final.<ext>` with the handed-in code and
+/// `saved.<ext>` with the code saved on the roster entry, when there is any.
+pub fn bundle(packet: &ExamPacket) -> Result<Vec<u8>, AppError> {
+    let document = serde_json::to_vec_pretty(packet)
+        .map_err(|err| AppError::Internal(format!("exam packet: {err}")))?;
+
+    let mut files: Vec<(String, &[u8])> = vec![("packet.json".to_owned(), &document)];
+    if let Some(final_submission) = &packet.final_submission {
+        let extension = highlight::extension_for(final_submission.language_id).unwrap_or("txt");
+        files.push((
+            format!("final.{extension}"),
+            final_submission.source_code.as_bytes(),
+        ));
+    }
+    if !packet.saved_code.trim().is_empty() {
+        let extension = highlight::extension_for(packet.language_id).unwrap_or("txt");
+        files.push((format!("saved.{extension}"), packet.saved_code.as_bytes()));
+    }
+
+    let zip_error = |err: zip::result::ZipError| AppError::Internal(format!("zip: {err}"));
+    let io_error = |err: std::io::Error| AppError::Internal(format!("zip: {err}"));
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, contents) in files {
+        zip.start_file(name, options).map_err(zip_error)?;
+        zip.write_all(contents).map_err(io_error)?;
+    }
+    Ok(zip.finish().map_err(zip_error)?.into_inner())
+}
@@ -0,0 +1,110 @@
+//! Minimal writer for text-only PDFs: lines of Courier on A4 pages and nothing else,
+//! so the text stays selectable and is read in order by screen readers.
+
+use std::io::Write;
+
+const PAGE_WIDTH: u32 = 595;
+const PAGE_HEIGHT: u32 = 842;
+const MARGIN: u32 = 56;
+const FONT_SIZE: u32 = 11;
+const LEADING: u32 = 15;
+
+/// Lines that fit on one page.
+pub const LINES_PER_PAGE: usize = ((PAGE_HEIGHT - 2 * MARGIN) / LEADING) as usize;
+
+/// Characters of Courier that fit on one line.
+pub const CHARS_PER_LINE: usize = ((PAGE_WIDTH - 2 * MARGIN) * 10 / (FONT_SIZE * 6)) as usize;
+
+/// Renders `pages` of text lines. `title` is shown as the window title and `language`
+/// (e.g. `id-ID`) tells screen readers how to read the text. Characters outside
+/// Latin-1 are printed as `?`.
+pub fn text_document(title: &str, language: &str, pages: &[Vec<String>]) -> Vec<u8> {
+    let first_page = 5;
+    let kids: Vec<String> = (0..pages.len())
+        .map(|index| format!("{} 0 R", first_page + 2 * index))
+        .collect();
+
+    let mut objects: Vec<Vec<u8>> = vec![
+        [
+            b"<< /Type /Catalog /Pages 2 0 R /Lang ".as_slice(),
+            &string(language),
+            b" /ViewerPreferences << /DisplayDocTitle true >> >>",
+        ]
+        .concat(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+        [
+            b"<< /Title ".as_slice(),
+            &string(title),
+            b" /Producer (ASM-LAB-SERVER) >>",
+        ]
+        .concat(),
+    ];
+
+    for (index, lines) in pages.iter().enumerate() {
+        let contents = first_page + 2 * index + 1;
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R >> >> /Contents {contents} 0 R >>"
+            )
+            .into_bytes(),
+        );
+
+        let mut stream = format!(
+            "BT /F1 {FONT_SIZE} Tf {LEADING} TL {MARGIN} {} Td\n",
+            PAGE_HEIGHT - MARGIN - FONT_SIZE
+        )
+        .into_bytes();
+        for line in lines {
+            stream.extend_from_slice(&string(line));
+            stream.extend_from_slice(b" Tj T*\n");
+        }
+        stream.extend_from_slice(b"ET");
+
+        let mut object = format!("<< /Length {} >>\nstream\n", stream.len()).into_bytes();
+        object.extend_from_slice(&stream);
+        object.extend_from_slice(b"\nendstream");
+        objects.push(object);
+    }
+
+    let mut out: Vec<u8> = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        let _ = writeln!(out, "{} 0 obj", index + 1);
+        out.extend_from_slice(object);
+        out.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = out.len();
+    let _ = write!(out, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(out, "{offset:010} 00000 n ");
+    }
+    let _ = write!(
+        out,
+        "trailer\n<< /Size {} /Root 1 0 R /Info 4 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        objects.len() + 1
+    );
+    out
+}
+
+/// A PDF literal string.
+fn string(text: &str) -> Vec<u8> {
+    let mut out = vec![b'('];
+    for ch in text.chars() {
+        match ch {
+            '\\' | '(' | ')' => out.extend_from_slice(&[b'\\', ch as u8]),
+            ' '..='~' | '\u{a0}'..='\u{ff}' => out.push(ch as u32 as u8),
+            _ => out.push(b'?'),
+        }
+    }
+    out.push(b')');
+    out
+}
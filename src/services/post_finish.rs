@@ -0,0 +1,71 @@
+//! What finishing an exam does to the student, set per classroom: deactivate them
+//! (the default), leave them active so practice classrooms can keep submitting, or
+//! keep them logged in with graded submissions locked until staff reactivate them.
+//! Hand-ins, auto-finalization and the post-exam deactivation all go through here.
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, sea_query::Expr};
+
+use crate::{
+    dto::PostFinishPolicy,
+    entities::{classroom, user},
+    error::AppError,
+};
+
+/// Applies the classroom's policy to a student who just finished.
+pub fn apply(
+    classroom_model: &classroom::Model,
+    user_am: &mut user::ActiveModel,
+    now: DateTime<Utc>,
+) {
+    match PostFinishPolicy::of(classroom_model) {
+        PostFinishPolicy::Deactivate => user_am.active = sea_orm::ActiveValue::Set(false),
+        PostFinishPolicy::KeepActive => {}
+        PostFinishPolicy::LockSubmissions => {
+            user_am.submissions_locked_at = sea_orm::ActiveValue::Set(Some(now));
+        }
+    }
+}
+
+/// [`apply`] for several students at once.
+pub async fn apply_many(
+    db: &impl ConnectionTrait,
+    classroom_model: &classroom::Model,
+    user_ids: Vec<i32>,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    let update = match PostFinishPolicy::of(classroom_model) {
+        PostFinishPolicy::KeepActive => return Ok(()),
+        PostFinishPolicy::Deactivate => {
+            user::Entity::update_many().col_expr(user::Column::Active, false.into())
+        }
+        PostFinishPolicy::LockSubmissions => user::Entity::update_many()
+            .col_expr(user::Column::SubmissionsLockedAt, Expr::value(now))
+            .filter(user::Column::SubmissionsLockedAt.is_null()),
+    };
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+    update
+        .col_expr(user::Column::UpdatedAt, Expr::value(now))
+        .filter(user::Column::Id.is_in(user_ids))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Whether the student's current attempt has ended: deactivated, or locked after
+/// finishing. Reactivating the student starts a new one.
+pub fn attempt_ended(user_model: &user::Model) -> bool {
+    !user_model.active || user_model.submissions_locked_at.is_some()
+}
+
+/// Refuses a graded submission from a student locked after finishing.
+pub fn ensure_unlocked(user_model: &user::Model) -> Result<(), AppError> {
+    if user_model.submissions_locked_at.is_some() {
+        return Err(AppError::Forbidden(
+            "submissions are locked after finishing the exam".into(),
+        ));
+    }
+    Ok(())
+}
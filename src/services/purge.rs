@@ -0,0 +1,313 @@
+//! Erasure of a student on request: every roster entry with the student's NPM, their
+//! account, and everything hanging off them (code, submissions, events, logs) are
+//! deleted in one transaction. On SQLite the deleted rows are also overwritten in the
+//! database file (`secure_delete`), then the file is vacuumed and the WAL checkpointed,
+//! so the data cannot be carved out of free pages afterwards. Student code is encrypted with server-wide
+//! keys, so the receipt lists the keys involved: copies in older backups stay
+//! readable until those keys are rotated out and dropped.
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait, DatabaseBackend,
+    EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QuerySelect, Statement,
+    TransactionTrait,
+};
+
+use crate::{
+    dto::{AccountRole, PurgeReceipt, PurgeRowCounts},
+    entities::{
+        account, audit_log, classroom_event, client_error, code_fingerprint, dispute,
+        grading_dead_letter, integrity_event, login_event, message_recipient, run_config,
+        submission, submission_case_result, task_progress, user, webauthn_credential,
+    },
+    error::AppError,
+    services::{code_encryption, signing},
+    state::AppState,
+};
+
+/// Deletes everything stored about the student on roster entry `user_id` and returns
+/// the signed receipt. `confirm_npm` must repeat the student's NPM.
+pub async fn purge_student(
+    state: &AppState,
+    user_id: i32,
+    confirm_npm: &str,
+    actor_id: i32,
+    now: DateTime<Utc>,
+) -> Result<PurgeReceipt, AppError> {
+    let db = &state.db;
+    let target = user::Entity::find_by_id(user_id)
+        .one(db)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+    let npm = target.npm;
+    if confirm_npm.trim() != npm {
+        return Err(AppError::ConfirmationRequired(
+            "confirmNpm must repeat the student's NPM".into(),
+        ));
+    }
+
+    let account = account::Entity::find()
+        .filter(account::Column::Npm.eq(&npm))
+        .one(db)
+        .await?;
+    if let Some(account) = &account {
+        if account.id == actor_id {
+            return Err(AppError::BadRequest("you cannot purge your own account".into()));
+        }
+        if AccountRole::from_str(&account.role) != Some(AccountRole::User) {
+            return Err(AppError::BadRequest(
+                "only student accounts can be purged; change the account's role first".into(),
+            ));
+        }
+    }
+
+    let txn = db.begin().await?;
+    let secure_delete = txn.get_database_backend() == DatabaseBackend::Sqlite;
+    if secure_delete {
+        pragma(&txn, "PRAGMA secure_delete = ON").await?;
+    }
+
+    let users = user::Entity::find()
+        .filter(user::Column::Npm.eq(&npm))
+        .all(&txn)
+        .await?;
+    let user_ids: Vec<i32> = users.iter().map(|model| model.id).collect();
+    let mut classroom_ids: Vec<i32> = users.iter().map(|model| model.classroom_id).collect();
+    classroom_ids.sort_unstable();
+    classroom_ids.dedup();
+
+    // Selected as raw strings so the envelope, and with it the key id, stays visible.
+    let mut stored_code: Vec<String> = user::Entity::find()
+        .select_only()
+        .column(user::Column::Code)
+        .filter(user::Column::Id.is_in(user_ids.clone()))
+        .into_tuple()
+        .all(&txn)
+        .await?;
+    let submissions: Vec<(i32, String)> = submission::Entity::find()
+        .select_only()
+        .column(submission::Column::Id)
+        .column(submission::Column::SourceCode)
+        .filter(submission::Column::UserId.is_in(user_ids.clone()))
+        .into_tuple()
+        .all(&txn)
+        .await?;
+    let submission_ids: Vec<i32> = submissions.iter().map(|(id, _)| *id).collect();
+    stored_code.extend(submissions.into_iter().map(|(_, code)| code));
+    let mut sealed_key_ids: Vec<String> = stored_code
+        .iter()
+        .filter_map(|stored| code_encryption::sealed_key_id(stored))
+        .map(str::to_owned)
+        .collect();
+    sealed_key_ids.sort_unstable();
+    sealed_key_ids.dedup();
+
+    // Counted before the roster entries and the account go, taking these with them.
+    let mut counts = PurgeRowCounts {
+        submissions: submission_ids.len() as u64,
+        case_results: submission_case_result::Entity::find()
+            .filter(submission_case_result::Column::SubmissionId.is_in(submission_ids.clone()))
+            .count(&txn)
+            .await?,
+        dead_letters: grading_dead_letter::Entity::find()
+            .filter(grading_dead_letter::Column::SubmissionId.is_in(submission_ids.clone()))
+            .count(&txn)
+            .await?,
+        disputes: dispute::Entity::find()
+            .filter(dispute::Column::UserId.is_in(user_ids.clone()))
+            .count(&txn)
+            .await?,
+        integrity_events: integrity_event::Entity::find()
+            .filter(integrity_event::Column::UserId.is_in(user_ids.clone()))
+            .count(&txn)
+            .await?,
+        task_progress: task_progress::Entity::find()
+            .filter(task_progress::Column::UserId.is_in(user_ids.clone()))
+            .count(&txn)
+            .await?,
+        run_configs: run_config::Entity::find()
+            .filter(run_config::Column::UserId.is_in(user_ids.clone()))
+            .count(&txn)
+            .await?,
+        message_receipts: message_recipient::Entity::find()
+            .filter(message_recipient::Column::UserId.is_in(user_ids.clone()))
+            .count(&txn)
+            .await?,
+        ..Default::default()
+    };
+
+    // Fingerprints have no foreign key to their submission.
+    counts.fingerprints = code_fingerprint::Entity::delete_many()
+        .filter(code_fingerprint::Column::SubmissionId.is_in(submission_ids))
+        .exec(&txn)
+        .await?
+        .rows_affected;
+    counts.classroom_events = scrub_events(&txn, &classroom_ids, &user_ids, &npm).await?;
+
+    let mut login_events = Condition::any().add(login_event::Column::Npm.eq(&npm));
+    if let Some(account) = &account {
+        login_events = login_events.add(login_event::Column::AccountId.eq(account.id));
+    }
+    counts.login_events = login_event::Entity::delete_many()
+        .filter(login_events)
+        .exec(&txn)
+        .await?
+        .rows_affected;
+    counts.client_errors = client_error::Entity::delete_many()
+        .filter(client_error::Column::Npm.eq(&npm))
+        .exec(&txn)
+        .await?
+        .rows_affected;
+
+    if let Some(account) = &account {
+        counts.passkeys = webauthn_credential::Entity::find()
+            .filter(webauthn_credential::Column::AccountId.eq(account.id))
+            .count(&txn)
+            .await?;
+        counts.audit_entries = audit_log::Entity::delete_many()
+            .filter(audit_log::Column::AccountId.eq(account.id))
+            .exec(&txn)
+            .await?
+            .rows_affected;
+        counts.accounts = account::Entity::delete_by_id(account.id)
+            .exec(&txn)
+            .await?
+            .rows_affected;
+    }
+    counts.roster_entries = user::Entity::delete_many()
+        .filter(user::Column::Id.is_in(user_ids))
+        .exec(&txn)
+        .await?
+        .rows_affected;
+
+    if secure_delete {
+        pragma(&txn, "PRAGMA secure_delete = OFF").await?;
+    }
+    txn.commit().await?;
+    // Earlier updates of the student's rows left stale copies in free space that
+    // secure_delete never saw; rewriting the file drops them.
+    let secure_delete = secure_delete && compact(db).await;
+
+    let mut receipt = PurgeReceipt {
+        receipt_id: hex::encode(rand::random::<[u8; 16]>()),
+        subject_hash: subject_hash(state, &npm),
+        classroom_ids,
+        counts,
+        sealed_key_ids,
+        secure_delete,
+        purged_by: actor_id,
+        purged_at: now,
+        signing_key_id: state.secrets.key_id().to_owned(),
+        signature: String::new(),
+    };
+    receipt.signature = signature(state, &receipt);
+    Ok(receipt)
+}
+
+/// Whether `receipt` was signed by this server's current secret and was not altered.
+pub fn verify(state: &AppState, receipt: &PurgeReceipt) -> bool {
+    receipt.signing_key_id == state.secrets.key_id()
+        && signing::constant_time_eq(
+            &signature(
+                state,
+                &PurgeReceipt {
+                    signature: String::new(),
+                    ..receipt.clone()
+                },
+            ),
+            &receipt.signature,
+        )
+}
+
+/// Keyed hash of `npm` as stored in [`PurgeReceipt::subject_hash`].
+pub fn subject_hash(state: &AppState, npm: &str) -> String {
+    hex::encode(
+        state
+            .secrets
+            .keyed_hash(format!("purge-subject:{}", npm.trim()).as_bytes()),
+    )
+}
+
+fn signature(state: &AppState, unsigned: &PurgeReceipt) -> String {
+    let body = serde_json::to_vec(unsigned).expect("receipt serializes");
+    hex::encode(
+        state
+            .secrets
+            .keyed_hash(&[b"purge-receipt:".as_slice(), &body].concat()),
+    )
+}
+
+/// Deletes stored events addressed only to the student or about them, and takes the
+/// student out of the audience of events also addressed to others.
+async fn scrub_events(
+    db: &impl ConnectionTrait,
+    classroom_ids: &[i32],
+    user_ids: &[i32],
+    npm: &str,
+) -> Result<u64, AppError> {
+    let events = classroom_event::Entity::find()
+        .filter(classroom_event::Column::ClassroomId.is_in(classroom_ids.to_vec()))
+        .all(db)
+        .await?;
+
+    let mut removed = 0;
+    for event in events {
+        let audience: Option<Vec<i32>> = event
+            .audience
+            .as_deref()
+            .and_then(|raw| serde_json::from_str(raw).ok());
+        let remaining: Option<Vec<i32>> = audience.as_ref().map(|ids| {
+            ids.iter()
+                .copied()
+                .filter(|id| !user_ids.contains(id))
+                .collect()
+        });
+
+        if mentions(&event.payload, user_ids, npm)
+            || remaining.as_ref().is_some_and(Vec::is_empty)
+        {
+            classroom_event::Entity::delete_by_id(event.id)
+                .exec(db)
+                .await?;
+            removed += 1;
+        } else if let (Some(audience), Some(remaining)) = (audience, remaining)
+            && remaining.len() != audience.len()
+        {
+            let mut active = event.into_active_model();
+            active.audience = Set(Some(
+                serde_json::to_string(&remaining).expect("ids serialize"),
+            ));
+            active.update(db).await?;
+        }
+    }
+    Ok(removed)
+}
+
+/// Whether an event payload names the student by user id or NPM.
+fn mentions(payload: &str, user_ids: &[i32], npm: &str) -> bool {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(payload) else {
+        return false;
+    };
+    value
+        .get("userId")
+        .and_then(serde_json::Value::as_i64)
+        .is_some_and(|id| user_ids.iter().any(|user_id| i64::from(*user_id) == id))
+        || value.get("npm").and_then(serde_json::Value::as_str) == Some(npm)
+}
+
+/// Vacuums the database and truncates the WAL; `false` when either failed.
+async fn compact(db: &impl ConnectionTrait) -> bool {
+    for sql in ["VACUUM", "PRAGMA wal_checkpoint(TRUNCATE)"] {
+        if let Err(err) = pragma(db, sql).await {
+            tracing::warn!("`{sql}` failed after a purge: {err}");
+            return false;
+        }
+    }
+    true
+}
+
+async fn pragma(db: &impl ConnectionTrait, sql: &str) -> Result<(), AppError> {
+    db.execute(Statement::from_string(DatabaseBackend::Sqlite, sql))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicU32, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{dto::RateLimitStatus, state::AppState};
+
+/// How often the controller compares Judge0 latency with the target.
+const CONTROL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Per-student submission budget whose rate follows Judge0 health: tightened while
+/// executor latency is above target, loosened again once it recovers.
+pub struct SubmissionLimiter {
+    min_per_minute: u32,
+    max_per_minute: u32,
+    current_per_minute: AtomicU32,
+    latency_target_ms: u64,
+    last_average_latency_ms: AtomicU64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl SubmissionLimiter {
+    /// `max_per_minute` of zero disables limiting.
+    pub fn new(min_per_minute: u32, max_per_minute: u32, latency_target_ms: u64) -> Self {
+        let min_per_minute = min_per_minute.clamp(1, max_per_minute.max(1));
+        Self {
+            min_per_minute,
+            max_per_minute,
+            current_per_minute: AtomicU32::new(max_per_minute),
+            latency_target_ms,
+            last_average_latency_ms: AtomicU64::new(0),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_per_minute > 0
+    }
+
+    /// Takes one submission from the key's budget, or returns the seconds until the
+    /// next one becomes available.
+    pub fn check(&self, key: &str) -> Result<(), u64> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        let capacity = f64::from(self.current_per_minute.load(Ordering::Relaxed));
+        let per_second = capacity / 60.0;
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock().expect("rate limit buckets poisoned");
+        let bucket = buckets.entry(key.to_owned()).or_insert(Bucket {
+            tokens: capacity,
+            refilled_at: now,
+        });
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * per_second).min(capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - bucket.tokens) / per_second).ceil().max(1.0) as u64)
+        }
+    }
+
+    pub fn status(&self) -> RateLimitStatus {
+        RateLimitStatus {
+            enabled: self.is_enabled(),
+            current_per_minute: self.current_per_minute.load(Ordering::Relaxed),
+            min_per_minute: self.min_per_minute,
+            max_per_minute: self.max_per_minute,
+            latency_target_ms: self.latency_target_ms,
+            last_average_latency_ms: self.last_average_latency_ms.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Cuts the rate by a quarter, down to the minimum.
+    fn tighten(&self) -> Option<u32> {
+        let current = self.current_per_minute.load(Ordering::Relaxed);
+        let next = (current * 3 / 4).max(self.min_per_minute);
+        self.set_rate(current, next)
+    }
+
+    /// Raises the rate by a tenth of the maximum, up to the maximum.
+    fn loosen(&self) -> Option<u32> {
+        let current = self.current_per_minute.load(Ordering::Relaxed);
+        let next = (current + (self.max_per_minute / 10).max(1)).min(self.max_per_minute);
+        self.set_rate(current, next)
+    }
+
+    fn set_rate(&self, current: u32, next: u32) -> Option<u32> {
+        if next == current {
+            return None;
+        }
+        self.current_per_minute.store(next, Ordering::Relaxed);
+        Some(next)
+    }
+
+    /// Drops buckets that have refilled completely; they are equivalent to new ones.
+    fn prune(&self) {
+        let capacity = f64::from(self.current_per_minute.load(Ordering::Relaxed));
+        let per_second = capacity / 60.0;
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().expect("rate limit buckets poisoned");
+        buckets.retain(|_, bucket| {
+            let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens + elapsed * per_second < capacity
+        });
+    }
+}
+
+/// Starts the feedback loop that adjusts the submission rate from the average
+/// Judge0 latency of each interval.
+pub fn spawn_controller(state: AppState) {
+    if !state.rate_limit.is_enabled() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let limiter = &state.rate_limit;
+        let mut previous = state.metrics.judge0.snapshot();
+        let mut ticker = tokio::time::interval(CONTROL_INTERVAL);
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            let snapshot = state.metrics.judge0.snapshot();
+            let completed = (snapshot.succeeded + snapshot.failed + snapshot.timed_out)
+                - (previous.succeeded + previous.failed + previous.timed_out);
+            let timed_out = snapshot.timed_out - previous.timed_out;
+            let average_ms = (snapshot.total_latency_ms - previous.total_latency_ms)
+                .checked_div(completed)
+                .unwrap_or(0);
+            previous = snapshot;
+
+            limiter
+                .last_average_latency_ms
+                .store(average_ms, Ordering::Relaxed);
+
+            if timed_out > 0 || average_ms > limiter.latency_target_ms {
+                if let Some(rate) = limiter.tighten() {
+                    tracing::warn!(
+                        "Judge0 saturated (avg {average_ms} ms, {timed_out} timeouts); submission limit lowered to {rate}/min"
+                    );
+                }
+            } else if average_ms <= limiter.latency_target_ms / 2
+                && let Some(rate) = limiter.loosen()
+            {
+                tracing::info!(
+                    "Judge0 healthy (avg {average_ms} ms); submission limit raised to {rate}/min"
+                );
+            }
+
+            limiter.prune();
+        }
+    });
+}
@@ -0,0 +1,205 @@
+use chrono::Utc;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    dto::{
+        Judge0SubmissionRequest, Judge0SubmissionResponse, PublishClassroomRequest,
+        ReadinessReport, SandboxFlags, SmokeTestResult,
+    },
+    entities::{classroom, task, test_case, user},
+    error::AppError,
+    services::{grading, judge0, output, test_runner},
+    state::AppState,
+};
+
+/// Checks that a classroom can go live: it has tasks, every task has test cases, the
+/// exam window is sane, and a smoke submission runs. Problems are reported as issues;
+/// only database failures are errors.
+pub async fn check(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+    smoke: &PublishClassroomRequest,
+) -> Result<ReadinessReport, AppError> {
+    let mut issues = Vec::new();
+
+    let tasks = task::Entity::find()
+        .filter(task::Column::ClassroomId.eq(classroom_model.id))
+        .order_by_asc(task::Column::Position)
+        .order_by_asc(task::Column::Id)
+        .all(&state.db)
+        .await?;
+    let cases = test_case::Entity::find()
+        .filter(test_case::Column::TaskId.is_in(tasks.iter().map(|task_model| task_model.id)))
+        .order_by_asc(test_case::Column::Position)
+        .order_by_asc(test_case::Column::Id)
+        .all(&state.db)
+        .await?;
+    let user_count = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(classroom_model.id))
+        .count(&state.db)
+        .await?;
+
+    if tasks.is_empty() {
+        issues.push("classroom has no tasks".to_string());
+    }
+    for task_model in &tasks {
+        if !cases.iter().any(|case| case.task_id == task_model.id) {
+            issues.push(format!("task {} has no test cases", task_label(task_model)));
+        }
+    }
+
+    if classroom_model.language_missing_since.is_some() {
+        issues.push(format!(
+            "no Judge0 language matches programming language {:?}",
+            classroom_model.programming_language
+        ));
+    }
+
+    if classroom_model.is_exam {
+        match (classroom_model.exam_start, classroom_model.exam_end) {
+            (Some(start), Some(end)) if end <= start => {
+                issues.push("exam end must be after exam start".to_string());
+            }
+            (Some(_), Some(end)) if end <= Utc::now() => {
+                issues.push("exam end is already in the past".to_string());
+            }
+            (Some(_), Some(_)) => {}
+            _ => issues.push("exam start and end must both be set".to_string()),
+        }
+    }
+
+    let test_case_count = cases.len();
+    let smoke_test = if issues.is_empty() {
+        Some(run_smoke_test(state, classroom_model, smoke, cases, &mut issues).await)
+    } else {
+        None
+    };
+
+    Ok(ReadinessReport {
+        classroom_id: classroom_model.id,
+        ready: issues.is_empty(),
+        issues,
+        task_count: tasks.len(),
+        test_case_count,
+        user_count,
+        smoke_test,
+    })
+}
+
+/// With a reference solution every case must pass; without one the starter code
+/// only has to be accepted by Judge0 on the first case.
+async fn run_smoke_test(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+    smoke: &PublishClassroomRequest,
+    cases: Vec<test_case::Model>,
+    issues: &mut Vec<String>,
+) -> SmokeTestResult {
+    let language_id = smoke
+        .language_id
+        .unwrap_or_else(|| grading::classroom_language_id(classroom_model));
+    let output_limit_kb = output::limit_kb(state, classroom_model);
+
+    if let Some(source_code) = &smoke.source_code {
+        let mut result = SmokeTestResult {
+            reference_solution: true,
+            language_id,
+            executed_cases: 0,
+            passed_cases: 0,
+            executor_error: None,
+        };
+        match test_runner::run_cases(
+            state,
+            source_code,
+            language_id,
+            cases,
+            output_limit_kb,
+            SandboxFlags::of(classroom_model),
+            None,
+        )
+        .await
+        {
+            Ok(outcomes) => {
+                result.executed_cases = outcomes.len();
+                for outcome in outcomes {
+                    if outcome.passed {
+                        result.passed_cases += 1;
+                    } else {
+                        issues.push(format!(
+                            "test case {} of task {} fails with the reference solution ({})",
+                            outcome.test_case.id,
+                            outcome.test_case.task_id,
+                            outcome
+                                .response
+                                .status
+                                .map(|status| status.description)
+                                .unwrap_or_else(|| "no status".into())
+                        ));
+                    }
+                }
+            }
+            Err(err) => {
+                issues.push(format!("smoke submission failed: {err}"));
+                result.executor_error = Some(err.to_string());
+            }
+        }
+        return result;
+    }
+
+    let stdin = cases.first().map(|case| case.stdin.clone());
+    let executor_error = run_starter_code(state, classroom_model, language_id, stdin)
+        .await
+        .err()
+        .map(|err| {
+            issues.push(format!("smoke submission failed: {err}"));
+            err.to_string()
+        });
+
+    SmokeTestResult {
+        reference_solution: false,
+        language_id,
+        executed_cases: usize::from(executor_error.is_none()),
+        passed_cases: 0,
+        executor_error,
+    }
+}
+
+/// Runs the classroom's starter code once to check that Judge0 accepts work for it.
+/// The caller is expected to hold an executor slot.
+pub async fn run_starter_code(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+    language_id: i32,
+    stdin: Option<String>,
+) -> Result<(), AppError> {
+    let sandbox = SandboxFlags::of(classroom_model);
+    let payload = Judge0SubmissionRequest {
+        source_code: classroom_model.presetup_code.clone(),
+        language_id,
+        stdin,
+        expected_output: None,
+        cpu_time_limit: None,
+        memory_limit: None,
+        max_file_size: Some(output::limit_kb(state, classroom_model)),
+        compiler_options: None,
+        command_line_arguments: None,
+        enable_network: sandbox.enable_network,
+        max_processes_and_or_threads: sandbox.max_processes_and_or_threads,
+        redirect_stderr_to_stdout: sandbox.redirect_stderr_to_stdout,
+        npm: None,
+        classroom_id: None,
+        task_id: None,
+        client_updated_at: None,
+        base_seq: None,
+    };
+    judge0::execute::<_, Judge0SubmissionResponse>(state, &payload).await?;
+    Ok(())
+}
+
+fn task_label(task_model: &task::Model) -> String {
+    if task_model.title.trim().is_empty() {
+        format!("#{}", task_model.position + 1)
+    } else {
+        format!("\"{}\"", task_model.title.trim())
+    }
+}
@@ -0,0 +1,77 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use axum::http::{HeaderMap, HeaderName};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::error::AppError;
+
+/// Client-chosen unique value of a signed-in request.
+pub const REQUEST_NONCE: HeaderName = HeaderName::from_static("x-request-nonce");
+/// Unix seconds at which the client sent the request.
+pub const REQUEST_TIMESTAMP: HeaderName = HeaderName::from_static("x-request-timestamp");
+
+/// How far a request timestamp may be from the server clock, either way. Nonces are
+/// remembered this long, so a replay is either stale or already seen.
+pub const WINDOW: Duration = Duration::minutes(5);
+
+const MAX_NONCE_LEN: usize = 128;
+
+/// Nonces seen per account within [`WINDOW`]. Kept in memory: a restart forgets them,
+/// which the idempotent finish covers.
+#[derive(Default)]
+pub struct NonceCache {
+    seen: Mutex<HashMap<(i32, String), DateTime<Utc>>>,
+}
+
+impl NonceCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accepts a request from `account_id` once: its timestamp must be within the window
+    /// and its nonce unused.
+    pub fn check(
+        &self,
+        account_id: i32,
+        headers: &HeaderMap,
+        now: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        let header = |name: &HeaderName| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let (Some(nonce), Some(timestamp)) = (header(&REQUEST_NONCE), header(&REQUEST_TIMESTAMP))
+        else {
+            return Err(AppError::BadRequest(
+                "requests with a token need X-Request-Nonce and X-Request-Timestamp".into(),
+            ));
+        };
+        if nonce.len() > MAX_NONCE_LEN {
+            return Err(AppError::BadRequest(format!(
+                "X-Request-Nonce is longer than {MAX_NONCE_LEN} characters"
+            )));
+        }
+        let sent_at = timestamp
+            .parse::<i64>()
+            .ok()
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+            .ok_or_else(|| {
+                AppError::BadRequest("X-Request-Timestamp must be Unix seconds".into())
+            })?;
+        if (now - sent_at).abs() > WINDOW {
+            return Err(AppError::ReplayedRequest(
+                "request timestamp is outside the accepted window".into(),
+            ));
+        }
+
+        let mut seen = self.seen.lock().expect("nonce cache poisoned");
+        seen.retain(|_, seen_at| now - *seen_at <= WINDOW);
+        if seen.insert((account_id, nonce.to_owned()), now).is_some() {
+            return Err(AppError::ReplayedRequest("nonce was already used".into()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,359 @@
+//! Warm standby by snapshot shipping. Every interval the primary copies its SQLite
+//! database with `VACUUM INTO` and posts the copy, signed with the shared secret, to
+//! the standby. The standby keeps the newest copy next to its own database together
+//! with a small manifest, and `asmctl promote` swaps that copy in once the primary
+//! stops answering `GET /api/health`. Writes made after the last shipped snapshot are
+//! lost on promotion; the status endpoint reports how many seconds that is.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration as StdDuration,
+};
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use reqwest::Client;
+use sea_orm::{ConnectionTrait, DbBackend, DbErr, Statement};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db,
+    dto::{ReplicationRole, ReplicationStatusResponse},
+    error::AppError,
+    services::signing,
+    state::AppState,
+};
+
+/// Largest snapshot the standby accepts.
+pub const MAX_SNAPSHOT_BYTES: usize = 1 << 30;
+
+/// How far in the future a snapshot's timestamp may lie before the standby refuses it
+/// as a clock problem; such a snapshot would otherwise block every later one.
+const MAX_CLOCK_SKEW_SECS: i64 = 300;
+
+/// Shortest interval between snapshots.
+const MIN_INTERVAL_SECS: u64 = 5;
+
+/// Upper bound for posting one snapshot to the standby.
+const SHIP_TIMEOUT: StdDuration = StdDuration::from_secs(300);
+
+/// Health checks `asmctl promote` makes before declaring the primary down.
+const HEALTH_CHECK_ATTEMPTS: u32 = 3;
+const HEALTH_CHECK_TIMEOUT: StdDuration = StdDuration::from_secs(5);
+const HEALTH_CHECK_PAUSE: StdDuration = StdDuration::from_secs(2);
+
+pub struct ReplicationSettings {
+    pub role: ReplicationRole,
+    pub standby_url: Option<String>,
+    pub primary_url: Option<String>,
+    pub secret: Option<String>,
+    pub interval_secs: u64,
+    pub snapshot_path: PathBuf,
+}
+
+/// Written next to the standby's snapshot as `<snapshot>.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotManifest {
+    pub taken_at: DateTime<Utc>,
+    pub received_at: DateTime<Utc>,
+    pub bytes: u64,
+}
+
+#[derive(Default)]
+struct Progress {
+    taken_at: Option<DateTime<Utc>>,
+    transferred_at: Option<DateTime<Utc>>,
+    bytes: Option<u64>,
+    last_error: Option<String>,
+}
+
+pub struct Replication {
+    role: ReplicationRole,
+    peer_url: Option<String>,
+    secret: Vec<u8>,
+    interval_secs: u64,
+    snapshot_path: PathBuf,
+    progress: Mutex<Progress>,
+    /// Serializes snapshot writes on the standby.
+    receiving: tokio::sync::Mutex<()>,
+}
+
+impl Replication {
+    /// Checks that the role has what it needs; a standby picks up the snapshot it held
+    /// before a restart.
+    pub fn new(settings: ReplicationSettings) -> Result<Self, String> {
+        let secret = settings.secret.unwrap_or_default().into_bytes();
+        let peer_url =
+            match settings.role {
+                ReplicationRole::Off => None,
+                ReplicationRole::Primary => Some(settings.standby_url.ok_or(
+                    "REPLICATION_STANDBY_URL wajib diatur untuk REPLICATION_ROLE=primary",
+                )?),
+                ReplicationRole::Standby => settings.primary_url,
+            };
+        if settings.role != ReplicationRole::Off && secret.is_empty() {
+            return Err("REPLICATION_SECRET wajib diatur jika replikasi aktif".into());
+        }
+
+        let mut progress = Progress::default();
+        if settings.role == ReplicationRole::Standby
+            && let Some(manifest) = read_manifest(&settings.snapshot_path)
+        {
+            progress.taken_at = Some(manifest.taken_at);
+            progress.transferred_at = Some(manifest.received_at);
+            progress.bytes = Some(manifest.bytes);
+        }
+
+        Ok(Self {
+            role: settings.role,
+            peer_url,
+            secret,
+            interval_secs: settings.interval_secs.max(MIN_INTERVAL_SECS),
+            snapshot_path: settings.snapshot_path,
+            progress: Mutex::new(progress),
+            receiving: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    pub fn role(&self) -> ReplicationRole {
+        self.role
+    }
+
+    pub fn status(&self, now: DateTime<Utc>) -> ReplicationStatusResponse {
+        let progress = self.progress.lock().expect("replication progress lock");
+        let lag_seconds = progress
+            .taken_at
+            .map(|taken_at| (now - taken_at).num_seconds().max(0));
+        let healthy = self.role != ReplicationRole::Off
+            && progress.last_error.is_none()
+            && lag_seconds.is_some_and(|lag| lag <= 3 * self.interval_secs as i64);
+        ReplicationStatusResponse {
+            role: self.role,
+            interval_secs: self.interval_secs,
+            peer_url: self.peer_url.clone(),
+            snapshot_taken_at: progress.taken_at,
+            last_transfer_at: progress.transferred_at,
+            snapshot_bytes: progress.bytes,
+            lag_seconds,
+            last_error: progress.last_error.clone(),
+            healthy,
+        }
+    }
+
+    fn record(&self, taken_at: DateTime<Utc>, bytes: u64) {
+        let mut progress = self.progress.lock().expect("replication progress lock");
+        progress.taken_at = Some(taken_at);
+        progress.transferred_at = Some(Utc::now());
+        progress.bytes = Some(bytes);
+        progress.last_error = None;
+    }
+
+    fn record_failure(&self, err: &AppError) {
+        self.progress
+            .lock()
+            .expect("replication progress lock")
+            .last_error = Some(err.to_string());
+    }
+}
+
+/// Ships a snapshot every interval when this server is the primary.
+pub fn spawn(state: AppState) {
+    if state.replication.role != ReplicationRole::Primary {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut ticker =
+            tokio::time::interval(StdDuration::from_secs(state.replication.interval_secs));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if let Err(err) = ship(&state).await {
+                tracing::warn!("replication snapshot failed: {err}");
+                state.replication.record_failure(&err);
+            }
+        }
+    });
+}
+
+async fn ship(state: &AppState) -> Result<(), AppError> {
+    let replication = &state.replication;
+    let standby_url = replication
+        .peer_url
+        .as_deref()
+        .ok_or_else(|| AppError::Internal("replication standby is not configured".into()))?;
+
+    // The signature carries whole seconds, so the snapshot is labelled with those.
+    let taken_at = Utc
+        .timestamp_opt(Utc::now().timestamp(), 0)
+        .single()
+        .unwrap_or_else(Utc::now);
+    let snapshot = std::env::temp_dir().join(format!(
+        "asm-lab-snapshot-{}-{}.db",
+        std::process::id(),
+        taken_at.timestamp()
+    ));
+    let path = snapshot
+        .to_str()
+        .ok_or_else(|| AppError::Internal("temporary snapshot path is not UTF-8".into()))?;
+    db::backup(&state.db, path).await?;
+    let body = tokio::fs::read(&snapshot).await;
+    let _ = tokio::fs::remove_file(&snapshot).await;
+    let body = body.map_err(|err| AppError::Internal(format!("reading snapshot: {err}")))?;
+    let bytes = body.len() as u64;
+
+    let signature = signing::sign(&replication.secret, taken_at.timestamp(), &body);
+    state
+        .http_client
+        .post(format!("{standby_url}/api/replication/snapshot"))
+        .header(signing::SIGNATURE_HEADER, signature)
+        .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+        .timeout(SHIP_TIMEOUT)
+        .body(body)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    replication.record(taken_at, bytes);
+    Ok(())
+}
+
+/// Stores a snapshot posted by the primary. Snapshots that are not newer than the one
+/// held are refused, so a replayed request cannot roll the standby back.
+pub async fn receive(
+    replication: &Replication,
+    signature: Option<&str>,
+    body: &[u8],
+) -> Result<SnapshotManifest, AppError> {
+    if replication.role != ReplicationRole::Standby {
+        return Err(AppError::Forbidden(
+            "this server is not a replication standby".into(),
+        ));
+    }
+    let taken_at = verify(&replication.secret, signature, body)?;
+    let now = Utc::now();
+    if taken_at > now + Duration::seconds(MAX_CLOCK_SKEW_SECS) {
+        return Err(AppError::BadRequest(
+            "snapshot timestamp lies in the future; check the primary's clock".into(),
+        ));
+    }
+
+    let _guard = replication.receiving.lock().await;
+    let held = replication
+        .progress
+        .lock()
+        .expect("replication progress lock")
+        .taken_at;
+    if held.is_some_and(|held| taken_at <= held) {
+        return Err(AppError::ReplayedRequest(
+            "a snapshot at least this recent is already held".into(),
+        ));
+    }
+
+    let manifest = SnapshotManifest {
+        taken_at,
+        received_at: now,
+        bytes: body.len() as u64,
+    };
+    write_snapshot(&replication.snapshot_path, body, &manifest)
+        .await
+        .map_err(|err| AppError::Internal(format!("storing replication snapshot: {err}")))?;
+    replication.record(taken_at, manifest.bytes);
+    Ok(manifest)
+}
+
+/// Checks the `X-Signature` of a snapshot and returns the instant it was taken.
+fn verify(secret: &[u8], signature: Option<&str>, body: &[u8]) -> Result<DateTime<Utc>, AppError> {
+    let unauthorized = || AppError::Unauthorized("invalid replication signature".into());
+    let signature = signature.ok_or_else(unauthorized)?;
+    let timestamp: i64 = signature
+        .split(',')
+        .find_map(|part| part.strip_prefix("t="))
+        .and_then(|raw| raw.parse().ok())
+        .ok_or_else(unauthorized)?;
+    if !signing::constant_time_eq(&signing::sign(secret, timestamp, body), signature) {
+        return Err(unauthorized());
+    }
+    Utc.timestamp_opt(timestamp, 0)
+        .single()
+        .ok_or_else(unauthorized)
+}
+
+/// Replaces the snapshot and its manifest; each file is written aside and renamed so
+/// a crash never leaves a half-written copy under the real name.
+async fn write_snapshot(
+    path: &Path,
+    body: &[u8],
+    manifest: &SnapshotManifest,
+) -> std::io::Result<()> {
+    let partial = with_suffix(path, ".part");
+    tokio::fs::write(&partial, body).await?;
+    tokio::fs::rename(&partial, path).await?;
+
+    let manifest_path = manifest_path(path);
+    let partial = with_suffix(&manifest_path, ".part");
+    let json = serde_json::to_vec_pretty(manifest).map_err(std::io::Error::other)?;
+    tokio::fs::write(&partial, json).await?;
+    tokio::fs::rename(&partial, manifest_path).await
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+pub fn manifest_path(snapshot: &Path) -> PathBuf {
+    with_suffix(snapshot, ".json")
+}
+
+/// The manifest of the snapshot at `snapshot`, if both exist.
+pub fn read_manifest(snapshot: &Path) -> Option<SnapshotManifest> {
+    if !snapshot.exists() {
+        return None;
+    }
+    let json = std::fs::read(manifest_path(snapshot)).ok()?;
+    serde_json::from_slice(&json).ok()
+}
+
+/// Whether the primary answers `GET /api/health` within a few attempts.
+pub async fn primary_reachable(client: &Client, primary_url: &str) -> bool {
+    for attempt in 1..=HEALTH_CHECK_ATTEMPTS {
+        let healthy = client
+            .get(format!("{primary_url}/api/health"))
+            .timeout(HEALTH_CHECK_TIMEOUT)
+            .send()
+            .await
+            .is_ok_and(|response| response.status().is_success());
+        if healthy {
+            return true;
+        }
+        if attempt < HEALTH_CHECK_ATTEMPTS {
+            tokio::time::sleep(HEALTH_CHECK_PAUSE).await;
+        }
+    }
+    false
+}
+
+/// Runs SQLite's integrity check on a snapshot file without modifying it.
+pub async fn verify_snapshot(snapshot: &Path) -> Result<(), DbErr> {
+    let url = format!("sqlite://{}?mode=ro", snapshot.display());
+    let conn = db::connect(&url).await?;
+    let result = conn
+        .query_one(Statement::from_string(
+            DbBackend::Sqlite,
+            "PRAGMA integrity_check",
+        ))
+        .await?
+        .map(|row| row.try_get_by_index::<String>(0))
+        .transpose()?;
+    conn.close().await?;
+    match result.as_deref() {
+        Some("ok") => Ok(()),
+        other => Err(DbErr::Custom(format!(
+            "snapshot gagal integrity_check: {}",
+            other.unwrap_or("tanpa hasil")
+        ))),
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+use crate::{entities::classroom_event, error::AppError, services::client_errors, state::AppState};
+
+/// How often old classroom events are pruned.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Starts the background job that deletes classroom events and reported errors older
+/// than `retention_days`. Zero keeps them forever.
+pub fn spawn(state: AppState, retention_days: u32) {
+    if retention_days == 0 {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(PRUNE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            match prune_events(&state, retention_days).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("pruned {deleted} old classroom event(s)"),
+                Err(err) => tracing::warn!("classroom event pruning failed: {err}"),
+            }
+            match client_errors::prune(&state.db, retention_days).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("pruned {deleted} old client error report(s)"),
+                Err(err) => tracing::warn!("client error pruning failed: {err}"),
+            }
+        }
+    });
+}
+
+async fn prune_events(state: &AppState, retention_days: u32) -> Result<u64, AppError> {
+    let cutoff = Utc::now() - chrono::Duration::days(i64::from(retention_days));
+    let result = classroom_event::Entity::delete_many()
+        .filter(classroom_event::Column::CreatedAt.lt(cutoff))
+        .exec(&state.db)
+        .await?;
+    Ok(result.rows_affected)
+}
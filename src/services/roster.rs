@@ -0,0 +1,312 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, ModelTrait,
+    QueryFilter, QueryOrder, TransactionTrait, sea_query::Expr,
+};
+
+use crate::{
+    dto::{
+        TransferConflict, TransferMode, TransferUsersRequest, TransferUsersResponse,
+        TransferredUser,
+    },
+    entities::{classroom, integrity_event, submission, task, task_progress, user},
+    error::AppError,
+    services::csv,
+};
+
+/// One student row of a roster file.
+pub struct RosterEntry {
+    pub npm: String,
+    pub name: String,
+    pub code: String,
+}
+
+pub struct RosterImport {
+    pub added: usize,
+    /// NPMs already enrolled in the classroom, left untouched.
+    pub skipped: Vec<String>,
+}
+
+/// Parses `npm,name,code` rows. A first row starting with `npm` is treated as a
+/// header; blank lines are ignored.
+pub fn parse(contents: &str) -> Result<Vec<RosterEntry>, AppError> {
+    let mut entries = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim_start_matches('\u{feff}');
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields = csv::parse_line(line);
+        if index == 0 && fields[0].eq_ignore_ascii_case("npm") {
+            continue;
+        }
+        match fields.as_slice() {
+            [npm, name, code, ..] if !npm.is_empty() && !name.is_empty() => {
+                entries.push(RosterEntry {
+                    npm: npm.clone(),
+                    name: name.clone(),
+                    code: code.clone(),
+                });
+            }
+            _ => {
+                return Err(AppError::BadRequest(format!(
+                    "line {}: expected npm,name,code",
+                    index + 1
+                )));
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Enrolls the entries in the classroom in one transaction, skipping NPMs that are
+/// already enrolled.
+pub async fn import(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+    entries: Vec<RosterEntry>,
+) -> Result<RosterImport, AppError> {
+    classroom::Entity::find_by_id(classroom_id)
+        .one(db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+
+    let txn = db.begin().await?;
+    let mut enrolled: HashSet<String> = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(classroom_id))
+        .all(&txn)
+        .await?
+        .into_iter()
+        .map(|user_model| user_model.npm)
+        .collect();
+
+    let now = Utc::now();
+    let mut added = 0;
+    let mut skipped = Vec::new();
+    for entry in entries {
+        if !enrolled.insert(entry.npm.clone()) {
+            skipped.push(entry.npm);
+            continue;
+        }
+        user::ActiveModel {
+            classroom_id: sea_orm::ActiveValue::Set(classroom_id),
+            name: sea_orm::ActiveValue::Set(entry.name),
+            npm: sea_orm::ActiveValue::Set(entry.npm),
+            code: sea_orm::ActiveValue::Set(entry.code.into()),
+            code_seq: sea_orm::ActiveValue::Set(0),
+            active: sea_orm::ActiveValue::Set(true),
+            created_at: sea_orm::ActiveValue::Set(now),
+            updated_at: sea_orm::ActiveValue::Set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        added += 1;
+    }
+    txn.commit().await?;
+
+    Ok(RosterImport { added, skipped })
+}
+
+/// Moves or copies roster entries of `source_id` into another classroom with their
+/// code, exam attempt, task progress and submissions, all in one transaction. Task
+/// progress follows tasks by position and is dropped where the target has no task
+/// at that position. Disputes stay with the submissions they were raised on, so a
+/// copy starts without any; device bindings are only kept by a move.
+pub async fn transfer(
+    db: &DatabaseConnection,
+    source_id: i32,
+    request: TransferUsersRequest,
+) -> Result<TransferUsersResponse, AppError> {
+    let target_id = request.target_classroom_id;
+    if target_id == source_id {
+        return Err(AppError::BadRequest(
+            "targetClassroomId must differ from the source classroom".into(),
+        ));
+    }
+    let mut user_ids = request.user_ids;
+    user_ids.sort_unstable();
+    user_ids.dedup();
+    if user_ids.is_empty() {
+        return Err(AppError::BadRequest("userIds must not be empty".into()));
+    }
+    for classroom_id in [source_id, target_id] {
+        classroom::Entity::find_by_id(classroom_id)
+            .one(db)
+            .await?
+            .ok_or(AppError::ClassroomNotFound)?;
+    }
+
+    let txn = db.begin().await?;
+    let users = user::Entity::find()
+        .filter(user::Column::Id.is_in(user_ids.clone()))
+        .filter(user::Column::ClassroomId.eq(source_id))
+        .order_by_asc(user::Column::Id)
+        .all(&txn)
+        .await?;
+    if users.len() != user_ids.len() {
+        return Err(AppError::UserNotFound);
+    }
+
+    let enrolled: HashMap<String, i32> = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(target_id))
+        .all(&txn)
+        .await?
+        .into_iter()
+        .map(|user_model| (user_model.npm, user_model.id))
+        .collect();
+    let task_positions = |classroom_id: i32| {
+        task::Entity::find()
+            .filter(task::Column::ClassroomId.eq(classroom_id))
+            .all(&txn)
+    };
+    let source_positions: HashMap<i32, i32> = task_positions(source_id)
+        .await?
+        .into_iter()
+        .map(|task_model| (task_model.id, task_model.position))
+        .collect();
+    let target_tasks: HashMap<i32, i32> = task_positions(target_id)
+        .await?
+        .into_iter()
+        .map(|task_model| (task_model.position, task_model.id))
+        .collect();
+    let target_task = |source_task_id: i32| {
+        source_positions
+            .get(&source_task_id)
+            .and_then(|position| target_tasks.get(position))
+            .copied()
+    };
+
+    let now = Utc::now();
+    let mut transferred = Vec::new();
+    let mut skipped = Vec::new();
+    for user_model in users {
+        let replaced = match enrolled.get(&user_model.npm) {
+            None => false,
+            Some(_) if request.on_conflict == TransferConflict::Fail => {
+                return Err(AppError::NpmConflict(format!(
+                    "npm {} is already enrolled in classroom {target_id}",
+                    user_model.npm
+                )));
+            }
+            Some(_) if request.on_conflict == TransferConflict::Skip => {
+                skipped.push(user_model.npm);
+                continue;
+            }
+            Some(existing_id) => {
+                user::Entity::delete_by_id(*existing_id).exec(&txn).await?;
+                true
+            }
+        };
+
+        let source_user_id = user_model.id;
+        let npm = user_model.npm.clone();
+        let progress = task_progress::Entity::find()
+            .filter(task_progress::Column::UserId.eq(source_user_id))
+            .all(&txn)
+            .await?;
+
+        let (target_user_id, submissions) = match request.mode {
+            TransferMode::Move => {
+                let mut user_am = user_model.into_active_model();
+                user_am.classroom_id = sea_orm::ActiveValue::Set(target_id);
+                user_am.seat_row = sea_orm::ActiveValue::Set(None);
+                user_am.seat_column = sea_orm::ActiveValue::Set(None);
+                user_am.updated_at = sea_orm::ActiveValue::Set(now);
+                user_am.update(&txn).await?;
+
+                let submissions = submission::Entity::update_many()
+                    .col_expr(submission::Column::ClassroomId, Expr::value(target_id))
+                    .filter(submission::Column::UserId.eq(source_user_id))
+                    .exec(&txn)
+                    .await?
+                    .rows_affected;
+                integrity_event::Entity::update_many()
+                    .col_expr(integrity_event::Column::ClassroomId, Expr::value(target_id))
+                    .filter(integrity_event::Column::UserId.eq(source_user_id))
+                    .exec(&txn)
+                    .await?;
+
+                for progress_model in progress {
+                    match target_task(progress_model.task_id) {
+                        Some(task_id) => {
+                            let mut progress_am = progress_model.into_active_model();
+                            progress_am.task_id = sea_orm::ActiveValue::Set(task_id);
+                            progress_am.update(&txn).await?;
+                        }
+                        None => {
+                            progress_model.delete(&txn).await?;
+                        }
+                    }
+                }
+                (source_user_id, submissions)
+            }
+            TransferMode::Copy => {
+                let copy = user::ActiveModel {
+                    id: sea_orm::ActiveValue::NotSet,
+                    classroom_id: sea_orm::ActiveValue::Set(target_id),
+                    device_fingerprint: sea_orm::ActiveValue::Set(None),
+                    device_bound_at: sea_orm::ActiveValue::Set(None),
+                    seat_row: sea_orm::ActiveValue::Set(None),
+                    seat_column: sea_orm::ActiveValue::Set(None),
+                    created_at: sea_orm::ActiveValue::Set(now),
+                    updated_at: sea_orm::ActiveValue::Set(now),
+                    ..user_model.into_active_model().reset_all()
+                }
+                .insert(&txn)
+                .await?;
+
+                let originals = submission::Entity::find()
+                    .filter(submission::Column::UserId.eq(source_user_id))
+                    .order_by_asc(submission::Column::Id)
+                    .all(&txn)
+                    .await?;
+                let submissions = originals.len() as u64;
+                for original in originals {
+                    submission::ActiveModel {
+                        id: sea_orm::ActiveValue::NotSet,
+                        classroom_id: sea_orm::ActiveValue::Set(target_id),
+                        user_id: sea_orm::ActiveValue::Set(copy.id),
+                        task_id: sea_orm::ActiveValue::Set(original.task_id.and_then(target_task)),
+                        ..original.into_active_model().reset_all()
+                    }
+                    .insert(&txn)
+                    .await?;
+                }
+
+                for progress_model in progress {
+                    if let Some(task_id) = target_task(progress_model.task_id) {
+                        task_progress::ActiveModel {
+                            id: sea_orm::ActiveValue::NotSet,
+                            task_id: sea_orm::ActiveValue::Set(task_id),
+                            user_id: sea_orm::ActiveValue::Set(copy.id),
+                            ..progress_model.into_active_model().reset_all()
+                        }
+                        .insert(&txn)
+                        .await?;
+                    }
+                }
+                (copy.id, submissions)
+            }
+        };
+
+        transferred.push(TransferredUser {
+            source_user_id,
+            target_user_id,
+            npm,
+            submissions,
+            replaced,
+        });
+    }
+    txn.commit().await?;
+
+    Ok(TransferUsersResponse {
+        mode: request.mode,
+        source_classroom_id: source_id,
+        target_classroom_id: target_id,
+        transferred,
+        skipped,
+    })
+}
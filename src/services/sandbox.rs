@@ -0,0 +1,93 @@
+//! Judge0 sandbox flags. Each classroom may set a bounded subset of them, applied to
+//! every run of its students, including test cases and grading. Clients may only send
+//! them outside exams and never to enable networking; the classroom's own values win.
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    dto::{Judge0SubmissionRequest, SandboxFlags},
+    entities::classroom,
+    error::AppError,
+    services::exam_clock::ExamClock,
+    state::AppState,
+};
+
+/// Most processes and threads a run may be given; Judge0's own
+/// `MAX_MAX_PROCESSES_AND_OR_THREADS` defaults to the same.
+pub const MAX_PROCESSES: u32 = 120;
+
+/// Checks flags an admin sets on a classroom.
+pub fn validate(flags: &SandboxFlags, is_exam: bool) -> Result<(), AppError> {
+    check_bounds(flags)?;
+    if is_exam && flags.enable_network == Some(true) {
+        return Err(AppError::BadRequest(
+            "enableNetwork cannot be turned on in an exam classroom".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Flags sent by the client with a run.
+pub fn requested(payload: &Judge0SubmissionRequest) -> SandboxFlags {
+    SandboxFlags {
+        enable_network: payload.enable_network,
+        max_processes_and_or_threads: payload.max_processes_and_or_threads,
+        redirect_stderr_to_stdout: payload.redirect_stderr_to_stdout,
+    }
+}
+
+/// Rejects client flags outside the bounds, or that would enable networking.
+pub fn check_client(payload: &Judge0SubmissionRequest) -> Result<(), AppError> {
+    let flags = requested(payload);
+    check_bounds(&flags)?;
+    if flags.enable_network == Some(true) {
+        return Err(AppError::Forbidden(
+            "enable_network can only be turned on in the classroom's settings".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// [`check_client`], and while the classroom's exam is running no flag may be sent.
+pub async fn check_client_in(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+    payload: &Judge0SubmissionRequest,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    check_client(payload)?;
+    if !classroom_model.is_exam || requested(payload).is_empty() {
+        return Ok(());
+    }
+    let clock = ExamClock::load(&state.db, classroom_model).await?;
+    if state.time_policy.is_running(&clock, now) {
+        return Err(AppError::Forbidden(
+            "sandbox flags cannot be overridden during an exam".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Sets the classroom's flags on `payload`, over any the client sent.
+pub fn apply(flags: SandboxFlags, payload: &mut Judge0SubmissionRequest) {
+    if let Some(enable_network) = flags.enable_network {
+        payload.enable_network = Some(enable_network);
+    }
+    if let Some(max_processes) = flags.max_processes_and_or_threads {
+        payload.max_processes_and_or_threads = Some(max_processes);
+    }
+    if let Some(redirect) = flags.redirect_stderr_to_stdout {
+        payload.redirect_stderr_to_stdout = Some(redirect);
+    }
+}
+
+fn check_bounds(flags: &SandboxFlags) -> Result<(), AppError> {
+    if let Some(max_processes) = flags.max_processes_and_or_threads
+        && !(1..=MAX_PROCESSES).contains(&max_processes)
+    {
+        return Err(AppError::BadRequest(format!(
+            "maxProcessesAndOrThreads must be between 1 and {MAX_PROCESSES}"
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,162 @@
+//! Clashes between scheduled exam windows: an NPM on the rosters of two exams that
+//! overlap, and stretches where more students sit exams at once than the executors
+//! are sized for (`EXAM_STUDENT_CAPACITY`). Only the scheduled windows count; pauses
+//! and extensions are decided during the exam and cannot be planned around.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect};
+
+use crate::{
+    dto::{ClassroomStatus, ScheduleConflict, ScheduleConflictKind},
+    entities::{classroom, user},
+    error::AppError,
+};
+
+struct ScheduledExam {
+    id: i32,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    npms: BTreeSet<String>,
+}
+
+/// Conflicts among exams that have not ended by `now`, ordered by start.
+pub async fn conflicts(
+    db: &DatabaseConnection,
+    capacity: Option<usize>,
+    now: DateTime<Utc>,
+) -> Result<Vec<ScheduleConflict>, AppError> {
+    let exams = scheduled_exams(db, now).await?;
+    let mut conflicts = npm_overlaps(&exams);
+    if let Some(capacity) = capacity {
+        conflicts.extend(over_capacity(&exams, capacity));
+    }
+    conflicts.sort_by_key(|conflict| (conflict.start, conflict.end));
+    Ok(conflicts)
+}
+
+/// Conflicts `classroom_id` is part of.
+pub async fn conflicts_of(
+    db: &DatabaseConnection,
+    capacity: Option<usize>,
+    classroom_id: i32,
+    now: DateTime<Utc>,
+) -> Result<Vec<ScheduleConflict>, AppError> {
+    let mut conflicts = conflicts(db, capacity, now).await?;
+    conflicts.retain(|conflict| conflict.classroom_ids.contains(&classroom_id));
+    Ok(conflicts)
+}
+
+async fn scheduled_exams(
+    db: &DatabaseConnection,
+    now: DateTime<Utc>,
+) -> Result<Vec<ScheduledExam>, AppError> {
+    let classrooms = classroom::Entity::find()
+        .filter(classroom::Column::IsExam.eq(true))
+        .filter(classroom::Column::Status.ne(ClassroomStatus::Archived.as_str()))
+        .filter(classroom::Column::ExamStart.is_not_null())
+        .filter(classroom::Column::ExamEnd.gt(now))
+        .all(db)
+        .await?;
+    if classrooms.is_empty() {
+        return Ok(Vec::new());
+    }
+    let ids: Vec<i32> = classrooms.iter().map(|model| model.id).collect();
+
+    let mut rosters: BTreeMap<i32, BTreeSet<String>> = BTreeMap::new();
+    let users: Vec<(i32, String)> = user::Entity::find()
+        .select_only()
+        .column(user::Column::ClassroomId)
+        .column(user::Column::Npm)
+        .filter(user::Column::ClassroomId.is_in(ids))
+        .into_tuple()
+        .all(db)
+        .await?;
+    for (classroom_id, npm) in users {
+        rosters.entry(classroom_id).or_default().insert(npm);
+    }
+
+    Ok(classrooms
+        .into_iter()
+        .filter_map(|model| {
+            let (start, end) = (model.exam_start?, model.exam_end?);
+            (start < end).then(|| ScheduledExam {
+                id: model.id,
+                start,
+                end,
+                npms: rosters.remove(&model.id).unwrap_or_default(),
+            })
+        })
+        .collect())
+}
+
+fn npm_overlaps(exams: &[ScheduledExam]) -> Vec<ScheduleConflict> {
+    let mut conflicts = Vec::new();
+    for (index, first) in exams.iter().enumerate() {
+        for second in &exams[index + 1..] {
+            if first.start >= second.end || second.start >= first.end {
+                continue;
+            }
+            let npms: Vec<String> = first.npms.intersection(&second.npms).cloned().collect();
+            if npms.is_empty() {
+                continue;
+            }
+            conflicts.push(ScheduleConflict {
+                kind: ScheduleConflictKind::NpmOverlap,
+                classroom_ids: vec![first.id.min(second.id), first.id.max(second.id)],
+                start: first.start.max(second.start),
+                end: first.end.min(second.end),
+                npms,
+                students: None,
+            });
+        }
+    }
+    conflicts
+}
+
+/// Walks the window boundaries and reports each stretch over `capacity`, merging
+/// adjacent stretches with the same exams.
+fn over_capacity(exams: &[ScheduledExam], capacity: usize) -> Vec<ScheduleConflict> {
+    let boundaries: BTreeSet<DateTime<Utc>> = exams
+        .iter()
+        .flat_map(|exam| [exam.start, exam.end])
+        .collect();
+    let boundaries: Vec<DateTime<Utc>> = boundaries.into_iter().collect();
+
+    let mut conflicts: Vec<ScheduleConflict> = Vec::new();
+    for window in boundaries.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        let running: Vec<&ScheduledExam> = exams
+            .iter()
+            .filter(|exam| exam.start < end && exam.end > start)
+            .collect();
+        let students = running
+            .iter()
+            .flat_map(|exam| exam.npms.iter())
+            .collect::<BTreeSet<_>>()
+            .len();
+        if students <= capacity {
+            continue;
+        }
+        let mut classroom_ids: Vec<i32> = running.iter().map(|exam| exam.id).collect();
+        classroom_ids.sort_unstable();
+
+        if let Some(previous) = conflicts.last_mut()
+            && previous.end == start
+            && previous.classroom_ids == classroom_ids
+        {
+            previous.end = end;
+            continue;
+        }
+        conflicts.push(ScheduleConflict {
+            kind: ScheduleConflictKind::OverCapacity,
+            classroom_ids,
+            start,
+            end,
+            npms: Vec::new(),
+            students: Some(students),
+        });
+    }
+    conflicts
+}
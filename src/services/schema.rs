@@ -0,0 +1,241 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::DateTime;
+use migration::{Migrator, MigratorTrait, seaql_migrations};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseBackend, DatabaseConnection, EntityTrait, IdenStatic,
+    Iterable, PrimaryKeyToColumn, Statement,
+};
+
+use crate::{
+    db::data_migration,
+    docs,
+    dto::{ColumnSchema, MigrationStatus, SchemaMigrationStatus, SchemaResponse, TableSchema},
+    entities::{
+        account, archive_outbox, audit_log, banner, classroom, classroom_assistant,
+        classroom_event, classroom_settings_version, client_error, code_fingerprint, daily_stat,
+        dispute, exam_pause, executor_config, export_job, fingerprint_settings,
+        grading_dead_letter, integrity_event, language_usage, login_event, message,
+        message_recipient, migration_meta, run_config, submission, submission_case_result, task,
+        task_progress, test_case, user, verdict_message, webauthn_credential, webhook_endpoint,
+    },
+    error::AppError,
+};
+
+/// Compares every entity with its table and lists the schema and data migrations. The
+/// database side is read with SQLite pragmas; other backends only report the entities.
+pub async fn inspect(db: &DatabaseConnection) -> Result<SchemaResponse, AppError> {
+    let tables = vec![
+        table::<account::Entity>(db).await?,
+        table::<archive_outbox::Entity>(db).await?,
+        table::<audit_log::Entity>(db).await?,
+        table::<banner::Entity>(db).await?,
+        table::<classroom::Entity>(db).await?,
+        table::<classroom_assistant::Entity>(db).await?,
+        table::<classroom_event::Entity>(db).await?,
+        table::<classroom_settings_version::Entity>(db).await?,
+        table::<client_error::Entity>(db).await?,
+        table::<code_fingerprint::Entity>(db).await?,
+        table::<daily_stat::Entity>(db).await?,
+        table::<dispute::Entity>(db).await?,
+        table::<exam_pause::Entity>(db).await?,
+        table::<executor_config::Entity>(db).await?,
+        table::<export_job::Entity>(db).await?,
+        table::<fingerprint_settings::Entity>(db).await?,
+        table::<grading_dead_letter::Entity>(db).await?,
+        table::<integrity_event::Entity>(db).await?,
+        table::<language_usage::Entity>(db).await?,
+        table::<login_event::Entity>(db).await?,
+        table::<message::Entity>(db).await?,
+        table::<message_recipient::Entity>(db).await?,
+        table::<migration_meta::Entity>(db).await?,
+        table::<run_config::Entity>(db).await?,
+        table::<submission::Entity>(db).await?,
+        table::<submission_case_result::Entity>(db).await?,
+        table::<task::Entity>(db).await?,
+        table::<task_progress::Entity>(db).await?,
+        table::<test_case::Entity>(db).await?,
+        table::<user::Entity>(db).await?,
+        table::<verdict_message::Entity>(db).await?,
+        table::<webauthn_credential::Entity>(db).await?,
+        table::<webhook_endpoint::Entity>(db).await?,
+    ];
+
+    let mut applied_schema: HashMap<String, i64> = seaql_migrations::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|model| (model.version, model.applied_at))
+        .collect();
+    let schema_migrations: Vec<SchemaMigrationStatus> = Migrator::migrations()
+        .iter()
+        .map(|migration| SchemaMigrationStatus {
+            name: migration.name().to_owned(),
+            applied_at: applied_schema
+                .remove(migration.name())
+                .and_then(|secs| DateTime::from_timestamp(secs, 0)),
+        })
+        .collect();
+    let mut unknown_schema_migrations: Vec<String> = applied_schema.into_keys().collect();
+    unknown_schema_migrations.sort_unstable();
+
+    let mut applied: HashMap<i32, _> = migration_meta::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|meta| (meta.version, meta.applied_at))
+        .collect();
+    let migrations: Vec<MigrationStatus> = data_migration::DATA_MIGRATIONS
+        .iter()
+        .map(|&(version, name)| MigrationStatus {
+            version,
+            name: name.to_owned(),
+            applied_at: applied.remove(&version),
+        })
+        .collect();
+    let mut unknown_migrations: Vec<i32> = applied.into_keys().collect();
+    unknown_migrations.sort_unstable();
+
+    let up_to_date = tables
+        .iter()
+        .all(|table| table.exists && table.missing_columns.is_empty())
+        && schema_migrations
+            .iter()
+            .all(|migration| migration.applied_at.is_some())
+        && migrations
+            .iter()
+            .all(|migration| migration.applied_at.is_some());
+
+    Ok(SchemaResponse {
+        server_version: docs::version_tag(),
+        backend: format!("{:?}", db.get_database_backend()).to_lowercase(),
+        up_to_date,
+        tables,
+        schema_migrations,
+        unknown_schema_migrations,
+        migrations,
+        unknown_migrations,
+    })
+}
+
+/// Names of the tables in the database, migration bookkeeping included. Other
+/// backends list the tables this build has entities for.
+pub async fn table_names(db: &DatabaseConnection) -> Result<Vec<String>, AppError> {
+    if db.get_database_backend() != DatabaseBackend::Sqlite {
+        return Ok(inspect(db)
+            .await?
+            .tables
+            .into_iter()
+            .map(|table| table.table)
+            .collect());
+    }
+    let rows = db
+        .query_all(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        ))
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| row.try_get::<String>("", "name"))
+        .collect::<Result<_, _>>()?)
+}
+
+/// A column as declared in the database.
+struct DatabaseColumn {
+    column_type: String,
+    nullable: bool,
+}
+
+async fn table<E>(db: &DatabaseConnection) -> Result<TableSchema, AppError>
+where
+    E: EntityTrait + Default,
+{
+    let name = E::default().table_name().to_owned();
+    let sqlite = db.get_database_backend() == DatabaseBackend::Sqlite;
+    let (actual, indexes) = if sqlite {
+        (
+            database_columns(db, &name).await?,
+            database_indexes(db, &name).await?,
+        )
+    } else {
+        (Vec::new(), Vec::new())
+    };
+    let mut by_name: HashMap<&str, &DatabaseColumn> = actual
+        .iter()
+        .map(|(name, column)| (name.as_str(), column))
+        .collect();
+
+    let primary_key: HashSet<String> = E::PrimaryKey::iter()
+        .map(|key| key.into_column().as_str().to_owned())
+        .collect();
+    let mut missing_columns = Vec::new();
+    let columns = E::Column::iter()
+        .map(|column| {
+            let def = column.def();
+            let name = column.as_str().to_owned();
+            let database = by_name.remove(name.as_str());
+            if sqlite && database.is_none() {
+                missing_columns.push(name.clone());
+            }
+            ColumnSchema {
+                column_type: format!("{:?}", def.get_column_type()),
+                nullable: def.is_null(),
+                primary_key: primary_key.contains(&name),
+                database_type: database.map(|column| column.column_type.clone()),
+                database_nullable: database.map(|column| column.nullable),
+                name,
+            }
+        })
+        .collect();
+    let mut extra_columns: Vec<String> = by_name.into_keys().map(str::to_owned).collect();
+    extra_columns.sort_unstable();
+
+    Ok(TableSchema {
+        table: name,
+        exists: !sqlite || !actual.is_empty(),
+        columns,
+        missing_columns,
+        extra_columns,
+        indexes,
+    })
+}
+
+async fn database_columns(
+    db: &DatabaseConnection,
+    table: &str,
+) -> Result<Vec<(String, DatabaseColumn)>, AppError> {
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "SELECT name, type, \"notnull\" FROM pragma_table_info(?) ORDER BY cid",
+            [table.into()],
+        ))
+        .await?;
+    rows.into_iter()
+        .map(|row| {
+            Ok((
+                row.try_get::<String>("", "name")?,
+                DatabaseColumn {
+                    column_type: row.try_get::<String>("", "type")?,
+                    nullable: row.try_get::<i32>("", "notnull")? == 0,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Named indexes of the table; SQLite's automatic ones for unique constraints are left out.
+async fn database_indexes(db: &DatabaseConnection, table: &str) -> Result<Vec<String>, AppError> {
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "SELECT name FROM pragma_index_list(?) WHERE name NOT LIKE 'sqlite_%' ORDER BY name",
+            [table.into()],
+        ))
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| row.try_get::<String>("", "name"))
+        .collect::<Result<_, _>>()?)
+}
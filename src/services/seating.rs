@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+use crate::entities::user;
+
+/// Largest seat map accepted, in rows and in columns.
+pub const MAX_GRID_SIZE: usize = 100;
+
+/// Spreadsheet-style seat label: the row as letters (`A`–`Z`, then `AA`…), followed
+/// by the column number.
+pub fn label(row: i32, column: i32) -> String {
+    let mut letters = Vec::new();
+    let mut rest = row.max(1);
+    while rest > 0 {
+        rest -= 1;
+        letters.push(b'A' + (rest % 26) as u8);
+        rest /= 26;
+    }
+    letters.reverse();
+    format!("{}{column}", String::from_utf8_lossy(&letters))
+}
+
+/// Seated students of one classroom, looked up by position.
+pub struct SeatIndex<'a> {
+    by_seat: HashMap<(i32, i32), &'a user::Model>,
+}
+
+impl<'a> SeatIndex<'a> {
+    pub fn new(users: impl IntoIterator<Item = &'a user::Model>) -> Self {
+        let by_seat = users
+            .into_iter()
+            .filter_map(|model| Some(((model.seat_row?, model.seat_column?), model)))
+            .collect();
+        Self { by_seat }
+    }
+
+    /// NPMs of the students in the up to eight seats around `model`'s seat, front
+    /// row first; empty when `model` has no seat.
+    pub fn neighbors(&self, model: &user::Model) -> Vec<String> {
+        let (Some(row), Some(column)) = (model.seat_row, model.seat_column) else {
+            return Vec::new();
+        };
+        let mut neighbors = Vec::new();
+        for d_row in -1..=1 {
+            for d_column in -1..=1 {
+                if (d_row, d_column) == (0, 0) {
+                    continue;
+                }
+                if let Some(neighbor) = self.by_seat.get(&(row + d_row, column + d_column)) {
+                    neighbors.push(neighbor.npm.clone());
+                }
+            }
+        }
+        neighbors
+    }
+}
@@ -0,0 +1,85 @@
+use aes_gcm::{
+    Aes256Gcm, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng},
+};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+
+/// Prefix of values written by [`SecretBox::seal`], so the format can change later.
+const SEALED_PREFIX: &str = "v1:";
+const NONCE_LEN: usize = 12;
+
+/// AES-256-GCM for secrets the server must read back, such as webhook signing keys.
+pub struct SecretBox {
+    cipher: Aes256Gcm,
+    key_id: String,
+    /// Separate key derived from the same material for [`SecretBox::keyed_hash`].
+    hash_key: [u8; 32],
+}
+
+impl SecretBox {
+    /// Uses a 64-character hex key as is; any other value is hashed into a key.
+    pub fn new(key_material: &str) -> Self {
+        let key: [u8; 32] = match hex::decode(key_material.trim()) {
+            Ok(bytes) if bytes.len() == 32 => bytes.try_into().expect("length checked"),
+            _ => Sha256::digest(key_material.as_bytes()).into(),
+        };
+
+        Self {
+            cipher: Aes256Gcm::new(&key.into()),
+            key_id: hex::encode(&Sha256::digest(key)[..4]),
+            hash_key: Sha256::new()
+                .chain_update(b"asm-lab-keyed-hash:")
+                .chain_update(key)
+                .finalize()
+                .into(),
+        }
+    }
+
+    /// Short fingerprint of the key, safe to store next to what it sealed.
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// HMAC-SHA256 of `data`: stable for as long as the key is, but cannot be
+    /// reversed by hashing guesses without the key.
+    pub fn keyed_hash(&self, data: &[u8]) -> [u8; 32] {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.hash_key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(data);
+        mac.finalize().into_bytes().into()
+    }
+
+    pub fn seal(&self, plaintext: &str) -> String {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .expect("AES-GCM encryption does not fail for in-memory input");
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        format!("{SEALED_PREFIX}{}", hex::encode(sealed))
+    }
+
+    pub fn open(&self, sealed: &str) -> Result<String, AppError> {
+        let undecryptable =
+            || AppError::Internal("secret cannot be decrypted with the configured key".into());
+
+        let bytes = sealed
+            .strip_prefix(SEALED_PREFIX)
+            .and_then(|encoded| hex::decode(encoded).ok())
+            .filter(|bytes| bytes.len() > NONCE_LEN)
+            .ok_or_else(undecryptable)?;
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let nonce: [u8; NONCE_LEN] = nonce.try_into().expect("split at nonce length");
+
+        let plaintext = self
+            .cipher
+            .decrypt(&Nonce::from(nonce), ciphertext)
+            .map_err(|_| undecryptable())?;
+        String::from_utf8(plaintext).map_err(|_| undecryptable())
+    }
+}
@@ -0,0 +1,92 @@
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder,
+};
+
+use crate::{
+    dto::{ClassroomSettings, SettingsChange},
+    entities::{classroom, classroom_settings_version},
+    error::AppError,
+};
+
+/// Stores a new settings version for the classroom when `before` and `after` differ
+/// in any setting, returning it. Call it in the same transaction as the update.
+pub async fn record(
+    db: &impl ConnectionTrait,
+    before: &classroom::Model,
+    after: &classroom::Model,
+    change: SettingsChange,
+    changed_by: Option<i32>,
+) -> Result<Option<classroom_settings_version::Model>, AppError> {
+    let before = ClassroomSettings::from_model(before);
+    let after_settings = ClassroomSettings::from_model(after);
+    if before == after_settings {
+        return Ok(None);
+    }
+
+    let version = latest_version(db, after.id).await? + 1;
+    let model = classroom_settings_version::ActiveModel {
+        classroom_id: sea_orm::ActiveValue::Set(after.id),
+        version: sea_orm::ActiveValue::Set(version),
+        change: sea_orm::ActiveValue::Set(change.as_str().to_owned()),
+        before: sea_orm::ActiveValue::Set(snapshot(&before)),
+        after: sea_orm::ActiveValue::Set(snapshot(&after_settings)),
+        changed_by: sea_orm::ActiveValue::Set(changed_by),
+        created_at: sea_orm::ActiveValue::Set(Utc::now()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(Some(model))
+}
+
+/// Settings as of `version`: the state after that change, or for version 0 the
+/// state before the first recorded change.
+pub async fn settings_at(
+    db: &impl ConnectionTrait,
+    classroom_id: i32,
+    version: i32,
+) -> Result<Option<ClassroomSettings>, AppError> {
+    let query = classroom_settings_version::Entity::find()
+        .filter(classroom_settings_version::Column::ClassroomId.eq(classroom_id));
+    let model = if version == 0 {
+        query
+            .order_by_asc(classroom_settings_version::Column::Version)
+            .one(db)
+            .await?
+    } else {
+        query
+            .filter(classroom_settings_version::Column::Version.eq(version))
+            .one(db)
+            .await?
+    };
+    let Some(model) = model else {
+        return Ok(None);
+    };
+    let raw = if version == 0 {
+        &model.before
+    } else {
+        &model.after
+    };
+
+    serde_json::from_str(raw).map(Some).map_err(|err| {
+        AppError::Internal(format!(
+            "settings version {} of classroom {classroom_id} is unreadable: {err}",
+            model.version
+        ))
+    })
+}
+
+async fn latest_version(db: &impl ConnectionTrait, classroom_id: i32) -> Result<i32, AppError> {
+    Ok(classroom_settings_version::Entity::find()
+        .filter(classroom_settings_version::Column::ClassroomId.eq(classroom_id))
+        .order_by_desc(classroom_settings_version::Column::Version)
+        .one(db)
+        .await?
+        .map_or(0, |model| model.version))
+}
+
+fn snapshot(settings: &ClassroomSettings) -> String {
+    serde_json::to_string(settings).expect("classroom settings serialize to JSON")
+}
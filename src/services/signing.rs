@@ -0,0 +1,30 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Header carrying the signature of an outgoing request body.
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+/// Compares two signatures in constant time so they cannot be guessed byte by byte.
+pub fn constant_time_eq(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Signs `body` as sent at `timestamp` (Unix seconds). The result is the
+/// `X-Signature` value `t=<timestamp>,v1=<hex HMAC-SHA256 of "<timestamp>.<body>">`;
+/// receivers recompute the HMAC with their secret and reject stale timestamps.
+pub fn sign(secret: &[u8], timestamp: i64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+
+    format!(
+        "t={timestamp},v1={}",
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
@@ -0,0 +1,88 @@
+//! Timing of every database statement through sea-orm's metric callback. Statements
+//! slower than `SQL_SLOW_QUERY_MS` are logged with their duration, the SQL with its
+//! placeholders, the kinds of the bound parameters (never their values) and the route
+//! that issued them, and all of them are counted in `GET /api/admin/metrics`.
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use sea_orm::{DatabaseConnection, Statement, metric::Info};
+
+use crate::services::metrics::Metrics;
+
+/// Longest SQL text quoted in a slow-statement log line.
+const MAX_LOGGED_SQL_CHARS: usize = 500;
+
+/// Route reported for statements issued outside a request, e.g. by background tasks.
+const BACKGROUND_ROUTE: &str = "background";
+
+tokio::task_local! {
+    static ROUTE: String;
+}
+
+/// Runs a request handler with `route` as the origin of its statements.
+pub async fn with_route<F: Future>(route: String, future: F) -> F::Output {
+    ROUTE.scope(route, future).await
+}
+
+/// Times every statement on `db`; `slow_threshold` of `None` only counts them.
+pub fn install(
+    db: &mut DatabaseConnection,
+    metrics: Arc<Metrics>,
+    slow_threshold: Option<Duration>,
+) {
+    metrics.database.set_slow_threshold(slow_threshold);
+    db.set_metric_callback(move |info: &Info<'_>| {
+        let slow = slow_threshold.is_some_and(|threshold| info.elapsed >= threshold);
+        if !slow {
+            metrics.database.record(info.elapsed, info.failed, None);
+            return;
+        }
+        let route = ROUTE
+            .try_with(String::clone)
+            .unwrap_or_else(|_| BACKGROUND_ROUTE.to_owned());
+        metrics
+            .database
+            .record(info.elapsed, info.failed, Some(&route));
+        tracing::warn!(
+            elapsed_ms = info.elapsed.as_millis() as u64,
+            route = %route,
+            failed = info.failed,
+            params = %parameter_summary(info.statement),
+            sql = %truncated_sql(&info.statement.sql),
+            "slow SQL statement"
+        );
+    });
+}
+
+/// Kinds of the bound parameters, e.g. `Int, String, String=null`; values are left out
+/// because they may hold student code or credentials.
+fn parameter_summary(statement: &Statement) -> String {
+    let Some(values) = &statement.values else {
+        return "none".to_owned();
+    };
+    if values.0.is_empty() {
+        return "none".to_owned();
+    }
+    values
+        .0
+        .iter()
+        .map(|value| {
+            let debug = format!("{value:?}");
+            let kind = debug.split('(').next().unwrap_or("?");
+            if debug.ends_with("(None)") {
+                format!("{kind}=null")
+            } else {
+                kind.to_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn truncated_sql(sql: &str) -> String {
+    let sql = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+    match sql.char_indices().nth(MAX_LOGGED_SQL_CHARS) {
+        Some((cut, _)) => format!("{}…", &sql[..cut]),
+        None => sql,
+    }
+}
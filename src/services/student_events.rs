@@ -0,0 +1,81 @@
+//! Student activity streamed to proctors on `GET /api/events`: first joining an exam,
+//! running code, and handing in. Students never see these on their own stream.
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    dto::{ExamFinishedEvent, UserJoinedEvent, UserSubmittedEvent},
+    entities::{submission, user},
+    services::event_bus::{Audience, EventKind},
+    state::AppState,
+};
+
+pub async fn joined(state: &AppState, user_model: &user::Model, joined_at: DateTime<Utc>) {
+    publish(
+        state,
+        user_model.classroom_id,
+        EventKind::UserJoined(UserJoinedEvent {
+            classroom_id: user_model.classroom_id,
+            user_id: user_model.id,
+            npm: user_model.npm.clone(),
+            name: user_model.name.clone(),
+            joined_at,
+        }),
+    )
+    .await;
+}
+
+/// `status` is Judge0's description of the run, before the classroom's wording.
+pub async fn submitted(
+    state: &AppState,
+    user_model: &user::Model,
+    task_id: Option<i32>,
+    language_id: i32,
+    status: Option<String>,
+) {
+    publish(
+        state,
+        user_model.classroom_id,
+        EventKind::UserSubmitted(UserSubmittedEvent {
+            classroom_id: user_model.classroom_id,
+            user_id: user_model.id,
+            npm: user_model.npm.clone(),
+            name: user_model.name.clone(),
+            task_id,
+            language_id,
+            status,
+            submitted_at: Utc::now(),
+        }),
+    )
+    .await;
+}
+
+pub async fn finished(
+    state: &AppState,
+    user_model: &user::Model,
+    submission_model: &submission::Model,
+) {
+    publish(
+        state,
+        submission_model.classroom_id,
+        EventKind::ExamFinished(ExamFinishedEvent {
+            classroom_id: submission_model.classroom_id,
+            user_id: user_model.id,
+            npm: user_model.npm.clone(),
+            name: user_model.name.clone(),
+            submission_id: submission_model.id,
+            task_id: submission_model.task_id,
+            auto_submitted: submission_model.auto_submitted,
+            late: submission_model.late,
+            finished_at: submission_model.created_at,
+        }),
+    )
+    .await;
+}
+
+async fn publish(state: &AppState, classroom_id: i32, kind: EventKind) {
+    state
+        .events
+        .publish(&state.db, classroom_id, Audience::Staff, kind)
+        .await;
+}
@@ -0,0 +1,88 @@
+//! Minimum interval between graded submissions of one student (practice runs against
+//! the test cases and hand-ins), set per classroom to discourage guessing hidden test
+//! cases by resubmitting. Unlike the per-minute rate limit it only counts graded
+//! submissions and differs between classrooms.
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, EntityTrait, QueryFilter, QuerySelect, sea_query::Expr,
+};
+
+use crate::{
+    entities::{classroom, user},
+    error::AppError,
+};
+
+/// Longest cooldown a classroom can set: one day.
+const MAX_COOLDOWN_SECS: i32 = 86_400;
+
+/// Validates a `submissionCooldownSecs` setting; `0` disables the cooldown.
+pub fn validate_setting(secs: i32) -> Result<Option<i32>, AppError> {
+    if !(0..=MAX_COOLDOWN_SECS).contains(&secs) {
+        return Err(AppError::BadRequest(format!(
+            "submissionCooldownSecs must be between 0 and {MAX_COOLDOWN_SECS}"
+        )));
+    }
+    Ok((secs > 0).then_some(secs))
+}
+
+fn cooldown(classroom_model: &classroom::Model) -> Option<Duration> {
+    classroom_model
+        .submission_cooldown_secs
+        .filter(|secs| *secs > 0)
+        .map(|secs| Duration::seconds(secs.into()))
+}
+
+/// When the student's next graded submission is accepted; `None` when it is now.
+pub fn available_at(
+    classroom_model: &classroom::Model,
+    last_graded_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let available_at = last_graded_at? + cooldown(classroom_model)?;
+    (available_at > now).then_some(available_at)
+}
+
+/// Whole seconds until `available_at`, rounded up so a client waiting that long is
+/// never early.
+pub fn remaining_secs(available_at: DateTime<Utc>, now: DateTime<Utc>) -> u64 {
+    let millis = (available_at - now).num_milliseconds().max(0) as u64;
+    millis.div_ceil(1000).max(1)
+}
+
+/// Counts a graded submission of `user_id` now, or refuses it with the remaining
+/// cooldown. The time is claimed by a conditional update, so of two requests sent at
+/// once only one passes.
+pub async fn claim(
+    db: &impl ConnectionTrait,
+    classroom_model: &classroom::Model,
+    user_id: i32,
+) -> Result<(), AppError> {
+    let now = Utc::now();
+    let mut update = user::Entity::update_many()
+        .col_expr(user::Column::LastGradedAt, Expr::value(now))
+        .filter(user::Column::Id.eq(user_id));
+    if let Some(cooldown) = cooldown(classroom_model) {
+        update = update.filter(
+            Condition::any()
+                .add(user::Column::LastGradedAt.is_null())
+                .add(user::Column::LastGradedAt.lte(now - cooldown)),
+        );
+    }
+    if update.exec(db).await?.rows_affected > 0 {
+        return Ok(());
+    }
+
+    let last_graded_at = user::Entity::find_by_id(user_id)
+        .select_only()
+        .column(user::Column::LastGradedAt)
+        .into_tuple::<Option<DateTime<Utc>>>()
+        .one(db)
+        .await?
+        .ok_or(AppError::UserNotFound)?;
+    let available_at =
+        available_at(classroom_model, last_graded_at, now).unwrap_or(now + Duration::seconds(1));
+    Err(AppError::SubmissionCooldown {
+        retry_after_secs: remaining_secs(available_at, now),
+    })
+}
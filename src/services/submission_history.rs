@@ -0,0 +1,136 @@
+//! Every execution of a student's code is kept: runs through the judge proxy are
+//! stored here as non-final submissions next to the exam hand-ins, with the output the
+//! student was shown.
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, sea_query::SimpleExpr,
+};
+use serde_json::Value;
+
+use crate::{
+    dto::{Judge0SubmissionRequest, SubmissionHistoryEntry, SubmissionListParams},
+    entities::submission,
+    error::AppError,
+};
+
+const DEFAULT_LIMIT: u64 = 100;
+const MAX_LIMIT: u64 = 1000;
+
+/// Judge0 status of a run submitted without waiting, until its result is fetched.
+pub const IN_QUEUE: (i32, &str) = (1, "In Queue");
+
+/// Stores a run of `payload` with its Judge0 `result`. Failures are logged and never
+/// fail the run.
+pub async fn record_run(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+    user_id: i32,
+    task_id: Option<i32>,
+    payload: &Judge0SubmissionRequest,
+    result: &Value,
+) {
+    let mut model = new_run(classroom_id, user_id, task_id, payload);
+    apply_result(&mut model, result);
+    if let Err(err) = model.insert(db).await {
+        tracing::warn!("failed to store run of user {user_id}: {err}");
+    }
+}
+
+/// Stores a run queued on the Judge0 instance at `instance` under `token`, for the
+/// polling worker to complete.
+pub async fn record_pending(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+    user_id: i32,
+    task_id: Option<i32>,
+    payload: &Judge0SubmissionRequest,
+    token: &str,
+    instance: &str,
+) -> Result<submission::Model, AppError> {
+    let mut model = new_run(classroom_id, user_id, task_id, payload);
+    model.status = sea_orm::ActiveValue::Set(Some(IN_QUEUE.1.to_owned()));
+    model.judge0_status_id = sea_orm::ActiveValue::Set(Some(IN_QUEUE.0));
+    model.judge0_token = sea_orm::ActiveValue::Set(Some(token.to_owned()));
+    model.judge0_instance = sea_orm::ActiveValue::Set(Some(instance.to_owned()));
+    Ok(model.insert(db).await?)
+}
+
+/// Copies the output and status of a Judge0 `result` onto a stored run.
+pub fn apply_result(model: &mut submission::ActiveModel, result: &Value) {
+    let text = |field: &str| result.get(field).and_then(Value::as_str).map(str::to_owned);
+    let int = |field: &str| {
+        result
+            .get(field)
+            .and_then(Value::as_i64)
+            .and_then(|value| i32::try_from(value).ok())
+    };
+    model.stdout = sea_orm::ActiveValue::Set(text("stdout"));
+    model.stderr = sea_orm::ActiveValue::Set(text("stderr"));
+    model.compile_output = sea_orm::ActiveValue::Set(text("compile_output"));
+    model.status = sea_orm::ActiveValue::Set(
+        result
+            .pointer("/status/description")
+            .and_then(Value::as_str)
+            .map(str::to_owned),
+    );
+    model.judge0_status_id = sea_orm::ActiveValue::Set(
+        result
+            .pointer("/status/id")
+            .and_then(Value::as_i64)
+            .and_then(|value| i32::try_from(value).ok()),
+    );
+    model.time = sea_orm::ActiveValue::Set(text("time"));
+    model.memory = sea_orm::ActiveValue::Set(result.get("memory").and_then(Value::as_i64));
+    model.exit_code = sea_orm::ActiveValue::Set(int("exit_code"));
+    model.exit_signal = sea_orm::ActiveValue::Set(int("exit_signal"));
+}
+
+fn new_run(
+    classroom_id: i32,
+    user_id: i32,
+    task_id: Option<i32>,
+    payload: &Judge0SubmissionRequest,
+) -> submission::ActiveModel {
+    submission::ActiveModel {
+        classroom_id: sea_orm::ActiveValue::Set(classroom_id),
+        user_id: sea_orm::ActiveValue::Set(user_id),
+        source_code: sea_orm::ActiveValue::Set(payload.source_code.clone().into()),
+        language_id: sea_orm::ActiveValue::Set(payload.language_id),
+        is_final: sea_orm::ActiveValue::Set(false),
+        auto_submitted: sea_orm::ActiveValue::Set(false),
+        task_id: sea_orm::ActiveValue::Set(task_id),
+        late: sea_orm::ActiveValue::Set(false),
+        grading_attempts: sea_orm::ActiveValue::Set(0),
+        created_at: sea_orm::ActiveValue::Set(Utc::now()),
+        ..Default::default()
+    }
+}
+
+/// Submissions matching `scope`, newest first.
+pub async fn list(
+    db: &DatabaseConnection,
+    scope: SimpleExpr,
+    params: &SubmissionListParams,
+) -> Result<Vec<SubmissionHistoryEntry>, AppError> {
+    let mut query = submission::Entity::find()
+        .filter(scope)
+        .order_by_desc(submission::Column::Id)
+        .limit(params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT));
+    if let Some(task_id) = params.task_id {
+        query = query.filter(submission::Column::TaskId.eq(task_id));
+    }
+    if let Some(is_final) = params.is_final {
+        query = query.filter(submission::Column::IsFinal.eq(is_final));
+    }
+    if let Some(before) = params.before {
+        query = query.filter(submission::Column::Id.lt(before));
+    }
+    Ok(query
+        .all(db)
+        .await?
+        .into_iter()
+        .map(SubmissionHistoryEntry::from)
+        .collect())
+}
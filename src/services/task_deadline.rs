@@ -0,0 +1,110 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+use crate::{
+    dto::{ClassroomStatus, TaskDueEvent},
+    entities::{classroom, task},
+    error::AppError,
+    services::event_bus::{Audience, EventKind},
+    state::AppState,
+};
+
+/// Reminder thresholds used when `TASK_DUE_REMINDERS` is not set.
+pub const DEFAULT_REMINDER_THRESHOLDS: &str = "24h,1h";
+
+/// How often the watcher looks for due dates crossing a reminder threshold.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Whether a hand-in made at `now` is late. With a task, that task's due date decides;
+/// without one, the hand-in is late once every task of the classroom is past due.
+pub async fn is_late(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+    task_id: Option<i32>,
+    now: DateTime<Utc>,
+) -> Result<bool, AppError> {
+    let tasks = task::Entity::find()
+        .filter(task::Column::ClassroomId.eq(classroom_id))
+        .all(db)
+        .await?;
+
+    match task_id {
+        Some(task_id) => {
+            let task_model = tasks
+                .iter()
+                .find(|task_model| task_model.id == task_id)
+                .ok_or(AppError::TaskNotFound)?;
+            Ok(task_model.due_at.is_some_and(|due_at| now > due_at))
+        }
+        None => Ok(!tasks.is_empty()
+            && tasks
+                .iter()
+                .all(|task_model| task_model.due_at.is_some_and(|due_at| now > due_at))),
+    }
+}
+
+/// Starts the watcher that publishes a `task-due` event to the classroom whenever a
+/// task's due date crosses one of `thresholds`, and once more when it passes. Due
+/// dates crossed while the server was down are not announced afterwards.
+pub fn spawn(state: AppState, thresholds: Arc<[chrono::Duration]>) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(SWEEP_INTERVAL);
+        let mut last_sweep = Utc::now();
+        loop {
+            ticker.tick().await;
+            let now = Utc::now();
+            if let Err(err) = sweep(&state, &thresholds, last_sweep, now).await {
+                tracing::warn!("task due date sweep failed: {err}");
+                continue;
+            }
+            last_sweep = now;
+        }
+    });
+}
+
+async fn sweep(
+    state: &AppState,
+    thresholds: &[chrono::Duration],
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<(), AppError> {
+    for threshold in thresholds
+        .iter()
+        .copied()
+        .chain(std::iter::once(chrono::Duration::zero()))
+    {
+        let crossed = task::Entity::find()
+            .find_also_related(classroom::Entity)
+            .filter(task::Column::DueAt.gt(since + threshold))
+            .filter(task::Column::DueAt.lte(now + threshold))
+            .filter(classroom::Column::Status.eq(ClassroomStatus::Published.as_str()))
+            .all(&state.db)
+            .await?;
+
+        for (task_model, _) in crossed {
+            let Some(due_at) = task_model.due_at else {
+                continue;
+            };
+            state
+                .events
+                .publish(
+                    &state.db,
+                    task_model.classroom_id,
+                    Audience::Everyone,
+                    EventKind::TaskDue(TaskDueEvent {
+                        classroom_id: task_model.classroom_id,
+                        task_id: task_model.id,
+                        title: task_model.title,
+                        due_at,
+                        threshold_seconds: threshold.num_seconds(),
+                        remaining_seconds: (due_at - now).num_seconds().max(0),
+                        server_time: now,
+                    }),
+                )
+                .await;
+        }
+    }
+    Ok(())
+}
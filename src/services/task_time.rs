@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveValue::Set,
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    sea_query::{Expr, OnConflict},
+};
+
+use crate::{
+    dto::{TaskActivity, TaskTimeSummary},
+    entities::{classroom, task, task_progress, user},
+    error::AppError,
+    services::{csv, display_time::TimeFormatter},
+};
+
+/// Records a task being opened or edited by a student. The first call sets the
+/// opening time; edits move the last edit time forward and bump the edit count.
+pub async fn record(
+    db: &DatabaseConnection,
+    task_id: i32,
+    user_id: i32,
+    activity: TaskActivity,
+) -> Result<(), AppError> {
+    let now = Utc::now();
+    let edited = activity == TaskActivity::Edited;
+
+    let model = task_progress::ActiveModel {
+        task_id: Set(task_id),
+        user_id: Set(user_id),
+        first_opened_at: Set(now),
+        last_edited_at: Set(edited.then_some(now)),
+        edit_count: Set(i32::from(edited)),
+        updated_at: Set(now),
+        ..Default::default()
+    };
+
+    let mut on_conflict =
+        OnConflict::columns([task_progress::Column::TaskId, task_progress::Column::UserId]);
+    if edited {
+        on_conflict
+            .update_columns([
+                task_progress::Column::LastEditedAt,
+                task_progress::Column::UpdatedAt,
+            ])
+            .value(
+                task_progress::Column::EditCount,
+                Expr::col((task_progress::Entity, task_progress::Column::EditCount)).add(1),
+            );
+    } else {
+        on_conflict.do_nothing();
+    }
+
+    task_progress::Entity::insert(model)
+        .on_conflict(on_conflict)
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Per-task time-spent aggregates for a classroom, in task order.
+pub async fn summarize(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+) -> Result<Vec<TaskTimeSummary>, AppError> {
+    let tasks = task::Entity::find()
+        .filter(task::Column::ClassroomId.eq(classroom_id))
+        .order_by_asc(task::Column::Position)
+        .all(db)
+        .await?;
+
+    let mut progress: HashMap<i32, Vec<task_progress::Model>> = HashMap::new();
+    for row in load_progress(db, classroom_id).await? {
+        progress.entry(row.task_id).or_default().push(row);
+    }
+
+    let mut summaries: Vec<TaskTimeSummary> = tasks
+        .into_iter()
+        .map(|task_model| {
+            let rows = progress.remove(&task_model.id).unwrap_or_default();
+            let mut spent: Vec<i64> = rows
+                .iter()
+                .filter(|row| row.last_edited_at.is_some())
+                .map(task_progress::Model::seconds_spent)
+                .collect();
+            spent.sort_unstable();
+
+            let total_seconds: i64 = spent.iter().sum();
+            TaskTimeSummary {
+                task_id: task_model.id,
+                position: task_model.position,
+                title: task_model.title,
+                students_opened: rows.len(),
+                students_edited: spent.len(),
+                total_edits: rows.iter().map(|row| i64::from(row.edit_count)).sum(),
+                total_seconds,
+                average_seconds: if spent.is_empty() {
+                    0
+                } else {
+                    total_seconds / spent.len() as i64
+                },
+                median_seconds: median(&spent),
+                max_seconds: spent.last().copied().unwrap_or(0),
+                share_of_total: 0.0,
+            }
+        })
+        .collect();
+
+    let classroom_total: i64 = summaries.iter().map(|summary| summary.total_seconds).sum();
+    if classroom_total > 0 {
+        for summary in &mut summaries {
+            let share = summary.total_seconds as f64 * 100.0 / classroom_total as f64;
+            summary.share_of_total = (share * 10.0).round() / 10.0;
+        }
+    }
+
+    Ok(summaries)
+}
+
+/// Progress rows of every task in the classroom.
+pub async fn load_progress(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+) -> Result<Vec<task_progress::Model>, AppError> {
+    Ok(task_progress::Entity::find()
+        .inner_join(task::Entity)
+        .filter(task::Column::ClassroomId.eq(classroom_id))
+        .order_by_asc(task_progress::Column::Id)
+        .all(db)
+        .await?)
+}
+
+/// CSV with one row per student and task: first opening, last edit, seconds spent
+/// and edit count.
+pub async fn export(
+    db: &DatabaseConnection,
+    classroom_id: i32,
+    times: TimeFormatter,
+) -> Result<String, AppError> {
+    classroom::Entity::find_by_id(classroom_id)
+        .one(db)
+        .await?
+        .ok_or(AppError::ClassroomNotFound)?;
+
+    let tasks: HashMap<i32, task::Model> = task::Entity::find()
+        .filter(task::Column::ClassroomId.eq(classroom_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|task_model| (task_model.id, task_model))
+        .collect();
+    let users: HashMap<i32, user::Model> = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(classroom_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|user_model| (user_model.id, user_model))
+        .collect();
+
+    let mut rows: Vec<_> = load_progress(db, classroom_id)
+        .await?
+        .into_iter()
+        .filter_map(|row| {
+            let task_model = tasks.get(&row.task_id)?;
+            let user_model = users.get(&row.user_id)?;
+            Some((task_model, user_model, row))
+        })
+        .collect();
+    rows.sort_by(|(task_a, user_a, _), (task_b, user_b, _)| {
+        (&user_a.npm, task_a.position).cmp(&(&user_b.npm, task_b.position))
+    });
+
+    let mut header: Vec<String> = ["npm", "name", "task_id", "task_position", "task_title"]
+        .map(String::from)
+        .to_vec();
+    header.extend(times.columns("first_opened_at"));
+    header.extend(times.columns("last_edited_at"));
+    header.extend(["seconds_spent".to_string(), "edit_count".to_string()]);
+    let mut out = header.join(",");
+    out.push('\n');
+    for (task_model, user_model, row) in rows {
+        let mut fields = vec![
+            csv::field(&user_model.npm),
+            csv::field(&user_model.name),
+            task_model.id.to_string(),
+            task_model.position.to_string(),
+            csv::field(&task_model.title),
+        ];
+        fields.extend(times.cells(Some(row.first_opened_at)));
+        fields.extend(times.cells(row.last_edited_at));
+        fields.extend([row.seconds_spent().to_string(), row.edit_count.to_string()]);
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+
+    Ok(out)
+}
+
+fn median(sorted: &[i64]) -> i64 {
+    match sorted.len() {
+        0 => 0,
+        len if len % 2 == 1 => sorted[len / 2],
+        len => (sorted[len / 2 - 1] + sorted[len / 2]) / 2,
+    }
+}
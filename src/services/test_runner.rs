@@ -0,0 +1,209 @@
+use crate::{
+    dto::{GradingStatus, Judge0SubmissionRequest, Judge0SubmissionResponse, SandboxFlags, TestMode},
+    entities::{submission, test_case},
+    error::AppError,
+    services::{grading_progress, judge0, output},
+    state::AppState,
+};
+
+/// Judge0 status id for "Accepted" (output matched `expected_output`).
+const JUDGE0_ACCEPTED: i32 = 3;
+
+pub struct CaseOutcome {
+    pub test_case: test_case::Model,
+    pub response: Judge0SubmissionResponse,
+    pub passed: bool,
+}
+
+/// Picks the cases a run executes. Sampled mode draws `sample_size` cases without
+/// replacement, weighted by points, from a generator seeded with `seed`, so the
+/// same seed always yields the same subset. `cases` must be in a stable order.
+pub fn select_cases(
+    cases: Vec<test_case::Model>,
+    mode: TestMode,
+    sample_size: usize,
+    seed: u64,
+) -> Vec<test_case::Model> {
+    if mode == TestMode::Full || cases.len() <= sample_size {
+        return cases;
+    }
+
+    // Efraimidis-Spirakis: key = u^(1/w), keep the largest keys.
+    let mut rng = SplitMix64(seed);
+    let mut keyed: Vec<(f64, test_case::Model)> = cases
+        .into_iter()
+        .map(|case| {
+            let weight = f64::from(case.points.max(1));
+            (rng.next_f64().powf(1.0 / weight), case)
+        })
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed.truncate(sample_size);
+
+    let mut sampled: Vec<test_case::Model> = keyed.into_iter().map(|(_, case)| case).collect();
+    sampled.sort_by_key(|case| (case.position, case.id));
+    sampled
+}
+
+/// Stable per-student, per-task seed (FNV-1a), independent of the Rust version.
+pub fn practice_seed(npm: &str, task_id: i32) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in npm.bytes().chain(task_id.to_le_bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// Executes `source_code` once per case, one Judge0 call at a time, with output capped
+/// at `output_limit_kb` and the classroom's `sandbox` flags. `graded` is the final
+/// submission being graded, if any; the case being run is reported to its student.
+pub async fn run_cases(
+    state: &AppState,
+    source_code: &str,
+    language_id: i32,
+    cases: Vec<test_case::Model>,
+    output_limit_kb: u32,
+    sandbox: SandboxFlags,
+    graded: Option<&submission::Model>,
+) -> Result<Vec<CaseOutcome>, AppError> {
+    let total = cases.len() as i32;
+    let mut outcomes = Vec::with_capacity(cases.len());
+
+    for (index, test_case) in cases.into_iter().enumerate() {
+        if let Some(submission_model) = graded {
+            grading_progress::set(
+                state,
+                submission_model,
+                GradingStatus::Running,
+                Some((index as i32 + 1, total)),
+            )
+            .await;
+        }
+        let payload = Judge0SubmissionRequest {
+            source_code: source_code.to_owned(),
+            language_id,
+            stdin: Some(test_case.stdin.clone()),
+            expected_output: Some(test_case.expected_output.clone()),
+            cpu_time_limit: None,
+            memory_limit: None,
+            max_file_size: Some(output_limit_kb),
+            compiler_options: None,
+            command_line_arguments: None,
+            enable_network: sandbox.enable_network,
+            max_processes_and_or_threads: sandbox.max_processes_and_or_threads,
+            redirect_stderr_to_stdout: sandbox.redirect_stderr_to_stdout,
+            npm: None,
+            classroom_id: None,
+            task_id: None,
+            client_updated_at: None,
+            base_seq: None,
+        };
+
+        let mut response: Judge0SubmissionResponse = judge0::execute(state, &payload).await?;
+        output::limit_response(&mut response, output_limit_kb);
+        let passed = response
+            .status
+            .as_ref()
+            .is_some_and(|status| status.id == JUDGE0_ACCEPTED);
+
+        outcomes.push(CaseOutcome {
+            test_case,
+            response,
+            passed,
+        });
+    }
+
+    Ok(outcomes)
+}
+
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in (0, 1]; zero is excluded so `powf` keys stay meaningful.
+    fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn cases(points: &[i32]) -> Vec<test_case::Model> {
+        let now = Utc::now();
+        points
+            .iter()
+            .enumerate()
+            .map(|(index, &points)| test_case::Model {
+                id: index as i32 + 1,
+                task_id: 1,
+                position: index as i32,
+                stdin: String::new(),
+                expected_output: String::new(),
+                hidden: false,
+                points,
+                created_at: now,
+                updated_at: now,
+            })
+            .collect()
+    }
+
+    fn ids(cases: &[test_case::Model]) -> Vec<i32> {
+        cases.iter().map(|case| case.id).collect()
+    }
+
+    #[test]
+    fn full_mode_runs_every_case() {
+        let selected = select_cases(cases(&[1; 8]), TestMode::Full, 3, 42);
+        assert_eq!(ids(&selected), (1..=8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn sampled_mode_runs_every_case_when_the_sample_covers_them() {
+        let selected = select_cases(cases(&[1; 3]), TestMode::Sampled, 3, 42);
+        assert_eq!(ids(&selected), [1, 2, 3]);
+    }
+
+    #[test]
+    fn the_same_seed_samples_the_same_cases_in_position_order() {
+        let first = select_cases(cases(&[1; 20]), TestMode::Sampled, 5, 7);
+        let second = select_cases(cases(&[1; 20]), TestMode::Sampled, 5, 7);
+        assert_eq!(first.len(), 5);
+        assert_eq!(ids(&first), ids(&second));
+        assert!(
+            first
+                .windows(2)
+                .all(|pair| pair[0].position < pair[1].position)
+        );
+
+        let subsets: std::collections::HashSet<Vec<i32>> = (0..10)
+            .map(|seed| ids(&select_cases(cases(&[1; 20]), TestMode::Sampled, 5, seed)))
+            .collect();
+        assert!(subsets.len() > 1, "different seeds should vary the sample");
+    }
+
+    #[test]
+    fn sampling_favours_cases_worth_more_points() {
+        let picked_heavy = (0..200)
+            .filter(|&seed| {
+                let selected = select_cases(cases(&[1, 1, 100, 1, 1]), TestMode::Sampled, 1, seed);
+                ids(&selected) == [3]
+            })
+            .count();
+        assert!(
+            picked_heavy > 150,
+            "heavy case picked {picked_heavy}/200 times"
+        );
+    }
+}
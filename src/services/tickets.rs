@@ -0,0 +1,249 @@
+//! Printed exam tickets: a slip per student with their NPM, seat, exam window and a
+//! one-time join code, for students who cannot rely on email to recover their NPM.
+//! The code is derived from a random nonce on the roster entry with the server secret,
+//! so printing again gives the same code until it is used; signing in with it clears
+//! the nonce, and the next print carries a new code.
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+
+use crate::{
+    dto::{ClassroomStatus, Seat},
+    entities::{classroom, user},
+    error::AppError,
+    services::{exam_clock::ExamClock, pdf},
+    state::AppState,
+};
+
+/// Letters and digits that cannot be mistaken for each other on paper.
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const CODE_LEN: usize = 8;
+const CUT_LINE: &str = "- - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -";
+
+pub struct Ticket {
+    pub name: String,
+    pub npm: String,
+    pub seat: Option<Seat>,
+    pub join_code: String,
+}
+
+/// Tickets of the classroom's students, or of `user_id` only, ordered by seat and
+/// then name. Students without a join code get one.
+pub async fn issue(
+    state: &AppState,
+    classroom_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<Ticket>, AppError> {
+    let mut query = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(classroom_id))
+        .order_by_asc(user::Column::Name);
+    if let Some(user_id) = user_id {
+        query = query.filter(user::Column::Id.eq(user_id));
+    }
+    let mut users = query.all(&state.db).await?;
+    if user_id.is_some() && users.is_empty() {
+        return Err(AppError::UserNotFound);
+    }
+    users.sort_by_key(|model| (model.seat_row.is_none(), model.seat_row, model.seat_column));
+
+    let mut tickets = Vec::with_capacity(users.len());
+    for model in users {
+        let nonce = match model.ticket_nonce.clone() {
+            Some(nonce) => nonce,
+            None => hex::encode(rand::random::<[u8; 16]>()),
+        };
+        let mut code = join_code(state, model.id, &nonce);
+        let code_hash = code_hash(state, &code);
+        if model.ticket_nonce.as_deref() != Some(nonce.as_str())
+            || model.ticket_code_hash.as_deref() != Some(code_hash.as_str())
+        {
+            // Only written while no code has been issued or used meanwhile; of two
+            // prints at once, the second prints the first one's code.
+            let written = user::Entity::update_many()
+                .set(user::ActiveModel {
+                    ticket_nonce: sea_orm::ActiveValue::Set(Some(nonce.clone())),
+                    ticket_code_hash: sea_orm::ActiveValue::Set(Some(code_hash)),
+                    ..Default::default()
+                })
+                .filter(user::Column::Id.eq(model.id))
+                .filter(match &model.ticket_nonce {
+                    Some(nonce) => user::Column::TicketNonce.eq(nonce.as_str()),
+                    None => user::Column::TicketNonce.is_null(),
+                })
+                .exec(&state.db)
+                .await?
+                .rows_affected;
+            if written == 0
+                && let Some(nonce) = user::Entity::find_by_id(model.id)
+                    .one(&state.db)
+                    .await?
+                    .and_then(|current| current.ticket_nonce)
+            {
+                code = join_code(state, model.id, &nonce);
+            }
+        }
+        tickets.push(Ticket {
+            seat: Seat::of(&model),
+            name: model.name,
+            npm: model.npm,
+            join_code: format_code(&code),
+        });
+    }
+    Ok(tickets)
+}
+
+/// Uses up the join code and returns the NPM of the student it was printed for.
+pub async fn redeem(state: &AppState, code: &str, now: DateTime<Utc>) -> Result<String, AppError> {
+    let invalid = || AppError::Unauthorized("join code is invalid or was already used".into());
+    let code: String = code
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|ch| ch.to_ascii_uppercase())
+        .collect();
+    if code.len() != CODE_LEN {
+        return Err(invalid());
+    }
+    let code_hash = code_hash(state, &code);
+
+    let (model, classroom_model) = user::Entity::find()
+        .find_also_related(classroom::Entity)
+        .filter(user::Column::TicketCodeHash.eq(code_hash.as_str()))
+        .one(&state.db)
+        .await?
+        .and_then(|(model, classroom_model)| Some((model, classroom_model?)))
+        .ok_or_else(invalid)?;
+    if ClassroomStatus::from_str(&classroom_model.status) != Some(ClassroomStatus::Published) {
+        return Err(invalid());
+    }
+    if classroom_model.is_exam {
+        let clock = ExamClock::load(&state.db, &classroom_model).await?;
+        if state.time_policy.has_ended(&clock, now) {
+            return Err(invalid());
+        }
+    }
+
+    let used = user::Entity::update_many()
+        .set(user::ActiveModel {
+            ticket_nonce: sea_orm::ActiveValue::Set(None),
+            ticket_code_hash: sea_orm::ActiveValue::Set(None),
+            ..Default::default()
+        })
+        .filter(user::Column::Id.eq(model.id))
+        .filter(user::Column::TicketCodeHash.eq(code_hash.as_str()))
+        .exec(&state.db)
+        .await?;
+    if used.rows_affected == 0 {
+        return Err(invalid());
+    }
+    Ok(model.npm)
+}
+
+/// Plain-text tickets separated by cut lines.
+pub fn render_text(classroom_model: &classroom::Model, tickets: &[Ticket], zone: Tz) -> String {
+    let mut out = String::new();
+    for ticket in tickets {
+        out.push_str(CUT_LINE);
+        out.push('\n');
+        for line in lines(classroom_model, ticket, zone) {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out.push_str(CUT_LINE);
+    out.push('\n');
+    out
+}
+
+/// A4 PDF with as many whole tickets per page as fit.
+pub fn render_pdf(classroom_model: &classroom::Model, tickets: &[Ticket], zone: Tz) -> Vec<u8> {
+    let mut pages: Vec<Vec<String>> = vec![Vec::new()];
+    for ticket in tickets {
+        let mut block = vec![CUT_LINE.to_owned()];
+        block.extend(lines(classroom_model, ticket, zone).into_iter().flat_map(wrap));
+        let page = pages.last_mut().expect("at least one page");
+        if !page.is_empty() && page.len() + block.len() + 1 > pdf::LINES_PER_PAGE {
+            page.push(CUT_LINE.to_owned());
+            pages.push(Vec::new());
+        }
+        pages.last_mut().expect("at least one page").extend(block);
+    }
+    pages
+        .last_mut()
+        .expect("at least one page")
+        .push(CUT_LINE.to_owned());
+    pdf::text_document(
+        &format!("Kartu Ujian - {}", classroom_model.name),
+        "id-ID",
+        &pages,
+    )
+}
+
+fn lines(classroom_model: &classroom::Model, ticket: &Ticket, zone: Tz) -> Vec<String> {
+    let local = |time: Option<DateTime<Utc>>| {
+        time.map(|time| time.with_timezone(&zone).format("%d-%m-%Y %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_owned())
+    };
+    vec![
+        format!("KARTU UJIAN - {}", classroom_model.name),
+        String::new(),
+        format!("Nama      : {}", ticket.name),
+        format!("NPM       : {}", ticket.npm),
+        format!(
+            "Kursi     : {}",
+            ticket.seat.as_ref().map_or("-", |seat| seat.label.as_str())
+        ),
+        format!("Mulai     : {} ({zone})", local(classroom_model.exam_start)),
+        format!("Selesai   : {} ({zone})", local(classroom_model.exam_end)),
+        format!("Kode masuk: {}", ticket.join_code),
+        String::new(),
+        "Masuk dengan NPM, atau dengan kode masuk di atas jika lupa NPM.".to_owned(),
+        "Kode masuk hanya berlaku satu kali; jangan berikan ke orang lain.".to_owned(),
+    ]
+}
+
+/// Splits a line at spaces to fit the PDF page width.
+fn wrap(line: String) -> Vec<String> {
+    if line.chars().count() <= pdf::CHARS_PER_LINE {
+        return vec![line];
+    }
+    let mut wrapped = Vec::new();
+    let mut current = String::new();
+    for word in line.split(' ') {
+        if !current.is_empty()
+            && current.chars().count() + 1 + word.chars().count() > pdf::CHARS_PER_LINE
+        {
+            wrapped.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    wrapped.push(current);
+    wrapped
+}
+
+fn join_code(state: &AppState, user_id: i32, nonce: &str) -> String {
+    state
+        .secrets
+        .keyed_hash(format!("exam-ticket:{user_id}:{nonce}").as_bytes())
+        .iter()
+        .take(CODE_LEN)
+        .map(|byte| CODE_ALPHABET[usize::from(*byte) % CODE_ALPHABET.len()] as char)
+        .collect()
+}
+
+fn code_hash(state: &AppState, code: &str) -> String {
+    hex::encode(
+        state
+            .secrets
+            .keyed_hash(format!("exam-ticket-code:{code}").as_bytes()),
+    )
+}
+
+/// `ABCD-EFGH`, easier to copy from paper.
+fn format_code(code: &str) -> String {
+    let (first, second) = code.split_at(CODE_LEN / 2);
+    format!("{first}-{second}")
+}
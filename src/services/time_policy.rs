@@ -0,0 +1,132 @@
+//! Student-facing exam window checks. Login, event streams, hand-ins and
+//! auto-submission all ask [`TimePolicy`] so they agree on when an exam is open, and
+//! a server clock a few seconds off does not lock students out or cut them short.
+//! Proctor actions such as pausing still use the exact [`ExamClock`] times.
+
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    entities::classroom, error::AppError, services::exam_clock::ExamClock, state::AppState,
+};
+
+/// How long the drift check waits for a reference clock.
+const DRIFT_CHECK_TIMEOUT: StdDuration = StdDuration::from_secs(5);
+
+#[derive(Debug, Clone, Copy)]
+pub struct TimePolicy {
+    /// Slack on both ends of the exam window for drift between the server and lab clocks.
+    pub skew_tolerance: Duration,
+    /// Time after the tolerant end in which a hand-in is still accepted, so clients that
+    /// submit on `timeup` are not lost to latency. Auto-submission waits it out too.
+    pub submit_grace: Duration,
+}
+
+impl TimePolicy {
+    pub fn new(skew_tolerance_secs: u64, submit_grace_secs: u64) -> Self {
+        Self {
+            skew_tolerance: Duration::seconds(skew_tolerance_secs as i64),
+            submit_grace: Duration::seconds(submit_grace_secs as i64),
+        }
+    }
+
+    /// The exam has begun, allowing for a server clock that runs behind.
+    pub fn has_started(&self, clock: &ExamClock, now: DateTime<Utc>) -> bool {
+        clock.has_started(now + self.skew_tolerance)
+    }
+
+    /// The exam is over, allowing for a server clock that runs ahead. A paused exam never is.
+    pub fn has_ended(&self, clock: &ExamClock, now: DateTime<Utc>) -> bool {
+        ended_after(clock, now, self.skew_tolerance)
+    }
+
+    pub fn is_running(&self, clock: &ExamClock, now: DateTime<Utc>) -> bool {
+        self.has_started(clock, now) && !self.has_ended(clock, now)
+    }
+
+    /// Hand-ins are refused from here on; unfinished attempts are auto-submitted instead.
+    pub fn submissions_closed(&self, clock: &ExamClock, now: DateTime<Utc>) -> bool {
+        ended_after(clock, now, self.skew_tolerance + self.submit_grace)
+    }
+}
+
+/// Refuses a hand-in, run or code save in an exam classroom once
+/// [`TimePolicy::submissions_closed`].
+pub async fn ensure_submissions_open(
+    state: &AppState,
+    classroom_model: &classroom::Model,
+) -> Result<(), AppError> {
+    if !classroom_model.is_exam {
+        return Ok(());
+    }
+    let clock = ExamClock::load(&state.db, classroom_model).await?;
+    if clock.end.is_some() && state.time_policy.submissions_closed(&clock, Utc::now()) {
+        return Err(AppError::Forbidden(
+            "exam has ended; hand-ins are closed".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn ended_after(clock: &ExamClock, now: DateTime<Utc>, slack: Duration) -> bool {
+    !clock.is_paused()
+        && clock
+            .effective_end(now)
+            .is_some_and(|end| now >= end + slack)
+}
+
+/// Compares the server clock with `reference_url`, or with every Judge0 instance when
+/// unset, using the `Date` response header. Drift beyond the tolerance is logged as a
+/// warning since it eats into the slack exam checks rely on. Runs once, in the background.
+pub fn spawn_drift_check(state: AppState, reference_url: Option<String>) {
+    tokio::spawn(async move {
+        let targets = match reference_url {
+            Some(url) => vec![url],
+            None => state
+                .executors
+                .candidates()
+                .iter()
+                .map(|instance| format!("{}/about", instance.base_url))
+                .collect(),
+        };
+        let tolerance = state.time_policy.skew_tolerance;
+        for target in targets {
+            match measure_offset(&state, &target).await {
+                Ok(offset) if offset.abs() > tolerance => tracing::warn!(
+                    "server clock is {}s off {target}, beyond the {}s exam tolerance; check NTP sync",
+                    offset.num_seconds(),
+                    tolerance.num_seconds()
+                ),
+                Ok(offset) => {
+                    tracing::info!("clock offset to {target}: {} ms", offset.num_milliseconds())
+                }
+                Err(err) => tracing::warn!("clock drift check against {target} failed: {err}"),
+            }
+        }
+    });
+}
+
+/// Reference time minus server time. The reference is assumed to have stamped the
+/// response halfway through the round trip; `Date` has one-second resolution.
+async fn measure_offset(state: &AppState, url: &str) -> Result<Duration, String> {
+    let sent = Utc::now();
+    let response = state
+        .http_client
+        .get(url)
+        .timeout(DRIFT_CHECK_TIMEOUT)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+    let received = Utc::now();
+    let date = response
+        .headers()
+        .get(reqwest::header::DATE)
+        .and_then(|value| value.to_str().ok())
+        .ok_or("response has no Date header")?;
+    let reference = DateTime::parse_from_rfc2822(date)
+        .map_err(|err| format!("invalid Date header {date:?}: {err}"))?
+        .with_timezone(&Utc);
+    let midpoint = sent + (received - sent) / 2;
+    Ok(reference - midpoint)
+}
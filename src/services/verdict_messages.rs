@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
+use serde_json::Value;
+
+use crate::{dto::judge::Judge0SubmissionStatus, entities::verdict_message, error::AppError};
+
+/// Judge0 statuses and the descriptions Judge0 reports for them.
+pub const JUDGE0_STATUSES: [(i32, &str); 14] = [
+    (1, "In Queue"),
+    (2, "Processing"),
+    (3, "Accepted"),
+    (4, "Wrong Answer"),
+    (5, "Time Limit Exceeded"),
+    (6, "Compilation Error"),
+    (7, "Runtime Error (SIGSEGV)"),
+    (8, "Runtime Error (SIGXFSZ)"),
+    (9, "Runtime Error (SIGFPE)"),
+    (10, "Runtime Error (SIGABRT)"),
+    (11, "Runtime Error (NZEC)"),
+    (12, "Runtime Error (Other)"),
+    (13, "Internal Error"),
+    (14, "Exec Format Error"),
+];
+
+pub fn default_description(status_id: i32) -> Option<&'static str> {
+    JUDGE0_STATUSES
+        .iter()
+        .find(|(id, _)| *id == status_id)
+        .map(|(_, description)| *description)
+}
+
+/// A classroom's verdict wording, keyed by Judge0 status id. Statuses without an
+/// entry keep Judge0's description.
+#[derive(Debug, Default)]
+pub struct VerdictMessages(HashMap<i32, String>);
+
+impl VerdictMessages {
+    pub async fn load(db: &impl ConnectionTrait, classroom_id: i32) -> Result<Self, AppError> {
+        let messages = verdict_message::Entity::find()
+            .filter(verdict_message::Column::ClassroomId.eq(classroom_id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|model| (model.status_id, model.message))
+            .collect();
+        Ok(Self(messages))
+    }
+
+    pub fn apply(&self, status: &mut Judge0SubmissionStatus) {
+        if let Some(message) = self.0.get(&status.id) {
+            status.description = message.clone();
+        }
+    }
+
+    /// [`apply`](Self::apply) for a raw Judge0 response.
+    pub fn apply_value(&self, result: &mut Value) {
+        let Some(status) = result.get_mut("status").and_then(Value::as_object_mut) else {
+            return;
+        };
+        let Some(message) = status
+            .get("id")
+            .and_then(Value::as_i64)
+            .and_then(|id| i32::try_from(id).ok())
+            .and_then(|id| self.0.get(&id))
+        else {
+            return;
+        };
+        status.insert("description".to_owned(), Value::String(message.clone()));
+    }
+}
@@ -0,0 +1,118 @@
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::Serialize;
+
+use crate::{entities::webhook_endpoint, error::AppError, services::signing, state::AppState};
+
+/// Upper bound for one webhook request, so a slow receiver cannot hold a worker.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Envelope posted to every receiver; `data` depends on `event`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Envelope<'a, T> {
+    event: &'a str,
+    endpoint_id: i32,
+    sent_at: chrono::DateTime<Utc>,
+    data: T,
+}
+
+/// Result of one delivery attempt. A receiver answering with an error status or not
+/// answering at all is an outcome, not an error.
+pub struct Delivery {
+    pub status_code: Option<u16>,
+    pub duration: Duration,
+    pub signature: String,
+    pub error: Option<String>,
+}
+
+impl Delivery {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+            && self
+                .status_code
+                .is_some_and(|code| (200..300).contains(&code))
+    }
+}
+
+/// Posts `data` to the endpoint as a signed JSON envelope. Every outgoing webhook goes
+/// through here so each carries an `X-Signature` made with the endpoint's secret.
+pub async fn deliver<T: Serialize>(
+    state: &AppState,
+    endpoint: &webhook_endpoint::Model,
+    event: &str,
+    data: T,
+) -> Result<Delivery, AppError> {
+    let secret = state.secrets.open(&endpoint.secret_encrypted)?;
+    let now = Utc::now();
+    let body = serde_json::to_vec(&Envelope {
+        event,
+        endpoint_id: endpoint.id,
+        sent_at: now,
+        data,
+    })
+    .map_err(|err| AppError::Internal(format!("webhook payload: {err}")))?;
+    let signature = signing::sign(secret.as_bytes(), now.timestamp(), &body);
+
+    let started = Instant::now();
+    let result = state
+        .http_client
+        .post(&endpoint.url)
+        .timeout(DELIVERY_TIMEOUT)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(signing::SIGNATURE_HEADER, &signature)
+        .header("x-webhook-event", event)
+        .body(body)
+        .send()
+        .await;
+
+    let (status_code, error) = match result {
+        Ok(response) => (Some(response.status().as_u16()), None),
+        Err(err) => (None, Some(err.to_string())),
+    };
+
+    Ok(Delivery {
+        status_code,
+        duration: started.elapsed(),
+        signature,
+        error,
+    })
+}
+
+/// Sends an event to every active endpoint in the background. Delivery problems are
+/// logged, never returned, so alerting cannot fail the operation that raised it.
+pub fn broadcast(state: &AppState, event: &'static str, data: serde_json::Value) {
+    let state = state.clone();
+    tokio::spawn(async move {
+        let endpoints = match webhook_endpoint::Entity::find()
+            .filter(webhook_endpoint::Column::Active.eq(true))
+            .all(&state.db)
+            .await
+        {
+            Ok(endpoints) => endpoints,
+            Err(err) => {
+                tracing::warn!("cannot load webhook endpoints for {event}: {err}");
+                return;
+            }
+        };
+
+        for endpoint in endpoints {
+            match deliver(&state, &endpoint, event, &data).await {
+                Ok(delivery) if delivery.succeeded() => {}
+                Ok(delivery) => tracing::warn!(
+                    "webhook {event} to endpoint {} failed: {}",
+                    endpoint.id,
+                    delivery.error.unwrap_or_else(|| format!(
+                        "status {}",
+                        delivery.status_code.unwrap_or_default()
+                    ))
+                ),
+                Err(err) => {
+                    tracing::warn!("webhook {event} to endpoint {} failed: {err}", endpoint.id)
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,30 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use axum::{Router, response::Html, routing::get};
+
+use crate::state::AppState;
+
+/// First-run wizard: registers the first admin through the login endpoint and hands
+/// the token to the admin docs.
+const SETUP_PAGE: &str = include_str!("setup.html");
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/setup", get(|| async { Html(SETUP_PAGE) }))
+}
+
+/// Opens the setup page in the default browser, if there is one.
+pub fn open_in_browser(addr: SocketAddr) {
+    let host = if addr.ip().is_unspecified() {
+        Ipv4Addr::LOCALHOST.into()
+    } else {
+        addr.ip()
+    };
+    let url = format!("http://{}/setup", SocketAddr::new(host, addr.port()));
+
+    tracing::info!("Buka {url} untuk menyelesaikan penyiapan awal");
+    std::thread::spawn(move || {
+        if let Err(err) = webbrowser::open(&url) {
+            tracing::warn!("gagal membuka browser: {err}");
+        }
+    });
+}
@@ -1,9 +1,89 @@
+use std::{path::PathBuf, sync::Arc, time::Duration as StdDuration};
+
+use chrono::{DateTime, Duration, Utc};
 use reqwest::Client;
 use sea_orm::DatabaseConnection;
+use tokio::sync::Notify;
+
+use crate::{
+    auth::{jwt::JwtKeys, webauthn::WebAuthn},
+    dto::ClientBranding,
+    services::{
+        admission::ExecutorQueue, alerting::ErrorBudget, archive::Archive,
+        connections::ConnectionTracker, event_bus::EventBus, executor_pool::ExecutorPool,
+        export_storage::ExportStorage, field_limits::FieldLimits, metrics::Metrics,
+        rate_limit::SubmissionLimiter, replay_guard::NonceCache, replication::Replication,
+        secret_box::SecretBox, time_policy::TimePolicy,
+    },
+};
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: DatabaseConnection,
     pub http_client: Client,
-    pub judge0_base_url: String,
+    /// Judge0 instances executor calls are balanced over.
+    pub executors: Arc<ExecutorPool>,
+    /// Upper bound for a single wait-mode Judge0 call.
+    pub judge0_timeout: StdDuration,
+    /// Bounded queue admitting work to Judge0.
+    pub executor_queue: Arc<ExecutorQueue>,
+    /// Students the executors can serve in concurrent exams; `None` skips the check.
+    pub exam_student_capacity: Option<usize>,
+    /// Failed gradings of a final submission before it is moved to the dead letters.
+    pub grading_max_attempts: u32,
+    /// Default stdout/stderr cap per run in KB; classrooms may set their own.
+    pub output_limit_kb: u32,
+    /// Default character caps on code and task descriptions; classrooms may set their own.
+    pub field_limits: FieldLimits,
+    /// Remaining-time marks at which exam event streams warn, largest first.
+    pub exam_warning_thresholds: Arc<[Duration]>,
+    /// Clock-skew tolerance and hand-in grace applied to exam windows.
+    pub time_policy: TimePolicy,
+    pub events: EventBus,
+    /// Open classroom event streams per user.
+    pub connections: Arc<ConnectionTracker>,
+    pub started_at: DateTime<Utc>,
+    pub metrics: Arc<Metrics>,
+    /// Error-rate windows that alert webhooks when a threshold is crossed.
+    pub error_budget: Arc<ErrorBudget>,
+    pub jwt: Arc<JwtKeys>,
+    /// Encrypts secrets stored in the database, e.g. webhook signing keys.
+    pub secrets: Arc<SecretBox>,
+    /// Per-student Judge0 submission budget, adjusted to executor latency.
+    pub rate_limit: Arc<SubmissionLimiter>,
+    /// Account email, verification and NPM recovery endpoints are enabled.
+    pub account_email_enabled: bool,
+    /// NPMs that are on no roster get a pending account until an admin approves it.
+    pub account_approval_required: bool,
+    /// Endpoint outgoing mail is posted to; mail is only logged when unset.
+    pub mail_relay_url: Option<String>,
+    /// Fixed budget for verification and recovery emails per address and client.
+    pub email_rate_limit: Arc<SubmissionLimiter>,
+    /// Passkey registration and login; `None` when `WEBAUTHN_RP_ID` is unset.
+    pub webauthn: Option<Arc<WebAuthn>>,
+    /// Stored OpenAPI snapshot the served document is diffed against.
+    pub openapi_baseline: Option<PathBuf>,
+    /// Share of frontend error reports without a request id that are stored.
+    pub client_error_sample_rate: f64,
+    /// Timezone of the readable timestamp columns in exports.
+    pub display_timezone: chrono_tz::Tz,
+    /// Fingerprint retention in days until an admin stores one; 0 keeps them forever.
+    pub fingerprint_retention_days: u32,
+    /// Names and links served to frontends by `GET /api/client-config`.
+    pub branding: Arc<ClientBranding>,
+    /// Where finished background exports are kept until they expire.
+    pub export_storage: Arc<ExportStorage>,
+    /// Lifetime of a signed export download link, in seconds.
+    pub export_url_ttl_secs: u64,
+    /// Nonces of recent signed-in finish requests, to refuse replays.
+    pub finish_nonces: Arc<NonceCache>,
+    /// Snapshot shipping to, or receiving from, a warm standby.
+    pub replication: Arc<Replication>,
+    /// Copy of every final submission in `ARCHIVE_SINK`, when configured.
+    pub archive: Option<Arc<Archive>>,
+    /// Wakes the worker completing runs submitted with `wait=false`.
+    pub judge0_polls: Arc<Notify>,
+    /// Fault injection rates set through `/api/admin/chaos`.
+    #[cfg(feature = "chaos")]
+    pub chaos: Arc<crate::services::chaos::Chaos>,
 }
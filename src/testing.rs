@@ -0,0 +1,295 @@
+//! Embedded server for black-box tests of tools built on the API, such as grading
+//! scripts and the proctoring bot. Enabled with the `test-server` feature.
+//!
+//! [`start_test_server`] boots the full router on an ephemeral port, backed by an
+//! in-memory SQLite database and a stub Judge0, so no docker-compose is needed:
+//!
+//! ```ignore
+//! let server = asm_lab_server::testing::start_test_server().await?;
+//! let token = server.admin_token().await?;
+//! let classrooms = reqwest::Client::new()
+//!     .get(server.url("/api/classrooms"))
+//!     .bearer_auth(&token)
+//!     .send()
+//!     .await?;
+//! ```
+
+use std::{
+    collections::VecDeque,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use anyhow::Result;
+use axum::{
+    Json, Router,
+    extract::State,
+    routing::{get, post},
+};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectOptions, EntityTrait, QueryFilter,
+};
+use serde_json::{Value, json};
+use tokio::{net::TcpListener, sync::oneshot};
+
+use crate::{
+    app, config, db,
+    dto::{AccountRole, AccountStatus, Judge0SubmissionResponse, judge::Judge0SubmissionStatus},
+    entities::account,
+    state::AppState,
+};
+
+/// NPM of the admin account created by [`TestServer::admin_token`].
+pub const TEST_ADMIN_NPM: &str = "admin";
+
+static NEXT_INSTANCE: AtomicU64 = AtomicU64::new(1);
+
+/// A running server. It stops when dropped; its database and export files go with it.
+pub struct TestServer {
+    /// `http://127.0.0.1:<port>`, without a trailing slash.
+    pub base_url: String,
+    /// The server's state, for arranging data directly in the database.
+    pub state: AppState,
+    /// The Judge0 stand-in the server executes code on.
+    pub executor: StubExecutor,
+    storage_dir: PathBuf,
+    shutdown: Vec<oneshot::Sender<()>>,
+}
+
+impl TestServer {
+    /// `path` appended to the server address, e.g. `url("/api/classrooms")`.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    /// Bearer token of the admin account, creating the account on first use.
+    pub async fn admin_token(&self) -> Result<String> {
+        let existing = account::Entity::find()
+            .filter(account::Column::Npm.eq(TEST_ADMIN_NPM))
+            .one(&self.state.db)
+            .await?;
+        let admin = match existing {
+            Some(model) => model,
+            None => {
+                let now = Utc::now();
+                account::ActiveModel {
+                    npm: Set(TEST_ADMIN_NPM.to_owned()),
+                    role: Set(AccountRole::Admin.as_str().to_owned()),
+                    status: Set(AccountStatus::Active.as_str().to_owned()),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(&self.state.db)
+                .await?
+            }
+        };
+        let (token, _) = self.state.jwt.issue(&admin)?;
+        Ok(token)
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        for shutdown in self.shutdown.drain(..) {
+            let _ = shutdown.send(());
+        }
+        let _ = std::fs::remove_dir_all(&self.storage_dir);
+    }
+}
+
+/// Boots a server on `127.0.0.1` with a fresh in-memory database, background jobs
+/// included. Settings not needed for isolation keep their defaults from the
+/// environment, as in [`config::Settings::from_env`].
+pub async fn start_test_server() -> Result<TestServer> {
+    let instance = NEXT_INSTANCE.fetch_add(1, Ordering::Relaxed);
+    let (executor, executor_shutdown) = StubExecutor::start().await?;
+
+    // Named shared-cache memory database: every pooled connection sees the same data,
+    // and it lives as long as one of them stays open.
+    let database_url = format!(
+        "sqlite:file:asm-lab-test-{}-{instance}?mode=memory&cache=shared",
+        std::process::id()
+    );
+    let mut options = ConnectOptions::new(database_url.clone());
+    options.min_connections(1).sqlx_logging(false);
+    let db = db::connect_with(options).await?;
+    db::init(&db).await?;
+
+    let storage_dir = std::env::temp_dir().join(format!(
+        "asm-lab-test-{}-{instance}-exports",
+        std::process::id()
+    ));
+    let mut settings = config::Settings::from_env();
+    settings.database_url = database_url;
+    settings.judge0_base_urls = vec![executor.base_url.clone()];
+    settings.jwt_secret = Some(format!("asm-lab-test-{instance}"));
+    settings.secret_encryption_key = None;
+    settings.mail_relay_url = None;
+    settings.webauthn_rp_id = None;
+    settings.openapi_baseline = None;
+    settings.export_storage_dir = storage_dir.clone();
+
+    let state = app::build_state(&settings, db)?;
+    app::start_background(&state, &settings).await?;
+    let router = app::router(state.clone());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let base_url = format!("http://{}", listener.local_addr()?);
+    let (shutdown, stopped) = oneshot::channel::<()>();
+    tokio::spawn(async move {
+        let served = axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(async {
+            let _ = stopped.await;
+        })
+        .await;
+        if let Err(err) = served {
+            tracing::warn!("test server stopped: {err}");
+        }
+    });
+
+    Ok(TestServer {
+        base_url,
+        state,
+        executor,
+        storage_dir,
+        shutdown: vec![shutdown, executor_shutdown],
+    })
+}
+
+/// Answers the Judge0 endpoints the server calls. By default a submission "prints"
+/// its stdin and is Accepted when that matches `expected_output` (or none is given),
+/// Wrong Answer otherwise; queue replies with [`StubExecutor::respond_with`] for
+/// anything else.
+#[derive(Clone)]
+pub struct StubExecutor {
+    /// Base URL the server was configured with.
+    pub base_url: String,
+    inner: Arc<StubInner>,
+}
+
+#[derive(Default)]
+struct StubInner {
+    replies: Mutex<VecDeque<Judge0SubmissionResponse>>,
+    requests: Mutex<Vec<Value>>,
+    next_token: AtomicU64,
+}
+
+impl StubExecutor {
+    async fn start() -> Result<(Self, oneshot::Sender<()>)> {
+        let inner = Arc::new(StubInner::default());
+        let router = Router::new()
+            .route(
+                "/about",
+                get(|| async { Json(json!({ "version": "stub" })) }),
+            )
+            .route("/languages", get(stub_languages))
+            .route("/submissions", post(stub_submission))
+            .with_state(inner.clone());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        tokio::spawn(async move {
+            let served = axum::serve(listener, router)
+                .with_graceful_shutdown(async {
+                    let _ = stopped.await;
+                })
+                .await;
+            if let Err(err) = served {
+                tracing::warn!("stub executor stopped: {err}");
+            }
+        });
+        Ok((Self { base_url, inner }, shutdown))
+    }
+
+    /// Queues the reply to the next submission; queued replies are used in order
+    /// before falling back to the echo behaviour.
+    pub fn respond_with(&self, reply: Judge0SubmissionResponse) {
+        self.inner
+            .replies
+            .lock()
+            .expect("stub executor poisoned")
+            .push_back(reply);
+    }
+
+    /// Bodies of every submission received so far, oldest first.
+    pub fn requests(&self) -> Vec<Value> {
+        self.inner
+            .requests
+            .lock()
+            .expect("stub executor poisoned")
+            .clone()
+    }
+}
+
+async fn stub_languages() -> Json<Value> {
+    Json(json!([
+        { "id": 45, "name": "Assembly (NASM 2.14.02)" },
+        { "id": 50, "name": "C (GCC 9.2.0)" },
+        { "id": 54, "name": "C++ (GCC 9.2.0)" },
+        { "id": 71, "name": "Python (3.8.1)" }
+    ]))
+}
+
+async fn stub_submission(
+    State(inner): State<Arc<StubInner>>,
+    Json(body): Json<Value>,
+) -> Json<Judge0SubmissionResponse> {
+    inner
+        .requests
+        .lock()
+        .expect("stub executor poisoned")
+        .push(body.clone());
+    let token = format!(
+        "stub-{}",
+        inner.next_token.fetch_add(1, Ordering::Relaxed) + 1
+    );
+    if let Some(mut reply) = inner
+        .replies
+        .lock()
+        .expect("stub executor poisoned")
+        .pop_front()
+    {
+        if reply.token.is_empty() {
+            reply.token = token;
+        }
+        return Json(reply);
+    }
+
+    let stdout = body["stdin"].as_str().unwrap_or_default().to_owned();
+    let accepted = body["expected_output"]
+        .as_str()
+        .is_none_or(|expected| expected.trim_end() == stdout.trim_end());
+    let (id, description) = if accepted {
+        (3, "Accepted")
+    } else {
+        (4, "Wrong Answer")
+    };
+    Json(Judge0SubmissionResponse {
+        stdout: Some(stdout),
+        stderr: None,
+        compile_output: None,
+        message: None,
+        status: Some(Judge0SubmissionStatus {
+            id,
+            description: description.to_owned(),
+        }),
+        token,
+        time: Some("0.001".to_owned()),
+        memory: Some(1024),
+        exit_code: Some(0),
+        exit_signal: None,
+        truncated: false,
+        stdout_bytes: None,
+        stderr_bytes: None,
+    })
+}
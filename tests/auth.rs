@@ -0,0 +1,154 @@
+mod common;
+
+use common::{Api, user_id};
+use reqwest::{Method, StatusCode};
+use serde_json::json;
+
+#[tokio::test]
+async fn admin_stats_need_an_admin_token() {
+    let api = Api::start().await;
+    let admin = api.admin_token().await;
+    let student = api.student_token("111").await;
+
+    for path in ["/api/admin/system-stats", "/api/admin/metrics"] {
+        let (status, _) = api.send(Method::GET, path, None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED, "{path} without a token");
+        let (status, _) = api.send(Method::GET, path, Some(&student), None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{path} as a student");
+        let (status, _) = api.send(Method::GET, path, Some(&admin), None).await;
+        assert_eq!(status, StatusCode::OK, "{path} as the admin");
+    }
+}
+
+#[tokio::test]
+async fn system_stats_count_every_table() {
+    let api = Api::start().await;
+    let admin = api.admin_token().await;
+
+    let (status, stats) = api
+        .send(Method::GET, "/api/admin/system-stats", Some(&admin), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let tables: Vec<&str> = stats["tables"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|table| table["table"].as_str())
+        .collect();
+    for table in [
+        "archive_outbox",
+        "classroom_settings_versions",
+        "webauthn_credentials",
+        "seaql_migrations",
+    ] {
+        assert!(tables.contains(&table), "{table} missing from {tables:?}");
+    }
+    assert_eq!(stats["storage"][0]["name"], "exports");
+}
+
+#[tokio::test]
+async fn students_cannot_manage_classrooms() {
+    let api = Api::start().await;
+    let student = api.student_token("111").await;
+
+    let (status, _) = api
+        .send(
+            Method::POST,
+            "/api/classrooms",
+            Some(&student),
+            Some(json!({ "name": "K", "users": [], "tasks": [] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = api
+        .send(
+            Method::POST,
+            "/api/classrooms",
+            None,
+            Some(json!({ "name": "K" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn students_only_acknowledge_their_own_messages() {
+    let api = Api::start().await;
+    let admin = api.admin_token().await;
+    let classroom = api
+        .create_classroom(json!({
+            "name": "K",
+            "users": [
+                { "name": "A", "npm": "111", "code": "" },
+                { "name": "B", "npm": "222", "code": "" }
+            ],
+            "tasks": ["t1"]
+        }))
+        .await;
+    let classroom_id = classroom["id"].as_i64().unwrap();
+
+    let (status, sent) = api
+        .send(
+            Method::POST,
+            &format!("/api/classrooms/{classroom_id}/messages"),
+            Some(&admin),
+            Some(json!({ "userIds": [user_id(&classroom, "111")], "message": "Waktu tinggal 5 menit" })),
+        )
+        .await;
+    assert!(status.is_success(), "send message: {sent}");
+    let ack = format!("/api/messages/{}/ack", sent["id"]);
+
+    let (status, _) = api
+        .send(Method::POST, &ack, None, Some(json!({ "npm": "111" })))
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let other = api.student_token("222").await;
+    let (status, _) = api
+        .send(
+            Method::POST,
+            &ack,
+            Some(&other),
+            Some(json!({ "npm": "111" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let recipient = api.student_token("111").await;
+    let (status, body) = api
+        .send(
+            Method::POST,
+            &ack,
+            Some(&recipient),
+            Some(json!({ "npm": "111" })),
+        )
+        .await;
+    assert!(status.is_success(), "ack as the recipient: {body}");
+}
+
+#[tokio::test]
+async fn proctor_stream_accepts_a_query_token() {
+    let api = Api::start().await;
+    let admin = api.admin_token().await;
+    let classroom = api
+        .create_classroom(json!({ "name": "K", "users": [], "tasks": ["t1"] }))
+        .await;
+    let path = format!("/api/events?classroom_ids={}", classroom["id"]);
+
+    let response = api.request(Method::GET, &path, None).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = api
+        .request(Method::GET, &format!("{path}&token={admin}"), None)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(
+        response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/event-stream")
+    );
+}
@@ -0,0 +1,93 @@
+//! Helpers shared by the end-to-end tests.
+
+use asm_lab_server::testing::{TestServer, start_test_server};
+use reqwest::{Method, StatusCode};
+use serde_json::{Value, json};
+
+pub struct Api {
+    pub server: TestServer,
+    client: reqwest::Client,
+}
+
+impl Api {
+    pub async fn start() -> Api {
+        Api {
+            server: start_test_server().await.expect("test server starts"),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn admin_token(&self) -> String {
+        self.server.admin_token().await.expect("admin token")
+    }
+
+    /// Signs in with an NPM, creating the student account on first use.
+    pub async fn student_token(&self, npm: &str) -> String {
+        let (status, body) = self
+            .send(
+                Method::POST,
+                "/api/auth/login",
+                None,
+                Some(json!({ "npm": npm })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "login as {npm}: {body}");
+        body["token"].as_str().expect("token").to_owned()
+    }
+
+    pub fn request(
+        &self,
+        method: Method,
+        path: &str,
+        token: Option<&str>,
+    ) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, self.server.url(path));
+        match token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Sends `body` as JSON and returns the status with the JSON reply (`null` when
+    /// the reply has no JSON body).
+    pub async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        token: Option<&str>,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let mut request = self.request(method, path, token);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request.send().await.expect("request is sent");
+        let status = response.status();
+        let body = response.json().await.unwrap_or(Value::Null);
+        (status, body)
+    }
+
+    /// Creates a classroom as the admin and returns its JSON.
+    pub async fn create_classroom(&self, classroom: Value) -> Value {
+        let token = self.admin_token().await;
+        let (status, body) = self
+            .send(
+                Method::POST,
+                "/api/classrooms",
+                Some(&token),
+                Some(classroom),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "create classroom: {body}");
+        body
+    }
+}
+
+/// Id of the roster entry with `npm` in a classroom response.
+pub fn user_id(classroom: &Value, npm: &str) -> i64 {
+    classroom["users"]
+        .as_array()
+        .and_then(|users| users.iter().find(|user| user["npm"] == npm))
+        .and_then(|user| user["id"].as_i64())
+        .unwrap_or_else(|| panic!("{npm} is on the roster"))
+}
@@ -0,0 +1,530 @@
+mod common;
+
+use asm_lab_server::{
+    entities::{classroom, submission, user},
+    services::finalizer,
+};
+use chrono::{Duration, Utc};
+use common::{Api, user_id};
+use reqwest::{Method, StatusCode};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder,
+};
+use serde_json::{Value, json};
+
+/// Hands in `code` as the student, with the replay headers a signed-in finish needs.
+async fn finish(
+    api: &Api,
+    classroom_id: i64,
+    token: &str,
+    npm: &str,
+    code: &str,
+) -> (StatusCode, Value) {
+    finish_with(
+        api,
+        classroom_id,
+        token,
+        json!({ "npm": npm, "code": code }),
+    )
+    .await
+}
+
+async fn finish_with(
+    api: &Api,
+    classroom_id: i64,
+    token: &str,
+    body: Value,
+) -> (StatusCode, Value) {
+    let response = api
+        .request(
+            Method::POST,
+            &format!("/api/classrooms/{classroom_id}/finish"),
+            Some(token),
+        )
+        .header("X-Request-Nonce", hex::encode(rand::random::<[u8; 16]>()))
+        .header("X-Request-Timestamp", Utc::now().timestamp().to_string())
+        .json(&body)
+        .send()
+        .await
+        .expect("finish is sent");
+    let status = response.status();
+    (status, response.json().await.unwrap_or(Value::Null))
+}
+
+async fn final_submissions(api: &Api, classroom_id: i64) -> Vec<submission::Model> {
+    submission::Entity::find()
+        .filter(submission::Column::ClassroomId.eq(classroom_id as i32))
+        .filter(submission::Column::IsFinal.eq(true))
+        .order_by_asc(submission::Column::UserId)
+        .all(&api.server.state.db)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn finish_hands_in_once() {
+    let api = Api::start().await;
+    let classroom = api
+        .create_classroom(json!({
+            "name": "K",
+            "isExam": true,
+            "users": [{ "name": "A", "npm": "111", "code": "" }],
+            "tasks": ["t1"]
+        }))
+        .await;
+    let classroom_id = classroom["id"].as_i64().unwrap();
+    let student = api.student_token("111").await;
+
+    let (status, first) = finish(&api, classroom_id, &student, "111", "mov ax, 1").await;
+    assert!(status.is_success(), "finish: {first}");
+    assert!(first["submissionId"].is_i64());
+
+    let (status, retry) = finish(&api, classroom_id, &student, "111", "mov ax, 2").await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(retry["code"], "already_finished");
+
+    let submissions = final_submissions(&api, classroom_id).await;
+    assert_eq!(submissions.len(), 1);
+    assert!(!submissions[0].auto_submitted);
+
+    let other = api.student_token("222").await;
+    let (status, _) = finish(&api, classroom_id, &other, "111", "mov ax, 3").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+/// Ids of the classroom's tasks, in order.
+async fn task_ids(api: &Api, classroom_id: i64, admin: &str) -> Vec<i64> {
+    let (status, tasks) = api
+        .send(
+            Method::GET,
+            &format!("/api/classrooms/{classroom_id}/tasks"),
+            Some(admin),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "tasks: {tasks}");
+    tasks
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["id"].as_i64().unwrap())
+        .collect()
+}
+
+async fn add_test_case(api: &Api, classroom_id: i64, task_id: i64, admin: &str, case: Value) {
+    let (status, body) = api
+        .send(
+            Method::POST,
+            &format!("/api/classrooms/{classroom_id}/tasks/{task_id}/test-cases"),
+            Some(admin),
+            Some(case),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "test case: {body}");
+}
+
+#[tokio::test]
+async fn task_hand_ins_are_graded_on_their_own_test_cases() {
+    let api = Api::start().await;
+    let admin = api.admin_token().await;
+    let classroom = api
+        .create_classroom(json!({
+            "name": "K",
+            "users": [{ "name": "A", "npm": "111", "code": "" }],
+            "tasks": ["t1", "t2"]
+        }))
+        .await;
+    let classroom_id = classroom["id"].as_i64().unwrap();
+    let tasks = task_ids(&api, classroom_id, &admin).await;
+    // The stub executor echoes stdin, so the first case passes and the second fails.
+    let first_case = json!({ "stdin": "1", "expectedOutput": "1", "points": 10 });
+    add_test_case(&api, classroom_id, tasks[0], &admin, first_case).await;
+    let second_case = json!({ "stdin": "2", "expectedOutput": "3", "points": 30 });
+    add_test_case(&api, classroom_id, tasks[1], &admin, second_case).await;
+
+    let student = api.student_token("111").await;
+    let body = json!({ "npm": "111", "code": "mov ax, 1", "taskId": tasks[0] });
+    let (status, reply) = finish_with(&api, classroom_id, &student, body).await;
+    assert!(status.is_success(), "finish: {reply}");
+
+    let mut graded = None;
+    for _ in 0..100 {
+        let submissions = final_submissions(&api, classroom_id).await;
+        if let Some(submission) = submissions.into_iter().find(|s| s.graded_at.is_some()) {
+            graded = Some(submission);
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    let graded = graded.expect("the hand-in is graded");
+    assert_eq!(graded.task_id, Some(tasks[0] as i32));
+    assert_eq!(graded.total_cases, Some(1));
+    assert_eq!(graded.passed_cases, Some(1));
+    assert_eq!(graded.score, Some(10));
+    assert_eq!(graded.max_score, Some(10));
+}
+
+#[tokio::test]
+async fn roster_updates_keep_hand_ins() {
+    let api = Api::start().await;
+    let admin = api.admin_token().await;
+    let classroom = api
+        .create_classroom(json!({
+            "name": "K",
+            "isExam": true,
+            "users": [
+                { "name": "A", "npm": "111", "code": "" },
+                { "name": "B", "npm": "222", "code": "" }
+            ],
+            "tasks": ["t1"]
+        }))
+        .await;
+    let classroom_id = classroom["id"].as_i64().unwrap();
+    let student = api.student_token("111").await;
+    let (status, body) = finish(&api, classroom_id, &student, "111", "mov ax, 1").await;
+    assert!(status.is_success(), "finish: {body}");
+
+    let (status, updated) = api
+        .send(
+            Method::PUT,
+            &format!("/api/classrooms/{classroom_id}"),
+            Some(&admin),
+            Some(json!({
+                "users": [
+                    { "name": "A renamed", "npm": "111", "code": "" },
+                    { "name": "C", "npm": "333", "code": "" }
+                ]
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "update: {updated}");
+    assert_eq!(user_id(&updated, "111"), user_id(&classroom, "111"));
+    user_id(&updated, "333");
+    assert!(
+        updated["users"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|user| user["npm"] != "222")
+    );
+
+    let submissions = final_submissions(&api, classroom_id).await;
+    assert_eq!(submissions.len(), 1);
+    assert_eq!(
+        i64::from(submissions[0].user_id),
+        user_id(&classroom, "111")
+    );
+}
+
+#[tokio::test]
+async fn finalizer_hands_in_saved_code_of_students_still_working() {
+    let api = Api::start().await;
+    let now = Utc::now();
+    let classroom = api
+        .create_classroom(json!({
+            "name": "K",
+            "isExam": true,
+            "examStart": now - Duration::hours(3),
+            "examEnd": now - Duration::hours(2),
+            "users": [
+                { "name": "A", "npm": "111", "code": "mov ax, 1" },
+                { "name": "B", "npm": "222", "code": "" }
+            ],
+            "tasks": ["t1"]
+        }))
+        .await;
+    let classroom_id = classroom["id"].as_i64().unwrap();
+    let db = &api.server.state.db;
+    let classroom_model = classroom::Entity::find_by_id(classroom_id as i32)
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let finalized = finalizer::finalize_expired_exam(&api.server.state, &classroom_model)
+        .await
+        .unwrap();
+    assert_eq!(finalized, 2);
+
+    let submissions = final_submissions(&api, classroom_id).await;
+    assert_eq!(submissions.len(), 2);
+    assert!(
+        submissions
+            .iter()
+            .all(|submission| submission.auto_submitted)
+    );
+    let empty = submissions
+        .iter()
+        .find(|submission| i64::from(submission.user_id) == user_id(&classroom, "222"))
+        .unwrap();
+    assert_eq!(empty.grading_status.as_deref(), Some("failed"));
+
+    let users = user::Entity::find()
+        .filter(user::Column::ClassroomId.eq(classroom_id as i32))
+        .all(db)
+        .await
+        .unwrap();
+    assert!(users.iter().all(|user| !user.active));
+
+    // Everyone is handed in, so a later sweep finds nothing left to do.
+    let finalized = finalizer::finalize_expired_exam(&api.server.state, &classroom_model)
+        .await
+        .unwrap();
+    assert_eq!(finalized, 0);
+}
+
+#[tokio::test]
+async fn student_runs_must_name_their_classroom() {
+    let api = Api::start().await;
+    let classroom = api
+        .create_classroom(json!({
+            "name": "K",
+            "users": [{ "name": "A", "npm": "111", "code": "" }],
+            "tasks": ["t1"]
+        }))
+        .await;
+    let classroom_id = classroom["id"].as_i64().unwrap();
+    let student = api.student_token("111").await;
+    let run = json!({ "source_code": "mov ax, 1", "language_id": 45, "npm": "111" });
+
+    let (status, _) = api
+        .send(
+            Method::POST,
+            "/api/judge0/submissions",
+            Some(&student),
+            Some(run.clone()),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let mut elsewhere = run.clone();
+    elsewhere["classroom_id"] = json!(classroom_id + 1);
+    let (status, body) = api
+        .send(
+            Method::POST,
+            "/api/judge0/submissions",
+            Some(&student),
+            Some(elsewhere),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "classroom_mismatch");
+
+    let mut enrolled = run;
+    enrolled["classroom_id"] = json!(classroom_id);
+    let (status, body) = api
+        .send(
+            Method::POST,
+            "/api/judge0/submissions",
+            Some(&student),
+            Some(enrolled),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "run: {body}");
+    let saved = submission::Entity::find()
+        .filter(submission::Column::ClassroomId.eq(classroom_id as i32))
+        .all(&api.server.state.db)
+        .await
+        .unwrap();
+    assert_eq!(saved.len(), 1);
+}
+
+#[tokio::test]
+async fn reordering_tasks_keeps_their_test_cases() {
+    let api = Api::start().await;
+    let admin = api.admin_token().await;
+    let classroom = api
+        .create_classroom(json!({ "name": "K", "tasks": ["t1", "t2"] }))
+        .await;
+    let classroom_id = classroom["id"].as_i64().unwrap();
+    let tasks = task_ids(&api, classroom_id, &admin).await;
+    let case = json!({ "stdin": "1", "expectedOutput": "1" });
+    add_test_case(&api, classroom_id, tasks[0], &admin, case).await;
+
+    let response = api
+        .request(
+            Method::PUT,
+            &format!("/api/classrooms/{classroom_id}"),
+            Some(&admin),
+        )
+        .header("Accept-Version", "2")
+        .json(&json!({
+            "tasks": [
+                { "id": tasks[1], "title": "t2" },
+                { "id": tasks[0], "title": "t1" }
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        task_ids(&api, classroom_id, &admin).await,
+        vec![tasks[1], tasks[0]]
+    );
+
+    let (status, cases) = api
+        .send(
+            Method::GET,
+            &format!(
+                "/api/classrooms/{classroom_id}/tasks/{}/test-cases",
+                tasks[0]
+            ),
+            Some(&admin),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "test cases: {cases}");
+    assert_eq!(cases.as_array().unwrap().len(), 1);
+
+    // Without ids a list may only rewrite the tasks in place.
+    let (status, _) = api
+        .send(
+            Method::PUT,
+            &format!("/api/classrooms/{classroom_id}"),
+            Some(&admin),
+            Some(json!({ "tasks": ["only one"] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn finishing_another_task_after_the_attempt_ended_is_refused() {
+    let api = Api::start().await;
+    let admin = api.admin_token().await;
+    let classroom = api
+        .create_classroom(json!({
+            "name": "K",
+            "isExam": true,
+            "users": [{ "name": "A", "npm": "111", "code": "" }],
+            "tasks": ["t1", "t2"]
+        }))
+        .await;
+    let classroom_id = classroom["id"].as_i64().unwrap();
+    let tasks = task_ids(&api, classroom_id, &admin).await;
+    let student = api.student_token("111").await;
+
+    let body = json!({ "npm": "111", "code": "mov ax, 1", "taskId": tasks[0] });
+    let (status, first) = finish_with(&api, classroom_id, &student, body).await;
+    assert!(status.is_success(), "finish: {first}");
+
+    let body = json!({ "npm": "111", "code": "mov ax, 2", "taskId": tasks[1] });
+    let (status, retry) = finish_with(&api, classroom_id, &student, body).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(retry["code"], "already_finished");
+    assert_eq!(final_submissions(&api, classroom_id).await.len(), 1);
+}
+
+/// Runs code as the student, then saves code through the autosave route; returns both
+/// statuses.
+async fn run_and_save(
+    api: &Api,
+    classroom: &Value,
+    token: &str,
+    npm: &str,
+) -> (StatusCode, StatusCode) {
+    let classroom_id = classroom["id"].as_i64().unwrap();
+    let (run, _) = api
+        .send(
+            Method::POST,
+            "/api/judge0/submissions",
+            Some(token),
+            Some(json!({
+                "source_code": "mov ax, 1",
+                "language_id": 45,
+                "npm": npm,
+                "classroom_id": classroom_id
+            })),
+        )
+        .await;
+    let (save, _) = api
+        .send(
+            Method::PUT,
+            &format!(
+                "/api/classrooms/{classroom_id}/users/{}",
+                user_id(classroom, npm)
+            ),
+            Some(token),
+            Some(json!({ "code": "mov ax, 2" })),
+        )
+        .await;
+    (run, save)
+}
+
+#[tokio::test]
+async fn exam_takes_no_work_after_hand_ins_close() {
+    let api = Api::start().await;
+    let classroom = api
+        .create_classroom(json!({
+            "name": "K",
+            "isExam": true,
+            "users": [{ "name": "A", "npm": "111", "code": "" }],
+            "tasks": ["t1"]
+        }))
+        .await;
+    let classroom_id = classroom["id"].as_i64().unwrap();
+    let student = api.student_token("111").await;
+
+    // Signed in during the exam, which then ends well past the tolerance and grace.
+    let db = &api.server.state.db;
+    let now = Utc::now();
+    let mut classroom_am = classroom::Entity::find_by_id(classroom_id as i32)
+        .one(db)
+        .await
+        .unwrap()
+        .unwrap()
+        .into_active_model();
+    classroom_am.exam_start = Set(Some(now - Duration::hours(3)));
+    classroom_am.exam_end = Set(Some(now - Duration::hours(2)));
+    classroom_am.update(db).await.unwrap();
+
+    let (run, save) = run_and_save(&api, &classroom, &student, "111").await;
+    assert_eq!(run, StatusCode::FORBIDDEN);
+    assert_eq!(save, StatusCode::FORBIDDEN);
+    let (status, _) = finish(&api, classroom_id, &student, "111", "mov ax, 3").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn locked_and_deactivated_students_cannot_run_or_save() {
+    let api = Api::start().await;
+    let admin = api.admin_token().await;
+    let classroom = api
+        .create_classroom(json!({
+            "name": "K",
+            "postFinishPolicy": "lock_submissions",
+            "users": [
+                { "name": "A", "npm": "111", "code": "" },
+                { "name": "B", "npm": "222", "code": "" }
+            ],
+            "tasks": ["t1"]
+        }))
+        .await;
+    let classroom_id = classroom["id"].as_i64().unwrap();
+    let locked = api.student_token("111").await;
+    let deactivated = api.student_token("222").await;
+
+    let (status, body) = finish(&api, classroom_id, &locked, "111", "mov ax, 1").await;
+    assert!(status.is_success(), "finish: {body}");
+    let (run, save) = run_and_save(&api, &classroom, &locked, "111").await;
+    assert_eq!(run, StatusCode::FORBIDDEN);
+    assert_eq!(save, StatusCode::FORBIDDEN);
+
+    let (status, body) = api
+        .send(
+            Method::PUT,
+            &format!(
+                "/api/classrooms/{classroom_id}/users/{}",
+                user_id(&classroom, "222")
+            ),
+            Some(&admin),
+            Some(json!({ "active": false })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "deactivate: {body}");
+    let (run, save) = run_and_save(&api, &classroom, &deactivated, "222").await;
+    assert_eq!(run, StatusCode::UNAUTHORIZED);
+    assert_eq!(save, StatusCode::UNAUTHORIZED);
+}